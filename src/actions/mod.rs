@@ -5,6 +5,8 @@
 //! - Remove unknown fields
 //! - Fix common mistakes
 
+pub mod placeholders;
 pub mod provider;
 
+pub use placeholders::EditStyle;
 pub use provider::CodeActionsProvider;
@@ -0,0 +1,82 @@
+//! Placeholders in generated edits.
+//!
+//! Values the user has to fill in are snippet tab stops (`${1:name}`) for
+//! clients that expand snippets in workspace edits, and plain text with
+//! `TODO` markers for the others.
+
+use serde::Deserialize;
+
+use crate::parser::Node;
+
+/// Marker left in plain edits where a value is missing
+pub const TODO: &str = "TODO";
+
+/// How generated edits mark the values to fill in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EditStyle {
+    /// Plain text, with `TODO` where there is no default
+    #[default]
+    Plain,
+    /// Snippet tab stops, with the default as placeholder
+    Snippet,
+}
+
+/// Numbers the placeholders of one edit.
+#[derive(Debug)]
+pub struct Placeholders {
+    style: EditStyle,
+    next: u32,
+}
+
+impl Placeholders {
+    /// Start the placeholders of a new edit.
+    pub fn new(style: EditStyle) -> Self {
+        Self { style, next: 1 }
+    }
+
+    /// A value to fill in, suggesting `default` when there is one.
+    pub fn value(&mut self, default: Option<&str>) -> Node {
+        match self.style {
+            EditStyle::Plain => Node::scalar(default.unwrap_or(TODO)),
+            EditStyle::Snippet => {
+                let tab_stop = match default {
+                    Some(default) => format!("${{{}:{}}}", self.next, escape(default)),
+                    None => format!("${}", self.next),
+                };
+                self.next += 1;
+                Node::scalar(tab_stop)
+            }
+        }
+    }
+}
+
+/// Escape the characters with a meaning in snippet placeholders.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('$', "\\$")
+        .replace('}', "\\}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders() {
+        let mut plain = Placeholders::new(EditStyle::Plain);
+        assert_eq!(plain.value(Some("alpine")).as_scalar(), Some("alpine"));
+        assert_eq!(plain.value(None).as_scalar(), Some("TODO"));
+
+        let mut snippet = Placeholders::new(EditStyle::Snippet);
+        assert_eq!(
+            snippet.value(Some("alpine")).as_scalar(),
+            Some("${1:alpine}")
+        );
+        assert_eq!(snippet.value(None).as_scalar(), Some("$2"));
+        assert_eq!(
+            snippet.value(Some("$(params.x)")).as_scalar(),
+            Some("${3:\\$(params.x)}")
+        );
+    }
+}
@@ -1,19 +1,48 @@
 //! Code actions provider implementation.
 
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use tower_lsp::lsp_types::{
     CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, Position, Range, TextEdit, Url,
     WorkspaceEdit,
 };
-use std::collections::HashMap;
+
+use super::placeholders::{EditStyle, Placeholders};
+use crate::parser::{to_yaml_indented, Node};
+use crate::validator::{DiagnosticData, Fix};
+use crate::workspace::{describe_references, WorkspaceIndex};
 
 /// Provides code actions (quick fixes) for Tekton YAML files.
 #[derive(Debug, Clone, Default)]
-pub struct CodeActionsProvider;
+pub struct CodeActionsProvider {
+    /// Workspace index used to warn about edits to resources other files reference
+    index: Option<WorkspaceIndex>,
+    /// Shared so that clones of the provider see setting changes
+    edit_style: Arc<RwLock<EditStyle>>,
+}
 
 impl CodeActionsProvider {
     /// Create a new code actions provider.
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Create a code actions provider backed by the workspace index.
+    pub fn with_index(index: WorkspaceIndex) -> Self {
+        Self {
+            index: Some(index),
+            ..Self::default()
+        }
+    }
+
+    /// Set how generated edits mark the values to fill in
+    pub fn set_edit_style(&self, style: EditStyle) {
+        *self.edit_style.write().unwrap() = style;
+    }
+
+    /// How generated edits mark the values to fill in
+    pub fn edit_style(&self) -> EditStyle {
+        *self.edit_style.read().unwrap()
     }
 
     /// Provide code actions for the given diagnostics.
@@ -25,7 +54,7 @@ impl CodeActionsProvider {
         let mut actions = Vec::new();
 
         for diagnostic in diagnostics {
-            if let Some(action) = self.create_action_for_diagnostic(uri, diagnostic) {
+            for action in self.create_actions_for_diagnostic(uri, diagnostic) {
                 actions.push(CodeActionOrCommand::CodeAction(action));
             }
         }
@@ -33,21 +62,85 @@ impl CodeActionsProvider {
         actions
     }
 
-    /// Create a code action for a specific diagnostic.
-    fn create_action_for_diagnostic(&self, uri: &Url, diagnostic: &Diagnostic) -> Option<CodeAction> {
-        let message = &diagnostic.message;
+    /// Create the code actions for a specific diagnostic, from the fix in its `data`.
+    fn create_actions_for_diagnostic(&self, uri: &Url, diagnostic: &Diagnostic) -> Vec<CodeAction> {
+        let Some(fix) = DiagnosticData::of(diagnostic).and_then(|data| data.fix) else {
+            return Vec::new();
+        };
+        let actions = match fix {
+            Fix::AddField { field } => vec![self.create_add_field_action(uri, diagnostic, &field)],
+            Fix::RemoveField { field } => {
+                vec![self.create_remove_field_action(uri, diagnostic, &field)]
+            }
+            // The suggestion may be wrong, so removing the field stays on offer
+            Fix::RenameField { field, to } => vec![
+                self.create_rename_field_action(uri, diagnostic, &field, &to),
+                self.create_remove_field_action(uri, diagnostic, &field),
+            ],
+            Fix::BundleResolver { edit } => {
+                vec![self.create_bundle_resolver_action(uri, diagnostic, edit)]
+            }
+        };
+        actions.into_iter().flatten().collect()
+    }
 
-        // Handle missing required field
-        if message.contains("Missing required field") {
-            return self.create_add_field_action(uri, diagnostic, message);
-        }
+    /// Create an action renaming a misspelled field to the known field it is
+    /// closest to.
+    ///
+    /// The diagnostic range is the field's key.
+    fn create_rename_field_action(
+        &self,
+        uri: &Url,
+        diagnostic: &Diagnostic,
+        field_name: &str,
+        to: &str,
+    ) -> Option<CodeAction> {
+        let mut changes = HashMap::new();
+        changes.insert(
+            uri.clone(),
+            vec![TextEdit {
+                range: diagnostic.range,
+                new_text: to.to_string(),
+            }],
+        );
 
-        // Handle unknown field
-        if message.contains("Unknown field") {
-            return self.create_remove_field_action(uri, diagnostic, message);
-        }
+        Some(CodeAction {
+            title: format!("Rename '{}' to '{}'", field_name, to),
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diagnostic.clone()]),
+            edit: Some(WorkspaceEdit {
+                changes: Some(changes),
+                ..Default::default()
+            }),
+            is_preferred: Some(true),
+            ..Default::default()
+        })
+    }
 
-        None
+    /// Create an action rewriting `taskRef.bundle` into the bundles resolver form.
+    ///
+    /// The validator computes the rewrite, since it needs the whole `taskRef`
+    /// entry.
+    fn create_bundle_resolver_action(
+        &self,
+        uri: &Url,
+        diagnostic: &Diagnostic,
+        edit: TextEdit,
+    ) -> Option<CodeAction> {
+        let mut changes = HashMap::new();
+        changes.insert(uri.clone(), vec![edit]);
+
+        Some(CodeAction {
+            title: "Convert 'bundle' to the bundles resolver".to_string(),
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diagnostic.clone()]),
+            edit: Some(WorkspaceEdit {
+                changes: Some(changes),
+                ..Default::default()
+            }),
+            is_preferred: Some(true),
+            ..Default::default()
+        })
     }
 
     /// Create an action to add a missing required field.
@@ -55,13 +148,10 @@ impl CodeActionsProvider {
         &self,
         uri: &Url,
         diagnostic: &Diagnostic,
-        message: &str,
+        field_name: &str,
     ) -> Option<CodeAction> {
-        // Extract field name from message like "Missing required field 'metadata'"
-        let field_name = self.extract_field_name(message, "Missing required field")?;
-
         // Determine the text to insert based on the field
-        let insert_text = self.get_field_template(&field_name);
+        let insert_text = self.get_field_template(field_name);
 
         // Insert at the end of the diagnostic range (after the current line)
         let insert_position = Position {
@@ -98,11 +188,8 @@ impl CodeActionsProvider {
         &self,
         uri: &Url,
         diagnostic: &Diagnostic,
-        message: &str,
+        field_name: &str,
     ) -> Option<CodeAction> {
-        // Extract field name from message like "Unknown field 'foo'"
-        let field_name = self.extract_field_name(message, "Unknown field")?;
-
         // Remove the entire line containing the unknown field
         let remove_range = Range {
             start: Position {
@@ -124,8 +211,19 @@ impl CodeActionsProvider {
             }],
         );
 
+        // Removing lines from a resource other files use deserves a second look
+        let mut title = format!("Remove unknown field '{}'", field_name);
+        let references = self
+            .index
+            .as_ref()
+            .map(|index| index.inbound_references(uri))
+            .unwrap_or_default();
+        if let Some(warning) = describe_references(&references) {
+            title.push_str(&format!(" (warning: {})", warning));
+        }
+
         Some(CodeAction {
-            title: format!("Remove unknown field '{}'", field_name),
+            title,
             kind: Some(CodeActionKind::QUICKFIX),
             diagnostics: Some(vec![diagnostic.clone()]),
             edit: Some(WorkspaceEdit {
@@ -136,31 +234,34 @@ impl CodeActionsProvider {
         })
     }
 
-    /// Extract a field name from a diagnostic message.
-    fn extract_field_name(&self, message: &str, prefix: &str) -> Option<String> {
-        // Look for pattern like "prefix 'fieldname'"
-        let start = message.find(prefix)? + prefix.len();
-        let after_prefix = &message[start..];
-
-        // Find the quoted field name
-        let quote_start = after_prefix.find('\'')?;
-        let name_start = quote_start + 1;
-        let quote_end = after_prefix[name_start..].find('\'')?;
+    /// Get a template for a field, with placeholders for the values to fill in.
+    fn get_field_template(&self, field_name: &str) -> String {
+        let mut placeholders = Placeholders::new(self.edit_style());
+        let p = &mut placeholders;
+        let step = |p: &mut Placeholders| {
+            Node::mapping([
+                ("name", p.value(Some("step-1"))),
+                ("image", p.value(Some("alpine"))),
+            ])
+        };
+        let task = |p: &mut Placeholders| {
+            Node::mapping([
+                ("name", p.value(Some("task-1"))),
+                ("taskRef", Node::mapping([("name", p.value(None))])),
+            ])
+        };
 
-        Some(after_prefix[name_start..name_start + quote_end].to_string())
-    }
+        // The value to insert, and the column its key starts at
+        let (value, indent) = match field_name {
+            "metadata" => (Node::mapping([("name", p.value(None))]), 0),
+            "spec" => (Node::mapping([("steps", Node::sequence([step(p)]))]), 0),
+            "steps" => (Node::sequence([step(p)]), 2),
+            "tasks" => (Node::sequence([task(p)]), 2),
+            "image" => (p.value(Some("alpine")), 6),
+            _ => (p.value(None), 2),
+        };
 
-    /// Get a template for a field.
-    fn get_field_template(&self, field_name: &str) -> String {
-        match field_name {
-            "metadata" => "metadata:\n  name: \n".to_string(),
-            "spec" => "spec:\n  steps:\n    - name: step-1\n      image: alpine\n".to_string(),
-            "name" => "  name: \n".to_string(),
-            "steps" => "  steps:\n    - name: step-1\n      image: alpine\n".to_string(),
-            "tasks" => "  tasks:\n    - name: task-1\n      taskRef:\n        name: \n".to_string(),
-            "image" => "      image: alpine\n".to_string(),
-            _ => format!("  {}: \n", field_name),
-        }
+        to_yaml_indented(&Node::mapping([(field_name, value)]), indent)
     }
 }
 
@@ -169,11 +270,18 @@ mod tests {
     use super::*;
     use tower_lsp::lsp_types::DiagnosticSeverity;
 
-    fn create_diagnostic(message: &str, line: u32) -> Diagnostic {
+    fn create_diagnostic(message: &str, line: u32, fix: Option<Fix>) -> Diagnostic {
+        let data = fix.map(|fix| DiagnosticData {
+            fix: Some(fix),
+            ..DiagnosticData::new("test")
+        });
         Diagnostic {
             range: Range {
                 start: Position { line, character: 0 },
-                end: Position { line, character: 10 },
+                end: Position {
+                    line,
+                    character: 10,
+                },
             },
             severity: Some(DiagnosticSeverity::ERROR),
             code: None,
@@ -182,7 +290,7 @@ mod tests {
             message: message.to_string(),
             related_information: None,
             tags: None,
-            data: None,
+            data: data.and_then(|data| serde_json::to_value(data).ok()),
         }
     }
 
@@ -191,7 +299,13 @@ mod tests {
         let provider = CodeActionsProvider::new();
         let uri = Url::parse("file:///tmp/test.yaml").unwrap();
 
-        let diagnostic = create_diagnostic("Missing required field 'metadata'", 0);
+        let diagnostic = create_diagnostic(
+            "Missing required field 'metadata'",
+            0,
+            Some(Fix::AddField {
+                field: "metadata".to_string(),
+            }),
+        );
         let actions = provider.provide_actions(&uri, &[diagnostic]);
 
         assert_eq!(actions.len(), 1);
@@ -220,7 +334,13 @@ mod tests {
         let provider = CodeActionsProvider::new();
         let uri = Url::parse("file:///tmp/test.yaml").unwrap();
 
-        let diagnostic = create_diagnostic("Unknown field 'foo' in Task spec", 5);
+        let diagnostic = create_diagnostic(
+            "Unknown field 'foo' in Task spec",
+            5,
+            Some(Fix::RemoveField {
+                field: "foo".to_string(),
+            }),
+        );
         let actions = provider.provide_actions(&uri, &[diagnostic]);
 
         assert_eq!(actions.len(), 1);
@@ -245,12 +365,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rename_field_action() {
+        let provider = CodeActionsProvider::new();
+        let uri = Url::parse("file:///tmp/test.yaml").unwrap();
+
+        let diagnostic = create_diagnostic(
+            "Unknown field 'taskz' in Pipeline spec, did you mean 'tasks'?",
+            5,
+            Some(Fix::RenameField {
+                field: "taskz".to_string(),
+                to: "tasks".to_string(),
+            }),
+        );
+        let actions = provider.provide_actions(&uri, std::slice::from_ref(&diagnostic));
+
+        let actions: Vec<&CodeAction> = actions
+            .iter()
+            .filter_map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => Some(action),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0].title, "Rename 'taskz' to 'tasks'");
+        assert_eq!(actions[0].is_preferred, Some(true));
+        let edits = &actions[0].edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+        assert_eq!(
+            edits,
+            &vec![TextEdit {
+                range: diagnostic.range,
+                new_text: "tasks".to_string(),
+            }]
+        );
+        assert!(actions[1].title.contains("Remove unknown field 'taskz'"));
+    }
+
     #[test]
     fn test_no_action_for_unhandled_diagnostic() {
         let provider = CodeActionsProvider::new();
         let uri = Url::parse("file:///tmp/test.yaml").unwrap();
 
-        let diagnostic = create_diagnostic("Some other error", 0);
+        let diagnostic = create_diagnostic("Some other error", 0, None);
         let actions = provider.provide_actions(&uri, &[diagnostic]);
 
         assert!(actions.is_empty());
@@ -262,8 +418,20 @@ mod tests {
         let uri = Url::parse("file:///tmp/test.yaml").unwrap();
 
         let diagnostics = vec![
-            create_diagnostic("Missing required field 'spec'", 3),
-            create_diagnostic("Unknown field 'bar'", 5),
+            create_diagnostic(
+                "Missing required field 'spec'",
+                3,
+                Some(Fix::AddField {
+                    field: "spec".to_string(),
+                }),
+            ),
+            create_diagnostic(
+                "Unknown field 'bar'",
+                5,
+                Some(Fix::RemoveField {
+                    field: "bar".to_string(),
+                }),
+            ),
         ];
 
         let actions = provider.provide_actions(&uri, &diagnostics);
@@ -271,22 +439,41 @@ mod tests {
     }
 
     #[test]
-    fn test_extract_field_name() {
+    fn test_field_templates() {
         let provider = CodeActionsProvider::new();
 
         assert_eq!(
-            provider.extract_field_name("Missing required field 'metadata'", "Missing required field"),
-            Some("metadata".to_string())
+            provider.get_field_template("metadata"),
+            "metadata:\n  name: TODO\n"
+        );
+        assert_eq!(
+            provider.get_field_template("tasks"),
+            "  tasks:\n    - name: task-1\n      taskRef:\n        name: TODO\n"
         );
-
         assert_eq!(
-            provider.extract_field_name("Unknown field 'foo' in spec", "Unknown field"),
-            Some("foo".to_string())
+            provider.get_field_template("image"),
+            "      image: alpine\n"
         );
+        assert_eq!(provider.get_field_template("timeout"), "  timeout: TODO\n");
+    }
+
+    #[test]
+    fn test_snippet_field_templates() {
+        let provider = CodeActionsProvider::new();
+        provider.set_edit_style(EditStyle::Snippet);
 
         assert_eq!(
-            provider.extract_field_name("No field here", "Missing"),
-            None
+            provider.get_field_template("tasks"),
+            "  tasks:\n    - name: ${1:task-1}\n      taskRef:\n        name: $2\n"
         );
+        assert_eq!(
+            provider.get_field_template("spec"),
+            "spec:\n  steps:\n    - name: ${1:step-1}\n      image: ${2:alpine}\n"
+        );
+
+        // Clones share the setting
+        let clone = provider.clone();
+        provider.set_edit_style(EditStyle::Plain);
+        assert_eq!(clone.get_field_template("timeout"), "  timeout: TODO\n");
     }
 }
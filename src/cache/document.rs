@@ -1,3 +1,4 @@
+use crate::parser::{positions, PositionEncoding};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use tower_lsp::lsp_types::{Range, TextDocumentContentChangeEvent, Url};
@@ -27,7 +28,13 @@ impl Document {
     }
 
     /// Apply incremental changes to the document
-    pub fn apply_changes(&mut self, changes: Vec<TextDocumentContentChangeEvent>) {
+    ///
+    /// `encoding` is the encoding of the `character` of the change ranges.
+    pub fn apply_changes(
+        &mut self,
+        changes: Vec<TextDocumentContentChangeEvent>,
+        encoding: PositionEncoding,
+    ) {
         for change in changes {
             match change.range {
                 // Full document sync
@@ -36,60 +43,17 @@ impl Document {
                 }
                 // Incremental sync
                 Some(range) => {
-                    self.apply_incremental_change(range, &change.text);
+                    self.apply_incremental_change(range, &change.text, encoding);
                 }
             }
         }
     }
 
     /// Apply an incremental change to a specific range
-    fn apply_incremental_change(&mut self, range: Range, text: &str) {
-        let lines: Vec<&str> = self.content.lines().collect();
-        let start = range.start;
-        let end = range.end;
-
-        let mut new_content = String::new();
-
-        // Lines before the change
-        for line in lines.iter().take(start.line as usize) {
-            new_content.push_str(line);
-            new_content.push('\n');
-        }
-
-        // Start line with prefix before change
-        if let Some(start_line) = lines.get(start.line as usize) {
-            let prefix = start_line
-                .chars()
-                .take(start.character as usize)
-                .collect::<String>();
-            new_content.push_str(&prefix);
-        }
-
-        // New text
-        new_content.push_str(text);
-
-        // End line with suffix after change
-        if let Some(end_line) = lines.get(end.line as usize) {
-            let suffix = end_line
-                .chars()
-                .skip(end.character as usize)
-                .collect::<String>();
-            new_content.push_str(&suffix);
-            new_content.push('\n');
-        }
-
-        // Lines after the change
-        for line in lines.iter().skip((end.line + 1) as usize) {
-            new_content.push_str(line);
-            new_content.push('\n');
-        }
-
-        // Remove trailing newline if original didn't have one
-        if !self.content.ends_with('\n') && new_content.ends_with('\n') {
-            new_content.pop();
-        }
-
-        self.content = new_content;
+    fn apply_incremental_change(&mut self, range: Range, text: &str, encoding: PositionEncoding) {
+        let start = positions::offset(&self.content, range.start, encoding);
+        let end = positions::offset(&self.content, range.end, encoding).max(start);
+        self.content.replace_range(start..end, text);
     }
 }
 
@@ -97,6 +61,8 @@ impl Document {
 #[derive(Debug, Clone)]
 pub struct DocumentCache {
     documents: Arc<RwLock<HashMap<Url, Document>>>,
+    /// Encoding of the positions in incremental changes
+    encoding: Arc<RwLock<PositionEncoding>>,
 }
 
 impl DocumentCache {
@@ -104,9 +70,15 @@ impl DocumentCache {
     pub fn new() -> Self {
         Self {
             documents: Arc::new(RwLock::new(HashMap::new())),
+            encoding: Arc::new(RwLock::new(PositionEncoding::default())),
         }
     }
 
+    /// Set the position encoding negotiated with the client
+    pub fn set_position_encoding(&self, encoding: PositionEncoding) {
+        *self.encoding.write().unwrap() = encoding;
+    }
+
     /// Add or update a document in the cache
     pub fn insert(&self, uri: Url, language_id: String, version: i32, content: String) {
         let doc = Document::new(uri.clone(), language_id, version, content);
@@ -120,12 +92,18 @@ impl DocumentCache {
         documents.get(uri).cloned()
     }
 
+    /// Version of an open document
+    pub fn version(&self, uri: &Url) -> Option<i32> {
+        let documents = self.documents.read().unwrap();
+        documents.get(uri).map(|doc| doc.version)
+    }
+
     /// Update a document with incremental changes
     pub fn update(&self, uri: &Url, version: i32, changes: Vec<TextDocumentContentChangeEvent>) {
         let mut documents = self.documents.write().unwrap();
         if let Some(doc) = documents.get_mut(uri) {
             doc.version = version;
-            doc.apply_changes(changes);
+            doc.apply_changes(changes, *self.encoding.read().unwrap());
         }
     }
 
@@ -135,8 +113,7 @@ impl DocumentCache {
         documents.remove(uri);
     }
 
-    /// Get all documents in the cache (for future workspace features)
-    #[allow(dead_code)]
+    /// Get all documents in the cache
     pub fn all(&self) -> Vec<Document> {
         let documents = self.documents.read().unwrap();
         documents.values().cloned().collect()
@@ -156,7 +133,12 @@ mod tests {
     #[test]
     fn test_document_creation() {
         let uri = Url::parse("file:///test.yaml").unwrap();
-        let doc = Document::new(uri.clone(), "yaml".to_string(), 1, "test content".to_string());
+        let doc = Document::new(
+            uri.clone(),
+            "yaml".to_string(),
+            1,
+            "test content".to_string(),
+        );
 
         assert_eq!(doc.uri, uri);
         assert_eq!(doc.language_id, "yaml");
@@ -169,11 +151,14 @@ mod tests {
         let uri = Url::parse("file:///test.yaml").unwrap();
         let mut doc = Document::new(uri, "yaml".to_string(), 1, "old content".to_string());
 
-        doc.apply_changes(vec![TextDocumentContentChangeEvent {
-            range: None,
-            range_length: None,
-            text: "new content".to_string(),
-        }]);
+        doc.apply_changes(
+            vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: "new content".to_string(),
+            }],
+            PositionEncoding::default(),
+        );
 
         assert_eq!(doc.content, "new content");
     }
@@ -207,7 +192,12 @@ mod tests {
         let cache = DocumentCache::new();
         let uri = Url::parse("file:///test.yaml").unwrap();
 
-        cache.insert(uri.clone(), "yaml".to_string(), 1, "line 1\nline 2".to_string());
+        cache.insert(
+            uri.clone(),
+            "yaml".to_string(),
+            1,
+            "line 1\nline 2".to_string(),
+        );
 
         cache.update(
             &uri,
@@ -222,5 +212,68 @@ mod tests {
         let doc = cache.get(&uri).unwrap();
         assert_eq!(doc.version, 2);
         assert_eq!(doc.content, "updated content");
+        assert_eq!(cache.version(&uri), Some(2));
+
+        cache.remove(&uri);
+        assert_eq!(cache.version(&uri), None);
+    }
+
+    #[test]
+    fn test_incremental_change_with_non_ascii() {
+        let uri = Url::parse("file:///test.yaml").unwrap();
+        let content = "description: 🚀 fast\nname: x";
+        let change = |character| TextDocumentContentChangeEvent {
+            range: Some(Range {
+                start: tower_lsp::lsp_types::Position { line: 0, character },
+                end: tower_lsp::lsp_types::Position {
+                    line: 0,
+                    character: character + 4,
+                },
+            }),
+            range_length: None,
+            text: "slow".to_string(),
+        };
+
+        // "fast" starts after the rocket: 2 UTF-16 units, 4 bytes
+        let mut doc = Document::new(uri.clone(), "yaml".to_string(), 1, content.to_string());
+        doc.apply_changes(vec![change(16)], PositionEncoding::Utf16);
+        assert_eq!(doc.content, "description: 🚀 slow\nname: x");
+
+        let mut doc = Document::new(uri, "yaml".to_string(), 1, content.to_string());
+        doc.apply_changes(vec![change(18)], PositionEncoding::Utf8);
+        assert_eq!(doc.content, "description: 🚀 slow\nname: x");
+    }
+
+    #[test]
+    fn test_incremental_change_keeps_line_breaks() {
+        let uri = Url::parse("file:///test.yaml").unwrap();
+        let change =
+            |start: (u32, u32), end: (u32, u32), text: &str| TextDocumentContentChangeEvent {
+                range: Some(Range {
+                    start: tower_lsp::lsp_types::Position {
+                        line: start.0,
+                        character: start.1,
+                    },
+                    end: tower_lsp::lsp_types::Position {
+                        line: end.0,
+                        character: end.1,
+                    },
+                }),
+                range_length: None,
+                text: text.to_string(),
+            };
+
+        let content = "a: 1\r\nb: 2\r\n";
+        let mut doc = Document::new(uri.clone(), "yaml".to_string(), 1, content.to_string());
+        doc.apply_changes(vec![change((1, 3), (1, 4), "3")], PositionEncoding::Utf16);
+        assert_eq!(doc.content, "a: 1\r\nb: 3\r\n");
+
+        // Joining lines, then typing on the empty last line
+        let mut doc = Document::new(uri, "yaml".to_string(), 1, "a: 1\nb: 2\n".to_string());
+        doc.apply_changes(
+            vec![change((0, 4), (1, 0), " "), change((1, 0), (1, 0), "c: 3")],
+            PositionEncoding::Utf16,
+        );
+        assert_eq!(doc.content, "a: 1 b: 2\nc: 3");
     }
 }
@@ -1,46 +1,819 @@
 //! Completion provider implementation.
 
-use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, Position};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-use crate::parser::{YamlDocument, Node, NodeValue};
-use super::schemas::{TektonSchemas, FieldSchema};
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, InsertTextFormat,
+    InsertTextMode, MarkupContent, MarkupKind, Position, Range, TextEdit,
+};
+
+use super::schemas::{
+    FieldSchema, FieldType, TektonSchemas, METADATA_ANNOTATIONS, METADATA_LABELS, RESOLVERS,
+    TASK_REF_KINDS, TEKTON_API_VERSIONS, V1_ONLY_STEP_FIELDS, WHEN_OPERATORS,
+};
+use crate::hover::docs::field_documentation;
+use crate::hover::explain::Section;
+use crate::integrations::HubClient;
+use crate::model::{
+    default_workspace_mount_path, ParamSpec, Pipeline, PipelineTask, Resource, Task,
+    WorkspaceDeclaration,
+};
+use crate::pac;
+use crate::parser::expressions::open_expression;
+use crate::parser::positions::contains;
+use crate::parser::templates::in_open_template;
+use crate::parser::{to_flow_yaml, Node, NodeValue, Style, YamlDocument};
+use crate::workspace::WorkspaceIndex;
+
+/// Fields of `$(workspaces.<name>.<field>)` variables, with what they hold
+const WORKSPACE_FIELDS: [(&str, &str); 4] = [
+    ("path", "Path the workspace is mounted at"),
+    (
+        "bound",
+        "Whether a volume is bound to the workspace: true or false",
+    ),
+    (
+        "claim",
+        "Name of the PersistentVolumeClaim of the workspace, empty if none",
+    ),
+    ("volume", "Name of the volume of the workspace"),
+];
+
+/// Fields holding lists of named items, for which a `- name: ` starter is offered
+const NAMED_LIST_FIELDS: [&str; 6] = ["tasks", "finally", "steps", "params", "include", "triggers"];
+
+/// Fields completed to a first item to fill in, as snippets indented relative
+/// to the line of the field
+const FIELD_SNIPPETS: [(&str, &str); 3] = [
+    (
+        "tasks",
+        "tasks:\n  - name: ${1:name}\n    taskRef:\n      name: ${2:task}",
+    ),
+    (
+        "finally",
+        "finally:\n  - name: ${1:name}\n    taskRef:\n      name: ${2:task}",
+    ),
+    (
+        "steps",
+        "steps:\n  - name: ${1:name}\n    image: ${2:image}\n    script: |\n      ${3:echo hello}",
+    ),
+];
+
+/// Resources scaffolded in an empty document, as snippets
+const DOCUMENT_SNIPPETS: [(&str, &str); 3] = [
+    (
+        "New Tekton Task",
+        "apiVersion: tekton.dev/v1\nkind: Task\nmetadata:\n  name: ${1:name}\nspec:\n  steps:\n    - name: ${2:step}\n      image: ${3:image}\n      script: |\n        ${4:echo hello}",
+    ),
+    (
+        "New Pipeline",
+        "apiVersion: tekton.dev/v1\nkind: Pipeline\nmetadata:\n  name: ${1:name}\nspec:\n  tasks:\n    - name: ${2:name}\n      taskRef:\n        name: ${3:task}",
+    ),
+    (
+        "New PipelineRun",
+        "apiVersion: tekton.dev/v1\nkind: PipelineRun\nmetadata:\n  generateName: ${1:name}-\nspec:\n  pipelineRef:\n    name: ${2:pipeline}",
+    ),
+];
+
+/// Data of a completion item, used to fill in its documentation when the
+/// client resolves it.
+#[derive(Debug, Serialize, Deserialize)]
+struct ResolveData {
+    /// The field the item completes
+    field: String,
+    /// The kind of the document
+    kind: Option<String>,
+    /// The apiVersion of the document
+    api_version: Option<String>,
+    /// The schema section the field belongs to
+    section: Option<Section>,
+}
 
 #[derive(Debug, Clone)]
 pub struct CompletionProvider {
     schemas: TektonSchemas,
+    /// Workspace index used for completions that depend on other files
+    index: Option<WorkspaceIndex>,
+    /// Whether the client expands snippets, shared so that clones of the
+    /// provider see it
+    snippets: Arc<AtomicBool>,
+    /// Tekton Hub catalog, for taskRefs using the `hub` resolver
+    hub: HubClient,
 }
 
 impl CompletionProvider {
     pub fn new() -> Self {
         Self {
             schemas: TektonSchemas::new(),
+            index: None,
+            snippets: Arc::new(AtomicBool::new(true)),
+            hub: HubClient::default(),
+        }
+    }
+
+    /// Create a completion provider backed by the workspace index.
+    pub fn with_index(index: WorkspaceIndex) -> Self {
+        Self {
+            schemas: TektonSchemas::new(),
+            index: Some(index),
+            snippets: Arc::new(AtomicBool::new(true)),
+            hub: HubClient::default(),
         }
     }
 
+    /// Set whether the client expands snippets in completion items; plain
+    /// text is inserted when it does not.
+    pub fn set_snippet_support(&self, supported: bool) {
+        self.snippets.store(supported, Ordering::Relaxed);
+    }
+
+    /// Whether the client expands snippets in completion items
+    pub fn snippet_support(&self) -> bool {
+        self.snippets.load(Ordering::Relaxed)
+    }
+
+    /// The Tekton Hub client, disabled until configured
+    pub fn hub(&self) -> &HubClient {
+        &self.hub
+    }
+
+    /// Fill in the Markdown documentation of a completion item the client
+    /// selected, left out of the completion list to keep it small.
+    pub fn resolve(&self, mut item: CompletionItem) -> CompletionItem {
+        let data = item
+            .data
+            .clone()
+            .and_then(|data| serde_json::from_value::<ResolveData>(data).ok());
+        let doc = data.and_then(|data| {
+            let kind = data.kind.as_deref();
+            let field = data.section?.field(
+                &self.schemas,
+                kind.unwrap_or_default(),
+                data.api_version.as_deref(),
+                &data.field,
+            )?;
+            field_documentation(kind, &field)
+        });
+        if let Some(doc) = doc {
+            item.documentation = Some(Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: doc,
+            }));
+        }
+        item
+    }
+
     /// Provide completion suggestions for a given position in a YAML document.
     pub fn provide_completions(
         &self,
         yaml_doc: &YamlDocument,
         position: Position,
     ) -> Vec<CompletionItem> {
+        let mut items = self.completions(yaml_doc, position);
+        rank(&mut items);
+        items
+    }
+
+    /// The completions at a position, most specific first.
+    fn completions(&self, yaml_doc: &YamlDocument, position: Position) -> Vec<CompletionItem> {
+        // Pipelines-as-Code variables inside `{{ ... }}`
+        if pac::is_pac_document(&yaml_doc.filename) && self.in_template(yaml_doc, position) {
+            return pac::VARIABLES
+                .iter()
+                .map(|(name, description)| CompletionItem {
+                    label: name.to_string(),
+                    kind: Some(CompletionItemKind::VARIABLE),
+                    detail: Some("Pipelines-as-Code variable".to_string()),
+                    documentation: Some(Documentation::String(description.to_string())),
+                    ..Default::default()
+                })
+                .collect();
+        }
+
+        // Variables inside an unclosed `$(` of a value
+        if let Some(expression) = self.open_expression_at(yaml_doc, position) {
+            return self.variable_completions(yaml_doc, position, &expression);
+        }
+
+        // Values of taskRef.kind / taskRef.apiVersion
+        if let Some(task_ref) = self.find_node_with_key(&yaml_doc.root, "taskRef", position) {
+            if let Some(field) = self.value_field_at(task_ref, position) {
+                return self.task_ref_value_completions(task_ref, field);
+            }
+        }
+
+        // Name of a resolver param of a taskRef / pipelineRef
+        if let Some(items) = self.resolver_param_completions(&yaml_doc.root, position) {
+            return items;
+        }
+
+        // Value of a workspace's mountPath
+        if let Some(workspace) = self.workspace_mount_path_at(&yaml_doc.root, position) {
+            return self.mount_path_completions(workspace);
+        }
+
+        // Name or value of a matrix param
+        if let Some(field) = self.matrix_param_field_at(&yaml_doc.root, position) {
+            return self.matrix_param_completions(yaml_doc, field);
+        }
+
+        // Name of a param passed to a pipeline task's referenced Task
+        if let Some(items) = self.task_param_name_completions(yaml_doc, position) {
+            return items;
+        }
+
+        // Name or pipeline workspace of a pipeline task's workspace binding
+        if let Some(items) = self.task_workspace_completions(yaml_doc, position) {
+            return items;
+        }
+
+        // Input or operator of a pipeline task's when expression
+        if let Some(items) = self.when_value_completions(yaml_doc, position) {
+            return items;
+        }
+
+        // Entries of a pipeline task's runAfter
+        if let Some(items) = self.run_after_completions(yaml_doc, position) {
+            return items;
+        }
+
+        // The lines of a multi-line scalar, such as a script, hold no fields
+        if in_scalar_continuation(&yaml_doc.root, position) {
+            return vec![];
+        }
+
+        // Keys of the metadata's labels or annotations
+        if let Some((field, map)) = metadata_map_at(yaml_doc, position) {
+            return metadata_key_completions(yaml_doc, position, field, map);
+        }
+
+        // After the `:` of a key the value is typed, not a field name. The
+        // context is the key's: an empty value is past the end of the mapping
+        if let Some((key, key_position)) = value_key_at(yaml_doc, position) {
+            let context = self.determine_context(yaml_doc, key_position);
+            return self.field_value_completions(&context, yaml_doc, &key);
+        }
+
         // Find the context at the cursor position
         let context = self.determine_context(yaml_doc, position);
 
-        // Get appropriate fields for the context
-        let fields = self.get_fields_for_context(&context, yaml_doc);
+        // Get appropriate fields for the context, leaving out those the
+        // mapping being typed in already has
+        let mapping = mapping_at(yaml_doc, position);
+        let fields = self.get_fields_for_context(&context, yaml_doc, mapping, position.line);
 
-        // Convert to completion items
-        fields
+        // Convert to completion items, offering a new list item first when the
+        // cursor sits at the indentation of a list's items, or whole resources
+        // in an empty document. Required fields the mapping lacks are preselected
+        let starter = self
+            .list_item_starter_at(&yaml_doc.root, position)
+            .map(|field| list_item_starter(field, self.snippet_support()));
+        let scaffolds = if context == CompletionContext::Document
+            && is_blank_except(yaml_doc, position.line)
+        {
+            DOCUMENT_SNIPPETS
+                .iter()
+                .map(|(label, snippet)| document_snippet(label, snippet, self.snippet_support()))
+                .collect()
+        } else {
+            vec![]
+        };
+        starter
+            .into_iter()
+            .chain(scaffolds)
+            .chain(fields.iter().map(|field| CompletionItem {
+                preselect: (mapping.is_some() && field.required).then_some(true),
+                ..self.field_to_completion_item(field, yaml_doc, context)
+            }))
+            .collect()
+    }
+
+    /// Whether the cursor is inside an unclosed `{{` of a single-line scalar.
+    fn in_template(&self, yaml_doc: &YamlDocument, position: Position) -> bool {
+        let mut nodes = Vec::new();
+        collect_nodes(&yaml_doc.root, &mut nodes);
+
+        nodes.iter().any(|node| {
+            let NodeValue::Scalar(text) = &node.value else {
+                return false;
+            };
+            let range = node.value_range;
+            if range.start.line != position.line
+                || range.end.line != position.line
+                || !contains(range, position)
+            {
+                return false;
+            }
+            let column = position.character - range.start.character;
+            in_open_template(text, yaml_doc.encoding.byte_offset(text, column))
+        })
+    }
+
+    /// The unclosed `$(` expression the cursor is typing in a scalar value.
+    ///
+    /// Block scalars are stored as written, so the line of the cursor is a line
+    /// of the scalar's text whatever its style.
+    fn open_expression_at(
+        &self,
+        yaml_doc: &YamlDocument,
+        position: Position,
+    ) -> Option<OpenExpression> {
+        let encoding = yaml_doc.encoding;
+        let mut nodes = Vec::new();
+        collect_nodes(&yaml_doc.root, &mut nodes);
+
+        nodes.iter().find_map(|node| {
+            let NodeValue::Scalar(text) = &node.value else {
+                return None;
+            };
+            let range = node.value_range;
+            if !contains(range, position) {
+                return None;
+            }
+            let index = position.line - range.start.line;
+            let line = text.lines().nth(index as usize)?;
+            let column = if index == 0 {
+                position.character - range.start.character
+            } else {
+                position.character
+            };
+            let byte = encoding.byte_offset(line, column);
+            let typed = open_expression(line, byte)?;
+
+            // The rest of a name already written after the cursor is replaced too
+            let rest = &line[byte..];
+            let name_len = rest
+                .find(|c: char| {
+                    !(c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | '[' | ']' | '*'))
+                })
+                .unwrap_or(rest.len());
+            Some(OpenExpression {
+                typed: typed.to_string(),
+                range: Range {
+                    start: Position {
+                        line: position.line,
+                        character: position.character - encoding.len(typed),
+                    },
+                    end: Position {
+                        line: position.line,
+                        character: position.character + encoding.len(&rest[..name_len]),
+                    },
+                },
+                closed: rest[name_len..].starts_with(')'),
+            })
+        })
+    }
+
+    /// Completions for the variables of an unclosed `$(`: the params in scope
+    /// at the cursor and, in a Pipeline, the results of its tasks.
+    fn variable_completions(
+        &self,
+        yaml_doc: &YamlDocument,
+        position: Position,
+        expression: &OpenExpression,
+    ) -> Vec<CompletionItem> {
+        let resource = Resource::from_document(yaml_doc);
+        let pipeline = resource.as_ref().and_then(pipeline_of);
+
+        let typed = expression.typed.as_str();
+        let mut items = Vec::new();
+        if "params.".starts_with(typed) || typed.starts_with("params.") {
+            let params: Vec<&ParamSpec> = match (&resource, pipeline) {
+                (_, Some(pipeline)) => pipeline_params(pipeline, position),
+                (Some(Resource::Task(task)), _) => task.params.iter().collect(),
+                (Some(Resource::TaskRun(run)), _) => {
+                    run.task_spec.iter().flat_map(|t| &t.params).collect()
+                }
+                _ => vec![],
+            };
+            items.extend(param_items(&params, expression));
+        }
+        // Tasks see every field of their workspaces, Pipelines only whether
+        // they are bound, in `when` expressions
+        let task = match (&resource, pipeline) {
+            (_, Some(pipeline)) => inline_task_spec(pipeline, position),
+            (Some(Resource::Task(task)), _) => Some(task.as_ref()),
+            (Some(Resource::TaskRun(run)), _) => run.task_spec.as_ref(),
+            _ => None,
+        };
+        match (task, pipeline) {
+            (Some(task), _) => items.extend(workspace_items(
+                &task.workspaces,
+                &WORKSPACE_FIELDS,
+                expression,
+            )),
+            (None, Some(pipeline)) => items.extend(workspace_items(
+                &pipeline.workspaces,
+                &WORKSPACE_FIELDS[1..2],
+                expression,
+            )),
+            (None, None) => {}
+        }
+        if let Some(pipeline) = pipeline {
+            items.extend(self.task_result_items(pipeline, position, expression));
+        }
+        items
+    }
+
+    /// Completions for `$(tasks.<task>.results.<result>)`: the names of the
+    /// other tasks of `tasks`, then the results of the named task.
+    fn task_result_items(
+        &self,
+        pipeline: &Pipeline,
+        position: Position,
+        expression: &OpenExpression,
+    ) -> Vec<CompletionItem> {
+        // A task cannot use its own results, and finally tasks have none to use
+        let tasks: Vec<_> = pipeline
+            .tasks
             .iter()
-            .map(|field| self.field_to_completion_item(field))
+            .filter(|task| !contains(task.node.range, position))
+            .filter_map(|task| Some((task.name?.value, task)))
+            .collect();
+
+        let typed = expression.typed.as_str();
+        let rest = match typed.strip_prefix("tasks.") {
+            Some(rest) => rest,
+            None if "tasks.".starts_with(typed) => "",
+            None => return vec![],
+        };
+        let Some((name, field)) = rest.split_once('.') else {
+            return tasks
+                .iter()
+                .map(|(name, task)| {
+                    let detail = match task.task_ref.as_ref().and_then(|r| r.name) {
+                        Some(task_ref) => format!("Pipeline task running '{}'", task_ref.value),
+                        None => "Pipeline task".to_string(),
+                    };
+                    variable_item(&format!("tasks.{}", name), detail, None, expression, false)
+                })
+                .collect();
+        };
+        if !("results.".starts_with(field) || field.starts_with("results.")) {
+            return vec![];
+        }
+        let Some((_, task)) = tasks.iter().find(|(n, _)| *n == name) else {
+            return vec![];
+        };
+
+        self.task_results(name, task)
+            .into_iter()
+            .map(|result| {
+                let reference = match result.result_type.as_str() {
+                    "array" => format!("tasks.{}.results.{}[*]", name, result.name),
+                    _ => format!("tasks.{}.results.{}", name, result.name),
+                };
+                variable_item(
+                    &reference,
+                    result.detail,
+                    result.description.as_deref(),
+                    expression,
+                    true,
+                )
+            })
             .collect()
     }
 
+    /// The results of the pipeline task `name`: those of its inline
+    /// `taskSpec`, or of the indexed Task its `taskRef` names.
+    fn task_results(&self, name: &str, task: &PipelineTask) -> Vec<TaskResult> {
+        if let Some(task_spec) = &task.task_spec {
+            return task_spec
+                .results
+                .iter()
+                .filter_map(|result| {
+                    let result_type = result.type_or_default();
+                    Some(TaskResult {
+                        name: result.name?.value.to_string(),
+                        result_type: result_type.to_string(),
+                        detail: format!("{} result of task '{}'", result_type, name),
+                        description: result.description.map(|d| d.value.to_string()),
+                    })
+                })
+                .collect();
+        }
+        let Some((index, task_ref)) = self.index.as_ref().zip(task.task_ref.as_ref()) else {
+            return vec![];
+        };
+        let Some(definition) = task_ref
+            .name
+            .and_then(|n| index.find_resource(task_ref.kind_or_default(), n.value))
+        else {
+            return vec![];
+        };
+        definition
+            .results
+            .iter()
+            .map(|result| TaskResult {
+                name: result.name.clone(),
+                result_type: result.result_type.clone(),
+                detail: format!(
+                    "{} result of {} '{}'",
+                    result.result_type, definition.kind, definition.name
+                ),
+                description: result.description.clone(),
+            })
+            .collect()
+    }
+
+    /// Find the list of named items (`tasks`, `steps`, ...) the cursor is about to
+    /// add an item to.
+    ///
+    /// The parsed tree has no node for an empty line, so this relies on indentation:
+    /// the cursor must be on a line with no content below the list, at the column of
+    /// the list's `-` markers (or one level deeper than the key of an empty list).
+    fn list_item_starter_at<'a>(&self, root: &'a Node, position: Position) -> Option<&'a str> {
+        let mut nodes = Vec::new();
+        collect_nodes(root, &mut nodes);
+
+        nodes.iter().find_map(|node| {
+            let key = node
+                .key
+                .as_deref()
+                .filter(|key| NAMED_LIST_FIELDS.contains(key))?;
+            let key_range = node.key_range?;
+            let column = match &node.value {
+                NodeValue::Sequence(_) if node.style != Style::Flow => {
+                    node.value_range.start.character
+                }
+                NodeValue::Null => key_range.start.character + 2,
+                _ => return None,
+            };
+            let end_line = node.last_content_line();
+            if position.character != column || end_line >= position.line {
+                return None;
+            }
+            // Nothing may be written between the end of the list and the cursor
+            let lines = end_line + 1..=position.line;
+            let blank = !nodes
+                .iter()
+                .any(|other| lines.contains(&other.range.start.line));
+            blank.then_some(key)
+        })
+    }
+
     fn determine_context(&self, yaml_doc: &YamlDocument, position: Position) -> CompletionContext {
+        // Keys without indentation are those of the resource itself
+        if at_top_level(yaml_doc, position) {
+            return CompletionContext::Document;
+        }
+
+        // A taskRef is nested in several places (pipeline tasks, TaskRuns), check it first
+        if self
+            .find_node_with_key(&yaml_doc.root, "taskRef", position)
+            .is_some()
+        {
+            return CompletionContext::TaskRef;
+        }
+
         // Walk the document tree to find the context
         self.find_completion_context(&yaml_doc.root, position, yaml_doc)
     }
 
+    /// Find the innermost node with the given key whose range contains the position.
+    fn find_node_with_key<'a>(
+        &self,
+        node: &'a Node,
+        key: &str,
+        position: Position,
+    ) -> Option<&'a Node> {
+        if !contains(node.range, position) {
+            return None;
+        }
+
+        let children: Vec<&Node> = match &node.value {
+            NodeValue::Mapping(map) => map.values().collect(),
+            NodeValue::Sequence(items) => items.iter().collect(),
+            _ => vec![],
+        };
+        for child in children {
+            if let Some(found) = self.find_node_with_key(child, key, position) {
+                return Some(found);
+            }
+        }
+
+        if node.key.as_deref() == Some(key) {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    /// Name of the field of `mapping` whose value is being typed at the position
+    /// (the cursor is on the key's line, after its colon).
+    fn value_field_at<'a>(&self, mapping: &'a Node, position: Position) -> Option<&'a str> {
+        let NodeValue::Mapping(children) = &mapping.value else {
+            return None;
+        };
+        children.iter().find_map(|(key, child)| {
+            let key_range = child.key_range?;
+            let on_value =
+                key_range.end.line == position.line && position.character > key_range.end.character;
+            on_value.then_some(key.as_str())
+        })
+    }
+
+    /// Completions for the `name` of a param of a `taskRef` or `pipelineRef`
+    /// using a resolver: the params of the resolver, except those already
+    /// passed. `None` when the cursor is not there or the resolver is unknown.
+    fn resolver_param_completions(
+        &self,
+        root: &Node,
+        position: Position,
+    ) -> Option<Vec<CompletionItem>> {
+        let reference = self
+            .find_node_with_key(root, "taskRef", position)
+            .or_else(|| self.find_node_with_key(root, "pipelineRef", position))?;
+        let resolver = reference.get("resolver")?.as_scalar()?;
+        let schema = RESOLVERS.iter().find(|schema| schema.name == resolver)?;
+        let NodeValue::Sequence(params) = &reference.get("params")?.value else {
+            return None;
+        };
+        let (current, field) = params.iter().find_map(|param| {
+            let field = self
+                .value_field_at(param, position)
+                .filter(|field| matches!(*field, "name" | "value"))?;
+            Some((param, field))
+        })?;
+        if field == "value" {
+            return self.hub_param_value_completions(reference, params, current);
+        }
+
+        let passed: Vec<&str> = params
+            .iter()
+            .filter(|param| !std::ptr::eq(*param, current))
+            .filter_map(|param| param.get("name")?.as_scalar())
+            .collect();
+        let items = schema
+            .params
+            .iter()
+            .filter(|(name, _)| !passed.contains(name))
+            .map(|(name, description)| value_item(name, description))
+            .collect();
+        Some(items)
+    }
+
+    /// Completions for the `value` of the `name` or `version` param of a
+    /// reference using the `hub` resolver: the tasks (or pipelines) of the Hub
+    /// catalog, in the `catalog` param's catalog if set, or the versions of
+    /// the named one. `None` for other params and resolvers.
+    fn hub_param_value_completions(
+        &self,
+        reference: &Node,
+        params: &[Node],
+        current: &Node,
+    ) -> Option<Vec<CompletionItem>> {
+        if reference.get("resolver")?.as_scalar()? != "hub" {
+            return None;
+        }
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|p| p.get("name").and_then(|n| n.as_scalar()) == Some(name))
+                .and_then(|p| p.get("value")?.as_scalar())
+        };
+        let default_kind = match reference.key.as_deref() {
+            Some("pipelineRef") => "pipeline",
+            _ => "task",
+        };
+        let kind = param("kind").unwrap_or(default_kind);
+        let catalog = param("catalog");
+        let resources = self.hub.resources(kind);
+        let in_catalog = resources
+            .iter()
+            .filter(|resource| catalog.is_none_or(|c| c == resource.catalog));
+
+        let items = match current.get("name")?.as_scalar()? {
+            "name" => {
+                let mut items: Vec<CompletionItem> = Vec::new();
+                for resource in in_catalog {
+                    if items.iter().any(|item| item.label == resource.name) {
+                        continue;
+                    }
+                    items.push(CompletionItem {
+                        documentation: resource.description.clone().map(Documentation::String),
+                        ..value_item(
+                            &resource.name,
+                            &format!(
+                                "{} {} of the '{}' Hub catalog",
+                                resource.kind, resource.latest_version, resource.catalog
+                            ),
+                        )
+                    });
+                }
+                items
+            }
+            "version" => {
+                let name = param("name")?;
+                let mut items = Vec::new();
+                for resource in in_catalog.filter(|resource| resource.name == name) {
+                    for version in self.hub.versions(resource) {
+                        let detail = if version == resource.latest_version {
+                            format!("Latest version of '{}' in '{}'", name, resource.catalog)
+                        } else {
+                            format!("Version of '{}' in '{}'", name, resource.catalog)
+                        };
+                        items.push(CompletionItem {
+                            // Quoted, as versions such as 0.9 would be numbers
+                            insert_text: Some(format!("\"{}\"", version)),
+                            ..value_item(&version, &detail)
+                        });
+                    }
+                }
+                items
+            }
+            _ => return None,
+        };
+        Some(items)
+    }
+
+    /// The workspace declaration whose `mountPath` value is being typed.
+    ///
+    /// Matched by line only: the cursor after `mountPath: ` is past the end of
+    /// the parsed ranges when nothing follows the colon.
+    fn workspace_mount_path_at<'a>(&self, root: &'a Node, position: Position) -> Option<&'a Node> {
+        let mut nodes = Vec::new();
+        collect_nodes(root, &mut nodes);
+
+        nodes
+            .into_iter()
+            .filter(|node| node.key.as_deref() == Some("workspaces"))
+            .filter_map(|node| match &node.value {
+                NodeValue::Sequence(items) => Some(items),
+                _ => None,
+            })
+            .flatten()
+            .find(|item| self.value_field_at(item, position) == Some("mountPath"))
+    }
+
+    /// Completions for a workspace's `mountPath`: the default path for its name.
+    fn mount_path_completions(&self, workspace: &Node) -> Vec<CompletionItem> {
+        workspace
+            .get("name")
+            .and_then(|name| name.as_scalar())
+            .map(|name| {
+                value_item(
+                    &default_workspace_mount_path(name),
+                    "Default workspace mount path",
+                )
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// Completions for the values of `taskRef.kind` and `taskRef.apiVersion`.
+    fn task_ref_value_completions(&self, task_ref: &Node, field: &str) -> Vec<CompletionItem> {
+        let custom_kinds = self
+            .index
+            .as_ref()
+            .map(|index| index.custom_kinds())
+            .unwrap_or_default();
+
+        match field {
+            "resolver" => RESOLVERS
+                .iter()
+                .map(|resolver| value_item(resolver.name, resolver.description))
+                .collect(),
+            "kind" => {
+                let builtin = TASK_REF_KINDS
+                    .iter()
+                    .map(|kind| value_item(kind, "Tekton Pipelines kind"));
+                let custom = custom_kinds
+                    .iter()
+                    .map(|ck| value_item(&ck.kind, &format!("Custom kind ({})", ck.group)));
+                builtin.chain(custom).collect()
+            }
+            "apiVersion" => {
+                let kind = task_ref.get("kind").and_then(|k| k.as_scalar());
+                let is_builtin = kind.is_none_or(|k| TASK_REF_KINDS.contains(&k));
+
+                let mut items: Vec<CompletionItem> = Vec::new();
+                if is_builtin {
+                    items.extend(
+                        TEKTON_API_VERSIONS
+                            .iter()
+                            .map(|v| value_item(v, "Tekton Pipelines API version")),
+                    );
+                }
+                for ck in custom_kinds
+                    .iter()
+                    .filter(|ck| kind.is_none_or(|k| k == ck.kind))
+                {
+                    for api_version in ck.api_versions() {
+                        items.push(value_item(
+                            &api_version,
+                            &format!("API version of {}", ck.kind),
+                        ));
+                    }
+                }
+                items
+            }
+            _ => vec![],
+        }
+    }
+
     fn find_completion_context(
         &self,
         node: &Node,
@@ -48,7 +821,7 @@ impl CompletionProvider {
         yaml_doc: &YamlDocument,
     ) -> CompletionContext {
         // Check if position is within this node's range
-        if !self.position_in_range(position, &node.range) {
+        if !contains(node.range, position) {
             return CompletionContext::Unknown;
         }
 
@@ -63,11 +836,13 @@ impl CompletionProvider {
                     // First check if we're inside a child array (tasks/steps)
                     if let NodeValue::Mapping(children) = &node.value {
                         for (child_key, child) in children {
-                            if self.position_in_range(position, &child.range) {
+                            if contains(child.range, position) {
                                 // We're inside a specific child - check what it is
                                 match child_key.as_str() {
-                                    "tasks" | "finally" => return CompletionContext::PipelineTask,
-                                    "steps" => return CompletionContext::Step,
+                                    "tasks" | "finally" => {
+                                        return self.pipeline_task_context(child, position)
+                                    }
+                                    "steps" => return self.step_context(child, position),
                                     _ => {}
                                 }
                             }
@@ -77,19 +852,30 @@ impl CompletionProvider {
                     // Not in a child array - return spec context based on kind
                     if let Some(kind) = &yaml_doc.kind {
                         match kind.as_str() {
-                            "Pipeline" => return CompletionContext::PipelineSpec,
-                            "Task" => return CompletionContext::TaskSpec,
+                            "Pipeline" | "Task" | "StepAction" => {
+                                return declaration_context(node, position, kind)
+                            }
+                            "PipelineRun" | "TaskRun" => {
+                                return self.run_spec_context(node, position, yaml_doc)
+                            }
+                            "EventListener"
+                            | "Trigger"
+                            | "TriggerBinding"
+                            | "ClusterTriggerBinding"
+                            | "TriggerTemplate" => {
+                                return triggers_spec_context(node, position, kind)
+                            }
                             _ => {}
                         }
                     }
                 }
                 "tasks" | "finally" => {
                     // We're in a tasks array - completions are for PipelineTask
-                    return CompletionContext::PipelineTask;
+                    return self.pipeline_task_context(node, position);
                 }
                 "steps" => {
                     // We're in a steps array - completions are for Step
-                    return CompletionContext::Step;
+                    return self.step_context(node, position);
                 }
                 _ => {}
             }
@@ -97,7 +883,7 @@ impl CompletionProvider {
 
         // Recursively check children
         if let NodeValue::Mapping(children) = &node.value {
-            for (_key, child) in children {
+            for child in children.values() {
                 let child_context = self.find_completion_context(child, position, yaml_doc);
                 if child_context != CompletionContext::Unknown {
                     return child_context;
@@ -115,52 +901,1040 @@ impl CompletionProvider {
         CompletionContext::Unknown
     }
 
-    fn position_in_range(&self, pos: Position, range: &tower_lsp::lsp_types::Range) -> bool {
-        if pos.line < range.start.line || pos.line > range.end.line {
-            return false;
+    /// Context inside the spec of a PipelineRun or TaskRun: its inline
+    /// `pipelineSpec` or `taskSpec`, or the run's own fields. Nothing is
+    /// offered inside its other fields.
+    fn run_spec_context(
+        &self,
+        spec: &Node,
+        position: Position,
+        yaml_doc: &YamlDocument,
+    ) -> CompletionContext {
+        if let Some((key, child)) = field_holding(spec, position) {
+            let context = self.find_completion_context(child, position, yaml_doc);
+            return match (key.as_str(), context) {
+                ("pipelineSpec", CompletionContext::Unknown) => {
+                    declaration_context(child, position, "Pipeline")
+                }
+                ("taskSpec", CompletionContext::Unknown) => {
+                    declaration_context(child, position, "Task")
+                }
+                (_, context) => context,
+            };
         }
-        if pos.line == range.start.line && pos.character < range.start.character {
-            return false;
+
+        match yaml_doc.kind.as_deref() {
+            Some("PipelineRun") => CompletionContext::PipelineRunSpec,
+            _ => CompletionContext::TaskRunSpec,
         }
-        if pos.line == range.end.line && pos.character > range.end.character {
-            return false;
+    }
+
+    /// Context inside a `tasks` list: a pipeline task, an entry of its
+    /// `when`, or a part of its `matrix`.
+    fn pipeline_task_context(&self, tasks: &Node, position: Position) -> CompletionContext {
+        // Only below the key: on its line, the key itself is being completed
+        let below = |node: &Node| {
+            node.key_range
+                .is_some_and(|key| key.end.line < position.line)
+        };
+        if self
+            .find_node_with_key(tasks, "when", position)
+            .is_some_and(below)
+        {
+            return CompletionContext::When;
         }
-        true
+        let Some(matrix) = self
+            .find_node_with_key(tasks, "matrix", position)
+            .filter(|m| below(m))
+        else {
+            return CompletionContext::PipelineTask;
+        };
+
+        let include = self
+            .find_node_with_key(matrix, "include", position)
+            .filter(|i| below(i));
+        let params = self
+            .find_node_with_key(include.unwrap_or(matrix), "params", position)
+            .filter(|p| below(p));
+        match (include, params) {
+            (_, Some(_)) => CompletionContext::MatrixParam,
+            (Some(_), None) => CompletionContext::MatrixInclude,
+            (None, None) => CompletionContext::Matrix,
+        }
+    }
+
+    /// The field (`name` or `value`) of a `matrix.params` entry being typed.
+    fn matrix_param_field_at<'a>(&self, root: &'a Node, position: Position) -> Option<&'a str> {
+        let mut nodes = Vec::new();
+        collect_nodes(root, &mut nodes);
+
+        nodes
+            .into_iter()
+            .filter(|node| node.key.as_deref() == Some("matrix"))
+            .filter_map(|matrix| match &matrix.get("params")?.value {
+                NodeValue::Sequence(items) => Some(items),
+                _ => None,
+            })
+            .flatten()
+            .find_map(|item| {
+                self.value_field_at(item, position)
+                    .filter(|field| matches!(*field, "name" | "value"))
+            })
+    }
+
+    /// Completions inside the `runAfter` of a pipeline task: the names of the
+    /// other tasks of `tasks` it does not run after yet. `None` when the cursor
+    /// is not in a `runAfter`.
+    fn run_after_completions(
+        &self,
+        yaml_doc: &YamlDocument,
+        position: Position,
+    ) -> Option<Vec<CompletionItem>> {
+        let resource = Resource::from_document(yaml_doc);
+        let pipeline = pipeline_of(resource.as_ref()?)?;
+        // Finally tasks cannot use runAfter
+        let (current, run_after) = pipeline.tasks.iter().find_map(|task| {
+            let run_after = task.node.get("runAfter")?;
+            let key_range = run_after.key_range?;
+            let on_key_line =
+                key_range.end.line == position.line && position.character > key_range.end.character;
+            // Below the key, up to the last entry or a trailing empty `- `,
+            // which is in the range of the sequence but not in its items
+            let end = &run_after.range.end;
+            let last_line = match end.character {
+                0 if end.line > key_range.end.line => end.line - 1,
+                _ => end.line,
+            };
+            let below_key = matches!(run_after.value, NodeValue::Sequence(_))
+                && position.line > key_range.end.line
+                && position.line <= last_line.max(run_after.last_content_line());
+            (on_key_line || below_key).then_some((task, run_after))
+        })?;
+
+        let listed: Vec<&str> = match &run_after.value {
+            NodeValue::Sequence(items) => items
+                .iter()
+                .filter(|item| !contains(item.range, position))
+                .filter_map(|item| item.as_scalar())
+                .collect(),
+            _ => vec![],
+        };
+        let items = pipeline
+            .tasks
+            .iter()
+            .filter(|task| !std::ptr::eq(*task, current))
+            .filter_map(|task| task.name)
+            .filter(|name| !listed.contains(&name.value))
+            .map(|name| value_item(name.value, "Pipeline task"))
+            .collect();
+        Some(items)
+    }
+
+    /// Completions for the `name` of a pipeline task param: the params declared
+    /// by the Task its `taskRef` names, except those already passed. `None`
+    /// when the cursor is not there or the Task is not indexed.
+    fn task_param_name_completions(
+        &self,
+        yaml_doc: &YamlDocument,
+        position: Position,
+    ) -> Option<Vec<CompletionItem>> {
+        let index = self.index.as_ref()?;
+        let resource = Resource::from_document(yaml_doc);
+        let pipeline = pipeline_of(resource.as_ref()?)?;
+        let (task, current) = pipeline
+            .tasks
+            .iter()
+            .chain(&pipeline.finally)
+            .find_map(|task| {
+                let param = task
+                    .params
+                    .iter()
+                    .find(|param| self.value_field_at(param.node, position) == Some("name"))?;
+                Some((task, param))
+            })?;
+        let task_ref = task.task_ref.as_ref()?;
+        let definition = index.find_resource(task_ref.kind_or_default(), task_ref.name?.value)?;
+
+        let passed: Vec<&str> = task
+            .params
+            .iter()
+            .filter(|param| !std::ptr::eq(*param, current))
+            .filter_map(|param| param.name.map(|name| name.value))
+            .collect();
+        let items = definition
+            .params
+            .iter()
+            .filter(|param| !passed.contains(&param.name.as_str()))
+            .map(|param| {
+                let mut detail = format!(
+                    "{} param of {} '{}'",
+                    param.param_type, definition.kind, definition.name
+                );
+                if let Some(default) = &param.default {
+                    detail.push_str(&format!(", default: {}", default));
+                }
+                CompletionItem {
+                    documentation: param.description.clone().map(Documentation::String),
+                    ..value_item(&param.name, &detail)
+                }
+            })
+            .collect();
+        Some(items)
+    }
+
+    /// Completions for a pipeline task's workspace binding: its `name` to the
+    /// workspaces declared by the Task its `taskRef` names, except those
+    /// already bound, and its `workspace` to the Pipeline's workspaces. `None`
+    /// when the cursor is not there, or for a `name` if the Task is not indexed.
+    fn task_workspace_completions(
+        &self,
+        yaml_doc: &YamlDocument,
+        position: Position,
+    ) -> Option<Vec<CompletionItem>> {
+        let resource = Resource::from_document(yaml_doc);
+        let pipeline = pipeline_of(resource.as_ref()?)?;
+        let (task, current, field) =
+            pipeline
+                .tasks
+                .iter()
+                .chain(&pipeline.finally)
+                .find_map(|task| {
+                    task.workspaces.iter().find_map(|binding| {
+                        let field = self
+                            .value_field_at(binding.node, position)
+                            .filter(|field| matches!(*field, "name" | "workspace"))?;
+                        Some((task, binding, field))
+                    })
+                })?;
+
+        if field == "workspace" {
+            let items = pipeline
+                .workspaces
+                .iter()
+                .filter_map(|workspace| {
+                    let detail = if workspace.is_optional() {
+                        "Optional workspace of the Pipeline"
+                    } else {
+                        "Workspace of the Pipeline"
+                    };
+                    Some(CompletionItem {
+                        documentation: workspace
+                            .description
+                            .map(|d| Documentation::String(d.value.to_string())),
+                        ..value_item(workspace.name?.value, detail)
+                    })
+                })
+                .collect();
+            return Some(items);
+        }
+
+        let index = self.index.as_ref()?;
+        let task_ref = task.task_ref.as_ref()?;
+        let definition = index.find_resource(task_ref.kind_or_default(), task_ref.name?.value)?;
+        let bound: Vec<&str> = task
+            .workspaces
+            .iter()
+            .filter(|binding| !std::ptr::eq(*binding, current))
+            .filter_map(|binding| binding.name.map(|name| name.value))
+            .collect();
+        let items = definition
+            .workspaces
+            .iter()
+            .filter(|workspace| !bound.contains(&workspace.name.as_str()))
+            .map(|workspace| {
+                let detail = if workspace.optional {
+                    format!(
+                        "Optional workspace of {} '{}'",
+                        definition.kind, definition.name
+                    )
+                } else {
+                    format!("Workspace of {} '{}'", definition.kind, definition.name)
+                };
+                value_item(&workspace.name, &detail)
+            })
+            .collect();
+        Some(items)
+    }
+
+    /// Completions for the `input` or `operator` of a pipeline task's `when`
+    /// expression. Inputs are the string params of the Pipeline and the string
+    /// results of the other tasks of `tasks`. `None` when the cursor is not there.
+    fn when_value_completions(
+        &self,
+        yaml_doc: &YamlDocument,
+        position: Position,
+    ) -> Option<Vec<CompletionItem>> {
+        let resource = Resource::from_document(yaml_doc);
+        let pipeline = pipeline_of(resource.as_ref()?)?;
+        let (current, field) = pipeline
+            .tasks
+            .iter()
+            .chain(&pipeline.finally)
+            .find_map(|task| {
+                let field = task.when.iter().find_map(|when| {
+                    self.value_field_at(when.node, position)
+                        .filter(|field| matches!(*field, "input" | "operator"))
+                })?;
+                Some((task, field))
+            })?;
+
+        if field == "operator" {
+            let items = WHEN_OPERATORS
+                .iter()
+                .map(|(operator, description)| value_item(operator, description))
+                .collect();
+            return Some(items);
+        }
+
+        let mut items: Vec<CompletionItem> = pipeline
+            .params
+            .iter()
+            .filter(|param| param.type_or_default() == "string")
+            .filter_map(|param| {
+                let mut detail = "string param".to_string();
+                if let Some(default) = param.default {
+                    detail.push_str(&format!(", default: {}", to_flow_yaml(default)));
+                }
+                Some(CompletionItem {
+                    documentation: param
+                        .description
+                        .map(|d| Documentation::String(d.value.to_string())),
+                    ..value_item(&format!("$(params.{})", param.name?.value), &detail)
+                })
+            })
+            .collect();
+        // A task cannot use its own results
+        for task in pipeline.tasks.iter().filter(|t| !std::ptr::eq(*t, current)) {
+            let Some(name) = task.name else {
+                continue;
+            };
+            let results = self
+                .task_results(name.value, task)
+                .into_iter()
+                .filter(|result| result.result_type == "string");
+            items.extend(results.map(|result| CompletionItem {
+                documentation: result.description.map(Documentation::String),
+                ..value_item(
+                    &format!("$(tasks.{}.results.{})", name.value, result.name),
+                    &result.detail,
+                )
+            }));
+        }
+        Some(items)
+    }
+
+    /// Completions for a matrix param: the array params of the Pipeline, which
+    /// are what a matrix fans out over.
+    fn matrix_param_completions(
+        &self,
+        yaml_doc: &YamlDocument,
+        field: &str,
+    ) -> Vec<CompletionItem> {
+        let Some(Resource::Pipeline(pipeline)) = Resource::from_document(yaml_doc) else {
+            return vec![];
+        };
+        pipeline
+            .params
+            .iter()
+            .filter(|param| param.param_type.is_some_and(|t| t.value == "array"))
+            .filter_map(|param| param.name)
+            .map(|name| match field {
+                "name" => value_item(name.value, "Array param of the Pipeline"),
+                _ => value_item(
+                    &format!("$(params.{}[*])", name.value),
+                    "Array param of the Pipeline",
+                ),
+            })
+            .collect()
+    }
+
+    /// Context inside a `steps` list: a step, or one of its fields with
+    /// fields of their own (`artifacts`, `securityContext`, ...).
+    fn step_context(&self, steps: &Node, position: Position) -> CompletionContext {
+        let nested = [
+            ("artifacts", CompletionContext::Artifacts),
+            ("securityContext", CompletionContext::SecurityContext),
+            ("computeResources", CompletionContext::ComputeResources),
+            ("volumeMounts", CompletionContext::VolumeMount),
+            ("stdoutConfig", CompletionContext::OutputConfig),
+            ("stderrConfig", CompletionContext::OutputConfig),
+        ];
+        nested
+            .into_iter()
+            .find(|(key, _)| {
+                self.find_node_with_key(steps, key, position)
+                    .is_some_and(|node| node.key_range.is_some_and(|k| k.end.line < position.line))
+            })
+            .map_or(CompletionContext::Step, |(_, context)| context)
     }
 
+    /// The fields of the context, except the keys of `mapping` on other lines
+    /// than `line`, where a key may be retyped.
     fn get_fields_for_context(
         &self,
         context: &CompletionContext,
-        _yaml_doc: &YamlDocument,
+        yaml_doc: &YamlDocument,
+        mapping: Option<&Node>,
+        line: u32,
     ) -> Vec<FieldSchema> {
-        match context {
+        let present = |name: &str| {
+            mapping
+                .and_then(|mapping| mapping.get(name))
+                .and_then(|node| node.key_range)
+                .is_some_and(|key| key.start.line != line)
+        };
+        let mut fields = match context {
+            CompletionContext::Document => self.schemas.get_document_fields().to_vec(),
             CompletionContext::Metadata => self.schemas.get_metadata_fields().to_vec(),
             CompletionContext::PipelineSpec => self.schemas.get_pipeline_spec_fields().to_vec(),
             CompletionContext::PipelineTask => self.schemas.get_pipeline_task_fields().to_vec(),
             CompletionContext::TaskSpec => self.schemas.get_task_spec_fields().to_vec(),
-            CompletionContext::Step => self.schemas.get_step_fields().to_vec(),
+            CompletionContext::Step => {
+                let is_v1 = yaml_doc.api_version.as_deref() == Some(TEKTON_API_VERSIONS[0]);
+                self.schemas
+                    .get_step_fields()
+                    .iter()
+                    .filter(|field| is_v1 || !V1_ONLY_STEP_FIELDS.contains(&field.name.as_str()))
+                    .cloned()
+                    .collect()
+            }
+            CompletionContext::StepActionSpec => {
+                self.schemas.get_step_action_spec_fields().to_vec()
+            }
+            CompletionContext::PipelineRunSpec => {
+                self.schemas.get_pipeline_run_spec_fields().to_vec()
+            }
+            CompletionContext::TaskRunSpec => self.schemas.get_task_run_spec_fields().to_vec(),
+            CompletionContext::TaskRef => self.schemas.get_task_ref_fields().to_vec(),
+            CompletionContext::Artifacts => self.schemas.get_artifacts_fields().to_vec(),
+            CompletionContext::SecurityContext => {
+                self.schemas.get_security_context_fields().to_vec()
+            }
+            CompletionContext::ComputeResources => {
+                self.schemas.get_compute_resources_fields().to_vec()
+            }
+            CompletionContext::VolumeMount => self.schemas.get_volume_mount_fields().to_vec(),
+            CompletionContext::OutputConfig => self.schemas.get_output_config_fields().to_vec(),
+            CompletionContext::Matrix => self.schemas.get_matrix_fields().to_vec(),
+            CompletionContext::MatrixInclude => self.schemas.get_matrix_include_fields().to_vec(),
+            CompletionContext::MatrixParam => self.schemas.get_matrix_param_fields().to_vec(),
+            CompletionContext::When => self.schemas.get_when_fields().to_vec(),
+            CompletionContext::ParamSpec => self.schemas.get_param_spec_fields().to_vec(),
+            CompletionContext::WorkspaceDeclaration => {
+                self.schemas.get_workspace_declaration_fields().to_vec()
+            }
+            CompletionContext::PipelineWorkspace => {
+                self.schemas.get_pipeline_workspace_fields().to_vec()
+            }
+            CompletionContext::EventListenerSpec => {
+                self.schemas.get_event_listener_spec_fields().to_vec()
+            }
+            CompletionContext::Trigger => self.schemas.get_trigger_fields().to_vec(),
+            CompletionContext::TriggerBindingSpec => {
+                self.schemas.get_trigger_binding_spec_fields().to_vec()
+            }
+            CompletionContext::TriggerBindingParam => {
+                self.schemas.get_trigger_binding_param_fields().to_vec()
+            }
+            CompletionContext::TriggerTemplateSpec => {
+                self.schemas.get_trigger_template_spec_fields().to_vec()
+            }
+            CompletionContext::TriggerTemplateParam => {
+                self.schemas.get_trigger_template_param_fields().to_vec()
+            }
             CompletionContext::Unknown => vec![],
-        }
+        };
+        fields.retain(|field| !present(&field.name));
+        fields
     }
 
-    fn field_to_completion_item(&self, field: &FieldSchema) -> CompletionItem {
-        use super::schemas::FieldType;
+    /// Completions for the value of the field `key` of the context: the values
+    /// of a field taking one of a few strings, or `true` and `false`.
+    fn field_value_completions(
+        &self,
+        context: &CompletionContext,
+        yaml_doc: &YamlDocument,
+        key: &str,
+    ) -> Vec<CompletionItem> {
+        let fields = self.get_fields_for_context(context, yaml_doc, None, 0);
+        let Some(field) = fields.iter().find(|field| field.name == key) else {
+            return vec![];
+        };
+        let values: &[&str] = match field.field_type {
+            FieldType::Enum(values) => values,
+            FieldType::Boolean => &["true", "false"],
+            _ => &[],
+        };
+        values
+            .iter()
+            .map(|value| value_item(value, &field.description))
+            .collect()
+    }
 
+    fn field_to_completion_item(
+        &self,
+        field: &FieldSchema,
+        yaml_doc: &YamlDocument,
+        context: CompletionContext,
+    ) -> CompletionItem {
         let kind = match field.field_type {
-            FieldType::String => CompletionItemKind::FIELD,
+            FieldType::String | FieldType::Enum(_) => CompletionItemKind::FIELD,
             FieldType::Array => CompletionItemKind::VALUE,
             FieldType::Object => CompletionItemKind::STRUCT,
             FieldType::Boolean => CompletionItemKind::VALUE,
         };
 
-        CompletionItem {
+        let data = ResolveData {
+            field: field.name.clone(),
+            kind: yaml_doc.kind.clone(),
+            api_version: yaml_doc.api_version.clone(),
+            section: context.section(),
+        };
+        let item = CompletionItem {
             label: field.name.clone(),
             kind: Some(kind),
             detail: Some(field.description.clone()),
             documentation: None,
+            data: serde_json::to_value(data).ok(),
+            ..Default::default()
+        };
+        let snippet = FIELD_SNIPPETS
+            .iter()
+            .find(|(name, _)| *name == field.name)
+            .filter(|_| self.snippet_support());
+        match snippet {
+            Some((_, snippet)) => CompletionItem {
+                insert_text: Some(snippet.to_string()),
+                insert_text_format: Some(InsertTextFormat::SNIPPET),
+                insert_text_mode: Some(InsertTextMode::ADJUST_INDENTATION),
+                ..item
+            },
+            None => item,
+        }
+    }
+}
+
+/// Order completion items in groups: snippets, then preselected items (the
+/// required fields a mapping lacks), then the others, each group keeping the
+/// order it was built in. Their `sort_text` keeps clients from sorting them
+/// by label instead.
+fn rank(items: &mut [CompletionItem]) {
+    let group = |item: &CompletionItem| match item {
+        _ if item.kind == Some(CompletionItemKind::SNIPPET) => 0,
+        _ if item.preselect == Some(true) => 1,
+        _ => 2,
+    };
+    items.sort_by_key(group);
+    for (index, item) in items.iter_mut().enumerate() {
+        item.sort_text = Some(format!("{}{:04}", group(item), index));
+    }
+}
+
+/// Build the completion starting a new item of the list `field`, leaving the
+/// cursor after `name: ` when the client expands snippets.
+fn list_item_starter(field: &str, snippets: bool) -> CompletionItem {
+    let (insert_text, format) = if snippets {
+        ("- name: $0", InsertTextFormat::SNIPPET)
+    } else {
+        ("- name: ", InsertTextFormat::PLAIN_TEXT)
+    };
+    CompletionItem {
+        label: "- name".to_string(),
+        kind: Some(CompletionItemKind::SNIPPET),
+        detail: Some(format!("New item of '{}'", field)),
+        insert_text: Some(insert_text.to_string()),
+        insert_text_format: Some(format),
+        ..Default::default()
+    }
+}
+
+/// Build the completion inserting a whole resource, with its placeholders
+/// left at their defaults when the client does not expand snippets.
+fn document_snippet(label: &str, snippet: &str, snippets: bool) -> CompletionItem {
+    let (insert_text, format) = if snippets {
+        (snippet.to_string(), InsertTextFormat::SNIPPET)
+    } else {
+        (snippet_to_plain_text(snippet), InsertTextFormat::PLAIN_TEXT)
+    };
+    CompletionItem {
+        label: label.to_string(),
+        kind: Some(CompletionItemKind::SNIPPET),
+        detail: Some(format!("{} resource", label.trim_start_matches("New "))),
+        insert_text: Some(insert_text),
+        insert_text_format: Some(format),
+        ..Default::default()
+    }
+}
+
+/// The text of a snippet with its `${n:default}` placeholders replaced by
+/// their defaults.
+fn snippet_to_plain_text(snippet: &str) -> String {
+    let mut text = String::new();
+    let mut rest = snippet;
+    while let Some(start) = rest.find("${") {
+        text.push_str(&rest[..start]);
+        let placeholder = &rest[start + 2..];
+        let end = placeholder.find('}').unwrap_or(placeholder.len());
+        text.push_str(placeholder[..end].split_once(':').map_or("", |(_, d)| d));
+        rest = placeholder.get(end + 1..).unwrap_or_default();
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Collect `node` and every node below it.
+fn collect_nodes<'a>(node: &'a Node, nodes: &mut Vec<&'a Node>) {
+    nodes.push(node);
+    match &node.value {
+        NodeValue::Mapping(map) => map.values().for_each(|child| collect_nodes(child, nodes)),
+        NodeValue::Sequence(items) => items.iter().for_each(|item| collect_nodes(item, nodes)),
+        _ => {}
+    }
+}
+
+/// An unclosed `$(` expression at the cursor.
+struct OpenExpression {
+    /// Text typed after `$(`, up to the cursor
+    typed: String,
+    /// Range of the typed text and of the rest of the name after the cursor
+    range: Range,
+    /// Whether a `)` already closes the expression
+    closed: bool,
+}
+
+/// The mapping whose keys are typed at the position: the innermost one with
+/// keys at the column of the line's key (or of the cursor on a blank line)
+/// whose range spans the line.
+fn mapping_at(yaml_doc: &YamlDocument, position: Position) -> Option<&Node> {
+    let line = yaml_doc.line_text(position.line).unwrap_or_default();
+    // Indentation and `- ` markers are ASCII: their bytes are columns
+    let mut rest = line.trim_start();
+    while let Some(item) = rest.strip_prefix("- ") {
+        rest = item.trim_start();
+    }
+    let column = if rest.is_empty() {
+        position.character
+    } else {
+        (line.len() - rest.len()) as u32
+    };
+
+    let mut nodes = Vec::new();
+    collect_nodes(&yaml_doc.root, &mut nodes);
+    // Nodes are collected parents first: the innermost match is the last
+    nodes.into_iter().rfind(|node| {
+        let spans_line =
+            node.range.start.line <= position.line && position.line <= node.range.end.line;
+        let has_key_at_column = match &node.value {
+            NodeValue::Mapping(map) => map.values().any(|child| {
+                child
+                    .key_range
+                    .is_some_and(|key| key.start.character == column)
+            }),
+            _ => false,
+        };
+        spans_line && has_key_at_column
+    })
+}
+
+/// Context inside the spec of a Pipeline, Task or StepAction outside of its
+/// tasks and steps: a declaration of its params or workspaces, or the spec's
+/// own fields.
+fn declaration_context(spec: &Node, position: Position, kind: &str) -> CompletionContext {
+    let (spec_context, lists): (_, &[(&str, CompletionContext)]) = match kind {
+        "Pipeline" => (
+            CompletionContext::PipelineSpec,
+            &[
+                ("params", CompletionContext::ParamSpec),
+                ("workspaces", CompletionContext::PipelineWorkspace),
+            ],
+        ),
+        "StepAction" => (
+            CompletionContext::StepActionSpec,
+            &[("params", CompletionContext::ParamSpec)],
+        ),
+        _ => (
+            CompletionContext::TaskSpec,
+            &[
+                ("params", CompletionContext::ParamSpec),
+                ("workspaces", CompletionContext::WorkspaceDeclaration),
+            ],
+        ),
+    };
+    list_item_context(spec, position, lists).unwrap_or(spec_context)
+}
+
+/// Context inside the spec of a Triggers resource: an item of one of its
+/// lists of named items, or the spec's own fields. Nothing is offered inside
+/// its other fields.
+fn triggers_spec_context(spec: &Node, position: Position, kind: &str) -> CompletionContext {
+    let (spec_context, lists): (_, &[(&str, CompletionContext)]) = match kind {
+        "EventListener" => (
+            CompletionContext::EventListenerSpec,
+            &[("triggers", CompletionContext::Trigger)],
+        ),
+        "Trigger" => (CompletionContext::Trigger, &[]),
+        "TriggerTemplate" => (
+            CompletionContext::TriggerTemplateSpec,
+            &[("params", CompletionContext::TriggerTemplateParam)],
+        ),
+        _ => (
+            CompletionContext::TriggerBindingSpec,
+            &[("params", CompletionContext::TriggerBindingParam)],
+        ),
+    };
+    match field_holding(spec, position) {
+        Some(_) => list_item_context(spec, position, lists).unwrap_or(CompletionContext::Unknown),
+        None => spec_context,
+    }
+}
+
+/// Context inside one of the `lists` of `spec`, given with the context of
+/// their items: the item's, or `Unknown` inside the values nested in it.
+/// `None` outside of these lists.
+fn list_item_context(
+    spec: &Node,
+    position: Position,
+    lists: &[(&str, CompletionContext)],
+) -> Option<CompletionContext> {
+    let (key, child) = field_holding(spec, position)?;
+    let (_, item_context) = lists.iter().find(|(list, _)| list == key)?;
+    let NodeValue::Sequence(items) = &child.value else {
+        return Some(CompletionContext::Unknown);
+    };
+    // Fields of an item, not of the values nested in it
+    match items.iter().find(|item| contains(item.range, position)) {
+        Some(item) if field_holding(item, position).is_some() => Some(CompletionContext::Unknown),
+        _ => Some(*item_context),
+    }
+}
+
+/// The field of `mapping` whose value spans the position, on a line below the
+/// field's key.
+fn field_holding(mapping: &Node, position: Position) -> Option<(&String, &Node)> {
+    let NodeValue::Mapping(children) = &mapping.value else {
+        return None;
+    };
+    children.iter().find(|(_, child)| {
+        contains(child.range, position)
+            && child
+                .key_range
+                .is_some_and(|key| key.end.line < position.line)
+    })
+}
+
+/// The `labels` or `annotations` of the metadata whose keys are typed at the
+/// position, with the field's name: the cursor is indented below the field,
+/// with only its keys or blank lines in between.
+fn metadata_map_at(yaml_doc: &YamlDocument, position: Position) -> Option<(&str, &Node)> {
+    let metadata = yaml_doc.root.get("metadata")?;
+    let line = yaml_doc.line_text(position.line).unwrap_or_default();
+    let byte = yaml_doc.encoding.byte_offset(line, position.character);
+    let typed = line.get(..byte).unwrap_or(line);
+    if typed.contains(':') {
+        return None;
+    }
+    // Indentation is ASCII: its bytes are columns
+    let indentation = (typed.len() - typed.trim_start().len()) as u32;
+
+    ["labels", "annotations"].into_iter().find_map(|field| {
+        let map = metadata.get(field)?;
+        let key = map.key_range?;
+        if position.line <= key.end.line || indentation <= key.start.character {
+            return None;
+        }
+        let last = map.last_content_line().max(key.end.line);
+        let blank = (last + 1..position.line).all(|l| {
+            yaml_doc
+                .line_text(l)
+                .is_none_or(|text| text.trim().is_empty())
+        });
+        blank.then_some((field, map))
+    })
+}
+
+/// Completions for the keys of the metadata's `labels` or `annotations`: the
+/// well-known ones the map does not have yet, replacing the key typed so far.
+/// The Pipelines-as-Code annotations are offered in PipelineRuns and in
+/// `.tekton/` files.
+fn metadata_key_completions(
+    yaml_doc: &YamlDocument,
+    position: Position,
+    field: &str,
+    map: &Node,
+) -> Vec<CompletionItem> {
+    let keys: Vec<(&str, &str, &str)> = match field {
+        "labels" => METADATA_LABELS
+            .iter()
+            .map(|(key, description)| (*key, *description, "Recommended Kubernetes label"))
+            .collect(),
+        _ => {
+            let pac = pac::is_pac_document(&yaml_doc.filename)
+                || yaml_doc.kind.as_deref() == Some("PipelineRun");
+            let pac_annotations = pac::ANNOTATIONS
+                .iter()
+                .filter(|_| pac)
+                .map(|(key, description)| (*key, *description, "Pipelines-as-Code annotation"));
+            METADATA_ANNOTATIONS
+                .iter()
+                .map(|(key, description)| (*key, *description, "Tekton annotation"))
+                .chain(pac_annotations)
+                .collect()
+        }
+    };
+
+    let line = yaml_doc.line_text(position.line).unwrap_or_default();
+    let indentation = (line.len() - line.trim_start().len()) as u32;
+    let range = Range {
+        start: Position {
+            line: position.line,
+            character: indentation.min(position.character),
+        },
+        end: position,
+    };
+    keys.into_iter()
+        .filter(|(key, _, _)| {
+            map.get(key)
+                .and_then(|node| node.key_range)
+                .is_none_or(|k| k.start.line == position.line)
+        })
+        .map(|(key, description, detail)| CompletionItem {
+            label: key.to_string(),
+            kind: Some(CompletionItemKind::PROPERTY),
+            detail: Some(detail.to_string()),
+            documentation: Some(Documentation::String(description.to_string())),
+            filter_text: Some(key.to_string()),
+            text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+                range,
+                new_text: key.to_string(),
+            })),
             ..Default::default()
+        })
+        .collect()
+}
+
+/// Whether the cursor types a key of the resource itself: nothing before it
+/// on its line is indented, a list item or a comment.
+fn at_top_level(yaml_doc: &YamlDocument, position: Position) -> bool {
+    let line = yaml_doc.line_text(position.line).unwrap_or_default();
+    let byte = yaml_doc.encoding.byte_offset(line, position.character);
+    let typed = line.get(..byte).unwrap_or(line);
+    !typed.starts_with([' ', '\t', '-', '#'])
+}
+
+/// Whether every line of the document but `line` is blank: a new file to
+/// scaffold a resource in.
+fn is_blank_except(yaml_doc: &YamlDocument, line: u32) -> bool {
+    yaml_doc
+        .source
+        .lines()
+        .enumerate()
+        .all(|(i, text)| i == line as usize || text.trim().is_empty())
+}
+
+/// Whether the position is on a line of a multi-line scalar after its first.
+fn in_scalar_continuation(root: &Node, position: Position) -> bool {
+    let mut nodes = Vec::new();
+    collect_nodes(root, &mut nodes);
+    nodes.into_iter().any(|node| {
+        matches!(node.value, NodeValue::Scalar(_))
+            && node.value_range.start.line < position.line
+            && position.line <= node.last_content_line()
+    })
+}
+
+/// The key whose value the cursor is in, with the position of the key, when a
+/// `:` followed by a space (or the cursor) comes before it on its line; `None`
+/// when a key is typed.
+fn value_key_at(yaml_doc: &YamlDocument, position: Position) -> Option<(String, Position)> {
+    let line = yaml_doc.line_text(position.line)?;
+    let byte = yaml_doc.encoding.byte_offset(line, position.character);
+    let mut rest = line.get(..byte)?.trim_start();
+    while let Some(item) = rest.strip_prefix("- ") {
+        rest = item.trim_start();
+    }
+
+    let mut quote = None;
+    for (i, c) in rest.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (':', None) => {
+                let after = &rest[i + 1..];
+                if after.is_empty() || after.starts_with([' ', '\t']) {
+                    let key = rest[..i].trim().trim_matches(['"', '\'']);
+                    let key_position = Position {
+                        line: position.line,
+                        character: yaml_doc.encoding.column(line, byte - rest.len()),
+                    };
+                    return Some((key.to_string(), key_position));
+                }
+            }
+            _ => {}
         }
     }
+    None
+}
+
+/// A result of a pipeline task.
+struct TaskResult {
+    name: String,
+    /// The declared type, `string` when absent
+    result_type: String,
+    detail: String,
+    description: Option<String>,
+}
+
+/// The Pipeline of a document: a Pipeline, or the `pipelineSpec` of a
+/// PipelineRun.
+fn pipeline_of<'r, 'a>(resource: &'r Resource<'a>) -> Option<&'r Pipeline<'a>> {
+    match resource {
+        Resource::Pipeline(pipeline) => Some(pipeline),
+        Resource::PipelineRun(run) => run.pipeline_spec.as_ref(),
+        _ => None,
+    }
+}
+
+/// The params in scope at a position of a Pipeline: those of the inline
+/// `taskSpec` holding it, then the Pipeline's.
+fn pipeline_params<'p, 'a>(
+    pipeline: &'p Pipeline<'a>,
+    position: Position,
+) -> Vec<&'p ParamSpec<'a>> {
+    inline_task_spec(pipeline, position)
+        .into_iter()
+        .flat_map(|task| &task.params)
+        .chain(&pipeline.params)
+        .collect()
+}
+
+/// The inline `taskSpec` of a pipeline task holding a position.
+fn inline_task_spec<'p, 'a>(
+    pipeline: &'p Pipeline<'a>,
+    position: Position,
+) -> Option<&'p Task<'a>> {
+    pipeline
+        .all_tasks()
+        .filter_map(|task| task.task_spec.as_ref())
+        .find(|task| task.spec.is_some_and(|spec| contains(spec.range, position)))
+}
+
+/// Completion items for `$(workspaces.<name>.<field>)`: the names of the
+/// declared workspaces, then their fields.
+fn workspace_items(
+    workspaces: &[WorkspaceDeclaration],
+    fields: &[(&str, &str)],
+    expression: &OpenExpression,
+) -> Vec<CompletionItem> {
+    let typed = expression.typed.as_str();
+    let rest = match typed.strip_prefix("workspaces.") {
+        Some(rest) => rest,
+        None if "workspaces.".starts_with(typed) => "",
+        None => return vec![],
+    };
+    let names = workspaces.iter().filter_map(|w| Some((w.name?.value, w)));
+
+    let Some((name, _)) = rest.split_once('.') else {
+        return names
+            .map(|(name, workspace)| {
+                let detail = if workspace.is_optional() {
+                    "Optional workspace"
+                } else {
+                    "Workspace"
+                };
+                variable_item(
+                    &format!("workspaces.{}", name),
+                    detail.to_string(),
+                    workspace.description.map(|d| d.value),
+                    expression,
+                    false,
+                )
+            })
+            .collect();
+    };
+    if !names.clone().any(|(n, _)| n == name) {
+        return vec![];
+    }
+    fields
+        .iter()
+        .map(|(field, description)| {
+            variable_item(
+                &format!("workspaces.{}.{}", name, field),
+                description.to_string(),
+                None,
+                expression,
+                true,
+            )
+        })
+        .collect()
+}
+
+/// Completion items for `$(params.<name>)` references to params.
+fn param_items(params: &[&ParamSpec], expression: &OpenExpression) -> Vec<CompletionItem> {
+    let mut items: Vec<CompletionItem> = Vec::new();
+    for param in params {
+        let Some(name) = param.name else {
+            continue;
+        };
+        let param_type = param.type_or_default();
+        let mut detail = format!("{} param", param_type);
+        if let Some(default) = param.default {
+            detail.push_str(&format!(", default: {}", to_flow_yaml(default)));
+        }
+        let reference = match param_type {
+            "array" => format!("params.{}[*]", name.value),
+            _ => format!("params.{}", name.value),
+        };
+        // A taskSpec param shadows the Pipeline's param of the same name
+        if items.iter().any(|item| item.label == reference) {
+            continue;
+        }
+        items.push(variable_item(
+            &reference,
+            detail,
+            param.description.map(|d| d.value),
+            expression,
+            true,
+        ));
+    }
+    items
+}
+
+/// Build the completion item replacing the name typed in an unclosed `$(`
+/// with `reference`, closing the expression when `complete`.
+fn variable_item(
+    reference: &str,
+    detail: String,
+    description: Option<&str>,
+    expression: &OpenExpression,
+    complete: bool,
+) -> CompletionItem {
+    let new_text = if complete && !expression.closed {
+        format!("{})", reference)
+    } else {
+        reference.to_string()
+    };
+    CompletionItem {
+        label: reference.to_string(),
+        kind: Some(CompletionItemKind::VARIABLE),
+        detail: Some(detail),
+        documentation: description.map(|d| Documentation::String(d.to_string())),
+        filter_text: Some(reference.to_string()),
+        text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+            range: expression.range,
+            new_text,
+        })),
+        ..Default::default()
+    }
+}
+
+/// Build a completion item for a field value.
+fn value_item(value: &str, detail: &str) -> CompletionItem {
+    CompletionItem {
+        label: value.to_string(),
+        kind: Some(CompletionItemKind::ENUM_MEMBER),
+        detail: Some(detail.to_string()),
+        ..Default::default()
+    }
 }
 
 impl Default for CompletionProvider {
@@ -169,12 +1943,72 @@ impl Default for CompletionProvider {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 enum CompletionContext {
+    Document,
     Metadata,
     PipelineSpec,
     PipelineTask,
     TaskSpec,
     Step,
+    StepActionSpec,
+    PipelineRunSpec,
+    TaskRunSpec,
+    TaskRef,
+    Artifacts,
+    SecurityContext,
+    ComputeResources,
+    VolumeMount,
+    OutputConfig,
+    Matrix,
+    MatrixInclude,
+    MatrixParam,
+    When,
+    EventListenerSpec,
+    Trigger,
+    TriggerBindingSpec,
+    TriggerBindingParam,
+    TriggerTemplateSpec,
+    TriggerTemplateParam,
+    ParamSpec,
+    WorkspaceDeclaration,
+    PipelineWorkspace,
     Unknown,
 }
+
+impl CompletionContext {
+    /// The schema section holding the fields of this context.
+    fn section(self) -> Option<Section> {
+        Some(match self {
+            CompletionContext::Document => Section::Root,
+            CompletionContext::Metadata => Section::Metadata,
+            CompletionContext::PipelineSpec => Section::PipelineSpec,
+            CompletionContext::PipelineTask => Section::PipelineTask,
+            CompletionContext::TaskSpec => Section::TaskSpec,
+            CompletionContext::Step => Section::Step,
+            CompletionContext::StepActionSpec => Section::StepActionSpec,
+            CompletionContext::PipelineRunSpec => Section::PipelineRunSpec,
+            CompletionContext::TaskRunSpec => Section::TaskRunSpec,
+            CompletionContext::TaskRef => Section::TaskRef,
+            CompletionContext::Artifacts => Section::Artifacts,
+            CompletionContext::SecurityContext => Section::SecurityContext,
+            CompletionContext::ComputeResources => Section::ComputeResources,
+            CompletionContext::VolumeMount => Section::VolumeMount,
+            CompletionContext::OutputConfig => Section::OutputConfig,
+            CompletionContext::Matrix => Section::Matrix,
+            CompletionContext::MatrixInclude => Section::MatrixInclude,
+            CompletionContext::MatrixParam => Section::MatrixParam,
+            CompletionContext::When => Section::When,
+            CompletionContext::EventListenerSpec => Section::EventListenerSpec,
+            CompletionContext::Trigger => Section::Trigger,
+            CompletionContext::TriggerBindingSpec => Section::TriggerBindingSpec,
+            CompletionContext::TriggerBindingParam => Section::TriggerBindingParam,
+            CompletionContext::TriggerTemplateSpec => Section::TriggerTemplateSpec,
+            CompletionContext::TriggerTemplateParam => Section::TriggerTemplateParam,
+            CompletionContext::ParamSpec => Section::ParamSpec,
+            CompletionContext::WorkspaceDeclaration => Section::WorkspaceDeclaration,
+            CompletionContext::PipelineWorkspace => Section::PipelineWorkspace,
+            CompletionContext::Unknown => return None,
+        })
+    }
+}
@@ -2,13 +2,151 @@
 //!
 //! Defines the fields available for different Tekton resource types.
 
+/// Built-in kinds a `taskRef` can point to.
+pub const TASK_REF_KINDS: [&str; 2] = ["Task", "ClusterTask"];
+
+/// API versions of the built-in Tekton Pipelines kinds.
+pub const TEKTON_API_VERSIONS: [&str; 2] = ["tekton.dev/v1", "tekton.dev/v1beta1"];
+
+/// Operators of `when` expressions, with what they check.
+pub const WHEN_OPERATORS: [(&str, &str); 2] = [
+    ("in", "The input is one of the values"),
+    ("notin", "The input is none of the values"),
+];
+
+/// Recommended Kubernetes labels of `metadata.labels`, with what they hold.
+pub const METADATA_LABELS: [(&str, &str); 6] = [
+    ("app.kubernetes.io/name", "Name of the application"),
+    (
+        "app.kubernetes.io/instance",
+        "Unique name of this instance of the application",
+    ),
+    (
+        "app.kubernetes.io/version",
+        "Version of the application; the Tekton catalog and Hub use it as the version of the resource",
+    ),
+    (
+        "app.kubernetes.io/component",
+        "Component of the application, such as build or deploy",
+    ),
+    (
+        "app.kubernetes.io/part-of",
+        "Name of the higher-level application this is part of",
+    ),
+    (
+        "app.kubernetes.io/managed-by",
+        "Tool managing the resource, such as Helm or Argo CD",
+    ),
+];
+
+/// Tekton annotations of `metadata.annotations`, read by the Tekton Hub and
+/// catalog, with what they do.
+pub const METADATA_ANNOTATIONS: [(&str, &str); 6] = [
+    (
+        "tekton.dev/pipelines.minVersion",
+        "Minimum Tekton Pipelines version the resource needs, e.g. \"0.50.0\"",
+    ),
+    (
+        "tekton.dev/displayName",
+        "Human-readable name of the resource shown by the Hub",
+    ),
+    (
+        "tekton.dev/categories",
+        "Comma-separated Hub categories of the resource, e.g. Git",
+    ),
+    (
+        "tekton.dev/tags",
+        "Comma-separated tags of the resource, used by the Hub search",
+    ),
+    (
+        "tekton.dev/platforms",
+        "Comma-separated platforms the resource runs on, e.g. linux/amd64,linux/arm64",
+    ),
+    (
+        "tekton.dev/deprecated",
+        "Set to \"true\" to mark the resource as deprecated in the Hub",
+    ),
+];
+
+/// A remote resolver, with the params it takes.
+#[derive(Debug)]
+pub struct ResolverSchema {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Names of the params, with what they hold
+    pub params: &'static [(&'static str, &'static str)],
+}
+
+/// The built-in remote resolvers of `taskRef` and `pipelineRef`.
+pub const RESOLVERS: [ResolverSchema; 4] = [
+    ResolverSchema {
+        name: "git",
+        description: "Fetch the resource from a Git repository",
+        params: &[
+            ("url", "URL of the repository to clone anonymously"),
+            ("revision", "Branch, tag or commit to check out"),
+            ("pathInRepo", "Path of the YAML file in the repository"),
+            ("repo", "Name of the repository, fetched with the SCM API"),
+            (
+                "org",
+                "Organization of the repository, fetched with the SCM API",
+            ),
+            ("token", "Secret holding the SCM API token"),
+            ("tokenKey", "Key of the token in the secret"),
+            ("scmType", "SCM provider: github, gitlab, gitea, bitbucket"),
+            (
+                "serverURL",
+                "URL of the SCM server, for self-hosted providers",
+            ),
+        ],
+    },
+    ResolverSchema {
+        name: "hub",
+        description: "Fetch the resource from Tekton Hub or Artifact Hub",
+        params: &[
+            ("catalog", "Catalog to fetch from"),
+            ("type", "Hub type: artifact (default) or tekton"),
+            ("kind", "Kind of the resource: task or pipeline"),
+            ("name", "Name of the resource in the catalog"),
+            ("version", "Version of the resource"),
+        ],
+    },
+    ResolverSchema {
+        name: "bundles",
+        description: "Fetch the resource from an OCI bundle",
+        params: &[
+            ("bundle", "Reference of the OCI bundle image"),
+            ("name", "Name of the resource in the bundle"),
+            ("kind", "Kind of the resource: task or pipeline"),
+            ("secret", "Secret with the credentials to pull the bundle"),
+        ],
+    },
+    ResolverSchema {
+        name: "cluster",
+        description: "Fetch the resource from the cluster",
+        params: &[
+            ("kind", "Kind of the resource: task or pipeline"),
+            ("name", "Name of the resource"),
+            ("namespace", "Namespace of the resource"),
+        ],
+    },
+];
+
+/// Step fields only available in tekton.dev/v1.
+pub const V1_ONLY_STEP_FIELDS: [&str; 1] = ["artifacts"];
+
+/// PipelineRun spec fields only available in tekton.dev/v1.
+pub const V1_ONLY_PIPELINE_RUN_FIELDS: [&str; 1] = ["taskRunTemplate"];
+
+/// PipelineRun spec fields tekton.dev/v1 moved under `taskRunTemplate`.
+pub const V1BETA1_ONLY_PIPELINE_RUN_FIELDS: [&str; 2] = ["podTemplate", "serviceAccountName"];
+
 #[derive(Debug, Clone)]
 pub struct FieldSchema {
     pub name: String,
     pub description: String,
     pub field_type: FieldType,
-    /// Whether this field is required (for future validation/snippets)
-    #[allow(dead_code)]
+    /// Whether this field is required; preselected in completion when missing
     pub required: bool,
 }
 
@@ -17,23 +155,78 @@ pub enum FieldType {
     String,
     Array,
     Object,
-    /// Boolean type for future use
-    #[allow(dead_code)]
+    /// Completed to `true` and `false` after its `:`
     Boolean,
+    /// A string taking one of the given values, completed after its `:`
+    Enum(&'static [&'static str]),
 }
 
 #[derive(Debug, Clone)]
 pub struct TektonSchemas {
+    document_fields: Vec<FieldSchema>,
     metadata_fields: Vec<FieldSchema>,
     pipeline_spec_fields: Vec<FieldSchema>,
     pipeline_task_fields: Vec<FieldSchema>,
     task_spec_fields: Vec<FieldSchema>,
     step_fields: Vec<FieldSchema>,
+    step_action_spec_fields: Vec<FieldSchema>,
+    pipeline_run_spec_fields: Vec<FieldSchema>,
+    task_run_spec_fields: Vec<FieldSchema>,
+    param_spec_fields: Vec<FieldSchema>,
+    workspace_declaration_fields: Vec<FieldSchema>,
+    pipeline_workspace_fields: Vec<FieldSchema>,
+    task_ref_fields: Vec<FieldSchema>,
+    artifacts_fields: Vec<FieldSchema>,
+    security_context_fields: Vec<FieldSchema>,
+    compute_resources_fields: Vec<FieldSchema>,
+    volume_mount_fields: Vec<FieldSchema>,
+    output_config_fields: Vec<FieldSchema>,
+    matrix_fields: Vec<FieldSchema>,
+    matrix_include_fields: Vec<FieldSchema>,
+    matrix_param_fields: Vec<FieldSchema>,
+    when_fields: Vec<FieldSchema>,
+    event_listener_spec_fields: Vec<FieldSchema>,
+    trigger_fields: Vec<FieldSchema>,
+    trigger_binding_spec_fields: Vec<FieldSchema>,
+    trigger_binding_param_fields: Vec<FieldSchema>,
+    trigger_template_spec_fields: Vec<FieldSchema>,
+    trigger_template_param_fields: Vec<FieldSchema>,
+    sidecar_fields: Vec<FieldSchema>,
+    step_template_fields: Vec<FieldSchema>,
+    pod_template_fields: Vec<FieldSchema>,
+    timeouts_fields: Vec<FieldSchema>,
+    task_run_template_fields: Vec<FieldSchema>,
 }
 
 impl TektonSchemas {
     pub fn new() -> Self {
         Self {
+            document_fields: vec![
+                FieldSchema {
+                    name: "apiVersion".to_string(),
+                    description: "API version, such as tekton.dev/v1 (required)".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "kind".to_string(),
+                    description: "Resource kind, such as Task or Pipeline (required)".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "metadata".to_string(),
+                    description: "Resource metadata (required)".to_string(),
+                    field_type: FieldType::Object,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "spec".to_string(),
+                    description: "Resource specification (required)".to_string(),
+                    field_type: FieldType::Object,
+                    required: true,
+                },
+            ],
             metadata_fields: vec![
                 FieldSchema {
                     name: "name".to_string(),
@@ -91,6 +284,18 @@ impl TektonSchemas {
                     field_type: FieldType::Array,
                     required: false,
                 },
+                FieldSchema {
+                    name: "description".to_string(),
+                    description: "Description of the Pipeline".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "displayName".to_string(),
+                    description: "Human-readable name of the Pipeline, shown by dashboards".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
             ],
             pipeline_task_fields: vec![
                 FieldSchema {
@@ -129,6 +334,48 @@ impl TektonSchemas {
                     field_type: FieldType::Array,
                     required: false,
                 },
+                FieldSchema {
+                    name: "matrix".to_string(),
+                    description: "Run the task once per combination of array param values".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "when".to_string(),
+                    description: "Conditions that must all hold for the task to run".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "displayName".to_string(),
+                    description: "Human-readable name of the task, shown by dashboards; may use params and results".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "description".to_string(),
+                    description: "Description of the task".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "timeout".to_string(),
+                    description: "Time the task may take, such as 10m; 0 for none".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "retries".to_string(),
+                    description: "Number of times to retry the task on failure".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "onError".to_string(),
+                    description: "What happens when the task fails: stopAndFail (default) or continue".to_string(),
+                    field_type: FieldType::Enum(&["continue", "stopAndFail"]),
+                    required: false,
+                },
             ],
             task_spec_fields: vec![
                 FieldSchema {
@@ -161,6 +408,30 @@ impl TektonSchemas {
                     field_type: FieldType::Array,
                     required: false,
                 },
+                FieldSchema {
+                    name: "description".to_string(),
+                    description: "Description of the Task".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "displayName".to_string(),
+                    description: "Human-readable name of the Task, shown by dashboards".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "stepTemplate".to_string(),
+                    description: "Container fields every step starts from, such as env and image".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "sidecars".to_string(),
+                    description: "Containers running alongside the steps, such as a database or a Docker daemon".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
             ],
             step_fields: vec![
                 FieldSchema {
@@ -205,28 +476,1064 @@ impl TektonSchemas {
                     field_type: FieldType::String,
                     required: false,
                 },
+                FieldSchema {
+                    name: "onError".to_string(),
+                    description: "What happens when the step fails: stopAndFail (default) or continue".to_string(),
+                    field_type: FieldType::Enum(&["continue", "stopAndFail"]),
+                    required: false,
+                },
+                FieldSchema {
+                    name: "stdoutConfig".to_string(),
+                    description: "File the step's standard output is also written to".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "stderrConfig".to_string(),
+                    description: "File the step's standard error is also written to".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "timeout".to_string(),
+                    description: "Maximum duration of the step, such as 5m".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "securityContext".to_string(),
+                    description: "Security options of the step's container".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "computeResources".to_string(),
+                    description: "CPU and memory requests and limits of the step's container".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "volumeMounts".to_string(),
+                    description: "Volumes of the Task to mount in the step's container".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "artifacts".to_string(),
+                    description: "Artifacts consumed and produced by the step (enable-artifacts feature flag)".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "ref".to_string(),
+                    description: "Reference to the StepAction to run, in place of image/script".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "params".to_string(),
+                    description: "Params passed to the referenced StepAction".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
             ],
-        }
-    }
-
-    pub fn get_metadata_fields(&self) -> &[FieldSchema] {
-        &self.metadata_fields
-    }
-
-    pub fn get_pipeline_spec_fields(&self) -> &[FieldSchema] {
-        &self.pipeline_spec_fields
-    }
-
-    pub fn get_pipeline_task_fields(&self) -> &[FieldSchema] {
-        &self.pipeline_task_fields
-    }
-
-    pub fn get_task_spec_fields(&self) -> &[FieldSchema] {
-        &self.task_spec_fields
-    }
-
-    pub fn get_step_fields(&self) -> &[FieldSchema] {
-        &self.step_fields
+            step_action_spec_fields: vec![
+                FieldSchema {
+                    name: "image".to_string(),
+                    description: "Container image (required)".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "script".to_string(),
+                    description: "Script to execute".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "command".to_string(),
+                    description: "Container entrypoint".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "args".to_string(),
+                    description: "Container arguments".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "env".to_string(),
+                    description: "Environment variables".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "workingDir".to_string(),
+                    description: "Working directory".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "params".to_string(),
+                    description: "StepAction parameters".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "results".to_string(),
+                    description: "StepAction results".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "description".to_string(),
+                    description: "Description of the StepAction".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+            ],
+            pipeline_run_spec_fields: vec![
+                FieldSchema {
+                    name: "pipelineRef".to_string(),
+                    description: "Reference to an existing Pipeline".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "pipelineSpec".to_string(),
+                    description: "Inline Pipeline specification".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "params".to_string(),
+                    description: "Values of the Pipeline's params".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "workspaces".to_string(),
+                    description: "Volumes bound to the Pipeline's workspaces".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "timeouts".to_string(),
+                    description: "Timeouts of the run: pipeline, tasks and finally".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "taskRunTemplate".to_string(),
+                    description: "Pod template and service account of every TaskRun (tekton.dev/v1)".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "podTemplate".to_string(),
+                    description: "Pod template of every TaskRun (tekton.dev/v1beta1)".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "serviceAccountName".to_string(),
+                    description: "Service account of every TaskRun (tekton.dev/v1beta1)".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "taskRunSpecs".to_string(),
+                    description: "Settings of the TaskRuns of given pipeline tasks".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "status".to_string(),
+                    description: "Set to 'Cancelled' or 'StoppedRunFinally' to stop the run".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+            ],
+            task_run_spec_fields: vec![
+                FieldSchema {
+                    name: "taskRef".to_string(),
+                    description: "Reference to an existing Task".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "taskSpec".to_string(),
+                    description: "Inline Task specification".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "params".to_string(),
+                    description: "Values of the Task's params".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "workspaces".to_string(),
+                    description: "Volumes bound to the Task's workspaces".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "timeout".to_string(),
+                    description: "Time the run may take, such as 1h30m".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "podTemplate".to_string(),
+                    description: "Template of the Pod running the steps".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "serviceAccountName".to_string(),
+                    description: "Service account the Pod runs as".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "computeResources".to_string(),
+                    description: "CPU and memory of the whole Task".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "stepSpecs".to_string(),
+                    description: "Overrides of the resources of given steps".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "sidecarSpecs".to_string(),
+                    description: "Overrides of the resources of given sidecars".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "retries".to_string(),
+                    description: "Number of times to retry the run on failure".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "status".to_string(),
+                    description: "Set to 'TaskRunCancelled' to cancel the run".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+            ],
+            param_spec_fields: vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    description: "Param name (required)".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "type".to_string(),
+                    description: "Param type: string (default), array or object".to_string(),
+                    field_type: FieldType::Enum(&["string", "array", "object"]),
+                    required: false,
+                },
+                FieldSchema {
+                    name: "description".to_string(),
+                    description: "Param description".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "default".to_string(),
+                    description: "Value used when none is passed".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "properties".to_string(),
+                    description: "Keys of an object param, with their types".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "enum".to_string(),
+                    description: "Values the param may take".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+            ],
+            workspace_declaration_fields: vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    description: "Workspace name (required)".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "description".to_string(),
+                    description: "Workspace description".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "mountPath".to_string(),
+                    description: "Path the workspace is mounted at, /workspace/<name> by default".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "readOnly".to_string(),
+                    description: "Whether the workspace is mounted read-only".to_string(),
+                    field_type: FieldType::Boolean,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "optional".to_string(),
+                    description: "Whether a run may leave the workspace unbound".to_string(),
+                    field_type: FieldType::Boolean,
+                    required: false,
+                },
+            ],
+            pipeline_workspace_fields: vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    description: "Workspace name (required)".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "description".to_string(),
+                    description: "Workspace description".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "optional".to_string(),
+                    description: "Whether a run may leave the workspace unbound".to_string(),
+                    field_type: FieldType::Boolean,
+                    required: false,
+                },
+            ],
+            task_ref_fields: vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    description: "Name of the referenced Task".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "kind".to_string(),
+                    description: "Kind of the referenced resource (Task, ClusterTask, or a custom kind)".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "apiVersion".to_string(),
+                    description: "API version of the referenced resource".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "resolver".to_string(),
+                    description: "Remote resolver fetching the resource: git, hub, bundles, cluster or a custom one".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "params".to_string(),
+                    description: "Resolver parameters".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+            ],
+            artifacts_fields: vec![
+                FieldSchema {
+                    name: "inputs".to_string(),
+                    description: "Artifacts the step consumes".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "outputs".to_string(),
+                    description: "Artifacts the step produces under $(step.artifacts.path)".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+            ],
+            security_context_fields: vec![
+                FieldSchema {
+                    name: "runAsNonRoot".to_string(),
+                    description: "Require the container to run as a user other than root".to_string(),
+                    field_type: FieldType::Boolean,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "runAsUser".to_string(),
+                    description: "User ID the container runs as".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "runAsGroup".to_string(),
+                    description: "Group ID the container runs as".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "privileged".to_string(),
+                    description: "Run the container in privileged mode".to_string(),
+                    field_type: FieldType::Boolean,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "allowPrivilegeEscalation".to_string(),
+                    description: "Whether a process can gain more privileges than its parent".to_string(),
+                    field_type: FieldType::Boolean,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "readOnlyRootFilesystem".to_string(),
+                    description: "Mount the container's root filesystem read-only".to_string(),
+                    field_type: FieldType::Boolean,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "capabilities".to_string(),
+                    description: "Linux capabilities to add or drop".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "seccompProfile".to_string(),
+                    description: "Seccomp profile of the container".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+            ],
+            compute_resources_fields: vec![
+                FieldSchema {
+                    name: "requests".to_string(),
+                    description: "Minimum CPU and memory the container needs, such as cpu: 500m".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "limits".to_string(),
+                    description: "Maximum CPU and memory the container can use, such as memory: 1Gi".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+            ],
+            volume_mount_fields: vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    description: "Name of the volume to mount".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "mountPath".to_string(),
+                    description: "Path the volume is mounted at in the container".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "readOnly".to_string(),
+                    description: "Mount the volume read-only".to_string(),
+                    field_type: FieldType::Boolean,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "subPath".to_string(),
+                    description: "Path within the volume to mount instead of its root".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+            ],
+            output_config_fields: vec![
+                FieldSchema {
+                    name: "path".to_string(),
+                    description: "File the output is written to, such as $(results.name.path)".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+            ],
+            matrix_fields: vec![
+                FieldSchema {
+                    name: "params".to_string(),
+                    description: "Array params to fan out: one TaskRun per combination of values".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "include".to_string(),
+                    description: "Extra combinations, or extra params for matching combinations".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+            ],
+            matrix_include_fields: vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    description: "Name of the combination".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "params".to_string(),
+                    description: "Params of the combination, with string values".to_string(),
+                    field_type: FieldType::Array,
+                    required: true,
+                },
+            ],
+            when_fields: vec![
+                FieldSchema {
+                    name: "input".to_string(),
+                    description: "Value to check, usually a param or result variable".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "operator".to_string(),
+                    description: "'in' or 'notin'".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "values".to_string(),
+                    description: "Values the input is compared against".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "cel".to_string(),
+                    description: "CEL expression, in place of input/operator/values (Tekton v0.53+)".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+            ],
+            matrix_param_fields: vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    description: "Param name (required)".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "value".to_string(),
+                    description: "Param value".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+            ],
+            event_listener_spec_fields: vec![
+                FieldSchema {
+                    name: "serviceAccountName".to_string(),
+                    description: "Service account of the EventListener's Pod".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "triggers".to_string(),
+                    description: "Triggers of the EventListener".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "triggerGroups".to_string(),
+                    description: "Groups of triggers sharing interceptors".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "namespaceSelector".to_string(),
+                    description: "Namespaces to look up Triggers in".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "labelSelector".to_string(),
+                    description: "Labels of the Triggers to process".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "resources".to_string(),
+                    description: "Kubernetes or custom resource running the EventListener".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+            ],
+            trigger_fields: vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    description: "Trigger name".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "bindings".to_string(),
+                    description: "TriggerBindings mapping the event to params".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "template".to_string(),
+                    description: "TriggerTemplate creating resources from the params".to_string(),
+                    field_type: FieldType::Object,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "interceptors".to_string(),
+                    description: "Interceptors filtering or changing the event".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "serviceAccountName".to_string(),
+                    description: "Service account creating the resources".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "triggerRef".to_string(),
+                    description: "Reference to an existing Trigger, in place of the other fields".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+            ],
+            trigger_binding_spec_fields: vec![
+                FieldSchema {
+                    name: "params".to_string(),
+                    description: "Params taken from the event".to_string(),
+                    field_type: FieldType::Array,
+                    required: true,
+                },
+            ],
+            trigger_binding_param_fields: vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    description: "Param name (required)".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "value".to_string(),
+                    description: "Value from the event, such as $(body.head_commit.id) (required)".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+            ],
+            trigger_template_spec_fields: vec![
+                FieldSchema {
+                    name: "params".to_string(),
+                    description: "Params of the template, used as $(tt.params.<name>)".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "resourcetemplates".to_string(),
+                    description: "Resources to create, such as PipelineRuns (required)".to_string(),
+                    field_type: FieldType::Array,
+                    required: true,
+                },
+            ],
+            trigger_template_param_fields: vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    description: "Param name (required)".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "description".to_string(),
+                    description: "Param description".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "default".to_string(),
+                    description: "Default value".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+            ],
+            sidecar_fields: vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    description: "Name of the sidecar".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "image".to_string(),
+                    description: "Container image of the sidecar".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                },
+                FieldSchema {
+                    name: "command".to_string(),
+                    description: "Entrypoint of the container".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "args".to_string(),
+                    description: "Arguments of the entrypoint".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "script".to_string(),
+                    description: "Script run in the container instead of a command".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "env".to_string(),
+                    description: "Environment variables".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "workingDir".to_string(),
+                    description: "Working directory of the container".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "computeResources".to_string(),
+                    description: "CPU and memory requests and limits".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "volumeMounts".to_string(),
+                    description: "Volumes mounted in the container".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "securityContext".to_string(),
+                    description: "Security settings of the container".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "ports".to_string(),
+                    description: "Ports the container exposes".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "readinessProbe".to_string(),
+                    description: "Probe telling when the sidecar is ready; steps wait for it".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+            ],
+            step_template_fields: vec![
+                FieldSchema {
+                    name: "image".to_string(),
+                    description: "Container image of the steps not setting one".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "env".to_string(),
+                    description: "Environment variables of every step".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "workingDir".to_string(),
+                    description: "Working directory of the steps".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "command".to_string(),
+                    description: "Entrypoint of the steps not setting one".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "args".to_string(),
+                    description: "Arguments of the steps not setting any".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "computeResources".to_string(),
+                    description: "CPU and memory requests and limits of every step".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "volumeMounts".to_string(),
+                    description: "Volumes mounted in every step".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "securityContext".to_string(),
+                    description: "Security settings of every step".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+            ],
+            pod_template_fields: vec![
+                FieldSchema {
+                    name: "nodeSelector".to_string(),
+                    description: "Labels of the nodes the Pod may run on".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "tolerations".to_string(),
+                    description: "Taints of nodes the Pod tolerates".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "affinity".to_string(),
+                    description: "Scheduling constraints of the Pod".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "securityContext".to_string(),
+                    description: "Security settings of the Pod".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "volumes".to_string(),
+                    description: "Volumes of the Pod".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "runtimeClassName".to_string(),
+                    description: "Runtime class of the Pod".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "automountServiceAccountToken".to_string(),
+                    description: "Whether to mount the service account token".to_string(),
+                    field_type: FieldType::Boolean,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "dnsPolicy".to_string(),
+                    description: "DNS policy of the Pod".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "hostNetwork".to_string(),
+                    description: "Whether the Pod uses the host network".to_string(),
+                    field_type: FieldType::Boolean,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "priorityClassName".to_string(),
+                    description: "Priority class of the Pod".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "schedulerName".to_string(),
+                    description: "Scheduler placing the Pod".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "imagePullSecrets".to_string(),
+                    description: "Secrets to pull the images with".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "env".to_string(),
+                    description: "Environment variables of every container".to_string(),
+                    field_type: FieldType::Array,
+                    required: false,
+                },
+            ],
+            timeouts_fields: vec![
+                FieldSchema {
+                    name: "pipeline".to_string(),
+                    description: "Time the whole run may take, such as 1h; 0 for none".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "tasks".to_string(),
+                    description: "Time the tasks, finally tasks left out, may take".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "finally".to_string(),
+                    description: "Time the finally tasks may take".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+            ],
+            task_run_template_fields: vec![
+                FieldSchema {
+                    name: "podTemplate".to_string(),
+                    description: "Template of the Pods running the TaskRuns".to_string(),
+                    field_type: FieldType::Object,
+                    required: false,
+                },
+                FieldSchema {
+                    name: "serviceAccountName".to_string(),
+                    description: "Service account the TaskRuns run as".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                },
+            ],
+        }
+    }
+
+    pub fn get_document_fields(&self) -> &[FieldSchema] {
+        &self.document_fields
+    }
+
+    pub fn get_metadata_fields(&self) -> &[FieldSchema] {
+        &self.metadata_fields
+    }
+
+    pub fn get_pipeline_spec_fields(&self) -> &[FieldSchema] {
+        &self.pipeline_spec_fields
+    }
+
+    pub fn get_pipeline_task_fields(&self) -> &[FieldSchema] {
+        &self.pipeline_task_fields
+    }
+
+    pub fn get_task_spec_fields(&self) -> &[FieldSchema] {
+        &self.task_spec_fields
+    }
+
+    pub fn get_step_fields(&self) -> &[FieldSchema] {
+        &self.step_fields
+    }
+
+    pub fn get_step_action_spec_fields(&self) -> &[FieldSchema] {
+        &self.step_action_spec_fields
+    }
+
+    pub fn get_pipeline_run_spec_fields(&self) -> &[FieldSchema] {
+        &self.pipeline_run_spec_fields
+    }
+
+    pub fn get_task_run_spec_fields(&self) -> &[FieldSchema] {
+        &self.task_run_spec_fields
+    }
+
+    pub fn get_param_spec_fields(&self) -> &[FieldSchema] {
+        &self.param_spec_fields
+    }
+
+    pub fn get_workspace_declaration_fields(&self) -> &[FieldSchema] {
+        &self.workspace_declaration_fields
+    }
+
+    pub fn get_pipeline_workspace_fields(&self) -> &[FieldSchema] {
+        &self.pipeline_workspace_fields
+    }
+
+    pub fn get_task_ref_fields(&self) -> &[FieldSchema] {
+        &self.task_ref_fields
+    }
+
+    pub fn get_artifacts_fields(&self) -> &[FieldSchema] {
+        &self.artifacts_fields
+    }
+
+    pub fn get_security_context_fields(&self) -> &[FieldSchema] {
+        &self.security_context_fields
+    }
+
+    pub fn get_compute_resources_fields(&self) -> &[FieldSchema] {
+        &self.compute_resources_fields
+    }
+
+    pub fn get_volume_mount_fields(&self) -> &[FieldSchema] {
+        &self.volume_mount_fields
+    }
+
+    pub fn get_output_config_fields(&self) -> &[FieldSchema] {
+        &self.output_config_fields
+    }
+
+    pub fn get_matrix_fields(&self) -> &[FieldSchema] {
+        &self.matrix_fields
+    }
+
+    pub fn get_matrix_include_fields(&self) -> &[FieldSchema] {
+        &self.matrix_include_fields
+    }
+
+    pub fn get_matrix_param_fields(&self) -> &[FieldSchema] {
+        &self.matrix_param_fields
+    }
+
+    pub fn get_when_fields(&self) -> &[FieldSchema] {
+        &self.when_fields
+    }
+
+    pub fn get_event_listener_spec_fields(&self) -> &[FieldSchema] {
+        &self.event_listener_spec_fields
+    }
+
+    pub fn get_trigger_fields(&self) -> &[FieldSchema] {
+        &self.trigger_fields
+    }
+
+    pub fn get_trigger_binding_spec_fields(&self) -> &[FieldSchema] {
+        &self.trigger_binding_spec_fields
+    }
+
+    pub fn get_trigger_binding_param_fields(&self) -> &[FieldSchema] {
+        &self.trigger_binding_param_fields
+    }
+
+    pub fn get_trigger_template_spec_fields(&self) -> &[FieldSchema] {
+        &self.trigger_template_spec_fields
+    }
+
+    pub fn get_trigger_template_param_fields(&self) -> &[FieldSchema] {
+        &self.trigger_template_param_fields
+    }
+
+    pub fn get_sidecar_fields(&self) -> &[FieldSchema] {
+        &self.sidecar_fields
+    }
+
+    pub fn get_step_template_fields(&self) -> &[FieldSchema] {
+        &self.step_template_fields
+    }
+
+    pub fn get_pod_template_fields(&self) -> &[FieldSchema] {
+        &self.pod_template_fields
+    }
+
+    pub fn get_timeouts_fields(&self) -> &[FieldSchema] {
+        &self.timeouts_fields
+    }
+
+    pub fn get_task_run_template_fields(&self) -> &[FieldSchema] {
+        &self.task_run_template_fields
     }
 }
 
@@ -0,0 +1,189 @@
+//! Project configuration file.
+//!
+//! A `.tekton-lsp.yaml` (or `.tekton-lsp.yml`, `.tekton-lsp.toml`) at the
+//! workspace root configures the server for everyone working on the project:
+//!
+//! ```yaml
+//! rules:
+//!   TPL076: off
+//!   unknown-field: error
+//! exclude:
+//!   - vendor/**
+//! schemas:
+//!   - ../catalog/crds
+//! ```
+//!
+//! Rule levels set in the client settings take precedence over the file's.
+
+use crate::error::ConfigError;
+use crate::validator::{find_rule, RuleLevel};
+use crate::workspace::Exclude;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Names of the configuration file, in order of precedence.
+pub const CONFIG_FILE_NAMES: [&str; 3] =
+    [".tekton-lsp.yaml", ".tekton-lsp.yml", ".tekton-lsp.toml"];
+
+/// Settings of the project configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Levels of rules, by rule ID or code; `off` drops their diagnostics
+    pub rules: HashMap<String, RuleLevel>,
+    /// Globs of paths, relative to the workspace root, left out of the
+    /// workspace index
+    pub exclude: Vec<String>,
+    /// Files or directories of extra resources to index, such as the
+    /// CustomResourceDefinitions of custom tasks, relative to the workspace root
+    pub schemas: Vec<PathBuf>,
+}
+
+impl ProjectConfig {
+    /// The configuration file of a workspace root, if there is one.
+    pub fn find(root: &Path) -> Option<PathBuf> {
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| root.join(name))
+            .find(|path| path.is_file())
+    }
+
+    /// Load the configuration of a workspace root; the defaults when it has
+    /// no configuration file.
+    pub fn load(root: &Path) -> Result<Self, ConfigError> {
+        let Some(path) = Self::find(root) else {
+            return Ok(Self::default());
+        };
+        let content = std::fs::read_to_string(&path).map_err(|source| ConfigError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        Self::parse(&path, &content)
+    }
+
+    /// Parse a configuration file, as TOML or YAML depending on its extension.
+    pub fn parse(path: &Path, content: &str) -> Result<Self, ConfigError> {
+        let invalid = |message: String| ConfigError::Invalid {
+            path: path.to_path_buf(),
+            message,
+        };
+        let config: Self = if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(content).map_err(|e| invalid(e.message().to_string()))?
+        } else if content.trim().is_empty() {
+            Self::default()
+        } else {
+            serde_yaml::from_str(content).map_err(|e| invalid(e.to_string()))?
+        };
+
+        if let Some(key) = config.rules.keys().find(|key| find_rule(key).is_none()) {
+            return Err(invalid(format!("unknown rule '{}'", key)));
+        }
+        Ok(config)
+    }
+
+    /// Whether a path is one of the configuration files of a workspace root.
+    pub fn is_config_file(root: &Path, path: &Path) -> bool {
+        path.parent() == Some(root)
+            && path
+                .file_name()
+                .is_some_and(|name| CONFIG_FILE_NAMES.iter().any(|n| name == *n))
+    }
+
+    /// The paths of a workspace root left out of the index.
+    pub fn exclude(&self, root: &Path) -> Result<Exclude, ConfigError> {
+        Exclude::new(root, &self.exclude).map_err(|e| ConfigError::Invalid {
+            path: root.to_path_buf(),
+            message: format!("invalid exclude glob: {}", e),
+        })
+    }
+
+    /// The schema paths, resolved against a workspace root.
+    pub fn schema_paths(&self, root: &Path) -> Vec<PathBuf> {
+        self.schemas.iter().map(|path| root.join(path)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yaml_and_toml() {
+        let yaml = r#"
+rules:
+  TPL076: off
+  unknown-field: error
+exclude:
+  - vendor/**
+schemas:
+  - crds
+"#;
+        let toml = r#"
+exclude = ["vendor/**"]
+schemas = ["crds"]
+
+[rules]
+TPL076 = "off"
+unknown-field = "error"
+"#;
+        let from_yaml = ProjectConfig::parse(Path::new(".tekton-lsp.yaml"), yaml).unwrap();
+        let from_toml = ProjectConfig::parse(Path::new(".tekton-lsp.toml"), toml).unwrap();
+
+        assert_eq!(from_yaml, from_toml);
+        assert_eq!(from_yaml.rules["TPL076"], RuleLevel::Off);
+        assert_eq!(from_yaml.rules["unknown-field"], RuleLevel::Error);
+        assert_eq!(from_yaml.exclude, vec!["vendor/**".to_string()]);
+        assert_eq!(
+            from_yaml.schema_paths(Path::new("/ws")),
+            vec![PathBuf::from("/ws/crds")]
+        );
+        assert_eq!(
+            ProjectConfig::parse(Path::new(".tekton-lsp.yaml"), "").unwrap(),
+            ProjectConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let path = Path::new(".tekton-lsp.yaml");
+        let unknown_rule = ProjectConfig::parse(path, "rules:\n  TPL999: off\n").unwrap_err();
+        assert_eq!(
+            unknown_rule.to_string(),
+            "Invalid .tekton-lsp.yaml: unknown rule 'TPL999'"
+        );
+
+        assert!(ProjectConfig::parse(path, "rules:\n  TPL076: loud\n").is_err());
+        assert!(ProjectConfig::parse(path, "excludes: [vendor]\n").is_err());
+
+        let config = ProjectConfig {
+            exclude: vec!["[".to_string()],
+            ..Default::default()
+        };
+        assert!(config.exclude(Path::new("/ws")).is_err());
+    }
+
+    #[test]
+    fn test_exclude_and_config_files() {
+        let root = Path::new("/ws");
+        let config = ProjectConfig {
+            exclude: vec!["vendor/**".to_string(), "**/testdata/*.yaml".to_string()],
+            ..Default::default()
+        };
+        let exclude = config.exclude(root).unwrap();
+
+        assert!(exclude.matches(Path::new("/ws/vendor/tasks/build.yaml")));
+        assert!(exclude.matches(Path::new("/ws/tasks/testdata/bad.yaml")));
+        assert!(!exclude.matches(Path::new("/ws/tasks/build.yaml")));
+        assert!(!exclude.matches(Path::new("/elsewhere/vendor/build.yaml")));
+
+        assert!(ProjectConfig::is_config_file(
+            root,
+            Path::new("/ws/.tekton-lsp.toml")
+        ));
+        assert!(!ProjectConfig::is_config_file(
+            root,
+            Path::new("/ws/sub/.tekton-lsp.yaml")
+        ));
+    }
+}
@@ -1,8 +1,11 @@
 //! Definition provider implementation.
 
-use tower_lsp::lsp_types::{GotoDefinitionResponse, Position};
+use tower_lsp::lsp_types::{GotoDefinitionResponse, Location, Position};
 
+use crate::model::Pipeline;
+use crate::parser::positions::contains;
 use crate::parser::{Node, NodeValue, YamlDocument};
+use crate::references::targets::{occurrences, target_at, Target};
 use crate::workspace::WorkspaceIndex;
 
 /// Provides go-to-definition functionality for Tekton resources.
@@ -29,7 +32,9 @@ impl DefinitionProvider {
         position: Position,
     ) -> Option<GotoDefinitionResponse> {
         // Find what we're hovering over
-        let context = self.find_reference_context(&yaml_doc.root, position, yaml_doc)?;
+        let Some(context) = self.find_reference_context(&yaml_doc.root, position) else {
+            return self.provide_pipeline_definition(yaml_doc, position);
+        };
 
         // Look up the definition in the workspace index
         let definition = self.index.find_resource(&context.kind, &context.name)?;
@@ -37,14 +42,38 @@ impl DefinitionProvider {
         Some(GotoDefinitionResponse::Scalar(definition.location))
     }
 
-    /// Find the reference context at a position (what resource is being referenced).
-    fn find_reference_context(
+    /// Go to the pipeline task or result named at a position of a Pipeline.
+    ///
+    /// Results of a pipeline task without an inline `taskSpec` go to the
+    /// indexed Task its `taskRef` names.
+    fn provide_pipeline_definition(
         &self,
-        node: &Node,
-        position: Position,
         yaml_doc: &YamlDocument,
-    ) -> Option<ReferenceContext> {
-        if !self.position_in_range(position, &node.range) {
+        position: Position,
+    ) -> Option<GotoDefinitionResponse> {
+        if yaml_doc.kind.as_deref() != Some("Pipeline") {
+            return None;
+        }
+        let pipeline = Pipeline::from_node(&yaml_doc.root);
+        let (target, _) = target_at(&pipeline, position, yaml_doc.encoding)?;
+        if let Some(range) = occurrences(&pipeline, &target, yaml_doc.encoding).declaration {
+            let location = Location::new(yaml_doc.uri()?, range);
+            return Some(GotoDefinitionResponse::Scalar(location));
+        }
+
+        let Target::TaskResult { task, .. } = target else {
+            return None;
+        };
+        let task_ref = pipeline.task(&task)?.task_ref.as_ref()?;
+        let definition = self
+            .index
+            .find_resource(task_ref.kind_or_default(), task_ref.name?.value)?;
+        Some(GotoDefinitionResponse::Scalar(definition.location))
+    }
+
+    /// Find the reference context at a position (what resource is being referenced).
+    fn find_reference_context(&self, node: &Node, position: Position) -> Option<ReferenceContext> {
+        if !contains(node.range, position) {
             return None;
         }
 
@@ -54,7 +83,7 @@ impl DefinitionProvider {
                 "taskRef" => {
                     // Check if we're on the name field
                     if let Some(name_node) = node.get("name") {
-                        if self.position_in_range(position, &name_node.range) {
+                        if contains(name_node.value_range, position) {
                             if let Some(name) = name_node.as_scalar() {
                                 // Get kind (default to Task)
                                 let kind = node
@@ -72,7 +101,7 @@ impl DefinitionProvider {
                 "pipelineRef" => {
                     // Check if we're on the name field
                     if let Some(name_node) = node.get("name") {
-                        if self.position_in_range(position, &name_node.range) {
+                        if contains(name_node.value_range, position) {
                             if let Some(name) = name_node.as_scalar() {
                                 return Some(ReferenceContext {
                                     kind: "Pipeline".to_string(),
@@ -82,6 +111,23 @@ impl DefinitionProvider {
                         }
                     }
                 }
+                "steps" => {
+                    // Check if we're on the name of a step's StepAction reference
+                    let steps = match &node.value {
+                        NodeValue::Sequence(steps) => steps.as_slice(),
+                        _ => &[],
+                    };
+                    let name = steps
+                        .iter()
+                        .filter_map(|step| step.get("ref")?.get("name"))
+                        .find(|name| contains(name.value_range, position));
+                    if let Some(name) = name.and_then(|n| n.as_scalar()) {
+                        return Some(ReferenceContext {
+                            kind: "StepAction".to_string(),
+                            name: name.to_string(),
+                        });
+                    }
+                }
                 _ => {}
             }
         }
@@ -89,15 +135,15 @@ impl DefinitionProvider {
         // Recursively check children
         match &node.value {
             NodeValue::Mapping(map) => {
-                for (_key, child) in map {
-                    if let Some(ctx) = self.find_reference_context(child, position, yaml_doc) {
+                for child in map.values() {
+                    if let Some(ctx) = self.find_reference_context(child, position) {
                         return Some(ctx);
                     }
                 }
             }
             NodeValue::Sequence(items) => {
                 for item in items {
-                    if let Some(ctx) = self.find_reference_context(item, position, yaml_doc) {
+                    if let Some(ctx) = self.find_reference_context(item, position) {
                         return Some(ctx);
                     }
                 }
@@ -107,19 +153,6 @@ impl DefinitionProvider {
 
         None
     }
-
-    fn position_in_range(&self, pos: Position, range: &tower_lsp::lsp_types::Range) -> bool {
-        if pos.line < range.start.line || pos.line > range.end.line {
-            return false;
-        }
-        if pos.line == range.start.line && pos.character < range.start.character {
-            return false;
-        }
-        if pos.line == range.end.line && pos.character > range.end.character {
-            return false;
-        }
-        true
-    }
 }
 
 /// Context for a resource reference.
@@ -211,7 +244,10 @@ spec:
         };
 
         let result = provider.provide_definition(&doc, position);
-        assert!(result.is_none(), "Should not find definition outside of refs");
+        assert!(
+            result.is_none(),
+            "Should not find definition outside of refs"
+        );
     }
 
     #[test]
@@ -238,6 +274,53 @@ spec:
         };
 
         let result = provider.provide_definition(&doc, position);
-        assert!(result.is_none(), "Should not find definition for nonexistent task");
+        assert!(
+            result.is_none(),
+            "Should not find definition for nonexistent task"
+        );
+    }
+
+    #[test]
+    fn test_goto_definition_only_on_name_value() {
+        let index = WorkspaceIndex::new();
+        let task_uri = make_test_uri("/workspace/tasks/build.yaml");
+        index
+            .index_document(&task_uri, "kind: Task\nmetadata:\n  name: build-task\n")
+            .unwrap();
+
+        let content = r#"kind: Pipeline
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build-task"#;
+
+        let doc = parser::parse_yaml("pipeline.yaml", content).unwrap();
+        let provider = DefinitionProvider::new(index);
+
+        // On the "name" key of taskRef: not a reference
+        let on_key = provider.provide_definition(
+            &doc,
+            Position {
+                line: 5,
+                character: 9,
+            },
+        );
+        assert!(on_key.is_none(), "Key of taskRef.name should not navigate");
+
+        // On the value: navigates, and targets the name value in the Task
+        let on_value = provider.provide_definition(
+            &doc,
+            Position {
+                line: 5,
+                character: 16,
+            },
+        );
+        match on_value {
+            Some(GotoDefinitionResponse::Scalar(location)) => {
+                assert_eq!(location.range.start.character, 8);
+            }
+            _ => panic!("Expected scalar location"),
+        }
     }
 }
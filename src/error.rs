@@ -0,0 +1,231 @@
+//! Error types for the parser and providers.
+//!
+//! Errors carry a category (and a range when one is known) so the server
+//! can decide how to log them and whether to surface them as diagnostics.
+
+use std::path::PathBuf;
+use thiserror::Error;
+use tower_lsp::lsp_types::{Range, Url};
+
+/// Broad classification of an error, used to pick log levels and severities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// The document content is not valid YAML
+    Syntax,
+    /// A requested document or resource is unknown to the server
+    NotFound,
+    /// Reading from disk failed
+    Io,
+    /// Something went wrong inside the server itself
+    Internal,
+    /// The document changed while the request was served
+    Stale,
+}
+
+/// Errors produced while parsing a YAML document.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ParseError {
+    /// The tree-sitter YAML grammar could not be loaded
+    #[error("Failed to set language: {0}")]
+    Language(String),
+    /// tree-sitter did not produce a syntax tree
+    #[error("Failed to parse YAML")]
+    NoTree,
+    /// The document contains a syntax error at the given range
+    #[error("{message}")]
+    Syntax { message: String, range: Range },
+    /// Aliases expand to more nodes than the parser accepts; `range` is the
+    /// alias that went over the limit
+    #[error("YAML aliases expand to more than {limit} nodes")]
+    AliasExpansion { limit: usize, range: Range },
+}
+
+impl ParseError {
+    /// The document range this error refers to, if any.
+    pub fn range(&self) -> Option<Range> {
+        match self {
+            ParseError::Syntax { range, .. } | ParseError::AliasExpansion { range, .. } => {
+                Some(*range)
+            }
+            _ => None,
+        }
+    }
+
+    /// The category of this error.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ParseError::Syntax { .. } | ParseError::AliasExpansion { .. } => ErrorCategory::Syntax,
+            ParseError::Language(_) | ParseError::NoTree => ErrorCategory::Internal,
+        }
+    }
+}
+
+/// Errors produced while serving LSP requests.
+#[derive(Debug, Error)]
+pub enum LspError {
+    /// The document could not be parsed
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// The document is not open in the cache
+    #[error("Document not found: {0}")]
+    DocumentNotFound(Url),
+    /// Reading a file from disk failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The document changed while it was analyzed
+    #[error("Content modified: {0}")]
+    ContentModified(Url),
+}
+
+impl LspError {
+    /// The category of this error.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            LspError::Parse(e) => e.category(),
+            LspError::DocumentNotFound(_) => ErrorCategory::NotFound,
+            LspError::Io(_) => ErrorCategory::Io,
+            LspError::ContentModified(_) => ErrorCategory::Stale,
+        }
+    }
+}
+
+/// Errors produced while loading the project configuration file.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// The file could not be read
+    #[error("Failed to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The file is not a valid configuration
+    #[error("Invalid {}: {message}", path.display())]
+    Invalid { path: PathBuf, message: String },
+}
+
+/// Errors produced while fetching the Tekton Hub catalog.
+#[derive(Debug, Error)]
+pub enum HubError {
+    /// The request for the catalog failed
+    #[error("Failed to fetch {url}: {source}")]
+    Fetch { url: String, source: RemoteError },
+    /// The answer is not a catalog
+    #[error("Invalid Hub catalog: {0}")]
+    Invalid(#[from] serde_json::Error),
+}
+
+/// Errors produced while fetching a remote file.
+#[derive(Debug, Error)]
+pub enum RemoteError {
+    /// The request failed or the server answered with an error
+    #[error(transparent)]
+    Http(Box<ureq::Error>),
+    /// The answer could not be read
+    #[error("Failed to read the answer: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<ureq::Error> for RemoteError {
+    fn from(error: ureq::Error) -> Self {
+        RemoteError::Http(Box::new(error))
+    }
+}
+
+/// Errors produced while asking a container registry for a digest.
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    /// A request to the registry or its token endpoint failed
+    #[error(transparent)]
+    Fetch(#[from] RemoteError),
+    /// The registry wants credentials other than an anonymous token
+    #[error("The registry wants credentials")]
+    Unauthorized,
+    /// The token endpoint answered with something else than a token
+    #[error("Invalid token response: {0}")]
+    InvalidToken(#[from] serde_json::Error),
+    /// The token endpoint returned no token
+    #[error("The registry returned no token")]
+    NoToken,
+    /// The registry returned no digest for the manifest
+    #[error("The registry returned no digest")]
+    NoDigest,
+}
+
+/// Errors produced while explaining a field path.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ExplainError {
+    /// No path was given
+    #[error("Expected a field path, e.g. spec.tasks.runAfter")]
+    EmptyPath,
+    /// The path goes below a field whose fields are not documented
+    #[error("Field '{field}' of {kind} has no documented fields")]
+    NoFields { kind: String, field: String },
+    /// A segment of the path is not a field of its parent (`parent` is empty
+    /// for the fields of the kind itself)
+    #[error(
+        "Unknown field '{field}' in {}; fields: {}",
+        field_location(parent, kind),
+        fields.join(", ")
+    )]
+    UnknownField {
+        kind: String,
+        parent: String,
+        field: String,
+        fields: Vec<String>,
+    },
+}
+
+/// Where a field was looked up, e.g. `spec of Pipeline`.
+fn field_location(parent: &str, kind: &str) -> String {
+    if parent.is_empty() {
+        kind.to_string()
+    } else {
+        format!("{} of {}", parent, kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::Position;
+
+    #[test]
+    fn test_parse_error_categories() {
+        let range = Range {
+            start: Position {
+                line: 2,
+                character: 0,
+            },
+            end: Position {
+                line: 2,
+                character: 5,
+            },
+        };
+        let syntax = ParseError::Syntax {
+            message: "Unexpected content".to_string(),
+            range,
+        };
+
+        assert_eq!(syntax.category(), ErrorCategory::Syntax);
+        assert_eq!(syntax.range(), Some(range));
+        assert_eq!(ParseError::NoTree.category(), ErrorCategory::Internal);
+        assert_eq!(ParseError::NoTree.range(), None);
+    }
+
+    #[test]
+    fn test_lsp_error_categories() {
+        let uri = Url::parse("file:///test.yaml").unwrap();
+        assert_eq!(
+            LspError::DocumentNotFound(uri.clone()).category(),
+            ErrorCategory::NotFound
+        );
+        assert_eq!(
+            LspError::from(ParseError::NoTree).category(),
+            ErrorCategory::Internal
+        );
+        assert_eq!(
+            LspError::ContentModified(uri).category(),
+            ErrorCategory::Stale
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//! Folding range provider for Tekton YAML files.
+//!
+//! Provides folding ranges for:
+//! - Multi-line mapping entries and sequence items
+//! - Functions defined in step scripts
+
+pub mod provider;
+
+pub use provider::FoldingProvider;
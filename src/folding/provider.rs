@@ -0,0 +1,100 @@
+//! Folding range provider implementation.
+
+use tower_lsp::lsp_types::FoldingRange;
+
+use crate::parser::{Node, NodeValue, YamlDocument};
+use crate::symbols::script_functions;
+
+/// Provides folding ranges for Tekton YAML files.
+///
+/// Clients drop their indentation-based folding once a server provides
+/// folding ranges, so the YAML structure is folded as well as scripts.
+#[derive(Debug, Clone, Default)]
+pub struct FoldingProvider;
+
+impl FoldingProvider {
+    /// Create a new folding provider.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Provide folding ranges for a YAML document, in document order.
+    pub fn provide_folding_ranges(&self, yaml_doc: &YamlDocument) -> Vec<FoldingRange> {
+        let mut ranges = Vec::new();
+        self.collect_ranges(&yaml_doc.root, yaml_doc, &mut ranges);
+        ranges.sort_by_key(|r| (r.start_line, std::cmp::Reverse(r.end_line)));
+        ranges
+    }
+
+    fn collect_ranges(&self, node: &Node, yaml_doc: &YamlDocument, ranges: &mut Vec<FoldingRange>) {
+        let children: Vec<&Node> = match &node.value {
+            NodeValue::Mapping(map) => map.values().collect(),
+            NodeValue::Sequence(items) => items.iter().collect(),
+            NodeValue::Scalar(_) if node.key.as_deref() == Some("script") => {
+                for function in script_functions(node, yaml_doc.encoding) {
+                    push_range(ranges, function.range.start.line, function.range.end.line);
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        for child in children {
+            push_range(ranges, child.range.start.line, child.last_content_line());
+            self.collect_ranges(child, yaml_doc, ranges);
+        }
+    }
+}
+
+/// Add a folding range if it spans several lines.
+fn push_range(ranges: &mut Vec<FoldingRange>, start_line: u32, end_line: u32) {
+    if end_line > start_line {
+        ranges.push(FoldingRange {
+            start_line,
+            end_line,
+            ..Default::default()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    #[test]
+    fn test_folding_ranges() {
+        let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - name: compile
+      image: golang
+      script: |
+        build() {
+          go build ./...
+        }
+
+        build
+
+"#;
+
+        let yaml_doc = parser::parse_yaml("test.yaml", content).unwrap();
+        let ranges = FoldingProvider::new().provide_folding_ranges(&yaml_doc);
+        let lines: Vec<(u32, u32)> = ranges.iter().map(|r| (r.start_line, r.end_line)).collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                (2, 3),  // metadata
+                (4, 13), // spec
+                (5, 13), // steps
+                (6, 13), // the compile step
+                (8, 13), // its script
+                (9, 11), // the build function
+            ]
+        );
+    }
+}
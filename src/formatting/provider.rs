@@ -1,22 +1,24 @@
 //! YAML formatting provider implementation.
 
+use crate::parser::PositionEncoding;
 use tower_lsp::lsp_types::{Position, Range, TextEdit};
 
 /// Provides document formatting for Tekton YAML files.
+///
+/// Output is produced by serde_yaml, which always indents by two spaces.
 #[derive(Debug, Clone, Default)]
-pub struct FormattingProvider {
-    /// Number of spaces per indentation level.
-    indent_size: usize,
-}
+pub struct FormattingProvider;
 
 impl FormattingProvider {
     /// Create a new formatting provider with default settings.
     pub fn new() -> Self {
-        Self { indent_size: 2 }
+        Self
     }
 
     /// Format a YAML document and return text edits.
-    pub fn format(&self, content: &str) -> Option<Vec<TextEdit>> {
+    ///
+    /// `encoding` is the encoding of the positions in the returned edits.
+    pub fn format(&self, content: &str, encoding: PositionEncoding) -> Option<Vec<TextEdit>> {
         // Parse the YAML
         let value: serde_yaml::Value = match serde_yaml::from_str(content) {
             Ok(v) => v,
@@ -37,7 +39,7 @@ impl FormattingProvider {
         // Calculate the range of the entire document
         let lines: Vec<&str> = content.lines().collect();
         let last_line = lines.len().saturating_sub(1);
-        let last_char = lines.last().map(|l| l.len()).unwrap_or(0);
+        let last_char = lines.last().map(|l| encoding.len(l)).unwrap_or(0);
 
         Some(vec![TextEdit {
             range: Range {
@@ -47,7 +49,7 @@ impl FormattingProvider {
                 },
                 end: Position {
                     line: last_line as u32,
-                    character: last_char as u32,
+                    character: last_char,
                 },
             },
             new_text: formatted,
@@ -73,7 +75,7 @@ spec:
       image: ubuntu
 "#;
 
-        let edits = provider.format(content);
+        let edits = provider.format(content, PositionEncoding::default());
         assert!(edits.is_some());
     }
 
@@ -87,7 +89,7 @@ kind: Task
   invalid: indentation
 "#;
 
-        let edits = provider.format(content);
+        let edits = provider.format(content, PositionEncoding::default());
         // Invalid YAML should return None
         assert!(edits.is_none());
     }
@@ -106,7 +108,7 @@ spec:
         - name: build
 "#;
 
-        let edits = provider.format(content);
+        let edits = provider.format(content, PositionEncoding::default());
         assert!(edits.is_some());
 
         let edits = edits.unwrap();
@@ -134,7 +136,7 @@ spec:
       image: golang:1.21
 "#;
 
-        let edits = provider.format(content);
+        let edits = provider.format(content, PositionEncoding::default());
         assert!(edits.is_some());
 
         let edits = edits.unwrap();
@@ -164,7 +166,7 @@ metadata:
         .unwrap();
         let canonical = serde_yaml::to_string(&value).unwrap();
 
-        let edits = provider.format(&canonical);
+        let edits = provider.format(&canonical, PositionEncoding::default());
         assert!(edits.is_some());
         // When content matches, should return empty vec
         assert!(edits.unwrap().is_empty());
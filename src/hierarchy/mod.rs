@@ -0,0 +1,9 @@
+//! Type hierarchy provider for Tekton YAML files.
+//!
+//! Presents the references between resources as a type hierarchy:
+//! - subtypes: PipelineRun → Pipeline → Tasks → StepActions (and TaskRun → Task)
+//! - supertypes: the resources referencing a resource
+
+pub mod provider;
+
+pub use provider::TypeHierarchyProvider;
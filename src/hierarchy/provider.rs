@@ -0,0 +1,170 @@
+//! Type hierarchy provider implementation.
+
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::{Position, Range, SymbolKind, TypeHierarchyItem, Url};
+
+use crate::parser::positions::contains;
+use crate::parser::YamlDocument;
+use crate::workspace::index::{ResourceDefinition, ResourceReference};
+use crate::workspace::WorkspaceIndex;
+
+/// Provides the type hierarchy of Tekton resources from the workspace index.
+#[derive(Debug, Clone)]
+pub struct TypeHierarchyProvider {
+    index: WorkspaceIndex,
+}
+
+/// Identifies the resource of an item between the prepare and the
+/// supertypes/subtypes requests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ItemData {
+    uri: Url,
+    kind: String,
+    name: String,
+}
+
+impl TypeHierarchyProvider {
+    /// Create a new type hierarchy provider with the given workspace index.
+    pub fn new(index: WorkspaceIndex) -> Self {
+        Self { index }
+    }
+
+    /// The item at a position: the indexed resource a reference there points
+    /// to, or else the resource the document defines.
+    pub fn prepare(
+        &self,
+        yaml_doc: &YamlDocument,
+        uri: &Url,
+        position: Position,
+    ) -> Option<Vec<TypeHierarchyItem>> {
+        let referenced = self
+            .index
+            .all_references()
+            .into_iter()
+            .filter(|r| &r.uri == uri && contains(r.location.range, position))
+            .find_map(|r| self.index.find_resource(&r.ref_kind, &r.ref_name));
+        if let Some(resource) = referenced {
+            return Some(vec![resource_item(&resource)]);
+        }
+
+        let kind = yaml_doc.kind.as_deref()?;
+        let metadata = yaml_doc.root.get("metadata")?;
+        let name_node = metadata
+            .get("name")
+            .or_else(|| metadata.get("generateName"))?;
+        let name = name_node.as_scalar()?;
+        Some(vec![item(
+            ItemData {
+                uri: uri.clone(),
+                kind: kind.to_string(),
+                name: name.to_string(),
+            },
+            yaml_doc.root.range,
+            name_node.value_range,
+        )])
+    }
+
+    /// The resources referencing the item's resource.
+    pub fn supertypes(&self, item: &TypeHierarchyItem) -> Vec<TypeHierarchyItem> {
+        let Some(data) = item_data(item) else {
+            return Vec::new();
+        };
+
+        let mut items = Vec::new();
+        for reference in self.index.find_references(&data.kind, &data.name) {
+            let referrer = self.referrer_item(&reference);
+            if !items.contains(&referrer) {
+                items.push(referrer);
+            }
+        }
+        items
+    }
+
+    /// The indexed resources the item's resource references.
+    pub fn subtypes(&self, item: &TypeHierarchyItem) -> Vec<TypeHierarchyItem> {
+        let Some(data) = item_data(item) else {
+            return Vec::new();
+        };
+
+        let mut items = Vec::new();
+        let outgoing = self.index.all_references().into_iter().filter(|r| {
+            r.uri == data.uri
+                && r.referrer_kind == data.kind
+                && r.referrer_name.as_deref() == Some(data.name.as_str())
+        });
+        for reference in outgoing {
+            let Some(resource) = self
+                .index
+                .find_resource(&reference.ref_kind, &reference.ref_name)
+            else {
+                continue;
+            };
+            let item = resource_item(&resource);
+            if !items.contains(&item) {
+                items.push(item);
+            }
+        }
+        items
+    }
+
+    /// The item of the resource holding a reference: its indexed definition,
+    /// or the reference itself for runs named by `generateName`.
+    fn referrer_item(&self, reference: &ResourceReference) -> TypeHierarchyItem {
+        let name = reference
+            .referrer_name
+            .clone()
+            .unwrap_or_else(|| reference.file_name().to_string());
+        match self.index.find_resource(&reference.referrer_kind, &name) {
+            Some(resource) if resource.uri == reference.uri => resource_item(&resource),
+            _ => item(
+                ItemData {
+                    uri: reference.uri.clone(),
+                    kind: reference.referrer_kind.clone(),
+                    name,
+                },
+                reference.location.range,
+                reference.location.range,
+            ),
+        }
+    }
+}
+
+/// The item of an indexed resource, selecting its name.
+fn resource_item(resource: &ResourceDefinition) -> TypeHierarchyItem {
+    item(
+        ItemData {
+            uri: resource.uri.clone(),
+            kind: resource.kind.clone(),
+            name: resource.name.clone(),
+        },
+        resource.location.range,
+        resource.location.range,
+    )
+}
+
+fn item(data: ItemData, range: Range, selection_range: Range) -> TypeHierarchyItem {
+    TypeHierarchyItem {
+        name: data.name.clone(),
+        kind: symbol_kind(&data.kind),
+        tags: None,
+        detail: Some(data.kind.clone()),
+        uri: data.uri.clone(),
+        range,
+        selection_range,
+        data: serde_json::to_value(&data).ok(),
+    }
+}
+
+/// The resource an item stands for.
+fn item_data(item: &TypeHierarchyItem) -> Option<ItemData> {
+    serde_json::from_value(item.data.clone()?).ok()
+}
+
+/// Symbol kind shown for a resource kind.
+fn symbol_kind(kind: &str) -> SymbolKind {
+    match kind {
+        "Pipeline" | "Task" | "ClusterTask" | "StepAction" => SymbolKind::CLASS,
+        "PipelineRun" | "TaskRun" => SymbolKind::OBJECT,
+        _ => SymbolKind::FILE,
+    }
+}
@@ -1,11 +1,14 @@
 //! Documentation content for Tekton resources and fields.
 //!
-//! Provides Markdown documentation for hover tooltips.
+//! Provides Markdown documentation for hover tooltips: written out for the
+//! resource kinds, generated from the completion schemas for their fields.
 
+use super::explain::{type_name, ResolvedField};
+use crate::completion::schemas::FieldSchema;
 use std::collections::HashMap;
 use std::sync::LazyLock;
 
-/// Documentation lookup table for Tekton resources and fields.
+/// Documentation lookup table for Tekton resource kinds.
 static TEKTON_DOCS: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| {
     let mut docs = HashMap::new();
 
@@ -54,372 +57,207 @@ The TaskRun references the Task you want to execute and provides the necessary p
 [Tekton TaskRuns Documentation](https://tekton.dev/docs/pipelines/taskruns/)"#,
     );
 
-    // Common fields
     docs.insert(
-        "tasks",
-        r#"# tasks
+        "StepAction",
+        r#"# StepAction
 
-Specifies the Tasks that comprise the Pipeline and the details of their execution.
+A StepAction is a reusable Step: it defines the image, script or command, params and results of a Step.
 
-Each PipelineTask must have:
-- **name**: unique name for the task in the pipeline
-- **taskRef** or **taskSpec**: reference to an existing Task or inline Task definition
+Tasks run it from a step with `ref`, passing its params:
 
-Optional fields:
-- **runAfter**: specify tasks that must complete before this task
-- **params**: parameters to pass to the task
-- **workspaces**: workspace bindings
-- **when**: conditional execution expressions"#,
-    );
-
-    docs.insert(
-        "steps",
-        r#"# steps
-
-Specifies one or more container images to run in the Task.
-
-Each Step runs sequentially in the order specified. If a Step fails, subsequent Steps are not executed.
-
-Required fields:
-- **image**: container image to run
-
-Common fields:
-- **name**: step name
-- **script**: script to execute in the container
-- **command**: command and arguments to run
-- **env**: environment variables
-- **workingDir**: working directory"#,
-    );
-
-    docs.insert(
-        "params",
-        r#"# params
-
-Specifies the execution parameters for the Pipeline/Task.
-
-Parameters can be:
-- **string**: simple string value
-- **array**: list of string values
-- **object**: structured data with properties
-
-Each parameter can have:
-- **name**: parameter name (required)
-- **type**: string, array, or object
-- **description**: human-readable description
-- **default**: default value if not provided"#,
-    );
-
-    docs.insert(
-        "workspaces",
-        r#"# workspaces
-
-Specifies paths to volumes required by the Pipeline/Task to execute.
-
-Workspaces allow Tasks to share data and can be backed by:
-- PersistentVolumeClaim
-- emptyDir
-- ConfigMap
-- Secret
-
-Each workspace has:
-- **name**: workspace name (required)
-- **description**: human-readable description
-- **optional**: whether the workspace is optional
-- **readOnly**: whether the workspace is read-only
-- **mountPath**: path where the workspace is mounted"#,
-    );
-
-    docs.insert(
-        "taskRef",
-        r#"# taskRef
-
-Reference to a Task that exists in the cluster.
-
-Can reference a Task by:
-- **name**: name of the Task in the same namespace
-- **kind**: Task or ClusterTask (optional)
-- **apiVersion**: API version (optional)
-
-Example:
-```yaml
-taskRef:
-  name: build-task
-```"#,
-    );
-
-    docs.insert(
-        "taskSpec",
-        r#"# taskSpec
-
-Inline Task specification embedded in the Pipeline.
-
-Allows defining a Task directly without creating a separate Task resource.
-
-Contains the same fields as a Task spec:
-- **steps**: task steps (required)
-- **params**: task parameters
-- **workspaces**: task workspaces
-- **results**: task results"#,
-    );
-
-    docs.insert(
-        "results",
-        r#"# results
-
-Specifies the results that the Task/Pipeline will emit.
-
-Results can be used to pass data between Tasks or output data from a Pipeline.
-
-Each result has:
-- **name**: result name (required)
-- **description**: human-readable description
-- **type**: string (default) or array
-
-Tasks emit results by writing to:
-```
-$(results.<name>.path)
-```"#,
-    );
-
-    docs.insert(
-        "finally",
-        r#"# finally
-
-Specifies Tasks that run after all other Pipeline tasks complete.
-
-Finally tasks run regardless of whether the Pipeline succeeded or failed. They are commonly used for:
-- Cleanup operations
-- Sending notifications
-- Recording metrics
-
-Finally tasks cannot have dependencies on regular Pipeline tasks and cannot use `runAfter`."#,
-    );
-
-    docs.insert(
-        "runAfter",
-        r#"# runAfter
-
-Specifies the list of PipelineTask names that must complete before this task runs.
-
-Example:
-```yaml
-- name: deploy
-  taskRef:
-    name: deploy-task
-  runAfter:
-    - build
-    - test
-```
-
-This ensures proper ordering of task execution in the Pipeline."#,
-    );
-
-    // Metadata fields
-    docs.insert(
-        "metadata",
-        r#"# metadata
-
-Standard Kubernetes object metadata.
-
-Required fields:
-- **name**: resource name (must be unique in namespace)
-
-Common fields:
-- **namespace**: namespace (defaults to "default")
-- **labels**: key-value labels for organizing resources
-- **annotations**: key-value annotations for storing metadata"#,
-    );
-
-    docs.insert(
-        "name",
-        r#"# name
-
-The name of the resource.
-
-Must be unique within its namespace and follow Kubernetes naming conventions:
-- Contain only lowercase alphanumeric characters, '-' or '.'
-- Start with an alphanumeric character
-- Be at most 253 characters
-
-Example: `my-pipeline`, `build-task-v1`"#,
-    );
-
-    docs.insert(
-        "namespace",
-        r#"# namespace
-
-The Kubernetes namespace where this resource exists.
-
-If not specified, defaults to the `default` namespace or the namespace in the current context.
-
-Example: `tekton-pipelines`, `my-project`"#,
-    );
-
-    docs.insert(
-        "labels",
-        r#"# labels
-
-Key-value pairs used to organize and select resources.
-
-Labels are used for:
-- Organizing resources into groups
-- Selecting resources with label selectors
-- Filtering in CLI and UI
-
-Example:
-```yaml
-labels:
-  app: my-app
-  environment: production
-```"#,
-    );
-
-    docs.insert(
-        "annotations",
-        r#"# annotations
-
-Key-value pairs for storing non-identifying metadata.
-
-Annotations are used for:
-- Build information
-- Tool configuration
-- Deployment details
-
-Example:
 ```yaml
-annotations:
-  description: "Main build pipeline"
-  author: "team@example.com"
-```"#,
-    );
-
-    docs.insert(
-        "spec",
-        r#"# spec
-
-Specification of the desired behavior of the resource.
-
-Contains resource-specific fields that define how the Pipeline/Task/Run should execute."#,
-    );
-
-    // Step fields
-    docs.insert(
-        "image",
-        r#"# image
-
-The container image to use for this Step.
-
-Must be a valid container image reference:
-- `ubuntu:latest`
-- `gcr.io/project/image:tag`
-- `registry.example.com/image@sha256:...`
-
-Example:
-```yaml
-- name: build
-  image: golang:1.21
-  script: |
-    go build ./...
-```"#,
-    );
-
-    docs.insert(
-        "script",
-        r#"# script
-
-A script to execute in the container.
-
-The script runs as the container's entrypoint. Use a shebang to specify the interpreter:
-
-```yaml
-script: |
-  #!/usr/bin/env bash
-  set -e
-  echo "Building..."
-  make build
-```
-
-If no shebang is provided, the default shell is used."#,
-    );
-
-    docs.insert(
-        "command",
-        r#"# command
-
-The container entrypoint command.
-
-Overrides the container image's ENTRYPOINT:
-
-```yaml
-command:
-  - /bin/bash
-  - -c
-  - echo "Hello"
+steps:
+  - name: clone
+    ref:
+      name: git-clone
+    params:
+      - name: url
+        value: $(params.url)
 ```
 
-Use `args` to provide additional arguments."#,
-    );
-
-    docs.insert(
-        "args",
-        r#"# args
-
-Arguments to the container entrypoint.
-
-Overrides the container image's CMD:
-
-```yaml
-command:
-  - python
-args:
-  - script.py
-  - --verbose
-```"#,
-    );
-
-    docs.insert(
-        "env",
-        r#"# env
-
-Environment variables for the container.
-
-Example:
-```yaml
-env:
-  - name: MY_VAR
-    value: "my-value"
-  - name: SECRET_VAR
-    valueFrom:
-      secretKeyRef:
-        name: my-secret
-        key: password
-```"#,
-    );
-
-    docs.insert(
-        "workingDir",
-        r#"# workingDir
-
-The working directory for the container.
-
-Defaults to the container image's WORKDIR. Can reference workspace paths:
-
-```yaml
-workingDir: $(workspaces.source.path)
-```"#,
+[Tekton StepActions Documentation](https://tekton.dev/docs/pipelines/stepactions/)"#,
     );
 
     docs
 });
 
-/// Get documentation for a given key (field name or resource kind).
+/// Documentation of the PipelineResources of specs and pipeline tasks
+const PIPELINE_RESOURCES_DOC: &str = r#"# Field `resources` (PipelineResources)
+
+Inputs and outputs such as git repositories and images, bound to the resource by a `PipelineResource`.
+
+**Deprecated:** PipelineResources were removed in tekton.dev/v1. Use workspaces and params instead, with Tasks such as `git-clone` fetching the inputs.
+
+[Replacing PipelineResources](https://tekton.dev/docs/pipelines/migrating-v1beta1-to-v1/#replacing-pipelineresources-with-tasks)"#;
+
+/// Documentation of fields tekton.dev/v1 removed, by API version, parent
+/// field and field: the schemas only describe tekton.dev/v1.
+static VERSIONED_DOCS: LazyLock<HashMap<(&'static str, &'static str, &'static str), &'static str>> =
+    LazyLock::new(|| {
+        let mut docs = HashMap::new();
+        for parent in ["spec", "tasks", "finally"] {
+            docs.insert(
+                ("tekton.dev/v1beta1", parent, "resources"),
+                PIPELINE_RESOURCES_DOC,
+            );
+        }
+
+        docs.insert(
+            ("tekton.dev/v1beta1", "steps", "resources"),
+            r#"# Field `resources` of a step
+
+Compute resources of the step's container: `requests` and `limits` of CPU and memory.
+
+**Renamed:** tekton.dev/v1 calls it `computeResources`."#,
+        );
+
+        for parent in ["tasks", "finally"] {
+            docs.insert(
+                ("tekton.dev/v1beta1", parent, "conditions"),
+                r#"# Field `conditions`
+
+Conditions guarding the pipeline task, each running a `Condition` resource.
+
+**Deprecated:** Conditions were removed in tekton.dev/v1. Use `when` expressions instead.
+
+[Guarding execution with when expressions](https://tekton.dev/docs/pipelines/pipelines/#guard-task-execution-using-when-expressions)"#,
+            );
+        }
+
+        docs.insert(
+            ("tekton.dev/v1beta1", "spec", "timeout"),
+            r#"# Field `timeout` of a PipelineRun
+
+Timeout of the whole run.
+
+**Deprecated:** removed in tekton.dev/v1. Use `timeouts.pipeline` instead, with `timeouts.tasks` and `timeouts.finally` for the parts of the run.
+
+[Configuring a failure timeout](https://tekton.dev/docs/pipelines/pipelineruns/#configuring-a-failure-timeout)"#,
+        );
+
+        docs.insert(
+            ("tekton.dev/v1beta1", "taskRef", "bundle"),
+            r#"# Field `bundle`
+
+OCI image of a Tekton Bundle holding the referenced Task.
+
+**Deprecated:** removed in tekton.dev/v1. Use the `bundles` resolver instead.
+
+[Bundles resolver](https://tekton.dev/docs/pipelines/bundle-resolver/)"#,
+        );
+
+        docs
+    });
+
+/// Pages of the Tekton documentation about fields, linked from their hover
+const FIELD_LINKS: [(&str, &str); 11] = [
+    (
+        "when",
+        "https://tekton.dev/docs/pipelines/pipelines/#guard-task-execution-using-when-expressions",
+    ),
+    ("matrix", "https://tekton.dev/docs/pipelines/matrix/"),
+    (
+        "sidecars",
+        "https://tekton.dev/docs/pipelines/tasks/#specifying-sidecars",
+    ),
+    (
+        "stepTemplate",
+        "https://tekton.dev/docs/pipelines/tasks/#specifying-a-step-template",
+    ),
+    (
+        "podTemplate",
+        "https://tekton.dev/docs/pipelines/podtemplates/",
+    ),
+    (
+        "serviceAccountName",
+        "https://tekton.dev/docs/pipelines/auth/",
+    ),
+    (
+        "timeouts",
+        "https://tekton.dev/docs/pipelines/pipelineruns/#configuring-a-failure-timeout",
+    ),
+    (
+        "retries",
+        "https://tekton.dev/docs/pipelines/pipelines/#using-the-retries-field",
+    ),
+    ("resolver", "https://tekton.dev/docs/pipelines/resolution/"),
+    (
+        "onError",
+        "https://tekton.dev/docs/pipelines/tasks/#specifying-onerror-for-a-step",
+    ),
+    (
+        "displayName",
+        "https://tekton.dev/docs/pipelines/pipelines/#specifying-displayname-in-pipelinetasks",
+    ),
+];
+
+/// Get documentation for a resource kind.
 pub fn get_documentation(key: &str) -> Option<&'static str> {
     TEKTON_DOCS.get(key).copied()
 }
 
+/// Get documentation for a field only `api_version` has, under the field
+/// `parent`.
+pub fn get_versioned_documentation(
+    api_version: &str,
+    parent: &str,
+    field: &str,
+) -> Option<&'static str> {
+    VERSIONED_DOCS.get(&(api_version, parent, field)).copied()
+}
+
+/// Markdown documentation of a field of `kind`: its description, type and
+/// whether it is required, then a table of the fields under it, if any, and
+/// a link to the Tekton documentation about it.
+pub(crate) fn field_documentation(kind: Option<&str>, field: &ResolvedField) -> Option<String> {
+    let schema = field.schema.as_ref()?;
+    let mut doc = match kind {
+        Some(kind) => format!("# Field `{}` of {}\n\n", schema.name, kind),
+        None => format!("# Field `{}`\n\n", schema.name),
+    };
+    doc.push_str(&format!("{}\n\n", schema.description));
+    doc.push_str(&format!(
+        "**Type:** `{}`  \n**Required:** {}",
+        type_name(&schema.field_type),
+        yes_no(schema.required)
+    ));
+
+    if !field.children.is_empty() {
+        doc.push_str("\n\n| Field | Type | Required | Description |\n| --- | --- | --- | --- |");
+        for child in &field.children {
+            doc.push_str(&format!("\n{}", field_row(child)));
+        }
+    }
+    if let Some((_, link)) = FIELD_LINKS.iter().find(|(name, _)| *name == schema.name) {
+        doc.push_str(&format!("\n\n[Tekton documentation]({})", link));
+    }
+    Some(doc)
+}
+
+/// A row of the table of fields.
+fn field_row(field: &FieldSchema) -> String {
+    format!(
+        "| `{}` | {} | {} | {} |",
+        field.name,
+        type_name(&field.field_type),
+        yes_no(field.required),
+        field.description.replace('|', "\\|")
+    )
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::completion::schemas::TektonSchemas;
+    use crate::hover::explain::Section;
 
     #[test]
     fn test_get_documentation_for_pipeline() {
@@ -429,10 +267,37 @@ mod tests {
     }
 
     #[test]
-    fn test_get_documentation_for_tasks_field() {
-        let doc = get_documentation("tasks");
-        assert!(doc.is_some());
-        assert!(doc.unwrap().contains("PipelineTask"));
+    fn test_field_documentation_lists_fields() {
+        let schemas = TektonSchemas::new();
+        let field = Section::PipelineSpec
+            .field(&schemas, "Pipeline", None, "tasks")
+            .unwrap();
+        let doc = field_documentation(Some("Pipeline"), &field).unwrap();
+        assert!(doc.starts_with("# Field `tasks` of Pipeline\n\n"));
+        assert!(doc.contains("**Type:** `array`  \n**Required:** yes"));
+        assert!(doc.contains("| Field | Type | Required | Description |"));
+        assert!(doc.contains("\n| `name` | string | yes |"));
+        assert!(doc.contains("\n| `taskRef` | object | no |"));
+    }
+
+    #[test]
+    fn test_field_documentation_without_fields() {
+        let schemas = TektonSchemas::new();
+        let field = Section::Step
+            .field(&schemas, "Task", None, "image")
+            .unwrap();
+        let doc = field_documentation(None, &field).unwrap();
+        assert!(doc.starts_with("# Field `image`\n\n"));
+        assert!(!doc.contains("| Field |"));
+    }
+
+    #[test]
+    fn test_versioned_documentation() {
+        let doc = get_versioned_documentation("tekton.dev/v1beta1", "spec", "resources");
+        assert!(doc.unwrap().contains("PipelineResources"));
+        assert!(get_versioned_documentation("tekton.dev/v1", "spec", "resources").is_none());
+        let doc = get_versioned_documentation("tekton.dev/v1beta1", "steps", "resources");
+        assert!(doc.unwrap().contains("computeResources"));
     }
 
     #[test]
@@ -0,0 +1,390 @@
+//! Field documentation for dotted paths, in the style of `kubectl explain`.
+//!
+//! Walks the completion schemas along a path such as `spec.tasks.runAfter`
+//! and renders the field with its description and the fields under it. The
+//! same walk gives hover its field documentation.
+
+use super::docs::get_documentation;
+use crate::completion::schemas::{
+    FieldSchema, FieldType, TektonSchemas, TEKTON_API_VERSIONS, V1BETA1_ONLY_PIPELINE_RUN_FIELDS,
+    V1_ONLY_PIPELINE_RUN_FIELDS, V1_ONLY_STEP_FIELDS,
+};
+use crate::error::ExplainError;
+use serde::{Deserialize, Serialize};
+
+/// Kinds whose fields can be explained, tried in order when the path does
+/// not start with a kind.
+const KINDS: [&str; 3] = ["Pipeline", "Task", "StepAction"];
+
+/// Explain the field at a dotted path, e.g. `spec.tasks.runAfter` or
+/// `task.spec.steps.image`.
+///
+/// Without a leading kind, the path is looked up in a Pipeline first, then in
+/// a Task and in a StepAction.
+pub fn explain(path: &str) -> Result<String, ExplainError> {
+    let mut segments: Vec<&str> = path.split('.').filter(|s| !s.is_empty()).collect();
+    if segments.is_empty() {
+        return Err(ExplainError::EmptyPath);
+    }
+
+    let schemas = TektonSchemas::new();
+    let explicit = KINDS
+        .iter()
+        .find(|kind| kind.eq_ignore_ascii_case(segments[0]))
+        .copied();
+    if let Some(kind) = explicit {
+        segments.remove(0);
+        return resolve(&schemas, kind, None, &segments)
+            .map(|field| render(kind, &segments, &field));
+    }
+
+    for kind in KINDS {
+        if let Ok(field) = resolve(&schemas, kind, None, &segments) {
+            return Ok(render(kind, &segments, &field));
+        }
+    }
+    // Report the error of the first kind
+    resolve(&schemas, KINDS[0], None, &segments).map(|field| render(KINDS[0], &segments, &field))
+}
+
+/// A section of the schema: the fields found under a path.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Section {
+    Root,
+    Metadata,
+    PipelineSpec,
+    PipelineTask,
+    TaskRef,
+    TaskSpec,
+    Step,
+    StepActionSpec,
+    PipelineRunSpec,
+    TaskRunSpec,
+    ParamSpec,
+    WorkspaceDeclaration,
+    PipelineWorkspace,
+    Artifacts,
+    SecurityContext,
+    ComputeResources,
+    VolumeMount,
+    OutputConfig,
+    Matrix,
+    MatrixInclude,
+    MatrixParam,
+    When,
+    EventListenerSpec,
+    Trigger,
+    TriggerBindingSpec,
+    TriggerBindingParam,
+    TriggerTemplateSpec,
+    TriggerTemplateParam,
+    Sidecar,
+    StepTemplate,
+    PodTemplate,
+    Timeouts,
+    TaskRunTemplate,
+}
+
+impl Section {
+    /// The fields of this section.
+    fn fields(self, schemas: &TektonSchemas) -> Vec<FieldSchema> {
+        match self {
+            Section::Root => root_fields(),
+            Section::Metadata => schemas.get_metadata_fields().to_vec(),
+            Section::PipelineSpec => schemas.get_pipeline_spec_fields().to_vec(),
+            Section::PipelineTask => schemas.get_pipeline_task_fields().to_vec(),
+            Section::TaskRef => schemas.get_task_ref_fields().to_vec(),
+            Section::TaskSpec => schemas.get_task_spec_fields().to_vec(),
+            Section::Step => schemas.get_step_fields().to_vec(),
+            Section::StepActionSpec => schemas.get_step_action_spec_fields().to_vec(),
+            Section::PipelineRunSpec => schemas.get_pipeline_run_spec_fields().to_vec(),
+            Section::TaskRunSpec => schemas.get_task_run_spec_fields().to_vec(),
+            Section::ParamSpec => schemas.get_param_spec_fields().to_vec(),
+            Section::WorkspaceDeclaration => schemas.get_workspace_declaration_fields().to_vec(),
+            Section::PipelineWorkspace => schemas.get_pipeline_workspace_fields().to_vec(),
+            Section::Artifacts => schemas.get_artifacts_fields().to_vec(),
+            Section::SecurityContext => schemas.get_security_context_fields().to_vec(),
+            Section::ComputeResources => schemas.get_compute_resources_fields().to_vec(),
+            Section::VolumeMount => schemas.get_volume_mount_fields().to_vec(),
+            Section::OutputConfig => schemas.get_output_config_fields().to_vec(),
+            Section::Matrix => schemas.get_matrix_fields().to_vec(),
+            Section::MatrixInclude => schemas.get_matrix_include_fields().to_vec(),
+            Section::MatrixParam => schemas.get_matrix_param_fields().to_vec(),
+            Section::When => schemas.get_when_fields().to_vec(),
+            Section::EventListenerSpec => schemas.get_event_listener_spec_fields().to_vec(),
+            Section::Trigger => schemas.get_trigger_fields().to_vec(),
+            Section::TriggerBindingSpec => schemas.get_trigger_binding_spec_fields().to_vec(),
+            Section::TriggerBindingParam => schemas.get_trigger_binding_param_fields().to_vec(),
+            Section::TriggerTemplateSpec => schemas.get_trigger_template_spec_fields().to_vec(),
+            Section::TriggerTemplateParam => schemas.get_trigger_template_param_fields().to_vec(),
+            Section::Sidecar => schemas.get_sidecar_fields().to_vec(),
+            Section::StepTemplate => schemas.get_step_template_fields().to_vec(),
+            Section::PodTemplate => schemas.get_pod_template_fields().to_vec(),
+            Section::Timeouts => schemas.get_timeouts_fields().to_vec(),
+            Section::TaskRunTemplate => schemas.get_task_run_template_fields().to_vec(),
+        }
+    }
+
+    /// The fields of this section that `api_version` serves, or all of them
+    /// when it is unknown.
+    fn fields_at(self, schemas: &TektonSchemas, api_version: Option<&str>) -> Vec<FieldSchema> {
+        let mut fields = self.fields(schemas);
+        if let Some(api_version) = api_version {
+            fields.retain(|field| self.serves(api_version, &field.name));
+        }
+        fields
+    }
+
+    /// Whether `api_version` has the field `name` in this section.
+    fn serves(self, api_version: &str, name: &str) -> bool {
+        let is_v1 = api_version == TEKTON_API_VERSIONS[0];
+        match self {
+            Section::Step => is_v1 || !V1_ONLY_STEP_FIELDS.contains(&name),
+            Section::PipelineRunSpec if is_v1 => !V1BETA1_ONLY_PIPELINE_RUN_FIELDS.contains(&name),
+            Section::PipelineRunSpec => !V1_ONLY_PIPELINE_RUN_FIELDS.contains(&name),
+            _ => true,
+        }
+    }
+
+    /// The section under a field of this section, if it has known fields.
+    fn child(self, kind: &str, field: &str) -> Option<Section> {
+        match (self, field) {
+            (Section::Root, "metadata") => Some(Section::Metadata),
+            (Section::Root, "spec") => match kind {
+                "Pipeline" => Some(Section::PipelineSpec),
+                "Task" | "ClusterTask" => Some(Section::TaskSpec),
+                "StepAction" => Some(Section::StepActionSpec),
+                "PipelineRun" => Some(Section::PipelineRunSpec),
+                "TaskRun" => Some(Section::TaskRunSpec),
+                "EventListener" => Some(Section::EventListenerSpec),
+                "Trigger" => Some(Section::Trigger),
+                "TriggerBinding" | "ClusterTriggerBinding" => Some(Section::TriggerBindingSpec),
+                "TriggerTemplate" => Some(Section::TriggerTemplateSpec),
+                _ => None,
+            },
+            (Section::PipelineSpec, "tasks" | "finally") => Some(Section::PipelineTask),
+            (Section::PipelineSpec | Section::TaskSpec | Section::StepActionSpec, "params") => {
+                Some(Section::ParamSpec)
+            }
+            (Section::PipelineSpec, "workspaces") => Some(Section::PipelineWorkspace),
+            (Section::TaskSpec, "workspaces") => Some(Section::WorkspaceDeclaration),
+            (Section::PipelineTask | Section::TaskRunSpec, "taskRef") => Some(Section::TaskRef),
+            (Section::PipelineTask | Section::TaskRunSpec, "taskSpec") => Some(Section::TaskSpec),
+            (Section::PipelineRunSpec, "pipelineSpec") => Some(Section::PipelineSpec),
+            (Section::PipelineTask, "matrix") => Some(Section::Matrix),
+            (Section::PipelineTask, "when") => Some(Section::When),
+            (Section::TaskSpec, "steps") => Some(Section::Step),
+            (Section::TaskSpec, "sidecars") => Some(Section::Sidecar),
+            (Section::TaskSpec, "stepTemplate") => Some(Section::StepTemplate),
+            (Section::Step, "artifacts") => Some(Section::Artifacts),
+            (Section::Step | Section::Sidecar | Section::StepTemplate, "securityContext") => {
+                Some(Section::SecurityContext)
+            }
+            (
+                Section::Step | Section::Sidecar | Section::StepTemplate | Section::TaskRunSpec,
+                "computeResources",
+            ) => Some(Section::ComputeResources),
+            (Section::Step | Section::Sidecar | Section::StepTemplate, "volumeMounts") => {
+                Some(Section::VolumeMount)
+            }
+            (Section::PipelineRunSpec, "timeouts") => Some(Section::Timeouts),
+            (Section::PipelineRunSpec, "taskRunTemplate") => Some(Section::TaskRunTemplate),
+            (
+                Section::PipelineRunSpec | Section::TaskRunSpec | Section::TaskRunTemplate,
+                "podTemplate",
+            ) => Some(Section::PodTemplate),
+            (Section::Step, "stdoutConfig" | "stderrConfig") => Some(Section::OutputConfig),
+            (Section::Matrix, "include") => Some(Section::MatrixInclude),
+            (Section::Matrix | Section::MatrixInclude, "params") => Some(Section::MatrixParam),
+            (Section::EventListenerSpec, "triggers") => Some(Section::Trigger),
+            (Section::TriggerBindingSpec, "params") => Some(Section::TriggerBindingParam),
+            (Section::TriggerTemplateSpec, "params") => Some(Section::TriggerTemplateParam),
+            _ => None,
+        }
+    }
+
+    /// The field `name` of this section at `api_version`, with the fields
+    /// under it.
+    pub(crate) fn field(
+        self,
+        schemas: &TektonSchemas,
+        kind: &str,
+        api_version: Option<&str>,
+        name: &str,
+    ) -> Option<ResolvedField> {
+        let schema = self
+            .fields_at(schemas, api_version)
+            .into_iter()
+            .find(|f| f.name == name)?;
+        Some(ResolvedField {
+            schema: Some(schema),
+            children: self
+                .child(kind, name)
+                .map(|s| s.fields_at(schemas, api_version))
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// A field found at a path, with the fields under it.
+pub(crate) struct ResolvedField {
+    /// The field, or `None` for the kind itself
+    pub(crate) schema: Option<FieldSchema>,
+    /// Fields under it, when known
+    pub(crate) children: Vec<FieldSchema>,
+}
+
+/// Follow a path from the root of a kind, through the fields `api_version`
+/// serves when it is known.
+pub(crate) fn resolve(
+    schemas: &TektonSchemas,
+    kind: &str,
+    api_version: Option<&str>,
+    segments: &[&str],
+) -> Result<ResolvedField, ExplainError> {
+    let mut section = Some(Section::Root);
+    let mut schema = None;
+    for (i, segment) in segments.iter().enumerate() {
+        let parent = segments[..i].join(".");
+        let Some(current) = section else {
+            return Err(ExplainError::NoFields {
+                kind: kind.to_string(),
+                field: parent,
+            });
+        };
+        let fields = current.fields_at(schemas, api_version);
+        let Some(field) = fields.iter().find(|f| f.name == *segment) else {
+            return Err(ExplainError::UnknownField {
+                kind: kind.to_string(),
+                parent,
+                field: segment.to_string(),
+                fields: fields.iter().map(|f| f.name.clone()).collect(),
+            });
+        };
+        schema = Some(field.clone());
+        section = current.child(kind, segment);
+    }
+
+    Ok(ResolvedField {
+        schema,
+        children: section
+            .map(|s| s.fields_at(schemas, api_version))
+            .unwrap_or_default(),
+    })
+}
+
+/// Render a field in the `kubectl explain` layout.
+fn render(kind: &str, segments: &[&str], field: &ResolvedField) -> String {
+    let mut out = format!("KIND:     {}\n", kind);
+    let documentation = match &field.schema {
+        Some(schema) => {
+            out.push_str(&format!(
+                "FIELD:    {} <{}>\n",
+                segments.join("."),
+                type_name(&schema.field_type)
+            ));
+            out.push_str(&format!("\nDESCRIPTION:\n    {}\n", schema.description));
+            None
+        }
+        None => get_documentation(kind),
+    };
+    if let Some(documentation) = documentation {
+        out.push('\n');
+        for line in documentation.lines() {
+            if line.is_empty() {
+                out.push('\n');
+            } else {
+                out.push_str(&format!("    {}\n", line));
+            }
+        }
+    }
+
+    if !field.children.is_empty() {
+        out.push_str("\nFIELDS:\n");
+        for child in &field.children {
+            let required = if child.required { " -required-" } else { "" };
+            out.push_str(&format!(
+                "    {}\t<{}>{}\n      {}\n",
+                child.name,
+                type_name(&child.field_type),
+                required,
+                child.description
+            ));
+        }
+    }
+    out
+}
+
+/// Fields at the root of every resource.
+fn root_fields() -> Vec<FieldSchema> {
+    [
+        (
+            "apiVersion",
+            "API version of the resource, e.g. tekton.dev/v1",
+            FieldType::String,
+        ),
+        ("kind", "Kind of the resource", FieldType::String),
+        ("metadata", "Standard object metadata", FieldType::Object),
+        ("spec", "Specification of the resource", FieldType::Object),
+    ]
+    .into_iter()
+    .map(|(name, description, field_type)| FieldSchema {
+        name: name.to_string(),
+        description: description.to_string(),
+        field_type,
+        required: true,
+    })
+    .collect()
+}
+
+pub(crate) fn type_name(field_type: &FieldType) -> &'static str {
+    match field_type {
+        FieldType::String | FieldType::Enum(_) => "string",
+        FieldType::Array => "array",
+        FieldType::Object => "object",
+        FieldType::Boolean => "boolean",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_pipeline_task_field() {
+        let text = explain("spec.tasks.runAfter").unwrap();
+        assert!(text.starts_with("KIND:     Pipeline\nFIELD:    spec.tasks.runAfter <array>\n"));
+        assert!(text.contains("\nDESCRIPTION:\n    "));
+        assert!(!text.contains("\nFIELDS:\n"));
+    }
+
+    #[test]
+    fn test_explain_infers_or_takes_the_kind() {
+        let text = explain("spec.steps").unwrap();
+        assert!(text.starts_with("KIND:     Task\n"));
+        assert!(text.contains("\nFIELDS:\n    name\t<string>"));
+
+        let text = explain("task").unwrap();
+        assert!(text.contains("# Task"));
+        assert!(text.contains("    spec\t<object> -required-"));
+    }
+
+    #[test]
+    fn test_explain_step_action() {
+        let text = explain("stepaction.spec").unwrap();
+        assert!(text.starts_with("KIND:     StepAction\nFIELD:    spec <object>\n"));
+        assert!(text.contains("    image\t<string> -required-"));
+    }
+
+    #[test]
+    fn test_explain_unknown_field() {
+        assert_eq!(
+            explain("pipeline.spec.steps").unwrap_err().to_string(),
+            "Unknown field 'steps' in spec of Pipeline; fields: tasks, finally, params, workspaces, results, description, displayName"
+        );
+        assert_eq!(
+            explain("spec.tasks.name.first").unwrap_err().to_string(),
+            "Field 'spec.tasks.name' of Pipeline has no documented fields"
+        );
+    }
+}
@@ -2,10 +2,12 @@
 //!
 //! Provides documentation on hover for:
 //! - Tekton resource kinds (Pipeline, Task, etc.)
-//! - Tekton field names (tasks, steps, params, etc.)
-//! - Common metadata fields
+//! - Tekton fields, from the completion schemas of their path
+//!
+//! The same schemas back `tekton-lsp explain <path>`.
 
 pub mod docs;
+pub mod explain;
 pub mod provider;
 
 pub use provider::HoverProvider;
@@ -1,25 +1,114 @@
 //! Hover provider implementation.
 
-use tower_lsp::lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position, Range};
+use tower_lsp::lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position, Range, Url};
 
-use crate::parser::{Node, NodeValue, YamlDocument};
-use super::docs::get_documentation;
+use super::docs::{field_documentation, get_documentation, get_versioned_documentation};
+use super::explain::resolve;
+use crate::completion::schemas::TektonSchemas;
+use crate::integrations::git::raw_file_url;
+use crate::integrations::hub::resource_yaml_url;
+use crate::integrations::{GitClient, HubClient, ImageReference, RegistryClient, RemoteFile};
+use crate::model::{ParamSpec, ParamValue, PipelineTask, Resource, ResultSpec, TaskRef};
+use crate::parser::expressions::{expressions_in, find_expressions, Reference};
+use crate::parser::positions::contains;
+use crate::parser::{self, to_flow_yaml, to_yaml, Node, NodeValue, PositionEncoding, YamlDocument};
+use crate::workspace::index::{ResourceDefinition, ResourceReference};
+use crate::workspace::WorkspaceIndex;
 
 /// Provides hover documentation for Tekton YAML files.
 #[derive(Debug, Clone)]
-pub struct HoverProvider;
+pub struct HoverProvider {
+    /// Schemas the documentation of fields is generated from
+    schemas: TektonSchemas,
+    /// Workspace index used to follow params through the resources passing them
+    index: Option<WorkspaceIndex>,
+    /// Tekton Hub, for references using the `hub` resolver
+    hub: HubClient,
+    /// Git repositories, for references using the `git` resolver
+    git: GitClient,
+    /// Container registries, for the current digest of images
+    registry: RegistryClient,
+}
 
 impl HoverProvider {
     /// Create a new hover provider.
     pub fn new() -> Self {
-        Self
+        Self {
+            schemas: TektonSchemas::new(),
+            index: None,
+            hub: HubClient::default(),
+            git: GitClient::default(),
+            registry: RegistryClient::default(),
+        }
+    }
+
+    /// Create a hover provider backed by the workspace index.
+    pub fn with_index(index: WorkspaceIndex) -> Self {
+        Self {
+            schemas: TektonSchemas::new(),
+            index: Some(index),
+            hub: HubClient::default(),
+            git: GitClient::default(),
+            registry: RegistryClient::default(),
+        }
+    }
+
+    /// Use `hub` for references using the `hub` resolver, such as the client
+    /// completion fetches the catalog with.
+    pub fn with_hub(self, hub: HubClient) -> Self {
+        Self { hub, ..self }
+    }
+
+    /// The Git client, disabled until configured
+    pub fn git(&self) -> &GitClient {
+        &self.git
+    }
+
+    /// The registry client, disabled until configured
+    pub fn registry(&self) -> &RegistryClient {
+        &self.registry
     }
 
     /// Provide hover information for a given position in a YAML document.
     pub fn provide_hover(&self, yaml_doc: &YamlDocument, position: Position) -> Option<Hover> {
+        // runAfter entries show a summary of the pipeline task they point to
+        if let Some(hover) = self.provide_run_after_hover(yaml_doc, position) {
+            return Some(hover);
+        }
+
+        // taskRef names show the interface of the indexed Task
+        if let Some(hover) = self.provide_task_ref_hover(yaml_doc, position) {
+            return Some(hover);
+        }
+
+        // Resolvers of references show the remote resource they fetch
+        if let Some(hover) = self.provide_resolver_hover(yaml_doc, position) {
+            return Some(hover);
+        }
+
+        // Task result references show the declared result
+        if let Some(hover) = self.provide_result_reference_hover(yaml_doc, position) {
+            return Some(hover);
+        }
+
+        // Param references show the param and where its value comes from
+        if let Some(hover) = self.provide_param_reference_hover(yaml_doc, position) {
+            return Some(hover);
+        }
+
+        // Names of described elements show their description
+        if let Some(hover) = self.provide_description_hover(yaml_doc, position) {
+            return Some(hover);
+        }
+
         // Find the node at the cursor position
         let (node, key) = self.find_node_with_key_at_position(&yaml_doc.root, position)?;
 
+        // Images of containers show where they come from and how they are pinned
+        if let Some(hover) = self.provide_image_hover(node, key.as_deref(), yaml_doc, position) {
+            return Some(hover);
+        }
+
         // Try to get documentation
         let documentation = self.get_hover_documentation(node, key.as_deref(), yaml_doc)?;
 
@@ -28,7 +117,457 @@ impl HoverProvider {
                 kind: MarkupKind::Markdown,
                 value: documentation,
             }),
-            range: Some(node.range),
+            range: token_range(node, yaml_doc, position),
+        })
+    }
+
+    /// Hover for a task name inside a pipeline task's `runAfter` list, in a
+    /// Pipeline or the `pipelineSpec` of a PipelineRun.
+    fn provide_run_after_hover(
+        &self,
+        yaml_doc: &YamlDocument,
+        position: Position,
+    ) -> Option<Hover> {
+        let resource = Resource::from_document(yaml_doc)?;
+        let pipeline = match &resource {
+            Resource::Pipeline(pipeline) => pipeline,
+            Resource::PipelineRun(run) => run.pipeline_spec.as_ref()?,
+            _ => return None,
+        };
+
+        let entry = pipeline
+            .all_tasks()
+            .filter_map(|task| task.run_after.as_ref())
+            .flat_map(|run_after| run_after.value.iter())
+            .find(|entry| contains(entry.range, position))?;
+        let target = pipeline.task(entry.value)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: pipeline_task_summary(entry.value, target),
+            }),
+            range: Some(entry.range),
+        })
+    }
+
+    /// Hover for the `name` of a `taskRef` of a pipeline task or TaskRun: the
+    /// interface of the Task it points to, found in the workspace index.
+    fn provide_task_ref_hover(&self, yaml_doc: &YamlDocument, position: Position) -> Option<Hover> {
+        let index = self.index.as_ref()?;
+        let resource = Resource::from_document(yaml_doc)?;
+        let task_refs: Vec<&TaskRef> = match &resource {
+            Resource::Pipeline(pipeline) => pipeline
+                .all_tasks()
+                .filter_map(|task| task.task_ref.as_ref())
+                .collect(),
+            Resource::PipelineRun(run) => run
+                .pipeline_spec
+                .iter()
+                .flat_map(|pipeline| pipeline.all_tasks())
+                .filter_map(|task| task.task_ref.as_ref())
+                .collect(),
+            Resource::TaskRun(run) => run.task_ref.iter().collect(),
+            Resource::Task(_) => return None,
+        };
+
+        let task_ref = task_refs
+            .into_iter()
+            .find(|r| r.name.is_some_and(|name| contains(name.range, position)))?;
+        let name = task_ref.name?;
+        let definition = index.find_resource(task_ref.kind_or_default(), name.value)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: definition_summary(&definition),
+            }),
+            range: Some(name.range),
+        })
+    }
+
+    /// Hover for the `resolver` of a `taskRef` or `pipelineRef` using the
+    /// `hub` or `git` resolver: the remote resource, once fetched.
+    fn provide_resolver_hover(&self, yaml_doc: &YamlDocument, position: Position) -> Option<Hover> {
+        let resource = Resource::from_document(yaml_doc)?;
+        let task_refs: Vec<&TaskRef> = match &resource {
+            Resource::Pipeline(pipeline) => pipeline
+                .all_tasks()
+                .filter_map(|task| task.task_ref.as_ref())
+                .collect(),
+            Resource::PipelineRun(run) => run
+                .pipeline_spec
+                .iter()
+                .flat_map(|pipeline| pipeline.all_tasks())
+                .filter_map(|task| task.task_ref.as_ref())
+                .collect(),
+            Resource::TaskRun(run) => run.task_ref.iter().collect(),
+            Resource::Task(_) => Vec::new(),
+        };
+        let pipeline_ref = match &resource {
+            Resource::PipelineRun(run) => run.pipeline_ref.as_ref(),
+            _ => None,
+        };
+        let (resolver, params, kind) = task_refs
+            .into_iter()
+            .filter_map(|r| Some((r.resolver?, r.params.as_slice(), "Task")))
+            .chain(pipeline_ref.and_then(|r| Some((r.resolver?, r.params.as_slice(), "Pipeline"))))
+            .find(|(resolver, _, _)| contains(resolver.range, position))?;
+
+        let summary = match resolver.value {
+            "hub" => self.hub_resource_summary(params, kind)?,
+            "git" => self.git_resource_summary(params, kind)?,
+            _ => return None,
+        };
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: summary,
+            }),
+            range: Some(resolver.range),
+        })
+    }
+
+    /// Summary of the Hub resource named by the params of a reference: its
+    /// catalog entry, then its interface once fetched. `None` when the Hub is
+    /// disabled or does not have it.
+    fn hub_resource_summary(&self, params: &[ParamValue], default_kind: &str) -> Option<String> {
+        let name = resolver_param(params, "name")?;
+        let kind = resolver_param(params, "kind").unwrap_or(default_kind);
+        let catalog = resolver_param(params, "catalog");
+        let resource = self.hub.resources(kind).into_iter().find(|resource| {
+            resource.name == name
+                && catalog.is_none_or(|c| c.eq_ignore_ascii_case(&resource.catalog))
+        })?;
+        let version = resolver_param(params, "version").unwrap_or(&resource.latest_version);
+
+        let mut summary = format!(
+            "# {} `{}` from the Hub\n\n**Catalog:** `{}`  \n**Version:** `{}` (latest `{}`)\n\n",
+            resource.kind, resource.name, resource.catalog, version, resource.latest_version
+        );
+        let url = resource_yaml_url(&self.hub.options().url, &resource, version);
+        let file = self.hub.resource_file(&resource, version)?;
+        match remote_definition(&url, &file) {
+            Some(definition) => summary.push_str(&definition_details(&definition)),
+            None => {
+                if let Some(description) = &resource.description {
+                    summary.push_str(&format!("{}\n\n", description.trim()));
+                }
+                summary.push_str(&fetch_status(&file));
+            }
+        }
+        Some(summary.trim_end().to_string())
+    }
+
+    /// Summary of the file of a Git repository named by the params of a
+    /// reference: where it is, then its interface once fetched. `None` when
+    /// Git is disabled or the repository is not on a supported host.
+    fn git_resource_summary(&self, params: &[ParamValue], kind: &str) -> Option<String> {
+        let url = resolver_param(params, "url")?;
+        let path = resolver_param(params, "pathInRepo")?;
+        let revision = resolver_param(params, "revision");
+        let file = self.git.file(url, revision, path)?;
+        let raw_url = raw_file_url(url, revision, path)?;
+
+        let definition = remote_definition(&raw_url, &file);
+        let mut summary = match &definition {
+            Some(definition) => format!("# {} `{}` from Git\n\n", definition.kind, definition.name),
+            None => format!("# {} from Git\n\n", kind),
+        };
+        summary.push_str(&format!(
+            "**Repository:** {}  \n**Revision:** `{}`  \n**Path:** `{}`\n\n",
+            url,
+            revision.unwrap_or("main"),
+            path
+        ));
+        match &definition {
+            Some(definition) => summary.push_str(&definition_details(definition)),
+            None => summary.push_str(&fetch_status(&file)),
+        }
+        Some(summary.trim_end().to_string())
+    }
+
+    /// Hover for the `image` value of a step, sidecar, `stepTemplate` or
+    /// StepAction: its registry, repository, tag and digest, and how to pin it.
+    fn provide_image_hover(
+        &self,
+        node: &Node,
+        key: Option<&str>,
+        yaml_doc: &YamlDocument,
+        position: Position,
+    ) -> Option<Hover> {
+        if key != Some("image") || !contains(node.value_range, position) {
+            return None;
+        }
+        let path = yaml_doc.root.path_to(node)?;
+        let container = match path.iter().rev().nth(1)? {
+            &"steps" | &"sidecars" | &"stepTemplate" => true,
+            &"spec" => path.len() == 2 && yaml_doc.kind.as_deref() == Some("StepAction"),
+            _ => false,
+        };
+        if !container {
+            return None;
+        }
+        let image = node.scalar_content()?;
+        let reference = ImageReference::parse(&image)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: self.image_summary(image.trim(), &reference),
+            }),
+            range: Some(node.value_range),
+        })
+    }
+
+    /// Summary of an image: where it comes from, then whether it is pinned,
+    /// with the digest its tag points to when registries may be asked.
+    fn image_summary(&self, image: &str, reference: &ImageReference) -> String {
+        let mut summary = format!(
+            "# Image `{}`
+
+**Registry:** [{}]({})  \n**Repository:** `{}`  \n",
+            image,
+            reference.registry,
+            reference.web_url(),
+            reference.repository
+        );
+        match &reference.tag {
+            Some(tag) => summary.push_str(&format!("**Tag:** `{}`", tag)),
+            None => summary.push_str("**Tag:** none (`latest`)"),
+        }
+        if let Some(digest) = &reference.digest {
+            summary.push_str(&format!("  \n**Digest:** `{}`", digest));
+            return summary;
+        }
+
+        summary.push_str("\n\n");
+        match reference.tag.as_deref() {
+            Some("latest") => summary.push_str(
+                "The `latest` tag moves to each new image: runs may not be reproducible. \
+                 Pin a version tag, or better a digest.",
+            ),
+            None => summary.push_str(
+                "Without a tag the `latest` one is pulled, which moves to each new image: \
+                 runs may not be reproducible. Pin a version tag, or better a digest.",
+            ),
+            Some(_) => summary.push_str(
+                "Tags can be moved to another image. Pin a digest so that every run \
+                 uses the same image.",
+            ),
+        }
+        let name = image.split('@').next().unwrap_or(image);
+        match self.registry.digest(reference) {
+            Some(RemoteFile::Fetched(digest)) => summary.push_str(&format!(
+                "\n\n**Current digest of `{}`:** `{}`\n\n```yaml\nimage: {}@{}\n```",
+                reference.tag_or_default(),
+                digest.trim(),
+                name,
+                digest.trim()
+            )),
+            Some(RemoteFile::Fetching) => summary.push_str("\n\n_Fetching the current digest..._"),
+            Some(RemoteFile::Failed(message)) => summary.push_str(&format!(
+                "\n\n_Failed to fetch the current digest: {}_",
+                message
+            )),
+            None => summary.push_str(&format!(
+                "\n\n```yaml\nimage: {}@sha256:<digest>\n```",
+                name
+            )),
+        }
+        summary
+    }
+
+    /// Hover for a `$(tasks.<task>.results.<result>)` reference to a result of an inline
+    /// `taskSpec` or of the indexed Task of a `taskRef`, or a `$(tasks.<task>.status)`
+    /// reference to a pipeline task (also under `finally`).
+    fn provide_result_reference_hover(
+        &self,
+        yaml_doc: &YamlDocument,
+        position: Position,
+    ) -> Option<Hover> {
+        let resource = Resource::from_document(yaml_doc)?;
+        let pipeline = match &resource {
+            Resource::Pipeline(pipeline) => pipeline,
+            Resource::PipelineRun(run) => run.pipeline_spec.as_ref()?,
+            _ => return None,
+        };
+
+        let (node, _) = self.find_node_with_key_at_position(&yaml_doc.root, position)?;
+        let expr = expressions_in(node, yaml_doc.encoding)
+            .into_iter()
+            .find(|expr| contains(expr.range, position))?;
+        let value = match expr.reference() {
+            Reference::TaskResult { task, result } => {
+                match pipeline.task_result(&task.name, &result.name) {
+                    Some(declared) => result_summary(&task.name, declared),
+                    None => self.indexed_result_summary(
+                        &task.name,
+                        pipeline.task(&task.name)?,
+                        &result.name,
+                    )?,
+                }
+            }
+            Reference::TaskField { task, .. } => {
+                pipeline_task_summary(&task.name, pipeline.task(&task.name)?)
+            }
+            _ => return None,
+        };
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(expr.range),
+        })
+    }
+
+    /// Summary of the result `result` of the indexed Task the `taskRef` of
+    /// pipeline task `name` points to.
+    fn indexed_result_summary(
+        &self,
+        name: &str,
+        task: &PipelineTask,
+        result: &str,
+    ) -> Option<String> {
+        let task_ref = task.task_ref.as_ref()?;
+        let definition = self
+            .index
+            .as_ref()?
+            .find_resource(task_ref.kind_or_default(), task_ref.name?.value)?;
+        let declared = definition.results.iter().find(|r| r.name == result)?;
+
+        let mut summary = format!("# Result `{}` of task `{}`\n\n", declared.name, name);
+        if let Some(description) = &declared.description {
+            summary.push_str(description.trim());
+            summary.push_str("\n\n");
+        }
+        summary.push_str(&format!("**Type:** `{}`  \n", declared.result_type));
+        summary.push_str(&format!(
+            "**Declared by:** {} `{}` in [{}]({}#L{})",
+            definition.kind,
+            definition.name,
+            definition.file_name(),
+            definition.uri,
+            definition.location.range.start.line + 1
+        ));
+        Some(summary)
+    }
+
+    /// Hover for a `$(params.<name>)` reference to a param of the Task or Pipeline.
+    fn provide_param_reference_hover(
+        &self,
+        yaml_doc: &YamlDocument,
+        position: Position,
+    ) -> Option<Hover> {
+        let resource = Resource::from_document(yaml_doc)?;
+        let (kind, metadata, params) = match &resource {
+            Resource::Pipeline(p) => ("Pipeline", &p.metadata, &p.params),
+            Resource::Task(t) => ("Task", &t.metadata, &t.params),
+            Resource::PipelineRun(_) | Resource::TaskRun(_) => return None,
+        };
+
+        let (node, _) = self.find_node_with_key_at_position(&yaml_doc.root, position)?;
+        let expr = expressions_in(node, yaml_doc.encoding)
+            .into_iter()
+            .find(|expr| contains(expr.range, position))?;
+        let Reference::Param { name } = expr.reference() else {
+            return None;
+        };
+        let param = params
+            .iter()
+            .find(|p| p.name.is_some_and(|n| n.value == name.name))?;
+
+        let mut summary = param_summary(&name.name, param);
+        let resolution = match (&self.index, metadata.name) {
+            (Some(index), Some(resource_name)) => {
+                resolution_chain(index, kind, resource_name.value, &name.name, 0)
+            }
+            _ => Vec::new(),
+        };
+        if !resolution.is_empty() {
+            summary.push_str("\n\n**Resolution:**\n");
+            summary.push_str(&resolution.join("\n"));
+        }
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: summary,
+            }),
+            range: Some(expr.range),
+        })
+    }
+
+    /// Hover for the name of a resource, param, result or workspace that has a description.
+    fn provide_description_hover(
+        &self,
+        yaml_doc: &YamlDocument,
+        position: Position,
+    ) -> Option<Hover> {
+        let resource = Resource::from_document(yaml_doc)?;
+        let (metadata, spec, params, results, workspaces) = match &resource {
+            Resource::Pipeline(p) => (&p.metadata, p.spec, &p.params, &p.results, &p.workspaces),
+            Resource::Task(t) => (&t.metadata, t.spec, &t.params, &t.results, &t.workspaces),
+            Resource::PipelineRun(_) | Resource::TaskRun(_) => return None,
+        };
+        let kind = yaml_doc.kind.as_deref().unwrap_or("Resource");
+
+        // (name, heading, details, owner node carrying the description)
+        let mut candidates = Vec::new();
+        if let (Some(name), Some(spec)) = (metadata.name, spec) {
+            candidates.push((name, kind, Vec::new(), spec));
+        }
+        for param in params {
+            let mut details = Vec::new();
+            details.push(format!(
+                "**Type:** `{}`",
+                param.param_type.map(|t| t.value).unwrap_or("string")
+            ));
+            if let Some(default) = param.default {
+                details.push(format!("**Default:** `{}`", to_yaml(default).trim_end()));
+            }
+            if let Some(name) = param.name {
+                candidates.push((name, "Param", details, param.node));
+            }
+        }
+        for result in results {
+            let details = vec![format!("**Type:** `{}`", result.type_or_default())];
+            if let Some(name) = result.name {
+                candidates.push((name, "Result", details, result.node));
+            }
+        }
+        for workspace in workspaces {
+            let mut details = Vec::new();
+            if let Some(mount_path) = workspace.mount_path {
+                details.push(format!("**Mount path:** `{}`", mount_path.value));
+            }
+            if workspace.optional.is_some_and(|o| o.value == "true") {
+                details.push("**Optional**".to_string());
+            }
+            if let Some(name) = workspace.name {
+                candidates.push((name, "Workspace", details, workspace.node));
+            }
+        }
+
+        let (name, heading, details, owner) = candidates
+            .into_iter()
+            .find(|(name, ..)| contains(name.range, position))?;
+        let description = owner.get("description")?.scalar_content()?;
+
+        let mut value = format!("# {} `{}`\n\n{}", heading, name.value, description.trim());
+        if !details.is_empty() {
+            value.push_str("\n\n");
+            value.push_str(&details.join("  \n"));
+        }
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(name.range),
         })
     }
 
@@ -38,7 +577,7 @@ impl HoverProvider {
         node: &'a Node,
         position: Position,
     ) -> Option<(&'a Node, Option<String>)> {
-        if !self.position_in_range(position, &node.range) {
+        if !contains(node.range, position) {
             return None;
         }
 
@@ -51,7 +590,7 @@ impl HoverProvider {
                     }
                     // If we're in the child's range but didn't find a more specific match,
                     // return the child with its key
-                    if self.position_in_range(position, &child.range) {
+                    if contains(child.range, position) {
                         return Some((child, Some(key.clone())));
                     }
                 }
@@ -70,34 +609,21 @@ impl HoverProvider {
         Some((node, node.key.clone()))
     }
 
-    /// Get hover documentation for a node.
+    /// Get hover documentation for a node: the documentation of a kind, or
+    /// the schema documentation of the field at the node's path.
     fn get_hover_documentation(
         &self,
         node: &Node,
         key: Option<&str>,
         yaml_doc: &YamlDocument,
     ) -> Option<String> {
-        // First, try to get documentation for the key (field name)
-        if let Some(key) = key {
-            if let Some(doc) = get_documentation(key) {
-                return Some(doc.to_string());
-            }
-        }
-
-        // If the node is a scalar value, check if it's a known kind
+        // Kinds, as the value of `kind` or of a reference's `kind`
         if let NodeValue::Scalar(value) = &node.value {
             if let Some(doc) = get_documentation(value) {
                 return Some(doc.to_string());
             }
         }
 
-        // Check if the key is a well-known value
-        if let Some(key) = &node.key {
-            if let Some(doc) = get_documentation(key) {
-                return Some(doc.to_string());
-            }
-        }
-
         // For document-level kind, provide context
         if key == Some("kind") {
             if let Some(kind) = &yaml_doc.kind {
@@ -107,21 +633,346 @@ impl HoverProvider {
             }
         }
 
-        None
+        // Fields, documented from the schemas along their path, or for the
+        // fields removed since the document's apiVersion by that version
+        let key = key?;
+        let path = yaml_doc.root.path_to(node)?;
+        let kind = yaml_doc.kind.as_deref();
+        let api_version = yaml_doc.api_version.as_deref();
+        if let Ok(field) = resolve(&self.schemas, kind.unwrap_or_default(), api_version, &path) {
+            return field_documentation(kind, &field);
+        }
+        let parent = path.iter().rev().nth(1)?;
+        get_versioned_documentation(api_version?, parent, key).map(str::to_string)
+    }
+}
+
+/// Render a Markdown summary of a pipeline task: where it is, what it runs,
+/// its params and when-conditions.
+fn pipeline_task_summary(name: &str, task: &PipelineTask) -> String {
+    let mut summary = format!("# Pipeline task `{}`\n\n", name);
+
+    let line = task
+        .name
+        .map_or(task.node.range.start, |n| n.range.start)
+        .line;
+    summary.push_str(&format!("**Defined at:** line {}\n\n", line + 1));
+
+    if let Some(task_ref) = &task.task_ref {
+        match (task_ref.name, task_ref.resolver) {
+            (Some(ref_name), _) => summary.push_str(&format!(
+                "**Runs:** `{}` ({})\n\n",
+                ref_name.value,
+                task_ref.kind_or_default()
+            )),
+            (None, Some(resolver)) => summary.push_str(&format!(
+                "**Runs:** remote Task via `{}` resolver\n\n",
+                resolver.value
+            )),
+            (None, None) => {}
+        }
+    } else if let Some(task_spec) = &task.task_spec {
+        summary.push_str("**Runs:** inline `taskSpec`\n\n");
+        if !task_spec.steps.is_empty() {
+            summary.push_str("**Steps:**\n");
+            for step in &task_spec.steps {
+                let step_name = step.name.map(|n| n.value).unwrap_or("unnamed");
+                match step.image {
+                    Some(image) => {
+                        summary.push_str(&format!("- `{}` (image `{}`)\n", step_name, image.value))
+                    }
+                    None => summary.push_str(&format!("- `{}`\n", step_name)),
+                }
+            }
+            summary.push('\n');
+        }
+    }
+
+    if !task.params.is_empty() {
+        summary.push_str("**Params:**\n");
+        for param in &task.params {
+            let param_name = param.name.map(|n| n.value).unwrap_or("unnamed");
+            match param.value.and_then(|v| v.as_scalar()) {
+                Some(value) => summary.push_str(&format!("- `{}`: `{}`\n", param_name, value)),
+                None => summary.push_str(&format!("- `{}`\n", param_name)),
+            }
+        }
+        summary.push('\n');
+    }
+
+    if !task.when.is_empty() {
+        summary.push_str("**When:**\n");
+        for condition in &task.when {
+            let input = condition.input.map(|n| n.value).unwrap_or("?");
+            let operator = condition.operator.map(|n| n.value).unwrap_or("?");
+            let values = condition
+                .values
+                .as_ref()
+                .map(|values| {
+                    values
+                        .value
+                        .iter()
+                        .map(|v| v.value)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_default();
+            summary.push_str(&format!("- `{}` {} [{}]\n", input, operator, values));
+        }
     }
 
-    fn position_in_range(&self, pos: Position, range: &Range) -> bool {
-        if pos.line < range.start.line || pos.line > range.end.line {
-            return false;
+    summary.trim_end().to_string()
+}
+
+/// Render a Markdown summary of the interface of an indexed resource: where
+/// it is defined, its description, params, workspaces and results.
+fn definition_summary(definition: &ResourceDefinition) -> String {
+    format!(
+        "# {} `{}`\n\n{}",
+        definition.kind,
+        definition.name,
+        definition_details(definition)
+    )
+}
+
+/// The body of a definition summary, below its heading.
+fn definition_details(definition: &ResourceDefinition) -> String {
+    let mut summary = String::new();
+
+    if let Some(description) = &definition.description {
+        summary.push_str(description);
+        summary.push_str("\n\n");
+    }
+    summary.push_str(&format!(
+        "**Defined in:** [{}]({}#L{})\n\n",
+        definition.file_name(),
+        definition.uri,
+        definition.location.range.start.line + 1
+    ));
+
+    if !definition.params.is_empty() {
+        summary.push_str("**Params:**\n");
+        for param in &definition.params {
+            let default = match &param.default {
+                Some(default) => format!("default `{}`", default),
+                None => "required".to_string(),
+            };
+            let mut line = format!("- `{}` (`{}`, {})", param.name, param.param_type, default);
+            if let Some(description) = &param.description {
+                line.push_str(&format!(": {}", description.trim()));
+            }
+            summary.push_str(&line);
+            summary.push('\n');
         }
-        if pos.line == range.start.line && pos.character < range.start.character {
-            return false;
+        summary.push('\n');
+    }
+
+    if !definition.workspaces.is_empty() {
+        summary.push_str("**Workspaces:**\n");
+        for workspace in &definition.workspaces {
+            if workspace.optional {
+                summary.push_str(&format!("- `{}` (optional)\n", workspace.name));
+            } else {
+                summary.push_str(&format!("- `{}`\n", workspace.name));
+            }
         }
-        if pos.line == range.end.line && pos.character > range.end.character {
-            return false;
+        summary.push('\n');
+    }
+
+    if !definition.results.is_empty() {
+        let results: Vec<String> = definition
+            .results
+            .iter()
+            .map(|result| format!("`{}`", result.name))
+            .collect();
+        summary.push_str(&format!("**Results:** {}", results.join(", ")));
+    }
+
+    summary.trim_end().to_string()
+}
+
+/// Range of the token under the cursor: the key when hovering it, a value on
+/// a single line, or the word of a multi-line value. `None` between tokens,
+/// such as in the indentation of a block.
+fn token_range(node: &Node, yaml_doc: &YamlDocument, position: Position) -> Option<Range> {
+    if let Some(key_range) = node.key_range.filter(|range| contains(*range, position)) {
+        return Some(key_range);
+    }
+    let value = node.value_range;
+    if !node.is_scalar() || !contains(value, position) {
+        return None;
+    }
+    if value.start.line == value.end.line {
+        return Some(value);
+    }
+
+    let line = yaml_doc.line_text(position.line)?;
+    let encoding = yaml_doc.encoding;
+    let offset = encoding.byte_offset(line, position.character);
+    let start = line[..offset]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let end = line[offset..]
+        .find(char::is_whitespace)
+        .map_or(line.len(), |i| offset + i);
+    if start == end {
+        return None;
+    }
+    let at = |byte| Position {
+        line: position.line,
+        character: encoding.column(line, byte),
+    };
+    Some(Range {
+        start: at(start),
+        end: at(end),
+    })
+}
+
+/// The resource defined by a fetched remote file, if it parses as one.
+fn remote_definition(url: &str, file: &RemoteFile) -> Option<ResourceDefinition> {
+    let RemoteFile::Fetched(content) = file else {
+        return None;
+    };
+    let uri = Url::parse(url).ok()?;
+    let yaml_doc = parser::parse_yaml(url, content).ok()?;
+    ResourceDefinition::from_document(&uri, &yaml_doc)
+}
+
+/// What became of a remote file that gives no resource.
+fn fetch_status(file: &RemoteFile) -> String {
+    match file {
+        RemoteFile::Fetching => "_Fetching the resource..._".to_string(),
+        RemoteFile::Fetched(_) => "_The fetched file defines no resource._".to_string(),
+        RemoteFile::Failed(message) => format!("_Failed to fetch the resource: {}_", message),
+    }
+}
+
+/// The value of the param `name` of a resolver, if a string.
+fn resolver_param<'a>(params: &[ParamValue<'a>], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|param| param.name.is_some_and(|n| n.value == name))?
+        .value?
+        .as_scalar()
+}
+
+/// Render a Markdown summary of a task result: its type, properties and description.
+fn result_summary(task: &str, result: &ResultSpec) -> String {
+    let name = result.name.map(|n| n.value).unwrap_or("unnamed");
+    let mut summary = format!("# Result `{}` of task `{}`\n\n", name, task);
+
+    if let Some(description) = result.description {
+        summary.push_str(description.value.trim());
+        summary.push_str("\n\n");
+    }
+    summary.push_str(&format!("**Type:** `{}`", result.type_or_default()));
+    let properties = result.property_names();
+    if !properties.is_empty() {
+        let properties: Vec<String> = properties.iter().map(|p| format!("`{}`", p)).collect();
+        summary.push_str(&format!("  \n**Properties:** {}", properties.join(", ")));
+    }
+
+    summary
+}
+
+/// Render a Markdown summary of a param: its description, type and default.
+fn param_summary(name: &str, param: &ParamSpec) -> String {
+    let mut summary = format!("# Param `{}`\n\n", name);
+
+    if let Some(description) = param.description {
+        summary.push_str(description.value.trim());
+        summary.push_str("\n\n");
+    }
+    let param_type = param.param_type.map(|t| t.value).unwrap_or("string");
+    summary.push_str(&format!("**Type:** `{}`", param_type));
+    match param.default {
+        Some(default) => summary.push_str(&format!("  \n**Default:** `{}`", to_flow_yaml(default))),
+        None => summary.push_str("  \n**Default:** none"),
+    }
+
+    summary
+}
+
+/// How many levels of resources passing a param are followed (Task ← Pipeline ← PipelineRun
+/// is two), which also bounds Pipelines referencing themselves
+const MAX_RESOLUTION_DEPTH: usize = 3;
+
+/// Markdown list of the indexed resources passing a value for param `param` of
+/// resource `kind`/`name`, each followed (indented) by where a
+/// `$(params.<name>)` it passes is set in turn.
+fn resolution_chain(
+    index: &WorkspaceIndex,
+    kind: &str,
+    name: &str,
+    param: &str,
+    depth: usize,
+) -> Vec<String> {
+    if depth == MAX_RESOLUTION_DEPTH {
+        return Vec::new();
+    }
+    let mut references = index.find_references(kind, name);
+    let location_key = |r: &ResourceReference| {
+        (
+            r.uri.to_string(),
+            r.location.range.start.line,
+            r.location.range.start.character,
+        )
+    };
+    references.sort_by_key(location_key);
+
+    let mut lines = Vec::new();
+    for reference in references {
+        let mut referrer = format!(
+            "{} `{}`",
+            reference.referrer_kind,
+            reference.referrer_name.as_deref().unwrap_or("unnamed")
+        );
+        if let Some(task) = &reference.pipeline_task {
+            referrer.push_str(&format!(" (task `{}`)", task));
+        }
+
+        let Some(passed) = reference.params.iter().find(|p| p.name == param) else {
+            lines.push(format!(
+                "- {} does not set it; the default applies",
+                referrer
+            ));
+            continue;
+        };
+        let verb = if reference.pipeline_task.is_some() {
+            "passes"
+        } else {
+            "sets"
+        };
+        lines.push(format!("- {} {} `{}`", referrer, verb, passed.value));
+
+        // Follow the params of the referrer used in the value
+        let Some(referrer_name) = &reference.referrer_name else {
+            continue;
+        };
+        for expr in find_expressions(
+            &passed.value,
+            Position::default(),
+            PositionEncoding::default(),
+        ) {
+            if let Reference::Param { name } = expr.reference() {
+                for line in resolution_chain(
+                    index,
+                    &reference.referrer_kind,
+                    referrer_name,
+                    &name.name,
+                    depth + 1,
+                ) {
+                    match line.strip_prefix("- ") {
+                        Some(rest) => lines.push(format!("  - `{}`: {}", name.name, rest)),
+                        None => lines.push(format!("  {}", line)),
+                    }
+                }
+            }
         }
-        true
     }
+    lines
 }
 
 impl Default for HoverProvider {
@@ -0,0 +1,126 @@
+//! Files of Git repositories, for references using the `git` resolver.
+//!
+//! The resolver clones the repository; the server instead fetches the one
+//! file it names from the raw file endpoint of GitHub or GitLab. Other hosts
+//! are not supported.
+
+use serde::Deserialize;
+use std::sync::{Arc, RwLock};
+
+use super::remote::{RemoteFile, RemoteFiles};
+
+/// URL probed at startup to tell whether files can be fetched
+pub const PROBE_URL: &str = "https://raw.githubusercontent.com";
+
+/// Revision the `git` resolver checks out when none is set
+const DEFAULT_REVISION: &str = "main";
+
+/// Settings of the Git integration.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GitOptions {
+    /// Whether to fetch files at all
+    pub enabled: bool,
+}
+
+/// Client fetching files of Git repositories, shared so that clones see the
+/// same files.
+#[derive(Debug, Clone, Default)]
+pub struct GitClient {
+    options: Arc<RwLock<GitOptions>>,
+    files: RemoteFiles,
+}
+
+impl GitClient {
+    /// Create a client with the given settings.
+    pub fn new(options: GitOptions) -> Self {
+        let client = Self::default();
+        client.set_options(options);
+        client
+    }
+
+    /// Change the settings.
+    pub fn set_options(&self, options: GitOptions) {
+        *self.options.write().unwrap() = options;
+    }
+
+    /// The settings of the client
+    pub fn options(&self) -> GitOptions {
+        self.options.read().unwrap().clone()
+    }
+
+    /// The file at `path` of the repository at `url`, at `revision` or the
+    /// default branch. `None` when disabled or on an unsupported host.
+    pub fn file(&self, url: &str, revision: Option<&str>, path: &str) -> Option<RemoteFile> {
+        if !self.options.read().unwrap().enabled {
+            return None;
+        }
+        Some(self.files.get(&raw_file_url(url, revision, path)?))
+    }
+
+    /// The files fetched so far
+    pub fn files(&self) -> &RemoteFiles {
+        &self.files
+    }
+}
+
+/// URL of the raw content of a file of a GitHub or GitLab repository.
+pub fn raw_file_url(url: &str, revision: Option<&str>, path: &str) -> Option<String> {
+    let revision = revision.unwrap_or(DEFAULT_REVISION);
+    let path = path.trim_start_matches('/');
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let (host, repository) = rest.split_once('/')?;
+    let repository = repository.trim_end_matches('/').trim_end_matches(".git");
+    match host {
+        "github.com" => Some(format!(
+            "https://raw.githubusercontent.com/{}/{}/{}",
+            repository, revision, path
+        )),
+        "gitlab.com" => Some(format!(
+            "https://gitlab.com/{}/-/raw/{}/{}",
+            repository, revision, path
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_file_url() {
+        assert_eq!(
+            raw_file_url(
+                "https://github.com/tektoncd/catalog.git",
+                Some("v1.0"),
+                "task/git-clone/0.9/git-clone.yaml"
+            )
+            .as_deref(),
+            Some("https://raw.githubusercontent.com/tektoncd/catalog/v1.0/task/git-clone/0.9/git-clone.yaml")
+        );
+        assert_eq!(
+            raw_file_url("https://gitlab.com/org/repo", None, "/tasks/build.yaml").as_deref(),
+            Some("https://gitlab.com/org/repo/-/raw/main/tasks/build.yaml")
+        );
+        assert_eq!(
+            raw_file_url("https://git.example.com/org/repo", None, "task.yaml"),
+            None
+        );
+        assert_eq!(
+            raw_file_url("git@github.com:org/repo.git", None, "task.yaml"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_disabled_client_fetches_nothing() {
+        let client = GitClient::default();
+        assert_eq!(
+            client.file("https://github.com/org/repo", None, "task.yaml"),
+            None
+        );
+    }
+}
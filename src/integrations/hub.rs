@@ -0,0 +1,494 @@
+//! Tekton Hub catalog.
+//!
+//! When enabled, the catalog of the Hub is fetched in the background and
+//! cached on disk, so that completion of `taskRef`s using the `hub` resolver
+//! can offer the tasks and versions it holds. Completion never waits for the
+//! network: it uses the catalog fetched so far, if any. Hover fetches the YAML
+//! of the resource a reference names the same way.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::remote::{fetch, RemoteFile, RemoteFiles};
+use crate::error::HubError;
+
+/// API of the public Tekton Hub
+pub const DEFAULT_HUB_URL: &str = "https://api.hub.tekton.dev";
+
+/// Name of the catalog cache file, in the cache directory
+const CACHE_FILE_NAME: &str = "hub-catalog.json";
+
+/// How long to wait before fetching again after a failure
+const RETRY_DELAY: Duration = Duration::from_secs(300);
+
+/// Settings of the Hub integration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HubOptions {
+    /// Whether to fetch the catalog at all
+    pub enabled: bool,
+    /// Base URL of the Hub API
+    pub url: String,
+    /// How long, in seconds, the cached catalog is used before fetching it again
+    pub cache_ttl: u64,
+    /// Directory of the cache file; the user cache directory when unset
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl Default for HubOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: DEFAULT_HUB_URL.to_string(),
+            cache_ttl: 24 * 60 * 60,
+            cache_dir: None,
+        }
+    }
+}
+
+impl HubOptions {
+    /// Path of the cache file, if there is a cache directory.
+    fn cache_file(&self) -> Option<PathBuf> {
+        let dir = self.cache_dir.clone().or_else(default_cache_dir)?;
+        Some(dir.join(CACHE_FILE_NAME))
+    }
+}
+
+/// A resource of the Hub catalog.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HubResource {
+    /// Identifier of the resource in the Hub; missing in older cache files
+    #[serde(default)]
+    pub id: Option<u64>,
+    pub name: String,
+    /// Name of the catalog holding it, such as `tekton`
+    pub catalog: String,
+    /// `Task` or `Pipeline`
+    pub kind: String,
+    pub latest_version: String,
+    pub description: Option<String>,
+}
+
+/// The catalog as written to the cache file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedCatalog {
+    /// URL of the Hub it was fetched from
+    url: String,
+    /// When it was fetched, in seconds since the Unix epoch
+    fetched_at: u64,
+    resources: Vec<HubResource>,
+}
+
+impl CachedCatalog {
+    /// Whether the catalog can still be used for `options`.
+    fn is_fresh(&self, options: &HubOptions) -> bool {
+        self.url == options.url && now().saturating_sub(self.fetched_at) < options.cache_ttl
+    }
+}
+
+/// Client of the Hub, shared so that clones see the same catalog.
+#[derive(Debug, Clone, Default)]
+pub struct HubClient {
+    options: Arc<RwLock<HubOptions>>,
+    /// The catalog fetched or loaded from the cache so far
+    catalog: Arc<RwLock<Option<CachedCatalog>>>,
+    /// Whether a fetch is in progress
+    fetching: Arc<AtomicBool>,
+    /// When the last fetch failed
+    failed_at: Arc<RwLock<Option<Instant>>>,
+    /// Whether the cache file was read with the current settings
+    cache_read: Arc<AtomicBool>,
+    /// YAML of the resources fetched so far
+    files: RemoteFiles,
+}
+
+impl HubClient {
+    /// Create a client with the given settings.
+    pub fn new(options: HubOptions) -> Self {
+        let client = Self::default();
+        client.set_options(options);
+        client
+    }
+
+    /// Change the settings, dropping the catalog fetched with the previous ones.
+    pub fn set_options(&self, options: HubOptions) {
+        let mut current = self.options.write().unwrap();
+        if *current == options {
+            return;
+        }
+        *current = options;
+        *self.catalog.write().unwrap() = None;
+        *self.failed_at.write().unwrap() = None;
+        self.cache_read.store(false, Ordering::Release);
+    }
+
+    /// The settings of the client
+    pub fn options(&self) -> HubOptions {
+        self.options.read().unwrap().clone()
+    }
+
+    /// The resources of the given kind (`task` or `pipeline`, in any case) in
+    /// the catalog. Empty when disabled or until the catalog is fetched; a
+    /// missing or stale catalog is fetched in the background.
+    pub fn resources(&self, kind: &str) -> Vec<HubResource> {
+        let options = self.options();
+        if !options.enabled {
+            return Vec::new();
+        }
+
+        // The cache file is read once, even when missing or unreadable
+        if !self.cache_read.swap(true, Ordering::AcqRel) {
+            let cached = options.cache_file().and_then(|path| read_cache(&path));
+            let mut catalog = self.catalog.write().unwrap();
+            if catalog.is_none() {
+                *catalog = cached;
+            }
+        }
+        let catalog = self.catalog.read().unwrap();
+        if !catalog.as_ref().is_some_and(|c| c.is_fresh(&options)) {
+            self.fetch_in_background(options);
+        }
+        catalog
+            .iter()
+            .flat_map(|catalog| &catalog.resources)
+            .filter(|resource| resource.kind.eq_ignore_ascii_case(kind))
+            .cloned()
+            .collect()
+    }
+
+    /// The versions of `resource`, newest first. Only its latest version until
+    /// the list of its versions, fetched in the background on first use,
+    /// arrives.
+    pub fn versions(&self, resource: &HubResource) -> Vec<String> {
+        let options = self.options();
+        let fetched = match resource.id {
+            Some(id) if options.enabled => {
+                match self.files.get(&resource_versions_url(&options.url, id)) {
+                    RemoteFile::Fetched(json) => parse_versions(&json)
+                        .inspect_err(|e| tracing::warn!("{}", e))
+                        .ok(),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        fetched.unwrap_or_else(|| vec![resource.latest_version.clone()])
+    }
+
+    /// The YAML of `resource` at `version`, fetched in the background on first
+    /// use. `None` when disabled.
+    pub fn resource_file(&self, resource: &HubResource, version: &str) -> Option<RemoteFile> {
+        let options = self.options();
+        if !options.enabled {
+            return None;
+        }
+        Some(
+            self.files
+                .get(&resource_yaml_url(&options.url, resource, version)),
+        )
+    }
+
+    /// The YAML of the resources fetched so far
+    pub fn files(&self) -> &RemoteFiles {
+        &self.files
+    }
+
+    /// Use `resources` as the catalog just fetched, and write it to the cache.
+    pub fn set_catalog(&self, resources: Vec<HubResource>) {
+        let options = self.options();
+        let catalog = CachedCatalog {
+            url: options.url.clone(),
+            fetched_at: now(),
+            resources,
+        };
+        if let Some(path) = options.cache_file() {
+            if let Err(e) = write_cache(&path, &catalog) {
+                tracing::warn!("Failed to write {}: {}", path.display(), e);
+            }
+        }
+        *self.catalog.write().unwrap() = Some(catalog);
+    }
+
+    /// Fetch the catalog on another thread, unless a fetch is in progress or
+    /// the last one failed recently.
+    fn fetch_in_background(&self, options: HubOptions) {
+        let failed_recently = self
+            .failed_at
+            .read()
+            .unwrap()
+            .is_some_and(|at| at.elapsed() < RETRY_DELAY);
+        if failed_recently || self.fetching.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let client = self.clone();
+        std::thread::spawn(move || {
+            match fetch_catalog(&options.url) {
+                // The settings may have changed while fetching
+                Ok(resources) if client.options() == options => client.set_catalog(resources),
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("{}", e);
+                    *client.failed_at.write().unwrap() = Some(Instant::now());
+                }
+            }
+            client.fetching.store(false, Ordering::Release);
+        });
+    }
+}
+
+/// Fetch the catalog of the Hub at `url`.
+pub fn fetch_catalog(url: &str) -> Result<Vec<HubResource>, HubError> {
+    let endpoint = format!("{}/v1/resources", url.trim_end_matches('/'));
+    let body = fetch(&endpoint).map_err(|source| HubError::Fetch {
+        url: endpoint.clone(),
+        source,
+    })?;
+    parse_catalog(&body)
+}
+
+/// URL of the YAML of `resource` at `version`, in the Hub at `url`.
+pub fn resource_yaml_url(url: &str, resource: &HubResource, version: &str) -> String {
+    format!(
+        "{}/v1/resource/{}/{}/{}/{}/yaml",
+        url.trim_end_matches('/'),
+        resource.catalog,
+        resource.kind.to_lowercase(),
+        resource.name,
+        version
+    )
+}
+
+/// URL of the list of versions of the resource with the given `id`, in the Hub
+/// at `url`.
+pub fn resource_versions_url(url: &str, id: u64) -> String {
+    format!("{}/v1/resource/{}/versions", url.trim_end_matches('/'), id)
+}
+
+/// Parse the answer of the Hub's `/v1/resource/{id}/versions` endpoint into
+/// the versions it lists, newest first.
+pub fn parse_versions(json: &str) -> Result<Vec<String>, HubError> {
+    #[derive(Deserialize)]
+    struct Answer {
+        data: Versions,
+    }
+    #[derive(Deserialize)]
+    struct Versions {
+        versions: Vec<Version>,
+    }
+    #[derive(Deserialize)]
+    struct Version {
+        version: String,
+    }
+
+    let answer: Answer = serde_json::from_str(json)?;
+    let mut versions: Vec<String> = answer
+        .data
+        .versions
+        .into_iter()
+        .map(|version| version.version)
+        .collect();
+    versions.sort_by_cached_key(|version| std::cmp::Reverse(version_key(version)));
+    Ok(versions)
+}
+
+/// Sort key of a version such as `0.10`: its numeric components, so that
+/// `0.10` comes after `0.9`.
+fn version_key(version: &str) -> Vec<Option<u64>> {
+    version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Parse the answer of the Hub's `/v1/resources` endpoint.
+pub fn parse_catalog(json: &str) -> Result<Vec<HubResource>, HubError> {
+    #[derive(Deserialize)]
+    struct Answer {
+        data: Vec<Resource>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Resource {
+        id: Option<u64>,
+        name: String,
+        kind: String,
+        catalog: Catalog,
+        latest_version: Version,
+    }
+    #[derive(Deserialize)]
+    struct Catalog {
+        name: String,
+    }
+    #[derive(Deserialize)]
+    struct Version {
+        version: String,
+        description: Option<String>,
+    }
+
+    let answer: Answer = serde_json::from_str(json)?;
+    Ok(answer
+        .data
+        .into_iter()
+        .map(|resource| HubResource {
+            id: resource.id,
+            name: resource.name,
+            catalog: resource.catalog.name,
+            kind: resource.kind,
+            latest_version: resource.latest_version.version,
+            description: resource.latest_version.description,
+        })
+        .collect())
+}
+
+/// `$XDG_CACHE_HOME/tekton-lsp`, or `~/.cache/tekton-lsp`.
+fn default_cache_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("tekton-lsp"))
+}
+
+/// Read the cache file; `None` when missing or unreadable.
+fn read_cache(path: &Path) -> Option<CachedCatalog> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_cache(path: &Path, catalog: &CachedCatalog) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string(catalog)?)
+}
+
+/// Seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_catalog() {
+        let json = r#"{"data": [
+            {"id": 1, "name": "git-clone", "kind": "Task",
+             "catalog": {"id": 1, "name": "tekton", "type": "community"},
+             "latestVersion": {"id": 9, "version": "0.9", "description": "Clone a repository"},
+             "rating": 4.5},
+            {"id": 2, "name": "buildpacks", "kind": "Pipeline",
+             "catalog": {"id": 1, "name": "tekton"},
+             "latestVersion": {"version": "0.2"}}
+        ]}"#;
+        let resources = parse_catalog(json).unwrap();
+        assert_eq!(
+            resources,
+            vec![
+                HubResource {
+                    id: Some(1),
+                    name: "git-clone".to_string(),
+                    catalog: "tekton".to_string(),
+                    kind: "Task".to_string(),
+                    latest_version: "0.9".to_string(),
+                    description: Some("Clone a repository".to_string()),
+                },
+                HubResource {
+                    id: Some(2),
+                    name: "buildpacks".to_string(),
+                    catalog: "tekton".to_string(),
+                    kind: "Pipeline".to_string(),
+                    latest_version: "0.2".to_string(),
+                    description: None,
+                },
+            ]
+        );
+        assert!(matches!(parse_catalog("[]"), Err(HubError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_catalog_cache() {
+        let dir = std::env::temp_dir().join(format!("tekton-lsp-hub-{}", std::process::id()));
+        let options = HubOptions {
+            enabled: true,
+            cache_dir: Some(dir.clone()),
+            ..HubOptions::default()
+        };
+        let resource = HubResource {
+            id: None,
+            name: "git-clone".to_string(),
+            catalog: "tekton".to_string(),
+            kind: "Task".to_string(),
+            latest_version: "0.9".to_string(),
+            description: None,
+        };
+        HubClient::new(options.clone()).set_catalog(vec![resource.clone()]);
+
+        // Another client reads the catalog from the cache file
+        let client = HubClient::new(options.clone());
+        assert_eq!(client.resources("task"), vec![resource]);
+        assert!(client.resources("pipeline").is_empty());
+
+        let catalog = read_cache(&dir.join(CACHE_FILE_NAME)).unwrap();
+        assert!(catalog.is_fresh(&options));
+        let expired = HubOptions {
+            cache_ttl: 0,
+            ..options.clone()
+        };
+        assert!(!catalog.is_fresh(&expired));
+        let other_hub = HubOptions {
+            url: "https://hub.example.com".to_string(),
+            ..options
+        };
+        assert!(!catalog.is_fresh(&other_hub));
+
+        // Nothing when disabled
+        assert!(HubClient::default().resources("task").is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_versions() {
+        let json = r#"{"data": {"id": 1, "name": "git-clone",
+            "versions": [
+                {"id": 7, "version": "0.9", "displayName": "git clone"},
+                {"id": 8, "version": "0.10"},
+                {"id": 3, "version": "0.2"}
+            ]}}"#;
+        assert_eq!(parse_versions(json).unwrap(), vec!["0.10", "0.9", "0.2"]);
+        assert!(matches!(parse_versions("{}"), Err(HubError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_versions() {
+        let options = HubOptions {
+            enabled: true,
+            url: "https://hub.example.com".to_string(),
+            ..HubOptions::default()
+        };
+        let client = HubClient::new(options.clone());
+        let mut resource = HubResource {
+            id: None,
+            name: "git-clone".to_string(),
+            catalog: "tekton".to_string(),
+            kind: "Task".to_string(),
+            latest_version: "0.9".to_string(),
+            description: None,
+        };
+        // Only the latest version without an id to fetch the others with
+        assert_eq!(client.versions(&resource), vec!["0.9"]);
+
+        resource.id = Some(1);
+        let json = r#"{"data": {"versions": [{"version": "0.8"}, {"version": "0.9"}]}}"#;
+        client.files().set(
+            &resource_versions_url(&options.url, 1),
+            RemoteFile::Fetched(json.to_string()),
+        );
+        assert_eq!(client.versions(&resource), vec!["0.9", "0.8"]);
+    }
+}
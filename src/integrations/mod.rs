@@ -0,0 +1,13 @@
+//! Integrations with services outside the workspace.
+//!
+//! All of them are optional and off by default: the server works offline.
+
+pub mod git;
+pub mod hub;
+pub mod registry;
+pub mod remote;
+
+pub use git::{GitClient, GitOptions};
+pub use hub::{HubClient, HubOptions, HubResource};
+pub use registry::{ImageReference, RegistryClient, RegistryOptions};
+pub use remote::{RemoteFile, RemoteFiles};
@@ -0,0 +1,354 @@
+//! Container registries, for the images of steps.
+//!
+//! Hover on an image shows where it comes from. When enabled, the digest its
+//! tag currently points to is asked from the registry in the background, with
+//! an anonymous token where the registry wants one: only public images resolve.
+
+use crate::error::{RegistryError, RemoteError};
+use serde::Deserialize;
+use std::sync::{Arc, RwLock};
+
+use super::remote::{RemoteFile, RemoteFiles, REQUEST_TIMEOUT};
+
+/// Registry of images whose name has no registry host
+const DEFAULT_REGISTRY: &str = "docker.io";
+
+/// URL probed at startup to tell whether registries can be reached: the API
+/// of the default registry
+pub const PROBE_URL: &str = "https://registry-1.docker.io/v2/";
+
+/// Tag of images whose name has no tag nor digest
+const DEFAULT_TAG: &str = "latest";
+
+/// Manifest types the digest is asked for, multi-platform indexes first so that
+/// the digest is the one `image@digest` pulls on any platform
+const MANIFEST_TYPES: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+/// Settings of the registry integration.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RegistryOptions {
+    /// Whether to ask registries for digests at all
+    pub enabled: bool,
+}
+
+/// A parsed image reference, such as `ghcr.io/org/app:1.2@sha256:...`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageReference {
+    /// Host of the registry, `docker.io` when the name has none
+    pub registry: String,
+    /// Path of the repository, with the `library/` of official Docker Hub images
+    pub repository: String,
+    pub tag: Option<String>,
+    pub digest: Option<String>,
+}
+
+impl ImageReference {
+    /// Parse an image reference. `None` when it is empty or malformed, or uses
+    /// a `$(...)` substitution that is only known at run time.
+    pub fn parse(image: &str) -> Option<Self> {
+        let image = image.trim();
+        if image.is_empty() || image.contains("$(") || image.contains(char::is_whitespace) {
+            return None;
+        }
+        let (name, digest) = match image.split_once('@') {
+            Some((name, digest)) if digest.contains(':') => (name, Some(digest.to_string())),
+            Some(_) => return None,
+            None => (image, None),
+        };
+        let last_slash = name.rfind('/').map_or(0, |i| i + 1);
+        let (name, tag) = match name[last_slash..].rfind(':') {
+            Some(i) => (
+                &name[..last_slash + i],
+                Some(name[last_slash + i + 1..].to_string()),
+            ),
+            None => (name, None),
+        };
+        if tag.as_deref() == Some("") {
+            return None;
+        }
+
+        // The first component is a host when it looks like one
+        let (registry, repository) = match name.split_once('/') {
+            Some((host, rest)) if host.contains(['.', ':']) || host == "localhost" => {
+                (host.to_string(), rest.to_string())
+            }
+            _ => (DEFAULT_REGISTRY.to_string(), name.to_string()),
+        };
+        let registry = match registry.as_str() {
+            "index.docker.io" | "registry-1.docker.io" => DEFAULT_REGISTRY.to_string(),
+            _ => registry,
+        };
+        if repository.is_empty() || repository.split('/').any(str::is_empty) {
+            return None;
+        }
+        let repository = if registry == DEFAULT_REGISTRY && !repository.contains('/') {
+            format!("library/{}", repository)
+        } else {
+            repository
+        };
+        Some(Self {
+            registry,
+            repository,
+            tag,
+            digest,
+        })
+    }
+
+    /// The tag the image is pulled by: its tag, or `latest` without one.
+    pub fn tag_or_default(&self) -> &str {
+        self.tag.as_deref().unwrap_or(DEFAULT_TAG)
+    }
+
+    /// Web page of the repository on its registry.
+    pub fn web_url(&self) -> String {
+        match self.registry.as_str() {
+            DEFAULT_REGISTRY => match self.repository.strip_prefix("library/") {
+                Some(name) => format!("https://hub.docker.com/_/{}", name),
+                None => format!("https://hub.docker.com/r/{}", self.repository),
+            },
+            "quay.io" => format!("https://quay.io/repository/{}", self.repository),
+            host => format!("https://{}/{}", host, self.repository),
+        }
+    }
+
+    /// URL of the manifest of the tag, on the registry API.
+    pub fn manifest_url(&self) -> String {
+        let host = match self.registry.as_str() {
+            DEFAULT_REGISTRY => "registry-1.docker.io",
+            host => host,
+        };
+        format!(
+            "https://{}/v2/{}/manifests/{}",
+            host,
+            self.repository,
+            self.tag_or_default()
+        )
+    }
+}
+
+/// Client asking registries for the digests of tags, shared so that clones
+/// see the same digests.
+#[derive(Debug, Clone, Default)]
+pub struct RegistryClient {
+    options: Arc<RwLock<RegistryOptions>>,
+    /// Digests by manifest URL
+    digests: RemoteFiles,
+}
+
+impl RegistryClient {
+    /// Create a client with the given settings.
+    pub fn new(options: RegistryOptions) -> Self {
+        let client = Self::default();
+        client.set_options(options);
+        client
+    }
+
+    /// Change the settings.
+    pub fn set_options(&self, options: RegistryOptions) {
+        *self.options.write().unwrap() = options;
+    }
+
+    /// The settings of the client
+    pub fn options(&self) -> RegistryOptions {
+        self.options.read().unwrap().clone()
+    }
+
+    /// The digest the tag of `image` points to, as a fetched file holding
+    /// it. `None` when disabled.
+    pub fn digest(&self, image: &ImageReference) -> Option<RemoteFile> {
+        if !self.options.read().unwrap().enabled {
+            return None;
+        }
+        Some(self.digests.get_with(&image.manifest_url(), fetch_digest))
+    }
+
+    /// The digests fetched so far, by manifest URL
+    pub fn digests(&self) -> &RemoteFiles {
+        &self.digests
+    }
+}
+
+/// Ask the registry for the digest of the manifest at `url`, with an
+/// anonymous token when it wants one.
+fn fetch_digest(url: &str) -> Result<String, RegistryError> {
+    let head = |token: Option<&str>| {
+        let request = ureq::head(url)
+            .timeout(REQUEST_TIMEOUT)
+            .set("Accept", MANIFEST_TYPES);
+        match token {
+            Some(token) => request.set("Authorization", &format!("Bearer {}", token)),
+            None => request,
+        }
+        .call()
+        .map_err(Box::new)
+    };
+    let response = match head(None).map_err(|e| *e) {
+        Ok(response) => response,
+        Err(ureq::Error::Status(401, response)) => {
+            let url = response
+                .header("www-authenticate")
+                .and_then(token_url)
+                .ok_or(RegistryError::Unauthorized)?;
+            let token = fetch_token(&url)?;
+            head(Some(&token)).map_err(RemoteError::Http)?
+        }
+        Err(e) => return Err(RemoteError::from(e).into()),
+    };
+    response
+        .header("docker-content-digest")
+        .map(str::to_string)
+        .ok_or(RegistryError::NoDigest)
+}
+
+/// Fetch an anonymous token from the token endpoint at `url`.
+fn fetch_token(url: &str) -> Result<String, RegistryError> {
+    #[derive(Deserialize)]
+    struct TokenResponse {
+        token: Option<String>,
+        access_token: Option<String>,
+    }
+    let body = ureq::get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .call()
+        .map_err(RemoteError::from)?
+        .into_string()
+        .map_err(RemoteError::from)?;
+    let response: TokenResponse = serde_json::from_str(&body)?;
+    response
+        .token
+        .or(response.access_token)
+        .ok_or(RegistryError::NoToken)
+}
+
+/// URL of the token endpoint of a `Bearer realm="...",service="...",scope="..."`
+/// challenge, asking for its service and scope.
+fn token_url(challenge: &str) -> Option<String> {
+    let params = challenge.strip_prefix("Bearer ")?;
+    let mut realm = None;
+    let mut query = Vec::new();
+    for param in params.split(',') {
+        let (name, value) = param.trim().split_once('=')?;
+        let value = value.trim_matches('"');
+        match name {
+            "realm" => realm = Some(value),
+            "service" | "scope" => query.push(format!("{}={}", name, value)),
+            _ => {}
+        }
+    }
+    let realm = realm?;
+    if query.is_empty() {
+        Some(realm.to_string())
+    } else {
+        Some(format!("{}?{}", realm, query.join("&")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(
+        registry: &str,
+        repository: &str,
+        tag: Option<&str>,
+        digest: Option<&str>,
+    ) -> ImageReference {
+        ImageReference {
+            registry: registry.to_string(),
+            repository: repository.to_string(),
+            tag: tag.map(str::to_string),
+            digest: digest.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_parse_image_reference() {
+        assert_eq!(
+            ImageReference::parse("golang"),
+            Some(image("docker.io", "library/golang", None, None))
+        );
+        assert_eq!(
+            ImageReference::parse("bitnami/kubectl:1.29"),
+            Some(image("docker.io", "bitnami/kubectl", Some("1.29"), None))
+        );
+        assert_eq!(
+            ImageReference::parse("ghcr.io/org/app:1.2@sha256:abc"),
+            Some(image("ghcr.io", "org/app", Some("1.2"), Some("sha256:abc")))
+        );
+        assert_eq!(
+            ImageReference::parse("localhost:5000/app"),
+            Some(image("localhost:5000", "app", None, None))
+        );
+        assert_eq!(
+            ImageReference::parse("index.docker.io/alpine@sha256:abc"),
+            Some(image(
+                "docker.io",
+                "library/alpine",
+                None,
+                Some("sha256:abc")
+            ))
+        );
+        for invalid in ["", "$(params.image)", "app:", "app@latest", "org//app"] {
+            assert_eq!(ImageReference::parse(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_image_urls() {
+        let golang = ImageReference::parse("golang:1.22").unwrap();
+        assert_eq!(golang.web_url(), "https://hub.docker.com/_/golang");
+        assert_eq!(
+            golang.manifest_url(),
+            "https://registry-1.docker.io/v2/library/golang/manifests/1.22"
+        );
+        let kubectl = ImageReference::parse("bitnami/kubectl").unwrap();
+        assert_eq!(
+            kubectl.web_url(),
+            "https://hub.docker.com/r/bitnami/kubectl"
+        );
+        assert_eq!(
+            kubectl.manifest_url(),
+            "https://registry-1.docker.io/v2/bitnami/kubectl/manifests/latest"
+        );
+        let quay = ImageReference::parse("quay.io/org/app:v1").unwrap();
+        assert_eq!(quay.web_url(), "https://quay.io/repository/org/app");
+        assert_eq!(
+            quay.manifest_url(),
+            "https://quay.io/v2/org/app/manifests/v1"
+        );
+    }
+
+    #[test]
+    fn test_token_url() {
+        assert_eq!(
+            token_url(
+                r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/golang:pull""#
+            )
+            .as_deref(),
+            Some("https://auth.docker.io/token?service=registry.docker.io&scope=repository:library/golang:pull")
+        );
+        assert_eq!(token_url(r#"Basic realm="registry""#), None);
+    }
+
+    #[test]
+    fn test_disabled_client_fetches_nothing() {
+        let client = RegistryClient::default();
+        let golang = ImageReference::parse("golang").unwrap();
+        assert_eq!(client.digest(&golang), None);
+
+        // Enabled, digests recorded so far are returned
+        client.set_options(RegistryOptions { enabled: true });
+        client.digests().set(
+            &golang.manifest_url(),
+            RemoteFile::Fetched("sha256:abc".to_string()),
+        );
+        assert_eq!(
+            client.digest(&golang),
+            Some(RemoteFile::Fetched("sha256:abc".to_string()))
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Files fetched from the sources of remote resolvers.
+//!
+//! Hover on the `resolver` of a `taskRef` or `pipelineRef` shows the resource
+//! the resolver would fetch. Its file is fetched in the background and kept in
+//! memory for the session: hover never waits for the network, it tells that
+//! the file is being fetched until it is.
+
+use crate::error::RemoteError;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// How long a request for a file may take
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a reachability probe may take
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait before fetching a file again after a failure
+const RETRY_DELAY: Duration = Duration::from_secs(300);
+
+/// A remote file, as far as it was fetched.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteFile {
+    /// The request is in progress
+    Fetching,
+    /// The content of the file
+    Fetched(String),
+    /// Why the request failed
+    Failed(String),
+}
+
+/// Files fetched so far, by URL, shared so that clones see the same files.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteFiles {
+    files: Arc<RwLock<HashMap<String, (RemoteFile, Instant)>>>,
+}
+
+impl RemoteFiles {
+    /// The file at `url`, fetched in the background on first use, or again
+    /// when the last request failed a while ago.
+    pub fn get(&self, url: &str) -> RemoteFile {
+        self.get_with(url, fetch)
+    }
+
+    /// Like [`get`](Self::get), fetching with `fetch` for services that need
+    /// more than a plain GET of the URL.
+    pub fn get_with<E: Display + 'static>(
+        &self,
+        url: &str,
+        fetch: fn(&str) -> Result<String, E>,
+    ) -> RemoteFile {
+        let mut files = self.files.write().unwrap();
+        match files.get(url) {
+            Some((RemoteFile::Failed(_), at)) if at.elapsed() >= RETRY_DELAY => {}
+            Some((file, _)) => return file.clone(),
+            None => {}
+        }
+        files.insert(url.to_string(), (RemoteFile::Fetching, Instant::now()));
+
+        let client = self.clone();
+        let url = url.to_string();
+        std::thread::spawn(move || {
+            let file = match fetch(&url) {
+                Ok(content) => RemoteFile::Fetched(content),
+                Err(error) => {
+                    tracing::warn!("Failed to fetch {}: {}", url, error);
+                    RemoteFile::Failed(error.to_string())
+                }
+            };
+            client.set(&url, file);
+        });
+        RemoteFile::Fetching
+    }
+
+    /// Record the file at `url`, as fetched.
+    pub fn set(&self, url: &str, file: RemoteFile) {
+        let mut files = self.files.write().unwrap();
+        files.insert(url.to_string(), (file, Instant::now()));
+    }
+}
+
+/// Check that the server at `url` can be reached. Any answer counts, even an
+/// error status: only failing to connect does not.
+pub fn probe(url: &str) -> Result<(), RemoteError> {
+    match ureq::head(url).timeout(PROBE_TIMEOUT).call() {
+        Ok(_) | Err(ureq::Error::Status(..)) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Fetch the body of the answer to a GET of `url`.
+pub(crate) fn fetch(url: &str) -> Result<String, RemoteError> {
+    let body = ureq::get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .call()?
+        .into_string()?;
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_files() {
+        let files = RemoteFiles::default();
+        let url = "https://example.com/task.yaml";
+        files.set(url, RemoteFile::Fetched("kind: Task".to_string()));
+
+        // Clones share the files
+        assert_eq!(
+            files.clone().get(url),
+            RemoteFile::Fetched("kind: Task".to_string())
+        );
+        files.set(url, RemoteFile::Failed("timeout".to_string()));
+        assert_eq!(files.get(url), RemoteFile::Failed("timeout".to_string()));
+    }
+    #[test]
+    fn test_probe() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        // A server answering with an error status is reachable
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\n\r\n")
+                .unwrap();
+        });
+        assert!(probe(&url).is_ok());
+        server.join().unwrap();
+
+        // Nothing listens once the listener is dropped
+        assert!(matches!(probe(&url), Err(RemoteError::Http(_))));
+    }
+}
@@ -3,11 +3,21 @@
 
 pub mod actions;
 pub mod cache;
-pub mod parser;
-pub mod validator;
 pub mod completion;
-pub mod hover;
-pub mod workspace;
+pub mod config;
 pub mod definition;
-pub mod symbols;
+pub mod error;
+pub mod folding;
 pub mod formatting;
+pub mod hierarchy;
+pub mod hover;
+pub mod integrations;
+pub mod model;
+pub mod pac;
+pub mod parser;
+pub mod references;
+pub mod rename;
+pub mod server;
+pub mod symbols;
+pub mod validator;
+pub mod workspace;
@@ -1,17 +1,12 @@
-mod actions;
-mod cache;
-mod completion;
-mod definition;
-mod formatting;
-mod hover;
-mod parser;
-mod server;
-mod symbols;
-mod validator;
-mod workspace;
-
-use clap::Parser;
-use server::Backend;
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use tekton_lsp::config::ProjectConfig;
+use tekton_lsp::hover::explain::explain;
+use tekton_lsp::server::{Backend, WORKSPACE_OVERVIEW_METHOD};
+use tekton_lsp::workspace::{WorkspaceAudit, WorkspaceIndex};
+use tower_lsp::lsp_types::Location;
 use tower_lsp::{LspService, Server};
 
 /// Tekton Language Server Protocol (LSP) implementation
@@ -26,6 +21,30 @@ struct Args {
     /// Enable verbose logging
     #[arg(short, long)]
     verbose: bool,
+
+    /// Run a standalone command instead of the language server
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Index a directory and list its resources, references and unresolved references
+    ///
+    /// Honors the exclude globs and schema paths of the directory's
+    /// `.tekton-lsp.yaml` (or `.toml`).
+    Index {
+        /// Print the listing as JSON
+        #[arg(long)]
+        json: bool,
+        /// Directory to index
+        dir: PathBuf,
+    },
+    /// Show the documentation of a field, e.g. `spec.tasks.runAfter` or `task.spec.steps`
+    Explain {
+        /// Dotted field path, optionally starting with the kind (pipeline, task)
+        path: String,
+    },
 }
 
 #[tokio::main]
@@ -43,15 +62,122 @@ async fn main() {
         .with_writer(std::io::stderr)
         .init();
 
-    tracing::info!("Starting Tekton LSP server (version {})", env!("CARGO_PKG_VERSION"));
+    match args.command {
+        Some(Command::Index { json, dir }) => std::process::exit(run_index(&dir, json)),
+        Some(Command::Explain { path }) => std::process::exit(run_explain(&path)),
+        None => {}
+    }
+
+    tracing::info!(
+        "Starting Tekton LSP server (version {})",
+        env!("CARGO_PKG_VERSION")
+    );
 
     // Create stdio transport
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
     // Create the LSP service
-    let (service, socket) = LspService::new(Backend::new);
+    let (service, socket) = LspService::build(Backend::new)
+        .custom_method(WORKSPACE_OVERVIEW_METHOD, Backend::workspace_overview)
+        .finish();
 
     // Run the server
     Server::new(stdin, stdout, socket).serve(service).await;
 }
+
+/// Run `tekton-lsp index`, returning the process exit code.
+fn run_index(dir: &std::path::Path, json: bool) -> i32 {
+    let index = WorkspaceIndex::new();
+    let config = match ProjectConfig::load(dir).and_then(|config| {
+        index.set_exclude(config.exclude(dir)?);
+        Ok(config)
+    }) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    let paths = std::iter::once(dir.to_path_buf()).chain(config.schema_paths(dir));
+    for path in paths {
+        if let Err(e) = index.index_path(&path) {
+            eprintln!("Failed to index {}: {}", path.display(), e);
+            return 1;
+        }
+    }
+    let audit = index.audit();
+
+    let mut out = std::io::stdout().lock();
+    let written = if json {
+        serde_json::to_writer_pretty(&mut out, &audit)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writeln!(out))
+    } else {
+        write_audit(&mut out, &audit)
+    };
+    // A closed pipe (`| head`) is not worth a panic
+    match written {
+        Ok(()) => 0,
+        Err(_) => 1,
+    }
+}
+
+/// Run `tekton-lsp explain`, returning the process exit code.
+fn run_explain(path: &str) -> i32 {
+    match explain(path) {
+        Ok(text) => match write!(std::io::stdout().lock(), "{}", text) {
+            Ok(()) => 0,
+            Err(_) => 1,
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Write the audit listing as text, one entry per line.
+fn write_audit(out: &mut impl Write, audit: &WorkspaceAudit) -> std::io::Result<()> {
+    writeln!(out, "Resources:")?;
+    for resource in &audit.resources {
+        writeln!(
+            out,
+            "  {} {}  {}",
+            resource.kind,
+            resource.name,
+            format_location(&resource.location)
+        )?;
+    }
+    for (heading, references) in [
+        ("References:", &audit.references),
+        ("Unresolved references:", &audit.unresolved),
+    ] {
+        writeln!(out, "{}", heading)?;
+        for reference in references {
+            writeln!(
+                out,
+                "  {} {}  {}",
+                reference.kind,
+                reference.name,
+                format_location(&reference.location)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Format a location as `path:line:column`, 1-based.
+fn format_location(location: &Location) -> String {
+    let path = location
+        .uri
+        .to_file_path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| location.uri.to_string());
+    format!(
+        "{}:{}:{}",
+        path,
+        location.range.start.line + 1,
+        location.range.start.character + 1
+    )
+}
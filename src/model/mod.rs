@@ -0,0 +1,314 @@
+//! Typed Tekton resource model.
+//!
+//! Converts a parsed [`YamlDocument`] into typed resources (`Pipeline`, `Task`,
+//! `PipelineRun`, `TaskRun`) whose fields carry the range they were read from, so
+//! validators and providers can work with named fields instead of chains of
+//! `node.get("spec").get("tasks")` lookups. The model borrows from the
+//! document and keeps a reference to the underlying [`Node`] of each
+//! resource for anything it does not cover.
+//!
+//! Conversion is lenient: missing or mistyped fields are simply absent from
+//! the model, since reporting them is the validator's job.
+
+mod pipeline;
+mod run;
+mod task;
+
+pub use pipeline::{Pipeline, PipelineTask, TaskRef, WhenExpression};
+pub use run::{PipelineRef, PipelineRun, PipelineTaskRunSpec, TaskRun};
+pub use task::{Step, Task, VolumeMount};
+
+use crate::parser::{Node, NodeValue, YamlDocument};
+use tower_lsp::lsp_types::Range;
+
+/// A value read from the document, with its source ranges.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spanned<T> {
+    pub value: T,
+    /// Range of the value
+    pub range: Range,
+    /// Range of the key, for values read from a mapping entry
+    pub key_range: Option<Range>,
+}
+
+impl<T> Spanned<T> {
+    /// Range of the key, falling back to the value range
+    pub fn key_or_range(&self) -> Range {
+        self.key_range.unwrap_or(self.range)
+    }
+}
+
+/// A typed Tekton resource.
+#[derive(Debug, Clone)]
+pub enum Resource<'a> {
+    Pipeline(Box<Pipeline<'a>>),
+    Task(Box<Task<'a>>),
+    PipelineRun(Box<PipelineRun<'a>>),
+    TaskRun(Box<TaskRun<'a>>),
+}
+
+impl<'a> Resource<'a> {
+    /// Build the typed model of a document, if it is a supported kind.
+    pub fn from_document(doc: &'a YamlDocument) -> Option<Self> {
+        match doc.kind.as_deref()? {
+            "Pipeline" => Some(Resource::Pipeline(Box::new(Pipeline::from_node(&doc.root)))),
+            "Task" | "ClusterTask" => Some(Resource::Task(Box::new(Task::from_node(&doc.root)))),
+            "PipelineRun" => Some(Resource::PipelineRun(Box::new(PipelineRun::from_node(
+                &doc.root,
+            )))),
+            "TaskRun" => Some(Resource::TaskRun(Box::new(TaskRun::from_node(&doc.root)))),
+            _ => None,
+        }
+    }
+
+    /// Metadata of the resource
+    pub fn metadata(&self) -> &Metadata<'a> {
+        match self {
+            Resource::Pipeline(p) => &p.metadata,
+            Resource::Task(t) => &t.metadata,
+            Resource::PipelineRun(r) => &r.metadata,
+            Resource::TaskRun(r) => &r.metadata,
+        }
+    }
+}
+
+/// `metadata` of a resource.
+#[derive(Debug, Clone, Default)]
+pub struct Metadata<'a> {
+    pub name: Option<Spanned<&'a str>>,
+    pub generate_name: Option<Spanned<&'a str>>,
+    pub namespace: Option<Spanned<&'a str>>,
+    /// The `metadata` entry itself
+    pub node: Option<&'a Node>,
+}
+
+impl<'a> Metadata<'a> {
+    /// Read the `metadata` of a document root.
+    pub fn from_root(root: &'a Node) -> Self {
+        let Some(node) = root.get("metadata") else {
+            return Self::default();
+        };
+        Self {
+            name: scalar(node, "name"),
+            generate_name: scalar(node, "generateName"),
+            namespace: scalar(node, "namespace"),
+            node: Some(node),
+        }
+    }
+}
+
+/// A param declaration (`spec.params[]` of a Task or Pipeline).
+#[derive(Debug, Clone)]
+pub struct ParamSpec<'a> {
+    pub name: Option<Spanned<&'a str>>,
+    /// `string` (the default), `array` or `object`
+    pub param_type: Option<Spanned<&'a str>>,
+    pub description: Option<Spanned<&'a str>>,
+    /// The `default` entry, which may be a scalar, sequence or mapping
+    pub default: Option<&'a Node>,
+    /// The `properties` entry of an object param
+    pub properties: Option<&'a Node>,
+    pub node: &'a Node,
+}
+
+impl<'a> ParamSpec<'a> {
+    fn from_node(node: &'a Node) -> Self {
+        Self {
+            name: scalar(node, "name"),
+            param_type: scalar(node, "type"),
+            description: scalar(node, "description"),
+            default: node.get("default"),
+            properties: node.get("properties"),
+            node,
+        }
+    }
+
+    /// The declared type, `string` when not set
+    pub fn type_or_default(&self) -> &'a str {
+        self.param_type.map_or("string", |t| t.value)
+    }
+}
+
+/// A param value passed to a task or run (`params[]` with `name`/`value`).
+#[derive(Debug, Clone)]
+pub struct ParamValue<'a> {
+    pub name: Option<Spanned<&'a str>>,
+    /// The `value` entry, which may be a scalar, sequence or mapping
+    pub value: Option<&'a Node>,
+    pub node: &'a Node,
+}
+
+impl<'a> ParamValue<'a> {
+    fn from_node(node: &'a Node) -> Self {
+        Self {
+            name: scalar(node, "name"),
+            value: node.get("value"),
+            node,
+        }
+    }
+}
+
+/// A result declaration (`spec.results[]`).
+#[derive(Debug, Clone)]
+pub struct ResultSpec<'a> {
+    pub name: Option<Spanned<&'a str>>,
+    /// `string` (the default), `array` or `object`
+    pub result_type: Option<Spanned<&'a str>>,
+    pub description: Option<Spanned<&'a str>>,
+    /// The `value` entry (Pipeline results and Task results fed from steps)
+    pub value: Option<&'a Node>,
+    /// The `properties` entry of an object result
+    pub properties: Option<&'a Node>,
+    pub node: &'a Node,
+}
+
+impl<'a> ResultSpec<'a> {
+    fn from_node(node: &'a Node) -> Self {
+        Self {
+            name: scalar(node, "name"),
+            result_type: scalar(node, "type"),
+            description: scalar(node, "description"),
+            value: node.get("value"),
+            properties: node.get("properties"),
+            node,
+        }
+    }
+
+    /// The declared type, `string` when not set
+    pub fn type_or_default(&self) -> &'a str {
+        self.result_type.map(|t| t.value).unwrap_or("string")
+    }
+
+    /// Names of the declared object properties
+    pub fn property_names(&self) -> Vec<&'a str> {
+        self.properties
+            .map(|p| p.entries().map(|(key, _)| key).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// A workspace declaration (`spec.workspaces[]` of a Task or Pipeline).
+#[derive(Debug, Clone)]
+pub struct WorkspaceDeclaration<'a> {
+    pub name: Option<Spanned<&'a str>>,
+    pub description: Option<Spanned<&'a str>>,
+    pub mount_path: Option<Spanned<&'a str>>,
+    pub read_only: Option<Spanned<&'a str>>,
+    pub optional: Option<Spanned<&'a str>>,
+    pub node: &'a Node,
+}
+
+impl<'a> WorkspaceDeclaration<'a> {
+    fn from_node(node: &'a Node) -> Self {
+        Self {
+            name: scalar(node, "name"),
+            description: scalar(node, "description"),
+            mount_path: scalar(node, "mountPath"),
+            read_only: scalar(node, "readOnly"),
+            optional: scalar(node, "optional"),
+            node,
+        }
+    }
+
+    /// Where the workspace is mounted: its `mountPath`, `/workspace/<name>` by default
+    pub fn effective_mount_path(&self) -> Option<String> {
+        match (self.mount_path, self.name) {
+            (Some(mount_path), _) => Some(mount_path.value.to_string()),
+            (None, Some(name)) => Some(default_workspace_mount_path(name.value)),
+            (None, None) => None,
+        }
+    }
+
+    /// Whether the workspace is declared `optional: true`
+    pub fn is_optional(&self) -> bool {
+        self.optional.is_some_and(|o| o.value == "true")
+    }
+}
+
+/// Default mount path of a Task workspace
+pub fn default_workspace_mount_path(name: &str) -> String {
+    format!("/workspace/{}", name)
+}
+
+/// A workspace binding (`workspaces[]` of a pipeline task or run).
+#[derive(Debug, Clone)]
+pub struct WorkspaceBinding<'a> {
+    pub name: Option<Spanned<&'a str>>,
+    /// The pipeline workspace bound to it (pipeline tasks only)
+    pub workspace: Option<Spanned<&'a str>>,
+    pub sub_path: Option<Spanned<&'a str>>,
+    pub node: &'a Node,
+}
+
+impl<'a> WorkspaceBinding<'a> {
+    fn from_node(node: &'a Node) -> Self {
+        Self {
+            name: scalar(node, "name"),
+            workspace: scalar(node, "workspace"),
+            sub_path: scalar(node, "subPath"),
+            node,
+        }
+    }
+}
+
+/// Read a scalar entry of a mapping.
+fn scalar<'a>(node: &'a Node, key: &str) -> Option<Spanned<&'a str>> {
+    let child = node.get(key)?;
+    Some(Spanned {
+        value: child.as_scalar()?,
+        range: child.value_range,
+        key_range: child.key_range,
+    })
+}
+
+/// Read a sequence of scalars, e.g. `runAfter` or `command`.
+fn scalar_list<'a>(node: &'a Node, key: &str) -> Option<Spanned<Vec<Spanned<&'a str>>>> {
+    let child = node.get(key)?;
+    let NodeValue::Sequence(items) = &child.value else {
+        return None;
+    };
+    let values = items
+        .iter()
+        .filter_map(|item| {
+            Some(Spanned {
+                value: item.as_scalar()?,
+                range: item.value_range,
+                key_range: None,
+            })
+        })
+        .collect();
+    Some(Spanned {
+        value: values,
+        range: child.value_range,
+        key_range: child.key_range,
+    })
+}
+
+/// Convert the items of a sequence entry, or nothing if it is absent or not a sequence.
+fn items<'a, T>(node: &'a Node, key: &str, convert: impl Fn(&'a Node) -> T) -> Vec<T> {
+    node.get(key)
+        .into_iter()
+        .flat_map(Node::items)
+        .map(convert)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_yaml;
+
+    #[test]
+    fn test_metadata_and_unsupported_kinds() {
+        let doc = parse_yaml("test.yaml", "kind: Task\nmetadata:\n  name: build\n").unwrap();
+        let resource = Resource::from_document(&doc).unwrap();
+
+        let name = resource.metadata().name.unwrap();
+        assert_eq!(name.value, "build");
+        assert_eq!((name.range.start.line, name.range.start.character), (2, 8));
+        assert_eq!(name.key_or_range().start.character, 2);
+
+        let config = parse_yaml("test.yaml", "kind: ConfigMap\n").unwrap();
+        assert!(Resource::from_document(&config).is_none());
+    }
+}
@@ -0,0 +1,230 @@
+//! Pipeline model.
+
+use super::task::Task;
+use super::{
+    items, scalar, scalar_list, Metadata, ParamSpec, ParamValue, ResultSpec, Spanned,
+    WorkspaceBinding, WorkspaceDeclaration,
+};
+use crate::parser::Node;
+
+/// A Pipeline.
+#[derive(Debug, Clone, Default)]
+pub struct Pipeline<'a> {
+    pub metadata: Metadata<'a>,
+    pub description: Option<Spanned<&'a str>>,
+    pub params: Vec<ParamSpec<'a>>,
+    pub results: Vec<ResultSpec<'a>>,
+    pub workspaces: Vec<WorkspaceDeclaration<'a>>,
+    pub tasks: Vec<PipelineTask<'a>>,
+    pub finally: Vec<PipelineTask<'a>>,
+    /// The `spec` node
+    pub spec: Option<&'a Node>,
+}
+
+impl<'a> Pipeline<'a> {
+    /// Build a Pipeline from a document root.
+    pub fn from_node(root: &'a Node) -> Self {
+        let mut pipeline = root.get("spec").map(Self::from_spec).unwrap_or_default();
+        pipeline.metadata = Metadata::from_root(root);
+        pipeline
+    }
+
+    /// Build a Pipeline from a spec node (`spec` or an embedded `pipelineSpec`).
+    pub fn from_spec(spec: &'a Node) -> Self {
+        Self {
+            metadata: Metadata::default(),
+            description: scalar(spec, "description"),
+            params: items(spec, "params", ParamSpec::from_node),
+            results: items(spec, "results", ResultSpec::from_node),
+            workspaces: items(spec, "workspaces", WorkspaceDeclaration::from_node),
+            tasks: items(spec, "tasks", PipelineTask::from_node),
+            finally: items(spec, "finally", PipelineTask::from_node),
+            spec: Some(spec),
+        }
+    }
+
+    /// All pipeline tasks, `tasks` first and then `finally`
+    pub fn all_tasks(&self) -> impl Iterator<Item = &PipelineTask<'a>> {
+        self.tasks.iter().chain(self.finally.iter())
+    }
+
+    /// Find a pipeline task (in `tasks` or `finally`) by name
+    pub fn task(&self, name: &str) -> Option<&PipelineTask<'a>> {
+        self.all_tasks()
+            .find(|t| t.name.is_some_and(|n| n.value == name))
+    }
+
+    /// Find a result declared by the inline `taskSpec` of a pipeline task
+    pub fn task_result(&self, task: &str, result: &str) -> Option<&ResultSpec<'a>> {
+        self.task(task)?
+            .task_spec
+            .as_ref()?
+            .results
+            .iter()
+            .find(|r| r.name.is_some_and(|n| n.value == result))
+    }
+}
+
+/// An entry of a Pipeline's `tasks` or `finally`.
+#[derive(Debug, Clone)]
+pub struct PipelineTask<'a> {
+    pub name: Option<Spanned<&'a str>>,
+    pub task_ref: Option<TaskRef<'a>>,
+    /// The embedded `taskSpec`
+    pub task_spec: Option<Task<'a>>,
+    pub run_after: Option<Spanned<Vec<Spanned<&'a str>>>>,
+    pub params: Vec<ParamValue<'a>>,
+    pub workspaces: Vec<WorkspaceBinding<'a>>,
+    pub when: Vec<WhenExpression<'a>>,
+    pub timeout: Option<Spanned<&'a str>>,
+    pub retries: Option<Spanned<&'a str>>,
+    /// The pipeline task's mapping node
+    pub node: &'a Node,
+}
+
+impl<'a> PipelineTask<'a> {
+    fn from_node(node: &'a Node) -> Self {
+        Self {
+            name: scalar(node, "name"),
+            task_ref: node.get("taskRef").map(TaskRef::from_node),
+            task_spec: node.get("taskSpec").map(Task::from_spec),
+            run_after: scalar_list(node, "runAfter"),
+            params: items(node, "params", ParamValue::from_node),
+            workspaces: items(node, "workspaces", WorkspaceBinding::from_node),
+            when: items(node, "when", WhenExpression::from_node),
+            timeout: scalar(node, "timeout"),
+            retries: scalar(node, "retries"),
+            node,
+        }
+    }
+
+    /// The task name, or `unnamed` for messages
+    pub fn display_name(&self) -> &'a str {
+        self.name.map(|n| n.value).unwrap_or("unnamed")
+    }
+}
+
+/// A pipeline task's `taskRef`.
+#[derive(Debug, Clone)]
+pub struct TaskRef<'a> {
+    pub name: Option<Spanned<&'a str>>,
+    pub kind: Option<Spanned<&'a str>>,
+    pub api_version: Option<Spanned<&'a str>>,
+    pub resolver: Option<Spanned<&'a str>>,
+    /// The OCI bundle of the removed v1beta1 `bundle` field
+    pub bundle: Option<Spanned<&'a str>>,
+    pub params: Vec<ParamValue<'a>>,
+    /// The `taskRef` entry
+    pub node: &'a Node,
+}
+
+impl<'a> TaskRef<'a> {
+    pub(super) fn from_node(node: &'a Node) -> Self {
+        Self {
+            name: scalar(node, "name"),
+            kind: scalar(node, "kind"),
+            api_version: scalar(node, "apiVersion"),
+            resolver: scalar(node, "resolver"),
+            bundle: scalar(node, "bundle"),
+            params: items(node, "params", ParamValue::from_node),
+            node,
+        }
+    }
+
+    /// The referenced kind, `Task` when not set
+    pub fn kind_or_default(&self) -> &'a str {
+        self.kind.map(|k| k.value).unwrap_or("Task")
+    }
+}
+
+/// An entry of a pipeline task's `when`.
+#[derive(Debug, Clone)]
+pub struct WhenExpression<'a> {
+    pub input: Option<Spanned<&'a str>>,
+    pub operator: Option<Spanned<&'a str>>,
+    pub values: Option<Spanned<Vec<Spanned<&'a str>>>>,
+    /// CEL expression form (`cel:`)
+    pub cel: Option<Spanned<&'a str>>,
+    pub node: &'a Node,
+}
+
+impl<'a> WhenExpression<'a> {
+    fn from_node(node: &'a Node) -> Self {
+        Self {
+            input: scalar(node, "input"),
+            operator: scalar(node, "operator"),
+            values: scalar_list(node, "values"),
+            cel: scalar(node, "cel"),
+            node,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_yaml;
+
+    #[test]
+    fn test_pipeline_model() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  workspaces:
+    - name: shared
+  tasks:
+    - name: build
+      taskRef:
+        name: golang-build
+        kind: ClusterTask
+      workspaces:
+        - name: source
+          workspace: shared
+    - name: test
+      runAfter: [build]
+      when:
+        - input: $(params.run-tests)
+          operator: in
+          values: ["true"]
+      taskSpec:
+        steps:
+          - image: golang
+  finally:
+    - name: notify
+      taskRef:
+        name: slack
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let pipeline = Pipeline::from_node(&doc.root);
+
+        assert_eq!(pipeline.metadata.name.unwrap().value, "ci");
+        assert_eq!(pipeline.workspaces[0].name.unwrap().value, "shared");
+        assert_eq!(pipeline.tasks.len(), 2);
+        assert_eq!(pipeline.finally.len(), 1);
+
+        let build = pipeline.task("build").unwrap();
+        let task_ref = build.task_ref.as_ref().unwrap();
+        assert_eq!(task_ref.name.unwrap().value, "golang-build");
+        assert_eq!(task_ref.kind_or_default(), "ClusterTask");
+        assert_eq!(build.workspaces[0].workspace.unwrap().value, "shared");
+
+        let test = pipeline.task("test").unwrap();
+        let run_after: Vec<&str> = test
+            .run_after
+            .as_ref()
+            .unwrap()
+            .value
+            .iter()
+            .map(|r| r.value)
+            .collect();
+        assert_eq!(run_after, vec!["build"]);
+        assert_eq!(test.when[0].operator.unwrap().value, "in");
+        assert_eq!(test.task_spec.as_ref().unwrap().steps.len(), 1);
+
+        assert!(pipeline.task("notify").is_some());
+        assert_eq!(pipeline.all_tasks().count(), 3);
+    }
+}
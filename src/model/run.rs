@@ -0,0 +1,178 @@
+//! PipelineRun and TaskRun model.
+
+use super::pipeline::{Pipeline, TaskRef};
+use super::task::Task;
+use super::{items, scalar, Metadata, ParamValue, Spanned, WorkspaceBinding};
+use crate::parser::Node;
+
+/// A PipelineRun.
+#[derive(Debug, Clone, Default)]
+pub struct PipelineRun<'a> {
+    pub metadata: Metadata<'a>,
+    pub pipeline_ref: Option<PipelineRef<'a>>,
+    /// The embedded `pipelineSpec`
+    pub pipeline_spec: Option<Pipeline<'a>>,
+    pub params: Vec<ParamValue<'a>>,
+    pub workspaces: Vec<WorkspaceBinding<'a>>,
+    pub task_run_specs: Vec<PipelineTaskRunSpec<'a>>,
+    /// The `spec` node
+    pub spec: Option<&'a Node>,
+}
+
+impl<'a> PipelineRun<'a> {
+    /// Build a PipelineRun from a document root.
+    pub fn from_node(root: &'a Node) -> Self {
+        let metadata = Metadata::from_root(root);
+        let Some(spec) = root.get("spec") else {
+            return Self {
+                metadata,
+                ..Self::default()
+            };
+        };
+
+        Self {
+            metadata,
+            pipeline_ref: spec.get("pipelineRef").map(PipelineRef::from_node),
+            pipeline_spec: spec.get("pipelineSpec").map(Pipeline::from_spec),
+            params: items(spec, "params", ParamValue::from_node),
+            workspaces: items(spec, "workspaces", WorkspaceBinding::from_node),
+            task_run_specs: items(spec, "taskRunSpecs", PipelineTaskRunSpec::from_node),
+            spec: Some(spec),
+        }
+    }
+}
+
+/// An entry of a PipelineRun's `taskRunSpecs`.
+#[derive(Debug, Clone)]
+pub struct PipelineTaskRunSpec<'a> {
+    /// The `pipelineTaskName` entry, whatever its value
+    pub pipeline_task_name: Option<&'a Node>,
+    pub node: &'a Node,
+}
+
+impl<'a> PipelineTaskRunSpec<'a> {
+    fn from_node(node: &'a Node) -> Self {
+        Self {
+            pipeline_task_name: node.get("pipelineTaskName"),
+            node,
+        }
+    }
+}
+
+/// A PipelineRun's `pipelineRef`.
+#[derive(Debug, Clone)]
+pub struct PipelineRef<'a> {
+    pub name: Option<Spanned<&'a str>>,
+    pub resolver: Option<Spanned<&'a str>>,
+    pub params: Vec<ParamValue<'a>>,
+    /// The `pipelineRef` entry
+    pub node: &'a Node,
+}
+
+impl<'a> PipelineRef<'a> {
+    fn from_node(node: &'a Node) -> Self {
+        Self {
+            name: scalar(node, "name"),
+            resolver: scalar(node, "resolver"),
+            params: items(node, "params", ParamValue::from_node),
+            node,
+        }
+    }
+}
+
+/// A TaskRun.
+#[derive(Debug, Clone, Default)]
+pub struct TaskRun<'a> {
+    pub metadata: Metadata<'a>,
+    pub task_ref: Option<TaskRef<'a>>,
+    /// The embedded `taskSpec`
+    pub task_spec: Option<Task<'a>>,
+    pub params: Vec<ParamValue<'a>>,
+    pub workspaces: Vec<WorkspaceBinding<'a>>,
+    /// The `spec` node
+    pub spec: Option<&'a Node>,
+}
+
+impl<'a> TaskRun<'a> {
+    /// Build a TaskRun from a document root.
+    pub fn from_node(root: &'a Node) -> Self {
+        let metadata = Metadata::from_root(root);
+        let Some(spec) = root.get("spec") else {
+            return Self {
+                metadata,
+                ..Self::default()
+            };
+        };
+
+        Self {
+            metadata,
+            task_ref: spec.get("taskRef").map(TaskRef::from_node),
+            task_spec: spec.get("taskSpec").map(Task::from_spec),
+            params: items(spec, "params", ParamValue::from_node),
+            workspaces: items(spec, "workspaces", WorkspaceBinding::from_node),
+            spec: Some(spec),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_yaml;
+
+    #[test]
+    fn test_pipeline_run_model() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  generateName: ci-run-
+spec:
+  pipelineRef:
+    name: ci
+  params:
+    - name: revision
+      value: main
+  workspaces:
+    - name: shared
+      subPath: src
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let run = PipelineRun::from_node(&doc.root);
+
+        assert!(run.metadata.name.is_none());
+        assert_eq!(run.metadata.generate_name.unwrap().value, "ci-run-");
+        assert_eq!(run.pipeline_ref.as_ref().unwrap().name.unwrap().value, "ci");
+        assert_eq!(run.params[0].name.unwrap().value, "revision");
+        assert_eq!(run.params[0].value.unwrap().as_scalar(), Some("main"));
+        assert_eq!(run.workspaces[0].sub_path.unwrap().value, "src");
+        assert!(run.pipeline_spec.is_none());
+    }
+
+    #[test]
+    fn test_task_run_model() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: TaskRun
+metadata:
+  name: build-run
+spec:
+  taskRef:
+    name: build
+  params:
+    - name: revision
+      value: main
+  workspaces:
+    - name: source
+      emptyDir: {}
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let run = TaskRun::from_node(&doc.root);
+
+        assert_eq!(run.metadata.name.unwrap().value, "build-run");
+        assert_eq!(run.task_ref.as_ref().unwrap().name.unwrap().value, "build");
+        assert_eq!(run.params[0].name.unwrap().value, "revision");
+        assert_eq!(run.workspaces[0].name.unwrap().value, "source");
+        assert!(run.task_spec.is_none());
+    }
+}
@@ -0,0 +1,153 @@
+//! Task and Step model.
+
+use super::{
+    items, scalar, scalar_list, Metadata, ParamSpec, ResultSpec, Spanned, WorkspaceDeclaration,
+};
+use crate::parser::Node;
+
+/// A Task (or ClusterTask), or the `taskSpec` embedded in a pipeline task.
+#[derive(Debug, Clone, Default)]
+pub struct Task<'a> {
+    pub metadata: Metadata<'a>,
+    pub description: Option<Spanned<&'a str>>,
+    pub params: Vec<ParamSpec<'a>>,
+    pub results: Vec<ResultSpec<'a>>,
+    pub workspaces: Vec<WorkspaceDeclaration<'a>>,
+    pub steps: Vec<Step<'a>>,
+    /// The `spec` node
+    pub spec: Option<&'a Node>,
+}
+
+impl<'a> Task<'a> {
+    /// Build a Task from a document root.
+    pub fn from_node(root: &'a Node) -> Self {
+        let mut task = root.get("spec").map(Self::from_spec).unwrap_or_default();
+        task.metadata = Metadata::from_root(root);
+        task
+    }
+
+    /// Build a Task from a spec node (`spec` or an embedded `taskSpec`).
+    pub fn from_spec(spec: &'a Node) -> Self {
+        Self {
+            metadata: Metadata::default(),
+            description: scalar(spec, "description"),
+            params: items(spec, "params", ParamSpec::from_node),
+            results: items(spec, "results", ResultSpec::from_node),
+            workspaces: items(spec, "workspaces", WorkspaceDeclaration::from_node),
+            steps: items(spec, "steps", Step::from_node),
+            spec: Some(spec),
+        }
+    }
+}
+
+/// A step of a Task.
+#[derive(Debug, Clone)]
+pub struct Step<'a> {
+    pub name: Option<Spanned<&'a str>>,
+    pub image: Option<Spanned<&'a str>>,
+    pub script: Option<Spanned<&'a str>>,
+    pub command: Option<Spanned<Vec<Spanned<&'a str>>>>,
+    pub args: Option<Spanned<Vec<Spanned<&'a str>>>>,
+    pub working_dir: Option<Spanned<&'a str>>,
+    pub image_pull_policy: Option<Spanned<&'a str>>,
+    pub on_error: Option<Spanned<&'a str>>,
+    pub volume_mounts: Vec<VolumeMount<'a>>,
+    /// The `artifacts` node, declaring the step's artifact `inputs` and `outputs`
+    pub artifacts: Option<&'a Node>,
+    /// The step's mapping node
+    pub node: &'a Node,
+}
+
+impl<'a> Step<'a> {
+    fn from_node(node: &'a Node) -> Self {
+        Self {
+            name: scalar(node, "name"),
+            image: scalar(node, "image"),
+            script: scalar(node, "script"),
+            command: scalar_list(node, "command"),
+            args: scalar_list(node, "args"),
+            working_dir: scalar(node, "workingDir"),
+            image_pull_policy: scalar(node, "imagePullPolicy"),
+            on_error: scalar(node, "onError"),
+            volume_mounts: items(node, "volumeMounts", VolumeMount::from_node),
+            artifacts: node.get("artifacts"),
+            node,
+        }
+    }
+
+    /// The step name, or `unnamed` for messages
+    pub fn display_name(&self) -> &'a str {
+        self.name.map(|n| n.value).unwrap_or("unnamed")
+    }
+}
+
+/// An entry of a step's `volumeMounts`.
+#[derive(Debug, Clone)]
+pub struct VolumeMount<'a> {
+    pub name: Option<Spanned<&'a str>>,
+    pub mount_path: Option<Spanned<&'a str>>,
+    pub node: &'a Node,
+}
+
+impl<'a> VolumeMount<'a> {
+    fn from_node(node: &'a Node) -> Self {
+        Self {
+            name: scalar(node, "name"),
+            mount_path: scalar(node, "mountPath"),
+            node,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_yaml;
+
+    #[test]
+    fn test_task_model() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  description: Builds the sources
+  params:
+    - name: flags
+      type: array
+      default: ["-v"]
+  results:
+    - name: digest
+  workspaces:
+    - name: source
+      mountPath: /src
+  steps:
+    - name: compile
+      image: golang
+      command: ["go", "build"]
+      script: |
+        go build ./...
+    - image: alpine
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let task = Task::from_node(&doc.root);
+
+        assert_eq!(task.metadata.name.unwrap().value, "build");
+        assert_eq!(task.description.unwrap().value, "Builds the sources");
+        assert_eq!(task.params[0].name.unwrap().value, "flags");
+        assert_eq!(task.params[0].param_type.unwrap().value, "array");
+        assert!(task.params[0].default.unwrap().is_sequence());
+        assert_eq!(task.results[0].name.unwrap().value, "digest");
+        assert_eq!(task.workspaces[0].mount_path.unwrap().value, "/src");
+
+        assert_eq!(task.steps.len(), 2);
+        let compile = &task.steps[0];
+        let command = compile.command.as_ref().unwrap();
+        let words: Vec<&str> = command.value.iter().map(|w| w.value).collect();
+        assert_eq!(words, vec!["go", "build"]);
+        assert_eq!(command.key_range.unwrap().start.line, 18);
+        assert!(compile.script.unwrap().value.contains("go build"));
+        assert_eq!(task.steps[1].display_name(), "unnamed");
+    }
+}
@@ -0,0 +1,146 @@
+//! Pipelines-as-Code support.
+//!
+//! Pipelines-as-Code runs the PipelineRuns of a repository's `.tekton/`
+//! directory, substituting `{{ variable }}` templates in them first.
+
+/// Standard variables, with a description for completion.
+pub const VARIABLES: [(&str, &str); 18] = [
+    (
+        "body",
+        "Payload of the webhook event, e.g. {{ body.pull_request.user.login }}",
+    ),
+    (
+        "event_type",
+        "Event type, e.g. pull_request, push or incoming",
+    ),
+    (
+        "files",
+        "Changed files: files.all, files.added, files.deleted, files.modified, files.renamed",
+    ),
+    (
+        "git_auth_secret",
+        "Name of the secret holding the git credentials",
+    ),
+    (
+        "headers",
+        "Headers of the webhook request, e.g. {{ headers['X-Github-Event'] }}",
+    ),
+    (
+        "pull_request_labels",
+        "Labels of the pull request, one per line",
+    ),
+    ("pull_request_number", "Number of the pull or merge request"),
+    ("repo_name", "Name of the repository"),
+    (
+        "repo_owner",
+        "Owner (organization or user) of the repository",
+    ),
+    ("repo_url", "URL of the repository"),
+    ("revision", "Commit SHA of the event"),
+    ("sender", "Account that triggered the event"),
+    ("source_branch", "Branch the event comes from"),
+    (
+        "source_url",
+        "URL of the repository the event comes from (the fork for pull requests)",
+    ),
+    ("target_branch", "Branch the event targets"),
+    ("target_namespace", "Namespace the PipelineRun runs in"),
+    (
+        "trigger_comment",
+        "Comment that triggered the run, e.g. /retest",
+    ),
+    ("git_tag", "Tag of a push event on a tag"),
+];
+
+/// Annotations of the PipelineRuns of `.tekton/`, with what they do.
+pub const ANNOTATIONS: [(&str, &str); 12] = [
+    (
+        "pipelinesascode.tekton.dev/on-event",
+        "Events that run the PipelineRun, e.g. [pull_request, push]",
+    ),
+    (
+        "pipelinesascode.tekton.dev/on-target-branch",
+        "Branches the event must target, e.g. [main]",
+    ),
+    (
+        "pipelinesascode.tekton.dev/on-cel-expression",
+        "CEL expression the event must match, in place of on-event and on-target-branch",
+    ),
+    (
+        "pipelinesascode.tekton.dev/on-path-change",
+        "Only run when a changed file matches one of these globs, e.g. [docs/**]",
+    ),
+    (
+        "pipelinesascode.tekton.dev/on-path-change-ignore",
+        "Do not run when every changed file matches one of these globs",
+    ),
+    (
+        "pipelinesascode.tekton.dev/on-comment",
+        "Regular expression of the pull request comments that run the PipelineRun",
+    ),
+    (
+        "pipelinesascode.tekton.dev/on-label",
+        "Pull request labels that run the PipelineRun, e.g. [bug]",
+    ),
+    (
+        "pipelinesascode.tekton.dev/max-keep-runs",
+        "Number of completed runs to keep; older ones are deleted",
+    ),
+    (
+        "pipelinesascode.tekton.dev/cancel-in-progress",
+        "Whether a new run cancels the running ones of the same pull request or branch",
+    ),
+    (
+        "pipelinesascode.tekton.dev/task",
+        "Remote Tasks to fetch from the Hub, a URL or the repository, e.g. [git-clone]",
+    ),
+    (
+        "pipelinesascode.tekton.dev/pipeline",
+        "Remote Pipeline to fetch from a URL or the repository",
+    ),
+    (
+        "pipelinesascode.tekton.dev/target-namespace",
+        "Namespace the PipelineRun must run in",
+    ),
+];
+
+/// Variables whose fields can be accessed with `.` or `[...]`.
+const STRUCTURED_VARIABLES: [&str; 3] = ["body", "headers", "files"];
+
+/// Whether a document is in a `.tekton/` directory, where Pipelines-as-Code
+/// substitutes templates.
+pub fn is_pac_document(filename: &str) -> bool {
+    filename
+        .split(['/', '\\'])
+        .any(|segment| segment == ".tekton")
+}
+
+/// Whether a template variable is a standard Pipelines-as-Code variable, or a
+/// field of one.
+pub fn is_known_variable(name: &str) -> bool {
+    let root = name.split(['.', '[']).next().unwrap_or(name);
+    VARIABLES.iter().any(|(variable, _)| *variable == root)
+        && (root == name || STRUCTURED_VARIABLES.contains(&root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_pac_document() {
+        assert!(is_pac_document("file:///repo/.tekton/pull-request.yaml"));
+        assert!(is_pac_document("file:///repo/.tekton/tasks/lint.yaml"));
+        assert!(!is_pac_document("file:///repo/tekton/pull-request.yaml"));
+        assert!(!is_pac_document("test.yaml"));
+    }
+
+    #[test]
+    fn test_is_known_variable() {
+        assert!(is_known_variable("revision"));
+        assert!(is_known_variable("body.pull_request.number"));
+        assert!(is_known_variable("headers['X-Github-Event']"));
+        assert!(!is_known_variable("revision.short"));
+        assert!(!is_known_variable("revison"));
+    }
+}
@@ -1,5 +1,8 @@
-use std::collections::HashMap;
-use tower_lsp::lsp_types::{Position, Range};
+use super::encoding::PositionEncoding;
+use super::positions;
+use crate::error::ParseError;
+use indexmap::IndexMap;
+use tower_lsp::lsp_types::{Position, Range, Url};
 
 /// A node in the YAML AST with position information
 #[derive(Debug, Clone)]
@@ -8,17 +11,56 @@ pub struct Node {
     pub key: Option<String>,
     /// The value of this node
     pub value: NodeValue,
-    /// The range in the document where this node appears
+    /// The range in the document where this node appears (the whole key/value pair for map entries)
     pub range: Range,
+    /// The range of the key token (only set for map entries)
+    pub key_range: Option<Range>,
+    /// The range of the value alone, excluding the key
+    pub value_range: Range,
+    /// Comments on the lines directly above this node
+    pub leading_comments: Vec<Comment>,
+    /// Comment at the end of this node's last line
+    pub trailing_comment: Option<Comment>,
+    /// How the value was written in the source
+    pub style: Style,
+    /// Source text between the quotes of a quoted scalar, when escapes or line
+    /// folding make it differ from the value
+    pub raw: Option<String>,
 }
 
+/// The source style of a node's value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Style {
+    /// Plain scalar, block scalar (`|`, `>`) or block collection
+    #[default]
+    Default,
+    /// `'single quoted'` scalar
+    SingleQuoted,
+    /// `"double quoted"` scalar
+    DoubleQuoted,
+    /// Flow collection (`[a, b]`, `{a: b}`)
+    Flow,
+}
+
+/// A YAML comment with its position in the document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// The comment text, including the leading `#`
+    pub text: String,
+    /// The range of the comment in the document
+    pub range: Range,
+}
+
+/// The entries of a YAML mapping, in document order
+pub type Mapping = IndexMap<String, Node>;
+
 /// The different types of values a YAML node can have
 #[derive(Debug, Clone)]
 pub enum NodeValue {
     /// A scalar value (string, number, boolean, null)
     Scalar(String),
-    /// A mapping of keys to nodes
-    Mapping(HashMap<String, Node>),
+    /// A mapping of keys to nodes, in document order
+    Mapping(Mapping),
     /// A sequence of nodes
     Sequence(Vec<Node>),
     /// Null value
@@ -26,9 +68,69 @@ pub enum NodeValue {
 }
 
 impl Node {
-    /// Create a new node
+    /// Create a new node whose value spans the whole range
     pub fn new(key: Option<String>, value: NodeValue, range: Range) -> Self {
-        Self { key, value, range }
+        Self {
+            key,
+            value,
+            range,
+            key_range: None,
+            value_range: range,
+            leading_comments: Vec::new(),
+            trailing_comment: None,
+            style: Style::Default,
+            raw: None,
+        }
+    }
+
+    /// Create a scalar node, for building trees to serialize
+    pub fn scalar(text: impl Into<String>) -> Self {
+        Self::new(None, NodeValue::Scalar(text.into()), Range::default())
+    }
+
+    /// Create a null node, for building trees to serialize
+    pub fn null() -> Self {
+        Self::new(None, NodeValue::Null, Range::default())
+    }
+
+    /// Create a mapping node from entries in order, for building trees to serialize
+    pub fn mapping<K: Into<String>>(entries: impl IntoIterator<Item = (K, Node)>) -> Self {
+        let map = entries
+            .into_iter()
+            .map(|(key, mut node)| {
+                let key = key.into();
+                node.key = Some(key.clone());
+                (key, node)
+            })
+            .collect();
+        Self::new(None, NodeValue::Mapping(map), Range::default())
+    }
+
+    /// Create a sequence node, for building trees to serialize
+    pub fn sequence(items: impl IntoIterator<Item = Node>) -> Self {
+        Self::new(
+            None,
+            NodeValue::Sequence(items.into_iter().collect()),
+            Range::default(),
+        )
+    }
+
+    /// Set the source style of the value
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the key and value token ranges of a map entry
+    pub fn with_ranges(mut self, key_range: Option<Range>, value_range: Range) -> Self {
+        self.key_range = key_range;
+        self.value_range = value_range;
+        self
+    }
+
+    /// Range of the key token, falling back to the whole node range
+    pub fn key_or_range(&self) -> Range {
+        self.key_range.unwrap_or(self.range)
     }
 
     /// Get a child node by key (for mappings)
@@ -39,6 +141,29 @@ impl Node {
         }
     }
 
+    /// Iterate over the entries of a mapping in document order
+    ///
+    /// Yields nothing for scalars, sequences and nulls.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Node)> {
+        let map = match &self.value {
+            NodeValue::Mapping(map) => Some(map),
+            _ => None,
+        };
+        map.into_iter()
+            .flatten()
+            .map(|(key, node)| (key.as_str(), node))
+    }
+
+    /// The items of a sequence
+    ///
+    /// Empty for scalars, mappings and nulls.
+    pub fn items(&self) -> &[Node] {
+        match &self.value {
+            NodeValue::Sequence(items) => items,
+            _ => &[],
+        }
+    }
+
     /// Get a scalar value as a string
     pub fn as_scalar(&self) -> Option<&str> {
         match &self.value {
@@ -47,6 +172,44 @@ impl Node {
         }
     }
 
+    /// Get the text a scalar stands for
+    ///
+    /// Block scalars (`|`, `>`) are stored as written, header and indentation
+    /// included; this strips the header, removes the indentation and folds
+    /// `>` scalars. Other scalars are returned as is.
+    pub fn scalar_content(&self) -> Option<String> {
+        let text = self.as_scalar()?;
+        if self.style != Style::Default || !(text.starts_with('|') || text.starts_with('>')) {
+            return Some(text.to_string());
+        }
+
+        let mut lines = text.lines();
+        let folded = lines.next().is_some_and(|header| header.starts_with('>'));
+        let content: Vec<&str> = lines.collect();
+        let indent = content
+            .iter()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.len() - l.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        let content: Vec<&str> = content
+            .iter()
+            .map(|l| l.get(indent..).unwrap_or(""))
+            .collect();
+
+        if !folded {
+            return Some(content.join("\n").trim_end().to_string());
+        }
+
+        // Folded: lines of a paragraph are joined with spaces, blank lines separate paragraphs
+        let paragraphs: Vec<String> = content
+            .split(|l| l.trim().is_empty())
+            .filter(|p| !p.is_empty())
+            .map(|p| p.join(" "))
+            .collect();
+        Some(paragraphs.join("\n"))
+    }
+
     /// Check if this node is a mapping (used in tests)
     #[allow(dead_code)]
     pub fn is_mapping(&self) -> bool {
@@ -59,24 +222,85 @@ impl Node {
         matches!(self.value, NodeValue::Sequence(_))
     }
 
-    /// Check if this node is a scalar (used in tests)
-    #[allow(dead_code)]
+    /// Check if this node is a scalar
     pub fn is_scalar(&self) -> bool {
         matches!(self.value, NodeValue::Scalar(_))
     }
+
+    /// Number of nodes in this subtree, this node included
+    pub fn node_count(&self) -> usize {
+        1 + match &self.value {
+            NodeValue::Mapping(map) => map.values().map(Node::node_count).sum(),
+            NodeValue::Sequence(items) => items.iter().map(Node::node_count).sum(),
+            _ => 0,
+        }
+    }
+
+    /// Keys leading from this node down to `target`, sequence items adding
+    /// none, or `None` if `target` is not in this subtree.
+    pub fn path_to(&self, target: &Node) -> Option<Vec<&str>> {
+        if std::ptr::eq(self, target) {
+            return Some(Vec::new());
+        }
+        let children: Vec<&Node> = match &self.value {
+            NodeValue::Mapping(map) => map.values().collect(),
+            NodeValue::Sequence(items) => items.iter().collect(),
+            _ => return None,
+        };
+        children.into_iter().find_map(|child| {
+            let mut path = child.path_to(target)?;
+            if let Some(key) = &child.key {
+                path.insert(0, key.as_str());
+            }
+            Some(path)
+        })
+    }
+
+    /// Last line holding content of this node.
+    ///
+    /// Block ranges extend over trailing blank lines, so this is taken from the
+    /// last leaf, ignoring a range that ends at the start of the following line.
+    pub fn last_content_line(&self) -> u32 {
+        let children: Vec<&Node> = match &self.value {
+            NodeValue::Mapping(map) => map.values().collect(),
+            NodeValue::Sequence(items) => items.iter().collect(),
+            // The text of plain and block scalars is the source text
+            NodeValue::Scalar(text) if self.style == Style::Default => {
+                let lines = text.trim_end().lines().count().max(1) as u32;
+                return self.value_range.start.line + lines - 1;
+            }
+            _ => vec![],
+        };
+        match children.into_iter().map(Node::last_content_line).max() {
+            Some(line) => line,
+            None if self.range.end.character == 0
+                && self.range.end.line > self.range.start.line =>
+            {
+                self.range.end.line - 1
+            }
+            None => self.range.end.line,
+        }
+    }
 }
 
 /// A parsed YAML document
 #[derive(Debug, Clone)]
 pub struct YamlDocument {
     /// The filename or URI of the document (used for diagnostics)
-    #[allow(dead_code)]
     pub filename: String,
     /// The root node of the document
     pub root: Node,
     /// Tekton-specific fields extracted for quick access
     pub api_version: Option<String>,
     pub kind: Option<String>,
+    /// Syntax errors tree-sitter recovered from while parsing
+    pub syntax_errors: Vec<ParseError>,
+    /// Every comment in the document, in document order
+    pub comments: Vec<Comment>,
+    /// Encoding of the `character` of every position in the document
+    pub encoding: PositionEncoding,
+    /// The text the document was parsed from
+    pub source: String,
 }
 
 impl YamlDocument {
@@ -98,9 +322,24 @@ impl YamlDocument {
             root,
             api_version,
             kind,
+            syntax_errors: Vec::new(),
+            comments: Vec::new(),
+            encoding: PositionEncoding::default(),
+            source: String::new(),
         }
     }
 
+    /// Text of a line of the source, without its line break.
+    pub fn line_text(&self, line: u32) -> Option<&str> {
+        self.source.lines().nth(line as usize)
+    }
+
+    /// The URI of the document; the server parses documents with their URI
+    /// as filename.
+    pub fn uri(&self) -> Option<Url> {
+        Url::parse(&self.filename).ok()
+    }
+
     /// Find the node at a specific position in the document (for hover/goto-definition)
     #[allow(dead_code)]
     pub fn find_node_at_position(&self, position: Position) -> Option<&Node> {
@@ -112,7 +351,7 @@ impl YamlDocument {
 #[allow(dead_code)]
 fn find_node_at_position_recursive(node: &Node, position: Position) -> Option<&Node> {
     // Check if position is within this node's range
-    if !position_in_range(position, node.range) {
+    if !positions::contains(node.range, position) {
         return None;
     }
 
@@ -139,21 +378,6 @@ fn find_node_at_position_recursive(node: &Node, position: Position) -> Option<&N
     Some(node)
 }
 
-/// Check if a position is within a range (for hover/goto-definition)
-#[allow(dead_code)]
-fn position_in_range(pos: Position, range: Range) -> bool {
-    if pos.line < range.start.line || pos.line > range.end.line {
-        return false;
-    }
-    if pos.line == range.start.line && pos.character < range.start.character {
-        return false;
-    }
-    if pos.line == range.end.line && pos.character > range.end.character {
-        return false;
-    }
-    true
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,29 +408,9 @@ mod tests {
         assert_eq!(node.as_scalar(), Some("value"));
     }
 
-    #[test]
-    fn test_position_in_range() {
-        let range = make_range(1, 5, 3, 10);
-
-        // Inside range
-        assert!(position_in_range(Position { line: 2, character: 5 }, range));
-
-        // At start
-        assert!(position_in_range(Position { line: 1, character: 5 }, range));
-
-        // At end
-        assert!(position_in_range(Position { line: 3, character: 10 }, range));
-
-        // Before range
-        assert!(!position_in_range(Position { line: 1, character: 4 }, range));
-
-        // After range
-        assert!(!position_in_range(Position { line: 3, character: 11 }, range));
-    }
-
     #[test]
     fn test_find_node_at_position() {
-        let mut map = HashMap::new();
+        let mut map = Mapping::new();
         map.insert(
             "key1".to_string(),
             Node::new(
@@ -216,17 +420,49 @@ mod tests {
             ),
         );
 
-        let root = Node::new(
-            None,
-            NodeValue::Mapping(map),
-            make_range(0, 0, 2, 0),
-        );
+        let root = Node::new(None, NodeValue::Mapping(map), make_range(0, 0, 2, 0));
 
         let doc = YamlDocument::new("test.yaml".to_string(), root);
 
         // Find node at position within key1
-        let found = doc.find_node_at_position(Position { line: 1, character: 5 });
+        let found = doc.find_node_at_position(Position {
+            line: 1,
+            character: 5,
+        });
         assert!(found.is_some());
         assert_eq!(found.unwrap().key, Some("key1".to_string()));
     }
+
+    #[test]
+    fn test_node_with_ranges() {
+        let node = Node::new(
+            Some("image".to_string()),
+            NodeValue::Scalar("alpine".to_string()),
+            make_range(2, 4, 2, 17),
+        )
+        .with_ranges(Some(make_range(2, 4, 2, 9)), make_range(2, 11, 2, 17));
+
+        assert_eq!(node.key_or_range(), make_range(2, 4, 2, 9));
+        assert_eq!(node.value_range, make_range(2, 11, 2, 17));
+        assert_eq!(node.range, make_range(2, 4, 2, 17));
+    }
+
+    #[test]
+    fn test_path_to() {
+        let root = Node::mapping([(
+            "spec",
+            Node::mapping([(
+                "steps",
+                Node::sequence([Node::mapping([("image", Node::scalar("alpine"))])]),
+            )]),
+        )]);
+        let steps = root.get("spec").unwrap().get("steps").unwrap().items();
+        assert_eq!(steps.len(), 1);
+        let image = steps[0].get("image").unwrap();
+        assert!(image.items().is_empty());
+
+        assert_eq!(root.path_to(image), Some(vec!["spec", "steps", "image"]));
+        assert_eq!(root.path_to(&root), Some(vec![]));
+        assert_eq!(root.path_to(&Node::null()), None);
+    }
 }
@@ -0,0 +1,176 @@
+//! Comment extraction and attachment.
+//!
+//! tree-sitter reports comments as extra nodes outside the AST structure.
+//! They are collected here and attached to the closest AST node: a comment
+//! sharing a line with content becomes that node's trailing comment, and a
+//! comment on its own line becomes a leading comment of the next node.
+
+use super::ast::{Comment, Node, NodeValue};
+use super::positions;
+use std::cmp::Reverse;
+use tower_lsp::lsp_types::Position;
+
+/// Collect every comment in the syntax tree, in document order.
+pub(super) fn collect_comments(
+    ts_node: &tree_sitter::Node,
+    content: &str,
+    comments: &mut Vec<Comment>,
+) {
+    if ts_node.kind() == "comment" {
+        comments.push(Comment {
+            text: ts_node
+                .utf8_text(content.as_bytes())
+                .unwrap_or("")
+                .to_string(),
+            range: super::yaml_parser::node_to_range(ts_node),
+        });
+        return;
+    }
+
+    let mut cursor = ts_node.walk();
+    for child in ts_node.children(&mut cursor) {
+        collect_comments(&child, content, comments);
+    }
+}
+
+/// Attach comments to the nodes of the AST rooted at `root`.
+///
+/// The nodes are walked once and sorted by position, so each comment finds
+/// its target with a binary search.
+pub(super) fn attach_comments(root: &mut Node, comments: &[Comment], content: &str) {
+    if comments.is_empty() {
+        return;
+    }
+    let lines: Vec<&str> = content.lines().collect();
+
+    let mut nodes = Vec::new();
+    collect_nodes(root, &mut Vec::new(), &mut nodes);
+
+    // Leading comments go to the outermost node starting first after them
+    let mut by_start: Vec<&NodeInfo> = nodes.iter().collect();
+    by_start.sort_by_key(|info| (info.start.line, info.start.character, info.path.len()));
+    // Trailing comments go to the innermost node ending last before them
+    // (the first visited wins among equals, hence the reversed visit order)
+    let mut by_end: Vec<(usize, &NodeInfo)> = nodes.iter().enumerate().collect();
+    by_end.sort_by_key(|(order, info)| {
+        (
+            info.end.line,
+            info.end.character,
+            info.path.len(),
+            Reverse(*order),
+        )
+    });
+    let by_end: Vec<&NodeInfo> = by_end.into_iter().map(|(_, info)| info).collect();
+
+    let mut targets = Vec::new();
+    for comment in comments {
+        let line = comment.range.start.line as usize;
+        let prefix = lines
+            .get(line)
+            .and_then(|l| l.get(..comment.range.start.character as usize))
+            .unwrap_or("");
+
+        if prefix.trim().is_empty() {
+            let after = comment.range.end;
+            let first =
+                by_start.partition_point(|info| positions::compare(info.start, after).is_lt());
+            if let Some(info) = by_start.get(first) {
+                targets.push((info.path.clone(), comment, true));
+            }
+        } else {
+            let before = comment.range.start;
+            let past = by_end.partition_point(|info| positions::compare(info.end, before).is_le());
+            let info = past.checked_sub(1).map(|last| by_end[last]);
+            if let Some(info) = info.filter(|info| info.end.line == before.line) {
+                targets.push((info.path.clone(), comment, false));
+            }
+        }
+    }
+
+    for (path, comment, leading) in targets {
+        let Some(node) = node_at_path_mut(root, &path) else {
+            continue;
+        };
+        if leading {
+            node.leading_comments.push(comment.clone());
+        } else {
+            node.trailing_comment = Some(comment.clone());
+        }
+    }
+}
+
+/// A node of the tree with the path leading to it.
+struct NodeInfo {
+    /// Index of the child at each level, in mapping or sequence order
+    path: Vec<usize>,
+    start: Position,
+    end: Position,
+}
+
+/// Collect every node below `node` (pre-order) with its path.
+fn collect_nodes(node: &Node, path: &mut Vec<usize>, nodes: &mut Vec<NodeInfo>) {
+    let children: Vec<&Node> = match &node.value {
+        NodeValue::Mapping(map) => map.values().collect(),
+        NodeValue::Sequence(items) => items.iter().collect(),
+        _ => return,
+    };
+    for (i, child) in children.into_iter().enumerate() {
+        path.push(i);
+        nodes.push(NodeInfo {
+            path: path.clone(),
+            start: child.range.start,
+            end: child.range.end,
+        });
+        collect_nodes(child, path, nodes);
+        path.pop();
+    }
+}
+
+/// Follow a path from `root` to a node.
+fn node_at_path_mut<'a>(root: &'a mut Node, path: &[usize]) -> Option<&'a mut Node> {
+    path.iter()
+        .try_fold(root, |node, &i| match &mut node.value {
+            NodeValue::Mapping(map) => map.get_index_mut(i).map(|(_, child)| child),
+            NodeValue::Sequence(items) => items.get_mut(i),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse_yaml;
+
+    #[test]
+    fn test_leading_and_trailing_comments() {
+        let yaml = r#"# Build task
+kind: Task
+spec:
+  steps:
+    # compile the sources
+    - name: compile # main step
+      image: golang
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        assert_eq!(doc.comments.len(), 3);
+
+        let kind = doc.root.get("kind").unwrap();
+        assert_eq!(kind.leading_comments.len(), 1);
+        assert_eq!(kind.leading_comments[0].text, "# Build task");
+
+        let steps = doc.root.get("spec").unwrap().get("steps").unwrap();
+        let step = match &steps.value {
+            crate::parser::NodeValue::Sequence(items) => &items[0],
+            _ => panic!("steps should be a sequence"),
+        };
+        assert_eq!(step.leading_comments[0].text, "# compile the sources");
+
+        let name = step.get("name").unwrap();
+        let trailing = name
+            .trailing_comment
+            .as_ref()
+            .expect("trailing comment on name");
+        assert_eq!(trailing.text, "# main step");
+        assert_eq!(trailing.range.start.line, 5);
+    }
+}
@@ -0,0 +1,139 @@
+//! Position encodings.
+//!
+//! tree-sitter reports columns in bytes, while LSP positions count UTF-16
+//! code units unless the client and server agree on another encoding during
+//! `initialize`. The parser converts every range it produces to the
+//! negotiated encoding, so providers can compare them with client positions
+//! directly; code computing columns from text goes through the same helpers.
+
+use tower_lsp::lsp_types::{PositionEncodingKind, Range};
+
+/// Unit in which the `character` of a position is counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PositionEncoding {
+    /// UTF-8 code units (bytes), what tree-sitter reports
+    Utf8,
+    /// UTF-16 code units, the LSP default
+    #[default]
+    Utf16,
+}
+
+impl PositionEncoding {
+    /// Pick the encoding to use given the encodings a client supports.
+    ///
+    /// UTF-8 is preferred since it needs no conversion; UTF-16 is the fallback
+    /// every client supports.
+    pub fn negotiate(supported: Option<&[PositionEncodingKind]>) -> Self {
+        match supported {
+            Some(kinds) if kinds.contains(&PositionEncodingKind::UTF8) => PositionEncoding::Utf8,
+            _ => PositionEncoding::Utf16,
+        }
+    }
+
+    /// The LSP name of the encoding, to report in the server capabilities
+    pub fn kind(self) -> PositionEncodingKind {
+        match self {
+            PositionEncoding::Utf8 => PositionEncodingKind::UTF8,
+            PositionEncoding::Utf16 => PositionEncodingKind::UTF16,
+        }
+    }
+
+    /// Length of `text` in code units.
+    pub fn len(self, text: &str) -> u32 {
+        match self {
+            PositionEncoding::Utf8 => text.len() as u32,
+            PositionEncoding::Utf16 => text.encode_utf16().count() as u32,
+        }
+    }
+
+    /// Column of the byte offset `byte` of `line`.
+    ///
+    /// Offsets past the end of the line or inside a character are clamped.
+    pub fn column(self, line: &str, byte: usize) -> u32 {
+        let mut byte = byte.min(line.len());
+        while !line.is_char_boundary(byte) {
+            byte -= 1;
+        }
+        self.len(&line[..byte])
+    }
+
+    /// Byte offset in `line` of the column `column`.
+    ///
+    /// Columns past the end of the line are clamped to its length, and
+    /// columns inside a character to its start.
+    pub fn byte_offset(self, line: &str, column: u32) -> usize {
+        let mut units = 0;
+        for (offset, c) in line.char_indices() {
+            units += match self {
+                PositionEncoding::Utf8 => c.len_utf8(),
+                PositionEncoding::Utf16 => c.len_utf16(),
+            } as u32;
+            if units > column {
+                return offset;
+            }
+        }
+        line.len()
+    }
+}
+
+/// Converts byte-column ranges of a text to a position encoding.
+pub(super) struct LineIndex<'a> {
+    lines: Vec<&'a str>,
+    encoding: PositionEncoding,
+}
+
+impl<'a> LineIndex<'a> {
+    pub(super) fn new(content: &'a str, encoding: PositionEncoding) -> Self {
+        Self {
+            lines: content.split('\n').collect(),
+            encoding,
+        }
+    }
+
+    /// Convert a range whose columns are byte offsets.
+    pub(super) fn convert(&self, range: &mut Range) {
+        for position in [&mut range.start, &mut range.end] {
+            if let Some(line) = self.lines.get(position.line as usize) {
+                position.character = self.encoding.column(line, position.character as usize);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_and_offsets() {
+        // 'é' is 2 bytes / 1 UTF-16 unit, '🚀' is 4 bytes / 2 UTF-16 units
+        let line = "a: é🚀x";
+        let x = line.find('x').unwrap();
+        assert_eq!(x, 9);
+
+        assert_eq!(PositionEncoding::Utf8.column(line, x), 9);
+        assert_eq!(PositionEncoding::Utf16.column(line, x), 6);
+        assert_eq!(PositionEncoding::Utf16.byte_offset(line, 6), x);
+        assert_eq!(PositionEncoding::Utf8.byte_offset(line, 9), x);
+
+        // Clamped inside a character and past the end
+        assert_eq!(PositionEncoding::Utf16.column(line, 4), 3);
+        assert_eq!(PositionEncoding::Utf16.byte_offset(line, 5), 5);
+        assert_eq!(PositionEncoding::Utf16.byte_offset(line, 100), line.len());
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(PositionEncoding::negotiate(None), PositionEncoding::Utf16);
+        let kinds = [PositionEncodingKind::UTF16, PositionEncodingKind::UTF8];
+        assert_eq!(
+            PositionEncoding::negotiate(Some(&kinds)),
+            PositionEncoding::Utf8
+        );
+        let kinds = [PositionEncodingKind::new("utf-32")];
+        assert_eq!(
+            PositionEncoding::negotiate(Some(&kinds)),
+            PositionEncoding::Utf16
+        );
+    }
+}
@@ -0,0 +1,479 @@
+//! Tekton variable expressions (`$(params.foo)`, `$(tasks.build.results.digest)`, ...).
+//!
+//! Finds `$(...)` references inside scalar values and splits them into
+//! path segments, each with its own range in the document.
+
+use super::ast::{Node, NodeValue, Style};
+use super::encoding::PositionEncoding;
+use super::positions;
+use super::quoted::unquote;
+use tower_lsp::lsp_types::{Position, Range};
+
+/// A `$(...)` variable expression found inside a scalar value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    /// The text between `$(` and `)`, e.g. `params.foo`
+    pub text: String,
+    /// The dotted/bracketed path segments, e.g. `params`, `foo`
+    pub segments: Vec<Segment>,
+    /// Whether the path ends with an `[*]` array expansion
+    pub wildcard: bool,
+    /// Range of the whole expression, including `$(` and `)`
+    pub range: Range,
+    /// Range of the text between `$(` and `)`
+    pub inner_range: Range,
+}
+
+/// One segment of an expression path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// The segment name, without quotes or brackets
+    pub name: String,
+    /// Range of the segment name in the document
+    pub range: Range,
+}
+
+/// What a variable expression refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reference<'a> {
+    /// `$(params.<name>)`, `$(params.<name>[*])`, `$(params.<name>.<key>)`
+    Param { name: &'a Segment },
+    /// `$(tasks.<task>.results.<result>)`, and `$(finally.<task>.results.<result>)`
+    /// in pipeline results
+    TaskResult {
+        task: &'a Segment,
+        result: &'a Segment,
+    },
+    /// `$(tasks.<task>.status)` and other task-level fields, also under `finally`
+    TaskField {
+        task: &'a Segment,
+        field: &'a Segment,
+    },
+    /// `$(results.<name>.path)`
+    Result { name: &'a Segment },
+    /// `$(workspaces.<name>.<field>)`
+    Workspace {
+        name: &'a Segment,
+        field: Option<&'a Segment>,
+    },
+    /// `$(artifacts.path)` and `$(step.artifacts.path)`
+    ArtifactsPath,
+    /// `$(context.*)`, `$(steps.*)`, `$(tasks.status)`, and anything else
+    Other,
+}
+
+impl Expression {
+    /// Names of the path segments.
+    pub fn path(&self) -> Vec<&str> {
+        self.segments.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// Classify what this expression refers to.
+    pub fn reference(&self) -> Reference<'_> {
+        let segs = &self.segments;
+        match segs.first().map(|s| s.name.as_str()) {
+            Some("params") if segs.len() >= 2 => Reference::Param { name: &segs[1] },
+            Some("tasks" | "finally") if segs.len() >= 4 && segs[2].name == "results" => {
+                Reference::TaskResult {
+                    task: &segs[1],
+                    result: &segs[3],
+                }
+            }
+            Some("tasks" | "finally") if segs.len() == 3 => Reference::TaskField {
+                task: &segs[1],
+                field: &segs[2],
+            },
+            Some("results") if segs.len() >= 2 => Reference::Result { name: &segs[1] },
+            Some("workspaces") if segs.len() >= 2 => Reference::Workspace {
+                name: &segs[1],
+                field: segs.get(2),
+            },
+            _ if matches!(
+                self.path().as_slice(),
+                ["artifacts", "path"] | ["step", "artifacts", "path"]
+            ) =>
+            {
+                Reference::ArtifactsPath
+            }
+            _ => Reference::Other,
+        }
+    }
+
+    /// Whether the position falls inside this expression.
+    pub fn contains(&self, position: Position) -> bool {
+        positions::contains(self.range, position)
+    }
+}
+
+/// Find all expressions in a scalar node's value.
+///
+/// `encoding` is the position encoding of the document the node comes from.
+pub fn expressions_in(node: &Node, encoding: PositionEncoding) -> Vec<Expression> {
+    match &node.value {
+        NodeValue::Scalar(text) => {
+            let locator = match &node.raw {
+                Some(raw) => Locator::quoted(raw, node.style, node.value_range.start, encoding),
+                None => Locator::new(text, node.value_range.start, encoding),
+            };
+            find_expressions_with(text, &locator)
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Find all expressions in the scalars of a node and of its descendants.
+pub fn expressions_under(node: &Node, encoding: PositionEncoding) -> Vec<Expression> {
+    match &node.value {
+        NodeValue::Mapping(map) => map
+            .values()
+            .flat_map(|child| expressions_under(child, encoding))
+            .collect(),
+        NodeValue::Sequence(items) => items
+            .iter()
+            .flat_map(|item| expressions_under(item, encoding))
+            .collect(),
+        _ => expressions_in(node, encoding),
+    }
+}
+
+/// Find all expressions in `text`, which starts at `start` in the document.
+pub fn find_expressions(
+    text: &str,
+    start: Position,
+    encoding: PositionEncoding,
+) -> Vec<Expression> {
+    find_expressions_with(text, &Locator::new(text, start, encoding))
+}
+
+/// Find all expressions in `text`, locating them with `locator`.
+fn find_expressions_with(text: &str, locator: &Locator) -> Vec<Expression> {
+    let bytes = text.as_bytes();
+    let mut expressions = Vec::new();
+    let mut i = 0;
+
+    while i + 1 < bytes.len() {
+        if bytes[i] != b'$' || bytes[i + 1] != b'(' {
+            i += 1;
+            continue;
+        }
+
+        let inner_start = i + 2;
+        let Some(inner_end) = matching_paren(bytes, inner_start) else {
+            // Unterminated expression: nothing more to find
+            break;
+        };
+
+        let inner = &text[inner_start..inner_end];
+        let (segments, wildcard) = split_segments(inner, inner_start, locator);
+        expressions.push(Expression {
+            text: inner.to_string(),
+            segments,
+            wildcard,
+            range: locator.range(i, inner_end + 1),
+            inner_range: locator.range(inner_start, inner_end),
+        });
+
+        i = inner_end + 1;
+    }
+
+    expressions
+}
+
+/// The text typed so far in an unclosed `$(` before byte `column` of a single
+/// line, e.g. `params.re` for `echo $(params.re`.
+pub fn open_expression(line: &str, column: usize) -> Option<&str> {
+    let before = &line[..column.min(line.len())];
+    let typed = &before[before.rfind("$(")? + 2..];
+    (!typed.contains(|c: char| c == ')' || c == '(' || c.is_whitespace())).then_some(typed)
+}
+
+/// Find the `)` closing an expression whose content starts at `from`.
+fn matching_paren(bytes: &[u8], from: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (offset, &b) in bytes[from..].iter().enumerate() {
+        match b {
+            b'(' => depth += 1,
+            b')' if depth == 0 => return Some(from + offset),
+            b')' => depth -= 1,
+            b'\n' => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split the expression content into segments (`a.b["c.d"][*]`).
+fn split_segments(inner: &str, base: usize, locator: &Locator) -> (Vec<Segment>, bool) {
+    let bytes = inner.as_bytes();
+    let mut segments = Vec::new();
+    let mut wildcard = false;
+    let mut start = 0;
+    let mut i = 0;
+
+    let push = |from: usize, to: usize, segments: &mut Vec<Segment>| {
+        segments.push(Segment {
+            name: inner[from..to].to_string(),
+            range: locator.range(base + from, base + to),
+        });
+    };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'.' => {
+                if i > start {
+                    push(start, i, &mut segments);
+                }
+                start = i + 1;
+                i += 1;
+            }
+            b'[' => {
+                if i > start {
+                    push(start, i, &mut segments);
+                }
+                let close = inner[i..].find(']').map(|c| i + c).unwrap_or(bytes.len());
+                let content = &inner[i + 1..close];
+                if content == "*" {
+                    wildcard = true;
+                } else if let Some(quote) =
+                    content.chars().next().filter(|c| *c == '"' || *c == '\'')
+                {
+                    let name_end = content[1..]
+                        .find(quote)
+                        .map(|e| e + 1)
+                        .unwrap_or(content.len());
+                    push(i + 2, i + 1 + name_end, &mut segments);
+                } else if !content.is_empty() {
+                    push(i + 1, close, &mut segments);
+                }
+                i = close + 1;
+                start = i;
+            }
+            _ => i += 1,
+        }
+    }
+    if start < bytes.len() {
+        push(start, bytes.len(), &mut segments);
+    }
+
+    (segments, wildcard)
+}
+
+/// Maps byte offsets within a scalar to document positions.
+pub(super) struct Locator<'t> {
+    /// The scalar's source text
+    text: &'t str,
+    /// Source offset of each byte of a decoded value, for quoted scalars
+    offsets: Option<Vec<usize>>,
+    /// Byte offset at which each line of the text starts
+    line_starts: Vec<usize>,
+    start: Position,
+    encoding: PositionEncoding,
+}
+
+impl<'t> Locator<'t> {
+    pub(super) fn new(text: &'t str, start: Position, encoding: PositionEncoding) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        Self {
+            text,
+            offsets: None,
+            line_starts,
+            start,
+            encoding,
+        }
+    }
+
+    /// Locator for the decoded value of a quoted scalar whose source text
+    /// between the quotes is `raw`.
+    pub(super) fn quoted(
+        raw: &'t str,
+        style: Style,
+        start: Position,
+        encoding: PositionEncoding,
+    ) -> Self {
+        Self {
+            offsets: Some(unquote(raw, style).offsets),
+            ..Self::new(raw, start, encoding)
+        }
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let offset = match &self.offsets {
+            Some(offsets) => offsets[offset.min(offsets.len() - 1)],
+            None => offset,
+        };
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+        let column = self
+            .encoding
+            .len(&self.text[self.line_starts[line]..offset]);
+        if line == 0 {
+            Position {
+                line: self.start.line,
+                character: self.start.character + column,
+            }
+        } else {
+            Position {
+                line: self.start.line + line as u32,
+                character: column,
+            }
+        }
+    }
+
+    pub(super) fn range(&self, from: usize, to: usize) -> Range {
+        Range {
+            start: self.position(from),
+            end: self.position(to),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn origin() -> Position {
+        Position {
+            line: 0,
+            character: 0,
+        }
+    }
+
+    #[test]
+    fn test_find_param_expression() {
+        let exprs = find_expressions(
+            "echo $(params.foo)",
+            Position {
+                line: 3,
+                character: 10,
+            },
+            PositionEncoding::Utf8,
+        );
+
+        assert_eq!(exprs.len(), 1);
+        let expr = &exprs[0];
+        assert_eq!(expr.text, "params.foo");
+        assert_eq!(expr.path(), vec!["params", "foo"]);
+        assert_eq!(
+            expr.range.start,
+            Position {
+                line: 3,
+                character: 15
+            }
+        );
+        assert_eq!(
+            expr.range.end,
+            Position {
+                line: 3,
+                character: 28
+            }
+        );
+        assert_eq!(expr.segments[1].range.start.character, 24);
+        assert!(matches!(expr.reference(), Reference::Param { name } if name.name == "foo"));
+    }
+
+    #[test]
+    fn test_task_result_and_workspace_references() {
+        let text = "$(tasks.build.results.digest) $(workspaces.src.path)";
+        let exprs = find_expressions(text, origin(), PositionEncoding::Utf8);
+
+        assert_eq!(exprs.len(), 2);
+        match exprs[0].reference() {
+            Reference::TaskResult { task, result } => {
+                assert_eq!(task.name, "build");
+                assert_eq!(result.name, "digest");
+            }
+            other => panic!("Expected task result, got {:?}", other),
+        }
+        match exprs[1].reference() {
+            Reference::Workspace { name, field } => {
+                assert_eq!(name.name, "src");
+                assert_eq!(field.unwrap().name, "path");
+            }
+            other => panic!("Expected workspace, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_finally_references() {
+        let text = "$(finally.notify.results.sent) $(finally.notify.status) $(tasks.status)";
+        let exprs = find_expressions(text, origin(), PositionEncoding::Utf8);
+
+        assert_eq!(exprs.len(), 3);
+        assert!(matches!(
+            exprs[0].reference(),
+            Reference::TaskResult { task, result } if task.name == "notify" && result.name == "sent"
+        ));
+        assert!(matches!(
+            exprs[1].reference(),
+            Reference::TaskField { task, field } if task.name == "notify" && field.name == "status"
+        ));
+        assert_eq!(exprs[2].reference(), Reference::Other);
+    }
+
+    #[test]
+    fn test_bracket_and_wildcard_segments() {
+        let exprs = find_expressions(
+            r#"$(params["my.param"]) $(params.list[*])"#,
+            origin(),
+            PositionEncoding::Utf8,
+        );
+
+        assert_eq!(exprs[0].path(), vec!["params", "my.param"]);
+        assert_eq!(exprs[0].segments[1].range.start.character, 10);
+        assert!(!exprs[0].wildcard);
+
+        assert_eq!(exprs[1].path(), vec!["params", "list"]);
+        assert!(exprs[1].wildcard);
+    }
+
+    #[test]
+    fn test_multiline_positions() {
+        let text = "|\n  echo one\n  echo $(results.out.path)\n";
+        let exprs = find_expressions(
+            text,
+            Position {
+                line: 5,
+                character: 14,
+            },
+            PositionEncoding::Utf8,
+        );
+
+        assert_eq!(exprs.len(), 1);
+        assert_eq!(
+            exprs[0].range.start,
+            Position {
+                line: 7,
+                character: 7
+            }
+        );
+        assert!(matches!(exprs[0].reference(), Reference::Result { name } if name.name == "out"));
+    }
+
+    #[test]
+    fn test_columns_use_position_encoding() {
+        let text = "🚀 $(params.foo)";
+        let utf16 = find_expressions(text, origin(), PositionEncoding::Utf16);
+        assert_eq!(utf16[0].range.start.character, 3);
+        assert_eq!(utf16[0].segments[1].range.start.character, 12);
+
+        let utf8 = find_expressions(text, origin(), PositionEncoding::Utf8);
+        assert_eq!(utf8[0].range.start.character, 5);
+    }
+
+    #[test]
+    fn test_unterminated_expression_is_ignored() {
+        assert!(find_expressions("$(params.foo", origin(), PositionEncoding::Utf8).is_empty());
+        assert!(find_expressions("no variables here", origin(), PositionEncoding::Utf8).is_empty());
+    }
+
+    #[test]
+    fn test_open_expression() {
+        assert_eq!(open_expression("echo $(", 7), Some(""));
+        assert_eq!(open_expression("echo $(params.re", 16), Some("params.re"));
+        assert_eq!(open_expression("$(params.a) $(par", 17), Some("par"));
+        assert_eq!(open_expression("$(params.a) done", 16), None);
+        assert_eq!(open_expression("$(params.a)", 5), Some("par"));
+        assert_eq!(open_expression("echo $( x", 9), None);
+        assert_eq!(open_expression("plain", 3), None);
+    }
+}
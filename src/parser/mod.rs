@@ -1,5 +1,14 @@
 mod ast;
+mod comments;
+mod encoding;
+pub mod expressions;
+pub mod positions;
+mod quoted;
+mod serializer;
+pub mod templates;
 mod yaml_parser;
 
-pub use ast::{Node, NodeValue, YamlDocument};
-pub use yaml_parser::parse_yaml;
+pub use ast::{Comment, Mapping, Node, NodeValue, Style, YamlDocument};
+pub use encoding::PositionEncoding;
+pub use serializer::{to_flow_yaml, to_yaml, to_yaml_indented};
+pub use yaml_parser::{parse_yaml, parse_yaml_with_encoding};
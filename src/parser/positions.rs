@@ -0,0 +1,156 @@
+//! Positions and ranges.
+//!
+//! Providers locate the cursor with [`contains`], which includes both ends of
+//! a range: a cursor right after a word still points at it. Ranges that are
+//! replaced or selected are half-open as in LSP, which [`contains_exclusive`]
+//! follows. [`offset`] and [`position`] convert between positions in the
+//! negotiated encoding and byte offsets of a text.
+
+use super::PositionEncoding;
+use std::cmp::Ordering;
+use tower_lsp::lsp_types::{Position, Range};
+
+/// Order two positions by line, then by column.
+pub fn compare(a: Position, b: Position) -> Ordering {
+    (a.line, a.character).cmp(&(b.line, b.character))
+}
+
+/// Whether a position falls inside a range, both ends included.
+pub fn contains(range: Range, position: Position) -> bool {
+    compare(range.start, position).is_le() && compare(position, range.end).is_le()
+}
+
+/// Whether a position falls inside a range, its end excluded.
+///
+/// An empty range contains no position.
+pub fn contains_exclusive(range: Range, position: Position) -> bool {
+    compare(range.start, position).is_le() && compare(position, range.end).is_lt()
+}
+
+/// Byte offset in `text` of a position.
+///
+/// Lines past the end of the text are clamped to its length, and columns
+/// past the end of a line to the line break.
+pub fn offset(text: &str, position: Position, encoding: PositionEncoding) -> usize {
+    let mut start = 0;
+    for _ in 0..position.line {
+        match text[start..].find('\n') {
+            Some(newline) => start += newline + 1,
+            None => return text.len(),
+        }
+    }
+    let line = line_at(text, start);
+    start + encoding.byte_offset(line, position.character)
+}
+
+/// Position of the byte offset `offset` of `text`.
+///
+/// Offsets past the end of the text or inside a character are clamped.
+pub fn position(text: &str, offset: usize, encoding: PositionEncoding) -> Position {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let start = text[..offset].rfind('\n').map_or(0, |newline| newline + 1);
+    Position {
+        line: text[..start].matches('\n').count() as u32,
+        character: encoding.column(line_at(text, start), offset - start),
+    }
+}
+
+/// The line starting at byte `start`, without its line break.
+fn line_at(text: &str, start: usize) -> &str {
+    let line = &text[start..];
+    let line = line.find('\n').map_or(line, |newline| &line[..newline]);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(line: u32, character: u32) -> Position {
+        Position { line, character }
+    }
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+        Range {
+            start: pos(start.0, start.1),
+            end: pos(end.0, end.1),
+        }
+    }
+
+    #[test]
+    fn test_contains() {
+        let r = range((1, 5), (3, 10));
+
+        assert!(contains(r, pos(2, 0)));
+        assert!(contains(r, pos(1, 5)));
+        assert!(contains(r, pos(3, 10)));
+        assert!(contains(r, pos(2, 100)));
+        assert!(!contains(r, pos(1, 4)));
+        assert!(!contains(r, pos(3, 11)));
+        assert!(!contains(r, pos(0, 7)));
+        assert!(!contains(r, pos(4, 0)));
+
+        // A cursor on an empty range is in it
+        assert!(contains(range((2, 3), (2, 3)), pos(2, 3)));
+    }
+
+    #[test]
+    fn test_contains_exclusive() {
+        let r = range((1, 5), (3, 10));
+
+        assert!(contains_exclusive(r, pos(1, 5)));
+        assert!(contains_exclusive(r, pos(3, 9)));
+        assert!(!contains_exclusive(r, pos(3, 10)));
+        assert!(!contains_exclusive(r, pos(1, 4)));
+        assert!(!contains_exclusive(range((2, 3), (2, 3)), pos(2, 3)));
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(compare(pos(1, 9), pos(2, 0)), Ordering::Less);
+        assert_eq!(compare(pos(2, 4), pos(2, 3)), Ordering::Greater);
+        assert_eq!(compare(pos(2, 3), pos(2, 3)), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_offset_and_position() {
+        // 'é' is 2 bytes / 1 UTF-16 unit, '🚀' is 4 bytes / 2 UTF-16 units
+        let text = "name: é\nvalue: 🚀x\n";
+        let x = text.find('x').unwrap();
+
+        assert_eq!(offset(text, pos(1, 9), PositionEncoding::Utf16), x);
+        assert_eq!(offset(text, pos(1, 11), PositionEncoding::Utf8), x);
+        assert_eq!(position(text, x, PositionEncoding::Utf16), pos(1, 9));
+        assert_eq!(position(text, x, PositionEncoding::Utf8), pos(1, 11));
+
+        // Start of a line, and the empty line after the last line break
+        assert_eq!(offset(text, pos(1, 0), PositionEncoding::Utf16), 9);
+        assert_eq!(position(text, 9, PositionEncoding::Utf16), pos(1, 0));
+        assert_eq!(
+            position(text, text.len(), PositionEncoding::Utf16),
+            pos(2, 0)
+        );
+
+        // Clamped past the end of a line and of the text
+        assert_eq!(offset(text, pos(0, 100), PositionEncoding::Utf16), 8);
+        assert_eq!(offset(text, pos(5, 0), PositionEncoding::Utf16), text.len());
+        assert_eq!(position(text, 1000, PositionEncoding::Utf16), pos(2, 0));
+
+        // Clamped to the start of the character an offset falls inside
+        assert_eq!(position("é", 1, PositionEncoding::Utf16), pos(0, 0));
+        assert_eq!(position(text, x - 1, PositionEncoding::Utf16), pos(1, 7));
+    }
+
+    #[test]
+    fn test_offset_with_crlf() {
+        let text = "a: 1\r\nb: 2\r\n";
+
+        // Columns past the end stop before the carriage return
+        assert_eq!(offset(text, pos(0, 100), PositionEncoding::Utf16), 4);
+        assert_eq!(offset(text, pos(1, 3), PositionEncoding::Utf16), 9);
+        assert_eq!(position(text, 9, PositionEncoding::Utf16), pos(1, 3));
+    }
+}
@@ -0,0 +1,166 @@
+//! Decoding of quoted flow scalars (`'...'`, `"..."`).
+//!
+//! The decoded value of a quoted scalar can differ in length from its source:
+//! `''` and escapes shrink it and line folding replaces line breaks and
+//! indentation. Decoding also returns where each byte of the value comes from
+//! in the source so that ranges found in the value can be mapped back.
+
+use super::ast::Style;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// A decoded quoted scalar.
+pub(super) struct Unquoted {
+    /// The value the scalar stands for
+    pub value: String,
+    /// Source byte offset of each byte of `value`, followed by the source length
+    pub offsets: Vec<usize>,
+}
+
+/// Decode the text between the quotes of a quoted scalar.
+///
+/// `style` tells the quoting: `''` is an escaped quote in single-quoted
+/// scalars, backslash escapes are decoded in double-quoted ones. Line breaks
+/// are folded in both: a single break becomes a space, each further break a
+/// newline, and the indentation of the continuation line is dropped.
+pub(super) fn unquote(raw: &str, style: Style) -> Unquoted {
+    let mut out = Unquoted {
+        value: String::with_capacity(raw.len()),
+        offsets: Vec::with_capacity(raw.len() + 1),
+    };
+    // Whitespace is only content if something other than a line break follows it
+    let mut pending: Vec<(usize, char)> = Vec::new();
+    let mut chars = raw.char_indices().peekable();
+
+    while let Some((at, c)) = chars.next() {
+        match c {
+            ' ' | '\t' | '\r' => pending.push((at, c)),
+            '\n' => {
+                pending.clear();
+                let mut breaks = 1;
+                while let Some(&(_, next)) = chars.peek() {
+                    match next {
+                        ' ' | '\t' | '\r' => {}
+                        '\n' => breaks += 1,
+                        _ => break,
+                    }
+                    chars.next();
+                }
+                if breaks == 1 {
+                    out.push(' ', at);
+                } else {
+                    (1..breaks).for_each(|_| out.push('\n', at));
+                }
+            }
+            '\'' if style == Style::SingleQuoted && matches!(chars.peek(), Some((_, '\''))) => {
+                out.flush(&mut pending);
+                chars.next();
+                out.push('\'', at);
+            }
+            '\\' if style == Style::DoubleQuoted => {
+                out.flush(&mut pending);
+                decode_escape(raw, at, &mut chars, &mut out);
+            }
+            _ => {
+                out.flush(&mut pending);
+                out.push(c, at);
+            }
+        }
+    }
+    out.flush(&mut pending);
+    out.offsets.push(raw.len());
+    out
+}
+
+impl Unquoted {
+    fn push(&mut self, c: char, at: usize) {
+        self.value.push(c);
+        self.offsets.extend(std::iter::repeat_n(at, c.len_utf8()));
+    }
+
+    fn flush(&mut self, pending: &mut Vec<(usize, char)>) {
+        for (at, c) in pending.drain(..) {
+            self.push(c, at);
+        }
+    }
+}
+
+/// Decode the escape whose backslash is at `at`, consuming it from `chars`.
+///
+/// An escaped line break joins the lines without a space. Unknown or
+/// malformed escapes are kept as written.
+fn decode_escape(raw: &str, at: usize, chars: &mut Peekable<CharIndices<'_>>, out: &mut Unquoted) {
+    let Some((escape_at, escape)) = chars.next() else {
+        out.push('\\', at);
+        return;
+    };
+    let decoded = match escape {
+        '0' => '\0',
+        'a' => '\x07',
+        'b' => '\x08',
+        't' | '\t' => '\t',
+        'n' => '\n',
+        'v' => '\x0b',
+        'f' => '\x0c',
+        'r' => '\r',
+        'e' => '\x1b',
+        ' ' | '"' | '/' | '\\' => escape,
+        'N' => '\u{85}',
+        '_' => '\u{a0}',
+        'L' => '\u{2028}',
+        'P' => '\u{2029}',
+        '\r' | '\n' => {
+            while let Some((_, ' ' | '\t' | '\r' | '\n')) = chars.peek() {
+                chars.next();
+            }
+            return;
+        }
+        'x' | 'u' | 'U' => {
+            let digits = match escape {
+                'x' => 2,
+                'u' => 4,
+                _ => 8,
+            };
+            let code = raw
+                .get(escape_at + 1..escape_at + 1 + digits)
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .and_then(char::from_u32);
+            match code {
+                Some(decoded) => {
+                    (0..digits).for_each(|_| {
+                        chars.next();
+                    });
+                    decoded
+                }
+                None => {
+                    out.push('\\', at);
+                    out.push(escape, escape_at);
+                    return;
+                }
+            }
+        }
+        _ => {
+            out.push('\\', at);
+            out.push(escape, escape_at);
+            return;
+        }
+    };
+    out.push(decoded, at);
+}
+
+/// Escape `text` for use between double quotes.
+pub(super) fn escape_double_quoted(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
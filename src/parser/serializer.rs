@@ -0,0 +1,321 @@
+//! Serialization of the AST back to YAML text.
+//!
+//! Mappings are written in document order, flow collections and quoted
+//! scalars keep their source style, block scalars are re-indented to their
+//! new position, and attached comments are written back next to their node.
+
+use super::ast::{Node, NodeValue, Style};
+use super::quoted::escape_double_quoted;
+
+/// Number of spaces per nesting level
+const INDENT: usize = 2;
+
+/// Serialize a node as a YAML document.
+pub fn to_yaml(node: &Node) -> String {
+    to_yaml_indented(node, 0)
+}
+
+/// Serialize a node as YAML whose top level starts at column `indent`.
+///
+/// Useful for producing text to insert below an existing key.
+pub fn to_yaml_indented(node: &Node, indent: usize) -> String {
+    let mut out = String::new();
+    match &node.value {
+        NodeValue::Mapping(_) if node.style != Style::Flow => write_mapping(node, indent, &mut out),
+        NodeValue::Sequence(_) if node.style != Style::Flow => {
+            write_sequence(node, indent, &mut out)
+        }
+        _ => {
+            write_comments(node, indent, &mut out);
+            out.push_str(&" ".repeat(indent));
+            out.push_str(&inline_value(node));
+            write_trailing_comment(node, &mut out);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Serialize a node on one line, with mappings and sequences in flow style.
+pub fn to_flow_yaml(node: &Node) -> String {
+    inline_value(node)
+}
+
+/// Write the entries of a block mapping, one per line at `indent`.
+fn write_mapping(node: &Node, indent: usize, out: &mut String) {
+    let pad = " ".repeat(indent);
+    for (key, child) in node.entries() {
+        write_comments(child, indent, out);
+        out.push_str(&pad);
+        out.push_str(&format_scalar(key, Style::Default));
+        out.push(':');
+        write_entry_value(child, indent, out);
+    }
+}
+
+/// Write a block sequence, one `- ` item per line at `indent`.
+fn write_sequence(node: &Node, indent: usize, out: &mut String) {
+    let NodeValue::Sequence(items) = &node.value else {
+        return;
+    };
+    let pad = " ".repeat(indent);
+
+    for item in items {
+        write_comments(item, indent, out);
+        match &item.value {
+            NodeValue::Mapping(map) if !map.is_empty() && item.style != Style::Flow => {
+                // The first entry shares the line with the dash
+                let mut body = String::new();
+                write_mapping(item, indent + INDENT, &mut body);
+                out.push_str(&pad);
+                out.push_str("- ");
+                out.push_str(&body[indent + INDENT..]);
+            }
+            NodeValue::Sequence(nested) if !nested.is_empty() && item.style != Style::Flow => {
+                out.push_str(&pad);
+                out.push_str("-\n");
+                write_sequence(item, indent + INDENT, out);
+            }
+            _ if is_block_scalar(item) => {
+                out.push_str(&pad);
+                out.push_str("- ");
+                write_block_scalar(item, indent + INDENT, out);
+            }
+            _ => {
+                out.push_str(&pad);
+                out.push('-');
+                let value = inline_value(item);
+                if !value.is_empty() {
+                    out.push(' ');
+                    out.push_str(&value);
+                }
+                write_trailing_comment(item, out);
+                out.push('\n');
+            }
+        }
+    }
+}
+
+/// Write the value of a mapping entry, after its `key:`.
+fn write_entry_value(child: &Node, indent: usize, out: &mut String) {
+    match &child.value {
+        NodeValue::Mapping(map) if !map.is_empty() && child.style != Style::Flow => {
+            write_trailing_comment(child, out);
+            out.push('\n');
+            write_mapping(child, indent + INDENT, out);
+        }
+        NodeValue::Sequence(items) if !items.is_empty() && child.style != Style::Flow => {
+            write_trailing_comment(child, out);
+            out.push('\n');
+            write_sequence(child, indent + INDENT, out);
+        }
+        _ if is_block_scalar(child) => {
+            out.push(' ');
+            write_block_scalar(child, indent + INDENT, out);
+        }
+        _ => {
+            let value = inline_value(child);
+            if !value.is_empty() {
+                out.push(' ');
+                out.push_str(&value);
+            }
+            write_trailing_comment(child, out);
+            out.push('\n');
+        }
+    }
+}
+
+/// Render a value that fits on one line: scalars, nulls and flow collections.
+fn inline_value(node: &Node) -> String {
+    match &node.value {
+        NodeValue::Scalar(text) => format_scalar(text, node.style),
+        NodeValue::Null => String::new(),
+        NodeValue::Mapping(_) => {
+            let entries: Vec<String> = node
+                .entries()
+                .map(|(key, child)| match &child.value {
+                    NodeValue::Null => format_scalar(key, Style::Default),
+                    _ => format!(
+                        "{}: {}",
+                        format_scalar(key, Style::Default),
+                        inline_value(child)
+                    ),
+                })
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+        NodeValue::Sequence(items) => {
+            let items: Vec<String> = items.iter().map(inline_value).collect();
+            format!("[{}]", items.join(", "))
+        }
+    }
+}
+
+/// Render a scalar in the given style, quoting plain scalars that need it.
+fn format_scalar(text: &str, style: Style) -> String {
+    match style {
+        Style::SingleQuoted => format!("'{}'", text.replace('\'', "''")),
+        Style::DoubleQuoted => format!("\"{}\"", escape_double_quoted(text)),
+        Style::Default | Style::Flow if needs_quotes(text) => {
+            format!("'{}'", text.replace('\'', "''"))
+        }
+        Style::Default | Style::Flow => text.to_string(),
+    }
+}
+
+/// Whether a plain scalar would be read back differently without quotes.
+fn needs_quotes(text: &str) -> bool {
+    const INDICATORS: &[char] = &[
+        '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@',
+        '`',
+    ];
+
+    if text.is_empty() || text.trim() != text || text.contains('\n') {
+        return true;
+    }
+    if text.contains(": ") || text.contains(" #") || text.ends_with(':') {
+        return true;
+    }
+    // `-foo` is a plain scalar but `- foo` and `-` are not
+    match text.chars().next() {
+        Some('-') => text.len() == 1 || text.starts_with("- ") || text.starts_with("---"),
+        Some(c) => INDICATORS.contains(&c),
+        None => true,
+    }
+}
+
+/// Whether the node is a `|` or `>` block scalar.
+fn is_block_scalar(node: &Node) -> bool {
+    node.style == Style::Default
+        && matches!(&node.value, NodeValue::Scalar(text) if text.starts_with('|') || text.starts_with('>'))
+}
+
+/// Write a block scalar: its header, then its content re-indented to `indent`.
+fn write_block_scalar(node: &Node, indent: usize, out: &mut String) {
+    let Some(text) = node.as_scalar() else {
+        return;
+    };
+    let mut lines = text.lines();
+    out.push_str(lines.next().unwrap_or("|"));
+    write_trailing_comment(node, out);
+    out.push('\n');
+
+    let content: Vec<&str> = lines.collect();
+    let common = content
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let pad = " ".repeat(indent);
+    for line in content {
+        if line.trim().is_empty() {
+            out.push('\n');
+        } else {
+            out.push_str(&pad);
+            out.push_str(&line[common..]);
+            out.push('\n');
+        }
+    }
+}
+
+/// Write a node's leading comments on their own lines at `indent`.
+fn write_comments(node: &Node, indent: usize, out: &mut String) {
+    for comment in &node.leading_comments {
+        out.push_str(&" ".repeat(indent));
+        out.push_str(&comment.text);
+        out.push('\n');
+    }
+}
+
+/// Append a node's trailing comment to the current line.
+fn write_trailing_comment(node: &Node, out: &mut String) {
+    if let Some(comment) = &node.trailing_comment {
+        out.push(' ');
+        out.push_str(&comment.text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_yaml;
+
+    #[test]
+    fn test_round_trip_preserves_order_styles_and_comments() {
+        let yaml = r#"# Build task
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+  labels: {app: demo, tier: ci}
+spec:
+  params:
+    - name: flags
+      type: array
+      default: ["-v", '--race']
+  steps:
+    # compile the sources
+    - name: compile # main step
+      image: golang
+      script: |
+        go build ./...
+        echo "done: $(params.flags[*])"
+      args:
+        - -o
+        - out
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        assert_eq!(to_yaml(&doc.root), yaml);
+    }
+
+    #[test]
+    fn test_built_tree_serialization() {
+        let step = Node::mapping([
+            ("name", Node::scalar("step-1")),
+            ("image", Node::scalar("alpine")),
+        ]);
+        let spec = Node::mapping([
+            ("description", Node::scalar("Build: all")),
+            ("steps", Node::sequence([step])),
+            ("workspaces", Node::sequence([])),
+            ("timeout", Node::null()),
+        ]);
+
+        assert_eq!(
+            to_yaml_indented(&Node::mapping([("spec", spec)]), 2),
+            "  spec:\n    description: 'Build: all'\n    steps:\n      - name: step-1\n        image: alpine\n    workspaces: []\n    timeout:\n"
+        );
+    }
+
+    #[test]
+    fn test_block_scalar_is_reindented() {
+        let doc = parse_yaml("test.yaml", "script: |\n    echo a\n      echo b\n").unwrap();
+        let script = doc.root.get("script").unwrap().clone();
+
+        let nested = Node::mapping([("step", Node::mapping([("script", script)]))]);
+        assert_eq!(
+            to_yaml(&nested),
+            "step:\n  script: |\n    echo a\n      echo b\n"
+        );
+    }
+
+    #[test]
+    fn test_quoted_scalars_round_trip() {
+        let yaml = "a: 'it''s'\nb: \"say \\\"hi\\\"\\n\"\n";
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        assert_eq!(to_yaml(&doc.root), yaml);
+    }
+
+    #[test]
+    fn test_needs_quotes() {
+        assert!(needs_quotes(""));
+        assert!(needs_quotes("a: b"));
+        assert!(needs_quotes("- item"));
+        assert!(needs_quotes("*alias"));
+        assert!(!needs_quotes("-v"));
+        assert!(!needs_quotes("tekton.dev/v1"));
+        assert!(!needs_quotes("$(params.foo)"));
+    }
+}
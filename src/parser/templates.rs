@@ -0,0 +1,113 @@
+//! `{{ ... }}` template variables, as substituted by Pipelines-as-Code.
+//!
+//! Finds the variables inside scalar values, with the range of the whole
+//! template and of the variable name.
+
+use super::ast::{Node, NodeValue};
+use super::encoding::PositionEncoding;
+use super::expressions::Locator;
+use tower_lsp::lsp_types::{Position, Range};
+
+/// A `{{ name }}` template found inside a scalar value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    /// The variable name, without the surrounding spaces, e.g. `revision`
+    pub name: String,
+    /// Range of the whole template, including `{{` and `}}`
+    pub range: Range,
+    /// Range of the variable name
+    pub name_range: Range,
+}
+
+/// Find all templates in a scalar node's value.
+///
+/// `encoding` is the position encoding of the document the node comes from.
+pub fn templates_in(node: &Node, encoding: PositionEncoding) -> Vec<Template> {
+    match &node.value {
+        NodeValue::Scalar(text) => find_templates(text, node.value_range.start, encoding),
+        _ => Vec::new(),
+    }
+}
+
+/// Find all templates in `text`, which starts at `start` in the document.
+pub fn find_templates(text: &str, start: Position, encoding: PositionEncoding) -> Vec<Template> {
+    let locator = Locator::new(text, start, encoding);
+    let mut templates = Vec::new();
+    let mut from = 0;
+
+    while let Some(open) = text[from..].find("{{").map(|o| from + o) {
+        let inner_start = open + 2;
+        let Some(close) = text[inner_start..].find("}}").map(|c| inner_start + c) else {
+            break;
+        };
+        let inner = &text[inner_start..close];
+        // Templates do not span lines
+        if inner.contains('\n') {
+            from = inner_start;
+            continue;
+        }
+
+        let name = inner.trim();
+        let name_start = inner_start + (inner.len() - inner.trim_start().len());
+        templates.push(Template {
+            name: name.to_string(),
+            range: locator.range(open, close + 2),
+            name_range: locator.range(name_start, name_start + name.len()),
+        });
+        from = close + 2;
+    }
+
+    templates
+}
+
+/// Whether `column` of a single-line text, starting at `start`, is inside an
+/// unclosed `{{`: after the last `{{` and before any `}}` following it.
+pub fn in_open_template(line: &str, column: usize) -> bool {
+    let before = &line[..column.min(line.len())];
+    match (before.rfind("{{"), before.rfind("}}")) {
+        (Some(open), Some(close)) => open > close,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_templates() {
+        let start = Position {
+            line: 3,
+            character: 10,
+        };
+        let templates = find_templates(
+            "{{ repo_url }}@{{revision}} {{ unclosed",
+            start,
+            PositionEncoding::Utf16,
+        );
+        let found: Vec<_> = templates
+            .iter()
+            .map(|t| {
+                (
+                    t.name.as_str(),
+                    t.range.start.character,
+                    t.name_range.start.character,
+                    t.name_range.end.character,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![("repo_url", 10, 13, 21), ("revision", 25, 27, 35)]
+        );
+    }
+
+    #[test]
+    fn test_in_open_template() {
+        assert!(in_open_template("{{ rev", 6));
+        assert!(!in_open_template("{{ revision }} ", 15));
+        assert!(in_open_template("{{ a }} {{ ", 11));
+        assert!(!in_open_template("plain", 3));
+    }
+}
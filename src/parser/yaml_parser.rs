@@ -1,48 +1,230 @@
-use super::ast::{Node, NodeValue, YamlDocument};
+use super::ast::{Mapping, Node, NodeValue, Style, YamlDocument};
+use super::comments::{attach_comments, collect_comments};
+use super::encoding::{LineIndex, PositionEncoding};
+use super::quoted::unquote;
+use crate::error::ParseError;
 use std::collections::HashMap;
 use tower_lsp::lsp_types::{Position, Range};
 use tree_sitter::Parser;
 
 /// Parse YAML content into a document with accurate position tracking using tree-sitter
-pub fn parse_yaml(filename: &str, content: &str) -> Result<YamlDocument, String> {
+///
+/// tree-sitter recovers from syntax errors, so a document with errors still parses;
+/// the errors are reported in [`YamlDocument::syntax_errors`]. Positions count
+/// UTF-16 code units, the LSP default.
+pub fn parse_yaml(filename: &str, content: &str) -> Result<YamlDocument, ParseError> {
+    parse_yaml_with_encoding(filename, content, PositionEncoding::default())
+}
+
+/// Parse YAML content into a document whose positions use `encoding`.
+pub fn parse_yaml_with_encoding(
+    filename: &str,
+    content: &str,
+    encoding: PositionEncoding,
+) -> Result<YamlDocument, ParseError> {
     let mut parser = Parser::new();
     parser
         .set_language(&tree_sitter_yaml::LANGUAGE.into())
-        .map_err(|e| format!("Failed to set language: {}", e))?;
+        .map_err(|e| ParseError::Language(e.to_string()))?;
 
-    let tree = parser
-        .parse(content, None)
-        .ok_or_else(|| "Failed to parse YAML".to_string())?;
+    let tree = parser.parse(content, None).ok_or(ParseError::NoTree)?;
 
     // Build AST from tree-sitter syntax tree
     let root_node = tree.root_node();
-    let root = build_ast_from_tree_sitter(&root_node, content, None)?;
+    let mut anchors = Anchors::default();
+    let mut root = build_ast_from_tree_sitter(&root_node, content, None, &mut anchors).map_err(
+        |mut error| {
+            if let ParseError::AliasExpansion { range, .. } = &mut error {
+                LineIndex::new(content, encoding).convert(range);
+            }
+            error
+        },
+    )?;
+
+    // Keep comments so edits can round-trip them
+    let mut comments = Vec::new();
+    collect_comments(&root_node, content, &mut comments);
+    attach_comments(&mut root, &comments, content);
+
+    let mut doc = YamlDocument::new(filename.to_string(), root);
+    doc.comments = comments;
+    doc.encoding = encoding;
+    doc.source = content.to_string();
+    if root_node.has_error() {
+        collect_syntax_errors(&root_node, &mut doc.syntax_errors);
+    }
+
+    // Everything above works with tree-sitter's byte columns
+    if encoding != PositionEncoding::Utf8 {
+        convert_positions(&mut doc, &LineIndex::new(content, encoding));
+    }
+
+    Ok(doc)
+}
+
+/// Convert every range of the document from byte columns.
+fn convert_positions(doc: &mut YamlDocument, index: &LineIndex) {
+    fn convert_node(node: &mut Node, index: &LineIndex) {
+        index.convert(&mut node.range);
+        index.convert(&mut node.value_range);
+        if let Some(key_range) = &mut node.key_range {
+            index.convert(key_range);
+        }
+        let comments = node
+            .leading_comments
+            .iter_mut()
+            .chain(node.trailing_comment.as_mut());
+        comments.for_each(|comment| index.convert(&mut comment.range));
+        match &mut node.value {
+            NodeValue::Mapping(map) => map
+                .values_mut()
+                .for_each(|child| convert_node(child, index)),
+            NodeValue::Sequence(items) => {
+                items.iter_mut().for_each(|item| convert_node(item, index))
+            }
+            _ => {}
+        }
+    }
+
+    convert_node(&mut doc.root, index);
+    doc.comments
+        .iter_mut()
+        .for_each(|comment| index.convert(&mut comment.range));
+    for error in &mut doc.syntax_errors {
+        if let ParseError::Syntax { range, .. } = error {
+            index.convert(range);
+        }
+    }
+}
+
+/// Collect ERROR and MISSING nodes from the syntax tree
+fn collect_syntax_errors(ts_node: &tree_sitter::Node, errors: &mut Vec<ParseError>) {
+    if ts_node.is_error() {
+        errors.push(ParseError::Syntax {
+            message: "Invalid YAML syntax".to_string(),
+            range: node_to_range(ts_node),
+        });
+        return;
+    }
+    if ts_node.is_missing() {
+        errors.push(ParseError::Syntax {
+            message: format!("Invalid YAML syntax: missing '{}'", ts_node.kind()),
+            range: node_to_range(ts_node),
+        });
+        return;
+    }
+
+    let mut cursor = ts_node.walk();
+    for child in ts_node.children(&mut cursor) {
+        if child.has_error() {
+            collect_syntax_errors(&child, errors);
+        }
+    }
+}
+
+/// Key of a YAML merge entry (`<<: *base`)
+const MERGE_KEY: &str = "<<";
+
+/// Expand a merge entry into `mapping`.
+///
+/// `source` is the (alias-resolved) value of the `<<` entry: a mapping or a
+/// sequence of mappings. Keys already present win, as do earlier mappings of
+/// a sequence; keys written after the `<<` entry replace the merged ones when
+/// they are inserted. Merged entries keep the ranges of the anchored mapping
+/// they come from, so diagnostics on them point at the definition.
+fn merge_into(mapping: &mut Mapping, source: &Node) {
+    let sources = match &source.value {
+        NodeValue::Sequence(items) => items.iter().collect(),
+        _ => vec![source],
+    };
+    for source in sources {
+        for (key, entry) in source.entries() {
+            if !mapping.contains_key(key) {
+                mapping.insert(key.to_string(), entry.clone());
+            }
+        }
+    }
+}
+
+/// Maximum number of nodes aliases may expand to in a document.
+///
+/// Each alias copies the anchored content, so nested aliases grow
+/// exponentially ("billion laughs"); a small file could otherwise keep the
+/// parser busy for ever.
+const MAX_ALIAS_NODES: usize = 100_000;
+
+/// The anchored nodes of a document and the number of nodes aliases expanded to.
+#[derive(Default)]
+struct Anchors {
+    /// Anchored node and the number of nodes in it, by anchor name
+    nodes: HashMap<String, (Node, usize)>,
+    /// Nodes copied by the aliases resolved so far
+    expanded: usize,
+}
+
+impl Anchors {
+    fn register(&mut self, name: String, node: &Node) {
+        self.nodes.insert(name, (node.clone(), node_count(node)));
+    }
+
+    /// The anchored node an alias at `range` stands for, counted against
+    /// [`MAX_ALIAS_NODES`].
+    fn resolve(&mut self, name: &str, range: Range) -> Result<Option<&Node>, ParseError> {
+        let Some((node, count)) = self.nodes.get(name) else {
+            return Ok(None);
+        };
+        self.expanded += count;
+        if self.expanded > MAX_ALIAS_NODES {
+            return Err(ParseError::AliasExpansion {
+                limit: MAX_ALIAS_NODES,
+                range,
+            });
+        }
+        Ok(Some(node))
+    }
+}
 
-    Ok(YamlDocument::new(filename.to_string(), root))
+/// Number of nodes in the tree rooted at `node`.
+fn node_count(node: &Node) -> usize {
+    let children = match &node.value {
+        NodeValue::Mapping(map) => map.values().map(node_count).sum(),
+        NodeValue::Sequence(items) => items.iter().map(node_count).sum(),
+        _ => 0,
+    };
+    1 + children
 }
 
 /// Convert tree-sitter node to our AST representation
+///
+/// `anchors` collects the anchored nodes seen so far, so that later aliases
+/// and merge keys (`<<: *anchor`) can be resolved. Fails when aliases expand
+/// to more than [`MAX_ALIAS_NODES`] nodes.
 fn build_ast_from_tree_sitter(
     ts_node: &tree_sitter::Node,
     content: &str,
     key: Option<String>,
-) -> Result<Node, String> {
+    anchors: &mut Anchors,
+) -> Result<Node, ParseError> {
     let range = node_to_range(ts_node);
     let node_kind = ts_node.kind();
 
     // Handle different YAML node types
     let node_value = match node_kind {
         "stream" | "document" => {
-            // Root nodes - process children
-            if let Some(child) = ts_node.child(0) {
-                return build_ast_from_tree_sitter(&child, content, key);
+            // Root nodes - process the first child that is not a comment
+            let mut cursor = ts_node.walk();
+            let content_child = ts_node
+                .children(&mut cursor)
+                .find(|c| c.kind() != "comment");
+            if let Some(child) = content_child {
+                return build_ast_from_tree_sitter(&child, content, key, anchors);
             }
             NodeValue::Null
         }
 
         "block_mapping" | "flow_mapping" => {
             // YAML mapping (dictionary/object)
-            let mut mapping = HashMap::new();
+            let mut mapping = Mapping::new();
 
             let mut cursor = ts_node.walk();
             for child in ts_node.children(&mut cursor) {
@@ -51,20 +233,52 @@ fn build_ast_from_tree_sitter(
                     if let Some(key_node) = child.child_by_field_name("key") {
                         let key_text = extract_text(&key_node, content);
 
-                        if let Some(value_node) = child.child_by_field_name("value") {
-                            // Use the position of the entire pair (key + value), not just the value
-                            // This ensures hover/goto-definition works on the key name
-                            let pair_range = node_to_range(&child);
-                            let value_ast = build_ast_from_tree_sitter(&value_node, content, Some(key_text.clone()))?;
-
-                            // Create a new node with the pair's range but the value's content
-                            let node_with_correct_range = Node::new(
-                                Some(key_text.clone()),
-                                value_ast.value,
-                                pair_range
-                            );
-                            mapping.insert(key_text, node_with_correct_range);
-                        }
+                        // Use the position of the entire pair (key + value), not just the value
+                        // This ensures hover/goto-definition works on the key name
+                        let pair_range = node_to_range(&child);
+                        let key_range = node_to_range(&key_node);
+
+                        let (value, value_range, style, raw) =
+                            match child.child_by_field_name("value") {
+                                Some(value_node) => {
+                                    let value_ast = build_ast_from_tree_sitter(
+                                        &value_node,
+                                        content,
+                                        Some(key_text.clone()),
+                                        anchors,
+                                    )?;
+                                    if key_text == MERGE_KEY {
+                                        merge_into(&mut mapping, &value_ast);
+                                        continue;
+                                    }
+                                    (
+                                        value_ast.value,
+                                        value_ast.range,
+                                        value_ast.style,
+                                        value_ast.raw,
+                                    )
+                                }
+                                // `key:` with nothing after it is a null entry; keep it so that
+                                // completion and validation can see the key being typed
+                                None => (
+                                    NodeValue::Null,
+                                    Range {
+                                        start: pair_range.end,
+                                        end: pair_range.end,
+                                    },
+                                    Style::Default,
+                                    None,
+                                ),
+                            };
+
+                        // Create a new node with the pair's range but the value's content,
+                        // keeping the key and value token ranges for precise targeting
+                        let mut node_with_correct_range =
+                            Node::new(Some(key_text.clone()), value, pair_range)
+                                .with_ranges(Some(key_range), value_range)
+                                .with_style(style);
+                        node_with_correct_range.raw = raw;
+                        mapping.insert(key_text, node_with_correct_range);
                     }
                 }
             }
@@ -79,28 +293,98 @@ fn build_ast_from_tree_sitter(
             for child in ts_node.children(&mut cursor) {
                 if child.kind() == "block_sequence_item" {
                     // Block sequence item contains the actual value
-                    if let Some(value_node) = child.child(1) { // Skip the '-' marker
-                        items.push(build_ast_from_tree_sitter(&value_node, content, None)?);
+                    if let Some(value_node) = child.child(1) {
+                        // Skip the '-' marker
+                        items.push(build_ast_from_tree_sitter(
+                            &value_node,
+                            content,
+                            None,
+                            anchors,
+                        )?);
                     }
                 } else if child.kind() == "flow_node" {
-                    items.push(build_ast_from_tree_sitter(&child, content, None)?);
+                    items.push(build_ast_from_tree_sitter(&child, content, None, anchors)?);
                 }
             }
             NodeValue::Sequence(items)
         }
 
-        "plain_scalar" | "single_quote_scalar" | "double_quote_scalar" | "block_scalar" => {
+        "single_quote_scalar" | "double_quote_scalar" => {
+            // Quoted scalars: store the decoded value and narrow the range to the
+            // text between the quotes
+            let style = if node_kind == "single_quote_scalar" {
+                Style::SingleQuoted
+            } else {
+                Style::DoubleQuoted
+            };
+            let text = extract_text(ts_node, content);
+            let raw = text.get(1..text.len().saturating_sub(1)).unwrap_or("");
+            let value = unquote(raw, style).value;
+
+            let mut inner_range = range;
+            if text.len() >= 2 {
+                inner_range.start.character += 1;
+                inner_range.end.character -= 1;
+            }
+            let mut node = Node::new(key, NodeValue::Scalar(value), inner_range).with_style(style);
+            if node.as_scalar() != Some(raw) {
+                node.raw = Some(raw.to_string());
+            }
+            return Ok(node);
+        }
+
+        "plain_scalar" | "block_scalar" => {
             // Scalar values (strings, numbers, etc.)
             let text = extract_text(ts_node, content);
             NodeValue::Scalar(text)
         }
 
-        "flow_node" => {
-            // Flow node wrapper - recurse to actual content
-            if let Some(child) = ts_node.child(0) {
-                return build_ast_from_tree_sitter(&child, content, key);
+        "block_node" | "flow_node" => {
+            // Node wrapper - skip the anchor and tag properties and recurse to the content,
+            // registering anchored content for later aliases
+            let mut anchor = None;
+            let mut value = None;
+            let mut cursor = ts_node.walk();
+            for child in ts_node.children(&mut cursor) {
+                match child.kind() {
+                    "anchor" => {
+                        anchor = child
+                            .children(&mut child.walk())
+                            .find(|c| c.kind() == "anchor_name")
+                            .map(|name| extract_text(&name, content));
+                    }
+                    "tag" | "comment" => {}
+                    _ if value.is_none() => {
+                        value = Some(build_ast_from_tree_sitter(
+                            &child,
+                            content,
+                            key.clone(),
+                            anchors,
+                        )?);
+                    }
+                    _ => {}
+                }
             }
-            NodeValue::Null
+            let node = value.unwrap_or_else(|| Node::new(key, NodeValue::Null, range));
+            if let Some(anchor) = anchor {
+                anchors.register(anchor, &node);
+            }
+            return Ok(node);
+        }
+
+        "alias" => {
+            // `*name` stands for a copy of the anchored content; unknown aliases are null
+            let name = ts_node
+                .children(&mut ts_node.walk())
+                .find(|c| c.kind() == "alias_name")
+                .map(|name| extract_text(&name, content))
+                .unwrap_or_default();
+            return Ok(match anchors.resolve(&name, range)? {
+                Some(anchored) => {
+                    Node::new(key, anchored.value.clone(), range).with_style(anchored.style)
+                }
+                None => Node::new(key, NodeValue::Null, range),
+            });
         }
 
         "null" | "null_scalar" => NodeValue::Null,
@@ -109,7 +393,7 @@ fn build_ast_from_tree_sitter(
             // For other node types, try to extract text or recurse
             if ts_node.child_count() > 0 {
                 if let Some(child) = ts_node.child(0) {
-                    return build_ast_from_tree_sitter(&child, content, key);
+                    return build_ast_from_tree_sitter(&child, content, key, anchors);
                 }
             }
             let text = extract_text(ts_node, content);
@@ -121,11 +405,15 @@ fn build_ast_from_tree_sitter(
         }
     };
 
-    Ok(Node::new(key, node_value, range))
+    let style = match node_kind {
+        "flow_mapping" | "flow_sequence" => Style::Flow,
+        _ => Style::Default,
+    };
+    Ok(Node::new(key, node_value, range).with_style(style))
 }
 
 /// Convert tree-sitter node position to LSP Range
-fn node_to_range(ts_node: &tree_sitter::Node) -> Range {
+pub(super) fn node_to_range(ts_node: &tree_sitter::Node) -> Range {
     let start = ts_node.start_position();
     let end = ts_node.end_position();
 
@@ -152,6 +440,7 @@ fn extract_text(ts_node: &tree_sitter::Node, content: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::expressions::expressions_in;
 
     #[test]
     fn test_parse_simple_yaml() {
@@ -209,6 +498,21 @@ spec:
         let _ = result;
     }
 
+    #[test]
+    fn test_syntax_errors_are_collected() {
+        let yaml = "kind: Task\nspec:\n  steps: [a, b\n";
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+
+        assert!(
+            !doc.syntax_errors.is_empty(),
+            "Unclosed flow sequence should be reported"
+        );
+        assert!(doc.syntax_errors.iter().all(|e| e.range().is_some()));
+
+        let valid = parse_yaml("test.yaml", "kind: Task\n").unwrap();
+        assert!(valid.syntax_errors.is_empty());
+    }
+
     #[test]
     fn test_accurate_position_tracking() {
         let yaml = r#"apiVersion: tekton.dev/v1
@@ -228,16 +532,231 @@ spec:
 
         // Check metadata node (starts at line 2)
         let metadata = doc.root.get("metadata").unwrap();
-        assert_eq!(metadata.range.start.line, 2, "metadata should start at line 2");
+        assert_eq!(
+            metadata.range.start.line, 2,
+            "metadata should start at line 2"
+        );
 
         // Check spec node (starts at line 5)
         let spec = doc.root.get("spec").unwrap();
         assert_eq!(spec.range.start.line, 5, "spec should start at line 5");
 
         // Character positions should be accurate, not placeholder
-        assert_ne!(metadata.range.end.character, 100, "should not have placeholder character position");
+        assert_ne!(
+            metadata.range.end.character, 100,
+            "should not have placeholder character position"
+        );
 
         // Verify character position is reasonable (metadata ends somewhere on its last line)
-        assert!(metadata.range.end.character > 0, "should have real character position");
+        assert!(
+            metadata.range.end.character > 0,
+            "should have real character position"
+        );
+    }
+
+    #[test]
+    fn test_key_and_value_ranges() {
+        let yaml = r#"kind: Task
+spec:
+  steps:
+    - image: alpine
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+
+        let kind = doc.root.get("kind").unwrap();
+        let key_range = kind.key_range.expect("map entries should have a key range");
+        assert_eq!((key_range.start.character, key_range.end.character), (0, 4));
+        assert_eq!(
+            (
+                kind.value_range.start.character,
+                kind.value_range.end.character
+            ),
+            (6, 10)
+        );
+
+        let spec = doc.root.get("spec").unwrap();
+        assert_eq!(
+            spec.value_range.start.line, 2,
+            "spec value starts on the next line"
+        );
+        assert_eq!(spec.range.start.line, 1, "spec pair starts at the key");
+    }
+
+    #[test]
+    fn test_key_without_value_is_null_entry() {
+        let yaml = "taskRef:\n  name: build\n  kind:\n";
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+
+        let kind = doc.root.get("taskRef").unwrap().get("kind").unwrap();
+        assert!(matches!(kind.value, NodeValue::Null));
+        assert_eq!(kind.value_range.start, kind.range.end);
+    }
+
+    #[test]
+    fn test_quoted_scalars_are_unquoted() {
+        let yaml = "a: \"true\"\nb: 'it''s'\nc: plain\n";
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+
+        let a = doc.root.get("a").unwrap();
+        assert_eq!(a.as_scalar(), Some("true"));
+        assert_eq!(
+            (a.value_range.start.character, a.value_range.end.character),
+            (4, 8)
+        );
+        assert_eq!(doc.root.get("b").unwrap().as_scalar(), Some("it's"));
+        assert_eq!(doc.root.get("c").unwrap().as_scalar(), Some("plain"));
+    }
+
+    #[test]
+    fn test_double_quoted_escapes_are_decoded() {
+        let yaml = "a: \"a\\\"b\"\nb: \"tab\\there \\u00e9\\x21\"\nc: \"one\n  two\\\n  three\"\n";
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+
+        assert_eq!(doc.root.get("a").unwrap().as_scalar(), Some("a\"b"));
+        assert_eq!(doc.root.get("b").unwrap().as_scalar(), Some("tab\there é!"));
+        assert_eq!(doc.root.get("c").unwrap().as_scalar(), Some("one twothree"));
+    }
+
+    #[test]
+    fn test_quoted_expression_ranges_follow_the_source() {
+        let yaml =
+            "a: 'it''s $(params.x)'\nb: \"\\\"$(params.y)\\\"\"\nc: \"first\n  $(params.z)\"\n";
+        let doc = parse_yaml_with_encoding("test.yaml", yaml, PositionEncoding::Utf8).unwrap();
+
+        let range_of = |key: &str| {
+            let exprs = expressions_in(doc.root.get(key).unwrap(), PositionEncoding::Utf8);
+            assert_eq!(exprs.len(), 1, "one expression in {}", key);
+            let r = exprs[0].range;
+            (r.start.line, r.start.character, r.end.line, r.end.character)
+        };
+        assert_eq!(range_of("a"), (0, 10, 0, 21));
+        assert_eq!(range_of("b"), (1, 6, 1, 17));
+        assert_eq!(range_of("c"), (3, 2, 3, 13));
+    }
+
+    #[test]
+    fn test_mapping_keeps_document_order() {
+        let yaml = "zeta: 1\nalpha: 2\nmiddle: 3\nbeta: 4\n";
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+
+        let keys: Vec<&str> = doc.root.entries().map(|(key, _)| key).collect();
+        assert_eq!(keys, vec!["zeta", "alpha", "middle", "beta"]);
+        assert_eq!(doc.root.get("middle").unwrap().as_scalar(), Some("3"));
+    }
+
+    #[test]
+    fn test_block_scalar_content() {
+        let yaml = "literal: |\n  line one\n    indented\nfolded: >\n  first\n  paragraph\n\n  second\nplain: text\n";
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+
+        let literal = doc.root.get("literal").unwrap();
+        assert_eq!(
+            literal.scalar_content().as_deref(),
+            Some("line one\n  indented")
+        );
+        let folded = doc.root.get("folded").unwrap();
+        assert_eq!(
+            folded.scalar_content().as_deref(),
+            Some("first paragraph\nsecond")
+        );
+        assert_eq!(
+            doc.root.get("plain").unwrap().scalar_content().as_deref(),
+            Some("text")
+        );
+    }
+
+    #[test]
+    fn test_anchors_and_aliases() {
+        let yaml = "base: &base\n  image: alpine\nimage: &img golang\ncopy: *base\nother: *img\nmissing: *nope\n";
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+
+        assert_eq!(
+            doc.root
+                .get("base")
+                .unwrap()
+                .get("image")
+                .unwrap()
+                .as_scalar(),
+            Some("alpine")
+        );
+        assert_eq!(doc.root.get("image").unwrap().as_scalar(), Some("golang"));
+        let copy = doc.root.get("copy").unwrap();
+        assert_eq!(copy.get("image").unwrap().as_scalar(), Some("alpine"));
+        assert_eq!(copy.value_range.start.line, 3);
+        assert_eq!(doc.root.get("other").unwrap().as_scalar(), Some("golang"));
+        assert!(matches!(
+            doc.root.get("missing").unwrap().value,
+            NodeValue::Null
+        ));
+    }
+
+    #[test]
+    fn test_alias_expansion_is_limited() {
+        let mut yaml = String::from("a0: &a0 [x, x, x, x, x, x, x, x, x, x]\n");
+        for level in 1..9 {
+            let aliases = vec![format!("*a{}", level - 1); 10].join(", ");
+            yaml.push_str(&format!("a{level}: &a{level} [{aliases}]\n"));
+        }
+
+        let error = parse_yaml("test.yaml", &yaml).unwrap_err();
+        assert!(matches!(error, ParseError::AliasExpansion { .. }));
+        assert_eq!(error.range().unwrap().start.line, 4);
+    }
+
+    #[test]
+    fn test_merge_keys_are_expanded() {
+        let yaml = r#"defaults: &defaults
+  image: alpine
+  workingDir: /src
+extra: &extra
+  image: ignored
+  onError: continue
+step:
+  name: build
+  <<: [*defaults, *extra]
+  workingDir: /workspace
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let step = doc.root.get("step").unwrap();
+
+        let keys: Vec<&str> = step.entries().map(|(key, _)| key).collect();
+        assert_eq!(keys, vec!["name", "image", "workingDir", "onError"]);
+        assert_eq!(
+            step.get("workingDir").unwrap().as_scalar(),
+            Some("/workspace")
+        );
+        assert_eq!(step.get("workingDir").unwrap().range.start.line, 9);
+
+        // Merged fields keep the ranges of the anchored mapping
+        let image = step.get("image").unwrap();
+        assert_eq!(image.as_scalar(), Some("alpine"));
+        assert_eq!(image.key_range.unwrap().start.line, 1);
+        assert_eq!(step.get("onError").unwrap().range.start.line, 5);
+
+        let single = parse_yaml("test.yaml", "a: &a\n  x: 1\nb:\n  <<: *a\n").unwrap();
+        assert_eq!(
+            single.root.get("b").unwrap().get("x").unwrap().as_scalar(),
+            Some("1")
+        );
+        assert!(single.root.get("b").unwrap().get("<<").is_none());
+    }
+
+    #[test]
+    fn test_positions_use_requested_encoding() {
+        let yaml = "a: {é: 🚀, b: c} # ✓ done\n";
+
+        let doc = parse_yaml_with_encoding("test.yaml", yaml, PositionEncoding::Utf16).unwrap();
+        assert_eq!(doc.encoding, PositionEncoding::Utf16);
+        let b = doc.root.get("a").unwrap().get("b").unwrap();
+        assert_eq!(b.key_range.unwrap().start.character, 11);
+        assert_eq!(b.value_range.start.character, 14);
+        assert_eq!(doc.comments[0].range.start.character, 17);
+        assert_eq!(doc.comments[0].range.end.character, 25);
+
+        let doc = parse_yaml_with_encoding("test.yaml", yaml, PositionEncoding::Utf8).unwrap();
+        let b = doc.root.get("a").unwrap().get("b").unwrap();
+        assert_eq!(b.key_range.unwrap().start.character, 14);
+        assert_eq!(doc.comments[0].range.end.character, 30);
     }
 }
@@ -0,0 +1,12 @@
+//! References provider for Tekton YAML files.
+//!
+//! Finds, within a Pipeline:
+//! - the references to a pipeline task of `tasks` or `finally`: `runAfter`
+//!   entries and `$(tasks.<task>...)` / `$(finally.<task>...)` expressions
+//! - the references to a result of a pipeline task, including those of the
+//!   Pipeline's own `results`
+
+pub mod provider;
+pub mod targets;
+
+pub use provider::ReferencesProvider;
@@ -0,0 +1,44 @@
+//! References provider implementation.
+
+use tower_lsp::lsp_types::{Location, Position, Url};
+
+use super::targets::{occurrences, target_at};
+use crate::model::Pipeline;
+use crate::parser::YamlDocument;
+
+/// Provides the references to pipeline tasks and their results.
+#[derive(Debug, Clone, Default)]
+pub struct ReferencesProvider;
+
+impl ReferencesProvider {
+    /// Create a new references provider.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The references to the pipeline task or result named at a position,
+    /// after its declaration when `include_declaration` is set.
+    pub fn provide_references(
+        &self,
+        yaml_doc: &YamlDocument,
+        uri: &Url,
+        position: Position,
+        include_declaration: bool,
+    ) -> Option<Vec<Location>> {
+        if yaml_doc.kind.as_deref() != Some("Pipeline") {
+            return None;
+        }
+        let pipeline = Pipeline::from_node(&yaml_doc.root);
+        let (target, _) = target_at(&pipeline, position, yaml_doc.encoding)?;
+        let found = occurrences(&pipeline, &target, yaml_doc.encoding);
+
+        let declaration = found.declaration.filter(|_| include_declaration);
+        Some(
+            declaration
+                .into_iter()
+                .chain(found.references)
+                .map(|range| Location::new(uri.clone(), range))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,246 @@
+//! Pipeline tasks and task results named in a Pipeline.
+//!
+//! A pipeline task of `tasks` or `finally` is declared by its `name` and
+//! referenced by `runAfter` entries and by `$(tasks.<task>...)` or
+//! `$(finally.<task>...)` expressions anywhere in the Pipeline spec, including
+//! its `results`. A result of a pipeline task is declared by the inline
+//! `taskSpec` and referenced by `$(tasks.<task>.results.<result>)`.
+
+use tower_lsp::lsp_types::{Position, Range};
+
+use crate::model::Pipeline;
+use crate::parser::expressions::{expressions_under, Expression, Reference};
+use crate::parser::positions::contains;
+use crate::parser::PositionEncoding;
+
+/// A pipeline task, or one of its results, named in a Pipeline.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    /// A pipeline task of `tasks` or `finally`
+    Task { task: String },
+    /// A result of a pipeline task
+    TaskResult { task: String, result: String },
+}
+
+/// Where a target is declared and referenced in a Pipeline.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Occurrences {
+    /// The name of the pipeline task, or of the result in its inline `taskSpec`
+    pub declaration: Option<Range>,
+    /// Ranges of the names in references, in document order
+    pub references: Vec<Range>,
+}
+
+/// The target named at a position, with the range of its name there.
+pub fn target_at(
+    pipeline: &Pipeline,
+    position: Position,
+    encoding: PositionEncoding,
+) -> Option<(Target, Range)> {
+    for task in pipeline.all_tasks() {
+        let Some(name) = task.name else {
+            continue;
+        };
+        if contains(name.range, position) {
+            let target = Target::Task {
+                task: name.value.to_string(),
+            };
+            return Some((target, name.range));
+        }
+        let results = task.task_spec.iter().flat_map(|spec| &spec.results);
+        if let Some(result) = results
+            .filter_map(|r| r.name)
+            .find(|r| contains(r.range, position))
+        {
+            let target = Target::TaskResult {
+                task: name.value.to_string(),
+                result: result.value.to_string(),
+            };
+            return Some((target, result.range));
+        }
+    }
+
+    let mut run_after = pipeline
+        .all_tasks()
+        .filter_map(|t| t.run_after.as_ref())
+        .flat_map(|r| r.value.iter());
+    if let Some(entry) = run_after.find(|e| contains(e.range, position)) {
+        let target = Target::Task {
+            task: entry.value.to_string(),
+        };
+        return Some((target, entry.range));
+    }
+
+    expressions(pipeline, encoding)
+        .iter()
+        .find_map(|expr| match expr.reference() {
+            Reference::TaskResult { task, result } if contains(result.range, position) => {
+                let target = Target::TaskResult {
+                    task: task.name.clone(),
+                    result: result.name.clone(),
+                };
+                Some((target, result.range))
+            }
+            Reference::TaskResult { task, .. } | Reference::TaskField { task, .. }
+                if contains(task.range, position) =>
+            {
+                let target = Target::Task {
+                    task: task.name.clone(),
+                };
+                Some((target, task.range))
+            }
+            _ => None,
+        })
+}
+
+/// Where a target is declared and referenced in a Pipeline.
+pub fn occurrences(
+    pipeline: &Pipeline,
+    target: &Target,
+    encoding: PositionEncoding,
+) -> Occurrences {
+    let mut occurrences = Occurrences::default();
+    match target {
+        Target::Task { task } => {
+            occurrences.declaration = pipeline.task(task).and_then(|t| t.name).map(|n| n.range);
+            occurrences.references = pipeline
+                .all_tasks()
+                .filter_map(|t| t.run_after.as_ref())
+                .flat_map(|r| r.value.iter())
+                .filter(|entry| entry.value == task)
+                .map(|entry| entry.range)
+                .collect();
+            for expr in expressions(pipeline, encoding) {
+                match expr.reference() {
+                    Reference::TaskResult { task: name, .. }
+                    | Reference::TaskField { task: name, .. }
+                        if name.name == *task =>
+                    {
+                        occurrences.references.push(name.range)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Target::TaskResult { task, result } => {
+            occurrences.declaration = pipeline
+                .task_result(task, result)
+                .and_then(|r| r.name)
+                .map(|n| n.range);
+            for expr in expressions(pipeline, encoding) {
+                match expr.reference() {
+                    Reference::TaskResult {
+                        task: task_name,
+                        result: result_name,
+                    } if task_name.name == *task && result_name.name == *result => {
+                        occurrences.references.push(result_name.range)
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    occurrences
+        .references
+        .sort_by_key(|r| (r.start.line, r.start.character));
+    occurrences
+}
+
+/// Every expression of the Pipeline spec.
+fn expressions(pipeline: &Pipeline, encoding: PositionEncoding) -> Vec<Expression> {
+    pipeline
+        .spec
+        .map(|spec| expressions_under(spec, encoding))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_yaml;
+
+    const PIPELINE: &str = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: build
+      taskSpec:
+        results:
+          - name: digest
+        steps:
+          - image: alpine
+    - name: push
+      runAfter: [build]
+      params:
+        - name: digest
+          value: $(tasks.build.results.digest)
+  finally:
+    - name: notify
+      when:
+        - input: $(tasks.build.status)
+          operator: in
+          values: [Succeeded]
+      taskSpec:
+        results:
+          - name: sent
+        steps:
+          - image: alpine
+  results:
+    - name: digest
+      value: $(tasks.build.results.digest)
+    - name: notified
+      value: $(finally.notify.results.sent)
+"#;
+
+    fn pos(line: u32, character: u32) -> Position {
+        Position { line, character }
+    }
+
+    #[test]
+    fn test_task_occurrences() {
+        let doc = parse_yaml("test.yaml", PIPELINE).unwrap();
+        let pipeline = Pipeline::from_node(&doc.root);
+
+        // From the runAfter entry
+        let (target, range) = target_at(&pipeline, pos(13, 17), doc.encoding).unwrap();
+        assert_eq!(
+            target,
+            Target::Task {
+                task: "build".to_string()
+            }
+        );
+        assert_eq!(range.start, pos(13, 17));
+
+        let found = occurrences(&pipeline, &target, doc.encoding);
+        assert_eq!(found.declaration.unwrap().start, pos(6, 12));
+        let lines: Vec<_> = found.references.iter().map(|r| r.start.line).collect();
+        assert_eq!(lines, vec![13, 16, 20, 30]);
+    }
+
+    #[test]
+    fn test_finally_result_occurrences() {
+        let doc = parse_yaml("test.yaml", PIPELINE).unwrap();
+        let pipeline = Pipeline::from_node(&doc.root);
+
+        // From `$(finally.notify.results.sent)` in the pipeline results
+        let (target, _) = target_at(&pipeline, pos(32, 39), doc.encoding).unwrap();
+        assert_eq!(
+            target,
+            Target::TaskResult {
+                task: "notify".to_string(),
+                result: "sent".to_string()
+            }
+        );
+        let found = occurrences(&pipeline, &target, doc.encoding);
+        assert_eq!(found.declaration.unwrap().start, pos(25, 18));
+        assert_eq!(found.references.len(), 1);
+
+        // From the finally task name, on `$(finally.notify...)`
+        let (target, _) = target_at(&pipeline, pos(32, 24), doc.encoding).unwrap();
+        let found = occurrences(&pipeline, &target, doc.encoding);
+        assert_eq!(found.declaration.unwrap().start, pos(18, 12));
+        assert_eq!(found.references.len(), 1);
+    }
+}
@@ -0,0 +1,9 @@
+//! Rename provider for Tekton YAML files.
+//!
+//! Renames:
+//! - Pipeline workspaces, with the pipeline task bindings and
+//!   `$(workspaces.<name>.bound)` references using them
+
+pub mod provider;
+
+pub use provider::{RenameProvider, WorkspaceRename};
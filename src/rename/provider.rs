@@ -0,0 +1,320 @@
+//! Rename provider implementation.
+
+use std::collections::HashMap;
+
+use tower_lsp::lsp_types::{Position, PrepareRenameResponse, Range, TextEdit, Url, WorkspaceEdit};
+
+use crate::model::{Pipeline, PipelineTask};
+use crate::parser::expressions::{find_expressions, Reference};
+use crate::parser::positions::contains;
+use crate::parser::{PositionEncoding, Style, YamlDocument};
+use crate::workspace::index::ResourceReference;
+use crate::workspace::WorkspaceIndex;
+
+/// Provides renaming of Pipeline workspaces.
+#[derive(Debug, Clone)]
+pub struct RenameProvider {
+    /// Workspace index used to find PipelineRuns binding a renamed workspace
+    index: Option<WorkspaceIndex>,
+}
+
+/// The outcome of renaming a Pipeline workspace.
+#[derive(Debug, Clone)]
+pub struct WorkspaceRename {
+    /// The workspace name before the rename
+    pub old_name: String,
+    /// Edits to the Pipeline document
+    pub edit: WorkspaceEdit,
+    /// PipelineRuns of the Pipeline that still bind the old name
+    pub stale_runs: Vec<ResourceReference>,
+}
+
+impl RenameProvider {
+    /// Create a new rename provider.
+    pub fn new() -> Self {
+        Self { index: None }
+    }
+
+    /// Create a rename provider backed by the workspace index.
+    pub fn with_index(index: WorkspaceIndex) -> Self {
+        Self { index: Some(index) }
+    }
+
+    /// The range and current name of the renameable workspace at a position.
+    pub fn prepare_rename(
+        &self,
+        yaml_doc: &YamlDocument,
+        position: Position,
+    ) -> Option<PrepareRenameResponse> {
+        let pipeline = pipeline_of(yaml_doc)?;
+        let (range, name) = workspace_at(&pipeline, position, yaml_doc.encoding)?;
+        Some(PrepareRenameResponse::RangeWithPlaceholder {
+            range,
+            placeholder: name.to_string(),
+        })
+    }
+
+    /// Rename the Pipeline workspace at a position to `new_name`.
+    ///
+    /// Updates the declaration, the pipeline task bindings and the
+    /// `$(workspaces.<name>.bound)` references of the document. Fails when
+    /// `new_name` is empty or names another declared workspace.
+    pub fn provide_rename(
+        &self,
+        yaml_doc: &YamlDocument,
+        uri: &Url,
+        position: Position,
+        new_name: &str,
+    ) -> Result<Option<WorkspaceRename>, String> {
+        let Some(pipeline) = pipeline_of(yaml_doc) else {
+            return Ok(None);
+        };
+        let Some((_, old_name)) = workspace_at(&pipeline, position, yaml_doc.encoding) else {
+            return Ok(None);
+        };
+        if new_name.is_empty() {
+            return Err("Workspace names cannot be empty".to_string());
+        }
+        if new_name == old_name {
+            return Ok(None);
+        }
+        if declared(&pipeline, new_name) {
+            return Err(format!(
+                "Pipeline already declares a workspace '{}'",
+                new_name
+            ));
+        }
+
+        let mut edits: Vec<TextEdit> = pipeline
+            .workspaces
+            .iter()
+            .filter_map(|w| w.name.filter(|n| n.value == old_name))
+            .map(|name| TextEdit::new(name.range, new_name.to_string()))
+            .collect();
+        for task in pipeline.all_tasks() {
+            binding_edits(task, old_name, new_name, &mut edits);
+            edits.extend(
+                bound_references(task, yaml_doc.encoding)
+                    .into_iter()
+                    .filter(|(_, name)| name == old_name)
+                    .map(|(range, _)| TextEdit::new(range, new_name.to_string())),
+            );
+        }
+
+        let stale_runs = match (&self.index, pipeline.metadata.name) {
+            (Some(index), Some(name)) => index
+                .find_references("Pipeline", name.value)
+                .into_iter()
+                .filter(|r| r.referrer_kind == "PipelineRun")
+                .filter(|r| r.workspaces.iter().any(|w| w == old_name))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(Some(WorkspaceRename {
+            old_name: old_name.to_string(),
+            edit: WorkspaceEdit {
+                changes: Some(HashMap::from([(uri.clone(), edits)])),
+                ..Default::default()
+            },
+            stale_runs,
+        }))
+    }
+}
+
+/// The Pipeline of a document, if it holds one.
+fn pipeline_of(yaml_doc: &YamlDocument) -> Option<Pipeline<'_>> {
+    (yaml_doc.kind.as_deref() == Some("Pipeline")).then(|| Pipeline::from_node(&yaml_doc.root))
+}
+
+/// Whether the Pipeline declares a workspace.
+fn declared(pipeline: &Pipeline, name: &str) -> bool {
+    pipeline
+        .workspaces
+        .iter()
+        .any(|w| w.name.is_some_and(|n| n.value == name))
+}
+
+/// The declared Pipeline workspace named at a position: by its declaration,
+/// a pipeline task's `workspace` binding or a `$(workspaces.<name>.bound)`.
+fn workspace_at<'a>(
+    pipeline: &Pipeline<'a>,
+    position: Position,
+    encoding: PositionEncoding,
+) -> Option<(Range, &'a str)> {
+    let declarations = pipeline.workspaces.iter().filter_map(|w| w.name);
+    let bindings = pipeline
+        .all_tasks()
+        .flat_map(|t| t.workspaces.iter().filter_map(|b| b.workspace));
+    if let Some(name) = declarations
+        .chain(bindings)
+        .find(|n| contains(n.range, position))
+    {
+        return declared(pipeline, name.value).then_some((name.range, name.value));
+    }
+
+    let (range, name) = pipeline
+        .all_tasks()
+        .flat_map(|t| bound_references(t, encoding))
+        .find(|(range, _)| contains(*range, position))?;
+    let name = pipeline
+        .workspaces
+        .iter()
+        .filter_map(|w| w.name)
+        .find(|n| n.value == name)?;
+    Some((range, name.value))
+}
+
+/// Edits moving the bindings of a pipeline task from `old_name` to `new_name`.
+fn binding_edits(task: &PipelineTask, old_name: &str, new_name: &str, edits: &mut Vec<TextEdit>) {
+    for binding in &task.workspaces {
+        match (binding.workspace, binding.name) {
+            (Some(workspace), _) if workspace.value == old_name => {
+                edits.push(TextEdit::new(workspace.range, new_name.to_string()));
+            }
+            // `workspace` defaults to `name`, which also names the Task's
+            // workspace: keep it and bind the new name explicitly
+            (None, Some(name)) if name.value == old_name => {
+                let start = name.key_or_range().start;
+                let text = if binding.node.style == Style::Flow {
+                    format!("workspace: {}, ", new_name)
+                } else {
+                    format!(
+                        "workspace: {}\n{}",
+                        new_name,
+                        " ".repeat(start.character as usize)
+                    )
+                };
+                edits.push(TextEdit::new(Range::new(start, start), text));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The `$(workspaces.<name>.bound)` references in the `when` expressions of a
+/// pipeline task, with the range of the name.
+fn bound_references(task: &PipelineTask, encoding: PositionEncoding) -> Vec<(Range, String)> {
+    task.when
+        .iter()
+        .flat_map(|when| [when.input, when.cel])
+        .flatten()
+        .flat_map(|value| find_expressions(value.value, value.range.start, encoding))
+        .filter_map(|expression| match expression.reference() {
+            Reference::Workspace {
+                name,
+                field: Some(field),
+            } if field.name == "bound" => Some((name.range, name.name.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+impl Default for RenameProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_yaml;
+
+    const PIPELINE: &str = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  workspaces:
+    - name: source
+    - name: cache
+  tasks:
+    - name: clone
+      taskRef:
+        name: git-clone
+      workspaces:
+        - name: output
+          workspace: source
+    - name: build
+      taskRef:
+        name: build
+      when:
+        - input: $(workspaces.source.bound)
+          operator: in
+          values: ["true"]
+      workspaces:
+        - name: source
+        - {name: cache}
+"#;
+
+    fn edits(rename: &WorkspaceRename) -> Vec<(u32, u32, String)> {
+        let changes = rename.edit.changes.as_ref().unwrap();
+        let mut edits: Vec<_> = changes
+            .values()
+            .flatten()
+            .map(|e| {
+                (
+                    e.range.start.line,
+                    e.range.start.character,
+                    e.new_text.clone(),
+                )
+            })
+            .collect();
+        edits.sort();
+        edits
+    }
+
+    #[test]
+    fn test_rename_pipeline_workspace() {
+        let uri = Url::parse("file:///tmp/ci.yaml").unwrap();
+        let doc = parse_yaml(uri.as_ref(), PIPELINE).unwrap();
+        let provider = RenameProvider::new();
+
+        // From the `workspace` binding of clone
+        let position = Position::new(14, 22);
+        assert_eq!(
+            provider.prepare_rename(&doc, position),
+            Some(PrepareRenameResponse::RangeWithPlaceholder {
+                range: Range::new(Position::new(14, 21), Position::new(14, 27)),
+                placeholder: "source".to_string(),
+            })
+        );
+        let rename = provider
+            .provide_rename(&doc, &uri, position, "src")
+            .unwrap()
+            .unwrap();
+        assert_eq!(rename.old_name, "source");
+        assert_eq!(
+            edits(&rename),
+            vec![
+                (6, 12, "src".to_string()),
+                (14, 21, "src".to_string()),
+                (19, 30, "src".to_string()),
+                (23, 10, "workspace: src\n          ".to_string()),
+            ]
+        );
+
+        // Flow bindings get the explicit binding inline
+        let rename = provider
+            .provide_rename(&doc, &uri, Position::new(7, 14), "deps")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            edits(&rename),
+            vec![
+                (7, 12, "deps".to_string()),
+                (24, 11, "workspace: deps, ".to_string())
+            ]
+        );
+
+        // Task workspace names are not Pipeline workspaces
+        assert_eq!(provider.prepare_rename(&doc, Position::new(13, 17)), None);
+        assert_eq!(
+            provider
+                .provide_rename(&doc, &uri, Position::new(6, 14), "cache")
+                .unwrap_err(),
+            "Pipeline already declares a workspace 'cache'"
+        );
+    }
+}
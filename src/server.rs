@@ -2,20 +2,38 @@
 //!
 //! Contains the Backend struct and LanguageServer trait implementation.
 
-use crate::actions::CodeActionsProvider;
-use crate::cache::DocumentCache;
+use crate::actions::{CodeActionsProvider, EditStyle};
+use crate::cache::{Document, DocumentCache};
 use crate::completion::CompletionProvider;
+use crate::config::ProjectConfig;
 use crate::definition::DefinitionProvider;
+use crate::error::RemoteError;
+use crate::error::{ErrorCategory, LspError, ParseError};
+use crate::folding::FoldingProvider;
 use crate::formatting::FormattingProvider;
+use crate::hierarchy::TypeHierarchyProvider;
 use crate::hover::HoverProvider;
-use crate::parser;
+use crate::integrations::remote::probe;
+use crate::integrations::{git, registry, GitOptions, HubOptions, RegistryOptions};
+use crate::parser::{self, PositionEncoding, YamlDocument};
+use crate::references::ReferencesProvider;
+use crate::rename::RenameProvider;
 use crate::symbols::SymbolsProvider;
-use crate::validator::TektonValidator;
-use crate::workspace::WorkspaceIndex;
-use tower_lsp::jsonrpc::Result;
+use crate::validator::{find_rule, TektonValidator, ValidatorOptions};
+use crate::workspace::{describe_references, WorkspaceIndex, WorkspaceOverview};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use tower_lsp::jsonrpc::{ErrorCode, Result};
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
 
+/// Code of the LSP `ContentModified` error.
+const CONTENT_MODIFIED: i64 = -32801;
+
+/// Method name of the custom workspace overview request.
+pub const WORKSPACE_OVERVIEW_METHOD: &str = "tekton/workspaceOverview";
+
 /// Backend state for the Tekton LSP server.
 #[derive(Debug, Clone)]
 pub struct Backend {
@@ -26,37 +44,555 @@ pub struct Backend {
     hover_provider: HoverProvider,
     definition_provider: DefinitionProvider,
     symbols_provider: SymbolsProvider,
+    folding_provider: FoldingProvider,
     formatting_provider: FormattingProvider,
     code_actions_provider: CodeActionsProvider,
+    references_provider: ReferencesProvider,
+    rename_provider: RenameProvider,
+    type_hierarchy_provider: TypeHierarchyProvider,
+    /// Position encoding negotiated in `initialize`
+    position_encoding: Arc<RwLock<PositionEncoding>>,
+    /// Whether the client registers type hierarchy support dynamically
+    dynamic_type_hierarchy: Arc<AtomicBool>,
+    /// Whether the client watches files for the server when asked dynamically
+    dynamic_watched_files: Arc<AtomicBool>,
+    /// Root of the workspace, holding the project configuration file
+    root: Arc<RwLock<Option<PathBuf>>>,
+    /// Folders of the workspace, whose YAML files are indexed at startup
+    workspace_folders: Arc<RwLock<Vec<PathBuf>>>,
+    /// Validation options from the client settings
+    client_options: Arc<RwLock<ValidatorOptions>>,
+    /// Project configuration file of the workspace root
+    project_config: Arc<RwLock<ProjectConfig>>,
 }
 
 impl Backend {
     /// Create a new Backend instance with the given client.
     pub fn new(client: Client) -> Self {
         let workspace_index = WorkspaceIndex::new();
+        let completion_provider = CompletionProvider::with_index(workspace_index.clone());
+        let hover_provider = HoverProvider::with_index(workspace_index.clone())
+            .with_hub(completion_provider.hub().clone());
         Self {
             client,
             cache: DocumentCache::new(),
-            validator: TektonValidator::new(),
-            completion_provider: CompletionProvider::new(),
-            hover_provider: HoverProvider::new(),
-            definition_provider: DefinitionProvider::new(workspace_index),
+            validator: TektonValidator::with_index(workspace_index.clone()),
+            completion_provider,
+            hover_provider,
+            definition_provider: DefinitionProvider::new(workspace_index.clone()),
             symbols_provider: SymbolsProvider::new(),
+            folding_provider: FoldingProvider::new(),
             formatting_provider: FormattingProvider::new(),
-            code_actions_provider: CodeActionsProvider::new(),
+            code_actions_provider: CodeActionsProvider::with_index(workspace_index.clone()),
+            references_provider: ReferencesProvider::new(),
+            rename_provider: RenameProvider::with_index(workspace_index.clone()),
+            type_hierarchy_provider: TypeHierarchyProvider::new(workspace_index),
+            position_encoding: Arc::new(RwLock::new(PositionEncoding::default())),
+            dynamic_type_hierarchy: Arc::new(AtomicBool::new(false)),
+            dynamic_watched_files: Arc::new(AtomicBool::new(false)),
+            root: Arc::new(RwLock::new(None)),
+            workspace_folders: Arc::new(RwLock::new(Vec::new())),
+            client_options: Arc::new(RwLock::new(ValidatorOptions::default())),
+            project_config: Arc::new(RwLock::new(ProjectConfig::default())),
+        }
+    }
+
+    /// Look up an open document and parse it.
+    fn load_document(&self, uri: &Url) -> std::result::Result<(Document, YamlDocument), LspError> {
+        let doc = self
+            .cache
+            .get(uri)
+            .ok_or_else(|| LspError::DocumentNotFound(uri.clone()))?;
+        let yaml_doc =
+            parser::parse_yaml_with_encoding(uri.as_ref(), &doc.content, self.position_encoding())?;
+        Ok((doc, yaml_doc))
+    }
+
+    /// Run an analysis on the current content of an open document.
+    ///
+    /// The content is a snapshot: when a `didChange` lands while the analysis
+    /// runs, the result refers to the old content and the request positions
+    /// may no longer match the new one, so it fails with `ContentModified`
+    /// for the client to ask again.
+    fn analyze<T>(
+        &self,
+        uri: &Url,
+        analysis: impl FnOnce(&YamlDocument) -> T,
+    ) -> std::result::Result<T, LspError> {
+        let (doc, yaml_doc) = self.load_document(uri)?;
+        let result = analysis(&yaml_doc);
+        if self.cache.version(uri) != Some(doc.version) {
+            return Err(LspError::ContentModified(uri.clone()));
+        }
+        Ok(result)
+    }
+
+    /// Handle the `tekton/workspaceOverview` request.
+    ///
+    /// Returns counts per kind and every indexed resource with its references,
+    /// for editor extensions rendering a tree of the workspace.
+    pub async fn workspace_overview(&self) -> Result<WorkspaceOverview> {
+        Ok(self.definition_provider.index().overview())
+    }
+
+    /// Position encoding negotiated with the client
+    fn position_encoding(&self) -> PositionEncoding {
+        *self.position_encoding.read().unwrap()
+    }
+
+    /// Use the best position encoding the client supports, and return it.
+    fn negotiate_position_encoding(&self, capabilities: &ClientCapabilities) -> PositionEncoding {
+        let supported = capabilities
+            .general
+            .as_ref()
+            .and_then(|general| general.position_encodings.as_deref());
+        let encoding = PositionEncoding::negotiate(supported);
+
+        *self.position_encoding.write().unwrap() = encoding;
+        self.cache.set_position_encoding(encoding);
+        self.definition_provider
+            .index()
+            .set_position_encoding(encoding);
+        encoding
+    }
+
+    /// Apply client settings, from `initializationOptions` or
+    /// `workspace/didChangeConfiguration`.
+    ///
+    /// Expects `{"editStyle": "snippet", "hub": {"enabled": true},
+    /// "git": {"enabled": true}, "registry": {"enabled": true},
+    /// "validation": {"resultsSizeLint": false}}`;
+    /// unknown fields are ignored.
+    fn apply_settings(&self, mut settings: serde_json::Value) {
+        let integrations = self.enabled_integrations();
+        if let Some(style) = settings.get_mut("editStyle").map(serde_json::Value::take) {
+            match serde_json::from_value::<EditStyle>(style) {
+                Ok(style) => self.code_actions_provider.set_edit_style(style),
+                Err(e) => tracing::warn!("Ignoring invalid edit style: {}", e),
+            }
+        }
+        if let Some(hub) = settings.get_mut("hub").map(serde_json::Value::take) {
+            match serde_json::from_value::<HubOptions>(hub) {
+                Ok(options) => self.completion_provider.hub().set_options(options),
+                Err(e) => tracing::warn!("Ignoring invalid Hub settings: {}", e),
+            }
+        }
+        if let Some(git) = settings.get_mut("git").map(serde_json::Value::take) {
+            match serde_json::from_value::<GitOptions>(git) {
+                Ok(options) => self.hover_provider.git().set_options(options),
+                Err(e) => tracing::warn!("Ignoring invalid Git settings: {}", e),
+            }
+        }
+        if let Some(registry) = settings.get_mut("registry").map(serde_json::Value::take) {
+            match serde_json::from_value::<RegistryOptions>(registry) {
+                Ok(options) => self.hover_provider.registry().set_options(options),
+                Err(e) => tracing::warn!("Ignoring invalid registry settings: {}", e),
+            }
+        }
+        self.probe_integrations(integrations);
+        let Some(validation) = settings.get_mut("validation").map(serde_json::Value::take) else {
+            return;
+        };
+        match serde_json::from_value::<ValidatorOptions>(validation) {
+            Ok(validator_options) => {
+                for key in validator_options.rules.keys() {
+                    if find_rule(key).is_none() {
+                        tracing::warn!("Ignoring level of unknown rule '{}'", key);
+                    }
+                }
+                *self.client_options.write().unwrap() = validator_options;
+                self.update_validator_options();
+            }
+            Err(e) => tracing::warn!("Ignoring invalid validation options: {}", e),
+        }
+    }
+
+    /// Combine the client's validation options with the rule levels of the
+    /// project configuration file; the client's levels win.
+    fn update_validator_options(&self) {
+        let mut options = self.client_options.read().unwrap().clone();
+        for (key, level) in &self.project_config.read().unwrap().rules {
+            options.rules.entry(key.clone()).or_insert(*level);
+        }
+        self.validator.set_options(options);
+    }
+
+    /// Load the project configuration file of the workspace root, apply it,
+    /// and re-validate open documents.
+    ///
+    /// An invalid file is reported to the user and leaves the previous
+    /// configuration in place.
+    async fn reload_project_config(&self) {
+        let Some(root) = self.root.read().unwrap().clone() else {
+            return;
+        };
+        let loaded =
+            ProjectConfig::load(&root).and_then(|config| Ok((config.exclude(&root)?, config)));
+        let (exclude, config) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                self.client
+                    .show_message(MessageType::WARNING, e.to_string())
+                    .await;
+                return;
+            }
+        };
+
+        let index = self.definition_provider.index();
+        index.set_exclude(exclude);
+        // Schema paths removed from the file stay indexed until a restart
+        for path in config.schema_paths(&root) {
+            if let Err(e) = index.index_path(&path) {
+                tracing::warn!("Failed to index schema path {}: {}", path.display(), e);
+            }
+        }
+        *self.project_config.write().unwrap() = config;
+        self.update_validator_options();
+
+        for doc in self.cache.all() {
+            self.publish_diagnostics_for(&doc.uri).await;
+        }
+    }
+
+    /// Which network integrations are enabled
+    fn enabled_integrations(&self) -> Integrations {
+        Integrations {
+            hub: self.completion_provider.hub().options().enabled,
+            git: self.hover_provider.git().options().enabled,
+            registry: self.hover_provider.registry().options().enabled,
+        }
+    }
+
+    /// Check in the background that the integrations enabled since `before`
+    /// can reach the network. Each one that cannot is disabled and reported
+    /// once with `window/showMessage`, instead of failing completions and
+    /// hovers later.
+    fn probe_integrations(&self, before: Integrations) {
+        let probed = self.enabled_integrations().since(before);
+        if probed == Integrations::default() {
+            return;
+        }
+        let backend = self.clone();
+        tokio::spawn(async move {
+            let mut hub = backend.completion_provider.hub().options();
+            let mut git = backend.hover_provider.git().options();
+            let mut registry = backend.hover_provider.registry().options();
+            let (hub_error, git_error, registry_error) = tokio::join!(
+                unreachable(probed.hub.then(|| hub.url.clone())),
+                unreachable(probed.git.then(|| git::PROBE_URL.to_string())),
+                unreachable(probed.registry.then(|| registry::PROBE_URL.to_string())),
+            );
+
+            // Settings changed while probing are probed on their own
+            let mut messages = Vec::new();
+            if let Some(e) = hub_error {
+                if backend.completion_provider.hub().options() == hub {
+                    messages.push(format!(
+                        "Tekton Hub at {} is unreachable ({}). Completion of Hub tasks and \
+                         hover of the resources they name are disabled: check the network \
+                         connection or the `hub.url` setting, then restart the server.",
+                        hub.url, e
+                    ));
+                    hub.enabled = false;
+                    backend.completion_provider.hub().set_options(hub);
+                }
+            }
+            if let Some(e) = git_error {
+                if backend.hover_provider.git().options() == git {
+                    messages.push(format!(
+                        "GitHub is unreachable ({}). Hover of references using the `git` \
+                         resolver is disabled: check the network connection, then restart \
+                         the server.",
+                        e
+                    ));
+                    git.enabled = false;
+                    backend.hover_provider.git().set_options(git);
+                }
+            }
+            if let Some(e) = registry_error {
+                if backend.hover_provider.registry().options() == registry {
+                    messages.push(format!(
+                        "Container registries are unreachable ({}). Image digests on hover \
+                         are disabled: check the network connection, then restart the server.",
+                        e
+                    ));
+                    registry.enabled = false;
+                    backend.hover_provider.registry().set_options(registry);
+                }
+            }
+            for message in messages {
+                backend
+                    .client
+                    .show_message(MessageType::WARNING, message)
+                    .await;
+            }
+        });
+    }
+
+    /// Index the YAML files of the workspace folders, so that resources of
+    /// files that were never opened are known, then re-validate open
+    /// documents against them.
+    async fn index_workspace_folders(&self) {
+        let folders = self.workspace_folders.read().unwrap().clone();
+        if folders.is_empty() {
+            return;
+        }
+        let index = self.definition_provider.index().clone();
+        let scan = tokio::task::spawn_blocking(move || {
+            let mut indexed = 0;
+            for folder in &folders {
+                match index.index_directory(folder) {
+                    Ok(count) => indexed += count,
+                    Err(e) => tracing::warn!("Failed to index {}: {}", folder.display(), e),
+                }
+            }
+            indexed
+        });
+        let indexed = match scan.await {
+            Ok(indexed) => indexed,
+            Err(e) => {
+                tracing::error!("Failed to index the workspace: {}", e);
+                return;
+            }
+        };
+        self.client
+            .log_message(
+                MessageType::INFO,
+                format!("Indexed {} files of the workspace", indexed),
+            )
+            .await;
+
+        // Open documents may have unsaved changes the scan read over
+        let index = self.definition_provider.index();
+        for doc in self.cache.all() {
+            if let Err(e) = index.index_document(&doc.uri, &doc.content) {
+                tracing::warn!("Failed to index document: {}", e);
+            }
         }
+        for doc in self.cache.all() {
+            self.publish_diagnostics_for(&doc.uri).await;
+        }
+    }
+
+    /// Whether a document is a file of one of the workspace folders.
+    fn in_workspace_folders(&self, uri: &Url) -> bool {
+        let Ok(path) = uri.to_file_path() else {
+            return false;
+        };
+        let folders = self.workspace_folders.read().unwrap();
+        folders.iter().any(|folder| path.starts_with(folder))
+    }
+
+    /// Parse, validate and publish diagnostics for an open document.
+    async fn publish_diagnostics_for(&self, uri: &Url) {
+        let (version, diagnostics) = match self.load_document(uri) {
+            Ok((doc, yaml_doc)) => {
+                tracing::debug!(
+                    "Parsed document: kind={:?}, apiVersion={:?}",
+                    yaml_doc.kind,
+                    yaml_doc.api_version
+                );
+
+                let mut diagnostics: Vec<Diagnostic> = yaml_doc
+                    .syntax_errors
+                    .iter()
+                    .map(parse_error_diagnostic)
+                    .collect();
+                diagnostics.extend(self.validator.validate_sliced(&yaml_doc).await);
+                (doc.version, diagnostics)
+            }
+            Err(LspError::Parse(e)) => {
+                log_error("diagnostics", &LspError::Parse(e.clone()));
+                let version = self.cache.version(uri).unwrap_or_default();
+                (version, vec![parse_error_diagnostic(&e)])
+            }
+            Err(e) => {
+                log_error("diagnostics", &e);
+                return;
+            }
+        };
+
+        // A change landed during the validation: its own run publishes
+        if self.cache.version(uri) != Some(version) {
+            tracing::debug!("Dropping diagnostics of {} version {}", uri, version);
+            return;
+        }
+        self.client
+            .publish_diagnostics(uri.clone(), diagnostics, Some(version))
+            .await;
+    }
+
+    /// Re-validate the open documents referencing the resources of a document,
+    /// whose checks depend on what it declares.
+    async fn publish_diagnostics_for_referrers(&self, uri: &Url) {
+        let mut referrers: Vec<Url> = self
+            .definition_provider
+            .index()
+            .inbound_references(uri)
+            .into_iter()
+            .map(|r| r.uri)
+            .collect();
+        referrers.sort();
+        referrers.dedup();
+        for referrer in referrers.iter().filter(|r| self.cache.get(r).is_some()) {
+            self.publish_diagnostics_for(referrer).await;
+        }
+    }
+}
+
+/// Whether each network integration is enabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Integrations {
+    hub: bool,
+    git: bool,
+    registry: bool,
+}
+
+impl Integrations {
+    /// The integrations enabled here but not in `before`
+    fn since(self, before: Integrations) -> Integrations {
+        Integrations {
+            hub: self.hub && !before.hub,
+            git: self.git && !before.git,
+            registry: self.registry && !before.registry,
+        }
+    }
+}
+
+/// Log a request failure at a level matching its category.
+fn log_error(request: &str, error: &LspError) {
+    match error.category() {
+        ErrorCategory::NotFound => tracing::warn!("{} failed: {}", request, error),
+        ErrorCategory::Syntax | ErrorCategory::Stale => {
+            tracing::debug!("{} failed: {}", request, error)
+        }
+        ErrorCategory::Io | ErrorCategory::Internal => {
+            tracing::error!("{} failed: {}", request, error)
+        }
+    }
+}
+
+/// The answer to a request that failed with `error`: the `ContentModified`
+/// error for the client to ask again when the document changed during the
+/// analysis, otherwise no result.
+fn request_failed<T>(request: &str, error: LspError) -> Result<Option<T>> {
+    log_error(request, &error);
+    match error {
+        LspError::ContentModified(_) => Err(tower_lsp::jsonrpc::Error {
+            code: ErrorCode::ServerError(CONTENT_MODIFIED),
+            message: error.to_string().into(),
+            data: None,
+        }),
+        _ => Ok(None),
+    }
+}
+
+/// Convert a parse error into a diagnostic, placed at its range when known.
+fn parse_error_diagnostic(error: &ParseError) -> Diagnostic {
+    let range = error.range().unwrap_or_default();
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: None,
+        code_description: None,
+        source: Some("tekton-lsp".to_string()),
+        message: match error {
+            ParseError::Syntax { .. } => error.to_string(),
+            _ => format!("Failed to parse YAML: {}", error),
+        },
+        related_information: None,
+        tags: None,
+        data: None,
+    }
+}
+
+/// Probe `url`, when set, off the async runtime: why it cannot be reached.
+async fn unreachable(url: Option<String>) -> Option<RemoteError> {
+    let url = url?;
+    tokio::task::spawn_blocking(move || probe(&url).err())
+        .await
+        .ok()
+        .flatten()
+}
+
+/// File operations on YAML files, which may hold Tekton resources.
+fn yaml_file_operations() -> FileOperationRegistrationOptions {
+    FileOperationRegistrationOptions {
+        filters: vec![FileOperationFilter {
+            scheme: Some("file".to_string()),
+            pattern: FileOperationPattern {
+                glob: "**/*.{yaml,yml}".to_string(),
+                matches: Some(FileOperationPatternKind::File),
+                options: None,
+            },
+        }],
     }
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
-    async fn initialize(&self, _params: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        if let Some(options) = params.initialization_options {
+            self.apply_settings(options);
+        }
+        let position_encoding = self.negotiate_position_encoding(&params.capabilities);
+        // lsp-types has no static `typeHierarchyProvider` capability: register
+        // the requests in `initialized` when the client allows it
+        let dynamic_type_hierarchy = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .and_then(|text_document| text_document.type_hierarchy.as_ref())
+            .and_then(|type_hierarchy| type_hierarchy.dynamic_registration)
+            .unwrap_or(false);
+        self.dynamic_type_hierarchy
+            .store(dynamic_type_hierarchy, Ordering::Relaxed);
+        let snippet_support = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .and_then(|text_document| text_document.completion.as_ref())
+            .and_then(|completion| completion.completion_item.as_ref())
+            .and_then(|completion_item| completion_item.snippet_support)
+            .unwrap_or(false);
+        self.completion_provider
+            .set_snippet_support(snippet_support);
+        let dynamic_watched_files = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.did_change_watched_files.as_ref())
+            .and_then(|watched_files| watched_files.dynamic_registration)
+            .unwrap_or(false);
+        self.dynamic_watched_files
+            .store(dynamic_watched_files, Ordering::Relaxed);
+
+        // The project configuration file lives at the root of the first folder
+        #[allow(deprecated)]
+        let root_uri = params
+            .workspace_folders
+            .as_ref()
+            .and_then(|folders| folders.first())
+            .map(|folder| folder.uri.clone())
+            .or(params.root_uri.clone());
+        *self.root.write().unwrap() = root_uri.and_then(|uri| uri.to_file_path().ok());
+        #[allow(deprecated)]
+        let folders: Vec<Url> = match params.workspace_folders {
+            Some(folders) => folders.into_iter().map(|folder| folder.uri).collect(),
+            None => params.root_uri.into_iter().collect(),
+        };
+        *self.workspace_folders.write().unwrap() = folders
+            .iter()
+            .filter_map(|uri| uri.to_file_path().ok())
+            .collect();
+
         Ok(InitializeResult {
             server_info: Some(ServerInfo {
                 name: "tekton-lsp".to_string(),
                 version: Some(env!("CARGO_PKG_VERSION").to_string()),
             }),
             capabilities: ServerCapabilities {
+                position_encoding: Some(position_encoding.kind()),
                 text_document_sync: Some(TextDocumentSyncCapability::Options(
                     TextDocumentSyncOptions {
                         open_close: Some(true),
@@ -65,20 +601,71 @@ impl LanguageServer for Backend {
                     },
                 )),
                 completion_provider: Some(CompletionOptions {
-                    trigger_characters: Some(vec![":".to_string(), " ".to_string(), "-".to_string()]),
+                    trigger_characters: Some(vec![
+                        ":".to_string(),
+                        " ".to_string(),
+                        "-".to_string(),
+                        "{".to_string(),
+                        "(".to_string(),
+                        ".".to_string(),
+                    ]),
+                    resolve_provider: Some(true),
                     ..Default::default()
                 }),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
                 definition_provider: Some(OneOf::Left(true)),
+                references_provider: Some(OneOf::Left(true)),
                 document_symbol_provider: Some(OneOf::Left(true)),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
                 document_formatting_provider: Some(OneOf::Left(true)),
                 code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                })),
+                workspace: Some(WorkspaceServerCapabilities {
+                    workspace_folders: None,
+                    file_operations: Some(WorkspaceFileOperationsServerCapabilities {
+                        will_delete: Some(yaml_file_operations()),
+                        ..Default::default()
+                    }),
+                }),
                 ..Default::default()
             },
         })
     }
 
     async fn initialized(&self, _params: InitializedParams) {
+        if self.dynamic_type_hierarchy.load(Ordering::Relaxed) {
+            let options = TypeHierarchyRegistrationOptions::default();
+            let registration = Registration {
+                id: "tekton-type-hierarchy".to_string(),
+                method: "textDocument/prepareTypeHierarchy".to_string(),
+                register_options: serde_json::to_value(options).ok(),
+            };
+            if let Err(e) = self.client.register_capability(vec![registration]).await {
+                tracing::warn!("Failed to register type hierarchy: {}", e);
+            }
+        }
+        if self.dynamic_watched_files.load(Ordering::Relaxed) {
+            let options = DidChangeWatchedFilesRegistrationOptions {
+                watchers: vec![FileSystemWatcher {
+                    glob_pattern: GlobPattern::String("**/.tekton-lsp.{yaml,yml,toml}".to_string()),
+                    kind: None,
+                }],
+            };
+            let registration = Registration {
+                id: "tekton-project-config".to_string(),
+                method: "workspace/didChangeWatchedFiles".to_string(),
+                register_options: serde_json::to_value(options).ok(),
+            };
+            if let Err(e) = self.client.register_capability(vec![registration]).await {
+                tracing::warn!("Failed to watch the project configuration file: {}", e);
+            }
+        }
+        self.reload_project_config().await;
+        self.index_workspace_folders().await;
+
         self.client
             .log_message(MessageType::INFO, "Tekton LSP server initialized")
             .await;
@@ -92,63 +679,48 @@ impl LanguageServer for Backend {
         let uri = &params.text_document_position.text_document.uri;
         let position = params.text_document_position.position;
 
-        // Get document from cache
-        if let Some(doc) = self.cache.get(uri) {
-            // Parse the document
-            match parser::parse_yaml(&uri.to_string(), &doc.content) {
-                Ok(yaml_doc) => {
-                    // Get completions from provider
-                    let completions = self.completion_provider.provide_completions(&yaml_doc, position);
-
-                    tracing::debug!(
-                        "Providing {} completions at {}:{}",
-                        completions.len(),
-                        position.line,
-                        position.character
-                    );
-
-                    Ok(Some(CompletionResponse::Array(completions)))
-                }
-                Err(e) => {
-                    tracing::error!("Failed to parse YAML for completion: {}", e);
-                    Ok(None)
-                }
+        let completions = self.analyze(uri, |yaml_doc| {
+            self.completion_provider
+                .provide_completions(yaml_doc, position)
+        });
+        match completions {
+            Ok(completions) => {
+                tracing::debug!(
+                    "Providing {} completions at {}:{}",
+                    completions.len(),
+                    position.line,
+                    position.character
+                );
+
+                Ok(Some(CompletionResponse::Array(completions)))
             }
-        } else {
-            tracing::warn!("Document not found in cache for completion: {}", uri);
-            Ok(None)
+            Err(e) => request_failed("completion", e),
         }
     }
 
+    async fn completion_resolve(&self, item: CompletionItem) -> Result<CompletionItem> {
+        Ok(self.completion_provider.resolve(item))
+    }
+
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
         let uri = &params.text_document_position_params.text_document.uri;
         let position = params.text_document_position_params.position;
 
-        // Get document from cache
-        if let Some(doc) = self.cache.get(uri) {
-            // Parse the document
-            match parser::parse_yaml(&uri.to_string(), &doc.content) {
-                Ok(yaml_doc) => {
-                    // Get hover from provider
-                    let hover = self.hover_provider.provide_hover(&yaml_doc, position);
-
-                    tracing::debug!(
-                        "Providing hover at {}:{}: {}",
-                        position.line,
-                        position.character,
-                        hover.is_some()
-                    );
-
-                    Ok(hover)
-                }
-                Err(e) => {
-                    tracing::error!("Failed to parse YAML for hover: {}", e);
-                    Ok(None)
-                }
+        let hover = self.analyze(uri, |yaml_doc| {
+            self.hover_provider.provide_hover(yaml_doc, position)
+        });
+        match hover {
+            Ok(hover) => {
+                tracing::debug!(
+                    "Providing hover at {}:{}: {}",
+                    position.line,
+                    position.character,
+                    hover.is_some()
+                );
+
+                Ok(hover)
             }
-        } else {
-            tracing::warn!("Document not found in cache for hover: {}", uri);
-            Ok(None)
+            Err(e) => request_failed("hover", e),
         }
     }
 
@@ -159,31 +731,41 @@ impl LanguageServer for Backend {
         let uri = &params.text_document_position_params.text_document.uri;
         let position = params.text_document_position_params.position;
 
-        // Get document from cache
-        if let Some(doc) = self.cache.get(uri) {
-            // Parse the document
-            match parser::parse_yaml(&uri.to_string(), &doc.content) {
-                Ok(yaml_doc) => {
-                    // Get definition from provider
-                    let definition = self.definition_provider.provide_definition(&yaml_doc, position);
-
-                    tracing::debug!(
-                        "Providing definition at {}:{}: {}",
-                        position.line,
-                        position.character,
-                        definition.is_some()
-                    );
-
-                    Ok(definition)
-                }
-                Err(e) => {
-                    tracing::error!("Failed to parse YAML for definition: {}", e);
-                    Ok(None)
-                }
+        let definition = self.analyze(uri, |yaml_doc| {
+            self.definition_provider
+                .provide_definition(yaml_doc, position)
+        });
+        match definition {
+            Ok(definition) => {
+                tracing::debug!(
+                    "Providing definition at {}:{}: {}",
+                    position.line,
+                    position.character,
+                    definition.is_some()
+                );
+
+                Ok(definition)
             }
-        } else {
-            tracing::warn!("Document not found in cache for definition: {}", uri);
-            Ok(None)
+            Err(e) => request_failed("definition", e),
+        }
+    }
+
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let uri = &params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+        let include_declaration = params.context.include_declaration;
+
+        let references = self.analyze(uri, |yaml_doc| {
+            self.references_provider.provide_references(
+                yaml_doc,
+                uri,
+                position,
+                include_declaration,
+            )
+        });
+        match references {
+            Ok(references) => Ok(references),
+            Err(e) => request_failed("references", e),
         }
     }
 
@@ -193,42 +775,44 @@ impl LanguageServer for Backend {
     ) -> Result<Option<DocumentSymbolResponse>> {
         let uri = &params.text_document.uri;
 
-        // Get document from cache
-        if let Some(doc) = self.cache.get(uri) {
-            // Parse the document
-            match parser::parse_yaml(&uri.to_string(), &doc.content) {
-                Ok(yaml_doc) => {
-                    // Get symbols from provider
-                    let symbols = self.symbols_provider.provide_symbols(&yaml_doc);
-
-                    tracing::debug!(
-                        "Providing {} document symbols",
-                        symbols.len()
-                    );
-
-                    Ok(Some(DocumentSymbolResponse::Nested(symbols)))
-                }
-                Err(e) => {
-                    tracing::error!("Failed to parse YAML for symbols: {}", e);
-                    Ok(None)
-                }
+        let symbols = self.analyze(uri, |yaml_doc| {
+            self.symbols_provider.provide_symbols(yaml_doc)
+        });
+        match symbols {
+            Ok(symbols) => {
+                tracing::debug!("Providing {} document symbols", symbols.len());
+
+                Ok(Some(DocumentSymbolResponse::Nested(symbols)))
             }
-        } else {
-            tracing::warn!("Document not found in cache for symbols: {}", uri);
-            Ok(None)
+            Err(e) => request_failed("symbols", e),
         }
     }
 
-    async fn formatting(
-        &self,
-        params: DocumentFormattingParams,
-    ) -> Result<Option<Vec<TextEdit>>> {
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        let uri = &params.text_document.uri;
+
+        let ranges = self.analyze(uri, |yaml_doc| {
+            self.folding_provider.provide_folding_ranges(yaml_doc)
+        });
+        match ranges {
+            Ok(ranges) => {
+                tracing::debug!("Providing {} folding ranges", ranges.len());
+
+                Ok(Some(ranges))
+            }
+            Err(e) => request_failed("folding", e),
+        }
+    }
+
+    async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
         let uri = &params.text_document.uri;
 
         // Get document from cache
         if let Some(doc) = self.cache.get(uri) {
             // Get formatting edits from provider
-            let edits = self.formatting_provider.format(&doc.content);
+            let edits = self
+                .formatting_provider
+                .format(&doc.content, self.position_encoding());
 
             tracing::debug!(
                 "Providing {} formatting edits",
@@ -242,10 +826,7 @@ impl LanguageServer for Backend {
         }
     }
 
-    async fn code_action(
-        &self,
-        params: CodeActionParams,
-    ) -> Result<Option<CodeActionResponse>> {
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
         let uri = &params.text_document.uri;
         let diagnostics = &params.context.diagnostics;
 
@@ -265,15 +846,152 @@ impl LanguageServer for Backend {
         }
     }
 
-    async fn did_open(&self, params: DidOpenTextDocumentParams) {
-        self.client
-            .log_message(
-                MessageType::INFO,
-                format!("Document opened: {}", params.text_document.uri),
-            )
-            .await;
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let uri = &params.text_document.uri;
+
+        let prepared = self.analyze(uri, |yaml_doc| {
+            self.rename_provider
+                .prepare_rename(yaml_doc, params.position)
+        });
+        match prepared {
+            Ok(prepared) => Ok(prepared),
+            Err(e) => request_failed("prepareRename", e),
+        }
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let uri = &params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+
+        let rename = match self.analyze(uri, |yaml_doc| {
+            self.rename_provider
+                .provide_rename(yaml_doc, uri, position, &params.new_name)
+        }) {
+            Ok(rename) => rename.map_err(tower_lsp::jsonrpc::Error::invalid_params)?,
+            Err(e) => return request_failed("rename", e),
+        };
+        let Some(rename) = rename else {
+            return Ok(None);
+        };
+
+        // PipelineRuns are not edited; they get diagnostics once the Pipeline changes
+        if !rename.stale_runs.is_empty() {
+            let runs: Vec<String> = rename
+                .stale_runs
+                .iter()
+                .map(|r| {
+                    format!(
+                        "PipelineRun '{}' ({})",
+                        r.referrer_name.as_deref().unwrap_or("unnamed"),
+                        r.file_name()
+                    )
+                })
+                .collect();
+            self.client
+                .show_message(
+                    MessageType::WARNING,
+                    format!(
+                        "Workspace '{}' is still bound by {}",
+                        rename.old_name,
+                        runs.join(", ")
+                    ),
+                )
+                .await;
+        }
+
+        Ok(Some(rename.edit))
+    }
+
+    async fn prepare_type_hierarchy(
+        &self,
+        params: TypeHierarchyPrepareParams,
+    ) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        let items = self.analyze(uri, |yaml_doc| {
+            self.type_hierarchy_provider
+                .prepare(yaml_doc, uri, position)
+        });
+        match items {
+            Ok(items) => Ok(items),
+            Err(e) => request_failed("prepareTypeHierarchy", e),
+        }
+    }
+
+    async fn supertypes(
+        &self,
+        params: TypeHierarchySupertypesParams,
+    ) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        Ok(Some(self.type_hierarchy_provider.supertypes(&params.item)))
+    }
+
+    async fn subtypes(
+        &self,
+        params: TypeHierarchySubtypesParams,
+    ) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        Ok(Some(self.type_hierarchy_provider.subtypes(&params.item)))
+    }
+
+    async fn will_delete_files(&self, params: DeleteFilesParams) -> Result<Option<WorkspaceEdit>> {
+        let deleted: Vec<Url> = params
+            .files
+            .iter()
+            .filter_map(|file| Url::parse(&file.uri).ok())
+            .collect();
+
+        // Warn about references from files that are kept; the deletion goes ahead
+        for uri in &deleted {
+            let mut references = self.definition_provider.index().inbound_references(uri);
+            references.retain(|r| !deleted.contains(&r.uri));
+            if let Some(warning) = describe_references(&references) {
+                self.client
+                    .show_message(
+                        MessageType::WARNING,
+                        format!("Deleting {} breaks references: {}", uri, warning),
+                    )
+                    .await;
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        // Settings may be scoped under the server's section
+        let mut settings = params.settings;
+        if let Some(tekton) = settings.get_mut("tekton") {
+            settings = tekton.take();
+        }
+        self.apply_settings(settings);
 
-        // Add document to cache
+        // Re-validate open documents with the new options
+        for doc in self.cache.all() {
+            self.publish_diagnostics_for(&doc.uri).await;
+        }
+    }
+
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        let Some(root) = self.root.read().unwrap().clone() else {
+            return;
+        };
+        let config_changed = params.changes.iter().any(|change| {
+            change
+                .uri
+                .to_file_path()
+                .is_ok_and(|path| ProjectConfig::is_config_file(&root, &path))
+        });
+        if config_changed {
+            self.reload_project_config().await;
+        }
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        // Add document to cache before awaiting, so that requests following
+        // the notification see it
         self.cache.insert(
             params.text_document.uri.clone(),
             params.text_document.language_id.clone(),
@@ -281,61 +999,36 @@ impl LanguageServer for Backend {
             params.text_document.text.clone(),
         );
 
+        self.client
+            .log_message(
+                MessageType::INFO,
+                format!("Document opened: {}", params.text_document.uri),
+            )
+            .await;
+
         // Index document for go-to-definition
-        if let Err(e) = self.definition_provider.index().index_document(
-            &params.text_document.uri,
-            &params.text_document.text,
-        ) {
+        if let Err(e) = self
+            .definition_provider
+            .index()
+            .index_document(&params.text_document.uri, &params.text_document.text)
+        {
             tracing::warn!("Failed to index document: {}", e);
         }
 
         // Parse and validate the document
-        if let Some(doc) = self.cache.get(&params.text_document.uri) {
-            match parser::parse_yaml(&params.text_document.uri.to_string(), &doc.content) {
-                Ok(yaml_doc) => {
-                    tracing::debug!(
-                        "Parsed document: kind={:?}, apiVersion={:?}",
-                        yaml_doc.kind,
-                        yaml_doc.api_version
-                    );
-
-                    // Validate and publish diagnostics
-                    let diagnostics = self.validator.validate(&yaml_doc);
-
-                    self.client
-                        .publish_diagnostics(params.text_document.uri.clone(), diagnostics, None)
-                        .await;
-                }
-                Err(e) => {
-                    tracing::error!("Failed to parse YAML: {}", e);
-
-                    // Publish parse error as diagnostic
-                    self.client
-                        .publish_diagnostics(
-                            params.text_document.uri,
-                            vec![Diagnostic {
-                                range: Range {
-                                    start: Position { line: 0, character: 0 },
-                                    end: Position { line: 0, character: 0 },
-                                },
-                                severity: Some(DiagnosticSeverity::ERROR),
-                                code: None,
-                                code_description: None,
-                                source: Some("tekton-lsp".to_string()),
-                                message: format!("Failed to parse YAML: {}", e),
-                                related_information: None,
-                                tags: None,
-                                data: None,
-                            }],
-                            None,
-                        )
-                        .await;
-                }
-            }
-        }
+        self.publish_diagnostics_for(&params.text_document.uri)
+            .await;
     }
 
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        // Update document in cache before awaiting, so that requests
+        // following the notification see the new content
+        self.cache.update(
+            &params.text_document.uri,
+            params.text_document.version,
+            params.content_changes,
+        );
+
         self.client
             .log_message(
                 MessageType::LOG,
@@ -343,61 +1036,22 @@ impl LanguageServer for Backend {
             )
             .await;
 
-        // Update document in cache
-        self.cache.update(
-            &params.text_document.uri,
-            params.text_document.version,
-            params.content_changes,
-        );
-
         // Re-index document for go-to-definition
         if let Some(doc) = self.cache.get(&params.text_document.uri) {
-            if let Err(e) = self.definition_provider.index().index_document(
-                &params.text_document.uri,
-                &doc.content,
-            ) {
+            if let Err(e) = self
+                .definition_provider
+                .index()
+                .index_document(&params.text_document.uri, &doc.content)
+            {
                 tracing::warn!("Failed to re-index document: {}", e);
             }
         }
 
         // Re-validate after change
-        if let Some(doc) = self.cache.get(&params.text_document.uri) {
-            match parser::parse_yaml(&params.text_document.uri.to_string(), &doc.content) {
-                Ok(yaml_doc) => {
-                    // Validate and publish updated diagnostics
-                    let diagnostics = self.validator.validate(&yaml_doc);
-
-                    self.client
-                        .publish_diagnostics(params.text_document.uri.clone(), diagnostics, None)
-                        .await;
-                }
-                Err(e) => {
-                    tracing::error!("Failed to parse YAML after change: {}", e);
-
-                    // Publish parse error
-                    self.client
-                        .publish_diagnostics(
-                            params.text_document.uri,
-                            vec![Diagnostic {
-                                range: Range {
-                                    start: Position { line: 0, character: 0 },
-                                    end: Position { line: 0, character: 0 },
-                                },
-                                severity: Some(DiagnosticSeverity::ERROR),
-                                code: None,
-                                code_description: None,
-                                source: Some("tekton-lsp".to_string()),
-                                message: format!("Failed to parse YAML: {}", e),
-                                related_information: None,
-                                tags: None,
-                                data: None,
-                            }],
-                            None,
-                        )
-                        .await;
-                }
-            }
-        }
+        self.publish_diagnostics_for(&params.text_document.uri)
+            .await;
+        self.publish_diagnostics_for_referrers(&params.text_document.uri)
+            .await;
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
@@ -408,10 +1062,87 @@ impl LanguageServer for Backend {
             )
             .await;
 
-        // Remove document from workspace index
-        self.definition_provider.index().remove_document(&params.text_document.uri);
+        // Files of the workspace stay indexed as saved; others leave the index
+        let index = self.definition_provider.index();
+        if self.in_workspace_folders(&params.text_document.uri) {
+            // Reading the file blocks, like the startup scan
+            let index = index.clone();
+            let uri = params.text_document.uri.clone();
+            let reload = tokio::task::spawn_blocking(move || index.reload_document(&uri));
+            if let Err(e) = reload.await {
+                tracing::error!("Failed to reload {}: {}", params.text_document.uri, e);
+            }
+        } else {
+            index.remove_document(&params.text_document.uri);
+        }
 
         // Remove document from cache
         self.cache.remove(&params.text_document.uri);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::LspService;
+
+    #[test]
+    fn test_content_modified_during_analysis() {
+        let (service, _socket) = LspService::new(Backend::new);
+        let backend = service.inner();
+        let uri = Url::parse("file:///test.yaml").unwrap();
+        backend.cache.insert(
+            uri.clone(),
+            "yaml".to_string(),
+            1,
+            "kind: Task\n".to_string(),
+        );
+
+        // A didChange lands while the analysis runs
+        let result = backend.analyze(&uri, |_| {
+            let change = TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: "kind: Pipeline\n".to_string(),
+            };
+            backend.cache.update(&uri, 2, vec![change]);
+        });
+        let error = request_failed::<()>("test", result.unwrap_err()).unwrap_err();
+        assert_eq!(error.code, ErrorCode::ServerError(CONTENT_MODIFIED));
+
+        // The next run sees the new version
+        assert!(backend.analyze(&uri, |_| ()).is_ok());
+
+        // Other failures answer with no result
+        let missing = Url::parse("file:///missing.yaml").unwrap();
+        let result = backend.analyze(&missing, |_| ());
+        assert!(matches!(
+            request_failed::<()>("test", result.unwrap_err()),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn test_integrations_enabled_since() {
+        let before = Integrations {
+            hub: true,
+            git: false,
+            registry: false,
+        };
+        let now = Integrations {
+            hub: true,
+            git: true,
+            registry: false,
+        };
+        // Only the newly enabled Git integration is probed
+        assert_eq!(
+            now.since(before),
+            Integrations {
+                hub: false,
+                git: true,
+                registry: false,
+            }
+        );
+        assert_eq!(before.since(now), Integrations::default());
+    }
+}
@@ -6,5 +6,7 @@
 //! - Individual items with their names
 
 pub mod provider;
+mod script;
 
 pub use provider::SymbolsProvider;
+pub use script::{script_functions, ScriptFunction};
@@ -2,7 +2,8 @@
 
 use tower_lsp::lsp_types::{DocumentSymbol, SymbolKind};
 
-use crate::parser::{Node, NodeValue, YamlDocument};
+use super::script::script_functions;
+use crate::parser::{Node, NodeValue, PositionEncoding, YamlDocument};
 
 /// Provides document symbols (outline) for Tekton YAML files.
 #[derive(Debug, Clone, Default)]
@@ -21,7 +22,9 @@ impl SymbolsProvider {
 
         // Get the resource kind and name for the root symbol
         let kind = yaml_doc.kind.as_deref().unwrap_or("Unknown");
-        let name = self.get_resource_name(&yaml_doc.root).unwrap_or_else(|| kind.to_string());
+        let name = self
+            .get_resource_name(&yaml_doc.root)
+            .unwrap_or_else(|| kind.to_string());
 
         // Create the root symbol for the resource
         let mut root_symbol = DocumentSymbol {
@@ -37,7 +40,7 @@ impl SymbolsProvider {
 
         // Add child symbols based on resource type
         if let Some(children) = &mut root_symbol.children {
-            self.add_resource_children(children, &yaml_doc.root, kind);
+            self.add_resource_children(children, yaml_doc, kind);
         }
 
         symbols.push(root_symbol);
@@ -55,7 +58,7 @@ impl SymbolsProvider {
     /// Map Tekton resource kind to LSP SymbolKind.
     fn resource_kind_to_symbol_kind(&self, kind: &str) -> SymbolKind {
         match kind {
-            "Pipeline" | "Task" | "ClusterTask" => SymbolKind::CLASS,
+            "Pipeline" | "Task" | "ClusterTask" | "StepAction" => SymbolKind::CLASS,
             "PipelineRun" | "TaskRun" => SymbolKind::OBJECT,
             "TriggerTemplate" | "TriggerBinding" | "EventListener" => SymbolKind::INTERFACE,
             _ => SymbolKind::FILE,
@@ -64,7 +67,14 @@ impl SymbolsProvider {
 
     /// Add child symbols based on resource type.
     #[allow(deprecated)]
-    fn add_resource_children(&self, children: &mut Vec<DocumentSymbol>, root: &Node, kind: &str) {
+    fn add_resource_children(
+        &self,
+        children: &mut Vec<DocumentSymbol>,
+        yaml_doc: &YamlDocument,
+        kind: &str,
+    ) {
+        let root = &yaml_doc.root;
+
         // Add metadata section
         if let Some(metadata) = root.get("metadata") {
             children.push(self.create_section_symbol("metadata", metadata, SymbolKind::NAMESPACE));
@@ -77,7 +87,9 @@ impl SymbolsProvider {
             if let Some(spec_children) = &mut spec_symbol.children {
                 match kind {
                     "Pipeline" => self.add_pipeline_spec_children(spec_children, spec),
-                    "Task" | "ClusterTask" => self.add_task_spec_children(spec_children, spec),
+                    "Task" | "ClusterTask" => {
+                        self.add_task_spec_children(spec_children, spec, yaml_doc.encoding)
+                    }
                     "PipelineRun" => self.add_pipeline_run_spec_children(spec_children, spec),
                     "TaskRun" => self.add_task_run_spec_children(spec_children, spec),
                     _ => {}
@@ -93,9 +105,9 @@ impl SymbolsProvider {
     fn add_pipeline_spec_children(&self, children: &mut Vec<DocumentSymbol>, spec: &Node) {
         // Add params
         if let Some(params) = spec.get("params") {
-            children.push(self.create_array_symbol("params", params, |item| {
-                self.get_name_from_node(item)
-            }));
+            children.push(
+                self.create_array_symbol("params", params, |item| self.get_name_from_node(item)),
+            );
         }
 
         // Add workspaces
@@ -105,36 +117,37 @@ impl SymbolsProvider {
             }));
         }
 
-        // Add tasks
-        if let Some(tasks) = spec.get("tasks") {
-            children.push(self.create_array_symbol("tasks", tasks, |item| {
-                self.get_name_from_node(item)
-            }));
-        }
-
-        // Add finally
-        if let Some(finally) = spec.get("finally") {
-            children.push(self.create_array_symbol("finally", finally, |item| {
-                self.get_name_from_node(item)
-            }));
+        // Add tasks and finally tasks, by their display names
+        for field in ["tasks", "finally"] {
+            if let Some(tasks) = spec.get(field) {
+                let mut symbol =
+                    self.create_array_symbol(field, tasks, |item| self.get_name_from_node(item));
+                self.apply_display_names(&mut symbol, tasks);
+                children.push(symbol);
+            }
         }
 
         // Add results
         if let Some(results) = spec.get("results") {
-            children.push(self.create_array_symbol("results", results, |item| {
-                self.get_name_from_node(item)
-            }));
+            children.push(
+                self.create_array_symbol("results", results, |item| self.get_name_from_node(item)),
+            );
         }
     }
 
     /// Add Task spec children.
     #[allow(deprecated)]
-    fn add_task_spec_children(&self, children: &mut Vec<DocumentSymbol>, spec: &Node) {
+    fn add_task_spec_children(
+        &self,
+        children: &mut Vec<DocumentSymbol>,
+        spec: &Node,
+        encoding: PositionEncoding,
+    ) {
         // Add params
         if let Some(params) = spec.get("params") {
-            children.push(self.create_array_symbol("params", params, |item| {
-                self.get_name_from_node(item)
-            }));
+            children.push(
+                self.create_array_symbol("params", params, |item| self.get_name_from_node(item)),
+            );
         }
 
         // Add workspaces
@@ -144,25 +157,26 @@ impl SymbolsProvider {
             }));
         }
 
-        // Add steps
-        if let Some(steps) = spec.get("steps") {
-            children.push(self.create_array_symbol_with_kind("steps", steps, SymbolKind::FUNCTION, |item| {
-                self.get_name_from_node(item)
-            }));
-        }
-
-        // Add sidecars
-        if let Some(sidecars) = spec.get("sidecars") {
-            children.push(self.create_array_symbol_with_kind("sidecars", sidecars, SymbolKind::FUNCTION, |item| {
-                self.get_name_from_node(item)
-            }));
+        // Add steps and sidecars, with the functions defined in their scripts
+        for field in ["steps", "sidecars"] {
+            if let Some(containers) = spec.get(field) {
+                let mut symbol = self.create_array_symbol_with_kind(
+                    field,
+                    containers,
+                    SymbolKind::FUNCTION,
+                    |item| self.get_name_from_node(item),
+                );
+                self.apply_display_names(&mut symbol, containers);
+                self.add_script_functions(&mut symbol, containers, encoding);
+                children.push(symbol);
+            }
         }
 
         // Add results
         if let Some(results) = spec.get("results") {
-            children.push(self.create_array_symbol("results", results, |item| {
-                self.get_name_from_node(item)
-            }));
+            children.push(
+                self.create_array_symbol("results", results, |item| self.get_name_from_node(item)),
+            );
         }
     }
 
@@ -171,14 +185,18 @@ impl SymbolsProvider {
     fn add_pipeline_run_spec_children(&self, children: &mut Vec<DocumentSymbol>, spec: &Node) {
         // Add pipelineRef
         if let Some(pipeline_ref) = spec.get("pipelineRef") {
-            children.push(self.create_section_symbol("pipelineRef", pipeline_ref, SymbolKind::PROPERTY));
+            children.push(self.create_section_symbol(
+                "pipelineRef",
+                pipeline_ref,
+                SymbolKind::PROPERTY,
+            ));
         }
 
         // Add params
         if let Some(params) = spec.get("params") {
-            children.push(self.create_array_symbol("params", params, |item| {
-                self.get_name_from_node(item)
-            }));
+            children.push(
+                self.create_array_symbol("params", params, |item| self.get_name_from_node(item)),
+            );
         }
 
         // Add workspaces
@@ -199,9 +217,9 @@ impl SymbolsProvider {
 
         // Add params
         if let Some(params) = spec.get("params") {
-            children.push(self.create_array_symbol("params", params, |item| {
-                self.get_name_from_node(item)
-            }));
+            children.push(
+                self.create_array_symbol("params", params, |item| self.get_name_from_node(item)),
+            );
         }
 
         // Add workspaces
@@ -275,7 +293,70 @@ impl SymbolsProvider {
             deprecated: None,
             range: node.range,
             selection_range: node.range,
-            children: if children.is_empty() { None } else { Some(children) },
+            children: if children.is_empty() {
+                None
+            } else {
+                Some(children)
+            },
+        }
+    }
+
+    /// Show the `displayName` of array items in place of their name, which
+    /// moves to the detail.
+    fn apply_display_names(&self, array_symbol: &mut DocumentSymbol, node: &Node) {
+        let (Some(symbols), NodeValue::Sequence(items)) = (&mut array_symbol.children, &node.value)
+        else {
+            return;
+        };
+        for (symbol, item) in symbols.iter_mut().zip(items) {
+            let Some(display_name) = item
+                .get("displayName")
+                .and_then(|n| n.as_scalar())
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+            else {
+                continue;
+            };
+            symbol.detail = Some(std::mem::replace(
+                &mut symbol.name,
+                display_name.to_string(),
+            ));
+        }
+    }
+
+    /// Add the functions defined in each container's script under its symbol.
+    #[allow(deprecated)]
+    fn add_script_functions(
+        &self,
+        array_symbol: &mut DocumentSymbol,
+        containers: &Node,
+        encoding: PositionEncoding,
+    ) {
+        let (Some(symbols), NodeValue::Sequence(items)) =
+            (&mut array_symbol.children, &containers.value)
+        else {
+            return;
+        };
+        for (symbol, item) in symbols.iter_mut().zip(items) {
+            let Some(script) = item.get("script") else {
+                continue;
+            };
+            let functions: Vec<DocumentSymbol> = script_functions(script, encoding)
+                .into_iter()
+                .map(|function| DocumentSymbol {
+                    name: function.name,
+                    detail: Some("script function".to_string()),
+                    kind: SymbolKind::METHOD,
+                    tags: None,
+                    deprecated: None,
+                    range: function.range,
+                    selection_range: function.name_range,
+                    children: None,
+                })
+                .collect();
+            if !functions.is_empty() {
+                symbol.children = Some(functions);
+            }
         }
     }
 
@@ -318,7 +399,10 @@ spec:
 
         let root = &symbols[0];
         assert!(root.name.contains("Pipeline"), "Root should be Pipeline");
-        assert!(root.name.contains("main-pipeline"), "Root should contain name");
+        assert!(
+            root.name.contains("main-pipeline"),
+            "Root should contain name"
+        );
         assert_eq!(root.kind, SymbolKind::CLASS);
 
         // Check children
@@ -330,7 +414,10 @@ spec:
         let spec_children = spec.children.as_ref().unwrap();
 
         // Check tasks array
-        let tasks = spec_children.iter().find(|c| c.name.starts_with("tasks")).unwrap();
+        let tasks = spec_children
+            .iter()
+            .find(|c| c.name.starts_with("tasks"))
+            .unwrap();
         assert!(tasks.name.contains("(2)"), "Tasks should show count of 2");
 
         let task_children = tasks.children.as_ref().unwrap();
@@ -368,7 +455,10 @@ spec:
         let spec_children = spec.children.as_ref().unwrap();
 
         // Check steps array
-        let steps = spec_children.iter().find(|c| c.name.starts_with("steps")).unwrap();
+        let steps = spec_children
+            .iter()
+            .find(|c| c.name.starts_with("steps"))
+            .unwrap();
         assert!(steps.name.contains("(2)"), "Steps should show count of 2");
 
         let step_children = steps.children.as_ref().unwrap();
@@ -378,6 +468,52 @@ spec:
         assert_eq!(step_children[0].kind, SymbolKind::FUNCTION);
     }
 
+    #[test]
+    fn test_display_names_replace_names() {
+        let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: t-1a2b
+      displayName: Build the image
+      taskSpec:
+        steps:
+          - name: s-1
+            image: alpine
+    - name: test
+      taskRef:
+        name: test-task
+  finally:
+    - name: f-9z
+      displayName: "Notify"
+      taskRef:
+        name: notify"#;
+
+        let yaml_doc = parser::parse_yaml("test.yaml", content).unwrap();
+        let symbols = SymbolsProvider::new().provide_symbols(&yaml_doc);
+
+        let spec = symbols[0]
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|c| c.name == "spec")
+            .unwrap();
+        let spec_children = spec.children.as_ref().unwrap();
+        let tasks = spec_children[0].children.as_ref().unwrap();
+        assert_eq!(tasks[0].name, "Build the image");
+        assert_eq!(tasks[0].detail.as_deref(), Some("t-1a2b"));
+        // Without a displayName, the name stays
+        assert_eq!(tasks[1].name, "test");
+        assert_eq!(tasks[1].detail, None);
+
+        let finally = spec_children[1].children.as_ref().unwrap();
+        assert_eq!(finally[0].name, "Notify");
+        assert_eq!(finally[0].detail.as_deref(), Some("f-9z"));
+    }
+
     #[test]
     fn test_empty_spec_symbols() {
         let content = r#"apiVersion: tekton.dev/v1
@@ -394,4 +530,58 @@ spec: {}"#;
         assert_eq!(symbols.len(), 1);
         assert!(symbols[0].name.contains("empty-pipeline"));
     }
+
+    #[test]
+    fn test_script_functions_are_nested_under_steps() {
+        let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: release
+spec:
+  steps:
+    - name: publish
+      image: alpine
+      script: |
+        upload() {
+          echo uploading
+        }
+        upload
+    - name: report
+      image: python
+      script: |
+        #!/usr/bin/env python3
+        def main():
+            print("done")
+        main()
+"#;
+
+        let yaml_doc = parser::parse_yaml("test.yaml", content).unwrap();
+        let symbols = SymbolsProvider::new().provide_symbols(&yaml_doc);
+
+        let spec = symbols[0]
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|c| c.name == "spec")
+            .unwrap();
+        let steps = spec
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|c| c.name.starts_with("steps"))
+            .unwrap();
+        let steps = steps.children.as_ref().unwrap();
+
+        let upload = &steps[0].children.as_ref().unwrap()[0];
+        assert_eq!(upload.name, "upload");
+        assert_eq!(upload.kind, SymbolKind::METHOD);
+        assert_eq!((upload.range.start.line, upload.range.end.line), (9, 11));
+        assert_eq!(upload.selection_range.start.character, 8);
+
+        let main = &steps[1].children.as_ref().unwrap()[0];
+        assert_eq!(main.name, "main");
+        assert_eq!((main.range.start.line, main.range.end.line), (17, 18));
+    }
 }
@@ -0,0 +1,297 @@
+//! Function definitions in embedded scripts.
+//!
+//! Finds shell functions (`name() {`, `function name {`) and Python
+//! definitions (`def name(`) in `script:` values, so long scripts can be
+//! navigated from the outline and folded per function. Like Tekton, scripts
+//! are taken to be shell scripts unless their shebang says otherwise.
+
+use tower_lsp::lsp_types::{Position, Range};
+
+use crate::parser::{Node, NodeValue, PositionEncoding, Style};
+
+/// A function defined in a script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptFunction {
+    pub name: String,
+    /// From the start of the definition to its last line
+    pub range: Range,
+    /// Range of the function name
+    pub name_range: Range,
+}
+
+/// Find the functions defined in a `script` node.
+///
+/// Only plain and block scalars are scanned: the value of quoted scalars
+/// no longer matches the source text.
+pub fn script_functions(script: &Node, encoding: PositionEncoding) -> Vec<ScriptFunction> {
+    let NodeValue::Scalar(text) = &script.value else {
+        return Vec::new();
+    };
+    if script.style != Style::Default {
+        return Vec::new();
+    }
+
+    let lines: Vec<&str> = text.lines().collect();
+    let language = script_language(&lines);
+    let start = script.value_range.start;
+    // Position of a byte offset of a line of the scalar
+    let position = |index: usize, byte: usize| {
+        let column = encoding.column(lines[index], byte);
+        Position {
+            line: start.line + index as u32,
+            character: if index == 0 {
+                start.character + column
+            } else {
+                column
+            },
+        }
+    };
+
+    let mut functions = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let Some(definition) = parse_definition(line, language) else {
+            continue;
+        };
+        let end = match language {
+            Language::Shell => shell_function_end(&lines, index),
+            Language::Python => python_function_end(&lines, index),
+        };
+        let indent = line.len() - line.trim_start().len();
+        functions.push(ScriptFunction {
+            name: definition.name.to_string(),
+            range: Range {
+                start: position(index, indent),
+                end: position(end, lines[end].trim_end().len()),
+            },
+            name_range: Range {
+                start: position(index, definition.offset),
+                end: position(index, definition.offset + definition.name.len()),
+            },
+        });
+    }
+    functions
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Language {
+    Shell,
+    Python,
+}
+
+/// Language of a script, from its shebang (the first line may be a block scalar header).
+fn script_language(lines: &[&str]) -> Language {
+    let shebang = lines
+        .iter()
+        .take(2)
+        .map(|line| line.trim_start())
+        .find(|line| line.starts_with("#!"));
+    match shebang {
+        Some(shebang) if shebang.contains("python") => Language::Python,
+        _ => Language::Shell,
+    }
+}
+
+/// A function definition found on a line.
+struct Definition<'a> {
+    name: &'a str,
+    /// Byte offset of the name in the line
+    offset: usize,
+}
+
+/// Recognize a function definition on a line.
+fn parse_definition(line: &str, language: Language) -> Option<Definition<'_>> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+
+    if language == Language::Python {
+        let rest = trimmed
+            .strip_prefix("async def ")
+            .or_else(|| trimmed.strip_prefix("def "))?;
+        let name = rest.trim_start();
+        let name = &name[..name.find('(')?];
+        return is_identifier(name).then(|| Definition {
+            name,
+            offset: indent + trimmed.len() - rest.trim_start().len(),
+        });
+    }
+
+    if let Some(rest) = trimmed.strip_prefix("function ") {
+        // `function name {` or `function name() {`
+        let rest_trimmed = rest.trim_start();
+        let end = rest_trimmed
+            .find(|c: char| c.is_whitespace() || c == '(' || c == '{')
+            .unwrap_or(rest_trimmed.len());
+        let name = &rest_trimmed[..end];
+        return is_identifier(name).then(|| Definition {
+            name,
+            offset: indent + trimmed.len() - rest_trimmed.len(),
+        });
+    }
+
+    // `name() {`, or `name()` with the brace on the next line
+    let open = trimmed.find('(')?;
+    let name = trimmed[..open].trim_end();
+    let after = trimmed[open + 1..]
+        .trim_start()
+        .strip_prefix(')')?
+        .trim_start();
+    let opens_body = after.is_empty() || after.starts_with('{');
+    (is_identifier(name) && opens_body).then_some(Definition {
+        name,
+        offset: indent,
+    })
+}
+
+/// Whether `name` can name a function (shell names may also contain `-`, `.` and `:`).
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+}
+
+/// Index of the line closing the shell function defined on line `start`.
+fn shell_function_end(lines: &[&str], start: usize) -> usize {
+    let mut depth = 0usize;
+    let mut opened = false;
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        for c in line.chars() {
+            match c {
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        if opened && depth == 0 {
+            return index;
+        }
+    }
+    last_non_blank(lines, start, lines.len())
+}
+
+/// Index of the last line of the Python function defined on line `start`:
+/// the body ends before the next line indented no deeper than the `def`.
+fn python_function_end(lines: &[&str], start: usize) -> usize {
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let def_indent = indent(lines[start]);
+    let next = lines
+        .iter()
+        .enumerate()
+        .skip(start + 1)
+        .find(|(_, line)| !line.trim().is_empty() && indent(line) <= def_indent)
+        .map(|(index, _)| index)
+        .unwrap_or(lines.len());
+    last_non_blank(lines, start, next)
+}
+
+/// Index of the last non-blank line in `start..end`, or `start`.
+fn last_non_blank(lines: &[&str], start: usize, end: usize) -> usize {
+    (start..end)
+        .rev()
+        .find(|&i| !lines[i].trim().is_empty())
+        .unwrap_or(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_yaml;
+
+    fn functions(yaml: &str) -> Vec<ScriptFunction> {
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        script_functions(doc.root.get("script").unwrap(), PositionEncoding::Utf16)
+    }
+
+    #[test]
+    fn test_shell_functions() {
+        let yaml = r#"script: |
+  #!/usr/bin/env bash
+  build() {
+    go build ./...
+  }
+
+  function publish {
+    if true; then echo "${IMAGE}"; fi
+  }
+  check_all ()
+  {
+    build
+  }
+  one() { echo one; }
+  build
+"#;
+
+        let found = functions(yaml);
+        let names: Vec<&str> = found.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "publish", "check_all", "one"]);
+
+        let build = &found[0];
+        assert_eq!(
+            build.range.start,
+            Position {
+                line: 2,
+                character: 2
+            }
+        );
+        assert_eq!(
+            build.range.end,
+            Position {
+                line: 4,
+                character: 3
+            }
+        );
+        assert_eq!(
+            build.name_range.end,
+            Position {
+                line: 2,
+                character: 7
+            }
+        );
+
+        assert_eq!((found[1].range.start.line, found[1].range.end.line), (6, 8));
+        assert_eq!(found[1].name_range.start.character, 11);
+        assert_eq!(
+            (found[2].range.start.line, found[2].range.end.line),
+            (9, 12)
+        );
+        assert_eq!(
+            (found[3].range.start.line, found[3].range.end.line),
+            (13, 13)
+        );
+    }
+
+    #[test]
+    fn test_python_functions() {
+        let yaml = r#"script: |
+  #!/usr/bin/env python3
+  import sys
+
+  def main(args):
+      for a in args:
+          print(a)
+
+  async def fetch(url):
+      pass
+  flush()
+  main(sys.argv)
+"#;
+
+        let found = functions(yaml);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].name, "main");
+        assert_eq!((found[0].range.start.line, found[0].range.end.line), (4, 6));
+        assert_eq!(found[0].name_range.start.character, 6);
+        assert_eq!(found[1].name, "fetch");
+        assert_eq!((found[1].range.start.line, found[1].range.end.line), (8, 9));
+    }
+
+    #[test]
+    fn test_calls_and_quoted_scripts_are_ignored() {
+        assert!(functions("script: |\n  echo $(date)\n  run_tests(1)\n").is_empty());
+        assert!(functions("script: \"f() { echo; }\"\n").is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+// apiVersion rules
+//
+// Every Tekton kind is served at a fixed set of group versions. An unknown
+// version makes the cluster reject the resource. tekton.dev/v1beta1 is still
+// served for the kinds that reached v1, but is deprecated in favor of it.
+
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::parser::YamlDocument;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag};
+
+/// The API versions each Tekton kind is served at, newest first.
+const API_VERSIONS: [(&str, &[&str]); 17] = [
+    ("Pipeline", &["tekton.dev/v1", "tekton.dev/v1beta1"]),
+    ("Task", &["tekton.dev/v1", "tekton.dev/v1beta1"]),
+    ("PipelineRun", &["tekton.dev/v1", "tekton.dev/v1beta1"]),
+    ("TaskRun", &["tekton.dev/v1", "tekton.dev/v1beta1"]),
+    ("ClusterTask", &["tekton.dev/v1beta1"]),
+    ("StepAction", &["tekton.dev/v1beta1", "tekton.dev/v1alpha1"]),
+    ("CustomRun", &["tekton.dev/v1beta1"]),
+    ("VerificationPolicy", &["tekton.dev/v1alpha1"]),
+    ("ResolutionRequest", &["resolution.tekton.dev/v1beta1"]),
+    (
+        "EventListener",
+        &[
+            "triggers.tekton.dev/v1beta1",
+            "triggers.tekton.dev/v1alpha1",
+        ],
+    ),
+    (
+        "TriggerTemplate",
+        &[
+            "triggers.tekton.dev/v1beta1",
+            "triggers.tekton.dev/v1alpha1",
+        ],
+    ),
+    (
+        "TriggerBinding",
+        &[
+            "triggers.tekton.dev/v1beta1",
+            "triggers.tekton.dev/v1alpha1",
+        ],
+    ),
+    (
+        "ClusterTriggerBinding",
+        &[
+            "triggers.tekton.dev/v1beta1",
+            "triggers.tekton.dev/v1alpha1",
+        ],
+    ),
+    (
+        "Trigger",
+        &[
+            "triggers.tekton.dev/v1beta1",
+            "triggers.tekton.dev/v1alpha1",
+        ],
+    ),
+    ("Interceptor", &["triggers.tekton.dev/v1alpha1"]),
+    ("ClusterInterceptor", &["triggers.tekton.dev/v1alpha1"]),
+    ("Repository", &["pipelinesascode.tekton.dev/v1alpha1"]),
+];
+
+/// The deprecated version of the kinds that reached tekton.dev/v1
+const DEPRECATED_VERSION: &str = "tekton.dev/v1beta1";
+
+/// Check the `apiVersion` of a Tekton resource against the versions its kind
+/// is served at.
+///
+/// Only documents whose apiVersion is in a Tekton group are checked, so that
+/// other operators' kinds with the same name are left alone.
+pub(super) fn check_api_version(doc: &YamlDocument, diagnostics: &mut Vec<Diagnostic>) {
+    let (Some(kind), Some(node)) = (doc.kind.as_deref(), doc.root.get("apiVersion")) else {
+        return;
+    };
+    let Some(api_version) = node.as_scalar() else {
+        return;
+    };
+    let group = api_version.rsplit_once('/').map_or("", |(group, _)| group);
+    if !(group == "tekton.dev" || group.ends_with(".tekton.dev")) {
+        return;
+    }
+    let Some((_, versions)) = API_VERSIONS.iter().find(|(k, _)| *k == kind) else {
+        return;
+    };
+
+    if !versions.contains(&api_version) {
+        diagnostics.push(make_diagnostic(
+            Rule::UnknownApiVersion,
+            node.value_range,
+            DiagnosticSeverity::ERROR,
+            format!(
+                "Unknown apiVersion '{}' for {}; expected one of: {}",
+                api_version,
+                kind,
+                versions.join(", ")
+            ),
+        ));
+    } else if api_version == DEPRECATED_VERSION && versions[0] == "tekton.dev/v1" {
+        let mut diagnostic = make_diagnostic(
+            Rule::DeprecatedApiVersion,
+            node.value_range,
+            DiagnosticSeverity::WARNING,
+            format!(
+                "{} is deprecated for {}; migrate to tekton.dev/v1",
+                api_version, kind
+            ),
+        );
+        diagnostic.tags = Some(vec![DiagnosticTag::DEPRECATED]);
+        diagnostics.push(diagnostic);
+    }
+}
@@ -0,0 +1,125 @@
+// Trusted artifacts
+//
+// Steps can declare the artifacts they consume and produce under `artifacts`,
+// and write them to `$(step.artifacts.path)` (or `$(artifacts.path)` for the
+// task). The feature only exists in tekton.dev/v1 and is off unless the
+// cluster sets the `enable-artifacts` feature flag, mirrored by the
+// `enableArtifacts` option.
+
+use super::params::collect_scalars;
+use super::rules::Rule;
+use super::suggestions::unknown_field;
+use super::tekton::make_diagnostic;
+use crate::model::{Step, Task};
+use crate::parser::expressions::{expressions_in, Reference};
+use crate::parser::{NodeValue, PositionEncoding};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Range};
+
+/// Fields of a step's `artifacts`
+const ARTIFACT_LISTS: [&str; 2] = ["inputs", "outputs"];
+
+/// The only API version with artifacts
+const ARTIFACTS_API_VERSION: &str = "tekton.dev/v1";
+
+/// Check the artifacts declared and used by the steps of a Task.
+pub(super) fn check_artifacts(
+    task: &Task,
+    api_version: Option<&str>,
+    enabled: bool,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for step in &task.steps {
+        let mut uses: Vec<Range> = step
+            .artifacts
+            .iter()
+            .map(|node| node.key_or_range())
+            .collect();
+        let mut scalars = Vec::new();
+        collect_scalars(step.node, &mut scalars);
+        uses.extend(
+            scalars
+                .into_iter()
+                .flat_map(|scalar| expressions_in(scalar, encoding))
+                .filter(|expr| expr.reference() == Reference::ArtifactsPath)
+                .map(|expr| expr.range),
+        );
+        uses.sort_by_key(|range| (range.start.line, range.start.character));
+
+        for range in uses {
+            if api_version.is_some_and(|version| version != ARTIFACTS_API_VERSION) {
+                diagnostics.push(make_diagnostic(
+                    Rule::ArtifactsApiVersion,
+                    range,
+                    DiagnosticSeverity::ERROR,
+                    format!("Artifacts are only available in {}", ARTIFACTS_API_VERSION),
+                ));
+            } else if !enabled {
+                diagnostics.push(make_diagnostic(
+                    Rule::ArtifactsFeatureFlag,
+                    range,
+                    DiagnosticSeverity::WARNING,
+                    "Artifacts require the 'enable-artifacts' feature flag; set the 'enableArtifacts' option if the cluster enables it".to_string(),
+                ));
+            }
+        }
+
+        check_declarations(step, diagnostics);
+    }
+}
+
+/// Check the shape of a step's `artifacts`: lists of named `inputs` and `outputs`.
+fn check_declarations(step: &Step, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(artifacts) = step.artifacts else {
+        return;
+    };
+    let step_name = step.display_name();
+    if !matches!(artifacts.value, NodeValue::Mapping(_)) {
+        diagnostics.push(make_diagnostic(
+            Rule::InvalidArtifacts,
+            artifacts.value_range,
+            DiagnosticSeverity::ERROR,
+            format!(
+                "'artifacts' of step '{}' must be a mapping with 'inputs' and 'outputs'",
+                step_name
+            ),
+        ));
+        return;
+    }
+
+    for (field, node) in artifacts.entries() {
+        if !ARTIFACT_LISTS.contains(&field) {
+            diagnostics.push(unknown_field(
+                field,
+                node.key_or_range(),
+                &ARTIFACT_LISTS,
+                &format!("artifacts of step '{}'", step_name),
+                true,
+            ));
+            continue;
+        }
+        let NodeValue::Sequence(items) = &node.value else {
+            diagnostics.push(make_diagnostic(
+                Rule::InvalidArtifacts,
+                node.value_range,
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "Artifact {} of step '{}' must be an array",
+                    field, step_name
+                ),
+            ));
+            continue;
+        };
+        for item in items.iter().filter(|item| item.get("name").is_none()) {
+            diagnostics.push(make_diagnostic(
+                Rule::MissingName,
+                item.range,
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "Each artifact in {} of step '{}' must have a 'name'",
+                    field, step_name
+                ),
+            ));
+        }
+    }
+}
@@ -0,0 +1,242 @@
+// Pipeline task graph
+//
+// Tasks run once every task listed in their `runAfter` has completed, so
+// every entry must name another task of `tasks`, and a cycle through
+// `runAfter` means none of the tasks on it can ever start. Tekton only
+// rejects such Pipelines at admission time.
+//
+// Using `$(tasks.<task>.results.<result>)` in a task's `params`, `when` or
+// `matrix` also makes it run after `<task>`. Anywhere else the reference
+// orders nothing, so the result may not exist yet.
+
+use super::params::collect_scalars;
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{Pipeline, PipelineTask};
+use crate::parser::expressions::{expressions_in, Expression, Reference};
+use crate::parser::PositionEncoding;
+use std::collections::{HashMap, VecDeque};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Report `runAfter` entries naming a finally task or no task at all.
+pub(super) fn check_run_after_names(pipeline: &Pipeline, diagnostics: &mut Vec<Diagnostic>) {
+    let tasks: Vec<&str> = pipeline
+        .tasks
+        .iter()
+        .filter_map(|t| t.name.map(|n| n.value))
+        .collect();
+    let finally: Vec<&str> = pipeline
+        .finally
+        .iter()
+        .filter_map(|t| t.name.map(|n| n.value))
+        .collect();
+
+    for task in &pipeline.tasks {
+        let Some(run_after) = &task.run_after else {
+            continue;
+        };
+        for entry in &run_after.value {
+            let message = if tasks.contains(&entry.value) {
+                continue;
+            } else if finally.contains(&entry.value) {
+                format!(
+                    "Pipeline task '{}' cannot run after finally task '{}'; finally tasks run after all tasks",
+                    task.display_name(),
+                    entry.value
+                )
+            } else {
+                format!(
+                    "Pipeline task '{}' runs after unknown task '{}'",
+                    task.display_name(),
+                    entry.value
+                )
+            };
+            diagnostics.push(make_diagnostic(
+                Rule::UnknownRunAfter,
+                entry.range,
+                DiagnosticSeverity::ERROR,
+                message,
+            ));
+        }
+    }
+}
+
+/// Report `runAfter` entries that close a dependency cycle between tasks.
+pub(super) fn check_run_after_cycles(pipeline: &Pipeline, diagnostics: &mut Vec<Diagnostic>) {
+    // Edges from each task to the tasks it runs after
+    let mut graph: HashMap<&str, Vec<&str>> = HashMap::new();
+    for task in &pipeline.tasks {
+        if let (Some(name), Some(run_after)) = (task.name, &task.run_after) {
+            graph
+                .entry(name.value)
+                .or_default()
+                .extend(run_after.value.iter().map(|entry| entry.value));
+        }
+    }
+
+    for task in &pipeline.tasks {
+        let (Some(name), Some(run_after)) = (task.name, &task.run_after) else {
+            continue;
+        };
+        for entry in &run_after.value {
+            let Some(path) = find_path(&graph, entry.value, name.value) else {
+                continue;
+            };
+            let cycle: Vec<&str> = std::iter::once(name.value).chain(path).collect();
+            diagnostics.push(make_diagnostic(
+                Rule::RunAfterCycle,
+                entry.range,
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "Pipeline task '{}' runs after '{}', which creates a cycle: {}",
+                    name.value,
+                    entry.value,
+                    cycle.join(" → ")
+                ),
+            ));
+        }
+    }
+}
+
+/// Fields of a pipeline task whose result references order it after the producing task
+const ORDERING_FIELDS: [&str; 3] = ["params", "when", "matrix"];
+
+/// Report task result references that do not guarantee the producing task runs first.
+pub(super) fn check_result_ordering(
+    pipeline: &Pipeline,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let finally: Vec<&str> = pipeline
+        .finally
+        .iter()
+        .filter_map(|t| t.name.map(|n| n.value))
+        .collect();
+
+    // Edges from each task to the tasks it runs after, explicitly or through results
+    let mut graph: HashMap<&str, Vec<&str>> = HashMap::new();
+    for task in &pipeline.tasks {
+        let Some(name) = task.name else {
+            continue;
+        };
+        let edges = graph.entry(name.value).or_default();
+        edges.extend(
+            task.run_after
+                .iter()
+                .flat_map(|r| r.value.iter().map(|entry| entry.value)),
+        );
+        for expr in result_references(task, encoding, true) {
+            if let Reference::TaskResult { task, .. } = expr.reference() {
+                if let Some(producer) = pipeline.task(&task.name).and_then(|t| t.name) {
+                    edges.push(producer.value);
+                }
+            }
+        }
+    }
+
+    for task in pipeline.all_tasks() {
+        let Some(name) = task.name else {
+            continue;
+        };
+        let ordering = result_references(task, encoding, true);
+        let others = result_references(task, encoding, false);
+        let references = ordering
+            .iter()
+            .map(|expr| (expr, true))
+            .chain(others.iter().map(|expr| (expr, false)));
+
+        for (expr, orders) in references {
+            let Reference::TaskResult { task: producer, .. } = expr.reference() else {
+                continue;
+            };
+            // Ordered by the reference itself or by running in `finally`, unless the
+            // producer is the task itself or a finally task
+            let ordered = orders
+                || finally.contains(&name.value)
+                || find_path(&graph, name.value, &producer.name).is_some();
+            let (severity, message) = if producer.name == name.value {
+                (
+                    DiagnosticSeverity::ERROR,
+                    format!("Pipeline task '{}' cannot use its own results", name.value),
+                )
+            } else if finally.contains(&producer.name.as_str()) {
+                (
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "Pipeline task '{}' cannot use results of finally task '{}', which is not guaranteed to run first",
+                        name.value, producer.name
+                    ),
+                )
+            } else if !ordered && pipeline.task(&producer.name).is_some() {
+                (
+                    DiagnosticSeverity::WARNING,
+                    format!(
+                        "Pipeline task '{}' uses a result of '{}' without running after it; add '{}' to 'runAfter' or pass the result as a param",
+                        name.value, producer.name, producer.name
+                    ),
+                )
+            } else {
+                continue;
+            };
+            diagnostics.push(make_diagnostic(
+                Rule::ResultOrdering,
+                expr.range,
+                severity,
+                message,
+            ));
+        }
+    }
+}
+
+/// Task result references in the fields of a pipeline task that order it
+/// (`ordering`), or in all its other fields.
+///
+/// `$(finally...)` references are left to the finally task rules.
+fn result_references(
+    task: &PipelineTask,
+    encoding: PositionEncoding,
+    ordering: bool,
+) -> Vec<Expression> {
+    let mut scalars = Vec::new();
+    for (key, child) in task.node.entries() {
+        if ORDERING_FIELDS.contains(&key) == ordering {
+            collect_scalars(child, &mut scalars);
+        }
+    }
+    scalars
+        .into_iter()
+        .flat_map(|scalar| expressions_in(scalar, encoding))
+        .filter(|expr| matches!(expr.reference(), Reference::TaskResult { .. }))
+        .filter(|expr| expr.path()[0] == "tasks")
+        .collect()
+}
+
+/// Shortest path from `from` to `to` following `runAfter` edges, both ends included.
+fn find_path<'a>(
+    graph: &HashMap<&'a str, Vec<&'a str>>,
+    from: &'a str,
+    to: &str,
+) -> Option<Vec<&'a str>> {
+    let mut previous: HashMap<&str, &str> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            let mut path = vec![current];
+            let mut node = current;
+            while node != from {
+                node = previous[node];
+                path.push(node);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for &next in graph.get(current).into_iter().flatten() {
+            if next != from && !previous.contains_key(next) {
+                previous.insert(next, current);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
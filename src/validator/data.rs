@@ -0,0 +1,180 @@
+// Diagnostic data
+//
+// Every diagnostic of the validator carries a JSON `data` object, so that
+// the code actions provider and external tools can act on it without
+// parsing its message:
+//
+// ```json
+// {
+//   "code": "unknown-field",
+//   "path": "spec.tasks[0].retry",
+//   "fix": { "id": "remove-field", "field": "retry" },
+//   "resource": "Pipeline/ci"
+// }
+// ```
+
+use crate::model::PipelineTask;
+use crate::parser::positions::contains;
+use crate::parser::{Node, NodeValue, YamlDocument};
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::{Diagnostic, Position, TextEdit};
+
+/// The `data` payload of a validator diagnostic.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticData {
+    /// Rule that reported the diagnostic, e.g. `unknown-field`
+    pub code: String,
+    /// Path of the node the diagnostic starts on, e.g. `spec.tasks[0].runAfter`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Fix the code actions provider can offer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<Fix>,
+    /// Indexed resource the diagnostic is about, as `Kind/name`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+}
+
+/// A fix for a diagnostic, identified by its `id`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "id", rename_all = "kebab-case")]
+pub enum Fix {
+    /// Remove the field the diagnostic is on
+    RemoveField { field: String },
+    /// Rename the field the diagnostic is on, e.g. a typo of a known field
+    RenameField { field: String, to: String },
+    /// Add a missing field below the diagnostic
+    AddField { field: String },
+    /// Rewrite `taskRef.bundle` into the bundles resolver form
+    BundleResolver { edit: TextEdit },
+}
+
+impl DiagnosticData {
+    /// Data of a diagnostic reported by a rule.
+    pub fn new(code: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            path: None,
+            fix: None,
+            resource: None,
+        }
+    }
+
+    /// The data of a diagnostic, if it has some.
+    pub fn of(diagnostic: &Diagnostic) -> Option<Self> {
+        serde_json::from_value(diagnostic.data.clone()?).ok()
+    }
+}
+
+/// Update the data of a validator diagnostic.
+pub(super) fn update(diagnostic: &mut Diagnostic, f: impl FnOnce(&mut DiagnosticData)) {
+    let Some(mut data) = DiagnosticData::of(diagnostic) else {
+        return;
+    };
+    f(&mut data);
+    diagnostic.data = serde_json::to_value(data).ok();
+}
+
+/// Attach a fix to a validator diagnostic.
+pub(super) fn with_fix(mut diagnostic: Diagnostic, fix: Fix) -> Diagnostic {
+    update(&mut diagnostic, |data| data.fix = Some(fix));
+    diagnostic
+}
+
+/// Attach the `Kind/name` of the indexed resource a diagnostic is about.
+pub(super) fn with_resource(mut diagnostic: Diagnostic, kind: &str, name: &str) -> Diagnostic {
+    update(&mut diagnostic, |data| {
+        data.resource = Some(format!("{}/{}", kind, name))
+    });
+    diagnostic
+}
+
+/// Attach the indexed Task a pipeline task refers to, if it has no inline
+/// `taskSpec`.
+pub(super) fn with_task_resource(diagnostic: Diagnostic, task: &PipelineTask) -> Diagnostic {
+    let task_ref = task.task_ref.as_ref().filter(|_| task.task_spec.is_none());
+    match task_ref.and_then(|r| Some((r.kind_or_default(), r.name?.value))) {
+        Some((kind, name)) => with_resource(diagnostic, kind, name),
+        None => diagnostic,
+    }
+}
+
+/// Fill in the path of every diagnostic from where its range starts.
+pub(super) fn add_paths(doc: &YamlDocument, diagnostics: &mut [Diagnostic]) {
+    for diagnostic in diagnostics {
+        let path = node_path(&doc.root, diagnostic.range.start);
+        if !path.is_empty() {
+            update(diagnostic, |data| data.path = Some(path));
+        }
+    }
+}
+
+/// Dotted path of the innermost node at a position, e.g. `spec.tasks[0].name`.
+fn node_path(root: &Node, position: Position) -> String {
+    let mut path = String::new();
+    let mut node = root;
+    loop {
+        let child = match &node.value {
+            NodeValue::Mapping(map) => map
+                .iter()
+                .find(|(_, child)| contains(child.range, position))
+                .map(|(key, child)| {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(key);
+                    child
+                }),
+            NodeValue::Sequence(items) => items
+                .iter()
+                .enumerate()
+                .find(|(_, child)| contains(child.range, position))
+                .map(|(i, child)| {
+                    path.push_str(&format!("[{}]", i));
+                    child
+                }),
+            _ => None,
+        };
+        match child {
+            Some(child) => node = child,
+            None => return path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_yaml;
+
+    #[test]
+    fn test_node_path() {
+        let doc = parse_yaml(
+            "test.yaml",
+            "spec:\n  tasks:\n    - name: build\n      runAfter: [lint]\n",
+        )
+        .unwrap();
+        let at = |line, character| node_path(&doc.root, Position { line, character });
+        assert_eq!(at(3, 6), "spec.tasks[0].runAfter");
+        assert_eq!(at(2, 14), "spec.tasks[0].name");
+        assert_eq!(at(0, 0), "spec");
+    }
+
+    #[test]
+    fn test_fix_serialization() {
+        let data = DiagnosticData {
+            fix: Some(Fix::RemoveField {
+                field: "retry".to_string(),
+            }),
+            ..DiagnosticData::new("unknown-field")
+        };
+        assert_eq!(
+            serde_json::to_value(&data).unwrap(),
+            serde_json::json!({
+                "code": "unknown-field",
+                "fix": { "id": "remove-field", "field": "retry" }
+            })
+        );
+    }
+}
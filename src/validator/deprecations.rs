@@ -0,0 +1,191 @@
+// Deprecation rules
+//
+// Constructs Tekton deprecated on the way to tekton.dev/v1: PipelineResources
+// (`resources`), ClusterTasks, Conditions and the `timeout` of PipelineRuns.
+// They are reported with the DEPRECATED tag, so that editors strike them
+// through, and as errors in tekton.dev/v1 resources, which no longer accept
+// the fields.
+
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{Pipeline, PipelineRun, Task, TaskRef, TaskRun};
+use crate::parser::{Node, YamlDocument};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, Range};
+
+/// Deprecated fields of a Pipeline spec, reported by these rules rather than
+/// as unknown fields
+pub(super) const DEPRECATED_PIPELINE_FIELDS: [&str; 1] = ["resources"];
+
+/// Deprecated fields of a pipeline task, reported by these rules rather than
+/// as unknown fields
+pub(super) const DEPRECATED_PIPELINE_TASK_FIELDS: [&str; 2] = ["resources", "conditions"];
+
+/// How to replace a ClusterTask
+const CLUSTER_TASK_HINT: &str = "use a Task fetched with the cluster resolver instead";
+
+/// Flag the deprecated ClusterTask and Condition kinds.
+pub(super) fn check_deprecated_kind(doc: &YamlDocument, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(node) = doc.root.get("kind") else {
+        return;
+    };
+    let (rule, hint) = match node.as_scalar() {
+        Some("ClusterTask") => (Rule::DeprecatedClusterTask, CLUSTER_TASK_HINT),
+        Some("Condition") => (Rule::DeprecatedConditions, "use 'when' expressions instead"),
+        _ => return,
+    };
+    diagnostics.push(deprecation(
+        rule,
+        node.value_range,
+        false,
+        format!(
+            "Kind '{}' is deprecated; {}",
+            node.as_scalar().unwrap_or_default(),
+            hint
+        ),
+    ));
+}
+
+/// Flag the PipelineResources of a Task, standalone or embedded.
+pub(super) fn check_task_deprecations(
+    task: &Task,
+    api_version: Option<&str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let Some(spec) = task.spec {
+        check_resources(spec, "Task", api_version, diagnostics);
+    }
+}
+
+/// Flag the PipelineResources, Conditions and ClusterTask references of a Pipeline.
+pub(super) fn check_pipeline_deprecations(
+    pipeline: &Pipeline,
+    api_version: Option<&str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let Some(spec) = pipeline.spec {
+        check_resources(spec, "Pipeline", api_version, diagnostics);
+    }
+    let labelled = pipeline
+        .tasks
+        .iter()
+        .map(|t| ("Pipeline task", t))
+        .chain(pipeline.finally.iter().map(|t| ("Finally task", t)));
+    for (label, task) in labelled {
+        let owner = format!("{} '{}'", label, task.display_name());
+        check_resources(task.node, &owner, api_version, diagnostics);
+        if let Some(conditions) = task.node.get("conditions") {
+            diagnostics.push(deprecation(
+                Rule::DeprecatedConditions,
+                conditions.key_or_range(),
+                is_v1(api_version),
+                format!(
+                    "'conditions' of {} {}; use 'when' expressions instead",
+                    owner,
+                    status(api_version)
+                ),
+            ));
+        }
+        if let Some(task_ref) = &task.task_ref {
+            check_cluster_task_ref(task_ref, diagnostics);
+        }
+    }
+}
+
+/// Flag the PipelineResources and `timeout` of a PipelineRun.
+pub(super) fn check_pipeline_run_deprecations(
+    run: &PipelineRun,
+    api_version: Option<&str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(spec) = run.spec else {
+        return;
+    };
+    check_resources(spec, "PipelineRun", api_version, diagnostics);
+    if let Some(timeout) = spec.get("timeout") {
+        diagnostics.push(deprecation(
+            Rule::DeprecatedRunTimeout,
+            timeout.key_or_range(),
+            is_v1(api_version),
+            format!(
+                "'timeout' of PipelineRun {}; use 'timeouts.pipeline' instead",
+                status(api_version)
+            ),
+        ));
+    }
+}
+
+/// Flag the PipelineResources and ClusterTask reference of a TaskRun.
+pub(super) fn check_task_run_deprecations(
+    run: &TaskRun,
+    api_version: Option<&str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let Some(spec) = run.spec {
+        check_resources(spec, "TaskRun", api_version, diagnostics);
+    }
+    if let Some(task_ref) = &run.task_ref {
+        check_cluster_task_ref(task_ref, diagnostics);
+    }
+}
+
+/// Flag the `resources` (PipelineResources) of a spec or pipeline task.
+fn check_resources(
+    node: &Node,
+    owner: &str,
+    api_version: Option<&str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(resources) = node.get("resources") else {
+        return;
+    };
+    diagnostics.push(deprecation(
+        Rule::DeprecatedPipelineResources,
+        resources.key_or_range(),
+        is_v1(api_version),
+        format!(
+            "'resources' (PipelineResources) of {} {}; use workspaces and params instead",
+            owner,
+            status(api_version)
+        ),
+    ));
+}
+
+/// Flag a `taskRef` to a ClusterTask.
+fn check_cluster_task_ref(task_ref: &TaskRef, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(kind) = task_ref.kind.filter(|k| k.value == "ClusterTask") else {
+        return;
+    };
+    diagnostics.push(deprecation(
+        Rule::DeprecatedClusterTask,
+        kind.range,
+        false,
+        format!("Kind 'ClusterTask' is deprecated; {}", CLUSTER_TASK_HINT),
+    ));
+}
+
+/// Whether a resource is served at tekton.dev/v1, which removed the
+/// deprecated fields.
+fn is_v1(api_version: Option<&str>) -> bool {
+    api_version == Some("tekton.dev/v1")
+}
+
+/// What happened to a deprecated field at an API version.
+fn status(api_version: Option<&str>) -> &'static str {
+    if is_v1(api_version) {
+        "was removed in tekton.dev/v1"
+    } else {
+        "is deprecated"
+    }
+}
+
+/// A diagnostic tagged as deprecated: an error once the construct is removed.
+fn deprecation(rule: Rule, range: Range, removed: bool, message: String) -> Diagnostic {
+    let severity = if removed {
+        DiagnosticSeverity::ERROR
+    } else {
+        DiagnosticSeverity::WARNING
+    };
+    let mut diagnostic = make_diagnostic(rule, range, severity, message);
+    diagnostic.tags = Some(vec![DiagnosticTag::DEPRECATED]);
+    diagnostic
+}
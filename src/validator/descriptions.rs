@@ -0,0 +1,112 @@
+// Description lint
+//
+// `description` is legal on the spec of Tasks and Pipelines and on their
+// params, results and workspaces. It must be a string, and its first line is
+// what the Dashboard, Hub and editors show as a summary, so long first lines
+// get truncated.
+
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{ParamSpec, Pipeline, ResultSpec, Task, WorkspaceDeclaration};
+use crate::parser::{Node, NodeValue};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// The parts of a Task or Pipeline that can carry a description.
+pub(super) struct Describable<'s, 'a> {
+    pub spec: Option<&'a Node>,
+    pub params: &'s [ParamSpec<'a>],
+    pub results: &'s [ResultSpec<'a>],
+    pub workspaces: &'s [WorkspaceDeclaration<'a>],
+}
+
+impl<'s, 'a> From<&'s Task<'a>> for Describable<'s, 'a> {
+    fn from(task: &'s Task<'a>) -> Self {
+        Self {
+            spec: task.spec,
+            params: &task.params,
+            results: &task.results,
+            workspaces: &task.workspaces,
+        }
+    }
+}
+
+impl<'s, 'a> From<&'s Pipeline<'a>> for Describable<'s, 'a> {
+    fn from(pipeline: &'s Pipeline<'a>) -> Self {
+        Self {
+            spec: pipeline.spec,
+            params: &pipeline.params,
+            results: &pipeline.results,
+            workspaces: &pipeline.workspaces,
+        }
+    }
+}
+
+/// Check every description of a Task or Pipeline.
+///
+/// `max_length` limits the first line of each description; 0 disables the check.
+pub(super) fn check_descriptions(
+    owner: &Describable,
+    max_length: usize,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let Some(spec) = owner.spec {
+        check_description(spec, "spec", max_length, diagnostics);
+    }
+
+    let elements = owner
+        .params
+        .iter()
+        .map(|p| ("param", p.name, p.node))
+        .chain(owner.results.iter().map(|r| ("result", r.name, r.node)))
+        .chain(
+            owner
+                .workspaces
+                .iter()
+                .map(|w| ("workspace", w.name, w.node)),
+        );
+    for (element, name, node) in elements {
+        let label = match name {
+            Some(name) => format!("{} '{}'", element, name.value),
+            None => element.to_string(),
+        };
+        check_description(node, &label, max_length, diagnostics);
+    }
+}
+
+/// Check the `description` entry of `owner`, if any.
+fn check_description(
+    owner: &Node,
+    label: &str,
+    max_length: usize,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(description) = owner.get("description") else {
+        return;
+    };
+
+    let Some(text) = description.scalar_content() else {
+        if !matches!(description.value, NodeValue::Null) {
+            diagnostics.push(make_diagnostic(
+                Rule::InvalidDescription,
+                description.value_range,
+                DiagnosticSeverity::ERROR,
+                format!("Description of {} must be a string", label),
+            ));
+        }
+        return;
+    };
+
+    let summary = text.lines().next().unwrap_or("").trim();
+    let length = summary.chars().count();
+    if max_length > 0 && length > max_length {
+        diagnostics.push(make_diagnostic(
+            Rule::DescriptionTooLong,
+            description.key_or_range(),
+            DiagnosticSeverity::WARNING,
+            format!(
+                "First line of the description of {} is {} characters long; UIs truncate it after {} characters, move details to the following lines",
+                label, length, max_length
+            ),
+        ));
+    }
+}
@@ -0,0 +1,141 @@
+// Step environment variables
+//
+// Each entry of a step's `env` is a Kubernetes `EnvVar`: a `name`, and either
+// a literal `value` or a `valueFrom` reading it from a Secret, a ConfigMap,
+// a field of the pod or a resource of the container. Kubernetes rejects a
+// malformed entry when the pod is created, long after the Task was applied.
+
+use super::rules::Rule;
+use super::suggestions::unknown_field;
+use super::tekton::make_diagnostic;
+use crate::model::Step;
+use crate::parser::{Node, NodeValue};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Sources of a `valueFrom`, with the fields each one requires
+const VALUE_SOURCES: [(&str, &[&str]); 4] = [
+    ("secretKeyRef", &["name", "key"]),
+    ("configMapKeyRef", &["name", "key"]),
+    ("fieldRef", &["fieldPath"]),
+    ("resourceFieldRef", &["resource"]),
+];
+
+/// Check the `env` entries of steps.
+pub(super) fn check_step_env(steps: &[Step], diagnostics: &mut Vec<Diagnostic>) {
+    for step in steps {
+        let Some(env) = step.node.get("env") else {
+            continue;
+        };
+        let step_name = step.display_name();
+        let NodeValue::Sequence(entries) = &env.value else {
+            diagnostics.push(make_diagnostic(
+                Rule::InvalidEnv,
+                env.value_range,
+                DiagnosticSeverity::ERROR,
+                format!("'env' of step '{}' must be an array", step_name),
+            ));
+            continue;
+        };
+        for entry in entries {
+            check_env_var(entry, step_name, diagnostics);
+        }
+    }
+}
+
+/// Check one `env` entry: a name, and exactly one of `value` or `valueFrom`.
+fn check_env_var(entry: &Node, step_name: &str, diagnostics: &mut Vec<Diagnostic>) {
+    if !matches!(entry.value, NodeValue::Mapping(_)) {
+        diagnostics.push(make_diagnostic(
+            Rule::InvalidEnv,
+            entry.value_range,
+            DiagnosticSeverity::ERROR,
+            format!(
+                "Env entries of step '{}' must be mappings with a 'name'",
+                step_name
+            ),
+        ));
+        return;
+    }
+    let Some((name_node, name)) = entry
+        .get("name")
+        .and_then(|node| Some((node, node.as_scalar()?)))
+    else {
+        diagnostics.push(make_diagnostic(
+            Rule::InvalidEnv,
+            entry.key_or_range(),
+            DiagnosticSeverity::ERROR,
+            format!("Env entry of step '{}' is missing 'name'", step_name),
+        ));
+        return;
+    };
+
+    match (entry.get("value"), entry.get("valueFrom")) {
+        (Some(_), Some(value_from)) => diagnostics.push(make_diagnostic(
+            Rule::EnvValue,
+            value_from.key_or_range(),
+            DiagnosticSeverity::ERROR,
+            format!(
+                "Env '{}' of step '{}' sets both 'value' and 'valueFrom'",
+                name, step_name
+            ),
+        )),
+        (None, None) => diagnostics.push(make_diagnostic(
+            Rule::EnvValue,
+            name_node.value_range,
+            DiagnosticSeverity::WARNING,
+            format!(
+                "Env '{}' of step '{}' has neither 'value' nor 'valueFrom'",
+                name, step_name
+            ),
+        )),
+        (None, Some(value_from)) => check_value_from(value_from, name, diagnostics),
+        (Some(_), None) => {}
+    }
+}
+
+/// Check a `valueFrom`: exactly one source, with the fields it requires.
+fn check_value_from(value_from: &Node, name: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let sources: Vec<(&str, &Node)> = value_from.entries().collect();
+    let known = VALUE_SOURCES.map(|(source, _)| source);
+    let message = match sources.as_slice() {
+        [(source, node)] => match VALUE_SOURCES.iter().find(|(s, _)| s == source) {
+            Some((_, required)) => {
+                for field in required.iter().filter(|f| node.get(f).is_none()) {
+                    diagnostics.push(make_diagnostic(
+                        Rule::InvalidValueFrom,
+                        node.key_or_range(),
+                        DiagnosticSeverity::ERROR,
+                        format!("'{}' of env '{}' is missing '{}'", source, name, field),
+                    ));
+                }
+                return;
+            }
+            None => {
+                diagnostics.push(unknown_field(
+                    source,
+                    node.key_or_range(),
+                    &known,
+                    &format!("'valueFrom' of env '{}'", name),
+                    true,
+                ));
+                return;
+            }
+        },
+        [] => format!(
+            "'valueFrom' of env '{}' must set one of: {}",
+            name,
+            known.join(", ")
+        ),
+        _ => format!(
+            "'valueFrom' of env '{}' must set only one of: {}",
+            name,
+            known.join(", ")
+        ),
+    };
+    diagnostics.push(make_diagnostic(
+        Rule::InvalidValueFrom,
+        value_from.key_or_range(),
+        DiagnosticSeverity::ERROR,
+        message,
+    ));
+}
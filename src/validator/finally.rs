@@ -0,0 +1,98 @@
+// Finally tasks
+//
+// Finally tasks run once every task of `tasks` is done, whether it succeeded
+// or not. They can read the results and the execution status of `tasks`
+// (`$(tasks.<task>.status)`), which regular tasks cannot. The results of
+// finally tasks are only available to the Pipeline's `results`, as
+// `$(finally.<task>.results.<result>)`; `$(tasks.<task>...)` only names
+// regular tasks there.
+//
+// `runAfter` on finally tasks and names shared with `tasks` are checked with
+// the structure and the names of the Pipeline.
+
+use super::params::collect_scalars;
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use super::when::check_status_references;
+use crate::model::Pipeline;
+use crate::parser::expressions::{expressions_in, Expression, Reference};
+use crate::parser::{Node, PositionEncoding};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Fields of a regular pipeline task where the status of tasks is not known yet
+/// (`when` is checked with the when expressions)
+const STATUS_FIELDS: [&str; 2] = ["params", "matrix"];
+
+/// Check the references to finally tasks, and the finally-only variables
+/// used by regular tasks.
+pub(super) fn check_finally_references(
+    pipeline: &Pipeline,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for task in pipeline.all_tasks() {
+        for expr in expressions(task.node, encoding) {
+            if expr.path().first() == Some(&"finally") {
+                diagnostics.push(make_diagnostic(
+                    Rule::FinallyReference,
+                    expr.range,
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "'$({})' can only be used in the Pipeline's results",
+                        expr.text
+                    ),
+                ));
+            }
+        }
+    }
+
+    for task in &pipeline.tasks {
+        let task_name = task.display_name();
+        for field in STATUS_FIELDS.iter().filter_map(|f| task.node.get(f)) {
+            check_status_references(field, task_name, encoding, diagnostics);
+        }
+    }
+
+    let is_finally = |name: &str| {
+        pipeline
+            .finally
+            .iter()
+            .any(|t| t.name.is_some_and(|n| n.value == name))
+    };
+    for value in pipeline.results.iter().filter_map(|r| r.value) {
+        for expr in expressions(value, encoding) {
+            let Reference::TaskResult { task, result } = expr.reference() else {
+                continue;
+            };
+            let message = match expr.path()[0] {
+                "tasks" if is_finally(&task.name) => format!(
+                    "'{}' is a finally task; reference its result as '$(finally.{}.results.{})'",
+                    task.name, task.name, result.name
+                ),
+                "finally" if !is_finally(&task.name) && pipeline.task(&task.name).is_some() => {
+                    format!(
+                        "'{}' is not a finally task; reference its result as '$(tasks.{}.results.{})'",
+                        task.name, task.name, result.name
+                    )
+                }
+                _ => continue,
+            };
+            diagnostics.push(make_diagnostic(
+                Rule::FinallyReference,
+                expr.segments[0].range,
+                DiagnosticSeverity::ERROR,
+                message,
+            ));
+        }
+    }
+}
+
+/// Every expression in the scalars of a node.
+fn expressions(node: &Node, encoding: PositionEncoding) -> Vec<Expression> {
+    let mut scalars = Vec::new();
+    collect_scalars(node, &mut scalars);
+    scalars
+        .into_iter()
+        .flat_map(|scalar| expressions_in(scalar, encoding))
+        .collect()
+}
@@ -0,0 +1,100 @@
+// v1 migration rules
+//
+// Fields that tekton.dev/v1beta1 accepted but tekton.dev/v1 removed. They
+// are reported as errors in v1 resources and as deprecations in v1beta1
+// ones, with the equivalent v1 form attached to the diagnostic so the code
+// actions provider can offer to rewrite it.
+
+use super::data::{with_fix, Fix};
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::TaskRef;
+use crate::parser::{to_yaml_indented, Node};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range, TextEdit};
+
+/// Flag `taskRef.bundle`, replaced by the bundles resolver in v1.
+pub(super) fn check_task_ref_bundle(
+    task_ref: &TaskRef,
+    api_version: Option<&str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(bundle) = task_ref.bundle else {
+        return;
+    };
+
+    let mut diagnostic = if api_version == Some("tekton.dev/v1") {
+        make_diagnostic(
+            Rule::RemovedBundle,
+            bundle.key_or_range(),
+            DiagnosticSeverity::ERROR,
+            "'taskRef.bundle' was removed in tekton.dev/v1; use the bundles resolver".to_string(),
+        )
+    } else {
+        make_diagnostic(
+            Rule::DeprecatedBundle,
+            bundle.key_or_range(),
+            DiagnosticSeverity::WARNING,
+            "'taskRef.bundle' is deprecated; use the bundles resolver".to_string(),
+        )
+    };
+    if let Some(edit) = bundle_resolver_edit(task_ref) {
+        diagnostic = with_fix(diagnostic, Fix::BundleResolver { edit });
+    }
+    diagnostics.push(diagnostic);
+}
+
+/// Edit rewriting a `taskRef` with a `bundle` into its bundles resolver form.
+///
+/// ```yaml
+/// taskRef:            taskRef:
+///   name: build         resolver: bundles
+///   bundle: img   =>    params:
+///                         - name: bundle
+///                           value: img
+///                         - name: name
+///                           value: build
+///                         - name: kind
+///                           value: task
+/// ```
+fn bundle_resolver_edit(task_ref: &TaskRef) -> Option<TextEdit> {
+    let bundle = task_ref.bundle?;
+    let key_range = task_ref.node.key_range?;
+
+    let param = |name: &str, value: &str| {
+        Node::mapping([("name", Node::scalar(name)), ("value", Node::scalar(value))])
+    };
+    let mut params = vec![param("bundle", bundle.value)];
+    if let Some(name) = task_ref.name {
+        params.push(param("name", name.value));
+    }
+    params.push(param("kind", &task_ref.kind_or_default().to_lowercase()));
+
+    let mut entries = Vec::new();
+    for (key, child) in task_ref.node.entries() {
+        match key {
+            "name" => {}
+            "bundle" => {
+                entries.push(("resolver", Node::scalar("bundles")));
+                entries.push(("params", Node::sequence(params.clone())));
+            }
+            _ => entries.push((key, child.clone())),
+        }
+    }
+
+    // Replace from the `taskRef` key to the end of its last line
+    let indent = key_range.start.character as usize;
+    let text = to_yaml_indented(
+        &Node::mapping([("taskRef", Node::mapping(entries))]),
+        indent,
+    );
+    Some(TextEdit {
+        range: Range {
+            start: key_range.start,
+            end: Position {
+                line: task_ref.node.last_content_line() + 1,
+                character: 0,
+            },
+        },
+        new_text: text[indent..].to_string(),
+    })
+}
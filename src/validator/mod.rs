@@ -1,5 +1,36 @@
 // Validation module for Tekton resources
 
+mod api_versions;
+mod artifacts;
+mod dag;
+mod data;
+mod deprecations;
+mod descriptions;
+mod env;
+mod finally;
+mod migration;
+mod names;
+mod outputs;
+mod pac;
+mod param_types;
+mod params;
+mod pipeline;
+mod references;
+mod resources;
+mod result_types;
+mod results;
+mod rules;
+mod runs;
+mod security;
+mod step_actions;
+mod suggestions;
+mod suppressions;
 mod tekton;
+mod triggers;
+mod unused;
+mod when;
+mod workspaces;
 
-pub use tekton::TektonValidator;
+pub use data::{DiagnosticData, Fix};
+pub use rules::{find_rule, Docs, Rule, RuleInfo, RuleLevel, RULES};
+pub use tekton::{TektonValidator, ValidatorOptions};
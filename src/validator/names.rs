@@ -0,0 +1,188 @@
+// Name uniqueness and format
+//
+// Tekton rejects a Pipeline or Task that declares two params, workspaces,
+// results, steps or pipeline tasks with the same name. The duplicate is
+// reported with a link to the first declaration.
+//
+// Kubernetes names follow RFC 1123: `metadata.name` must be a DNS subdomain
+// (lowercase alphanumerics, '-' and '.', at most 253 characters) and pipeline
+// task names, which end up in labels, a DNS label (no '.', at most 63).
+
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{Pipeline, Spanned, Task};
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Url,
+};
+
+/// Check the names declared by a Task (or an inline `taskSpec`).
+pub(super) fn check_task_names(task: &Task, uri: Option<&Url>, diagnostics: &mut Vec<Diagnostic>) {
+    check_unique(
+        "param",
+        task.params.iter().map(|p| p.name),
+        uri,
+        diagnostics,
+    );
+    check_unique(
+        "workspace",
+        task.workspaces.iter().map(|w| w.name),
+        uri,
+        diagnostics,
+    );
+    check_unique(
+        "result",
+        task.results.iter().map(|r| r.name),
+        uri,
+        diagnostics,
+    );
+    check_unique("step", task.steps.iter().map(|s| s.name), uri, diagnostics);
+}
+
+/// Check the names declared by a Pipeline; `tasks` and `finally` share one namespace.
+pub(super) fn check_pipeline_names(
+    pipeline: &Pipeline,
+    uri: Option<&Url>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    check_unique(
+        "param",
+        pipeline.params.iter().map(|p| p.name),
+        uri,
+        diagnostics,
+    );
+    check_unique(
+        "workspace",
+        pipeline.workspaces.iter().map(|w| w.name),
+        uri,
+        diagnostics,
+    );
+    check_unique(
+        "result",
+        pipeline.results.iter().map(|r| r.name),
+        uri,
+        diagnostics,
+    );
+    check_unique(
+        "pipeline task",
+        pipeline.all_tasks().map(|t| t.name),
+        uri,
+        diagnostics,
+    );
+}
+
+/// Longest DNS-1123 label
+const MAX_LABEL_LENGTH: usize = 63;
+
+/// Longest DNS-1123 subdomain
+const MAX_SUBDOMAIN_LENGTH: usize = 253;
+
+/// Check that a `metadata.name` is a DNS-1123 subdomain.
+pub(super) fn check_resource_name(name: Spanned<&str>, diagnostics: &mut Vec<Diagnostic>) {
+    check_format("Name", name, MAX_SUBDOMAIN_LENGTH, true, diagnostics);
+}
+
+/// Check that every pipeline task name is a DNS-1123 label.
+pub(super) fn check_pipeline_task_name_format(
+    pipeline: &Pipeline,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for name in pipeline.all_tasks().filter_map(|t| t.name) {
+        check_format(
+            "Pipeline task name",
+            name,
+            MAX_LABEL_LENGTH,
+            false,
+            diagnostics,
+        );
+    }
+}
+
+/// Report the first RFC 1123 violation of a name. Names holding `$(...)`
+/// variables are only known once resolved, and are skipped.
+fn check_format(
+    what: &str,
+    name: Spanned<&str>,
+    max_length: usize,
+    allow_dots: bool,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let value = name.value;
+    if value.is_empty() || value.contains("$(") {
+        return;
+    }
+
+    let allowed = |c: char| {
+        c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || (allow_dots && c == '.')
+    };
+    let message = if value.chars().any(|c| c.is_ascii_uppercase()) {
+        format!("{} '{}' must be lowercase", what, value)
+    } else if value.contains('_') {
+        format!("{} '{}' cannot contain '_'; use '-' instead", what, value)
+    } else if let Some(c) = value.chars().find(|c| !allowed(*c)) {
+        let expected = if allow_dots {
+            "lowercase letters, digits, '-' and '.'"
+        } else {
+            "lowercase letters, digits and '-'"
+        };
+        format!(
+            "{} '{}' cannot contain '{}'; use only {}",
+            what, value, c, expected
+        )
+    } else if !value.starts_with(|c: char| c.is_ascii_alphanumeric())
+        || !value.ends_with(|c: char| c.is_ascii_alphanumeric())
+    {
+        format!(
+            "{} '{}' must start and end with a lowercase letter or digit",
+            what, value
+        )
+    } else if value.len() > max_length {
+        format!(
+            "{} '{}' is {} characters long; the maximum is {}",
+            what,
+            value,
+            value.len(),
+            max_length
+        )
+    } else {
+        return;
+    };
+    diagnostics.push(make_diagnostic(
+        Rule::InvalidName,
+        name.range,
+        DiagnosticSeverity::ERROR,
+        message,
+    ));
+}
+
+/// Report every name already used by an earlier element.
+fn check_unique<'a>(
+    what: &str,
+    names: impl Iterator<Item = Option<Spanned<&'a str>>>,
+    uri: Option<&Url>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut seen: Vec<Spanned<&str>> = Vec::new();
+    for name in names.flatten() {
+        let Some(first) = seen.iter().find(|s| s.value == name.value) else {
+            seen.push(name);
+            continue;
+        };
+
+        let mut diagnostic = make_diagnostic(
+            Rule::DuplicateName,
+            name.range,
+            DiagnosticSeverity::ERROR,
+            format!("Duplicate {} name '{}'", what, name.value),
+        );
+        diagnostic.related_information = uri.map(|uri| {
+            vec![DiagnosticRelatedInformation {
+                location: Location {
+                    uri: uri.clone(),
+                    range: first.range,
+                },
+                message: format!("First {} named '{}'", what, name.value),
+            }]
+        });
+        diagnostics.push(diagnostic);
+    }
+}
@@ -0,0 +1,66 @@
+// Step output rules
+//
+// `stdoutConfig` and `stderrConfig` make a step also write its standard
+// output or error to a file, usually a result or a file in a workspace. Each
+// is a mapping with a single `path`; anything else is rejected by the webhook.
+
+use super::rules::Rule;
+use super::suggestions::unknown_field;
+use super::tekton::make_diagnostic;
+use crate::model::Step;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Fields of a step whose output stream is written to a file
+const OUTPUT_CONFIGS: [&str; 2] = ["stdoutConfig", "stderrConfig"];
+
+/// Fields of a `stdoutConfig` or `stderrConfig`
+const OUTPUT_CONFIG_FIELDS: [&str; 1] = ["path"];
+
+/// Check the `stdoutConfig` and `stderrConfig` of steps.
+pub(super) fn check_step_outputs(steps: &[Step], diagnostics: &mut Vec<Diagnostic>) {
+    for step in steps {
+        for field in OUTPUT_CONFIGS {
+            let Some(config) = step.node.get(field) else {
+                continue;
+            };
+            let owner = format!("'{}' of step '{}'", field, step.display_name());
+            if !config.is_mapping() {
+                diagnostics.push(make_diagnostic(
+                    Rule::InvalidOutputConfig,
+                    config.value_range,
+                    DiagnosticSeverity::ERROR,
+                    format!("{} must be a mapping with a 'path'", owner),
+                ));
+                continue;
+            }
+
+            for (key, node) in config.entries() {
+                if !OUTPUT_CONFIG_FIELDS.contains(&key) {
+                    diagnostics.push(unknown_field(
+                        key,
+                        node.key_or_range(),
+                        &OUTPUT_CONFIG_FIELDS,
+                        &owner,
+                        true,
+                    ));
+                }
+            }
+
+            match config.get("path") {
+                Some(path) if path.as_scalar().is_some_and(|p| !p.trim().is_empty()) => {}
+                Some(path) => diagnostics.push(make_diagnostic(
+                    Rule::InvalidOutputConfig,
+                    path.value_range,
+                    DiagnosticSeverity::ERROR,
+                    format!("'path' of {} must be a non-empty string", owner),
+                )),
+                None => diagnostics.push(make_diagnostic(
+                    Rule::InvalidOutputConfig,
+                    config.key_or_range(),
+                    DiagnosticSeverity::ERROR,
+                    format!("{} must have a 'path'", owner),
+                )),
+            }
+        }
+    }
+}
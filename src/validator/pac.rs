@@ -0,0 +1,48 @@
+// Pipelines-as-Code template variables
+//
+// Pipelines-as-Code substitutes `{{ variable }}` templates in the files of a
+// repository's `.tekton/` directory before applying them. A misspelled
+// variable is left as is, so the run gets the literal template. Variables
+// can also be custom params of the Repository, which the server does not
+// see: unknown ones are warnings.
+
+use super::params::collect_scalars;
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::pac::{is_known_variable, is_pac_document};
+use crate::parser::templates::templates_in;
+use crate::parser::YamlDocument;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Check the template variables of a document in a `.tekton/` directory.
+pub(super) fn check_pac_variables(doc: &YamlDocument, diagnostics: &mut Vec<Diagnostic>) {
+    if !is_pac_document(&doc.filename) {
+        return;
+    }
+
+    let mut scalars = Vec::new();
+    collect_scalars(&doc.root, &mut scalars);
+    for template in scalars
+        .iter()
+        .flat_map(|scalar| templates_in(scalar, doc.encoding))
+    {
+        if template.name.is_empty() {
+            diagnostics.push(make_diagnostic(
+                Rule::EmptyPacTemplate,
+                template.range,
+                DiagnosticSeverity::WARNING,
+                "Empty Pipelines-as-Code template".to_string(),
+            ));
+        } else if !is_known_variable(&template.name) {
+            diagnostics.push(make_diagnostic(
+                Rule::UnknownPacVariable,
+                template.name_range,
+                DiagnosticSeverity::WARNING,
+                format!(
+                    "'{}' is not a standard Pipelines-as-Code variable; it must be a param of the Repository",
+                    template.name
+                ),
+            ));
+        }
+    }
+}
@@ -0,0 +1,134 @@
+// Param type rules
+//
+// Params can be `string` (the default), `array` or `object`. The default of
+// a param must have its type: a sequence for arrays, a mapping for objects
+// and a scalar for strings. Object params declare their keys under
+// `properties`. Only array and object params can be expanded with `[*]`:
+// `$(params.foo[*])` on a string param is rejected when the resource is
+// created.
+
+use super::params::collect_scope_scalars;
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::ParamSpec;
+use crate::parser::expressions::{expressions_in, Reference};
+use crate::parser::{Node, NodeValue, PositionEncoding};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Types a param can declare
+const PARAM_TYPES: [&str; 3] = ["string", "array", "object"];
+
+/// Check the `type`, `default` and `properties` of param declarations.
+pub(super) fn check_param_declarations(params: &[ParamSpec], diagnostics: &mut Vec<Diagnostic>) {
+    for param in params {
+        let name = param.name.map(|n| n.value).unwrap_or("unnamed");
+
+        if let Some(param_type) = param.param_type {
+            if !PARAM_TYPES.contains(&param_type.value) {
+                diagnostics.push(make_diagnostic(
+                    Rule::InvalidParamType,
+                    param_type.range,
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "Invalid type '{}' for param '{}'; expected one of: {}",
+                        param_type.value,
+                        name,
+                        PARAM_TYPES.join(", ")
+                    ),
+                ));
+                continue;
+            }
+        }
+
+        let param_type = param.type_or_default();
+        if let Some(default) = param.default {
+            let matches = matches!(
+                (&default.value, param_type),
+                (NodeValue::Null, _)
+                    | (NodeValue::Sequence(_), "array")
+                    | (NodeValue::Mapping(_), "object")
+                    | (NodeValue::Scalar(_), "string")
+            );
+            if !matches {
+                diagnostics.push(make_diagnostic(
+                    Rule::ParamDefaultType,
+                    default.value_range,
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "Default of {} param '{}' must be {}, not {}",
+                        param_type,
+                        name,
+                        expected_shape(param_type),
+                        shape(default)
+                    ),
+                ));
+            }
+        }
+
+        if param_type == "object" && param.properties.is_none() {
+            diagnostics.push(make_diagnostic(
+                Rule::MissingProperties,
+                param.param_type.map_or(param.node.range, |t| t.range),
+                DiagnosticSeverity::ERROR,
+                format!("Object param '{}' must declare its 'properties'", name),
+            ));
+        }
+    }
+}
+
+/// Report `$(params.foo[*])` expansions of string params below `spec`,
+/// outside of its `params` declarations and of the `nested` specs.
+pub(super) fn check_param_expansions(
+    spec: &Node,
+    nested: &[&Node],
+    params: &[ParamSpec],
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut scalars = Vec::new();
+    for (_, child) in spec.entries().filter(|(key, _)| *key != "params") {
+        collect_scope_scalars(child, nested, &mut scalars);
+    }
+
+    for expr in scalars
+        .into_iter()
+        .flat_map(|scalar| expressions_in(scalar, encoding))
+        .filter(|expr| expr.wildcard)
+    {
+        let Reference::Param { name } = expr.reference() else {
+            continue;
+        };
+        let declared = params
+            .iter()
+            .find(|p| p.name.is_some_and(|n| n.value == name.name));
+        if declared.is_some_and(|p| p.type_or_default() == "string") {
+            diagnostics.push(make_diagnostic(
+                Rule::StringParamExpansion,
+                expr.range,
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "Param '{}' is a string; '[*]' only applies to array and object params",
+                    name.name
+                ),
+            ));
+        }
+    }
+}
+
+/// How a value of a param type is written
+fn expected_shape(param_type: &str) -> &'static str {
+    match param_type {
+        "array" => "an array",
+        "object" => "a mapping",
+        _ => "a string",
+    }
+}
+
+/// How a value is written
+fn shape(node: &Node) -> &'static str {
+    match node.value {
+        NodeValue::Sequence(_) => "an array",
+        NodeValue::Mapping(_) => "a mapping",
+        _ => "a string",
+    }
+}
@@ -0,0 +1,204 @@
+// Param rules
+//
+// Tekton does not substitute variables in param defaults: a default of
+// `$(params.other)` or `$(tasks.build.results.digest)` reaches the step
+// verbatim, which is rarely what the author intended.
+//
+// A `$(params.foo)` reference to a param that the enclosing Task or Pipeline
+// does not declare is rejected when the resource is created.
+
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{ParamSpec, Pipeline, PipelineTask, Task};
+use crate::parser::expressions::{expressions_in, Reference};
+use crate::parser::{Node, NodeValue, PositionEncoding};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Report param and task result references inside param defaults.
+pub(super) fn check_param_defaults(
+    params: &[ParamSpec],
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for param in params {
+        let Some(default) = param.default else {
+            continue;
+        };
+        let param_name = param.name.map(|n| n.value).unwrap_or("unnamed");
+
+        let mut scalars = Vec::new();
+        collect_scalars(default, &mut scalars);
+        for expr in scalars
+            .into_iter()
+            .flat_map(|scalar| expressions_in(scalar, encoding))
+        {
+            let what = match expr.reference() {
+                Reference::Param { .. } => "param",
+                Reference::TaskResult { .. } => "task result",
+                _ => continue,
+            };
+            diagnostics.push(make_diagnostic(
+                Rule::VariableInParamDefault,
+                expr.range,
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "Default of param '{}' references {} '$({})'; variables are not substituted in param defaults",
+                    param_name, what, expr.text
+                ),
+            ));
+        }
+    }
+}
+
+/// Report `$(params.x)` references to params a Task does not declare.
+pub(super) fn check_task_param_references(
+    task: &Task,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(spec) = task.spec else {
+        return;
+    };
+    let declared: Vec<&str> = declared_names(&task.params).collect();
+    check_param_references(spec, &[], &declared, "spec.params", encoding, diagnostics);
+}
+
+/// Report `$(params.x)` references to params a Pipeline does not declare.
+///
+/// Embedded `taskSpec`s are checked on their own, by [`check_task_spec_param_references`].
+pub(super) fn check_pipeline_param_references(
+    pipeline: &Pipeline,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(spec) = pipeline.spec else {
+        return;
+    };
+    let task_specs: Vec<&Node> = pipeline
+        .all_tasks()
+        .filter_map(|task| task.task_spec.as_ref()?.spec)
+        .collect();
+    let declared: Vec<&str> = declared_names(&pipeline.params).collect();
+    check_param_references(
+        spec,
+        &task_specs,
+        &declared,
+        "spec.params",
+        encoding,
+        diagnostics,
+    );
+}
+
+/// Report `$(params.x)` references in the `taskSpec` of a pipeline task to params
+/// that are neither declared by it, passed to it, nor propagated from the Pipeline.
+pub(super) fn check_task_spec_param_references(
+    pipeline: &Pipeline,
+    task: &PipelineTask,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(task_spec) = &task.task_spec else {
+        return;
+    };
+    let Some(spec) = task_spec.spec else {
+        return;
+    };
+    let declared: Vec<&str> = declared_names(&task_spec.params)
+        .chain(task.params.iter().filter_map(|p| p.name.map(|n| n.value)))
+        .chain(declared_names(&pipeline.params))
+        .collect();
+    let scope = format!(
+        "the taskSpec of pipeline task '{}', its params or spec.params",
+        task.name.map(|n| n.value).unwrap_or("unnamed")
+    );
+    check_param_references(spec, &[], &declared, &scope, encoding, diagnostics);
+}
+
+/// Report `$(params.x)` references to params a StepAction does not declare.
+pub(super) fn check_step_action_param_references(
+    spec: &Node,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let declared: Vec<&str> = match spec.get("params").map(|p| &p.value) {
+        Some(NodeValue::Sequence(params)) => params
+            .iter()
+            .filter_map(|p| p.get("name")?.as_scalar())
+            .collect(),
+        _ => Vec::new(),
+    };
+    check_param_references(spec, &[], &declared, "spec.params", encoding, diagnostics);
+}
+
+fn declared_names<'a>(params: &'a [ParamSpec]) -> impl Iterator<Item = &'a str> + 'a {
+    params.iter().filter_map(|p| p.name.map(|n| n.value))
+}
+
+/// Flag param references below `spec`, outside of its `params` declarations
+/// (covered by [`check_param_defaults`]) and of the `nested` specs.
+fn check_param_references(
+    spec: &Node,
+    nested: &[&Node],
+    declared: &[&str],
+    scope: &str,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut scalars = Vec::new();
+    for (_, child) in spec.entries().filter(|(key, _)| *key != "params") {
+        collect_scope_scalars(child, nested, &mut scalars);
+    }
+
+    for expr in scalars
+        .into_iter()
+        .flat_map(|scalar| expressions_in(scalar, encoding))
+    {
+        let Reference::Param { name } = expr.reference() else {
+            continue;
+        };
+        if !declared.contains(&name.name.as_str()) {
+            diagnostics.push(make_diagnostic(
+                Rule::UnknownParam,
+                expr.range,
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "Unknown param '{}'; it is not declared in {}",
+                    name.name, scope
+                ),
+            ));
+        }
+    }
+}
+
+/// Collect every scalar below `node`, skipping the `nested` nodes.
+pub(super) fn collect_scope_scalars<'a>(
+    node: &'a Node,
+    nested: &[&Node],
+    scalars: &mut Vec<&'a Node>,
+) {
+    if nested.iter().any(|n| std::ptr::eq(*n, node)) {
+        return;
+    }
+    match &node.value {
+        NodeValue::Scalar(_) => scalars.push(node),
+        NodeValue::Sequence(items) => items
+            .iter()
+            .for_each(|item| collect_scope_scalars(item, nested, scalars)),
+        NodeValue::Mapping(_) => node
+            .entries()
+            .for_each(|(_, child)| collect_scope_scalars(child, nested, scalars)),
+        NodeValue::Null => {}
+    }
+}
+
+/// Collect every scalar below `node`, e.g. for defaults of array and object params.
+pub(super) fn collect_scalars<'a>(node: &'a Node, scalars: &mut Vec<&'a Node>) {
+    match &node.value {
+        NodeValue::Scalar(_) => scalars.push(node),
+        NodeValue::Sequence(items) => items.iter().for_each(|item| collect_scalars(item, scalars)),
+        NodeValue::Mapping(_) => node
+            .entries()
+            .for_each(|(_, child)| collect_scalars(child, scalars)),
+        NodeValue::Null => {}
+    }
+}
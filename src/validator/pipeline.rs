@@ -0,0 +1,202 @@
+// Pipeline structure rules
+//
+// Checks the entries of `tasks` and `finally` the way the Tekton webhook
+// does: every pipeline task needs a name and exactly one task definition,
+// param entries need a name, and `finally` tasks cannot use `runAfter`
+// since they always run after all of `tasks`.
+
+use super::deprecations::DEPRECATED_PIPELINE_TASK_FIELDS;
+use super::rules::Rule;
+use super::suggestions::unknown_field;
+use super::tekton::make_diagnostic;
+use crate::model::{ParamSpec, ParamValue, Pipeline, PipelineTask};
+use crate::parser::Node;
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Range, Url,
+};
+
+/// Fields of a pipeline task (`tasks[]` and `finally[]`)
+const PIPELINE_TASK_FIELDS: [&str; 15] = [
+    "name",
+    "displayName",
+    "description",
+    "taskRef",
+    "taskSpec",
+    "pipelineRef",
+    "pipelineSpec",
+    "runAfter",
+    "params",
+    "matrix",
+    "workspaces",
+    "when",
+    "timeout",
+    "retries",
+    "onError",
+];
+
+/// Fields defining what a pipeline task runs; exactly one is required
+const TASK_DEFINITION_FIELDS: [&str; 4] = ["taskRef", "taskSpec", "pipelineRef", "pipelineSpec"];
+
+/// Check the params, tasks and finally tasks of a Pipeline.
+pub(super) fn check_pipeline_structure(
+    pipeline: &Pipeline,
+    uri: Option<&Url>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    check_param_spec_names(&pipeline.params, diagnostics);
+
+    for task in &pipeline.tasks {
+        check_pipeline_task(task, "Pipeline task", uri, diagnostics);
+    }
+    for task in &pipeline.finally {
+        check_pipeline_task(task, "Finally task", uri, diagnostics);
+        if let Some(run_after) = &task.run_after {
+            diagnostics.push(make_diagnostic(
+                Rule::FinallyRunAfter,
+                run_after.key_or_range(),
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "Finally task '{}' cannot use 'runAfter'; finally tasks run after all tasks",
+                    task.display_name()
+                ),
+            ));
+        }
+    }
+}
+
+/// Check a single entry of `tasks` or `finally`.
+fn check_pipeline_task(
+    task: &PipelineTask,
+    label: &str,
+    uri: Option<&Url>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if !task.node.is_mapping() {
+        diagnostics.push(make_diagnostic(
+            Rule::InvalidPipelineTask,
+            task.node.range,
+            DiagnosticSeverity::ERROR,
+            format!("Each {} must be a mapping", label),
+        ));
+        return;
+    }
+
+    let name = task.display_name();
+    if task.name.is_none() {
+        diagnostics.push(make_diagnostic(
+            Rule::MissingName,
+            first_key_range(task.node),
+            DiagnosticSeverity::ERROR,
+            format!("{} must have a 'name'", label),
+        ));
+    }
+
+    let definitions: Vec<(&str, &Node)> = TASK_DEFINITION_FIELDS
+        .into_iter()
+        .filter_map(|field| Some((field, task.node.get(field)?)))
+        .collect();
+    match definitions.as_slice() {
+        [] => diagnostics.push(make_diagnostic(
+            Rule::MissingTaskDefinition,
+            task.name
+                .map(|n| n.key_or_range())
+                .unwrap_or(task.node.range),
+            DiagnosticSeverity::ERROR,
+            format!(
+                "{} '{}' must define one of 'taskRef' or 'taskSpec'",
+                label, name
+            ),
+        )),
+        [_] => {}
+        conflicts => report_conflicts(
+            &format!("{} '{}'", label, name),
+            conflicts,
+            uri,
+            diagnostics,
+        ),
+    }
+
+    for (field, node) in task.node.entries() {
+        if !PIPELINE_TASK_FIELDS.contains(&field)
+            && !DEPRECATED_PIPELINE_TASK_FIELDS.contains(&field)
+        {
+            diagnostics.push(unknown_field(
+                field,
+                node.key_or_range(),
+                &PIPELINE_TASK_FIELDS,
+                &format!("{} '{}'", label, name),
+                false,
+            ));
+        }
+    }
+
+    check_param_value_names(&task.params, &format!("{} '{}'", label, name), diagnostics);
+}
+
+/// Every param declaration needs a name.
+fn check_param_spec_names(params: &[ParamSpec], diagnostics: &mut Vec<Diagnostic>) {
+    for param in params.iter().filter(|p| p.name.is_none()) {
+        diagnostics.push(make_diagnostic(
+            Rule::MissingName,
+            first_key_range(param.node),
+            DiagnosticSeverity::ERROR,
+            "Pipeline param must have a 'name'".to_string(),
+        ));
+    }
+}
+
+/// Every param passed to a pipeline task needs a name.
+fn check_param_value_names(params: &[ParamValue], owner: &str, diagnostics: &mut Vec<Diagnostic>) {
+    for param in params.iter().filter(|p| p.name.is_none()) {
+        diagnostics.push(make_diagnostic(
+            Rule::MissingName,
+            first_key_range(param.node),
+            DiagnosticSeverity::ERROR,
+            format!("Param of {} must have a 'name'", owner),
+        ));
+    }
+}
+
+/// Range of the first key of a mapping, to flag the entry without covering all of it
+pub(super) fn first_key_range(node: &Node) -> Range {
+    node.entries()
+        .next()
+        .map_or(node.range, |(_, first)| first.key_or_range())
+}
+
+/// Report fields of `owner` that cannot be set together, on each of their
+/// keys, with the other keys as related information.
+pub(super) fn report_conflicts(
+    owner: &str,
+    fields: &[(&str, &Node)],
+    uri: Option<&Url>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let names: Vec<String> = fields.iter().map(|(f, _)| format!("'{}'", f)).collect();
+    let message = match names.as_slice() {
+        [first, second] => format!("{} cannot have both {} and {}", owner, first, second),
+        _ => format!("{} can only have one of {}", owner, names.join(", ")),
+    };
+    for (field, node) in fields {
+        let mut diagnostic = make_diagnostic(
+            Rule::ConflictingFields,
+            node.key_or_range(),
+            DiagnosticSeverity::ERROR,
+            message.clone(),
+        );
+        diagnostic.related_information = uri.map(|uri| {
+            fields
+                .iter()
+                .filter(|(other, _)| other != field)
+                .map(|(other, other_node)| DiagnosticRelatedInformation {
+                    location: Location {
+                        uri: uri.clone(),
+                        range: other_node.key_or_range(),
+                    },
+                    message: format!("'{}' is also set here", other),
+                })
+                .collect()
+        });
+        diagnostics.push(diagnostic);
+    }
+}
@@ -0,0 +1,196 @@
+// Cross-file reference rules
+//
+// A pipeline task's `taskRef.name` names a Task (or ClusterTask) that should
+// be defined somewhere in the workspace. Refs fetched by a resolver or from
+// a bundle, and custom tasks (`apiVersion` + `kind`), live elsewhere and are
+// not checked. A pipeline task whose `taskRef` is indexed, and a PipelineRun
+// whose `pipelineRef` is, must pass every param of the referenced resource
+// without a default, and only params it declares. Matrix params count as
+// passed params.
+
+use super::data::with_resource;
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{ParamValue, Pipeline, PipelineRun, PipelineTask};
+use crate::parser::Node;
+use crate::workspace::index::ResourceDefinition;
+use crate::workspace::WorkspaceIndex;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Range};
+
+/// Warn about `taskRef`s that do not resolve to an indexed Task.
+pub(super) fn check_task_refs(
+    pipeline: &Pipeline,
+    index: Option<&WorkspaceIndex>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(index) = index else {
+        return;
+    };
+    for task in pipeline.all_tasks() {
+        let Some(task_ref) = &task.task_ref else {
+            continue;
+        };
+        let remote = task_ref.resolver.is_some() || task_ref.bundle.is_some();
+        if remote || task_ref.api_version.is_some() {
+            continue;
+        }
+        let Some(name) = task_ref.name else {
+            continue;
+        };
+        let kind = task_ref.kind_or_default();
+        if index.find_resource(kind, name.value).is_none() {
+            diagnostics.push(make_diagnostic(
+                Rule::UnresolvedTaskRef,
+                name.range,
+                DiagnosticSeverity::WARNING,
+                format!(
+                    "{} '{}' of pipeline task '{}' is not defined in the workspace",
+                    kind,
+                    name.value,
+                    task.display_name()
+                ),
+            ));
+        }
+    }
+}
+
+/// Check the params pipeline tasks pass against their indexed Tasks.
+pub(super) fn check_task_ref_params(
+    pipeline: &Pipeline,
+    index: Option<&WorkspaceIndex>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(index) = index else {
+        return;
+    };
+    for task in pipeline.all_tasks().filter(|t| t.task_spec.is_none()) {
+        let Some(task_ref) = &task.task_ref else {
+            continue;
+        };
+        let Some(name) = task_ref.name else {
+            continue;
+        };
+        let Some(resource) = index.find_resource(task_ref.kind_or_default(), name.value) else {
+            continue;
+        };
+        let owner = format!("Pipeline task '{}'", task.display_name());
+        let anchor = task.name.map_or(task.node.range, |n| n.range);
+        check_passed_params(&passed_params(task), &owner, anchor, &resource, diagnostics);
+    }
+}
+
+/// Check the params a PipelineRun passes against its indexed Pipeline.
+pub(super) fn check_run_params(
+    run: &PipelineRun,
+    index: Option<&WorkspaceIndex>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(name) = run.pipeline_ref.as_ref().and_then(|r| r.name) else {
+        return;
+    };
+    let Some(pipeline) = index.and_then(|index| index.find_resource("Pipeline", name.value)) else {
+        return;
+    };
+    let passed = names_of(&run.params);
+    check_passed_params(&passed, "PipelineRun", name.range, &pipeline, diagnostics);
+}
+
+/// Report passed params the resource does not declare, and params it
+/// requires that are not passed, on `anchor`.
+fn check_passed_params(
+    passed: &[(&str, Range)],
+    owner: &str,
+    anchor: Range,
+    resource: &ResourceDefinition,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (param, range) in passed {
+        if resource.params.iter().any(|p| p.name == *param) {
+            continue;
+        }
+        let names: Vec<&str> = resource.params.iter().map(|p| p.name.as_str()).collect();
+        let declared = if names.is_empty() {
+            "it declares no params".to_string()
+        } else {
+            format!("declared params: {}", names.join(", "))
+        };
+        diagnostics.push(link_resource(
+            make_diagnostic(
+                Rule::UnknownParam,
+                *range,
+                DiagnosticSeverity::WARNING,
+                format!(
+                    "{} '{}' declares no param '{}'; {}",
+                    resource.kind, resource.name, param, declared
+                ),
+            ),
+            resource,
+        ));
+    }
+
+    for param in resource.params.iter().filter(|p| p.default.is_none()) {
+        if !passed.iter().any(|(name, _)| *name == param.name) {
+            diagnostics.push(link_resource(
+                make_diagnostic(
+                    Rule::MissingParam,
+                    anchor,
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "{} does not pass param '{}', which {} '{}' requires",
+                        owner, param.name, resource.kind, resource.name
+                    ),
+                ),
+                resource,
+            ));
+        }
+    }
+}
+
+/// Names of the params a pipeline task passes, including its matrix params
+/// and those of `matrix.include` combinations.
+fn passed_params<'a>(task: &PipelineTask<'a>) -> Vec<(&'a str, Range)> {
+    let mut passed = names_of(&task.params);
+    let Some(matrix) = task.node.get("matrix") else {
+        return passed;
+    };
+    let mut lists = vec![matrix.get("params")];
+    lists.extend(
+        matrix
+            .get("include")
+            .into_iter()
+            .flat_map(Node::items)
+            .map(|i| i.get("params")),
+    );
+    for name in lists
+        .into_iter()
+        .flatten()
+        .flat_map(Node::items)
+        .filter_map(|param| param.get("name"))
+    {
+        let Some(value) = name.as_scalar() else {
+            continue;
+        };
+        if !passed.iter().any(|(n, _)| *n == value) {
+            passed.push((value, name.value_range));
+        }
+    }
+    passed
+}
+
+/// Names of params passed by value, with their ranges.
+fn names_of<'a>(params: &[ParamValue<'a>]) -> Vec<(&'a str, Range)> {
+    params
+        .iter()
+        .filter_map(|p| p.name.map(|n| (n.value, n.range)))
+        .collect()
+}
+
+/// Point a diagnostic at the indexed resource it is about.
+pub(super) fn link_resource(diagnostic: Diagnostic, resource: &ResourceDefinition) -> Diagnostic {
+    let mut diagnostic = with_resource(diagnostic, &resource.kind, &resource.name);
+    diagnostic.related_information = Some(vec![DiagnosticRelatedInformation {
+        location: resource.location.clone(),
+        message: format!("{} '{}' is defined here", resource.kind, resource.name),
+    }]);
+    diagnostic
+}
@@ -0,0 +1,178 @@
+// Compute resources
+//
+// `computeResources.requests` and `computeResources.limits` of steps, the
+// step template and sidecars are Kubernetes quantities: a decimal number with
+// an optional binary (`Ki`, `Mi`, ...), decimal (`m`, `k`, `M`, ...) or
+// exponent (`e3`) suffix. Kubernetes rejects a malformed quantity, or a
+// request above its limit, only when the pod is created.
+
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::Task;
+use crate::parser::{Node, NodeValue};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Binary suffixes and their multipliers
+const BINARY_SUFFIXES: [(&str, i32); 6] = [
+    ("Ki", 10),
+    ("Mi", 20),
+    ("Gi", 30),
+    ("Ti", 40),
+    ("Pi", 50),
+    ("Ei", 60),
+];
+
+/// Decimal suffixes and their powers of ten
+const DECIMAL_SUFFIXES: [(&str, i32); 9] = [
+    ("n", -9),
+    ("u", -6),
+    ("m", -3),
+    ("k", 3),
+    ("M", 6),
+    ("G", 9),
+    ("T", 12),
+    ("P", 15),
+    ("E", 18),
+];
+
+/// Check the compute resources of the steps, step template and sidecars of a Task.
+pub(super) fn check_compute_resources(task: &Task, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(spec) = task.spec else {
+        return;
+    };
+    if let Some(template) = spec.get("stepTemplate") {
+        check_container(template, "stepTemplate", diagnostics);
+    }
+    for step in &task.steps {
+        check_container(
+            step.node,
+            &format!("step '{}'", step.display_name()),
+            diagnostics,
+        );
+    }
+    if let Some(NodeValue::Sequence(sidecars)) = spec.get("sidecars").map(|s| &s.value) {
+        for sidecar in sidecars {
+            let name = sidecar
+                .get("name")
+                .and_then(|n| n.as_scalar())
+                .unwrap_or("unnamed");
+            check_container(sidecar, &format!("sidecar '{}'", name), diagnostics);
+        }
+    }
+}
+
+/// Check the requests and limits of a container, described as `container`.
+fn check_container(container: &Node, label: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(resources) = container.get("computeResources") else {
+        return;
+    };
+    let requests = quantities(resources.get("requests"), label, diagnostics);
+    let limits = quantities(resources.get("limits"), label, diagnostics);
+
+    for (resource, request, node) in &requests {
+        let Some((_, limit, limit_node)) = limits.iter().find(|(r, _, _)| r == resource) else {
+            continue;
+        };
+        if request > limit {
+            diagnostics.push(make_diagnostic(
+                Rule::RequestExceedsLimit,
+                node.value_range,
+                DiagnosticSeverity::WARNING,
+                format!(
+                    "The '{}' request of {} is above its limit of {}",
+                    resource,
+                    label,
+                    limit_node.as_scalar().unwrap_or_default()
+                ),
+            ));
+        }
+    }
+}
+
+/// The quantities of a `requests` or `limits` mapping, flagging malformed ones.
+fn quantities<'a>(
+    node: Option<&'a Node>,
+    label: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<(&'a str, f64, &'a Node)> {
+    let mut quantities = Vec::new();
+    for (resource, value) in node.into_iter().flat_map(|n| n.entries()) {
+        let Some(text) = value.as_scalar() else {
+            continue;
+        };
+        // Quantities set from params are checked once substituted
+        if text.contains("$(") {
+            continue;
+        }
+        match parse_quantity(text) {
+            Some(quantity) => quantities.push((resource, quantity, value)),
+            None => diagnostics.push(make_diagnostic(
+                Rule::InvalidQuantity,
+                value.value_range,
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "Invalid quantity '{}' for '{}' of {}; expected a number with an optional suffix, e.g. '500m' or '1Gi'",
+                    text, resource, label
+                ),
+            )),
+        }
+    }
+    quantities
+}
+
+/// The value of a Kubernetes quantity, e.g. `500m`, `1Gi` or `2e3`.
+fn parse_quantity(text: &str) -> Option<f64> {
+    let digits_end = text
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '+' | '-')))
+        .unwrap_or(text.len());
+    let (number, suffix) = text.split_at(digits_end);
+    let unsigned = number.strip_prefix(['+', '-']).unwrap_or(number);
+    if unsigned.is_empty()
+        || unsigned.contains(['+', '-'])
+        || unsigned.matches('.').count() > 1
+        || !unsigned.chars().any(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier = if suffix.is_empty() {
+        1.0
+    } else if let Some((_, power)) = BINARY_SUFFIXES.iter().find(|(s, _)| *s == suffix) {
+        2f64.powi(*power)
+    } else if let Some((_, power)) = DECIMAL_SUFFIXES.iter().find(|(s, _)| *s == suffix) {
+        10f64.powi(*power)
+    } else {
+        let exponent = suffix.strip_prefix(['e', 'E'])?;
+        let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        10f64.powi(exponent.parse().ok()?)
+    };
+    Some(number * multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(parse_quantity("2"), Some(2.0));
+        assert_eq!(parse_quantity("500m"), Some(0.5));
+        assert_eq!(parse_quantity("1Gi"), Some(1073741824.0));
+        assert_eq!(parse_quantity("1.5k"), Some(1500.0));
+        assert_eq!(parse_quantity("2e3"), Some(2000.0));
+        assert_eq!(parse_quantity("+1E-3"), Some(0.001));
+        assert_eq!(parse_quantity(".5"), Some(0.5));
+
+        assert_eq!(parse_quantity("1GB"), None);
+        assert_eq!(parse_quantity("1gi"), None);
+        assert_eq!(parse_quantity("Gi"), None);
+        assert_eq!(parse_quantity("1..5"), None);
+        assert_eq!(parse_quantity("1 Gi"), None);
+        assert_eq!(parse_quantity("1e"), None);
+        assert_eq!(parse_quantity(""), None);
+    }
+}
@@ -0,0 +1,223 @@
+// Result type rules
+//
+// Results can be `string` (the default), `array` or `object`. Object
+// results declare their keys under `properties`, and every property is a
+// string. References to task results in a Pipeline must match the declared
+// type: `[*]` or an index for arrays, `[*]` or a property for objects, and
+// neither for strings.
+//
+// The referenced task must exist, and the result must be declared by its
+// inline `taskSpec`, or by the Task it references when that Task is in the
+// workspace index.
+
+use super::data::with_task_resource;
+use super::params::collect_scalars;
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{Pipeline, PipelineTask, ResultSpec};
+use crate::parser::expressions::{expressions_in, Expression, Reference};
+use crate::parser::{NodeValue, PositionEncoding};
+use crate::workspace::WorkspaceIndex;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Types a result can declare
+const RESULT_TYPES: [&str; 3] = ["string", "array", "object"];
+
+/// Check the `type` and `properties` of result declarations.
+pub(super) fn check_result_declarations(results: &[ResultSpec], diagnostics: &mut Vec<Diagnostic>) {
+    for result in results {
+        let name = result.name.map(|n| n.value).unwrap_or("unnamed");
+
+        if let Some(result_type) = result.result_type {
+            if !RESULT_TYPES.contains(&result_type.value) {
+                diagnostics.push(make_diagnostic(
+                    Rule::InvalidResultType,
+                    result_type.range,
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "Invalid type '{}' for result '{}'; expected one of: {}",
+                        result_type.value,
+                        name,
+                        RESULT_TYPES.join(", ")
+                    ),
+                ));
+                continue;
+            }
+        }
+
+        match (result.type_or_default(), result.properties) {
+            ("object", None) => diagnostics.push(make_diagnostic(
+                Rule::MissingProperties,
+                result.result_type.map_or(result.node.range, |t| t.range),
+                DiagnosticSeverity::ERROR,
+                format!("Object result '{}' must declare its 'properties'", name),
+            )),
+            ("object", Some(properties)) => {
+                if !matches!(properties.value, NodeValue::Mapping(_)) {
+                    diagnostics.push(make_diagnostic(
+                        Rule::InvalidProperties,
+                        properties.value_range,
+                        DiagnosticSeverity::ERROR,
+                        format!("'properties' of result '{}' must be a mapping", name),
+                    ));
+                    continue;
+                }
+                for (key, property) in properties.entries() {
+                    let property_type = property.get("type").and_then(|t| t.as_scalar());
+                    if let Some(property_type) = property_type.filter(|t| *t != "string") {
+                        diagnostics.push(make_diagnostic(
+                            Rule::InvalidProperties,
+                            property.get("type").map_or(property.key_or_range(), |t| t.value_range),
+                            DiagnosticSeverity::ERROR,
+                            format!(
+                                "Property '{}' of result '{}' has type '{}'; object properties must be strings",
+                                key, name, property_type
+                            ),
+                        ));
+                    }
+                }
+            }
+            (result_type, Some(properties)) => diagnostics.push(make_diagnostic(
+                Rule::UnexpectedProperties,
+                properties.key_or_range(),
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "Result '{}' of type '{}' cannot declare 'properties'; only object results have properties",
+                    name, result_type
+                ),
+            )),
+            _ => {}
+        }
+    }
+}
+
+/// Check that `$(tasks.<task>.results.<result>...)` references name a declared
+/// result of an existing task, and match the result's type.
+///
+/// Types are only known for results declared by an inline `taskSpec`.
+pub(super) fn check_result_references(
+    pipeline: &Pipeline,
+    index: Option<&WorkspaceIndex>,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(spec) = pipeline.spec else {
+        return;
+    };
+    let mut scalars = Vec::new();
+    collect_scalars(spec, &mut scalars);
+
+    for expr in scalars
+        .into_iter()
+        .flat_map(|scalar| expressions_in(scalar, encoding))
+    {
+        let Reference::TaskResult { task, result } = expr.reference() else {
+            continue;
+        };
+        let Some(pipeline_task) = pipeline.task(&task.name) else {
+            diagnostics.push(make_diagnostic(
+                Rule::UnknownPipelineTask,
+                task.range,
+                DiagnosticSeverity::ERROR,
+                format!("Result reference to unknown pipeline task '{}'", task.name),
+            ));
+            continue;
+        };
+        if let Some(results) = declared_results(pipeline_task, index) {
+            if !results.contains(&result.name) {
+                let declared = if results.is_empty() {
+                    "it declares no results".to_string()
+                } else {
+                    format!("declared results: {}", results.join(", "))
+                };
+                diagnostics.push(with_task_resource(
+                    make_diagnostic(
+                        Rule::UnknownResult,
+                        result.range,
+                        DiagnosticSeverity::ERROR,
+                        format!(
+                            "Pipeline task '{}' has no result '{}'; {}",
+                            task.name, result.name, declared
+                        ),
+                    ),
+                    pipeline_task,
+                ));
+                continue;
+            }
+        }
+        let Some(declared) = pipeline.task_result(&task.name, &result.name) else {
+            continue;
+        };
+        if let Some(message) = reference_mismatch(&expr, declared, &task.name, &result.name) {
+            diagnostics.push(make_diagnostic(
+                Rule::ResultReference,
+                expr.range,
+                DiagnosticSeverity::ERROR,
+                message,
+            ));
+        }
+    }
+}
+
+/// Names of the results of a pipeline task, when known: from its inline
+/// `taskSpec`, or from the indexed Task its `taskRef` names.
+fn declared_results(task: &PipelineTask, index: Option<&WorkspaceIndex>) -> Option<Vec<String>> {
+    if let Some(task_spec) = &task.task_spec {
+        let names = task_spec
+            .results
+            .iter()
+            .filter_map(|r| r.name.map(|n| n.value.to_string()));
+        return Some(names.collect());
+    }
+    let task_ref = task.task_ref.as_ref()?;
+    let name = task_ref.name?;
+    let resource = index?.find_resource(task_ref.kind_or_default(), name.value)?;
+    Some(resource.results.into_iter().map(|r| r.name).collect())
+}
+
+/// Why a task result reference does not match the result's type, if it doesn't.
+fn reference_mismatch(
+    expr: &Expression,
+    declared: &ResultSpec,
+    task: &str,
+    result: &str,
+) -> Option<String> {
+    // Segments after `tasks.<task>.results.<result>`: an index or a property
+    let selector = expr.segments.get(4).map(|s| s.name.as_str());
+
+    match (declared.type_or_default(), selector) {
+        ("string", None) if !expr.wildcard => None,
+        ("string", _) => Some(format!(
+            "Result '{}' of task '{}' is a string; it cannot be expanded with '[*]' or indexed",
+            result, task
+        )),
+        ("array", None) if expr.wildcard => None,
+        ("array", Some(index)) if index.parse::<usize>().is_ok() && !expr.wildcard => None,
+        ("array", Some(index)) if !expr.wildcard => Some(format!(
+            "Array result '{}' of task '{}' must be indexed with a number, not '{}'",
+            result, task, index
+        )),
+        ("array", _) => Some(format!(
+            "Array result '{}' of task '{}' must be referenced with '[*]' or an index",
+            result, task
+        )),
+        ("object", None) if expr.wildcard => None,
+        ("object", Some(key)) if !expr.wildcard => {
+            let properties = declared.property_names();
+            (declared.properties.is_some() && !properties.contains(&key)).then(|| {
+                format!(
+                    "Object result '{}' of task '{}' has no property '{}'; declared properties: {}",
+                    result,
+                    task,
+                    key,
+                    properties.join(", ")
+                )
+            })
+        }
+        ("object", _) => Some(format!(
+            "Object result '{}' of task '{}' must be referenced with '[*]' or a property",
+            result, task
+        )),
+        _ => None,
+    }
+}
@@ -0,0 +1,126 @@
+// Results size lint
+//
+// Task results travel through the container termination message, which
+// Kubernetes caps at 4096 bytes for all results of a task combined. Scripts
+// that dump whole files into a result, or array results with many literal
+// entries, tend to hit that limit at runtime.
+
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{ResultSpec, Spanned, Task};
+use crate::parser::expressions::{find_expressions, Reference};
+use crate::parser::{NodeValue, PositionEncoding};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Size limit of the termination message, in bytes
+pub(super) const TERMINATION_MESSAGE_LIMIT: usize = 4096;
+
+/// Array results with more literal entries than this are flagged
+const MAX_ARRAY_RESULT_ENTRIES: usize = 20;
+
+/// Commands that copy whole file contents into their output
+const FILE_DUMP_COMMANDS: [&str; 2] = ["cat", "cp"];
+
+/// Warn about steps and results that are likely to overflow the termination message.
+pub(super) fn check_results_size(
+    task: &Task,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for script in task.steps.iter().filter_map(|step| step.script) {
+        check_script(script, encoding, diagnostics);
+    }
+
+    for result in &task.results {
+        check_result_value(result, diagnostics);
+    }
+}
+
+/// Flag `cat file > $(results.x.path)`-style writes in a step script.
+fn check_script(
+    script: Spanned<&str>,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let lines: Vec<&str> = script.value.lines().collect();
+    let first_line = script.range.start.line;
+
+    for expr in find_expressions(script.value, script.range.start, encoding) {
+        let Reference::Result { name } = expr.reference() else {
+            continue;
+        };
+        let line_index = (expr.range.start.line - first_line) as usize;
+        let Some(line) = lines.get(line_index) else {
+            continue;
+        };
+
+        // Column of the expression within this line of the scalar text
+        let column = if line_index == 0 {
+            expr.range.start.character - script.range.start.character
+        } else {
+            expr.range.start.character
+        };
+        let command = &line[..encoding.byte_offset(line, column)];
+
+        if writes_file_contents(command) {
+            diagnostics.push(make_diagnostic(
+                Rule::ResultSize,
+                expr.range,
+                DiagnosticSeverity::WARNING,
+                format!(
+                    "Result '{}' is written from file contents; results share the {}-byte termination message limit, consider a workspace for large data",
+                    name.name, TERMINATION_MESSAGE_LIMIT
+                ),
+            ));
+        }
+    }
+}
+
+/// Whether the shell text before a result path copies a whole file into it.
+fn writes_file_contents(command: &str) -> bool {
+    command.split(['|', ';', '&']).any(|part| {
+        part.split_whitespace()
+            .next()
+            .is_some_and(|word| FILE_DUMP_COMMANDS.contains(&word))
+    })
+}
+
+/// Flag array result values with many literal entries.
+fn check_result_value(result: &ResultSpec, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(value) = result.value else {
+        return;
+    };
+    let NodeValue::Sequence(entries) = &value.value else {
+        return;
+    };
+    if entries.len() <= MAX_ARRAY_RESULT_ENTRIES {
+        return;
+    }
+
+    let name = result.name.map(|n| n.value).unwrap_or("unnamed");
+    diagnostics.push(make_diagnostic(
+        Rule::ResultSize,
+        value.key_or_range(),
+        DiagnosticSeverity::WARNING,
+        format!(
+            "Array result '{}' has {} entries; results share the {}-byte termination message limit",
+            name,
+            entries.len(),
+            TERMINATION_MESSAGE_LIMIT
+        ),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_file_contents() {
+        assert!(writes_file_contents("cat report.json > "));
+        assert!(writes_file_contents("make && cat out.txt | tee "));
+        assert!(writes_file_contents("  cp /workspace/big.log "));
+        assert!(!writes_file_contents("echo -n done > "));
+        assert!(!writes_file_contents("printf '%s' \"$DIGEST\" | tee "));
+    }
+}
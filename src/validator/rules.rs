@@ -0,0 +1,664 @@
+// Rule registry
+//
+// Every code the validator reports has a stable rule ID, published as the
+// `code` of its diagnostics. Users can change the severity of a rule, or
+// turn it off, with `validation.rules` keyed by rule ID or code:
+//
+// ```json
+// { "TPL011": "hint", "unresolved-task-ref": "off" }
+// ```
+//
+// Diagnostics also link to the documentation of their rule through their
+// `codeDescription`, which editors render as a clickable code.
+
+use super::data::DiagnosticData;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tower_lsp::lsp_types::{CodeDescription, Diagnostic, DiagnosticSeverity, NumberOrString, Url};
+
+/// A validation rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleInfo {
+    /// Stable identifier, e.g. `TPL001`
+    pub id: &'static str,
+    /// Code in the diagnostic data, e.g. `unknown-field`
+    pub code: &'static str,
+    /// What the rule reports
+    pub description: &'static str,
+    /// Documentation of what the rule checks
+    pub docs: Docs,
+}
+
+/// A documentation page, with an optional `#anchor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Docs {
+    /// Tekton Pipelines docs, e.g. `pipelines.md#adding-tasks-to-the-pipeline`
+    Pipeline(&'static str),
+    /// Tekton Triggers docs, e.g. `triggerbindings.md`
+    Triggers(&'static str),
+    /// Pipelines-as-Code guide, e.g. `authoringprs/`
+    PipelinesAsCode(&'static str),
+    /// Kubernetes docs, e.g. `concepts/containers/images/`
+    Kubernetes(&'static str),
+    /// Docs of this server, e.g. `LSP_USAGE.md#2-diagnostics-validation`
+    Server(&'static str),
+}
+
+impl Docs {
+    /// URL of the page.
+    pub fn url(&self) -> String {
+        match self {
+            Docs::Pipeline(page) => {
+                format!(
+                    "https://github.com/tektoncd/pipeline/blob/main/docs/{}",
+                    page
+                )
+            }
+            Docs::Triggers(page) => {
+                format!(
+                    "https://github.com/tektoncd/triggers/blob/main/docs/{}",
+                    page
+                )
+            }
+            Docs::PipelinesAsCode(page) => {
+                format!("https://pipelinesascode.com/docs/guide/{}", page)
+            }
+            Docs::Kubernetes(page) => format!("https://kubernetes.io/docs/{}", page),
+            Docs::Server(page) => {
+                format!(
+                    "https://github.com/tektoncd/tekton-lsp/blob/main/docs/{}",
+                    page
+                )
+            }
+        }
+    }
+}
+
+/// Level a rule is reported at, as configured by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    /// Do not report the rule
+    Off,
+    Error,
+    Warning,
+    #[serde(alias = "info")]
+    Information,
+    Hint,
+}
+
+const fn rule(
+    id: &'static str,
+    code: &'static str,
+    description: &'static str,
+    docs: Docs,
+) -> RuleInfo {
+    RuleInfo {
+        id,
+        code,
+        description,
+        docs,
+    }
+}
+
+const fn pipeline(page: &'static str) -> Docs {
+    Docs::Pipeline(page)
+}
+
+const fn triggers(page: &'static str) -> Docs {
+    Docs::Triggers(page)
+}
+
+const fn pipelines_as_code(page: &'static str) -> Docs {
+    Docs::PipelinesAsCode(page)
+}
+
+const fn kubernetes(page: &'static str) -> Docs {
+    Docs::Kubernetes(page)
+}
+
+const fn server(page: &'static str) -> Docs {
+    Docs::Server(page)
+}
+
+/// Pages shared by several rules
+const WHEN_EXPRESSIONS: &str = "pipelines.md#guard-task-execution-using-when-expressions";
+const RESULTS_IN_PIPELINES: &str =
+    "pipelines.md#passing-one-tasks-results-into-the-parameters-or-when-expressions-of-another";
+const SECURITY_CONTEXT: &str = "tasks/configure-pod-container/security-context/";
+const COMPUTE_RESOURCES: &str = "concepts/configuration/manage-resources-containers/";
+const ENV_VARS: &str = "tasks/inject-data-application/define-environment-variable-container/";
+
+/// A validation rule, reported by the validator as the code of its diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    // Resources
+    UnknownField,
+    MissingField,
+    MissingName,
+    ConflictingFields,
+    MissingValue,
+    UnknownApiVersion,
+    DeprecatedApiVersion,
+    InvalidName,
+    DuplicateName,
+    InvalidDescription,
+    DescriptionTooLong,
+    InvalidImagePullPolicy,
+    MissingImage,
+    ScriptWithCommand,
+    // Pipelines
+    MissingTasks,
+    InvalidTasks,
+    InvalidFinally,
+    InvalidPipelineTask,
+    MissingTaskDefinition,
+    FinallyRunAfter,
+    UnknownRunAfter,
+    RunAfterCycle,
+    ResultOrdering,
+    UnresolvedTaskRef,
+    DeprecatedBundle,
+    RemovedBundle,
+    // When expressions
+    InvalidWhen,
+    InvalidOperator,
+    InvalidValues,
+    InvalidCel,
+    FinallyOnlyVariable,
+    UnsupportedFeature,
+    // Params
+    UnknownParam,
+    MissingParam,
+    InvalidParamType,
+    ParamDefaultType,
+    VariableInParamDefault,
+    StringParamExpansion,
+    MissingProperties,
+    InvalidProperties,
+    UnexpectedProperties,
+    // Results
+    InvalidResultType,
+    ResultReference,
+    UnknownResult,
+    UnknownPipelineTask,
+    ResultSize,
+    // Workspaces
+    UnknownWorkspace,
+    UnboundWorkspace,
+    OptionalWorkspace,
+    OverlappingMountPath,
+    WorkspaceMountConflict,
+    // Runs
+    MissingDefinition,
+    MissingPipelineTaskName,
+    InvalidTaskRunSpec,
+    MissingVolume,
+    // Artifacts
+    InvalidArtifacts,
+    ArtifactsApiVersion,
+    ArtifactsFeatureFlag,
+    // Pipelines-as-Code
+    UnknownPacVariable,
+    EmptyPacTemplate,
+    // Triggers
+    MissingTemplate,
+    InvalidTriggerRef,
+    MissingResourceTemplates,
+    MissingResourceField,
+    UnknownTemplateParam,
+    InvalidBinding,
+    InvalidBindingKind,
+    InvalidBindingParam,
+    InvalidBindingValue,
+    MissingInterceptorRef,
+    MissingInterceptorName,
+    InvalidInterceptorParam,
+    // Unused declarations
+    UnusedParam,
+    UnusedWorkspace,
+    UnusedResult,
+    // Security
+    UnpinnedImage,
+    PrivilegedStep,
+    RootUser,
+    CurlPipeShell,
+    // Finally tasks
+    FinallyReference,
+    // Step env
+    InvalidEnv,
+    EnvValue,
+    InvalidValueFrom,
+    // Compute resources
+    InvalidQuantity,
+    RequestExceedsLimit,
+    // Suppression comments
+    InvalidSuppression,
+    // Deprecations
+    DeprecatedPipelineResources,
+    DeprecatedClusterTask,
+    DeprecatedConditions,
+    DeprecatedRunTimeout,
+    // Step failures and outputs
+    InvalidOnError,
+    InvalidOutputConfig,
+}
+
+impl Rule {
+    /// Every rule, in ID order
+    pub const ALL: [Rule; 92] = [
+        Rule::UnknownField,
+        Rule::MissingField,
+        Rule::MissingName,
+        Rule::ConflictingFields,
+        Rule::MissingValue,
+        Rule::UnknownApiVersion,
+        Rule::DeprecatedApiVersion,
+        Rule::InvalidName,
+        Rule::DuplicateName,
+        Rule::InvalidDescription,
+        Rule::DescriptionTooLong,
+        Rule::InvalidImagePullPolicy,
+        Rule::MissingImage,
+        Rule::ScriptWithCommand,
+        Rule::MissingTasks,
+        Rule::InvalidTasks,
+        Rule::InvalidFinally,
+        Rule::InvalidPipelineTask,
+        Rule::MissingTaskDefinition,
+        Rule::FinallyRunAfter,
+        Rule::UnknownRunAfter,
+        Rule::RunAfterCycle,
+        Rule::ResultOrdering,
+        Rule::UnresolvedTaskRef,
+        Rule::DeprecatedBundle,
+        Rule::RemovedBundle,
+        Rule::InvalidWhen,
+        Rule::InvalidOperator,
+        Rule::InvalidValues,
+        Rule::InvalidCel,
+        Rule::FinallyOnlyVariable,
+        Rule::UnsupportedFeature,
+        Rule::UnknownParam,
+        Rule::MissingParam,
+        Rule::InvalidParamType,
+        Rule::ParamDefaultType,
+        Rule::VariableInParamDefault,
+        Rule::StringParamExpansion,
+        Rule::MissingProperties,
+        Rule::InvalidProperties,
+        Rule::UnexpectedProperties,
+        Rule::InvalidResultType,
+        Rule::ResultReference,
+        Rule::UnknownResult,
+        Rule::UnknownPipelineTask,
+        Rule::ResultSize,
+        Rule::UnknownWorkspace,
+        Rule::UnboundWorkspace,
+        Rule::OptionalWorkspace,
+        Rule::OverlappingMountPath,
+        Rule::WorkspaceMountConflict,
+        Rule::MissingDefinition,
+        Rule::MissingPipelineTaskName,
+        Rule::InvalidTaskRunSpec,
+        Rule::MissingVolume,
+        Rule::InvalidArtifacts,
+        Rule::ArtifactsApiVersion,
+        Rule::ArtifactsFeatureFlag,
+        Rule::UnknownPacVariable,
+        Rule::EmptyPacTemplate,
+        Rule::MissingTemplate,
+        Rule::InvalidTriggerRef,
+        Rule::MissingResourceTemplates,
+        Rule::MissingResourceField,
+        Rule::UnknownTemplateParam,
+        Rule::InvalidBinding,
+        Rule::InvalidBindingKind,
+        Rule::InvalidBindingParam,
+        Rule::InvalidBindingValue,
+        Rule::MissingInterceptorRef,
+        Rule::MissingInterceptorName,
+        Rule::InvalidInterceptorParam,
+        Rule::UnusedParam,
+        Rule::UnusedWorkspace,
+        Rule::UnusedResult,
+        Rule::UnpinnedImage,
+        Rule::PrivilegedStep,
+        Rule::RootUser,
+        Rule::CurlPipeShell,
+        Rule::FinallyReference,
+        Rule::InvalidEnv,
+        Rule::EnvValue,
+        Rule::InvalidValueFrom,
+        Rule::InvalidQuantity,
+        Rule::RequestExceedsLimit,
+        Rule::InvalidSuppression,
+        Rule::DeprecatedPipelineResources,
+        Rule::DeprecatedClusterTask,
+        Rule::DeprecatedConditions,
+        Rule::DeprecatedRunTimeout,
+        Rule::InvalidOnError,
+        Rule::InvalidOutputConfig,
+    ];
+
+    /// ID, code, description and documentation of the rule.
+    #[rustfmt::skip]
+    pub const fn info(self) -> RuleInfo {
+        match self {
+            // Resources
+            Rule::UnknownField => rule("TPL001", "unknown-field", "Field not allowed where it is set",
+                pipeline("pipelines.md#configuring-a-pipeline")),
+            Rule::MissingField => rule("TPL002", "missing-field", "Required field is missing",
+                pipeline("pipelines.md#configuring-a-pipeline")),
+            Rule::MissingName => rule("TPL003", "missing-name", "Resource, task, step or param has no name",
+                kubernetes("concepts/overview/working-with-objects/names/")),
+            Rule::ConflictingFields => rule("TPL004", "conflicting-fields", "Fields that cannot be set together",
+                pipeline("pipelines.md#adding-tasks-to-the-pipeline")),
+            Rule::MissingValue => rule("TPL005", "missing-value", "Param passed to a run has no value",
+                pipeline("pipelineruns.md#specifying-parameters")),
+            Rule::UnknownApiVersion => rule("TPL006", "unknown-api-version", "apiVersion is not a Tekton API version",
+                pipeline("migrating-v1beta1-to-v1.md")),
+            Rule::DeprecatedApiVersion => rule("TPL007", "deprecated-api-version", "apiVersion is deprecated",
+                pipeline("migrating-v1beta1-to-v1.md")),
+            Rule::InvalidName => rule("TPL008", "invalid-name", "Name is not a valid DNS-1123 label",
+                kubernetes("concepts/overview/working-with-objects/names/#dns-label-names")),
+            Rule::DuplicateName => rule("TPL009", "duplicate-name", "Name is declared twice",
+                pipeline("pipelines.md#adding-tasks-to-the-pipeline")),
+            Rule::InvalidDescription => rule("TPL010", "invalid-description", "Description is not a string",
+                pipeline("pipelines.md#configuring-a-pipeline")),
+            Rule::DescriptionTooLong => rule("TPL011", "description-too-long", "First line of a description is too long",
+                pipeline("pipelines.md#configuring-a-pipeline")),
+            Rule::InvalidImagePullPolicy => rule("TPL012", "invalid-image-pull-policy", "Unknown imagePullPolicy",
+                kubernetes("concepts/containers/images/#image-pull-policy")),
+            Rule::MissingImage => rule("TPL013", "missing-image", "StepAction has no image",
+                pipeline("stepactions.md#configuring-a-stepaction")),
+            Rule::ScriptWithCommand => rule("TPL014", "script-with-command", "Step sets both script and command",
+                pipeline("tasks.md#running-scripts-within-steps")),
+            // Pipelines
+            Rule::MissingTasks => rule("TPL015", "missing-tasks", "Pipeline has no tasks",
+                pipeline("pipelines.md#adding-tasks-to-the-pipeline")),
+            Rule::InvalidTasks => rule("TPL016", "invalid-tasks", "Pipeline 'tasks' is not a list",
+                pipeline("pipelines.md#adding-tasks-to-the-pipeline")),
+            Rule::InvalidFinally => rule("TPL017", "invalid-finally", "Pipeline 'finally' is not a list",
+                pipeline("pipelines.md#adding-finally-to-the-pipeline")),
+            Rule::InvalidPipelineTask => rule("TPL018", "invalid-pipeline-task", "Pipeline task is not a mapping",
+                pipeline("pipelines.md#adding-tasks-to-the-pipeline")),
+            Rule::MissingTaskDefinition => rule("TPL019", "missing-task-definition", "Pipeline task has no taskRef or taskSpec",
+                pipeline("pipelines.md#adding-tasks-to-the-pipeline")),
+            Rule::FinallyRunAfter => rule("TPL020", "finally-run-after", "Finally task sets runAfter",
+                pipeline("pipelines.md#adding-finally-to-the-pipeline")),
+            Rule::UnknownRunAfter => rule("TPL021", "unknown-run-after", "runAfter names an unknown pipeline task",
+                pipeline("pipelines.md#using-the-runafter-field")),
+            Rule::RunAfterCycle => rule("TPL022", "run-after-cycle", "Pipeline tasks depend on each other in a cycle",
+                pipeline("pipelines.md#using-the-runafter-field")),
+            Rule::ResultOrdering => rule("TPL023", "result-ordering", "Result is used by a task that may run before it is produced",
+                pipeline(RESULTS_IN_PIPELINES)),
+            Rule::UnresolvedTaskRef => rule("TPL024", "unresolved-task-ref", "taskRef names a Task not defined in the workspace",
+                pipeline("pipelines.md#adding-tasks-to-the-pipeline")),
+            Rule::DeprecatedBundle => rule("TPL025", "deprecated-bundle", "taskRef uses the deprecated bundle field",
+                pipeline("bundle-resolver.md")),
+            Rule::RemovedBundle => rule("TPL026", "removed-bundle", "taskRef uses the bundle field removed from v1",
+                pipeline("bundle-resolver.md")),
+            // When expressions
+            Rule::InvalidWhen => rule("TPL027", "invalid-when", "when expression is malformed",
+                pipeline(WHEN_EXPRESSIONS)),
+            Rule::InvalidOperator => rule("TPL028", "invalid-operator", "when operator is not in or notin",
+                pipeline(WHEN_EXPRESSIONS)),
+            Rule::InvalidValues => rule("TPL029", "invalid-values", "when values are missing or not a list",
+                pipeline(WHEN_EXPRESSIONS)),
+            Rule::InvalidCel => rule("TPL030", "invalid-cel", "CEL expression does not parse",
+                pipeline(WHEN_EXPRESSIONS)),
+            Rule::FinallyOnlyVariable => rule("TPL031", "finally-only-variable", "Variable only available to finally tasks",
+                pipeline("pipelines.md#adding-finally-to-the-pipeline")),
+            Rule::UnsupportedFeature => rule("TPL032", "unsupported-feature", "Feature missing from the targeted Tekton release",
+                pipeline(WHEN_EXPRESSIONS)),
+            // Params
+            Rule::UnknownParam => rule("TPL033", "unknown-param", "Param is not declared",
+                pipeline("variables.md")),
+            Rule::MissingParam => rule("TPL034", "missing-param", "Required param is not passed",
+                pipeline("pipelines.md#specifying-parameters")),
+            Rule::InvalidParamType => rule("TPL035", "invalid-param-type", "Param type is not string, array or object",
+                pipeline("tasks.md#specifying-parameters")),
+            Rule::ParamDefaultType => rule("TPL036", "param-default-type", "Param default does not match its type",
+                pipeline("tasks.md#specifying-parameters")),
+            Rule::VariableInParamDefault => rule("TPL037", "variable-in-param-default", "Param default references a variable",
+                pipeline("tasks.md#specifying-parameters")),
+            Rule::StringParamExpansion => rule("TPL038", "string-param-expansion", "'[*]' applied to a string param",
+                pipeline("tasks.md#substituting-array-parameters")),
+            Rule::MissingProperties => rule("TPL039", "missing-properties", "Object param or result declares no properties",
+                pipeline("tasks.md#specifying-parameters")),
+            Rule::InvalidProperties => rule("TPL040", "invalid-properties", "Object properties are malformed",
+                pipeline("tasks.md#specifying-parameters")),
+            Rule::UnexpectedProperties => rule("TPL041", "unexpected-properties", "Properties set on a non-object",
+                pipeline("tasks.md#specifying-parameters")),
+            // Results
+            Rule::InvalidResultType => rule("TPL042", "invalid-result-type", "Result type is not string, array or object",
+                pipeline("tasks.md#emitting-results")),
+            Rule::ResultReference => rule("TPL043", "result-reference", "Result reference does not match the result type",
+                pipeline(RESULTS_IN_PIPELINES)),
+            Rule::UnknownResult => rule("TPL044", "unknown-result", "Referenced result is not declared",
+                pipeline(RESULTS_IN_PIPELINES)),
+            Rule::UnknownPipelineTask => rule("TPL045", "unknown-pipeline-task", "Result reference names an unknown pipeline task",
+                pipeline(RESULTS_IN_PIPELINES)),
+            Rule::ResultSize => rule("TPL046", "result-size", "Results may exceed the termination message limit",
+                pipeline("tasks.md#emitting-results")),
+            // Workspaces
+            Rule::UnknownWorkspace => rule("TPL047", "unknown-workspace", "Workspace is not declared",
+                pipeline("workspaces.md#using-workspaces-in-pipelines")),
+            Rule::UnboundWorkspace => rule("TPL048", "unbound-workspace", "Required workspace is not bound",
+                pipeline("workspaces.md#using-workspaces-in-pipelines")),
+            Rule::OptionalWorkspace => rule("TPL049", "optional-workspace", "Optional workspace bound to a required one",
+                pipeline("workspaces.md#optional-workspaces")),
+            Rule::OverlappingMountPath => rule("TPL050", "overlapping-mount-path", "Workspaces mounted at the same path",
+                pipeline("workspaces.md#using-workspaces-in-tasks")),
+            Rule::WorkspaceMountConflict => rule("TPL051", "workspace-mount-conflict", "Workspace and volume mount at the same path",
+                pipeline("workspaces.md#using-workspaces-in-tasks")),
+            // Runs
+            Rule::MissingDefinition => rule("TPL052", "missing-definition", "Run has no ref or spec",
+                pipeline("pipelineruns.md#configuring-a-pipelinerun")),
+            Rule::MissingPipelineTaskName => rule("TPL053", "missing-pipeline-task-name", "taskRunSpecs entry has no pipelineTaskName",
+                pipeline("pipelineruns.md#specifying-taskrunspecs")),
+            Rule::InvalidTaskRunSpec => rule("TPL054", "invalid-task-run-spec", "taskRunSpecs entry is malformed",
+                pipeline("pipelineruns.md#specifying-taskrunspecs")),
+            Rule::MissingVolume => rule("TPL055", "missing-volume", "Run workspace is not backed by a volume",
+                pipeline("workspaces.md#specifying-volumesources-in-workspaces")),
+            // Artifacts
+            Rule::InvalidArtifacts => rule("TPL056", "invalid-artifacts", "Artifacts are malformed",
+                pipeline("artifacts.md")),
+            Rule::ArtifactsApiVersion => rule("TPL057", "artifacts-api-version", "Artifacts used outside the v1 API",
+                pipeline("artifacts.md")),
+            Rule::ArtifactsFeatureFlag => rule("TPL058", "artifacts-feature-flag", "Artifacts used without enable-artifacts",
+                pipeline("artifacts.md")),
+            // Pipelines-as-Code
+            Rule::UnknownPacVariable => rule("TPL059", "unknown-pac-variable", "Unknown Pipelines-as-Code template variable",
+                pipelines_as_code("authoringprs/#dynamic-variables")),
+            Rule::EmptyPacTemplate => rule("TPL060", "empty-pac-template", "Empty Pipelines-as-Code template",
+                pipelines_as_code("authoringprs/#dynamic-variables")),
+            // Triggers
+            Rule::MissingTemplate => rule("TPL061", "missing-template", "Trigger or its template has nothing to run",
+                triggers("eventlisteners.md")),
+            Rule::InvalidTriggerRef => rule("TPL062", "invalid-trigger-ref", "triggerRef is not a Trigger name",
+                triggers("eventlisteners.md")),
+            Rule::MissingResourceTemplates => rule("TPL063", "missing-resource-templates", "TriggerTemplate has no resourcetemplates",
+                triggers("triggertemplates.md")),
+            Rule::MissingResourceField => rule("TPL064", "missing-resource-field", "Resource template lacks a required field",
+                triggers("triggertemplates.md")),
+            Rule::UnknownTemplateParam => rule("TPL065", "unknown-template-param", "TriggerTemplate param is not declared",
+                triggers("triggertemplates.md")),
+            Rule::InvalidBinding => rule("TPL066", "invalid-binding", "Trigger binding has no ref, name or value",
+                triggers("triggerbindings.md")),
+            Rule::InvalidBindingKind => rule("TPL067", "invalid-binding-kind", "Trigger binding kind is unknown",
+                triggers("triggerbindings.md")),
+            Rule::InvalidBindingParam => rule("TPL068", "invalid-binding-param", "Binding param lacks a name or value",
+                triggers("triggerbindings.md")),
+            Rule::InvalidBindingValue => rule("TPL069", "invalid-binding-value", "Binding param value does not come from the event",
+                triggers("triggerbindings.md")),
+            Rule::MissingInterceptorRef => rule("TPL070", "missing-interceptor-ref", "Interceptor has no ref",
+                triggers("interceptors.md")),
+            Rule::MissingInterceptorName => rule("TPL071", "missing-interceptor-name", "Interceptor ref has no name",
+                triggers("interceptors.md")),
+            Rule::InvalidInterceptorParam => rule("TPL072", "invalid-interceptor-param", "Interceptor param lacks a name or value",
+                triggers("interceptors.md")),
+            // Unused declarations
+            Rule::UnusedParam => rule("TPL073", "unused-param", "Param is declared but never used",
+                pipeline("variables.md")),
+            Rule::UnusedWorkspace => rule("TPL074", "unused-workspace", "Workspace is declared but never used",
+                pipeline("workspaces.md")),
+            Rule::UnusedResult => rule("TPL075", "unused-result", "Result is declared but never written",
+                pipeline("tasks.md#emitting-results")),
+            // Security
+            Rule::UnpinnedImage => rule("TPL076", "unpinned-image", "Step image has no tag or uses 'latest'",
+                kubernetes("concepts/containers/images/#image-names")),
+            Rule::PrivilegedStep => rule("TPL077", "privileged-step", "Step runs privileged",
+                kubernetes(SECURITY_CONTEXT)),
+            Rule::RootUser => rule("TPL078", "root-user", "Step runs as root",
+                kubernetes(SECURITY_CONTEXT)),
+            Rule::CurlPipeShell => rule("TPL079", "curl-pipe-shell", "Step script pipes a download into a shell",
+                pipeline("tasks.md#running-scripts-within-steps")),
+            // Finally tasks
+            Rule::FinallyReference => rule("TPL080", "finally-reference", "Result reference uses the wrong tasks or finally prefix",
+                pipeline("pipelines.md#adding-finally-to-the-pipeline")),
+            // Step env
+            Rule::InvalidEnv => rule("TPL081", "invalid-env", "Step env is not a list of named entries",
+                kubernetes(ENV_VARS)),
+            Rule::EnvValue => rule("TPL082", "env-value", "Env entry sets both or neither of value and valueFrom",
+                kubernetes(ENV_VARS)),
+            Rule::InvalidValueFrom => rule("TPL083", "invalid-value-from", "Env valueFrom has no single, complete source",
+                kubernetes("tasks/inject-data-application/environment-variable-expose-pod-information/")),
+            // Compute resources
+            Rule::InvalidQuantity => rule("TPL084", "invalid-quantity", "Compute resource quantity is malformed",
+                kubernetes(COMPUTE_RESOURCES)),
+            Rule::RequestExceedsLimit => rule("TPL085", "request-exceeds-limit", "Compute resource request is above its limit",
+                kubernetes(COMPUTE_RESOURCES)),
+            // Suppression comments
+            Rule::InvalidSuppression => rule("TPL086", "invalid-suppression", "Suppression comment has an unknown directive or rule, or an empty rule list",
+                server("LSP_USAGE.md#suppression-comments")),
+            // Deprecations
+            Rule::DeprecatedPipelineResources => rule("TPL087", "deprecated-pipeline-resources", "Uses the deprecated PipelineResources",
+                pipeline("migrating-v1beta1-to-v1.md#replacing-pipelineresources-with-tasks")),
+            Rule::DeprecatedClusterTask => rule("TPL088", "deprecated-cluster-task", "Declares or references a deprecated ClusterTask",
+                pipeline("migrating-v1beta1-to-v1.md#replacing-clustertask-with-remote-resolution")),
+            Rule::DeprecatedConditions => rule("TPL089", "deprecated-conditions", "Uses the deprecated Conditions",
+                pipeline(WHEN_EXPRESSIONS)),
+            Rule::DeprecatedRunTimeout => rule("TPL090", "deprecated-run-timeout", "PipelineRun uses the deprecated timeout field",
+                pipeline("pipelineruns.md#configuring-a-failure-timeout")),
+            // Step failures and outputs
+            Rule::InvalidOnError => rule("TPL091", "invalid-on-error", "Step onError is not continue or stopAndFail",
+                pipeline("tasks.md#specifying-onerror-for-a-step")),
+            Rule::InvalidOutputConfig => rule("TPL092", "invalid-output-config", "Step stdoutConfig or stderrConfig has no valid path",
+                pipeline("tasks.md#redirecting-step-output-streams-with-stdoutconfig-and-stderrconfig")),
+        }
+    }
+
+    /// Code of the rule in the diagnostic data, e.g. `unknown-field`
+    pub const fn code(self) -> &'static str {
+        self.info().code
+    }
+}
+
+/// Every rule of the validator, by ID.
+pub const RULES: [RuleInfo; Rule::ALL.len()] = {
+    let mut rules = [Rule::ALL[0].info(); Rule::ALL.len()];
+    let mut i = 1;
+    while i < rules.len() {
+        rules[i] = Rule::ALL[i].info();
+        i += 1;
+    }
+    rules
+};
+
+/// The rule with an ID or code.
+pub fn find_rule(key: &str) -> Option<&'static RuleInfo> {
+    RULES.iter().find(|r| r.id == key || r.code == key)
+}
+
+/// Set the rule ID of every diagnostic as its `code`, link the rule's
+/// documentation as its `codeDescription`, and apply the levels configured
+/// for rules.
+pub(super) fn apply_rule_levels(
+    levels: &HashMap<String, RuleLevel>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    diagnostics.retain_mut(|diagnostic| {
+        let Some(rule) = DiagnosticData::of(diagnostic).and_then(|d| find_rule(&d.code)) else {
+            return true;
+        };
+        diagnostic.code = Some(NumberOrString::String(rule.id.to_string()));
+        diagnostic.code_description = Url::parse(&rule.docs.url())
+            .ok()
+            .map(|href| CodeDescription { href });
+        let level = levels.get(rule.id).or_else(|| levels.get(rule.code));
+        diagnostic.severity = match level {
+            None => diagnostic.severity,
+            Some(RuleLevel::Off) => return false,
+            Some(RuleLevel::Error) => Some(DiagnosticSeverity::ERROR),
+            Some(RuleLevel::Warning) => Some(DiagnosticSeverity::WARNING),
+            Some(RuleLevel::Information) => Some(DiagnosticSeverity::INFORMATION),
+            Some(RuleLevel::Hint) => Some(DiagnosticSeverity::HINT),
+        };
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_rules_are_unique() {
+        let ids: HashSet<_> = RULES.iter().map(|r| r.id).collect();
+        let codes: HashSet<_> = RULES.iter().map(|r| r.code).collect();
+        assert_eq!(ids.len(), RULES.len());
+        assert_eq!(codes.len(), RULES.len());
+    }
+
+    #[test]
+    fn test_rule_docs_are_urls() {
+        for rule in RULES {
+            assert!(Url::parse(&rule.docs.url()).is_ok(), "{}", rule.id);
+        }
+    }
+
+    #[test]
+    fn test_all_lists_every_rule_in_id_order() {
+        for (i, rule) in Rule::ALL.iter().enumerate() {
+            assert_eq!(rule.info().id, format!("TPL{:03}", i + 1));
+            assert_eq!(find_rule(rule.code()), Some(&RULES[i]));
+        }
+    }
+
+    #[test]
+    fn test_rule_levels() {
+        let levels: HashMap<String, RuleLevel> =
+            serde_json::from_value(serde_json::json!({"TPL001": "hint", "missing-name": "off"}))
+                .unwrap();
+        let diagnostic = |code| Diagnostic {
+            severity: Some(DiagnosticSeverity::ERROR),
+            data: serde_json::to_value(DiagnosticData::new(code)).ok(),
+            ..Diagnostic::default()
+        };
+        let mut diagnostics = vec![
+            diagnostic("unknown-field"),
+            diagnostic("missing-name"),
+            diagnostic("missing-tasks"),
+        ];
+
+        apply_rule_levels(&levels, &mut diagnostics);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].code,
+            Some(NumberOrString::String("TPL001".to_string()))
+        );
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::HINT));
+        assert_eq!(
+            diagnostics[0].code_description.as_ref().unwrap().href.as_str(),
+            "https://github.com/tektoncd/pipeline/blob/main/docs/pipelines.md#configuring-a-pipeline"
+        );
+        assert_eq!(
+            diagnostics[1].code,
+            Some(NumberOrString::String("TPL015".to_string()))
+        );
+        assert_eq!(diagnostics[1].severity, Some(DiagnosticSeverity::ERROR));
+    }
+}
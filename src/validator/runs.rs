@@ -0,0 +1,150 @@
+// PipelineRun and TaskRun rules
+//
+// A run executes exactly one Pipeline or Task, given by reference or
+// embedded. The params it passes need a name and a value, and every
+// workspace it provides must be backed by a volume source.
+
+use super::pipeline::{first_key_range, report_conflicts};
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{ParamValue, PipelineRun, TaskRun, WorkspaceBinding};
+use crate::parser::{Node, NodeValue};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Url};
+
+/// Fields a run workspace can take its volume from
+const WORKSPACE_BACKINGS: [&str; 7] = [
+    "persistentVolumeClaim",
+    "volumeClaimTemplate",
+    "emptyDir",
+    "configMap",
+    "secret",
+    "projected",
+    "csi",
+];
+
+/// Check the spec of a PipelineRun.
+pub(super) fn check_pipeline_run(
+    run: &PipelineRun,
+    uri: Option<&Url>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(spec) = run.spec else {
+        return;
+    };
+    check_definition(
+        spec,
+        "PipelineRun",
+        ["pipelineRef", "pipelineSpec"],
+        uri,
+        diagnostics,
+    );
+    check_params(&run.params, "PipelineRun", diagnostics);
+    check_workspaces(&run.workspaces, "PipelineRun", diagnostics);
+
+    for task_run_spec in &run.task_run_specs {
+        match task_run_spec.pipeline_task_name {
+            Some(name) if matches!(name.value, NodeValue::Scalar(_)) => {}
+            Some(name) => diagnostics.push(make_diagnostic(
+                Rule::InvalidTaskRunSpec,
+                name.value_range,
+                DiagnosticSeverity::ERROR,
+                "'pipelineTaskName' of a taskRunSpecs entry must be a string".to_string(),
+            )),
+            None => diagnostics.push(make_diagnostic(
+                Rule::MissingPipelineTaskName,
+                first_key_range(task_run_spec.node),
+                DiagnosticSeverity::ERROR,
+                "Each taskRunSpecs entry must have a 'pipelineTaskName'".to_string(),
+            )),
+        }
+    }
+}
+
+/// Check the spec of a TaskRun.
+pub(super) fn check_task_run(run: &TaskRun, uri: Option<&Url>, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(spec) = run.spec else {
+        return;
+    };
+    check_definition(spec, "TaskRun", ["taskRef", "taskSpec"], uri, diagnostics);
+    check_params(&run.params, "TaskRun", diagnostics);
+    check_workspaces(&run.workspaces, "TaskRun", diagnostics);
+}
+
+/// A run needs exactly one of its reference or embedded spec.
+fn check_definition(
+    spec: &Node,
+    kind: &str,
+    [reference, embedded]: [&str; 2],
+    uri: Option<&Url>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    match (spec.get(reference), spec.get(embedded)) {
+        (None, None) => diagnostics.push(make_diagnostic(
+            Rule::MissingDefinition,
+            spec.key_or_range(),
+            DiagnosticSeverity::ERROR,
+            format!(
+                "{} must define one of '{}' or '{}'",
+                kind, reference, embedded
+            ),
+        )),
+        (Some(first), Some(second)) => report_conflicts(
+            kind,
+            &[(reference, first), (embedded, second)],
+            uri,
+            diagnostics,
+        ),
+        _ => {}
+    }
+}
+
+/// Every param passed to the run needs a name and a value.
+fn check_params(params: &[ParamValue], kind: &str, diagnostics: &mut Vec<Diagnostic>) {
+    for param in params {
+        match (param.name, param.value) {
+            (None, _) => diagnostics.push(make_diagnostic(
+                Rule::MissingName,
+                first_key_range(param.node),
+                DiagnosticSeverity::ERROR,
+                format!("Param of {} must have a 'name'", kind),
+            )),
+            (Some(name), None) => diagnostics.push(make_diagnostic(
+                Rule::MissingValue,
+                name.key_or_range(),
+                DiagnosticSeverity::ERROR,
+                format!("Param '{}' of {} must have a 'value'", name.value, kind),
+            )),
+            _ => {}
+        }
+    }
+}
+
+/// Every workspace provided to the run needs a volume source.
+fn check_workspaces(
+    workspaces: &[WorkspaceBinding],
+    kind: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for workspace in workspaces {
+        if WORKSPACE_BACKINGS
+            .iter()
+            .any(|field| workspace.node.get(field).is_some())
+        {
+            continue;
+        }
+        let name = workspace.name.map(|n| n.value).unwrap_or("unnamed");
+        diagnostics.push(make_diagnostic(
+            Rule::MissingVolume,
+            workspace
+                .name
+                .map_or(first_key_range(workspace.node), |n| n.key_or_range()),
+            DiagnosticSeverity::ERROR,
+            format!(
+                "Workspace '{}' of {} must be backed by a volume: one of {}",
+                name,
+                kind,
+                WORKSPACE_BACKINGS.join(", ")
+            ),
+        ));
+    }
+}
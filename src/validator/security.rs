@@ -0,0 +1,166 @@
+// Security lint
+//
+// Opt-in checks for step settings that weaken the supply chain or the
+// isolation of the pod: images that are not pinned to a tag or digest,
+// privileged containers, containers running as root, and scripts that pipe a
+// download straight into a shell. Each check has its own code so teams can
+// turn them off one by one.
+
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{Spanned, Step};
+use crate::parser::{Node, PositionEncoding, Style};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+
+/// Commands downloading a script
+const DOWNLOADERS: [&str; 2] = ["curl", "wget"];
+
+/// Shells running a script read from their input
+const SHELLS: [&str; 6] = ["sh", "bash", "zsh", "dash", "ash", "ksh"];
+
+/// Check the images, security contexts and scripts of steps.
+pub(super) fn check_step_security(
+    steps: &[Step],
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for step in steps {
+        let step_name = step.display_name();
+        if let Some(image) = step.image {
+            check_image(image, step_name, diagnostics);
+        }
+        if let Some(context) = step.node.get("securityContext") {
+            check_security_context(context, step_name, diagnostics);
+        }
+        if let Some(script) = step.node.get("script") {
+            check_script(script, step_name, encoding, diagnostics);
+        }
+    }
+}
+
+/// Flag images without a tag or digest, or on the `latest` tag.
+fn check_image(image: Spanned<&str>, step_name: &str, diagnostics: &mut Vec<Diagnostic>) {
+    // Images set from params are pinned by whoever passes them
+    if image.value.contains("$(") || image.value.contains('@') {
+        return;
+    }
+    let name = image.value.rsplit('/').next().unwrap_or(image.value);
+    let problem = match name.split_once(':') {
+        None => "has no tag",
+        Some((_, "latest")) => "uses the 'latest' tag",
+        Some(_) => return,
+    };
+    diagnostics.push(make_diagnostic(
+        Rule::UnpinnedImage,
+        image.range,
+        DiagnosticSeverity::WARNING,
+        format!(
+            "Image '{}' of step '{}' {}; pin a version or a digest so runs are reproducible",
+            image.value, step_name, problem
+        ),
+    ));
+}
+
+/// Flag privileged containers and containers running as root.
+fn check_security_context(context: &Node, step_name: &str, diagnostics: &mut Vec<Diagnostic>) {
+    if let Some(privileged) = context.get("privileged") {
+        if privileged.as_scalar() == Some("true") {
+            diagnostics.push(make_diagnostic(
+                Rule::PrivilegedStep,
+                privileged.key_or_range(),
+                DiagnosticSeverity::WARNING,
+                format!(
+                    "Step '{}' runs privileged, with full access to the node",
+                    step_name
+                ),
+            ));
+        }
+    }
+    if let Some(user) = context.get("runAsUser") {
+        if user.as_scalar() == Some("0") {
+            diagnostics.push(make_diagnostic(
+                Rule::RootUser,
+                user.key_or_range(),
+                DiagnosticSeverity::WARNING,
+                format!(
+                    "Step '{}' runs as root; use a non-root user or set 'runAsNonRoot: true'",
+                    step_name
+                ),
+            ));
+        }
+    }
+}
+
+/// Flag script lines piping a download into a shell.
+fn check_script(
+    script: &Node,
+    step_name: &str,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(text) = script.as_scalar() else {
+        return;
+    };
+    let start = script.value_range.start;
+    for (index, line) in text.lines().enumerate() {
+        if !pipes_download_to_shell(line) {
+            continue;
+        }
+        // The text of quoted scalars no longer matches the source
+        let range = if script.style == Style::Default {
+            let indent = line.len() - line.trim_start().len();
+            let position = |byte: usize| {
+                let column = encoding.column(line, byte);
+                Position {
+                    line: start.line + index as u32,
+                    character: if index == 0 {
+                        start.character + column
+                    } else {
+                        column
+                    },
+                }
+            };
+            Range {
+                start: position(indent),
+                end: position(line.trim_end().len()),
+            }
+        } else {
+            script.value_range
+        };
+        diagnostics.push(make_diagnostic(
+            Rule::CurlPipeShell,
+            range,
+            DiagnosticSeverity::WARNING,
+            format!(
+                "Step '{}' pipes a download into a shell; download the script, verify it, then run it",
+                step_name
+            ),
+        ));
+    }
+}
+
+/// Whether a shell line pipes the output of curl or wget into a shell.
+fn pipes_download_to_shell(line: &str) -> bool {
+    // Pipelines of the line's command lists
+    let mut pipelines = line
+        .split("||")
+        .flat_map(|list| list.split("&&"))
+        .flat_map(|list| list.split(';'));
+    pipelines.any(|pipeline| {
+        let commands: Vec<&str> = pipeline.split('|').map(program).collect();
+        commands
+            .iter()
+            .position(|c| DOWNLOADERS.contains(c))
+            .is_some_and(|download| commands[download + 1..].iter().any(|c| SHELLS.contains(c)))
+    })
+}
+
+/// Name of the program a command runs, without its directory or `sudo`.
+fn program(command: &str) -> &str {
+    let mut words = command.split_whitespace();
+    let mut word = words.next().unwrap_or("");
+    if word == "sudo" {
+        word = words.next().unwrap_or("");
+    }
+    word.rsplit('/').next().unwrap_or(word)
+}
@@ -0,0 +1,71 @@
+// StepAction rules
+//
+// A StepAction is a reusable step: its spec holds the fields of a step
+// container, along with the params and results it declares. Like a step, it
+// needs an image and runs either a `script` or a `command`.
+
+use super::params::check_step_action_param_references;
+use super::rules::Rule;
+use super::suggestions::unknown_field;
+use super::tekton::make_diagnostic;
+use crate::parser::{PositionEncoding, YamlDocument};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Fields of a StepAction spec
+const STEP_ACTION_FIELDS: [&str; 11] = [
+    "description",
+    "image",
+    "command",
+    "args",
+    "env",
+    "script",
+    "workingDir",
+    "params",
+    "results",
+    "securityContext",
+    "volumeMounts",
+];
+
+/// Check the spec of a StepAction.
+pub(super) fn check_step_action(
+    doc: &YamlDocument,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(spec) = doc.root.get("spec") else {
+        return;
+    };
+
+    for (field, node) in spec.entries() {
+        if !STEP_ACTION_FIELDS.contains(&field) {
+            diagnostics.push(unknown_field(
+                field,
+                node.key_or_range(),
+                &STEP_ACTION_FIELDS,
+                "StepAction spec",
+                false,
+            ));
+        }
+    }
+
+    if spec.get("image").is_none() {
+        diagnostics.push(make_diagnostic(
+            Rule::MissingImage,
+            spec.key_or_range(),
+            DiagnosticSeverity::ERROR,
+            "StepAction must define an 'image'".to_string(),
+        ));
+    }
+
+    if let (Some(_), Some(command)) = (spec.get("script"), spec.get("command")) {
+        diagnostics.push(make_diagnostic(
+            Rule::ScriptWithCommand,
+            command.key_or_range(),
+            DiagnosticSeverity::ERROR,
+            "StepAction sets both 'script' and 'command'; 'command' cannot be used together with 'script'"
+                .to_string(),
+        ));
+    }
+
+    check_step_action_param_references(spec, encoding, diagnostics);
+}
@@ -0,0 +1,103 @@
+// Typo suggestions
+//
+// Unknown fields are usually misspelled known ones (`taskz`, `runafter`,
+// `workspace` for `workspaces`). The closest known field by edit distance,
+// ignoring case, is suggested in the message and attached as a rename fix.
+
+use super::data::{with_fix, Fix};
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Range};
+
+/// Report a field that is not one of `known`, in `context` (e.g. `Pipeline spec`).
+///
+/// The fix renames the field to the closest known one, or removes it when none
+/// is close; without a suggestion, `list_known` lists the known fields in the
+/// message.
+pub(super) fn unknown_field(
+    field: &str,
+    range: Range,
+    known: &[&str],
+    context: &str,
+    list_known: bool,
+) -> Diagnostic {
+    let mut message = format!("Unknown field '{}' in {}", field, context);
+    let fix = match closest(field, known) {
+        Some(suggestion) => {
+            message.push_str(&format!(", did you mean '{}'?", suggestion));
+            Fix::RenameField {
+                field: field.to_string(),
+                to: suggestion.to_string(),
+            }
+        }
+        None => {
+            if list_known {
+                message.push_str(&format!("; expected one of: {}", known.join(", ")));
+            }
+            Fix::RemoveField {
+                field: field.to_string(),
+            }
+        }
+    };
+    with_fix(
+        make_diagnostic(
+            Rule::UnknownField,
+            range,
+            DiagnosticSeverity::WARNING,
+            message,
+        ),
+        fix,
+    )
+}
+
+/// The candidate closest to `word`, if close enough to be a typo of it: at
+/// most a third of its characters differ.
+fn closest<'a>(word: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let word = word.to_lowercase();
+    let max_distance = (word.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|candidate| (distance(&word, &candidate.to_lowercase()), *candidate))
+        .filter(|(d, _)| *d <= max_distance)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings, in characters.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closest() {
+        let fields = ["tasks", "finally", "params", "runAfter", "workspaces"];
+
+        assert_eq!(closest("taskz", &fields), Some("tasks"));
+        assert_eq!(closest("runafter", &fields), Some("runAfter"));
+        assert_eq!(closest("workspace", &fields), Some("workspaces"));
+        assert_eq!(closest("finaly", &fields), Some("finally"));
+        assert_eq!(closest("retry", &fields), None);
+        assert_eq!(closest("x", &fields), None);
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("same", "same"), 0);
+    }
+}
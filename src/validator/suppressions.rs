@@ -0,0 +1,138 @@
+// Suppression comments
+//
+// Comments silence diagnostics locally:
+//
+//   image: alpine  # tekton-lsp:disable=unpinned-image
+//   # tekton-lsp:disable-next-line=TPL076 -- pinned by the release job
+//   image: alpine
+//
+// `disable` at the end of a line applies to that line, and on a line of its
+// own to the rest of the document; `disable-next-line` applies to the line
+// after the comment. Rules are named by ID or code, separated by commas, and
+// without `=<rules>` every rule is silenced. Text after ` -- ` is a reason
+// for readers.
+
+use super::data::DiagnosticData;
+use super::rules::{find_rule, Rule};
+use super::tekton::make_diagnostic;
+use crate::parser::{Comment, Node, NodeValue, YamlDocument};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position};
+
+/// Prefix of the suppression comments, after the `#`
+const PREFIX: &str = "tekton-lsp:";
+
+/// Diagnostics silenced by a comment.
+struct Suppression {
+    /// First and last lines silenced
+    lines: (u32, u32),
+    /// IDs of the rules silenced; every rule when `None`
+    rules: Option<Vec<&'static str>>,
+}
+
+impl Suppression {
+    /// Whether the suppression silences a diagnostic.
+    fn covers(&self, diagnostic: &Diagnostic) -> bool {
+        let line = diagnostic.range.start.line;
+        if line < self.lines.0 || line > self.lines.1 {
+            return false;
+        }
+        let Some(rules) = &self.rules else {
+            return true;
+        };
+        DiagnosticData::of(diagnostic)
+            .and_then(|data| find_rule(&data.code))
+            .is_some_and(|rule| rules.contains(&rule.id))
+    }
+}
+
+/// Drop the diagnostics silenced by suppression comments, and report the
+/// unknown rules these comments name.
+pub(super) fn apply_suppressions(doc: &YamlDocument, diagnostics: &mut Vec<Diagnostic>) {
+    let mut suppressions = Vec::new();
+    let mut invalid = Vec::new();
+    for comment in &doc.comments {
+        if let Some(suppression) = parse_suppression(doc, comment, &mut invalid) {
+            suppressions.push(suppression);
+        }
+    }
+    diagnostics.retain(|diagnostic| !suppressions.iter().any(|s| s.covers(diagnostic)));
+    diagnostics.extend(invalid);
+}
+
+/// The suppression of a comment, if it is one.
+fn parse_suppression(
+    doc: &YamlDocument,
+    comment: &Comment,
+    invalid: &mut Vec<Diagnostic>,
+) -> Option<Suppression> {
+    let body = comment.text.trim_start_matches('#').trim();
+    let directive = body.strip_prefix(PREFIX)?;
+    let directive = directive
+        .split_once(" -- ")
+        .map_or(directive, |(d, _)| d)
+        .trim();
+    let (name, rules) = match directive.split_once('=') {
+        Some((name, rules)) => (name.trim(), Some(rules)),
+        None => (directive, None),
+    };
+
+    let line = comment.range.start.line;
+    let lines = match name {
+        "disable-next-line" => (line + 1, line + 1),
+        "disable" if has_content_before(&doc.root, comment.range.start) => (line, line),
+        "disable" => (line + 1, u32::MAX),
+        _ => {
+            invalid.push(make_diagnostic(
+                Rule::InvalidSuppression,
+                comment.range,
+                DiagnosticSeverity::WARNING,
+                format!(
+                    "Unknown directive '{}'; expected 'disable' or 'disable-next-line'",
+                    name
+                ),
+            ));
+            return None;
+        }
+    };
+
+    if rules.is_some_and(|rules| rules.split(',').all(|key| key.trim().is_empty())) {
+        invalid.push(make_diagnostic(
+            Rule::InvalidSuppression,
+            comment.range,
+            DiagnosticSeverity::WARNING,
+            "No rules after '=' in suppression comment".to_string(),
+        ));
+        return None;
+    }
+
+    let rules = rules.map(|rules| {
+        let mut ids = Vec::new();
+        for key in rules.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+            match find_rule(key) {
+                Some(rule) => ids.push(rule.id),
+                None => invalid.push(make_diagnostic(
+                    Rule::InvalidSuppression,
+                    comment.range,
+                    DiagnosticSeverity::WARNING,
+                    format!("Unknown rule '{}' in suppression comment", key),
+                )),
+            }
+        }
+        ids
+    });
+    Some(Suppression { lines, rules })
+}
+
+/// Whether a node starts on the line of `position`, before it.
+fn has_content_before(node: &Node, position: Position) -> bool {
+    let starts_before =
+        |start: Position| start.line == position.line && start.character < position.character;
+    if starts_before(node.range.start) || node.key_range.is_some_and(|r| starts_before(r.start)) {
+        return true;
+    }
+    match &node.value {
+        NodeValue::Mapping(map) => map.values().any(|n| has_content_before(n, position)),
+        NodeValue::Sequence(items) => items.iter().any(|n| has_content_before(n, position)),
+        _ => false,
+    }
+}
@@ -1,72 +1,299 @@
 // Tekton resource validator
 
-use crate::parser::YamlDocument;
-use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+use super::api_versions::check_api_version;
+use super::artifacts::check_artifacts;
+use super::dag::{check_result_ordering, check_run_after_cycles, check_run_after_names};
+use super::data::{add_paths, with_fix, DiagnosticData, Fix};
+use super::deprecations::{
+    check_deprecated_kind, check_pipeline_deprecations, check_pipeline_run_deprecations,
+    check_task_deprecations, check_task_run_deprecations, DEPRECATED_PIPELINE_FIELDS,
+};
+use super::descriptions::{check_descriptions, Describable};
+use super::env::check_step_env;
+use super::finally::check_finally_references;
+use super::migration::check_task_ref_bundle;
+use super::names::{
+    check_pipeline_names, check_pipeline_task_name_format, check_resource_name, check_task_names,
+};
+use super::outputs::check_step_outputs;
+use super::pac::check_pac_variables;
+use super::param_types::{check_param_declarations, check_param_expansions};
+use super::params::{
+    check_param_defaults, check_pipeline_param_references, check_task_param_references,
+    check_task_spec_param_references,
+};
+use super::pipeline::check_pipeline_structure;
+use super::references::{check_run_params, check_task_ref_params, check_task_refs};
+use super::resources::check_compute_resources;
+use super::result_types::{check_result_declarations, check_result_references};
+use super::results::check_results_size;
+use super::rules::{apply_rule_levels, Rule, RuleLevel};
+use super::runs::{check_pipeline_run, check_task_run};
+use super::security::check_step_security;
+use super::step_actions::check_step_action;
+use super::suggestions::unknown_field;
+use super::suppressions::apply_suppressions;
+use super::triggers::check_triggers;
+use super::unused::{check_unused_pipeline_declarations, check_unused_task_declarations};
+use super::when::check_when_expressions;
+use super::workspaces::{
+    check_mount_paths, check_run_workspace_bindings, check_workspace_bindings,
+};
+use crate::model::{Metadata, Pipeline, PipelineTask, Resource, Step, Task};
+use crate::parser::{NodeValue, YamlDocument};
+use crate::workspace::WorkspaceIndex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Range};
+
+/// Options toggling the optional validation rules
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ValidatorOptions {
+    /// Warn when a Task's results may exceed the termination message limit
+    pub results_size_lint: bool,
+    /// Warn about unpinned images, privileged or root steps and scripts
+    /// piping downloads into a shell
+    pub security_lint: bool,
+    /// Maximum length of the first line of a description (0 disables the check)
+    pub description_max_length: usize,
+    /// Node count above which validation yields between rules (0 disables slicing)
+    pub time_slice_node_threshold: usize,
+    /// Whether the cluster sets the `enable-artifacts` feature flag
+    pub enable_artifacts: bool,
+    /// Tekton Pipelines release the resources target, e.g. `v0.50`; features
+    /// added after it are flagged
+    pub tekton_version: Option<String>,
+    /// Levels of rules, by rule ID or code; `off` drops their diagnostics
+    pub rules: HashMap<String, RuleLevel>,
+}
+
+impl Default for ValidatorOptions {
+    fn default() -> Self {
+        Self {
+            results_size_lint: true,
+            security_lint: false,
+            description_max_length: 120,
+            time_slice_node_threshold: 5000,
+            enable_artifacts: false,
+            tekton_version: None,
+            rules: HashMap::new(),
+        }
+    }
+}
 
 /// Validator for Tekton resources
 #[derive(Debug, Clone)]
-pub struct TektonValidator;
+pub struct TektonValidator {
+    /// Shared so that clones of the validator see option changes
+    options: Arc<RwLock<ValidatorOptions>>,
+    /// Workspace index used to check references to other resources
+    index: Option<WorkspaceIndex>,
+}
 
 impl TektonValidator {
     /// Create a new Tekton validator
     pub fn new() -> Self {
-        Self
+        Self {
+            options: Arc::new(RwLock::new(ValidatorOptions::default())),
+            index: None,
+        }
+    }
+
+    /// Create a validator that checks references against the workspace index.
+    pub fn with_index(index: WorkspaceIndex) -> Self {
+        Self {
+            index: Some(index),
+            ..Self::new()
+        }
+    }
+
+    /// Replace the validation options
+    pub fn set_options(&self, options: ValidatorOptions) {
+        *self.options.write().unwrap() = options;
+    }
+
+    /// The current validation options
+    pub fn options(&self) -> ValidatorOptions {
+        self.options.read().unwrap().clone()
     }
 
     /// Validate a parsed YAML document and return diagnostics
     pub fn validate(&self, doc: &YamlDocument) -> Vec<Diagnostic> {
+        let resource = Resource::from_document(doc);
         let mut diagnostics = vec![];
-
-        // Validate metadata.name exists (required for all Tekton resources)
-        if let Some(metadata_node) = doc.root.get("metadata") {
-            if metadata_node.get("name").is_none() {
-                // Missing metadata.name
-                diagnostics.push(Diagnostic {
-                    range: metadata_node.range,
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    code: None,
-                    code_description: None,
-                    source: Some("tekton-lsp".to_string()),
-                    message: "Required field 'metadata.name' is missing".to_string(),
-                    related_information: None,
-                    tags: None,
-                    data: None,
-                });
-            }
+        for rule in self.rules(doc, resource.as_ref()) {
+            rule(&mut diagnostics);
         }
+        add_paths(doc, &mut diagnostics);
+        apply_suppressions(doc, &mut diagnostics);
+        apply_rule_levels(&self.options().rules, &mut diagnostics);
+        diagnostics
+    }
 
-        // Validate Pipeline-specific rules
-        if doc.kind.as_deref() == Some("Pipeline") {
-            self.validate_pipeline(doc, &mut diagnostics);
+    /// Validate a document, yielding to the executor between rules when it is large.
+    ///
+    /// Documents with more nodes than `time_slice_node_threshold` (a generated
+    /// Pipeline with thousands of tasks, ...) would otherwise hold a worker
+    /// thread for the whole validation and delay every other request.
+    pub async fn validate_sliced(&self, doc: &YamlDocument) -> Vec<Diagnostic> {
+        let threshold = self.options().time_slice_node_threshold;
+        if threshold == 0 || doc.root.node_count() <= threshold {
+            return self.validate(doc);
         }
 
+        let resource = Resource::from_document(doc);
+        let mut diagnostics = vec![];
+        for rule in self.rules(doc, resource.as_ref()) {
+            rule(&mut diagnostics);
+            tokio::task::yield_now().await;
+        }
+        add_paths(doc, &mut diagnostics);
+        apply_suppressions(doc, &mut diagnostics);
+        apply_rule_levels(&self.options().rules, &mut diagnostics);
         diagnostics
     }
 
-    /// Validate Pipeline-specific rules
-    fn validate_pipeline(&self, doc: &YamlDocument, diagnostics: &mut Vec<Diagnostic>) {
-        if let Some(spec_node) = doc.root.get("spec") {
-            use crate::parser::NodeValue;
+    /// The rules to run on a document, in the order their diagnostics are reported.
+    ///
+    /// Rules over the elements of a Pipeline run per pipeline task, so that a
+    /// sliced validation can yield between tasks.
+    fn rules<'r>(
+        &'r self,
+        doc: &'r YamlDocument,
+        resource: Option<&'r Resource<'r>>,
+    ) -> Vec<Check<'r>> {
+        let encoding = doc.encoding;
+        let api_version = doc.api_version.as_deref();
+        let mut rules: Vec<Check<'r>> = vec![Box::new(move |d| {
+            check_api_version(doc, d);
+            check_deprecated_kind(doc, d);
+            check_metadata_name(doc, d);
+            check_pac_variables(doc, d);
+        })];
+
+        match resource {
+            Some(Resource::Pipeline(pipeline)) => {
+                self.push_pipeline_rules(doc, pipeline, &mut rules)
+            }
+            Some(Resource::Task(task)) => {
+                rules.push(Box::new(move |d| self.validate_task(doc, task, None, d)))
+            }
+            Some(Resource::PipelineRun(run)) => {
+                rules.push(Box::new(move |d| {
+                    check_pipeline_run(run, doc.uri().as_ref(), d);
+                    check_pipeline_run_deprecations(run, api_version, d);
+                    check_run_workspace_bindings(run, self.index.as_ref(), d);
+                    check_run_params(run, self.index.as_ref(), d);
+                }));
+                if let Some(pipeline) = &run.pipeline_spec {
+                    self.push_pipeline_rules(doc, pipeline, &mut rules);
+                }
+            }
+            Some(Resource::TaskRun(run)) => {
+                rules.push(Box::new(move |d| {
+                    check_task_run(run, doc.uri().as_ref(), d);
+                    check_task_run_deprecations(run, api_version, d);
+                    if let Some(task_ref) = &run.task_ref {
+                        check_task_ref_bundle(task_ref, api_version, d);
+                    }
+                }));
+                if let Some(task) = &run.task_spec {
+                    rules.push(Box::new(move |d| self.validate_task(doc, task, None, d)));
+                }
+            }
+            None if doc.kind.as_deref() == Some("StepAction") => {
+                rules.push(Box::new(move |d| check_step_action(doc, encoding, d)))
+            }
+            // Triggers resources, and kinds without rules
+            None => rules.push(Box::new(move |d| check_triggers(doc, encoding, d))),
+        }
+
+        rules
+    }
+
+    /// Add the rules of a Pipeline, standalone or the `pipelineSpec` of a
+    /// PipelineRun, and of the inline `taskSpec`s of its tasks.
+    fn push_pipeline_rules<'r>(
+        &'r self,
+        doc: &'r YamlDocument,
+        pipeline: &'r Pipeline<'r>,
+        rules: &mut Vec<Check<'r>>,
+    ) {
+        let encoding = doc.encoding;
+        let api_version = doc.api_version.as_deref();
+        let tekton_version = self.options().tekton_version;
+        rules.push(Box::new(move |d| {
+            self.validate_pipeline(pipeline, d);
+            check_pipeline_structure(pipeline, doc.uri().as_ref(), d);
+            check_pipeline_deprecations(pipeline, api_version, d);
+            check_pipeline_names(pipeline, doc.uri().as_ref(), d);
+            check_pipeline_task_name_format(pipeline, d);
+            check_run_after_names(pipeline, d);
+            check_run_after_cycles(pipeline, d);
+            check_result_ordering(pipeline, encoding, d);
+            check_finally_references(pipeline, encoding, d);
+            check_when_expressions(pipeline, tekton_version.as_deref(), encoding, d);
+            self.validate_descriptions(Describable::from(pipeline), d);
+            check_param_defaults(&pipeline.params, encoding, d);
+            check_param_declarations(&pipeline.params, d);
+            check_pipeline_param_references(pipeline, encoding, d);
+            if let Some(spec) = pipeline.spec {
+                let task_specs: Vec<_> = pipeline
+                    .all_tasks()
+                    .filter_map(|t| t.task_spec.as_ref()?.spec)
+                    .collect();
+                check_param_expansions(spec, &task_specs, &pipeline.params, encoding, d);
+            }
+            check_result_declarations(&pipeline.results, d);
+            check_unused_pipeline_declarations(pipeline, encoding, d);
+        }));
+        for (task, task_spec) in pipeline
+            .all_tasks()
+            .filter_map(|t| Some((t, t.task_spec.as_ref()?)))
+        {
+            rules.push(Box::new(move |d| {
+                self.validate_task(doc, task_spec, Some((pipeline, task)), d)
+            }));
+        }
+        rules.push(Box::new(move |d| {
+            check_result_references(pipeline, self.index.as_ref(), encoding, d);
+            check_workspace_bindings(pipeline, self.index.as_ref(), d);
+            check_task_refs(pipeline, self.index.as_ref(), d);
+            check_task_ref_params(pipeline, self.index.as_ref(), d);
+            for task_ref in pipeline.all_tasks().filter_map(|t| t.task_ref.as_ref()) {
+                check_task_ref_bundle(task_ref, api_version, d);
+            }
+        }));
+    }
 
+    /// Validate the fields of a Pipeline spec
+    fn validate_pipeline(&self, pipeline: &Pipeline, diagnostics: &mut Vec<Diagnostic>) {
+        if let Some(spec_node) = pipeline.spec {
             // Known fields in Pipeline spec
-            let known_fields = ["tasks", "params", "workspaces", "results", "finally", "description"];
+            let known_fields = [
+                "tasks",
+                "params",
+                "workspaces",
+                "results",
+                "finally",
+                "description",
+                "displayName",
+            ];
 
             // Check for unknown fields in spec
-            if let NodeValue::Mapping(ref spec_map) = spec_node.value {
-                for (field_name, field_node) in spec_map {
-                    if !known_fields.contains(&field_name.as_str()) {
-                        diagnostics.push(Diagnostic {
-                            range: field_node.range,
-                            severity: Some(DiagnosticSeverity::WARNING),
-                            code: None,
-                            code_description: None,
-                            source: Some("tekton-lsp".to_string()),
-                            message: format!("Unknown field '{}' in Pipeline spec", field_name),
-                            related_information: None,
-                            tags: None,
-                            data: None,
-                        });
-                    }
+            for (field_name, field_node) in spec_node.entries() {
+                if !known_fields.contains(&field_name)
+                    && !DEPRECATED_PIPELINE_FIELDS.contains(&field_name)
+                {
+                    diagnostics.push(unknown_field(
+                        field_name,
+                        field_node.key_or_range(),
+                        &known_fields,
+                        "Pipeline spec",
+                        false,
+                    ));
                 }
             }
 
@@ -77,39 +304,210 @@ impl TektonValidator {
                     NodeValue::Sequence(ref tasks) => {
                         // It's a sequence - check if it's empty
                         if tasks.is_empty() {
-                            diagnostics.push(Diagnostic {
-                                range: tasks_node.range,
-                                severity: Some(DiagnosticSeverity::ERROR),
-                                code: None,
-                                code_description: None,
-                                source: Some("tekton-lsp".to_string()),
-                                message: "Pipeline must have at least one task".to_string(),
-                                related_information: None,
-                                tags: None,
-                                data: None,
-                            });
+                            diagnostics.push(make_diagnostic(
+                                Rule::MissingTasks,
+                                tasks_node.value_range,
+                                DiagnosticSeverity::ERROR,
+                                "Pipeline must have at least one task".to_string(),
+                            ));
                         }
                     }
                     _ => {
                         // Wrong type - should be an array/sequence
-                        diagnostics.push(Diagnostic {
-                            range: tasks_node.range,
-                            severity: Some(DiagnosticSeverity::ERROR),
-                            code: None,
-                            code_description: None,
-                            source: Some("tekton-lsp".to_string()),
-                            message: "Field 'tasks' must be an array".to_string(),
-                            related_information: None,
-                            tags: None,
-                            data: None,
-                        });
+                        diagnostics.push(make_diagnostic(
+                            Rule::InvalidTasks,
+                            tasks_node.value_range,
+                            DiagnosticSeverity::ERROR,
+                            "Field 'tasks' must be an array".to_string(),
+                        ));
                     }
                 }
             }
+
+            // finally is optional, but must be an array when present
+            if let Some(finally_node) = spec_node.get("finally") {
+                if !matches!(finally_node.value, NodeValue::Sequence(_) | NodeValue::Null) {
+                    diagnostics.push(make_diagnostic(
+                        Rule::InvalidFinally,
+                        finally_node.value_range,
+                        DiagnosticSeverity::ERROR,
+                        "Field 'finally' must be an array".to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Validate a Task, standalone or embedded as the `taskSpec` of a TaskRun
+    /// or of a pipeline task, given with its Pipeline.
+    fn validate_task(
+        &self,
+        doc: &YamlDocument,
+        task: &Task,
+        pipeline_task: Option<(&Pipeline, &PipelineTask)>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let encoding = doc.encoding;
+        let options = self.options();
+        self.validate_steps(&task.steps, diagnostics);
+        check_step_env(&task.steps, diagnostics);
+        check_step_outputs(&task.steps, diagnostics);
+        check_compute_resources(task, diagnostics);
+        check_task_deprecations(task, doc.api_version.as_deref(), diagnostics);
+        check_mount_paths(task, diagnostics);
+        if options.results_size_lint {
+            check_results_size(task, encoding, diagnostics);
+        }
+        if options.security_lint {
+            check_step_security(&task.steps, encoding, diagnostics);
+        }
+        check_task_names(task, doc.uri().as_ref(), diagnostics);
+        self.validate_descriptions(Describable::from(task), diagnostics);
+        check_param_defaults(&task.params, encoding, diagnostics);
+        check_param_declarations(&task.params, diagnostics);
+
+        // An embedded taskSpec also sees the params of its Pipeline
+        let mut params = task.params.clone();
+        match pipeline_task {
+            Some((pipeline, pipeline_task)) => {
+                check_task_spec_param_references(pipeline, pipeline_task, encoding, diagnostics);
+                params.extend(pipeline.params.iter().cloned());
+            }
+            None => check_task_param_references(task, encoding, diagnostics),
+        }
+        if let Some(spec) = task.spec {
+            check_param_expansions(spec, &[], &params, encoding, diagnostics);
+        }
+
+        check_result_declarations(&task.results, diagnostics);
+        check_unused_task_declarations(task, encoding, diagnostics);
+        let api_version = doc.api_version.as_deref();
+        check_artifacts(
+            task,
+            api_version,
+            options.enable_artifacts,
+            encoding,
+            diagnostics,
+        );
+    }
+
+    /// Validate the descriptions of a Task or Pipeline
+    fn validate_descriptions(&self, owner: Describable, diagnostics: &mut Vec<Diagnostic>) {
+        check_descriptions(&owner, self.options().description_max_length, diagnostics);
+    }
+
+    /// Validate the steps of a Task
+    fn validate_steps(&self, steps: &[Step], diagnostics: &mut Vec<Diagnostic>) {
+        for step in steps {
+            let step_name = step.display_name();
+
+            // `script` and `command` are mutually exclusive: Tekton rejects the step at runtime
+            if let (Some(_), Some(command)) = (&step.script, &step.command) {
+                diagnostics.push(make_diagnostic(
+                    Rule::ScriptWithCommand,
+                    command.key_or_range(),
+                    DiagnosticSeverity::WARNING,
+                    format!(
+                        "Step '{}' sets both 'script' and 'command'; 'command' cannot be used together with 'script'",
+                        step_name
+                    ),
+                ));
+            }
+
+            // imagePullPolicy must be one of the Kubernetes pull policies
+            if let Some(policy) = step.image_pull_policy {
+                if !IMAGE_PULL_POLICIES.contains(&policy.value) {
+                    diagnostics.push(make_diagnostic(
+                        Rule::InvalidImagePullPolicy,
+                        policy.range,
+                        DiagnosticSeverity::ERROR,
+                        format!(
+                            "Invalid imagePullPolicy '{}' in step '{}'; expected one of: {}",
+                            policy.value,
+                            step_name,
+                            IMAGE_PULL_POLICIES.join(", ")
+                        ),
+                    ));
+                }
+            }
+
+            // onError only accepts the two failure modes; params are checked once substituted
+            if let Some(on_error) = step.on_error {
+                if !ON_ERROR_VALUES.contains(&on_error.value) && !on_error.value.contains("$(") {
+                    diagnostics.push(make_diagnostic(
+                        Rule::InvalidOnError,
+                        on_error.range,
+                        DiagnosticSeverity::ERROR,
+                        format!(
+                            "Invalid onError '{}' in step '{}'; expected one of: {}",
+                            on_error.value,
+                            step_name,
+                            ON_ERROR_VALUES.join(", ")
+                        ),
+                    ));
+                }
+            }
         }
     }
 }
 
+/// A validation check over a document, adding to the diagnostics
+type Check<'r> = Box<dyn FnOnce(&mut Vec<Diagnostic>) + Send + 'r>;
+
+/// Validate metadata.name exists (required for all Tekton resources,
+/// except runs which are usually created with a generateName) and is a valid
+/// Kubernetes name
+fn check_metadata_name(doc: &YamlDocument, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(metadata_node) = doc.root.get("metadata") else {
+        return;
+    };
+    if let Some(name) = Metadata::from_root(&doc.root).name {
+        check_resource_name(name, diagnostics);
+    }
+    let is_run = matches!(doc.kind.as_deref(), Some("PipelineRun" | "TaskRun"));
+    let generated = is_run && metadata_node.get("generateName").is_some();
+    if metadata_node.get("name").is_none() && !generated {
+        diagnostics.push(with_fix(
+            make_diagnostic(
+                Rule::MissingName,
+                metadata_node.key_or_range(),
+                DiagnosticSeverity::ERROR,
+                "Required field 'metadata.name' is missing".to_string(),
+            ),
+            Fix::AddField {
+                field: "name".to_string(),
+            },
+        ));
+    }
+}
+
+/// Valid values for a container's `imagePullPolicy`
+const IMAGE_PULL_POLICIES: [&str; 3] = ["Always", "IfNotPresent", "Never"];
+
+/// Valid values for a step's `onError`
+const ON_ERROR_VALUES: [&str; 2] = ["continue", "stopAndFail"];
+
+/// Build a diagnostic with the fields shared by every Tekton rule, tagged
+/// with the code of the `rule` reporting it
+pub(super) fn make_diagnostic(
+    rule: Rule,
+    range: Range,
+    severity: DiagnosticSeverity,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        code: None,
+        code_description: None,
+        source: Some("tekton-lsp".to_string()),
+        message,
+        related_information: None,
+        tags: None,
+        data: serde_json::to_value(DiagnosticData::new(rule.code())).ok(),
+    }
+}
+
 impl Default for TektonValidator {
     fn default() -> Self {
         Self::new()
@@ -119,7 +517,18 @@ impl Default for TektonValidator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser::parse_yaml;
+    use crate::parser::{parse_yaml, parse_yaml_with_encoding, PositionEncoding};
+    use tower_lsp::lsp_types::{DiagnosticTag, NumberOrString, Position, Url};
+
+    /// Drop the unused declaration hints, for tests of other rules.
+    fn without_unused(mut diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics.retain(|d| {
+            !d.tags
+                .as_ref()
+                .is_some_and(|tags| tags.contains(&DiagnosticTag::UNNECESSARY))
+        });
+        diagnostics
+    }
 
     #[test]
     fn test_valid_pipeline_no_errors() {
@@ -164,4 +573,2635 @@ spec:
         assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
         assert!(diagnostics[0].message.contains("metadata.name"));
     }
+
+    #[test]
+    fn test_step_script_and_command_conflict() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - name: compile
+      image: golang
+      command: ["go", "build"]
+      script: |
+        go build ./...
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert!(diagnostics[0].message.contains("'script' and 'command'"));
+        assert_eq!(diagnostics[0].range.start.line, 9);
+    }
+
+    #[test]
+    fn test_step_env() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: env
+spec:
+  steps:
+    - name: build
+      image: alpine:3
+      env:
+        - name: OK
+          value: "1"
+        - value: no-name
+        - name: BOTH
+          value: "1"
+          valueFrom:
+            fieldRef:
+              fieldPath: metadata.name
+        - name: EMPTY
+        - name: TOKEN
+          valueFrom:
+            secretKeyRef:
+              name: creds
+        - name: TWO
+          valueFrom:
+            fieldRef:
+              fieldPath: metadata.name
+            configMapKeyRef:
+              name: config
+              key: two
+        - name: TYPO
+          valueFrom:
+            secretKeyref:
+              name: creds
+              key: token
+    - name: test
+      image: alpine:3
+      env: FOO=1
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        let messages: Vec<(u32, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (12, "Env entry of step 'build' is missing 'name'"),
+                (15, "Env 'BOTH' of step 'build' sets both 'value' and 'valueFrom'"),
+                (18, "Env 'EMPTY' of step 'build' has neither 'value' nor 'valueFrom'"),
+                (21, "'secretKeyRef' of env 'TOKEN' is missing 'key'"),
+                (24, "'valueFrom' of env 'TWO' must set only one of: secretKeyRef, configMapKeyRef, fieldRef, resourceFieldRef"),
+                (32, "Unknown field 'secretKeyref' in 'valueFrom' of env 'TYPO', did you mean 'secretKeyRef'?"),
+                (37, "'env' of step 'test' must be an array"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_step_on_error_and_output_configs() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  params:
+    - name: on-error
+  steps:
+    - name: lint
+      image: golangci/golangci-lint:v1.55
+      onError: ignore
+      stdoutConfig:
+        path: $(results.report.path)
+    - name: test
+      image: golang:1.21
+      onError: $(params.on-error)
+      stdoutConfig: report.txt
+      stderrConfig:
+        file: errors.txt
+    - name: build
+      image: golang:1.21
+      onError: continue
+      stderrConfig:
+        path: ""
+        pth: errors.txt
+  results:
+    - name: report
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = without_unused(TektonValidator::new().validate(&doc));
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (10, "Invalid onError 'ignore' in step 'lint'; expected one of: continue, stopAndFail"),
+                (16, "'stdoutConfig' of step 'test' must be a mapping with a 'path'"),
+                (18, "Unknown field 'file' in 'stderrConfig' of step 'test'; expected one of: path"),
+                (17, "'stderrConfig' of step 'test' must have a 'path'"),
+                (24, "Unknown field 'pth' in 'stderrConfig' of step 'build', did you mean 'path'?"),
+                (23, "'path' of 'stderrConfig' of step 'build' must be a non-empty string"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_resources() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: resources
+spec:
+  params:
+    - name: cpu
+  stepTemplate:
+    computeResources:
+      requests:
+        memory: 1GB
+  steps:
+    - name: build
+      image: alpine:3
+      computeResources:
+        requests:
+          cpu: "2"
+          memory: 512Mi
+        limits:
+          cpu: 500m
+          memory: 1Gi
+    - name: test
+      image: alpine:3
+      computeResources:
+        limits:
+          cpu: $(params.cpu)
+  sidecars:
+    - name: db
+      image: postgres:16
+      computeResources:
+        limits:
+          memory: 1.5.Gi
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = without_unused(TektonValidator::new().validate(&doc));
+
+        let messages: Vec<(u32, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (11, "Invalid quantity '1GB' for 'memory' of stepTemplate; expected a number with an optional suffix, e.g. '500m' or '1Gi'"),
+                (17, "The 'cpu' request of step 'build' is above its limit of 500m"),
+                (32, "Invalid quantity '1.5.Gi' for 'memory' of sidecar 'db'; expected a number with an optional suffix, e.g. '500m' or '1Gi'"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suppression_comments() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  retry: 1  # tekton-lsp:disable=unknown-field
+  # tekton-lsp:disable-next-line=TPL001 -- kept for old clusters
+  timeout: 1h
+  colour: red  # tekton-lsp:disable=missing-name
+  tasks:
+    # tekton-lsp:disable-next-line
+    - taskRef:
+        name: build
+    # tekton-lsp:disable=unknown-rule,conflicting-fields
+    - name: test
+      taskRef:
+        name: test
+      taskSpec:
+        steps: []
+      retry: 2
+  # tekton-lsp:disable-next-line=
+  color: blue
+  # tekton-lsp:enable
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        let found: Vec<(u32, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (9, "Unknown field 'colour' in Pipeline spec"),
+                (22, "Unknown field 'color' in Pipeline spec"),
+                (20, "Unknown field 'retry' in Pipeline task 'test'"),
+                (14, "Unknown rule 'unknown-rule' in suppression comment"),
+                (21, "No rules after '=' in suppression comment"),
+                (
+                    23,
+                    "Unknown directive 'enable'; expected 'disable' or 'disable-next-line'"
+                ),
+            ]
+        );
+        for diagnostic in &diagnostics[3..] {
+            assert_eq!(
+                diagnostic.code,
+                Some(NumberOrString::String("TPL086".to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_image_pull_policy() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - name: ok
+      image: alpine
+      imagePullPolicy: IfNotPresent
+    - name: bad
+      image: alpine
+      imagePullPolicy: Sometimes
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert!(diagnostics[0].message.contains("Sometimes"));
+        assert_eq!(diagnostics[0].range.start.line, 12);
+    }
+
+    #[test]
+    fn test_steps_with_merge_keys() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - &base
+      name: one
+      image: alpine
+      imagePullPolicy: Always
+    - <<: *base
+      name: two
+      imagePullPolicy: Sometimes
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let task = Task::from_node(&doc.root);
+        assert_eq!(task.steps[1].image.unwrap().value, "alpine");
+
+        let diagnostics = TektonValidator::new().validate(&doc);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("Sometimes"));
+        assert_eq!(diagnostics[0].range.start.line, 13);
+    }
+
+    #[test]
+    fn test_diagnostic_columns_follow_position_encoding() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  params:
+    - name: tag
+      default: 🚀 $(params.image)
+  steps:
+    - image: alpine
+"#;
+
+        let validator = TektonValidator::new();
+        let doc = parse_yaml_with_encoding("test.yaml", yaml, PositionEncoding::Utf16).unwrap();
+        let diagnostics = without_unused(validator.validate(&doc));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.start.character, 18);
+
+        let doc = parse_yaml_with_encoding("test.yaml", yaml, PositionEncoding::Utf8).unwrap();
+        let diagnostics = validator.validate(&doc);
+        assert_eq!(diagnostics[0].range.start.character, 20);
+    }
+
+    #[test]
+    fn test_unknown_fields_reported_in_document_order() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test-pipeline
+spec:
+  zeta: 1
+  tasks:
+    - name: build
+      taskRef:
+        name: some-task
+  alpha: 2
+  middle: 3
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown field 'zeta' in Pipeline spec",
+                "Unknown field 'alpha' in Pipeline spec",
+                "Unknown field 'middle' in Pipeline spec",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_field_suggestions() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test-pipeline
+spec:
+  taskz:
+    - name: build
+  tasks:
+    - name: build
+      runafter: [lint]
+      taskRef:
+        name: some-task
+    - name: lint
+      taskRef:
+        name: lint
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown field 'taskz' in Pipeline spec, did you mean 'tasks'?",
+                "Unknown field 'runafter' in Pipeline task 'build', did you mean 'runAfter'?",
+            ]
+        );
+        assert_eq!(
+            DiagnosticData::of(&diagnostics[0]).unwrap().fix,
+            Some(Fix::RenameField {
+                field: "taskz".to_string(),
+                to: "tasks".to_string(),
+            })
+        );
+        assert_eq!(diagnostics[0].range.start, Position::new(6, 2));
+        assert_eq!(diagnostics[0].range.end, Position::new(6, 7));
+    }
+
+    #[test]
+    fn test_pipeline_task_structure() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  params:
+    - description: no name
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+      taskSpec:
+        steps:
+          - image: alpine
+      retry: 3
+    - name: test
+      params:
+        - value: x
+    - taskRef:
+        name: lint
+  finally:
+    - name: cleanup
+      runAfter: [build]
+      taskRef:
+        name: cleanup
+"#;
+
+        let doc = parse_yaml("file:///ci/pipeline.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        let found: Vec<(u32, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (7, "Pipeline param must have a 'name'"),
+                (10, "Pipeline task 'build' cannot have both 'taskRef' and 'taskSpec'"),
+                (12, "Pipeline task 'build' cannot have both 'taskRef' and 'taskSpec'"),
+                (15, "Unknown field 'retry' in Pipeline task 'build'"),
+                (16, "Pipeline task 'test' must define one of 'taskRef' or 'taskSpec'"),
+                (18, "Param of Pipeline task 'test' must have a 'name'"),
+                (19, "Pipeline task must have a 'name'"),
+                (23, "Finally task 'cleanup' cannot use 'runAfter'; finally tasks run after all tasks"),
+            ]
+        );
+        assert_eq!(diagnostics[3].severity, Some(DiagnosticSeverity::WARNING));
+
+        // Each conflicting key points at the other
+        for (diagnostic, other, line) in [
+            (&diagnostics[1], "taskSpec", 12),
+            (&diagnostics[2], "taskRef", 10),
+        ] {
+            let related = diagnostic.related_information.as_ref().unwrap();
+            assert_eq!(related.len(), 1);
+            assert_eq!(related[0].location.uri.as_str(), "file:///ci/pipeline.yaml");
+            assert_eq!(related[0].location.range.start.line, line);
+            assert_eq!(related[0].message, format!("'{}' is also set here", other));
+        }
+    }
+
+    #[test]
+    fn test_finally_must_be_an_array() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+  finally: cleanup
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Field 'finally' must be an array");
+    }
+
+    #[test]
+    fn test_results_size_lint() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: report
+spec:
+  results:
+    - name: report
+    - name: digest
+  steps:
+    - name: build
+      image: alpine
+      script: |
+        make report
+        cat report.json > $(results.report.path)
+        echo -n "$DIGEST" > $(results.digest.path)
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let validator = TektonValidator::new();
+        let diagnostics = validator.validate(&doc);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert!(diagnostics[0].message.contains("'report'"));
+        assert!(diagnostics[0].message.contains("4096"));
+        assert_eq!(diagnostics[0].range.start.line, 14);
+        assert_eq!(diagnostics[0].range.start.character, 26);
+
+        validator.set_options(ValidatorOptions {
+            results_size_lint: false,
+            ..ValidatorOptions::default()
+        });
+        assert!(validator.validate(&doc).is_empty());
+    }
+
+    #[test]
+    fn test_results_size_lint_flags_large_array_values() {
+        let entries: Vec<String> = (0..25).map(|i| format!("\"item-{}\"", i)).collect();
+        let yaml = format!(
+            "apiVersion: tekton.dev/v1\nkind: Task\nmetadata:\n  name: list\nspec:\n  results:\n    - name: items\n      type: array\n      value: [{}]\n",
+            entries.join(", ")
+        );
+
+        let doc = parse_yaml("test.yaml", &yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0]
+            .message
+            .contains("Array result 'items' has 25 entries"));
+        assert_eq!(diagnostics[0].range.start.line, 8);
+    }
+
+    #[test]
+    fn test_description_checks() {
+        let long = "word ".repeat(30);
+        let yaml = format!(
+            r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  description: |
+    {long}
+  params:
+    - name: revision
+      description: Short and sweet
+    - name: flags
+      description:
+        nested: mapping
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+"#
+        );
+
+        let doc = parse_yaml("test.yaml", &yaml).unwrap();
+        let validator = TektonValidator::new();
+        let diagnostics = without_unused(validator.validate(&doc));
+
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0]
+            .message
+            .contains("description of spec is 149 characters"));
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(diagnostics[0].range.start.line, 5);
+        assert_eq!(
+            diagnostics[1].message,
+            "Description of param 'flags' must be a string"
+        );
+        assert_eq!(diagnostics[1].severity, Some(DiagnosticSeverity::ERROR));
+
+        validator.set_options(ValidatorOptions {
+            description_max_length: 0,
+            ..ValidatorOptions::default()
+        });
+        assert_eq!(without_unused(validator.validate(&doc)).len(), 1);
+    }
+
+    #[test]
+    fn test_param_default_with_references() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  params:
+    - name: image
+      default: registry.example.com/app
+    - name: tag
+      default: $(params.image):latest
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+    - name: deploy
+      runAfter: [build]
+      taskSpec:
+        params:
+          - name: digests
+            type: array
+            default:
+              - $(tasks.build.results.digest)
+              - $(context.pipelineRun.name)
+        steps:
+          - image: alpine
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = without_unused(TektonValidator::new().validate(&doc));
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].message,
+            "Default of param 'tag' references param '$(params.image)'; variables are not substituted in param defaults"
+        );
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(
+            (
+                diagnostics[0].range.start.line,
+                diagnostics[0].range.start.character
+            ),
+            (10, 15)
+        );
+        assert!(diagnostics[1]
+            .message
+            .contains("param 'digests' references task result"));
+        assert_eq!(diagnostics[1].range.start.line, 22);
+    }
+
+    #[test]
+    fn test_result_type_declarations() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: scan
+spec:
+  results:
+    - name: digest
+    - name: files
+      type: array
+    - name: report
+      type: object
+      properties:
+        url:
+          type: string
+        count:
+          type: number
+    - name: summary
+      type: object
+    - name: tags
+      type: list
+    - name: id
+      type: string
+      properties:
+        value: {type: string}
+  steps:
+    - image: alpine
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let messages: Vec<String> = without_unused(TektonValidator::new().validate(&doc))
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "Property 'count' of result 'report' has type 'number'; object properties must be strings",
+                "Object result 'summary' must declare its 'properties'",
+                "Invalid type 'list' for result 'tags'; expected one of: string, array, object",
+                "Result 'id' of type 'string' cannot declare 'properties'; only object results have properties",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_task_result_references_match_types() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: build
+      taskSpec:
+        results:
+          - name: digest
+          - name: images
+            type: array
+          - name: report
+            type: object
+            properties:
+              url: {type: string}
+        steps:
+          - image: alpine
+    - name: publish
+      taskRef:
+        name: publish
+      params:
+        - name: all
+          value: $(tasks.build.results.images[*])
+        - name: first
+          value: $(tasks.build.results.images[0])
+        - name: url
+          value: $(tasks.build.results.report.url)
+        - name: whole
+          value: $(tasks.build.results.report[*])
+        - name: digest
+          value: $(tasks.build.results.digest)
+        - name: bad-digest
+          value: $(tasks.build.results.digest[*])
+        - name: bad-images
+          value: $(tasks.build.results.images)
+        - name: bad-report
+          value: $(tasks.build.results.report.size)
+        - name: remote
+          value: $(tasks.other.results.anything[*])
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = without_unused(TektonValidator::new().validate(&doc));
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "Result 'digest' of task 'build' is a string; it cannot be expanded with '[*]' or indexed",
+                "Array result 'images' of task 'build' must be referenced with '[*]' or an index",
+                "Object result 'report' of task 'build' has no property 'size'; declared properties: url",
+                "Result reference to unknown pipeline task 'other'",
+            ]
+        );
+        assert_eq!(diagnostics[0].range.start.line, 34);
+        assert_eq!(diagnostics[0].range.start.character, 17);
+    }
+
+    #[test]
+    fn test_workspace_mount_path_collisions() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  workspaces:
+    - name: source
+    - name: cache
+      mountPath: /workspace/source/
+    - name: output
+      mountPath: /out
+  steps:
+    - name: compile
+      image: golang
+      volumeMounts:
+        - name: tmp
+          mountPath: /tmp
+        - name: results
+          mountPath: /out
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = without_unused(TektonValidator::new().validate(&doc));
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].message,
+            "Workspaces 'source' and 'cache' are both mounted at '/workspace/source'"
+        );
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(diagnostics[0].range.start.line, 9);
+        assert_eq!(
+            diagnostics[1].message,
+            "Volume mount 'results' of step 'compile' uses '/out', where workspace 'output' is mounted"
+        );
+        assert_eq!(diagnostics[1].range.start.line, 19);
+    }
+
+    #[test]
+    fn test_pipeline_run_checks() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  generateName: ci-
+spec:
+  pipelineRef:
+    name: ci
+  pipelineSpec:
+    tasks: []
+  params:
+    - name: revision
+      value: main
+    - name: image
+    - value: orphan
+  workspaces:
+    - name: source
+      volumeClaimTemplate:
+        spec: {}
+    - name: cache
+      subPath: go
+  taskRunSpecs:
+    - pipelineTaskName: build
+    - pipelineTaskName: [build]
+    - serviceAccountName: builder
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let messages: Vec<String> = TektonValidator::new()
+            .validate(&doc)
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "PipelineRun cannot have both 'pipelineRef' and 'pipelineSpec'".to_string(),
+                "PipelineRun cannot have both 'pipelineRef' and 'pipelineSpec'".to_string(),
+                "Param 'image' of PipelineRun must have a 'value'".to_string(),
+                "Param of PipelineRun must have a 'name'".to_string(),
+                format!(
+                    "Workspace 'cache' of PipelineRun must be backed by a volume: one of {}",
+                    "persistentVolumeClaim, volumeClaimTemplate, emptyDir, configMap, secret, projected, csi"
+                ),
+                "'pipelineTaskName' of a taskRunSpecs entry must be a string".to_string(),
+                "Each taskRunSpecs entry must have a 'pipelineTaskName'".to_string(),
+                // From the embedded pipelineSpec
+                "Pipeline must have at least one task".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_task_run_checks() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: TaskRun
+metadata:
+  generateName: build-
+spec:
+  params:
+    - name: revision
+      value: main
+  workspaces:
+    - name: source
+      emptyDir: {}
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "TaskRun must define one of 'taskRef' or 'taskSpec'"
+        );
+        assert_eq!(diagnostics[0].range.start.line, 5);
+    }
+
+    #[test]
+    fn test_task_ref_bundle_is_deprecated_in_v1beta1() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1beta1
+kind: TaskRun
+metadata:
+  name: build-run
+spec:
+  taskRef:
+    name: build
+    bundle: registry.example.com/tasks:1.0
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        // Along with the deprecated apiVersion itself
+        assert_eq!(diagnostics.len(), 2);
+        let bundle = diagnostics
+            .iter()
+            .find(|d| d.message == "'taskRef.bundle' is deprecated; use the bundles resolver")
+            .unwrap();
+        assert_eq!(bundle.severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(bundle.range.start.line, 8);
+        assert!(bundle.data.is_some());
+    }
+
+    #[test]
+    fn test_deprecated_constructs() {
+        let yaml = r#"apiVersion: tekton.dev/v1beta1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  resources:
+    - name: source
+      type: git
+  tasks:
+    - name: build
+      taskRef:
+        name: golang-build
+        kind: ClusterTask
+      conditions:
+        - conditionRef: is-main
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics: Vec<_> = TektonValidator::new()
+            .validate(&doc)
+            .into_iter()
+            .filter(|d| d.tags == Some(vec![DiagnosticTag::DEPRECATED]))
+            .map(|d| (d.range.start.line, d.severity.unwrap(), d.message))
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                (0, DiagnosticSeverity::WARNING, "tekton.dev/v1beta1 is deprecated for Pipeline; migrate to tekton.dev/v1".to_string()),
+                (5, DiagnosticSeverity::WARNING, "'resources' (PipelineResources) of Pipeline is deprecated; use workspaces and params instead".to_string()),
+                (13, DiagnosticSeverity::WARNING, "'conditions' of Pipeline task 'build' is deprecated; use 'when' expressions instead".to_string()),
+                (12, DiagnosticSeverity::WARNING, "Kind 'ClusterTask' is deprecated; use a Task fetched with the cluster resolver instead".to_string()),
+            ]
+        );
+
+        // Removed fields are errors in v1 resources
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  name: ci-run
+spec:
+  pipelineRef:
+    name: ci
+  timeout: 1h
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "'timeout' of PipelineRun was removed in tekton.dev/v1; use 'timeouts.pipeline' instead"
+        );
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(diagnostics[0].tags, Some(vec![DiagnosticTag::DEPRECATED]));
+
+        let doc = parse_yaml(
+            "test.yaml",
+            "apiVersion: tekton.dev/v1alpha1\nkind: Condition\nmetadata:\n  name: is-main\n",
+        )
+        .unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "Kind 'Condition' is deprecated; use 'when' expressions instead"
+        );
+        assert_eq!(diagnostics[0].range.start.line, 1);
+    }
+
+    #[test]
+    fn test_api_version_validation() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1beta1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - name: build
+      image: golang
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "tekton.dev/v1beta1 is deprecated for Task; migrate to tekton.dev/v1"
+        );
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(diagnostics[0].tags, Some(vec![DiagnosticTag::DEPRECATED]));
+        assert_eq!(diagnostics[0].range.start.line, 1);
+        assert_eq!(diagnostics[0].range.start.character, 12);
+
+        // StepActions have no v1 to migrate to
+        let doc = parse_yaml("test.yaml", &yaml.replace("kind: Task", "kind: StepAction")).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        assert!(diagnostics
+            .iter()
+            .all(|d| !d.message.contains("apiVersion") && !d.message.contains("deprecated")));
+
+        let doc = parse_yaml("test.yaml", &yaml.replace("v1beta1", "v2")).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "Unknown apiVersion 'tekton.dev/v2' for Task; expected one of: tekton.dev/v1, tekton.dev/v1beta1"
+        );
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+
+        // Kinds of other groups are left alone
+        let doc = parse_yaml(
+            "test.yaml",
+            &yaml.replace("tekton.dev/v1beta1", "example.com/v1"),
+        )
+        .unwrap();
+        assert!(TektonValidator::new().validate(&doc).is_empty());
+    }
+
+    #[test]
+    fn test_run_after_cycles() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: fetch
+      taskRef: {name: git-clone}
+      runAfter: [test]
+    - name: build
+      taskRef: {name: build}
+      runAfter: [fetch]
+    - name: test
+      taskRef: {name: test}
+      runAfter:
+        - build
+    - name: lint
+      taskRef: {name: lint}
+      runAfter: [lint, fetch]
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        let found: Vec<(u32, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.message.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (9, "Pipeline task 'fetch' runs after 'test', which creates a cycle: fetch → test → build → fetch"),
+                (12, "Pipeline task 'build' runs after 'fetch', which creates a cycle: build → fetch → test → build"),
+                (16, "Pipeline task 'test' runs after 'build', which creates a cycle: test → build → fetch → test"),
+                (19, "Pipeline task 'lint' runs after 'lint', which creates a cycle: lint → lint"),
+            ]
+        );
+        assert_eq!(diagnostics[0].range.start.character, 17);
+    }
+
+    #[test]
+    fn test_run_after_names_must_exist() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: build
+      taskRef: {name: build}
+    - name: test
+      taskRef: {name: test}
+      runAfter:
+        - build
+        - biuld
+        - notify
+  finally:
+    - name: notify
+      taskRef: {name: notify}
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        let found: Vec<(u32, u32, &str)> = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.range.start.line,
+                    d.range.start.character,
+                    d.message.as_str(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (13, 10, "Pipeline task 'test' runs after unknown task 'biuld'"),
+                (
+                    14,
+                    10,
+                    "Pipeline task 'test' cannot run after finally task 'notify'; finally tasks run after all tasks"
+                ),
+            ]
+        );
+        assert_eq!(diagnostics[0].range.end.character, 15);
+    }
+
+    #[test]
+    fn test_duplicate_names() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  params:
+    - name: revision
+    - name: revision
+  tasks:
+    - name: build
+      taskSpec:
+        steps:
+          - name: compile
+            image: golang
+          - name: compile
+            image: golang
+  finally:
+    - name: build
+      taskRef: {name: notify}
+"#;
+
+        let doc = parse_yaml("file:///ci/pipeline.yaml", yaml).unwrap();
+        let diagnostics = without_unused(TektonValidator::new().validate(&doc));
+        let found: Vec<(u32, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.message.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (8, "Duplicate param name 'revision'"),
+                (18, "Duplicate pipeline task name 'build'"),
+                (15, "Duplicate step name 'compile'"),
+            ]
+        );
+
+        let related = diagnostics[1].related_information.as_ref().unwrap();
+        assert_eq!(related[0].location.uri.as_str(), "file:///ci/pipeline.yaml");
+        assert_eq!(related[0].location.range.start.line, 10);
+        assert_eq!(related[0].message, "First pipeline task named 'build'");
+
+        // Without a URI to point to, the diagnostic is still reported
+        let doc = parse_yaml("pipeline.yaml", yaml).unwrap();
+        let diagnostics = without_unused(TektonValidator::new().validate(&doc));
+        assert_eq!(diagnostics.len(), 3);
+        assert!(diagnostics[0].related_information.is_none());
+    }
+
+    #[test]
+    fn test_sliced_validation_yields_between_rules() {
+        use std::future::Future;
+        use std::task::{Context, Poll, Waker};
+
+        let mut yaml = String::from("apiVersion: tekton.dev/v1\nkind: Pipeline\nmetadata:\n  name: generated\nspec:\n  tasks:\n");
+        for i in 0..50 {
+            yaml.push_str(&format!(
+                "    - name: task-{i}\n      taskSpec:\n        steps:\n          - name: run\n            image: alpine\n          - name: run\n            image: alpine\n"
+            ));
+        }
+        let doc = parse_yaml("test.yaml", &yaml).unwrap();
+        assert!(doc.root.node_count() > 500);
+
+        let validator = TektonValidator::new();
+        validator.set_options(ValidatorOptions {
+            time_slice_node_threshold: 500,
+            ..ValidatorOptions::default()
+        });
+        let expected = validator.validate(&doc);
+        assert_eq!(expected.len(), 50);
+
+        // The first poll stops after the first rule instead of validating everything
+        let mut context = Context::from_waker(Waker::noop());
+        let mut sliced = std::pin::pin!(validator.validate_sliced(&doc));
+        let mut polls = 1;
+        let diagnostics = loop {
+            match sliced.as_mut().poll(&mut context) {
+                Poll::Ready(diagnostics) => break diagnostics,
+                Poll::Pending => polls += 1,
+            }
+        };
+        assert!(
+            polls > 50,
+            "Expected a yield per pipeline task, got {} polls",
+            polls
+        );
+        assert_eq!(diagnostics, expected);
+
+        // Small documents are validated in one go
+        validator.set_options(ValidatorOptions::default());
+        let mut sliced = std::pin::pin!(validator.validate_sliced(&doc));
+        assert!(sliced.as_mut().poll(&mut context).is_ready());
+    }
+
+    #[test]
+    fn test_step_artifacts_are_feature_gated() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - name: build
+      image: alpine
+      script: tar czf $(step.artifacts.path)/source.tgz .
+      artifacts:
+        outputs:
+          - name: source
+          - uri: oci://example
+        extra: []
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let validator = TektonValidator::new();
+
+        // Without the feature flag, each use is flagged
+        let diagnostics = validator.validate(&doc);
+        let gated: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.message.contains("'enable-artifacts' feature flag"))
+            .collect();
+        assert_eq!(gated.len(), 2, "Got: {:?}", diagnostics);
+        assert_eq!(gated[0].range.start.line, 9);
+        assert_eq!(gated[1].range.start.line, 10);
+
+        validator.set_options(ValidatorOptions {
+            enable_artifacts: true,
+            ..ValidatorOptions::default()
+        });
+        let messages: Vec<String> = validator
+            .validate(&doc)
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Each artifact in outputs of step 'build' must have a 'name'",
+                "Unknown field 'extra' in artifacts of step 'build'; expected one of: inputs, outputs",
+            ]
+        );
+
+        // Artifacts are not part of tekton.dev/v1beta1, whatever the flag
+        let doc = parse_yaml(
+            "test.yaml",
+            &yaml.replace("tekton.dev/v1", "tekton.dev/v1beta1"),
+        )
+        .unwrap();
+        let unavailable = validator
+            .validate(&doc)
+            .into_iter()
+            .filter(|d| d.message == "Artifacts are only available in tekton.dev/v1")
+            .count();
+        assert_eq!(unavailable, 2);
+    }
+
+    #[test]
+    fn test_undeclared_param_references() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: build
+spec:
+  params:
+    - name: revision
+  tasks:
+    - name: clone
+      params:
+        - name: url
+          value: "$(params.url)"
+      taskSpec:
+        params:
+          - name: depth
+        steps:
+          - name: clone
+            image: alpine
+            script: git clone $(params.url) --depth $(params.depth) --rev $(params.revision) $(params.mirror)
+    - name: test
+      when:
+        - input: "$(params.revision)"
+          operator: notin
+          values: ["$(params.skip)"]
+      taskRef:
+        name: test
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        let unknown: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.message.starts_with("Unknown param"))
+            .map(|d| {
+                (
+                    d.message.as_str(),
+                    d.range.start.line,
+                    d.range.start.character,
+                    d.range.end.character,
+                )
+            })
+            .collect();
+        assert_eq!(
+            unknown,
+            vec![
+                ("Unknown param 'url'; it is not declared in spec.params", 12, 18, 31),
+                ("Unknown param 'skip'; it is not declared in spec.params", 24, 20, 34),
+                (
+                    "Unknown param 'mirror'; it is not declared in the taskSpec of pipeline task 'clone', its params or spec.params",
+                    19,
+                    93,
+                    109
+                ),
+            ]
+        );
+
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: echo
+spec:
+  params:
+    - name: message
+      default: $(params.other)
+  steps:
+    - name: echo
+      image: alpine
+      args: ["$(params.message)", '$(params["missing"])']
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        let unknown: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.message.starts_with("Unknown param"))
+            .map(|d| (d.message.as_str(), d.range.start.line))
+            .collect();
+        assert_eq!(
+            unknown,
+            vec![(
+                "Unknown param 'missing'; it is not declared in spec.params",
+                12
+            )]
+        );
+    }
+
+    #[test]
+    fn test_task_result_references_are_declared_and_ordered() {
+        let task = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: git-clone
+spec:
+  results:
+    - name: commit
+    - name: url
+  steps:
+    - image: alpine
+"#;
+        let index = WorkspaceIndex::new();
+        index
+            .index_document(&Url::parse("file:///repo/git-clone.yaml").unwrap(), task)
+            .unwrap();
+
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: clone
+      taskRef:
+        name: git-clone
+    - name: build
+      params:
+        - name: revision
+          value: $(tasks.clone.results.commit) $(tasks.clone.results.branch)
+      taskRef:
+        name: build
+    - name: test
+      displayName: Test $(tasks.clone.results.commit)
+      taskRef:
+        name: test
+    - name: lint
+      runAfter: [build]
+      displayName: Lint $(tasks.clone.results.url) $(tasks.lint.results.report)
+      taskRef:
+        name: lint
+    - name: scan
+      params:
+        - name: report
+          value: $(tasks.notify.results.sent)
+      taskRef:
+        name: scan
+  finally:
+    - name: notify
+      params:
+        - name: commit
+          value: $(tasks.clone.results.commit)
+      taskRef:
+        name: notify
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::with_index(index).validate(&doc);
+        // Only git-clone is indexed; the other taskRefs are reported as unresolved
+        let found: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| !d.message.ends_with("is not defined in the workspace"))
+            .map(|d| (d.severity.unwrap(), d.message.as_str(), d.range.start.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    DiagnosticSeverity::WARNING,
+                    "Pipeline task 'test' uses a result of 'clone' without running after it; add 'clone' to 'runAfter' or pass the result as a param",
+                    17
+                ),
+                (DiagnosticSeverity::ERROR, "Pipeline task 'lint' cannot use its own results", 22),
+                (
+                    DiagnosticSeverity::ERROR,
+                    "Pipeline task 'scan' cannot use results of finally task 'notify', which is not guaranteed to run first",
+                    28
+                ),
+                (
+                    DiagnosticSeverity::ERROR,
+                    "Pipeline task 'clone' has no result 'branch'; declared results: commit, url",
+                    13
+                ),
+            ]
+        );
+
+        // Without the Task in the index, its results are unknown
+        let diagnostics = TektonValidator::new().validate(&doc);
+        assert!(!diagnostics
+            .iter()
+            .any(|d| d.message.contains("has no result")));
+    }
+
+    #[test]
+    fn test_pipeline_task_workspace_bindings() {
+        let task = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: git-clone
+spec:
+  workspaces:
+    - name: output
+    - name: ssh-directory
+      optional: true
+    - name: cache
+  steps:
+    - image: alpine
+"#;
+        let index = WorkspaceIndex::new();
+        index
+            .index_document(&Url::parse("file:///repo/git-clone.yaml").unwrap(), task)
+            .unwrap();
+
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  workspaces:
+    - name: shared
+    - name: cache
+      optional: true
+  tasks:
+    - name: clone
+      taskRef:
+        name: git-clone
+      workspaces:
+        - name: output
+          workspace: shared
+        - name: cache
+        - name: credentials
+          workspace: secrets
+    - name: build
+      taskSpec:
+        workspaces:
+          - name: source
+        steps:
+          - image: alpine
+      workspaces:
+        - name: sources
+          workspace: shared
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = without_unused(TektonValidator::with_index(index).validate(&doc));
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.message.as_str(), d.range.start.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "Optional pipeline workspace 'cache' is bound to workspace 'cache' of pipeline task 'clone', which is required",
+                    17
+                ),
+                (
+                    "Pipeline task 'clone' binds workspace 'credentials' to 'secrets', which is not declared in spec.workspaces",
+                    19
+                ),
+                (
+                    "Task 'git-clone' of pipeline task 'clone' declares no workspace 'credentials'; declared workspaces: output, ssh-directory, cache",
+                    18
+                ),
+                (
+                    "The taskSpec of pipeline task 'build' declares no workspace 'sources'; declared workspaces: source",
+                    27
+                ),
+            ]
+        );
+
+        // Without the binding of a required workspace
+        let doc = parse_yaml(
+            "test.yaml",
+            &yaml.replace("        - name: output\n          workspace: shared\n", ""),
+        )
+        .unwrap();
+        let validator = TektonValidator::new();
+        assert!(!validator
+            .validate(&doc)
+            .iter()
+            .any(|d| d.message.contains("does not bind")));
+        let index = WorkspaceIndex::new();
+        index
+            .index_document(&Url::parse("file:///repo/git-clone.yaml").unwrap(), task)
+            .unwrap();
+        let diagnostics = TektonValidator::with_index(index).validate(&doc);
+        let unbound: Vec<&str> = diagnostics
+            .iter()
+            .filter(|d| d.message.contains("does not bind"))
+            .map(|d| d.message.as_str())
+            .collect();
+        assert_eq!(
+            unbound,
+            vec!["Pipeline task 'clone' does not bind workspace 'output', which Task 'git-clone' requires"]
+        );
+    }
+
+    #[test]
+    fn test_when_expressions() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  params:
+    - name: branch
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+      when:
+        - input: $(params.branch)
+          operator: equals
+          values: main
+        - input: $(tasks.status)
+          values: []
+        - cel: "'$(params.branch)' == 'main'"
+          operator: in
+          extra: true
+  finally:
+    - name: notify
+      taskRef:
+        name: notify
+      when:
+        - input: $(tasks.build.status)
+          operator: in
+          values: ["Failed"]
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.severity.unwrap(),
+                    d.message.as_str(),
+                    d.range.start.line,
+                    d.range.start.character,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    DiagnosticSeverity::ERROR,
+                    "Invalid operator 'equals' in when expression of pipeline task 'build'; expected one of: in, notin",
+                    14,
+                    20
+                ),
+                (
+                    DiagnosticSeverity::ERROR,
+                    "'values' of a when expression of pipeline task 'build' must be an array",
+                    15,
+                    18
+                ),
+                (
+                    DiagnosticSeverity::ERROR,
+                    "When expression of pipeline task 'build' is missing 'operator'",
+                    16,
+                    10
+                ),
+                (
+                    DiagnosticSeverity::ERROR,
+                    "'values' of a when expression of pipeline task 'build' must not be empty",
+                    17,
+                    18
+                ),
+                (
+                    DiagnosticSeverity::WARNING,
+                    "'$(tasks.status)' is only available to finally tasks; pipeline task 'build' runs before it is known",
+                    16,
+                    17
+                ),
+                (
+                    DiagnosticSeverity::ERROR,
+                    "When expression of pipeline task 'build' cannot combine 'cel' with 'operator'",
+                    19,
+                    10
+                ),
+                (
+                    DiagnosticSeverity::WARNING,
+                    "Unknown field 'extra' in when expression of pipeline task 'build'",
+                    20,
+                    10
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_when_cel_expressions() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  params:
+    - name: branch
+    - name: env
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+      when:
+        - cel: "'$(params.branch)' == 'main' && ('$(params.env)' in ['prod', 'staging'])"
+        - cel: "'$(params.branch)' == 'main' &&"
+        - cel: "('$(params.branch)' == 'main'"
+        - cel: "'$(params.branch) == 'main'"
+        - cel: ""
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let validator = TektonValidator::new();
+        let found: Vec<_> = validator
+            .validate(&doc)
+            .into_iter()
+            .map(|d| (d.message, d.range.start.line))
+            .collect();
+        let prefix = "CEL expression of pipeline task 'build'";
+        assert_eq!(
+            found,
+            vec![
+                (format!("{} ends with the operator '&&'", prefix), 14),
+                (format!("{} has an unclosed '('", prefix), 15),
+                (format!("{} has an unterminated string", prefix), 16),
+                (format!("{} is empty", prefix), 17),
+            ]
+        );
+
+        // Releases before v0.53 have no CEL when expressions
+        validator.set_options(ValidatorOptions {
+            tekton_version: Some("v0.50.2".to_string()),
+            ..ValidatorOptions::default()
+        });
+        let gated = validator
+            .validate(&doc)
+            .into_iter()
+            .filter(|d| d.message.starts_with("CEL when expressions need"))
+            .count();
+        assert_eq!(gated, 5);
+
+        validator.set_options(ValidatorOptions {
+            tekton_version: Some("0.53".to_string()),
+            ..ValidatorOptions::default()
+        });
+        assert_eq!(validator.validate(&doc).len(), 4);
+    }
+
+    #[test]
+    fn test_pipeline_run_workspace_bindings() {
+        let pipeline = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  params:
+    - name: revision
+    - name: verbose
+      default: "false"
+  workspaces:
+    - name: src
+    - name: cache
+      optional: true
+  tasks: []
+"#;
+        let index = WorkspaceIndex::new();
+        let pipeline_uri = Url::parse("file:///repo/ci.yaml").unwrap();
+        index.index_document(&pipeline_uri, pipeline).unwrap();
+
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  generateName: ci-
+spec:
+  pipelineRef:
+    name: ci
+  params:
+    - name: revison
+      value: main
+  workspaces:
+    - name: source
+      emptyDir: {}
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::with_index(index).validate(&doc);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.severity.unwrap(), d.message.as_str(), d.range.start.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    DiagnosticSeverity::ERROR,
+                    "Pipeline 'ci' declares no workspace 'source'; declared workspaces: src, cache",
+                    12
+                ),
+                (
+                    DiagnosticSeverity::ERROR,
+                    "PipelineRun does not bind workspace 'src', which Pipeline 'ci' requires",
+                    7
+                ),
+                (
+                    DiagnosticSeverity::WARNING,
+                    "Pipeline 'ci' declares no param 'revison'; declared params: revision, verbose",
+                    9
+                ),
+                (
+                    DiagnosticSeverity::ERROR,
+                    "PipelineRun does not pass param 'revision', which Pipeline 'ci' requires",
+                    7
+                ),
+            ]
+        );
+        for diagnostic in &diagnostics {
+            let related = diagnostic.related_information.as_ref().unwrap();
+            assert_eq!(related[0].location.uri, pipeline_uri);
+            assert_eq!(related[0].message, "Pipeline 'ci' is defined here");
+            let data = DiagnosticData::of(diagnostic).unwrap();
+            assert_eq!(data.resource.as_deref(), Some("Pipeline/ci"));
+        }
+        let data = DiagnosticData::of(&diagnostics[0]).unwrap();
+        assert_eq!(data.code, "unknown-workspace");
+    }
+
+    #[test]
+    fn test_pipeline_task_params_against_indexed_task() {
+        let task = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: golang-build
+spec:
+  params:
+    - name: package
+    - name: platform
+    - name: flags
+      default: "-v"
+  steps:
+    - image: golang
+"#;
+        let index = WorkspaceIndex::new();
+        index
+            .index_document(&Url::parse("file:///repo/golang-build.yaml").unwrap(), task)
+            .unwrap();
+
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: golang-build
+      params:
+        - name: package
+          value: ./...
+        - name: flag
+          value: -x
+    - name: cross-build
+      taskRef:
+        name: golang-build
+      params:
+        - name: package
+          value: ./...
+      matrix:
+        params:
+          - name: platform
+            value: [linux, darwin]
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::with_index(index).validate(&doc);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.severity.unwrap(), d.message.as_str(), d.range.start.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    DiagnosticSeverity::WARNING,
+                    "Task 'golang-build' declares no param 'flag'; declared params: package, platform, flags",
+                    12
+                ),
+                (
+                    DiagnosticSeverity::ERROR,
+                    "Pipeline task 'build' does not pass param 'platform', which Task 'golang-build' requires",
+                    6
+                ),
+            ]
+        );
+        let data = DiagnosticData::of(&diagnostics[1]).unwrap();
+        assert_eq!(data.code, "missing-param");
+        assert_eq!(data.resource.as_deref(), Some("Task/golang-build"));
+    }
+
+    #[test]
+    fn test_kubernetes_name_format() {
+        let long = "a".repeat(64);
+        let yaml = format!(
+            r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: Build_Pipeline
+spec:
+  params:
+    - name: task-name
+  tasks:
+    - name: fetch_source
+      taskSpec:
+        steps:
+          - image: alpine
+    - name: build.image
+      taskSpec:
+        steps:
+          - image: alpine
+    - name: -test
+      taskSpec:
+        steps:
+          - image: alpine
+    - name: {}
+      taskSpec:
+        steps:
+          - image: alpine
+    - name: $(params.task-name)
+      taskSpec:
+        steps:
+          - image: alpine
+"#,
+            long
+        );
+        let doc = parse_yaml("test.yaml", &yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.message.clone(),
+                    d.range.start.line,
+                    d.range.start.character,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Name 'Build_Pipeline' must be lowercase".to_string(), 4, 8),
+                (
+                    "Pipeline task name 'fetch_source' cannot contain '_'; use '-' instead"
+                        .to_string(),
+                    9,
+                    12
+                ),
+                (
+                    "Pipeline task name 'build.image' cannot contain '.'; use only lowercase letters, digits and '-'"
+                        .to_string(),
+                    13,
+                    12
+                ),
+                (
+                    "Pipeline task name '-test' must start and end with a lowercase letter or digit"
+                        .to_string(),
+                    17,
+                    12
+                ),
+                (
+                    format!(
+                        "Pipeline task name '{}' is 64 characters long; the maximum is 63",
+                        long
+                    ),
+                    21,
+                    12
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pac_template_variables() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  name: pull-request
+spec:
+  pipelineRef:
+    name: ci
+  params:
+    - name: url
+      value: "{{ repo_url }}"
+    - name: revision
+      value: "{{ revison }}"
+    - name: author
+      value: "{{ body.pull_request.user.login }} via {{}}"
+"#;
+        let doc = parse_yaml("file:///repo/.tekton/pull-request.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.message.as_str(),
+                    d.range.start.line,
+                    d.range.start.character,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "'revison' is not a standard Pipelines-as-Code variable; it must be a param of the Repository",
+                    12,
+                    17
+                ),
+                ("Empty Pipelines-as-Code template", 14, 53),
+            ]
+        );
+
+        // Outside .tekton/ templates are plain text
+        let doc = parse_yaml("file:///repo/tekton/pull-request.yaml", yaml).unwrap();
+        assert!(TektonValidator::new().validate(&doc).is_empty());
+    }
+
+    #[test]
+    fn test_trigger_template_params() {
+        let yaml = r#"apiVersion: triggers.tekton.dev/v1beta1
+kind: TriggerTemplate
+metadata:
+  name: ci
+spec:
+  params:
+    - name: revision
+  resourcetemplates:
+    - apiVersion: tekton.dev/v1
+      kind: PipelineRun
+      metadata:
+        generateName: ci-
+      spec:
+        pipelineRef:
+          name: ci
+        params:
+          - name: revision
+            value: $(tt.params.revision)
+          - name: url
+            value: $(tt.params.repo-url)
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "Unknown TriggerTemplate param 'repo-url'; declared params: revision"
+        );
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(diagnostics[0].range.start.line, 19);
+        assert_eq!(diagnostics[0].range.start.character, 31);
+
+        let doc = parse_yaml("test.yaml", "apiVersion: triggers.tekton.dev/v1beta1\nkind: TriggerTemplate\nmetadata:\n  name: empty\nspec:\n  resourcetemplates: []\n").unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "TriggerTemplate must define at least one resource in 'resourcetemplates'"
+        );
+        assert_eq!(diagnostics[0].range.start.line, 5);
+    }
+
+    #[test]
+    fn test_trigger_binding_params() {
+        let yaml = r#"apiVersion: triggers.tekton.dev/v1beta1
+kind: TriggerBinding
+metadata:
+  name: push
+spec:
+  params:
+    - name: revision
+      value: $(body.head_commit.id)
+    - name: event
+      value: $(header.X-GitHub-Event)
+    - name: url
+      value: $(params.url)
+    - name: branch
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "TriggerBinding param 'url' must take its value from body, header, extensions, not 'params'",
+                "TriggerBinding params need a 'name' and a 'value'",
+            ]
+        );
+        assert_eq!(diagnostics[0].range.start.line, 11);
+        assert_eq!(diagnostics[1].range.start.line, 12);
+    }
+
+    #[test]
+    fn test_event_listener_triggers() {
+        let yaml = r#"apiVersion: triggers.tekton.dev/v1beta1
+kind: EventListener
+metadata:
+  name: listener
+spec:
+  triggers:
+    - name: push
+      interceptors:
+        - ref:
+            name: github
+          params:
+            - name: eventTypes
+              value: ["push"]
+        - params:
+            - name: filter
+      bindings:
+        - ref: push
+        - kind: Binding
+          ref: push
+        - name: only-name
+      template:
+        ref: ci
+    - name: shared
+      triggerRef: shared
+      template:
+        ref: ci
+    - name: nothing
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    17,
+                    "Trigger binding kind must be one of: TriggerBinding, ClusterTriggerBinding"
+                ),
+                (
+                    19,
+                    "Trigger bindings must define 'ref', or a 'name' and a 'value'"
+                ),
+                (13, "Interceptor must define 'ref'"),
+                (14, "Interceptor params need a 'name' and a 'value'"),
+                (24, "'template' cannot be combined with 'triggerRef'"),
+                (26, "Trigger must define 'triggerRef' or 'template'"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_step_action_spec() {
+        let yaml = r#"apiVersion: tekton.dev/v1beta1
+kind: StepAction
+metadata:
+  name: git-clone
+spec:
+  params:
+    - name: url
+  script: git clone $(params.url) $(params.revision)
+  command: ["git"]
+  steps: []
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (9, "Unknown field 'steps' in StepAction spec"),
+                (4, "StepAction must define an 'image'"),
+                (8, "StepAction sets both 'script' and 'command'; 'command' cannot be used together with 'script'"),
+                (7, "Unknown param 'revision'; it is not declared in spec.params"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_param_type_consistency() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  params:
+    - name: flags
+      type: array
+      default: "-v"
+    - name: target
+      default: [linux, darwin]
+    - name: config
+      type: object
+    - name: platforms
+      type: array
+      default: [linux]
+    - name: level
+      type: number
+  steps:
+    - name: build
+      image: golang
+      args: ["$(params.flags[*])", "$(params.target[*])", "$(params.platforms[*])"]
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = without_unused(TektonValidator::new().validate(&doc));
+
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (8, "Default of array param 'flags' must be an array, not a string"),
+                (10, "Default of string param 'target' must be a string, not an array"),
+                (12, "Object param 'config' must declare its 'properties'"),
+                (17, "Invalid type 'number' for param 'level'; expected one of: string, array, object"),
+                (21, "Param 'target' is a string; '[*]' only applies to array and object params"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_data() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  labels: {}
+spec:
+  tasks:
+    - name: build
+      retry: 2
+      taskSpec:
+        steps:
+          - name: build
+            image: golang
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+
+        let data: Vec<_> = diagnostics
+            .iter()
+            .map(|d| DiagnosticData::of(d).unwrap())
+            .collect();
+        assert_eq!(
+            data,
+            vec![
+                DiagnosticData {
+                    path: Some("metadata".to_string()),
+                    fix: Some(Fix::AddField {
+                        field: "name".to_string()
+                    }),
+                    ..DiagnosticData::new("missing-name")
+                },
+                DiagnosticData {
+                    path: Some("spec.tasks[0].retry".to_string()),
+                    fix: Some(Fix::RemoveField {
+                        field: "retry".to_string()
+                    }),
+                    ..DiagnosticData::new("unknown-field")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unresolved_task_refs() {
+        let task = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: git-clone
+spec:
+  steps:
+    - image: alpine
+"#;
+        let index = WorkspaceIndex::new();
+        index
+            .index_document(&Url::parse("file:///repo/git-clone.yaml").unwrap(), task)
+            .unwrap();
+
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: clone
+      taskRef:
+        name: git-clone
+    - name: build
+      taskRef:
+        name: golang-build
+    - name: remote
+      taskRef:
+        resolver: hub
+        params:
+          - name: name
+            value: golang-test
+    - name: approve
+      taskRef:
+        apiVersion: openshift-pipelines.org/v1alpha1
+        kind: ApprovalTask
+        name: approve
+  finally:
+    - name: notify
+      taskRef:
+        kind: ClusterTask
+        name: git-clone
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::with_index(index).validate(&doc);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.severity.unwrap(), d.message.as_str(), d.range.start.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    DiagnosticSeverity::WARNING,
+                    "Kind 'ClusterTask' is deprecated; use a Task fetched with the cluster resolver instead",
+                    26
+                ),
+                (
+                    DiagnosticSeverity::WARNING,
+                    "Task 'golang-build' of pipeline task 'build' is not defined in the workspace",
+                    11
+                ),
+                (
+                    DiagnosticSeverity::WARNING,
+                    "ClusterTask 'git-clone' of pipeline task 'notify' is not defined in the workspace",
+                    27
+                ),
+            ]
+        );
+
+        // Without an index, references are not checked
+        let diagnostics = TektonValidator::new().validate(&doc);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.starts_with("Kind 'ClusterTask'"));
+    }
+    #[test]
+    fn test_rule_levels() {
+        let yaml = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - taskRef:
+        name: build
+      retry: 2
+"#;
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let validator = TektonValidator::new();
+
+        // Diagnostics carry their rule ID as code
+        let diagnostics = validator.validate(&doc);
+        let codes: Vec<_> = diagnostics.iter().map(|d| d.code.clone()).collect();
+        assert_eq!(
+            codes,
+            vec![
+                Some(NumberOrString::String("TPL003".to_string())),
+                Some(NumberOrString::String("TPL001".to_string())),
+            ],
+            "Got: {:?}",
+            diagnostics
+        );
+
+        validator.set_options(ValidatorOptions {
+            rules: serde_json::from_value(serde_json::json!({
+                "TPL001": "off",
+                "missing-name": "information"
+            }))
+            .unwrap(),
+            ..ValidatorOptions::default()
+        });
+        let diagnostics = validator.validate(&doc);
+        assert_eq!(diagnostics.len(), 1, "Got: {:?}", diagnostics);
+        assert_eq!(
+            diagnostics[0].severity,
+            Some(DiagnosticSeverity::INFORMATION)
+        );
+    }
+
+    #[test]
+    fn test_unused_declarations() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  params:
+    - name: revision
+    - name: verbose
+  workspaces:
+    - name: source
+    - name: cache
+    - name: scratch
+  tasks:
+    - name: build
+      params:
+        - name: revision
+          value: $(params.revision)
+      workspaces:
+        - name: source
+        - name: cache
+          workspace: cache
+      taskSpec:
+        params:
+          - name: revision
+          - name: unused
+        workspaces:
+          - name: source
+          - name: cache
+            mountPath: /cache
+          - name: output
+        results:
+          - name: digest
+          - name: report
+          - name: status
+        steps:
+          - image: alpine
+            script: |
+              ls /cache
+              cd $(workspaces.source.path) && git checkout $(params.revision)
+              echo ok > /tekton/results/status
+              echo sha > $(results.digest.path)
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        let unused: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.tags == Some(vec![DiagnosticTag::UNNECESSARY]))
+            .map(|d| (d.range.start.line, d.severity, d.message.as_str()))
+            .collect();
+        let hint = Some(DiagnosticSeverity::HINT);
+        assert_eq!(
+            unused,
+            vec![
+                (7, hint, "Param 'verbose' is declared but never used"),
+                (11, hint, "Workspace 'scratch' is declared but never used"),
+                (24, hint, "Param 'unused' is declared but never used"),
+                (29, hint, "Workspace 'output' is declared but never used"),
+                (32, hint, "Result 'report' is declared but never written"),
+            ],
+            "Got: {:?}",
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn test_security_lint() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: deploy
+spec:
+  params:
+    - name: image
+  steps:
+    - name: fetch
+      image: alpine
+      script: |
+        set -e
+        curl -sSL https://example.com/install.sh | sudo bash -s
+        curl -o tool.sh https://example.com/tool.sh || sh ./fallback.sh
+    - name: build
+      image: docker.io/library/docker:latest
+      securityContext:
+        privileged: true
+        runAsUser: 0
+    - name: pinned
+      image: registry.local:5000/tools/kubectl:1.30@sha256:abc
+      securityContext:
+        runAsUser: 1000
+    - name: param
+      image: $(params.image)
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let validator = TektonValidator::new();
+        assert!(validator.validate(&doc).is_empty(), "Off by default");
+
+        validator.set_options(ValidatorOptions {
+            security_lint: true,
+            ..ValidatorOptions::default()
+        });
+        let diagnostics = validator.validate(&doc);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| {
+                let code = DiagnosticData::of(d).unwrap().code;
+                (code, d.range.start.line, d.range.start.character)
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("unpinned-image".to_string(), 9, 13),
+                ("curl-pipe-shell".to_string(), 12, 8),
+                ("unpinned-image".to_string(), 15, 13),
+                ("privileged-step".to_string(), 17, 8),
+                ("root-user".to_string(), 18, 8),
+            ],
+            "Got: {:?}",
+            diagnostics
+        );
+        assert!(diagnostics[1]
+            .message
+            .contains("Step 'fetch' pipes a download"));
+        assert_eq!(diagnostics[1].range.end.character, 63);
+    }
+
+    #[test]
+    fn test_finally_references() {
+        let yaml = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+      params:
+        - name: previous
+          value: $(tasks.status)
+    - name: publish
+      runAfter: [build]
+      taskRef:
+        name: publish
+      params:
+        - name: sent
+          value: $(finally.notify.results.sent)
+  finally:
+    - name: notify
+      taskRef:
+        name: notify
+      params:
+        - name: status
+          value: $(tasks.build.status)
+  results:
+    - name: sent
+      value: $(tasks.notify.results.sent)
+    - name: digest
+      value: $(finally.build.results.digest)
+    - name: notified
+      value: $(finally.notify.results.sent)
+"#;
+
+        let doc = parse_yaml("test.yaml", yaml).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (DiagnosticData::of(d).unwrap().code, d.range.start.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("finally-reference".to_string(), 18),
+                ("finally-only-variable".to_string(), 11),
+                ("finally-reference".to_string(), 28),
+                ("finally-reference".to_string(), 30),
+            ],
+            "Got: {:?}",
+            diagnostics
+        );
+        assert_eq!(
+            diagnostics[2].message,
+            "'notify' is a finally task; reference its result as '$(finally.notify.results.sent)'"
+        );
+    }
+
+    #[test]
+    fn test_embedded_specs() {
+        let pipeline_run = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  generateName: release-
+spec:
+  pipelineSpec:
+    tasks:
+      - name: build
+        taskSpec:
+          steps:
+            - name: compile
+              image: golang:1.22
+              imagePullPolicy: Sometimes
+              script: make
+              command: [sh]
+      - name: build
+        taskRef:
+          name: build
+"#;
+
+        let doc = parse_yaml("test.yaml", pipeline_run).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (DiagnosticData::of(d).unwrap().code, d.range.start.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("duplicate-name".to_string(), 15),
+                ("script-with-command".to_string(), 14),
+                ("invalid-image-pull-policy".to_string(), 12),
+            ],
+            "Got: {:?}",
+            diagnostics
+        );
+
+        let task_run = r#"apiVersion: tekton.dev/v1
+kind: TaskRun
+metadata:
+  generateName: build-
+spec:
+  taskSpec:
+    params:
+      - name: flags
+        type: array
+        default: --verbose
+    steps:
+      - image: alpine
+        script: echo $(params.flags[*]) $(params.missing)
+"#;
+
+        let doc = parse_yaml("test.yaml", task_run).unwrap();
+        let diagnostics = TektonValidator::new().validate(&doc);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (DiagnosticData::of(d).unwrap().code, d.range.start.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("param-default-type".to_string(), 9),
+                ("unknown-param".to_string(), 12),
+            ],
+            "Got: {:?}",
+            diagnostics
+        );
+    }
 }
@@ -0,0 +1,283 @@
+// Tekton Triggers rules
+//
+// Checks TriggerTemplates, TriggerBindings and EventListeners the way the
+// Triggers webhook does: templates need resources and may only use the
+// params they declare through `$(tt.params.<name>)`, bindings map params to
+// the event, and every trigger names its template, bindings and interceptors.
+
+use super::params::collect_scalars;
+use super::pipeline::first_key_range;
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::parser::expressions::expressions_in;
+use crate::parser::{Node, PositionEncoding, YamlDocument};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Roots a TriggerBinding param value can take from the event
+const BINDING_ROOTS: [&str; 3] = ["body", "header", "extensions"];
+
+/// Kinds a trigger binding `ref` can point to
+const BINDING_KINDS: [&str; 2] = ["TriggerBinding", "ClusterTriggerBinding"];
+
+/// Legacy (v1alpha1) interceptor fields, used in place of `ref`
+const LEGACY_INTERCEPTORS: [&str; 5] = ["webhook", "github", "gitlab", "bitbucket", "cel"];
+
+/// Check a Triggers resource; other kinds are left alone.
+pub(super) fn check_triggers(
+    doc: &YamlDocument,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(kind) = doc.kind.as_deref() else {
+        return;
+    };
+    let spec = doc.root.get("spec");
+    match kind {
+        "TriggerTemplate" => check_trigger_template(doc, spec, encoding, diagnostics),
+        "TriggerBinding" | "ClusterTriggerBinding" => {
+            if let Some(params) = spec.and_then(|s| s.get("params")) {
+                check_binding_params(params, kind, encoding, diagnostics);
+            }
+        }
+        "EventListener" => {
+            for trigger in spec
+                .and_then(|s| s.get("triggers"))
+                .into_iter()
+                .flat_map(Node::items)
+            {
+                check_trigger(trigger, diagnostics);
+            }
+        }
+        "Trigger" => {
+            if let Some(spec) = spec {
+                check_trigger(spec, diagnostics);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A TriggerTemplate needs resource templates, and they can only use its params.
+fn check_trigger_template(
+    doc: &YamlDocument,
+    spec: Option<&Node>,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let resources = spec.and_then(|s| s.get("resourcetemplates"));
+    if resources.is_none_or(|r| r.items().is_empty()) {
+        let range = resources
+            .or(spec)
+            .or_else(|| doc.root.get("kind"))
+            .map_or(doc.root.range, Node::key_or_range);
+        diagnostics.push(make_diagnostic(
+            Rule::MissingResourceTemplates,
+            range,
+            DiagnosticSeverity::ERROR,
+            "TriggerTemplate must define at least one resource in 'resourcetemplates'".to_string(),
+        ));
+    }
+
+    let mut declared = Vec::new();
+    for param in spec
+        .and_then(|s| s.get("params"))
+        .into_iter()
+        .flat_map(Node::items)
+    {
+        match param.get("name").and_then(Node::as_scalar) {
+            Some(name) => declared.push(name),
+            None => diagnostics.push(make_diagnostic(
+                Rule::MissingName,
+                first_key_range(param),
+                DiagnosticSeverity::ERROR,
+                "TriggerTemplate params need a 'name'".to_string(),
+            )),
+        }
+    }
+
+    for resource in resources.into_iter().flat_map(Node::items) {
+        for field in ["apiVersion", "kind"] {
+            if resource.get(field).is_none() {
+                diagnostics.push(make_diagnostic(
+                    Rule::MissingResourceField,
+                    first_key_range(resource),
+                    DiagnosticSeverity::ERROR,
+                    format!("Resource templates need a '{}'", field),
+                ));
+            }
+        }
+
+        let mut scalars = Vec::new();
+        collect_scalars(resource, &mut scalars);
+        for expression in scalars.iter().flat_map(|s| expressions_in(s, encoding)) {
+            let ["tt", "params", name, ..] = expression.path()[..] else {
+                continue;
+            };
+            if !declared.contains(&name) {
+                diagnostics.push(make_diagnostic(
+                    Rule::UnknownTemplateParam,
+                    expression.segments[2].range,
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "Unknown TriggerTemplate param '{}'; declared params: {}",
+                        name,
+                        declared.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Binding params need a name and a value taken from the event.
+fn check_binding_params(
+    params: &Node,
+    kind: &str,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for param in params.items() {
+        let name = param.get("name").and_then(Node::as_scalar);
+        let (Some(name), Some(value)) = (name, param.get("value")) else {
+            diagnostics.push(make_diagnostic(
+                Rule::InvalidBindingParam,
+                first_key_range(param),
+                DiagnosticSeverity::ERROR,
+                format!("{} params need a 'name' and a 'value'", kind),
+            ));
+            continue;
+        };
+        for expression in expressions_in(value, encoding) {
+            let Some(root) = expression.segments.first() else {
+                continue;
+            };
+            if !BINDING_ROOTS.contains(&root.name.as_str()) {
+                diagnostics.push(make_diagnostic(
+                    Rule::InvalidBindingValue,
+                    root.range,
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "{} param '{}' must take its value from {}, not '{}'",
+                        kind,
+                        name,
+                        BINDING_ROOTS.join(", "),
+                        root.name
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// A trigger runs a template with bindings and interceptors, or refers to a
+/// Trigger that does.
+fn check_trigger(trigger: &Node, diagnostics: &mut Vec<Diagnostic>) {
+    if let Some(trigger_ref) = trigger.get("triggerRef") {
+        for field in ["template", "bindings", "interceptors"] {
+            if let Some(node) = trigger.get(field) {
+                diagnostics.push(make_diagnostic(
+                    Rule::ConflictingFields,
+                    node.key_or_range(),
+                    DiagnosticSeverity::ERROR,
+                    format!("'{}' cannot be combined with 'triggerRef'", field),
+                ));
+            }
+        }
+        if trigger_ref.as_scalar().is_none() {
+            diagnostics.push(make_diagnostic(
+                Rule::InvalidTriggerRef,
+                trigger_ref.value_range,
+                DiagnosticSeverity::ERROR,
+                "'triggerRef' must be the name of a Trigger".to_string(),
+            ));
+        }
+        return;
+    }
+
+    match trigger.get("template") {
+        Some(template)
+            if ["ref", "name", "spec"]
+                .iter()
+                .any(|f| template.get(f).is_some()) => {}
+        Some(template) => diagnostics.push(make_diagnostic(
+            Rule::MissingTemplate,
+            template.key_or_range(),
+            DiagnosticSeverity::ERROR,
+            "Trigger template must define 'ref' or 'spec'".to_string(),
+        )),
+        None => diagnostics.push(make_diagnostic(
+            Rule::MissingTemplate,
+            first_key_range(trigger),
+            DiagnosticSeverity::ERROR,
+            "Trigger must define 'triggerRef' or 'template'".to_string(),
+        )),
+    }
+
+    for binding in trigger.get("bindings").into_iter().flat_map(Node::items) {
+        let inline = binding.get("name").is_some() && binding.get("value").is_some();
+        if binding.get("ref").is_none() && !inline {
+            diagnostics.push(make_diagnostic(
+                Rule::InvalidBinding,
+                first_key_range(binding),
+                DiagnosticSeverity::ERROR,
+                "Trigger bindings must define 'ref', or a 'name' and a 'value'".to_string(),
+            ));
+        }
+        if let Some(kind) = binding.get("kind") {
+            if !kind.as_scalar().is_some_and(|k| BINDING_KINDS.contains(&k)) {
+                diagnostics.push(make_diagnostic(
+                    Rule::InvalidBindingKind,
+                    kind.value_range,
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "Trigger binding kind must be one of: {}",
+                        BINDING_KINDS.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+
+    for interceptor in trigger
+        .get("interceptors")
+        .into_iter()
+        .flat_map(Node::items)
+    {
+        check_interceptor(interceptor, diagnostics);
+    }
+}
+
+/// Interceptors name the interceptor to run and pass it named params.
+fn check_interceptor(interceptor: &Node, diagnostics: &mut Vec<Diagnostic>) {
+    match interceptor.get("ref") {
+        Some(interceptor_ref) if interceptor_ref.get("name").is_none() => {
+            diagnostics.push(make_diagnostic(
+                Rule::MissingInterceptorName,
+                interceptor_ref.key_or_range(),
+                DiagnosticSeverity::ERROR,
+                "Interceptor 'ref' must have a 'name'".to_string(),
+            ));
+        }
+        Some(_) => {}
+        None if LEGACY_INTERCEPTORS
+            .iter()
+            .any(|f| interceptor.get(f).is_some()) => {}
+        None => diagnostics.push(make_diagnostic(
+            Rule::MissingInterceptorRef,
+            first_key_range(interceptor),
+            DiagnosticSeverity::ERROR,
+            "Interceptor must define 'ref'".to_string(),
+        )),
+    }
+
+    for param in interceptor.get("params").into_iter().flat_map(Node::items) {
+        if param.get("name").is_none() || param.get("value").is_none() {
+            diagnostics.push(make_diagnostic(
+                Rule::InvalidInterceptorParam,
+                first_key_range(param),
+                DiagnosticSeverity::ERROR,
+                "Interceptor params need a 'name' and a 'value'".to_string(),
+            ));
+        }
+    }
+}
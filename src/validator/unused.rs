@@ -0,0 +1,172 @@
+// Unused declarations
+//
+// A param, workspace or result that the spec declaring it never references
+// is dead weight: callers still have to pass the param or bind the workspace.
+// These are reported as hints tagged `Unnecessary`, which editors render
+// faded.
+//
+// A Task uses a param through `$(params.<name>)`, a workspace through
+// `$(workspaces.<name>...)`, a step or sidecar `workspaces` entry or its mount
+// path written out in the spec, and writes a result through
+// `$(results.<name>.path)`, its `/tekton/results/<name>` path or a `value`.
+// A Pipeline uses a workspace by binding it to a pipeline task. Pipeline
+// results are read by runs rather than referenced, so they are not checked.
+
+use super::params::collect_scalars;
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{Pipeline, Spanned, Task};
+use crate::parser::expressions::{expressions_in, Expression, Reference};
+use crate::parser::{Node, NodeValue, PositionEncoding};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag};
+
+/// Directory where steps write Task results
+const RESULTS_DIR: &str = "/tekton/results/";
+
+/// Flag the params, workspaces and results a Task never references.
+pub(super) fn check_unused_task_declarations(
+    task: &Task,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(spec) = task.spec else {
+        return;
+    };
+    // The declarations' own `mountPath`s do not count as uses
+    let mut scalars = Vec::new();
+    for (_, child) in spec.entries().filter(|(key, _)| *key != "workspaces") {
+        collect_scalars(child, &mut scalars);
+    }
+    let expressions = expressions(&scalars, encoding);
+    let mentions = |text: &str| {
+        scalars
+            .iter()
+            .filter_map(|s| s.as_scalar())
+            .any(|s| s.contains(text))
+    };
+
+    for name in task.params.iter().filter_map(|p| p.name) {
+        if !uses_param(&expressions, name.value) {
+            diagnostics.push(unused(Rule::UnusedParam, "Param", name, "used"));
+        }
+    }
+
+    let sidecars = match spec.get("sidecars").map(|s| &s.value) {
+        Some(NodeValue::Sequence(sidecars)) => sidecars.as_slice(),
+        _ => &[],
+    };
+    let bound_in_steps: Vec<&str> = task
+        .steps
+        .iter()
+        .map(|step| step.node)
+        .chain(sidecars)
+        .flat_map(container_workspaces)
+        .collect();
+    for workspace in &task.workspaces {
+        let Some(name) = workspace.name else {
+            continue;
+        };
+        let used = uses_workspace(&expressions, name.value)
+            || bound_in_steps.contains(&name.value)
+            || workspace
+                .effective_mount_path()
+                .is_some_and(|path| mentions(&path));
+        if !used {
+            diagnostics.push(unused(Rule::UnusedWorkspace, "Workspace", name, "used"));
+        }
+    }
+
+    for result in &task.results {
+        let Some(name) = result.name else {
+            continue;
+        };
+        let written = result.value.is_some()
+            || expressions.iter().any(|expr| {
+                matches!(expr.reference(), Reference::Result { name: n } if n.name == name.value)
+            })
+            || mentions(&format!("{}{}", RESULTS_DIR, name.value));
+        if !written {
+            diagnostics.push(unused(Rule::UnusedResult, "Result", name, "written"));
+        }
+    }
+}
+
+/// Flag the params and workspaces a Pipeline never references.
+///
+/// References from embedded `taskSpec`s count, since Pipeline params and
+/// workspaces propagate to them.
+pub(super) fn check_unused_pipeline_declarations(
+    pipeline: &Pipeline,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(spec) = pipeline.spec else {
+        return;
+    };
+    let mut scalars = Vec::new();
+    collect_scalars(spec, &mut scalars);
+    let expressions = expressions(&scalars, encoding);
+
+    for name in pipeline.params.iter().filter_map(|p| p.name) {
+        if !uses_param(&expressions, name.value) {
+            diagnostics.push(unused(Rule::UnusedParam, "Param", name, "used"));
+        }
+    }
+
+    let bound: Vec<&str> = pipeline
+        .all_tasks()
+        .flat_map(|task| &task.workspaces)
+        .filter_map(|binding| binding.workspace.or(binding.name))
+        .map(|workspace| workspace.value)
+        .collect();
+    for name in pipeline.workspaces.iter().filter_map(|w| w.name) {
+        if !bound.contains(&name.value) && !uses_workspace(&expressions, name.value) {
+            diagnostics.push(unused(Rule::UnusedWorkspace, "Workspace", name, "used"));
+        }
+    }
+}
+
+/// Every expression in the scalars of a spec.
+fn expressions(scalars: &[&Node], encoding: PositionEncoding) -> Vec<Expression> {
+    scalars
+        .iter()
+        .flat_map(|scalar| expressions_in(scalar, encoding))
+        .collect()
+}
+
+/// Whether an expression references the param `name`.
+fn uses_param(expressions: &[Expression], name: &str) -> bool {
+    expressions
+        .iter()
+        .any(|expr| matches!(expr.reference(), Reference::Param { name: n } if n.name == name))
+}
+
+/// Whether an expression references the workspace `name`.
+fn uses_workspace(expressions: &[Expression], name: &str) -> bool {
+    expressions.iter().any(
+        |expr| matches!(expr.reference(), Reference::Workspace { name: n, .. } if n.name == name),
+    )
+}
+
+/// Names in the `workspaces` of a step or sidecar.
+fn container_workspaces(container: &Node) -> Vec<&str> {
+    match container.get("workspaces").map(|w| &w.value) {
+        Some(NodeValue::Sequence(workspaces)) => workspaces
+            .iter()
+            .filter_map(|workspace| workspace.get("name")?.as_scalar())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A hint for an unused declaration, faded by editors.
+fn unused(rule: Rule, what: &str, name: Spanned<&str>, verb: &str) -> Diagnostic {
+    let mut diagnostic = make_diagnostic(
+        rule,
+        name.range,
+        DiagnosticSeverity::HINT,
+        format!("{} '{}' is declared but never {}", what, name.value, verb),
+    );
+    diagnostic.tags = Some(vec![DiagnosticTag::UNNECESSARY]);
+    diagnostic
+}
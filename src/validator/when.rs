@@ -0,0 +1,308 @@
+// When expressions
+//
+// A pipeline task runs only if all its `when` entries hold. Each entry is
+// either `input` / `operator` / `values` with `in` or `notin` and a
+// non-empty array of values, or a single `cel` expression. CEL expressions
+// came with Tekton v0.53; they get a syntax check (balanced delimiters,
+// terminated strings, no dangling operator) and are flagged when the
+// `tektonVersion` option targets an older release. The execution status of
+// tasks (`$(tasks.<task>.status)`, `$(tasks.status)`) is only known once
+// they are done, so only finally tasks can check it.
+
+use super::data::{with_fix, Fix};
+use super::params::collect_scalars;
+use super::pipeline::first_key_range;
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{Pipeline, PipelineTask};
+use crate::parser::expressions::expressions_in;
+use crate::parser::{Node, NodeValue, PositionEncoding};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Operators of a when expression
+const WHEN_OPERATORS: [&str; 2] = ["in", "notin"];
+
+/// Fields of an `input` / `operator` / `values` when expression
+const WHEN_FIELDS: [&str; 3] = ["input", "operator", "values"];
+
+/// First Tekton Pipelines release with CEL when expressions
+const CEL_MIN_VERSION: (u32, u32) = (0, 53);
+
+/// Binary operators a CEL expression cannot end with
+const CEL_OPERATORS: [&str; 13] = [
+    "&&", "||", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%",
+];
+
+/// Check the `when` entries of every pipeline task.
+///
+/// `tekton_version` is the Tekton Pipelines release the Pipeline targets, if known.
+pub(super) fn check_when_expressions(
+    pipeline: &Pipeline,
+    tekton_version: Option<&str>,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for task in &pipeline.tasks {
+        check_task_when(task, false, tekton_version, encoding, diagnostics);
+    }
+    for task in &pipeline.finally {
+        check_task_when(task, true, tekton_version, encoding, diagnostics);
+    }
+}
+
+fn check_task_when(
+    task: &PipelineTask,
+    is_finally: bool,
+    tekton_version: Option<&str>,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(when) = task.node.get("when") else {
+        return;
+    };
+    let task_name = task.display_name();
+    let NodeValue::Sequence(entries) = &when.value else {
+        diagnostics.push(make_diagnostic(
+            Rule::InvalidWhen,
+            when.value_range,
+            DiagnosticSeverity::ERROR,
+            format!("'when' of pipeline task '{}' must be an array", task_name),
+        ));
+        return;
+    };
+
+    for entry in entries {
+        if !matches!(entry.value, NodeValue::Mapping(_)) {
+            diagnostics.push(make_diagnostic(
+                Rule::InvalidWhen,
+                entry.range,
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "When expression of pipeline task '{}' must be a mapping",
+                    task_name
+                ),
+            ));
+            continue;
+        }
+
+        for (key, child) in entry.entries() {
+            if key == "cel" {
+                continue;
+            }
+            if !WHEN_FIELDS.contains(&key) {
+                diagnostics.push(with_fix(
+                    make_diagnostic(
+                        Rule::UnknownField,
+                        child.key_or_range(),
+                        DiagnosticSeverity::WARNING,
+                        format!(
+                            "Unknown field '{}' in when expression of pipeline task '{}'",
+                            key, task_name
+                        ),
+                    ),
+                    Fix::RemoveField {
+                        field: key.to_string(),
+                    },
+                ));
+            } else if entry.get("cel").is_some() {
+                diagnostics.push(make_diagnostic(
+                    Rule::ConflictingFields,
+                    child.key_or_range(),
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "When expression of pipeline task '{}' cannot combine 'cel' with '{}'",
+                        task_name, key
+                    ),
+                ));
+            }
+        }
+        match entry.get("cel") {
+            Some(cel) => check_cel(cel, task_name, tekton_version, diagnostics),
+            None => check_fields(entry, task_name, diagnostics),
+        }
+
+        if !is_finally {
+            check_status_references(entry, task_name, encoding, diagnostics);
+        }
+    }
+}
+
+/// Check the `input`, `operator` and `values` of a when expression.
+fn check_fields(entry: &Node, task_name: &str, diagnostics: &mut Vec<Diagnostic>) {
+    for field in WHEN_FIELDS
+        .iter()
+        .filter(|field| entry.get(field).is_none())
+    {
+        diagnostics.push(make_diagnostic(
+            Rule::MissingField,
+            first_key_range(entry),
+            DiagnosticSeverity::ERROR,
+            format!(
+                "When expression of pipeline task '{}' is missing '{}'",
+                task_name, field
+            ),
+        ));
+    }
+
+    if let Some(operator) = entry.get("operator") {
+        let valid = operator
+            .as_scalar()
+            .is_some_and(|op| WHEN_OPERATORS.contains(&op));
+        if !valid {
+            diagnostics.push(make_diagnostic(
+                Rule::InvalidOperator,
+                operator.value_range,
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "Invalid operator '{}' in when expression of pipeline task '{}'; expected one of: {}",
+                    operator.as_scalar().unwrap_or_default(),
+                    task_name,
+                    WHEN_OPERATORS.join(", ")
+                ),
+            ));
+        }
+    }
+
+    if let Some(values) = entry.get("values") {
+        let message = match &values.value {
+            NodeValue::Sequence(items) if items.is_empty() => "must not be empty",
+            NodeValue::Sequence(_) => return,
+            _ => "must be an array",
+        };
+        diagnostics.push(make_diagnostic(
+            Rule::InvalidValues,
+            values.value_range,
+            DiagnosticSeverity::ERROR,
+            format!(
+                "'values' of a when expression of pipeline task '{}' {}",
+                task_name, message
+            ),
+        ));
+    }
+}
+
+/// Check the syntax of a `cel` expression, and that the targeted release
+/// supports it.
+fn check_cel(
+    cel: &Node,
+    task_name: &str,
+    tekton_version: Option<&str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let too_old = |version: &&str| parse_version(version).is_some_and(|v| v < CEL_MIN_VERSION);
+    if let Some(version) = tekton_version.filter(too_old) {
+        diagnostics.push(make_diagnostic(
+            Rule::UnsupportedFeature,
+            cel.key_or_range(),
+            DiagnosticSeverity::ERROR,
+            format!(
+                "CEL when expressions need Tekton Pipelines v{}.{} or later; the 'tektonVersion' option targets {}",
+                CEL_MIN_VERSION.0, CEL_MIN_VERSION.1, version
+            ),
+        ));
+    }
+
+    let error = match cel.as_scalar() {
+        Some(expression) => cel_syntax_error(expression),
+        None => Some("must be a string".to_string()),
+    };
+    if let Some(error) = error {
+        diagnostics.push(make_diagnostic(
+            Rule::InvalidCel,
+            cel.value_range,
+            DiagnosticSeverity::ERROR,
+            format!("CEL expression of pipeline task '{}' {}", task_name, error),
+        ));
+    }
+}
+
+/// The first syntax error of a CEL expression, if it has one.
+///
+/// Only catches what a quick scan can: the expression is not parsed.
+fn cel_syntax_error(expression: &str) -> Option<String> {
+    let expression = expression.trim();
+    if expression.is_empty() {
+        return Some("is empty".to_string());
+    }
+
+    let mut open: Vec<char> = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut chars = expression.chars();
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                _ if c == q => quote = None,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' => quote = Some(c),
+            '(' | '[' | '{' => open.push(c),
+            ')' | ']' | '}' => {
+                let expected = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                if open.pop() != Some(expected) {
+                    return Some(format!("has an unmatched '{}'", c));
+                }
+            }
+            _ => {}
+        }
+    }
+    if quote.is_some() {
+        return Some("has an unterminated string".to_string());
+    }
+    if let Some(c) = open.pop() {
+        return Some(format!("has an unclosed '{}'", c));
+    }
+    CEL_OPERATORS
+        .iter()
+        .find(|op| expression.ends_with(*op))
+        .map(|op| format!("ends with the operator '{}'", op))
+}
+
+/// `(major, minor)` of a release such as `v0.53.1` or `0.50`.
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |m| m.parse().ok())?;
+    Some((major, minor))
+}
+
+/// Flag task status references in a field of a non-finally task.
+pub(super) fn check_status_references(
+    entry: &Node,
+    task_name: &str,
+    encoding: PositionEncoding,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut scalars = Vec::new();
+    collect_scalars(entry, &mut scalars);
+
+    for expr in scalars
+        .into_iter()
+        .flat_map(|scalar| expressions_in(scalar, encoding))
+    {
+        let is_status = matches!(
+            expr.path().as_slice(),
+            ["tasks", "status"] | ["tasks", _, "status" | "reason"]
+        );
+        if is_status {
+            diagnostics.push(make_diagnostic(
+                Rule::FinallyOnlyVariable,
+                expr.range,
+                DiagnosticSeverity::WARNING,
+                format!(
+                    "'$({})' is only available to finally tasks; pipeline task '{}' runs before it is known",
+                    expr.text, task_name
+                ),
+            ));
+        }
+    }
+}
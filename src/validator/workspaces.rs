@@ -0,0 +1,268 @@
+// Workspace mount paths
+//
+// Task workspaces are mounted at their `mountPath`, `/workspace/<name>` when
+// unset. Two workspaces, or a workspace and a step volume mount, sharing a
+// path make the pod fail to start with a duplicate mount error.
+//
+// Pipeline tasks bind the workspaces of their Task to workspaces of the
+// Pipeline: `{name: <task workspace>, workspace: <pipeline workspace>}`,
+// where `workspace` defaults to `name`. Both ends must be declared, and every
+// workspace the Task requires must be bound, to a required pipeline workspace.
+//
+// A PipelineRun binds the workspaces of its Pipeline by name; when the
+// Pipeline is indexed, the names must be ones it declares, so that renaming a
+// pipeline workspace flags the runs still using the old name.
+
+use super::data::with_task_resource;
+use super::references::link_resource;
+use super::rules::Rule;
+use super::tekton::make_diagnostic;
+use crate::model::{Pipeline, PipelineRun, PipelineTask, Task};
+use crate::workspace::{DeclaredWorkspace, WorkspaceIndex};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Warn about workspaces and step volume mounts sharing a mount path.
+pub(super) fn check_mount_paths(task: &Task, diagnostics: &mut Vec<Diagnostic>) {
+    // (normalized path, workspace name) of the workspaces seen so far
+    let mut mounted: Vec<(String, &str)> = Vec::new();
+
+    for workspace in &task.workspaces {
+        let (Some(name), Some(path)) = (workspace.name, workspace.effective_mount_path()) else {
+            continue;
+        };
+        let path = normalize(&path);
+        if let Some((_, other)) = mounted.iter().find(|(p, _)| *p == path) {
+            diagnostics.push(make_diagnostic(
+                Rule::OverlappingMountPath,
+                workspace.mount_path.map_or(name.range, |m| m.range),
+                DiagnosticSeverity::WARNING,
+                format!(
+                    "Workspaces '{}' and '{}' are both mounted at '{}'",
+                    other, name.value, path
+                ),
+            ));
+        } else {
+            mounted.push((path, name.value));
+        }
+    }
+
+    for step in &task.steps {
+        for mount in &step.volume_mounts {
+            let Some(mount_path) = mount.mount_path else {
+                continue;
+            };
+            let path = normalize(mount_path.value);
+            if let Some((_, workspace)) = mounted.iter().find(|(p, _)| *p == path) {
+                diagnostics.push(make_diagnostic(
+                    Rule::WorkspaceMountConflict,
+                    mount_path.range,
+                    DiagnosticSeverity::WARNING,
+                    format!(
+                        "Volume mount '{}' of step '{}' uses '{}', where workspace '{}' is mounted",
+                        mount.name.map(|n| n.value).unwrap_or("unnamed"),
+                        step.display_name(),
+                        path,
+                        workspace
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Check the workspace bindings of every pipeline task.
+pub(super) fn check_workspace_bindings(
+    pipeline: &Pipeline,
+    index: Option<&WorkspaceIndex>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for task in pipeline.all_tasks() {
+        let task_name = task.display_name();
+        let declared = task_workspaces(task, index);
+
+        for binding in &task.workspaces {
+            let Some(name) = binding.name else {
+                continue;
+            };
+            let target = binding.workspace.unwrap_or(name);
+            let pipeline_workspace = pipeline
+                .workspaces
+                .iter()
+                .find(|w| w.name.is_some_and(|n| n.value == target.value));
+            if pipeline_workspace.is_none() {
+                diagnostics.push(make_diagnostic(
+                    Rule::UnknownWorkspace,
+                    target.range,
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "Pipeline task '{}' binds workspace '{}' to '{}', which is not declared in spec.workspaces",
+                        task_name, name.value, target.value
+                    ),
+                ));
+            }
+
+            let Some((task_label, workspaces)) = &declared else {
+                continue;
+            };
+            match workspaces.iter().find(|w| w.name == name.value) {
+                None => diagnostics.push(with_task_resource(
+                    make_diagnostic(
+                        Rule::UnknownWorkspace,
+                        name.range,
+                        DiagnosticSeverity::ERROR,
+                        format!(
+                            "{} of pipeline task '{}' declares no workspace '{}'; {}",
+                            task_label,
+                            task_name,
+                            name.value,
+                            declared_list(workspaces)
+                        ),
+                    ),
+                    task,
+                )),
+                Some(workspace) if !workspace.optional && pipeline_workspace.is_some_and(|w| w.is_optional()) => {
+                    diagnostics.push(make_diagnostic(
+                        Rule::OptionalWorkspace,
+                        target.range,
+                        DiagnosticSeverity::ERROR,
+                        format!(
+                            "Optional pipeline workspace '{}' is bound to workspace '{}' of pipeline task '{}', which is required",
+                            target.value, name.value, task_name
+                        ),
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+
+        // Inline taskSpecs can use propagated pipeline workspaces without a binding
+        if task.task_spec.is_some() {
+            continue;
+        }
+        let Some((task_label, workspaces)) = &declared else {
+            continue;
+        };
+        for workspace in workspaces.iter().filter(|w| !w.optional) {
+            let bound = task
+                .workspaces
+                .iter()
+                .any(|b| b.name.is_some_and(|n| n.value == workspace.name));
+            if !bound {
+                diagnostics.push(with_task_resource(
+                    make_diagnostic(
+                        Rule::UnboundWorkspace,
+                        task.name.map_or(task.node.range, |n| n.range),
+                        DiagnosticSeverity::ERROR,
+                        format!(
+                            "Pipeline task '{}' does not bind workspace '{}', which {} requires",
+                            task_name, workspace.name, task_label
+                        ),
+                    ),
+                    task,
+                ));
+            }
+        }
+    }
+}
+
+/// Check that a PipelineRun binds the workspaces its indexed Pipeline declares,
+/// and only those.
+pub(super) fn check_run_workspace_bindings(
+    run: &PipelineRun,
+    index: Option<&WorkspaceIndex>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(name) = run.pipeline_ref.as_ref().and_then(|r| r.name) else {
+        return;
+    };
+    let Some(pipeline) = index.and_then(|index| index.find_resource("Pipeline", name.value)) else {
+        return;
+    };
+
+    for binding in run.workspaces.iter().filter_map(|b| b.name) {
+        if pipeline.workspaces.iter().any(|w| w.name == binding.value) {
+            continue;
+        }
+        diagnostics.push(link_resource(
+            make_diagnostic(
+                Rule::UnknownWorkspace,
+                binding.range,
+                DiagnosticSeverity::ERROR,
+                format!(
+                    "Pipeline '{}' declares no workspace '{}'; {}",
+                    pipeline.name,
+                    binding.value,
+                    declared_list(&pipeline.workspaces)
+                ),
+            ),
+            &pipeline,
+        ));
+    }
+
+    for workspace in pipeline.workspaces.iter().filter(|w| !w.optional) {
+        let bound = run
+            .workspaces
+            .iter()
+            .any(|b| b.name.is_some_and(|n| n.value == workspace.name));
+        if !bound {
+            diagnostics.push(link_resource(
+                make_diagnostic(
+                    Rule::UnboundWorkspace,
+                    name.range,
+                    DiagnosticSeverity::ERROR,
+                    format!(
+                        "PipelineRun does not bind workspace '{}', which Pipeline '{}' requires",
+                        workspace.name, pipeline.name
+                    ),
+                ),
+                &pipeline,
+            ));
+        }
+    }
+}
+
+/// The workspaces of the Task a pipeline task runs, when known, with a label
+/// for messages: from its inline `taskSpec`, or from the indexed Task its
+/// `taskRef` names.
+fn task_workspaces(
+    task: &PipelineTask,
+    index: Option<&WorkspaceIndex>,
+) -> Option<(String, Vec<DeclaredWorkspace>)> {
+    if let Some(task_spec) = &task.task_spec {
+        let workspaces = task_spec
+            .workspaces
+            .iter()
+            .filter_map(|w| {
+                Some(DeclaredWorkspace {
+                    name: w.name?.value.to_string(),
+                    optional: w.is_optional(),
+                })
+            })
+            .collect();
+        return Some(("The taskSpec".to_string(), workspaces));
+    }
+    let task_ref = task.task_ref.as_ref()?;
+    let name = task_ref.name?;
+    let resource = index?.find_resource(task_ref.kind_or_default(), name.value)?;
+    Some((
+        format!("{} '{}'", resource.kind, resource.name),
+        resource.workspaces,
+    ))
+}
+
+/// List the declared workspaces for a message.
+fn declared_list(workspaces: &[DeclaredWorkspace]) -> String {
+    if workspaces.is_empty() {
+        return "it declares no workspaces".to_string();
+    }
+    let names: Vec<&str> = workspaces.iter().map(|w| w.name.as_str()).collect();
+    format!("declared workspaces: {}", names.join(", "))
+}
+
+/// Compare paths without their trailing slashes.
+fn normalize(path: &str) -> String {
+    match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
@@ -4,10 +4,13 @@
 //! for cross-file navigation and reference finding.
 
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use tower_lsp::lsp_types::{Location, Url};
 
-use crate::parser::{self, NodeValue, YamlDocument};
+use super::overview::{build_audit, build_overview, WorkspaceAudit, WorkspaceOverview};
+use crate::error::ParseError;
+use crate::parser::{self, to_flow_yaml, NodeValue, PositionEncoding, YamlDocument};
 
 /// A Tekton resource definition in the workspace.
 #[derive(Debug, Clone)]
@@ -22,6 +25,79 @@ pub struct ResourceDefinition {
     pub api_version: Option<String>,
     /// Location of the resource name in the document
     pub location: Location,
+    /// The `spec.description`
+    pub description: Option<String>,
+    /// The declared `spec.results` (Tasks and Pipelines)
+    pub results: Vec<DeclaredResult>,
+    /// The declared `spec.workspaces` (Tasks and Pipelines)
+    pub workspaces: Vec<DeclaredWorkspace>,
+    /// The declared `spec.params` (Tasks and Pipelines)
+    pub params: Vec<DeclaredParam>,
+}
+
+impl ResourceDefinition {
+    /// The resource a document defines, if it has a kind and a name.
+    pub fn from_document(uri: &Url, yaml_doc: &YamlDocument) -> Option<Self> {
+        let kind = yaml_doc.kind.clone()?;
+        let name_node = yaml_doc.root.get("metadata")?.get("name")?;
+        let name = name_node.as_scalar()?.to_string();
+
+        Some(Self {
+            uri: uri.clone(),
+            kind,
+            name,
+            api_version: yaml_doc.api_version.clone(),
+            location: Location {
+                uri: uri.clone(),
+                range: name_node.value_range,
+            },
+            description: yaml_doc
+                .root
+                .get("spec")
+                .and_then(|spec| spec.get("description"))
+                .and_then(|d| d.scalar_content())
+                .map(|d| d.trim().to_string()),
+            results: declared_results(&yaml_doc.root),
+            workspaces: declared_workspaces(&yaml_doc.root),
+            params: declared_params(&yaml_doc.root),
+        })
+    }
+
+    /// File name of the document containing this resource, for messages.
+    pub fn file_name(&self) -> &str {
+        self.uri
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or(self.uri.as_str())
+    }
+}
+
+/// A param declared by an indexed resource.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeclaredParam {
+    pub name: String,
+    /// The declared `type`, `string` when absent
+    pub param_type: String,
+    pub description: Option<String>,
+    /// The `default`, in YAML flow style; a param with one can be left out
+    pub default: Option<String>,
+}
+
+/// A result declared by an indexed resource.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeclaredResult {
+    pub name: String,
+    /// The declared `type`, `string` when absent
+    pub result_type: String,
+    pub description: Option<String>,
+}
+
+/// A workspace declared by an indexed resource.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeclaredWorkspace {
+    pub name: String,
+    /// Whether the workspace can be left unbound
+    pub optional: bool,
 }
 
 /// A reference to a Tekton resource.
@@ -35,6 +111,92 @@ pub struct ResourceReference {
     pub ref_name: String,
     /// Location of the reference in the document
     pub location: Location,
+    /// Kind of the resource holding the reference
+    pub referrer_kind: String,
+    /// Name of the resource holding the reference (`generateName` for runs)
+    pub referrer_name: Option<String>,
+    /// Pipeline task holding a `taskRef`
+    pub pipeline_task: Option<String>,
+    /// Params passed along with the reference
+    pub params: Vec<PassedParam>,
+    /// Names of the workspaces bound along with the reference
+    pub workspaces: Vec<String>,
+}
+
+impl ResourceReference {
+    /// File name of the document containing this reference, for messages.
+    pub fn file_name(&self) -> &str {
+        self.uri
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or(self.uri.as_str())
+    }
+}
+
+/// A param value passed to a referenced resource, by a pipeline task or a PipelineRun.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PassedParam {
+    pub name: String,
+    /// The value, with arrays and objects in YAML flow style
+    pub value: String,
+}
+
+/// A custom resource kind declared by a CustomResourceDefinition in the workspace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomKind {
+    /// Kind name from spec.names.kind
+    pub kind: String,
+    /// API group from spec.group
+    pub group: String,
+    /// Served versions from spec.versions[].name
+    pub versions: Vec<String>,
+}
+
+impl CustomKind {
+    /// The `group/version` strings usable as `apiVersion` for this kind.
+    pub fn api_versions(&self) -> Vec<String> {
+        self.versions
+            .iter()
+            .map(|v| format!("{}/{}", self.group, v))
+            .collect()
+    }
+}
+
+/// Paths left out of the index, as globs relative to a root directory.
+///
+/// `*` stays within a directory and `**` crosses directories, e.g.
+/// `vendor/**` or `**/testdata/*.yaml`.
+#[derive(Debug, Clone, Default)]
+pub struct Exclude {
+    root: PathBuf,
+    patterns: Vec<glob::Pattern>,
+}
+
+impl Exclude {
+    /// Exclude the paths below `root` matching one of `globs`.
+    pub fn new(root: &Path, globs: &[String]) -> Result<Self, glob::PatternError> {
+        Ok(Self {
+            root: std::path::absolute(root).unwrap_or_else(|_| root.to_path_buf()),
+            patterns: globs
+                .iter()
+                .map(|g| glob::Pattern::new(g))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Whether a file or directory is excluded.
+    pub fn matches(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches_path_with(relative, options))
+    }
 }
 
 /// Thread-safe workspace index for Tekton resources.
@@ -44,8 +206,14 @@ pub struct WorkspaceIndex {
     resources: Arc<RwLock<HashMap<String, ResourceDefinition>>>,
     /// References indexed by "Kind/Name" (what they point to)
     references: Arc<RwLock<HashMap<String, Vec<ResourceReference>>>>,
+    /// Custom kinds declared by CustomResourceDefinitions, indexed by "Kind/Name" of the CRD
+    custom_kinds: Arc<RwLock<HashMap<String, CustomKind>>>,
     /// Track which resources/references came from which document
     document_resources: Arc<RwLock<HashMap<Url, Vec<String>>>>,
+    /// Encoding of the positions of indexed locations
+    encoding: Arc<RwLock<PositionEncoding>>,
+    /// Paths left out of the index
+    exclude: Arc<RwLock<Exclude>>,
 }
 
 impl WorkspaceIndex {
@@ -54,17 +222,56 @@ impl WorkspaceIndex {
         Self {
             resources: Arc::new(RwLock::new(HashMap::new())),
             references: Arc::new(RwLock::new(HashMap::new())),
+            custom_kinds: Arc::new(RwLock::new(HashMap::new())),
             document_resources: Arc::new(RwLock::new(HashMap::new())),
+            encoding: Arc::new(RwLock::new(PositionEncoding::default())),
+            exclude: Arc::new(RwLock::new(Exclude::default())),
+        }
+    }
+
+    /// Set the position encoding negotiated with the client.
+    ///
+    /// Only affects documents indexed afterwards.
+    pub fn set_position_encoding(&self, encoding: PositionEncoding) {
+        *self.encoding.write().unwrap() = encoding;
+    }
+
+    /// Set the paths left out of the index, and drop the indexed documents
+    /// they exclude.
+    pub fn set_exclude(&self, exclude: Exclude) {
+        let excluded: Vec<Url> = {
+            let doc_resources = self.document_resources.read().unwrap();
+            doc_resources
+                .keys()
+                .filter(|uri| uri.to_file_path().is_ok_and(|path| exclude.matches(&path)))
+                .cloned()
+                .collect()
+        };
+        *self.exclude.write().unwrap() = exclude;
+        for uri in &excluded {
+            self.remove_document(uri);
         }
     }
 
+    /// Whether a document is left out of the index.
+    pub fn is_excluded(&self, uri: &Url) -> bool {
+        let exclude = self.exclude.read().unwrap();
+        uri.to_file_path().is_ok_and(|path| exclude.matches(&path))
+    }
+
     /// Index a document and extract resources and references.
-    pub fn index_document(&self, uri: &Url, content: &str) -> Result<(), String> {
+    ///
+    /// Excluded documents are not indexed.
+    pub fn index_document(&self, uri: &Url, content: &str) -> Result<(), ParseError> {
         // First remove any existing entries from this document
         self.remove_document(uri);
+        if self.is_excluded(uri) {
+            return Ok(());
+        }
 
         // Parse the document
-        let yaml_doc = parser::parse_yaml(&uri.to_string(), content)?;
+        let encoding = *self.encoding.read().unwrap();
+        let yaml_doc = parser::parse_yaml_with_encoding(uri.as_ref(), content, encoding)?;
 
         // Index the resource definition
         self.index_resource_definition(uri, &yaml_doc);
@@ -75,41 +282,104 @@ impl WorkspaceIndex {
         Ok(())
     }
 
-    /// Index a resource definition from a document.
-    fn index_resource_definition(&self, uri: &Url, yaml_doc: &YamlDocument) {
-        let kind = match &yaml_doc.kind {
-            Some(k) => k.clone(),
-            None => return,
-        };
+    /// Index every YAML file below a directory.
+    ///
+    /// Hidden directories (`.git`, ...) and excluded paths are skipped, and
+    /// so are subdirectories and files that cannot be read or parsed. Fails
+    /// only when `dir` itself cannot be read. Returns the number of files
+    /// indexed.
+    pub fn index_directory(&self, dir: &Path) -> std::io::Result<usize> {
+        let mut entries: Vec<_> = std::fs::read_dir(dir)?
+            .filter_map(|entry| {
+                entry
+                    .inspect_err(|e| {
+                        tracing::warn!("Skipping an entry of {}: {}", dir.display(), e)
+                    })
+                    .ok()
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        let mut indexed = 0;
+        for entry in entries {
+            let path = match std::path::absolute(entry.path()) {
+                Ok(path) => path,
+                Err(e) => {
+                    tracing::warn!("Skipping {}: {}", entry.path().display(), e);
+                    continue;
+                }
+            };
+            if self.exclude.read().unwrap().matches(&path) {
+                continue;
+            }
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if path.is_dir() {
+                if !hidden {
+                    match self.index_directory(&path) {
+                        Ok(count) => indexed += count,
+                        Err(e) => tracing::warn!("Skipping {}: {}", path.display(), e),
+                    }
+                }
+                continue;
+            }
+            let is_yaml = path
+                .extension()
+                .is_some_and(|ext| ext == "yaml" || ext == "yml");
+            if is_yaml && self.index_file(&path) {
+                indexed += 1;
+            }
+        }
+        Ok(indexed)
+    }
 
-        // Get metadata.name
-        let metadata = match yaml_doc.root.get("metadata") {
-            Some(m) => m,
-            None => return,
-        };
+    /// Index a YAML file, or every YAML file below a directory.
+    ///
+    /// Returns the number of files indexed.
+    pub fn index_path(&self, path: &Path) -> std::io::Result<usize> {
+        if path.is_dir() {
+            return self.index_directory(path);
+        }
+        // Fail like `index_directory` on a path that does not exist
+        std::fs::metadata(path)?;
+        Ok(usize::from(self.index_file(&std::path::absolute(path)?)))
+    }
 
-        let name_node = match metadata.get("name") {
-            Some(n) => n,
-            None => return,
-        };
+    /// Index a document from its file, as saved, or remove it from the
+    /// index when it is not a file that can be read and parsed anymore.
+    pub fn reload_document(&self, uri: &Url) {
+        let indexed = uri.to_file_path().is_ok_and(|path| self.index_file(&path));
+        if !indexed {
+            self.remove_document(uri);
+        }
+    }
 
-        let name = match name_node.as_scalar() {
-            Some(s) => s.to_string(),
-            None => return,
+    /// Index a file, returning whether it was indexed.
+    fn index_file(&self, path: &Path) -> bool {
+        let Ok(uri) = Url::from_file_path(path) else {
+            return false;
         };
-
-        let key = format!("{}/{}", kind, name);
-
-        let resource = ResourceDefinition {
-            uri: uri.clone(),
-            kind: kind.clone(),
-            name: name.clone(),
-            api_version: yaml_doc.api_version.clone(),
-            location: Location {
-                uri: uri.clone(),
-                range: name_node.range,
+        match std::fs::read_to_string(path) {
+            Ok(content) => match self.index_document(&uri, &content) {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!("Skipping {}: {}", path.display(), e);
+                    false
+                }
             },
+            Err(e) => {
+                tracing::warn!("Skipping {}: {}", path.display(), e);
+                false
+            }
+        }
+    }
+
+    /// Index a resource definition from a document.
+    fn index_resource_definition(&self, uri: &Url, yaml_doc: &YamlDocument) {
+        let Some(resource) = ResourceDefinition::from_document(uri, yaml_doc) else {
+            return;
         };
+        let kind = resource.kind.clone();
+        let key = format!("{}/{}", kind, resource.name);
 
         // Add to resources
         {
@@ -117,16 +387,42 @@ impl WorkspaceIndex {
             resources.insert(key.clone(), resource);
         }
 
+        // Remember the kind declared by a CRD (for taskRef kind/apiVersion completion)
+        if kind == "CustomResourceDefinition" {
+            if let Some(custom_kind) = self.extract_custom_kind(&yaml_doc.root) {
+                let mut custom_kinds = self.custom_kinds.write().unwrap();
+                custom_kinds.insert(key.clone(), custom_kind);
+            }
+        }
+
         // Track which resources came from this document
         {
             let mut doc_resources = self.document_resources.write().unwrap();
-            doc_resources
-                .entry(uri.clone())
-                .or_insert_with(Vec::new)
-                .push(key);
+            doc_resources.entry(uri.clone()).or_default().push(key);
         }
     }
 
+    /// Extract the kind, group, and versions declared by a CustomResourceDefinition.
+    fn extract_custom_kind(&self, root: &crate::parser::Node) -> Option<CustomKind> {
+        let spec = root.get("spec")?;
+        let kind = spec.get("names")?.get("kind")?.as_scalar()?.to_string();
+        let group = spec.get("group")?.as_scalar()?.to_string();
+
+        let versions = spec
+            .get("versions")
+            .into_iter()
+            .flat_map(crate::parser::Node::items)
+            .filter_map(|item| item.get("name").and_then(|n| n.as_scalar()))
+            .map(String::from)
+            .collect();
+
+        Some(CustomKind {
+            kind,
+            group,
+            versions,
+        })
+    }
+
     /// Index references from a document (e.g., taskRef in Pipelines).
     fn index_references(&self, uri: &Url, yaml_doc: &YamlDocument) {
         let kind = match &yaml_doc.kind {
@@ -134,15 +430,27 @@ impl WorkspaceIndex {
             None => return,
         };
 
+        let referrer = Referrer {
+            kind,
+            name: yaml_doc.root.get("metadata").and_then(|metadata| {
+                metadata
+                    .get("name")
+                    .or_else(|| metadata.get("generateName"))
+                    .and_then(|name| name.as_scalar())
+            }),
+        };
+
         match kind {
-            "Pipeline" => self.index_pipeline_references(uri, yaml_doc),
-            "PipelineRun" => self.index_pipeline_run_references(uri, yaml_doc),
+            "Pipeline" => self.index_pipeline_references(uri, &referrer, yaml_doc),
+            "PipelineRun" => self.index_pipeline_run_references(uri, &referrer, yaml_doc),
+            "Task" => self.index_task_references(uri, &referrer, yaml_doc),
+            "TaskRun" => self.index_task_run_references(uri, &referrer, yaml_doc),
             _ => {}
         }
     }
 
     /// Index taskRef references in a Pipeline.
-    fn index_pipeline_references(&self, uri: &Url, yaml_doc: &YamlDocument) {
+    fn index_pipeline_references(&self, uri: &Url, referrer: &Referrer, yaml_doc: &YamlDocument) {
         let spec = match yaml_doc.root.get("spec") {
             Some(s) => s,
             None => return,
@@ -150,17 +458,22 @@ impl WorkspaceIndex {
 
         // Index tasks array
         if let Some(tasks) = spec.get("tasks") {
-            self.index_pipeline_tasks(uri, tasks);
+            self.index_pipeline_tasks(uri, referrer, tasks);
         }
 
         // Index finally array
         if let Some(finally) = spec.get("finally") {
-            self.index_pipeline_tasks(uri, finally);
+            self.index_pipeline_tasks(uri, referrer, finally);
         }
     }
 
     /// Index taskRef references in a tasks/finally array.
-    fn index_pipeline_tasks(&self, uri: &Url, tasks_node: &crate::parser::Node) {
+    fn index_pipeline_tasks(
+        &self,
+        uri: &Url,
+        referrer: &Referrer,
+        tasks_node: &crate::parser::Node,
+    ) {
         let tasks = match &tasks_node.value {
             NodeValue::Sequence(items) => items,
             _ => return,
@@ -169,13 +482,20 @@ impl WorkspaceIndex {
         for task in tasks {
             // Check for taskRef
             if let Some(task_ref) = task.get("taskRef") {
-                self.index_task_ref(uri, task_ref, "Task");
+                self.index_task_ref(uri, referrer, task, task_ref, "Task");
             }
         }
     }
 
-    /// Index a taskRef reference.
-    fn index_task_ref(&self, uri: &Url, task_ref: &crate::parser::Node, default_kind: &str) {
+    /// Index a taskRef reference of a pipeline task.
+    fn index_task_ref(
+        &self,
+        uri: &Url,
+        referrer: &Referrer,
+        task: &crate::parser::Node,
+        task_ref: &crate::parser::Node,
+        default_kind: &str,
+    ) {
         // Get the name
         let name_node = match task_ref.get("name") {
             Some(n) => n,
@@ -194,39 +514,55 @@ impl WorkspaceIndex {
             .map(|s| s.to_string())
             .unwrap_or_else(|| default_kind.to_string());
 
-        let key = format!("{}/{}", kind, name);
-
         let reference = ResourceReference {
             uri: uri.clone(),
             ref_kind: kind,
             ref_name: name,
             location: Location {
                 uri: uri.clone(),
-                range: name_node.range,
+                range: name_node.value_range,
             },
+            referrer_kind: referrer.kind.to_string(),
+            referrer_name: referrer.name.map(str::to_string),
+            pipeline_task: task
+                .get("name")
+                .and_then(|n| n.as_scalar())
+                .map(str::to_string),
+            params: passed_params(task),
+            workspaces: bound_workspaces(task),
         };
 
+        self.add_reference(reference);
+    }
+
+    /// Record a reference, and that it came from its document.
+    fn add_reference(&self, reference: ResourceReference) {
+        let key = format!("{}/{}", reference.ref_kind, reference.ref_name);
+        let uri = reference.uri.clone();
+
         // Add to references
         {
             let mut references = self.references.write().unwrap();
-            references
-                .entry(key.clone())
-                .or_insert_with(Vec::new)
-                .push(reference);
+            references.entry(key.clone()).or_default().push(reference);
         }
 
         // Track which references came from this document
         {
             let mut doc_resources = self.document_resources.write().unwrap();
             doc_resources
-                .entry(uri.clone())
-                .or_insert_with(Vec::new)
+                .entry(uri)
+                .or_default()
                 .push(format!("ref:{}", key));
         }
     }
 
     /// Index pipelineRef references in a PipelineRun.
-    fn index_pipeline_run_references(&self, uri: &Url, yaml_doc: &YamlDocument) {
+    fn index_pipeline_run_references(
+        &self,
+        uri: &Url,
+        referrer: &Referrer,
+        yaml_doc: &YamlDocument,
+    ) {
         let spec = match yaml_doc.root.get("spec") {
             Some(s) => s,
             None => return,
@@ -235,38 +571,95 @@ impl WorkspaceIndex {
         if let Some(pipeline_ref) = spec.get("pipelineRef") {
             if let Some(name_node) = pipeline_ref.get("name") {
                 if let Some(name) = name_node.as_scalar() {
-                    let key = format!("Pipeline/{}", name);
-
                     let reference = ResourceReference {
                         uri: uri.clone(),
                         ref_kind: "Pipeline".to_string(),
                         ref_name: name.to_string(),
                         location: Location {
                             uri: uri.clone(),
-                            range: name_node.range,
+                            range: name_node.value_range,
                         },
+                        referrer_kind: referrer.kind.to_string(),
+                        referrer_name: referrer.name.map(str::to_string),
+                        pipeline_task: None,
+                        params: passed_params(spec),
+                        workspaces: bound_workspaces(spec),
                     };
-
-                    {
-                        let mut references = self.references.write().unwrap();
-                        references
-                            .entry(key.clone())
-                            .or_insert_with(Vec::new)
-                            .push(reference);
-                    }
-
-                    {
-                        let mut doc_resources = self.document_resources.write().unwrap();
-                        doc_resources
-                            .entry(uri.clone())
-                            .or_insert_with(Vec::new)
-                            .push(format!("ref:{}", key));
-                    }
+                    self.add_reference(reference);
                 }
             }
         }
     }
 
+    /// Index the StepAction references (`steps[].ref`) of a Task.
+    fn index_task_references(&self, uri: &Url, referrer: &Referrer, yaml_doc: &YamlDocument) {
+        let Some(NodeValue::Sequence(steps)) = yaml_doc
+            .root
+            .get("spec")
+            .and_then(|spec| spec.get("steps"))
+            .map(|steps| &steps.value)
+        else {
+            return;
+        };
+
+        for step in steps {
+            let Some(name_node) = step.get("ref").and_then(|r| r.get("name")) else {
+                continue;
+            };
+            let Some(name) = name_node.as_scalar() else {
+                continue;
+            };
+            self.add_reference(ResourceReference {
+                uri: uri.clone(),
+                ref_kind: "StepAction".to_string(),
+                ref_name: name.to_string(),
+                location: Location {
+                    uri: uri.clone(),
+                    range: name_node.value_range,
+                },
+                referrer_kind: referrer.kind.to_string(),
+                referrer_name: referrer.name.map(str::to_string),
+                pipeline_task: None,
+                params: passed_params(step),
+                workspaces: Vec::new(),
+            });
+        }
+    }
+
+    /// Index the taskRef reference of a TaskRun.
+    fn index_task_run_references(&self, uri: &Url, referrer: &Referrer, yaml_doc: &YamlDocument) {
+        let Some(spec) = yaml_doc.root.get("spec") else {
+            return;
+        };
+        let Some(task_ref) = spec.get("taskRef") else {
+            return;
+        };
+        let Some(name_node) = task_ref.get("name") else {
+            return;
+        };
+        let Some(name) = name_node.as_scalar() else {
+            return;
+        };
+        self.add_reference(ResourceReference {
+            uri: uri.clone(),
+            ref_kind: task_ref
+                .get("kind")
+                .and_then(|k| k.as_scalar())
+                .unwrap_or("Task")
+                .to_string(),
+            ref_name: name.to_string(),
+            location: Location {
+                uri: uri.clone(),
+                range: name_node.value_range,
+            },
+            referrer_kind: referrer.kind.to_string(),
+            referrer_name: referrer.name.map(str::to_string),
+            pipeline_task: None,
+            params: passed_params(spec),
+            workspaces: bound_workspaces(spec),
+        });
+    }
+
     /// Find a resource definition by kind and name.
     pub fn find_resource(&self, kind: &str, name: &str) -> Option<ResourceDefinition> {
         let key = format!("{}/{}", kind, name);
@@ -281,6 +674,24 @@ impl WorkspaceIndex {
         references.get(&key).cloned().unwrap_or_default()
     }
 
+    /// References from other documents to the resources defined in a document.
+    pub fn inbound_references(&self, uri: &Url) -> Vec<ResourceReference> {
+        let keys: Vec<String> = {
+            let doc_resources = self.document_resources.read().unwrap();
+            let keys = doc_resources.get(uri).into_iter().flatten();
+            keys.filter(|key| !key.starts_with("ref:"))
+                .cloned()
+                .collect()
+        };
+
+        let references = self.references.read().unwrap();
+        keys.iter()
+            .flat_map(|key| references.get(key).into_iter().flatten())
+            .filter(|r| &r.uri != uri)
+            .cloned()
+            .collect()
+    }
+
     /// Remove a document from the index.
     pub fn remove_document(&self, uri: &Url) {
         let keys_to_remove: Vec<String>;
@@ -301,6 +712,14 @@ impl WorkspaceIndex {
             }
         }
 
+        // Remove custom kinds declared by CRDs
+        {
+            let mut custom_kinds = self.custom_kinds.write().unwrap();
+            for key in &keys_to_remove {
+                custom_kinds.remove(key);
+            }
+        }
+
         // Remove references
         {
             let mut references = self.references.write().unwrap();
@@ -323,12 +742,179 @@ impl WorkspaceIndex {
         }
     }
 
+    /// Get all custom kinds declared by CustomResourceDefinitions in the workspace.
+    pub fn custom_kinds(&self) -> Vec<CustomKind> {
+        let custom_kinds = self.custom_kinds.read().unwrap();
+        let mut kinds: Vec<CustomKind> = custom_kinds.values().cloned().collect();
+        kinds.sort_by(|a, b| a.kind.cmp(&b.kind));
+        kinds
+    }
+
     /// Get all indexed resources.
-    #[allow(dead_code)]
     pub fn all_resources(&self) -> Vec<ResourceDefinition> {
         let resources = self.resources.read().unwrap();
         resources.values().cloned().collect()
     }
+
+    /// Get all indexed references.
+    pub fn all_references(&self) -> Vec<ResourceReference> {
+        let references = self.references.read().unwrap();
+        references.values().flatten().cloned().collect()
+    }
+
+    /// Summarize the workspace: counts per kind and resources with their references.
+    pub fn overview(&self) -> WorkspaceOverview {
+        build_overview(self.all_resources(), self.all_references())
+    }
+
+    /// List every resource and reference, and the references that do not resolve.
+    pub fn audit(&self) -> WorkspaceAudit {
+        build_audit(self.all_resources(), self.all_references())
+    }
+}
+
+/// Describe references to resources for a warning before breaking them, e.g.
+/// "Task 'build' is referenced from pipeline.yaml, release.yaml".
+///
+/// Returns `None` when there are no references.
+pub fn describe_references(references: &[ResourceReference]) -> Option<String> {
+    // (kind, name) -> referencing file names, in first-seen order
+    let mut targets: Vec<((&str, &str), Vec<&str>)> = Vec::new();
+    for reference in references {
+        let file = reference.file_name();
+        let target = (reference.ref_kind.as_str(), reference.ref_name.as_str());
+        match targets.iter_mut().find(|(t, _)| *t == target) {
+            Some((_, files)) if files.contains(&file) => {}
+            Some((_, files)) => files.push(file),
+            None => targets.push((target, vec![file])),
+        }
+    }
+
+    let descriptions: Vec<String> = targets
+        .iter()
+        .map(|((kind, name), files)| {
+            format!(
+                "{} '{}' is referenced from {}",
+                kind,
+                name,
+                files.join(", ")
+            )
+        })
+        .collect();
+    (!descriptions.is_empty()).then(|| descriptions.join("; "))
+}
+
+/// The resource holding the references being indexed.
+struct Referrer<'a> {
+    kind: &'a str,
+    name: Option<&'a str>,
+}
+
+/// The `spec.results` of a resource.
+fn declared_results(root: &crate::parser::Node) -> Vec<DeclaredResult> {
+    let Some(NodeValue::Sequence(results)) = root
+        .get("spec")
+        .and_then(|spec| spec.get("results"))
+        .map(|r| &r.value)
+    else {
+        return Vec::new();
+    };
+    results
+        .iter()
+        .filter_map(|result| {
+            Some(DeclaredResult {
+                name: result.get("name")?.as_scalar()?.to_string(),
+                result_type: result
+                    .get("type")
+                    .and_then(|t| t.as_scalar())
+                    .unwrap_or("string")
+                    .to_string(),
+                description: result
+                    .get("description")
+                    .and_then(|d| d.as_scalar())
+                    .map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// The `spec.workspaces` of a resource.
+fn declared_workspaces(root: &crate::parser::Node) -> Vec<DeclaredWorkspace> {
+    let Some(NodeValue::Sequence(workspaces)) = root
+        .get("spec")
+        .and_then(|spec| spec.get("workspaces"))
+        .map(|w| &w.value)
+    else {
+        return Vec::new();
+    };
+    workspaces
+        .iter()
+        .filter_map(|workspace| {
+            Some(DeclaredWorkspace {
+                name: workspace.get("name")?.as_scalar()?.to_string(),
+                optional: workspace.get("optional").and_then(|o| o.as_scalar()) == Some("true"),
+            })
+        })
+        .collect()
+}
+
+/// The `spec.params` of a resource.
+fn declared_params(root: &crate::parser::Node) -> Vec<DeclaredParam> {
+    let Some(NodeValue::Sequence(params)) = root
+        .get("spec")
+        .and_then(|spec| spec.get("params"))
+        .map(|p| &p.value)
+    else {
+        return Vec::new();
+    };
+    params
+        .iter()
+        .filter_map(|param| {
+            Some(DeclaredParam {
+                name: param.get("name")?.as_scalar()?.to_string(),
+                param_type: param
+                    .get("type")
+                    .and_then(|t| t.as_scalar())
+                    .unwrap_or("string")
+                    .to_string(),
+                description: param
+                    .get("description")
+                    .and_then(|d| d.as_scalar())
+                    .map(str::to_string),
+                default: param.get("default").map(to_flow_yaml),
+            })
+        })
+        .collect()
+}
+
+/// The `params` passed by a pipeline task or a PipelineRun spec.
+fn passed_params(node: &crate::parser::Node) -> Vec<PassedParam> {
+    let Some(NodeValue::Sequence(params)) = node.get("params").map(|p| &p.value) else {
+        return Vec::new();
+    };
+    params
+        .iter()
+        .filter_map(|param| {
+            let value = param.get("value")?;
+            Some(PassedParam {
+                name: param.get("name")?.as_scalar()?.to_string(),
+                value: value
+                    .as_scalar()
+                    .map_or_else(|| parser::to_flow_yaml(value), str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// The names of the `workspaces` bound by a pipeline task or a PipelineRun spec.
+fn bound_workspaces(node: &crate::parser::Node) -> Vec<String> {
+    let Some(NodeValue::Sequence(workspaces)) = node.get("workspaces").map(|w| &w.value) else {
+        return Vec::new();
+    };
+    workspaces
+        .iter()
+        .filter_map(|workspace| Some(workspace.get("name")?.as_scalar()?.to_string()))
+        .collect()
 }
 
 impl Default for WorkspaceIndex {
@@ -419,7 +1005,9 @@ spec:
     - name: build
       taskRef:
         name: build-task"#;
-        index.index_document(&pipeline_uri, pipeline_content).unwrap();
+        index
+            .index_document(&pipeline_uri, pipeline_content)
+            .unwrap();
 
         // Find references to build-task
         let refs = index.find_references("Task", "build-task");
@@ -471,4 +1059,145 @@ metadata:
         assert!(index.find_resource("Task", "build-task-v1").is_none());
         assert!(index.find_resource("Task", "build-task-v2").is_some());
     }
+
+    #[test]
+    fn test_index_custom_resource_definition() {
+        let index = WorkspaceIndex::new();
+
+        let uri = make_test_uri("/workspace/crds/custom-task.yaml");
+        let content = r#"apiVersion: apiextensions.k8s.io/v1
+kind: CustomResourceDefinition
+metadata:
+  name: approvaltasks.openshift-pipelines.org
+spec:
+  group: openshift-pipelines.org
+  names:
+    kind: ApprovalTask
+  versions:
+    - name: v1alpha1"#;
+
+        index.index_document(&uri, content).unwrap();
+
+        let kinds = index.custom_kinds();
+        assert_eq!(kinds.len(), 1);
+        assert_eq!(kinds[0].kind, "ApprovalTask");
+        assert_eq!(
+            kinds[0].api_versions(),
+            vec!["openshift-pipelines.org/v1alpha1"]
+        );
+
+        index.remove_document(&uri);
+        assert!(index.custom_kinds().is_empty());
+    }
+
+    #[test]
+    fn test_workspace_overview() {
+        let index = WorkspaceIndex::new();
+
+        let task_uri = make_test_uri("/workspace/tasks/build.yaml");
+        index
+            .index_document(&task_uri, "kind: Task\nmetadata:\n  name: build-task\n")
+            .unwrap();
+        let pipeline_uri = make_test_uri("/workspace/pipelines/main.yaml");
+        let pipeline = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: main-pipeline
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build-task
+    - name: deploy
+      taskRef:
+        name: deploy-task"#;
+        index.index_document(&pipeline_uri, pipeline).unwrap();
+
+        let overview = index.overview();
+        assert_eq!(overview.counts.get("Pipeline"), Some(&1));
+        assert_eq!(overview.counts.get("Task"), Some(&1));
+
+        let names: Vec<&str> = overview.resources.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["main-pipeline", "build-task"]);
+
+        let pipeline = &overview.resources[0];
+        assert_eq!(pipeline.api_version.as_deref(), Some("tekton.dev/v1"));
+        let refs: Vec<(&str, bool)> = pipeline
+            .references
+            .iter()
+            .map(|r| (r.name.as_str(), r.resolved))
+            .collect();
+        assert_eq!(refs, vec![("build-task", true), ("deploy-task", false)]);
+
+        let task = &overview.resources[1];
+        assert_eq!(task.referenced_by.len(), 1);
+        assert_eq!(task.referenced_by[0].uri, pipeline_uri);
+
+        let json = serde_json::to_value(&overview).unwrap();
+        assert!(json["resources"][1]["referencedBy"].is_array());
+        assert!(json["resources"][1].get("apiVersion").is_none());
+    }
+
+    #[test]
+    fn test_index_directory_and_audit() {
+        let dir = std::env::temp_dir().join(format!("tekton-lsp-index-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("tasks")).unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        std::fs::write(
+            dir.join("tasks/build.yaml"),
+            "kind: Task\nmetadata:\n  name: build-task\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("pipeline.yml"),
+            "kind: Pipeline\nmetadata:\n  name: ci\nspec:\n  tasks:\n    - name: a\n      taskRef:\n        name: build-task\n    - name: b\n      taskRef:\n        name: missing\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(".git/task.yaml"),
+            "kind: Task\nmetadata:\n  name: hidden\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("README.md"), "kind: Task\n").unwrap();
+
+        let index = WorkspaceIndex::new();
+        let indexed = index.index_directory(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(indexed.unwrap(), 2);
+
+        let audit = index.audit();
+        let resources: Vec<(&str, &str)> = audit
+            .resources
+            .iter()
+            .map(|r| (r.kind.as_str(), r.name.as_str()))
+            .collect();
+        assert_eq!(resources, vec![("Pipeline", "ci"), ("Task", "build-task")]);
+        assert_eq!(audit.references.len(), 2);
+        assert_eq!(audit.unresolved.len(), 1);
+        assert_eq!(audit.unresolved[0].name, "missing");
+        assert_eq!(audit.unresolved[0].location.range.start.line, 10);
+    }
+
+    #[test]
+    fn test_reload_document_from_disk() {
+        let dir = std::env::temp_dir().join(format!("tekton-lsp-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = std::path::absolute(dir.join("task.yaml")).unwrap();
+        std::fs::write(&path, "kind: Task\nmetadata:\n  name: saved\n").unwrap();
+        let uri = Url::from_file_path(&path).unwrap();
+
+        // Unsaved edits are replaced by the saved file
+        let index = WorkspaceIndex::new();
+        index
+            .index_document(&uri, "kind: Task\nmetadata:\n  name: edited\n")
+            .unwrap();
+        index.reload_document(&uri);
+        assert!(index.find_resource("Task", "edited").is_none());
+        assert!(index.find_resource("Task", "saved").is_some());
+
+        // A file that is gone leaves the index
+        std::fs::remove_dir_all(&dir).unwrap();
+        index.reload_document(&uri);
+        assert!(index.find_resource("Task", "saved").is_none());
+    }
 }
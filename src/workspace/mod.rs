@@ -4,7 +4,16 @@
 //! - Go-to-definition (navigate from taskRef to Task)
 //! - Find references (find all uses of a Task/Pipeline)
 //! - Cross-file validation
+//! - The `tekton/workspaceOverview` request (resource tree for editor extensions)
+//! - The `tekton-lsp index` audit listing
 
 pub mod index;
+mod overview;
 
-pub use index::WorkspaceIndex;
+pub use index::{
+    describe_references, CustomKind, DeclaredParam, DeclaredResult, DeclaredWorkspace, Exclude,
+    PassedParam, WorkspaceIndex,
+};
+pub use overview::{
+    AuditResource, OverviewReference, OverviewResource, WorkspaceAudit, WorkspaceOverview,
+};
@@ -0,0 +1,173 @@
+//! Workspace overview for the `tekton/workspaceOverview` request.
+//!
+//! Summarizes the index as counts per kind plus every resource with its
+//! location and the references going out of and into it, so that editor
+//! extensions can render a tree of the workspace. The flat audit listing
+//! backs `tekton-lsp index`.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tower_lsp::lsp_types::Location;
+
+use super::index::{ResourceDefinition, ResourceReference};
+
+/// Counts and resources of the whole workspace.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceOverview {
+    /// Number of resources per kind
+    pub counts: BTreeMap<String, usize>,
+    /// Every indexed resource, sorted by kind and name
+    pub resources: Vec<OverviewResource>,
+}
+
+/// A resource in the workspace overview.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverviewResource {
+    pub kind: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
+    /// Location of the resource name
+    pub location: Location,
+    /// Resources referenced from this resource's document
+    pub references: Vec<OverviewReference>,
+    /// Locations referencing this resource
+    pub referenced_by: Vec<Location>,
+}
+
+/// A reference from one resource to another.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverviewReference {
+    pub kind: String,
+    pub name: String,
+    /// Location of the reference
+    pub location: Location,
+    /// Whether the referenced resource is in the workspace
+    pub resolved: bool,
+}
+
+/// Flat listing of the index, for `tekton-lsp index`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceAudit {
+    /// Every indexed resource, sorted by kind and name
+    pub resources: Vec<AuditResource>,
+    /// Every reference, in file and document order
+    pub references: Vec<OverviewReference>,
+    /// The references whose target is not in the workspace
+    pub unresolved: Vec<OverviewReference>,
+}
+
+/// A resource in the audit listing.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditResource {
+    pub kind: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
+    /// Location of the resource name
+    pub location: Location,
+}
+
+/// Build the overview from the indexed resources and references.
+pub(super) fn build_overview(
+    mut resources: Vec<ResourceDefinition>,
+    references: Vec<ResourceReference>,
+) -> WorkspaceOverview {
+    resources.sort_by(|a, b| (&a.kind, &a.name).cmp(&(&b.kind, &b.name)));
+
+    let mut counts = BTreeMap::new();
+    for resource in &resources {
+        *counts.entry(resource.kind.clone()).or_default() += 1;
+    }
+
+    let is_resolved =
+        |kind: &str, name: &str| resources.iter().any(|r| r.kind == kind && r.name == name);
+
+    let overview_resources = resources
+        .iter()
+        .map(|resource| {
+            let mut outgoing: Vec<OverviewReference> = references
+                .iter()
+                .filter(|r| r.uri == resource.uri)
+                .map(|r| OverviewReference {
+                    kind: r.ref_kind.clone(),
+                    name: r.ref_name.clone(),
+                    location: r.location.clone(),
+                    resolved: is_resolved(&r.ref_kind, &r.ref_name),
+                })
+                .collect();
+            outgoing.sort_by_key(|r| location_key(&r.location));
+
+            let mut incoming: Vec<Location> = references
+                .iter()
+                .filter(|r| r.ref_kind == resource.kind && r.ref_name == resource.name)
+                .map(|r| r.location.clone())
+                .collect();
+            incoming.sort_by_key(location_key);
+
+            OverviewResource {
+                kind: resource.kind.clone(),
+                name: resource.name.clone(),
+                api_version: resource.api_version.clone(),
+                location: resource.location.clone(),
+                references: outgoing,
+                referenced_by: incoming,
+            }
+        })
+        .collect();
+
+    WorkspaceOverview {
+        counts,
+        resources: overview_resources,
+    }
+}
+
+/// Build the audit listing from the indexed resources and references.
+pub(super) fn build_audit(
+    mut resources: Vec<ResourceDefinition>,
+    references: Vec<ResourceReference>,
+) -> WorkspaceAudit {
+    resources.sort_by(|a, b| (&a.kind, &a.name).cmp(&(&b.kind, &b.name)));
+    let is_resolved =
+        |kind: &str, name: &str| resources.iter().any(|r| r.kind == kind && r.name == name);
+
+    let mut references: Vec<OverviewReference> = references
+        .into_iter()
+        .map(|r| OverviewReference {
+            resolved: is_resolved(&r.ref_kind, &r.ref_name),
+            kind: r.ref_kind,
+            name: r.ref_name,
+            location: r.location,
+        })
+        .collect();
+    references.sort_by_key(|r| location_key(&r.location));
+    let unresolved = references.iter().filter(|r| !r.resolved).cloned().collect();
+
+    WorkspaceAudit {
+        resources: resources
+            .into_iter()
+            .map(|r| AuditResource {
+                kind: r.kind,
+                name: r.name,
+                api_version: r.api_version,
+                location: r.location,
+            })
+            .collect(),
+        references,
+        unresolved,
+    }
+}
+
+/// Sort key placing locations in file, then document order.
+fn location_key(location: &Location) -> (String, u32, u32) {
+    (
+        location.uri.to_string(),
+        location.range.start.line,
+        location.range.start.character,
+    )
+}
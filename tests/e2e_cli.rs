@@ -0,0 +1,168 @@
+//! End-to-end tests for the command line.
+//!
+//! These tests run the `tekton-lsp` binary on a directory of resources
+//! and check its output.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Create a directory with a Task and a Pipeline referencing it and a missing Task.
+fn create_workspace(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tekton-lsp-cli-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("task.yaml"),
+        "apiVersion: tekton.dev/v1\nkind: Task\nmetadata:\n  name: build\nspec:\n  steps:\n    - image: golang\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("pipeline.yaml"),
+        r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+    - name: test
+      taskRef:
+        name: go-test
+"#,
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn test_index_json_lists_resources_and_unresolved_references() {
+    let dir = create_workspace("json");
+    let output = Command::new(env!("CARGO_BIN_EXE_tekton-lsp"))
+        .args(["index", "--json"])
+        .arg(&dir)
+        .output()
+        .expect("Failed to run tekton-lsp");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Output should be JSON");
+
+    let resources: Vec<(&str, &str)> = json["resources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| (r["kind"].as_str().unwrap(), r["name"].as_str().unwrap()))
+        .collect();
+    assert_eq!(resources, vec![("Pipeline", "ci"), ("Task", "build")]);
+
+    assert_eq!(json["references"].as_array().unwrap().len(), 2);
+    let unresolved = json["unresolved"].as_array().unwrap();
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(unresolved[0]["name"], "go-test");
+    assert_eq!(unresolved[0]["resolved"], false);
+    assert!(unresolved[0]["location"]["uri"]
+        .as_str()
+        .unwrap()
+        .ends_with("/pipeline.yaml"));
+}
+
+#[test]
+fn test_index_honors_project_config() {
+    let dir = create_workspace("config");
+    let catalog = dir.with_extension("catalog");
+    std::fs::create_dir_all(dir.join("vendor")).unwrap();
+    std::fs::create_dir_all(&catalog).unwrap();
+    std::fs::write(
+        dir.join("vendor/task.yaml"),
+        "apiVersion: tekton.dev/v1\nkind: Task\nmetadata:\n  name: vendored\n",
+    )
+    .unwrap();
+    std::fs::write(
+        catalog.join("go-test.yaml"),
+        "apiVersion: tekton.dev/v1\nkind: Task\nmetadata:\n  name: go-test\n",
+    )
+    .unwrap();
+    let catalog_name = catalog.file_name().unwrap().to_str().unwrap();
+    std::fs::write(
+        dir.join(".tekton-lsp.yaml"),
+        format!(
+            "exclude:\n  - vendor/**\nschemas:\n  - ../{}\n",
+            catalog_name
+        ),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_tekton-lsp"))
+        .args(["index", "--json"])
+        .arg(&dir)
+        .output()
+        .expect("Failed to run tekton-lsp");
+    std::fs::remove_dir_all(&dir).unwrap();
+    std::fs::remove_dir_all(&catalog).unwrap();
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Output should be JSON");
+    let names: Vec<&str> = json["resources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["ci", "build", "go-test"]);
+    assert!(json["unresolved"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_index_fails_on_invalid_project_config() {
+    let dir = create_workspace("bad-config");
+    std::fs::write(dir.join(".tekton-lsp.toml"), "[rules]\nTPL999 = \"off\"\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_tekton-lsp"))
+        .args(["index"])
+        .arg(&dir)
+        .output()
+        .expect("Failed to run tekton-lsp");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown rule 'TPL999'"));
+}
+
+#[test]
+fn test_index_fails_on_missing_directory() {
+    let output = Command::new(env!("CARGO_BIN_EXE_tekton-lsp"))
+        .args(["index", "/nonexistent/tekton-lsp"])
+        .output()
+        .expect("Failed to run tekton-lsp");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Failed to index"));
+}
+
+#[test]
+fn test_explain_prints_field_documentation() {
+    let output = Command::new(env!("CARGO_BIN_EXE_tekton-lsp"))
+        .args(["explain", "spec.tasks.runAfter"])
+        .output()
+        .expect("Failed to run tekton-lsp");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("KIND:     Pipeline\nFIELD:    spec.tasks.runAfter <array>\n"));
+    assert!(stdout.contains("\nDESCRIPTION:\n    Tasks that must complete before this task\n"));
+}
+
+#[test]
+fn test_explain_fails_on_unknown_field() {
+    let output = Command::new(env!("CARGO_BIN_EXE_tekton-lsp"))
+        .args(["explain", "task.spec.foo"])
+        .output()
+        .expect("Failed to run tekton-lsp");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .starts_with("Unknown field 'foo' in spec of Task; fields: "));
+}
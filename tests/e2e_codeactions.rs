@@ -4,11 +4,23 @@
 //! quick fixes for diagnostics.
 
 use tekton_lsp::actions::CodeActionsProvider;
+use tekton_lsp::validator::{DiagnosticData, Fix};
+use tekton_lsp::workspace::WorkspaceIndex;
 use tower_lsp::lsp_types::{
     CodeActionKind, CodeActionOrCommand, Diagnostic, DiagnosticSeverity, Position, Range, Url,
 };
 
-fn create_diagnostic(message: &str, line: u32, start_char: u32, end_char: u32) -> Diagnostic {
+fn create_diagnostic(
+    message: &str,
+    line: u32,
+    start_char: u32,
+    end_char: u32,
+    fix: Option<Fix>,
+) -> Diagnostic {
+    let data = fix.map(|fix| DiagnosticData {
+        fix: Some(fix),
+        ..DiagnosticData::new("test")
+    });
     Diagnostic {
         range: Range {
             start: Position {
@@ -27,7 +39,7 @@ fn create_diagnostic(message: &str, line: u32, start_char: u32, end_char: u32) -
         message: message.to_string(),
         related_information: None,
         tags: None,
-        data: None,
+        data: data.and_then(|data| serde_json::to_value(data).ok()),
     }
 }
 
@@ -36,7 +48,15 @@ fn test_quick_fix_for_missing_metadata() {
     let provider = CodeActionsProvider::new();
     let uri = Url::parse("file:///tmp/task.yaml").unwrap();
 
-    let diagnostic = create_diagnostic("Missing required field 'metadata'", 0, 0, 10);
+    let diagnostic = create_diagnostic(
+        "Missing required field 'metadata'",
+        0,
+        0,
+        10,
+        Some(Fix::AddField {
+            field: "metadata".to_string(),
+        }),
+    );
     let actions = provider.provide_actions(&uri, &[diagnostic]);
 
     assert_eq!(actions.len(), 1, "Should return one action");
@@ -79,7 +99,15 @@ fn test_quick_fix_for_missing_spec() {
     let provider = CodeActionsProvider::new();
     let uri = Url::parse("file:///tmp/task.yaml").unwrap();
 
-    let diagnostic = create_diagnostic("Missing required field 'spec'", 3, 0, 10);
+    let diagnostic = create_diagnostic(
+        "Missing required field 'spec'",
+        3,
+        0,
+        10,
+        Some(Fix::AddField {
+            field: "spec".to_string(),
+        }),
+    );
     let actions = provider.provide_actions(&uri, &[diagnostic]);
 
     assert_eq!(actions.len(), 1);
@@ -109,7 +137,15 @@ fn test_quick_fix_for_unknown_field() {
     let provider = CodeActionsProvider::new();
     let uri = Url::parse("file:///tmp/task.yaml").unwrap();
 
-    let diagnostic = create_diagnostic("Unknown field 'unknownField' in Task spec", 5, 2, 15);
+    let diagnostic = create_diagnostic(
+        "Unknown field 'unknownField' in Task spec",
+        5,
+        2,
+        15,
+        Some(Fix::RemoveField {
+            field: "unknownField".to_string(),
+        }),
+    );
     let actions = provider.provide_actions(&uri, &[diagnostic]);
 
     assert_eq!(actions.len(), 1);
@@ -148,9 +184,33 @@ fn test_multiple_diagnostics_multiple_actions() {
     let uri = Url::parse("file:///tmp/task.yaml").unwrap();
 
     let diagnostics = vec![
-        create_diagnostic("Missing required field 'metadata'", 0, 0, 10),
-        create_diagnostic("Unknown field 'foo'", 5, 2, 5),
-        create_diagnostic("Missing required field 'steps'", 8, 0, 10),
+        create_diagnostic(
+            "Missing required field 'metadata'",
+            0,
+            0,
+            10,
+            Some(Fix::AddField {
+                field: "metadata".to_string(),
+            }),
+        ),
+        create_diagnostic(
+            "Unknown field 'foo'",
+            5,
+            2,
+            5,
+            Some(Fix::RemoveField {
+                field: "foo".to_string(),
+            }),
+        ),
+        create_diagnostic(
+            "Missing required field 'steps'",
+            8,
+            0,
+            10,
+            Some(Fix::AddField {
+                field: "steps".to_string(),
+            }),
+        ),
     ];
 
     let actions = provider.provide_actions(&uri, &diagnostics);
@@ -163,7 +223,7 @@ fn test_no_action_for_unhandled_diagnostic() {
     let provider = CodeActionsProvider::new();
     let uri = Url::parse("file:///tmp/task.yaml").unwrap();
 
-    let diagnostic = create_diagnostic("Type mismatch: expected string", 5, 10, 20);
+    let diagnostic = create_diagnostic("Type mismatch: expected string", 5, 10, 20, None);
     let actions = provider.provide_actions(&uri, &[diagnostic]);
 
     assert!(
@@ -177,13 +237,24 @@ fn test_action_has_correct_diagnostics_reference() {
     let provider = CodeActionsProvider::new();
     let uri = Url::parse("file:///tmp/task.yaml").unwrap();
 
-    let diagnostic = create_diagnostic("Missing required field 'name'", 3, 0, 10);
-    let actions = provider.provide_actions(&uri, &[diagnostic.clone()]);
+    let diagnostic = create_diagnostic(
+        "Missing required field 'name'",
+        3,
+        0,
+        10,
+        Some(Fix::AddField {
+            field: "name".to_string(),
+        }),
+    );
+    let actions = provider.provide_actions(&uri, std::slice::from_ref(&diagnostic));
 
     assert_eq!(actions.len(), 1);
 
     if let CodeActionOrCommand::CodeAction(action) = &actions[0] {
-        let action_diagnostics = action.diagnostics.as_ref().expect("Should have diagnostics");
+        let action_diagnostics = action
+            .diagnostics
+            .as_ref()
+            .expect("Should have diagnostics");
         assert_eq!(action_diagnostics.len(), 1);
         assert_eq!(action_diagnostics[0].message, diagnostic.message);
     } else {
@@ -196,7 +267,15 @@ fn test_quick_fix_for_missing_steps() {
     let provider = CodeActionsProvider::new();
     let uri = Url::parse("file:///tmp/task.yaml").unwrap();
 
-    let diagnostic = create_diagnostic("Missing required field 'steps'", 5, 0, 10);
+    let diagnostic = create_diagnostic(
+        "Missing required field 'steps'",
+        5,
+        0,
+        10,
+        Some(Fix::AddField {
+            field: "steps".to_string(),
+        }),
+    );
     let actions = provider.provide_actions(&uri, &[diagnostic]);
 
     assert_eq!(actions.len(), 1);
@@ -222,3 +301,141 @@ fn test_quick_fix_for_missing_steps() {
         panic!("Expected CodeAction");
     }
 }
+
+/// Apply a single edit to ASCII content.
+fn apply_edit(content: &str, edit: &tower_lsp::lsp_types::TextEdit) -> String {
+    let offset = |position: Position| {
+        let line_start: usize = content
+            .split_inclusive('\n')
+            .take(position.line as usize)
+            .map(str::len)
+            .sum();
+        (line_start + position.character as usize).min(content.len())
+    };
+    let (start, end) = (offset(edit.range.start), offset(edit.range.end));
+    format!("{}{}{}", &content[..start], edit.new_text, &content[end..])
+}
+
+#[test]
+fn test_quick_fix_converts_task_ref_bundle_to_resolver() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: golang-build
+        bundle: gcr.io/tekton-releases/catalog/upstream/golang-build:0.1
+      params:
+        - name: package
+          value: ./...
+"#;
+    let uri = Url::parse("file:///tmp/pipeline.yaml").unwrap();
+    let yaml_doc = tekton_lsp::parser::parse_yaml("pipeline.yaml", content).unwrap();
+    let diagnostics = tekton_lsp::validator::TektonValidator::new().validate(&yaml_doc);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "'taskRef.bundle' was removed in tekton.dev/v1; use the bundles resolver"
+    );
+    assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+
+    let actions = CodeActionsProvider::new().provide_actions(&uri, &diagnostics);
+    assert_eq!(actions.len(), 1);
+    let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+        panic!("Expected CodeAction");
+    };
+    assert_eq!(action.title, "Convert 'bundle' to the bundles resolver");
+    assert_eq!(action.kind, Some(CodeActionKind::QUICKFIX));
+
+    let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(
+        apply_edit(content, &edits[0]),
+        r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        resolver: bundles
+        params:
+          - name: bundle
+            value: gcr.io/tekton-releases/catalog/upstream/golang-build:0.1
+          - name: name
+            value: golang-build
+          - name: kind
+            value: task
+      params:
+        - name: package
+          value: ./...
+"#
+    );
+}
+
+#[test]
+fn test_remove_field_warns_about_referencing_files() {
+    let task_uri = Url::parse("file:///repo/tasks/build.yaml").unwrap();
+    let task = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  unknownField: true
+  steps:
+    - image: alpine"#;
+    let pipeline = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: compile
+      taskRef:
+        name: build
+    - name: recompile
+      taskRef:
+        name: build"#;
+
+    let index = WorkspaceIndex::new();
+    index.index_document(&task_uri, task).unwrap();
+    index
+        .index_document(
+            &Url::parse("file:///repo/pipelines/release.yaml").unwrap(),
+            pipeline,
+        )
+        .unwrap();
+    let provider = CodeActionsProvider::with_index(index.clone());
+
+    let diagnostic = create_diagnostic(
+        "Unknown field 'unknownField' in Task spec",
+        5,
+        2,
+        14,
+        Some(Fix::RemoveField {
+            field: "unknownField".to_string(),
+        }),
+    );
+    let actions = provider.provide_actions(&task_uri, std::slice::from_ref(&diagnostic));
+    let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+        panic!("Expected CodeAction");
+    };
+    assert_eq!(
+        action.title,
+        "Remove unknown field 'unknownField' (warning: Task 'build' is referenced from release.yaml)"
+    );
+    assert!(action.edit.is_some(), "The removal is still offered");
+
+    // Nothing references the Pipeline
+    let pipeline_uri = Url::parse("file:///repo/pipelines/release.yaml").unwrap();
+    assert!(index.inbound_references(&pipeline_uri).is_empty());
+    let actions = provider.provide_actions(&pipeline_uri, &[diagnostic]);
+    let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+        panic!("Expected CodeAction");
+    };
+    assert_eq!(action.title, "Remove unknown field 'unknownField'");
+}
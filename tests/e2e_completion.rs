@@ -3,8 +3,10 @@
 //! These tests verify that the completion provider returns appropriate
 //! suggestions based on cursor position and document context.
 
-use tekton_lsp::{parser, completion::CompletionProvider};
-use tower_lsp::lsp_types::Position;
+use tekton_lsp::{completion::CompletionProvider, parser};
+use tower_lsp::lsp_types::{
+    CompletionTextEdit, Documentation, InsertTextFormat, MarkupKind, Position,
+};
 
 // TDD Cycle 1: Basic metadata completion
 #[test]
@@ -16,31 +18,42 @@ metadata:
   namespace: default"#;
 
     // Parse the document
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
 
     // Create completion provider
     let provider = CompletionProvider::new();
 
     // When: Request completions in metadata section (at beginning of "namespace" line)
     let position = Position {
-        line: 3,  // The "namespace: default" line
-        character: 2,  // At start of "namespace"
+        line: 3,      // The "namespace: default" line
+        character: 2, // At start of "namespace"
     };
 
     let completions = provider.provide_completions(&yaml_doc, position);
 
     // Then: Should suggest metadata fields
     let labels: Vec<String> = completions.iter().map(|c| c.label.clone()).collect();
-    
-    assert!(labels.contains(&"name".to_string()), 
-        "Should suggest 'name' field. Got: {:?}", labels);
-    assert!(labels.contains(&"namespace".to_string()),
-        "Should suggest 'namespace' field. Got: {:?}", labels);
-    assert!(labels.contains(&"labels".to_string()),
-        "Should suggest 'labels' field. Got: {:?}", labels);
-    assert!(labels.contains(&"annotations".to_string()),
-        "Should suggest 'annotations' field. Got: {:?}", labels);
+
+    assert!(
+        labels.contains(&"name".to_string()),
+        "Should suggest 'name' field. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"namespace".to_string()),
+        "Should suggest 'namespace' field. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"labels".to_string()),
+        "Should suggest 'labels' field. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"annotations".to_string()),
+        "Should suggest 'annotations' field. Got: {:?}",
+        labels
+    );
 }
 
 // TDD Cycle 2: Pipeline spec completion
@@ -53,21 +66,32 @@ metadata:
 spec:
   params: []"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
     let provider = CompletionProvider::new();
 
-    let position = Position { line: 5, character: 2 };  // In spec section, on "params" line
+    let position = Position {
+        line: 5,
+        character: 2,
+    }; // In spec section, on "params" line
     let completions = provider.provide_completions(&yaml_doc, position);
 
     let labels: Vec<String> = completions.iter().map(|c| c.label.clone()).collect();
 
-    assert!(labels.contains(&"tasks".to_string()),
-        "Should suggest 'tasks'. Got: {:?}", labels);
-    assert!(labels.contains(&"params".to_string()),
-        "Should suggest 'params'. Got: {:?}", labels);
-    assert!(labels.contains(&"workspaces".to_string()),
-        "Should suggest 'workspaces'. Got: {:?}", labels);
+    assert!(
+        labels.contains(&"tasks".to_string()),
+        "Should suggest 'tasks'. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"params".to_string()),
+        "Should suggest 'params'. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"workspaces".to_string()),
+        "Should suggest 'workspaces'. Got: {:?}",
+        labels
+    );
 }
 
 // TDD Cycle 3: PipelineTask fields completion
@@ -81,21 +105,32 @@ spec:
   tasks:
     - name: build"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
     let provider = CompletionProvider::new();
 
-    let position = Position { line: 6, character: 6 };  // In tasks array, on "name" field
+    let position = Position {
+        line: 6,
+        character: 6,
+    }; // In tasks array, on "name" field
     let completions = provider.provide_completions(&yaml_doc, position);
 
     let labels: Vec<String> = completions.iter().map(|c| c.label.clone()).collect();
 
-    assert!(labels.contains(&"name".to_string()),
-        "Should suggest 'name'. Got: {:?}", labels);
-    assert!(labels.contains(&"taskRef".to_string()),
-        "Should suggest 'taskRef'. Got: {:?}", labels);
-    assert!(labels.contains(&"taskSpec".to_string()),
-        "Should suggest 'taskSpec'. Got: {:?}", labels);
+    assert!(
+        labels.contains(&"name".to_string()),
+        "Should suggest 'name'. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"taskRef".to_string()),
+        "Should suggest 'taskRef'. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"taskSpec".to_string()),
+        "Should suggest 'taskSpec'. Got: {:?}",
+        labels
+    );
 }
 
 // TDD Cycle 4: Task spec completion
@@ -108,21 +143,32 @@ metadata:
 spec:
   params: []"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
     let provider = CompletionProvider::new();
 
-    let position = Position { line: 5, character: 2 };  // In Task spec, on "params" line
+    let position = Position {
+        line: 5,
+        character: 2,
+    }; // In Task spec, on "params" line
     let completions = provider.provide_completions(&yaml_doc, position);
 
     let labels: Vec<String> = completions.iter().map(|c| c.label.clone()).collect();
 
-    assert!(labels.contains(&"steps".to_string()),
-        "Should suggest 'steps'. Got: {:?}", labels);
-    assert!(labels.contains(&"params".to_string()),
-        "Should suggest 'params'. Got: {:?}", labels);
-    assert!(labels.contains(&"workspaces".to_string()),
-        "Should suggest 'workspaces'. Got: {:?}", labels);
+    assert!(
+        labels.contains(&"steps".to_string()),
+        "Should suggest 'steps'. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"params".to_string()),
+        "Should suggest 'params'. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"workspaces".to_string()),
+        "Should suggest 'workspaces'. Got: {:?}",
+        labels
+    );
 }
 
 // TDD Cycle 5: Step fields completion
@@ -136,21 +182,1869 @@ spec:
   steps:
     - name: build"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+
+    let position = Position {
+        line: 6,
+        character: 6,
+    }; // In steps array, on "name" field
+    let completions = provider.provide_completions(&yaml_doc, position);
+
+    let labels: Vec<String> = completions.iter().map(|c| c.label.clone()).collect();
+
+    assert!(
+        labels.contains(&"name".to_string()),
+        "Should suggest 'name'. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"image".to_string()),
+        "Should suggest 'image'. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"script".to_string()),
+        "Should suggest 'script'. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"command".to_string()),
+        "Should suggest 'command'. Got: {:?}",
+        labels
+    );
+}
+
+// TDD Cycle 6: taskRef kind and apiVersion values
+#[test]
+fn test_complete_leaves_out_present_keys() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: test-task
+spec:
+  steps:
+    - image: alpine
+      sc
+      script: echo hello
+"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+    let completions = provider.provide_completions(
+        &yaml_doc,
+        Position {
+            line: 7,
+            character: 8,
+        },
+    );
+    let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+    assert!(labels.contains(&"command"), "Got: {:?}", labels);
+    assert!(
+        !labels.contains(&"image") && !labels.contains(&"script"),
+        "Should not suggest keys the step has. Got: {:?}",
+        labels
+    );
+
+    // The missing required name is preselected
+    let preselected: Vec<&str> = completions
+        .iter()
+        .filter(|c| c.preselect == Some(true))
+        .map(|c| c.label.as_str())
+        .collect();
+    assert_eq!(preselected, vec!["name"]);
+
+    // The key on the cursor's line can be retyped
+    let completions = provider.provide_completions(
+        &yaml_doc,
+        Position {
+            line: 6,
+            character: 6,
+        },
+    );
+    assert!(completions.iter().any(|c| c.label == "image"));
+}
+
+#[test]
+fn test_complete_task_ref_kind_values() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build-task
+        kind: "#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+
+    let index = tekton_lsp::workspace::WorkspaceIndex::new();
+    let crd_uri = tower_lsp::lsp_types::Url::parse("file:///crds/approval.yaml").unwrap();
+    index
+        .index_document(
+            &crd_uri,
+            r#"apiVersion: apiextensions.k8s.io/v1
+kind: CustomResourceDefinition
+metadata:
+  name: approvaltasks.openshift-pipelines.org
+spec:
+  group: openshift-pipelines.org
+  names:
+    kind: ApprovalTask
+  versions:
+    - name: v1alpha1"#,
+        )
+        .unwrap();
+    let provider = CompletionProvider::with_index(index);
+
+    let position = Position {
+        line: 9,
+        character: 14,
+    }; // After "kind: "
+    let completions = provider.provide_completions(&yaml_doc, position);
+
+    let labels: Vec<String> = completions.iter().map(|c| c.label.clone()).collect();
+
+    assert_eq!(
+        labels,
+        vec!["Task", "ClusterTask", "ApprovalTask"],
+        "Should suggest taskRef kinds. Got: {:?}",
+        labels
+    );
+}
+
+#[test]
+fn test_complete_task_ref_api_version_values() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build-task
+        kind: Task
+        apiVersion: "#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+
+    let position = Position {
+        line: 10,
+        character: 20,
+    };
+    let completions = provider.provide_completions(&yaml_doc, position);
+
+    let labels: Vec<String> = completions.iter().map(|c| c.label.clone()).collect();
+
+    assert!(
+        labels.contains(&"tekton.dev/v1".to_string()),
+        "Should suggest 'tekton.dev/v1'. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"tekton.dev/v1beta1".to_string()),
+        "Should suggest 'tekton.dev/v1beta1'. Got: {:?}",
+        labels
+    );
+}
+
+#[test]
+fn test_complete_task_ref_fields() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build-task"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+
+    let position = Position {
+        line: 8,
+        character: 8,
+    }; // On "name" inside taskRef
+    let completions = provider.provide_completions(&yaml_doc, position);
+
+    let labels: Vec<String> = completions.iter().map(|c| c.label.clone()).collect();
+
+    assert!(
+        labels.contains(&"kind".to_string()),
+        "Should suggest 'kind'. Got: {:?}",
+        labels
+    );
+    assert!(
+        labels.contains(&"apiVersion".to_string()),
+        "Should suggest 'apiVersion'. Got: {:?}",
+        labels
+    );
+    assert!(
+        !labels.contains(&"runAfter".to_string()),
+        "Should not suggest pipeline task fields. Got: {:?}",
+        labels
+    );
+}
+
+#[test]
+fn test_complete_resolver_params() {
+    let pipeline = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        resolver: 
+        params:
+          - name: url
+            value: https://github.com/tektoncd/catalog
+          - name: 
+"#;
+    let run = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  name: test
+spec:
+  pipelineRef:
+    resolver: cluster
+    params:
+      - name: 
+"#;
+    let provider = CompletionProvider::new();
+    let labels = |content: &str, line, character| -> Vec<String> {
+        let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+
+    assert_eq!(
+        labels(pipeline, 8, 18),
+        vec!["git", "hub", "bundles", "cluster"]
+    );
+    // No resolver yet: nothing known about its params
+    assert!(!labels(pipeline, 12, 18).contains(&"pathInRepo".to_string()));
+
+    let pipeline = pipeline.replace("resolver: ", "resolver: git");
+    assert_eq!(
+        labels(&pipeline, 12, 18),
+        vec![
+            "revision",
+            "pathInRepo",
+            "repo",
+            "org",
+            "token",
+            "tokenKey",
+            "scmType",
+            "serverURL"
+        ]
+    );
+    assert_eq!(labels(run, 8, 14), vec!["kind", "name", "namespace"]);
+}
+
+#[test]
+fn test_complete_list_item_starter_on_empty_line() {
+    let content = "apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build-task
+    
+";
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+
+    // At the indentation of the `-` markers, below the last task
+    let position = Position {
+        line: 9,
+        character: 4,
+    };
+    let completions = provider.provide_completions(&yaml_doc, position);
+
+    let starter = completions.first().expect("Should offer a completion");
+    assert_eq!(starter.label, "- name");
+    assert_eq!(starter.insert_text.as_deref(), Some("- name: $0"));
+    assert_eq!(starter.insert_text_format, Some(InsertTextFormat::SNIPPET));
+    assert_eq!(starter.detail.as_deref(), Some("New item of 'tasks'"));
+
+    // Any other column is not an item position
+    let position = Position {
+        line: 9,
+        character: 6,
+    };
+    let completions = provider.provide_completions(&yaml_doc, position);
+    assert!(
+        completions.iter().all(|c| c.label != "- name"),
+        "Should not offer a list item. Got: {:?}",
+        completions
+    );
+}
+
+#[test]
+fn test_complete_list_item_starter_for_empty_list() {
+    let content = "apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: test
+spec:
+  steps:
+    
+";
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
     let provider = CompletionProvider::new();
 
-    let position = Position { line: 6, character: 6 };  // In steps array, on "name" field
+    let position = Position {
+        line: 6,
+        character: 4,
+    };
+    let completions = provider.provide_completions(&yaml_doc, position);
+
+    let starter = completions.first().expect("Should offer a completion");
+    assert_eq!(starter.label, "- name");
+    assert_eq!(starter.detail.as_deref(), Some("New item of 'steps'"));
+}
+
+#[test]
+fn test_complete_list_fields_as_snippets() {
+    let pipeline = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test
+spec:
+  params: []"#;
+    let task = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: test
+spec:
+  params: []"#;
+    let provider = CompletionProvider::new();
+    let item = |content: &str, label: &str| {
+        let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+        provider
+            .provide_completions(
+                &yaml_doc,
+                Position {
+                    line: 5,
+                    character: 2,
+                },
+            )
+            .into_iter()
+            .find(|c| c.label == label)
+            .unwrap_or_else(|| panic!("Should suggest '{}'", label))
+    };
+
+    let tasks = item(pipeline, "tasks");
+    assert_eq!(tasks.insert_text_format, Some(InsertTextFormat::SNIPPET));
+    assert_eq!(
+        tasks.insert_text.as_deref(),
+        Some("tasks:\n  - name: ${1:name}\n    taskRef:\n      name: ${2:task}")
+    );
+    let steps = item(task, "steps");
+    assert_eq!(
+        steps.insert_text.as_deref(),
+        Some("steps:\n  - name: ${1:name}\n    image: ${2:image}\n    script: |\n      ${3:echo hello}")
+    );
+    // Other fields are inserted as their name
+    assert_eq!(item(task, "params").insert_text, None);
+
+    // Clients without snippet support get plain items
+    provider.set_snippet_support(false);
+    let tasks = item(pipeline, "tasks");
+    assert_eq!(tasks.insert_text, None);
+    assert_eq!(tasks.insert_text_format, None);
+}
+
+#[test]
+fn test_resolve_field_documentation() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test
+spec:
+  params: []"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+    let completions = provider.provide_completions(
+        &yaml_doc,
+        Position {
+            line: 5,
+            character: 2,
+        },
+    );
+
+    // The list only carries the short description
+    assert!(completions.iter().all(|c| c.documentation.is_none()));
+    let tasks = completions
+        .into_iter()
+        .find(|c| c.label == "tasks")
+        .expect("Should suggest 'tasks'");
+
+    let resolved = provider.resolve(tasks);
+    let Some(Documentation::MarkupContent(doc)) = resolved.documentation else {
+        panic!("Expected Markdown documentation");
+    };
+    assert_eq!(doc.kind, MarkupKind::Markdown);
+    assert!(
+        doc.value.starts_with("# Field `tasks` of Pipeline\n\n"),
+        "Got: {}",
+        doc.value
+    );
+    // The fields of a pipeline task, from the same schema as the completions
+    assert!(
+        doc.value.contains("\n| `taskRef` | object | no |"),
+        "Got: {}",
+        doc.value
+    );
+}
+
+#[test]
+fn test_complete_workspace_mount_path_default() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  workspaces:
+    - name: source
+      mountPath: 
+  steps:
+    - image: golang"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+
+    let position = Position {
+        line: 7,
+        character: 17,
+    }; // After "mountPath: "
     let completions = provider.provide_completions(&yaml_doc, position);
 
     let labels: Vec<String> = completions.iter().map(|c| c.label.clone()).collect();
+    assert_eq!(
+        labels,
+        vec!["/workspace/source"],
+        "Should suggest the default mount path. Got: {:?}",
+        labels
+    );
+}
+
+#[test]
+fn test_complete_step_artifacts_fields() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: test-task
+spec:
+  steps:
+    - name: build
+      image: alpine
+      artifacts:
+        ou"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+
+    // On a step without it, 'artifacts' is offered for tekton.dev/v1
+    let step = content.replace("      artifacts:\n        ou", "");
+    let step_doc = parser::parse_yaml("test.yaml", &step).expect("Failed to parse YAML");
+    let completions = provider.provide_completions(
+        &step_doc,
+        Position {
+            line: 6,
+            character: 6,
+        },
+    );
+    let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+    assert!(
+        labels.contains(&"artifacts"),
+        "Should suggest 'artifacts'. Got: {:?}",
+        labels
+    );
+
+    // Inside 'artifacts', its inputs and outputs
+    let completions = provider.provide_completions(
+        &yaml_doc,
+        Position {
+            line: 9,
+            character: 10,
+        },
+    );
+    let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+    assert_eq!(labels, vec!["inputs", "outputs"]);
+
+    // Artifacts do not exist in tekton.dev/v1beta1
+    let content = step.replace("tekton.dev/v1", "tekton.dev/v1beta1");
+    let yaml_doc = parser::parse_yaml("test.yaml", &content).expect("Failed to parse YAML");
+    let completions = provider.provide_completions(
+        &yaml_doc,
+        Position {
+            line: 6,
+            character: 6,
+        },
+    );
+    let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+    assert!(labels.contains(&"script"));
+    assert!(
+        !labels.contains(&"artifacts"),
+        "Should not suggest 'artifacts'. Got: {:?}",
+        labels
+    );
+}
+
+#[test]
+fn test_complete_step_security_and_resource_fields() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: test-task
+spec:
+  steps:
+    - name: build
+      image: alpine
+      securityContext:
+        ru
+      computeResources:
+        re
+      volumeMounts:
+        - na
+      stdoutConfig:
+        pa"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+    let labels = |line, character| -> Vec<String> {
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+
+    // On a step without them, the fields are offered
+    let step_only = content.split("      securityContext").next().unwrap();
+    let step_doc = parser::parse_yaml("test.yaml", step_only).expect("Failed to parse YAML");
+    let step: Vec<String> = provider
+        .provide_completions(
+            &step_doc,
+            Position {
+                line: 6,
+                character: 6,
+            },
+        )
+        .into_iter()
+        .map(|c| c.label)
+        .collect();
+    for field in [
+        "securityContext",
+        "computeResources",
+        "volumeMounts",
+        "timeout",
+    ] {
+        assert!(
+            step.contains(&field.to_string()),
+            "Should suggest '{}'. Got: {:?}",
+            field,
+            step
+        );
+    }
+    assert_eq!(
+        labels(9, 10),
+        vec![
+            "runAsNonRoot",
+            "runAsUser",
+            "runAsGroup",
+            "privileged",
+            "allowPrivilegeEscalation",
+            "readOnlyRootFilesystem",
+            "capabilities",
+            "seccompProfile"
+        ]
+    );
+    assert_eq!(labels(11, 10), vec!["requests", "limits"]);
+    assert_eq!(
+        labels(13, 12),
+        vec!["name", "mountPath", "readOnly", "subPath"]
+    );
+    assert_eq!(labels(15, 10), vec!["path"]);
+}
+
+#[test]
+fn test_complete_values_instead_of_fields_after_colon() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: test-task
+spec:
+  steps:
+    - name: build
+      image: alpine
+      onError: 
+      securityContext:
+        privileged: 
+      script: |
+        echo "url: $URL"
+"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+    let labels = |line, character| -> Vec<String> {
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+
+    // On a key, field names
+    assert!(labels(7, 6).contains(&"image".to_string()));
+    // On a value, no field names
+    assert!(labels(7, 13).is_empty(), "Got: {:?}", labels(7, 13));
+    assert!(labels(6, 14).is_empty(), "Got: {:?}", labels(6, 14));
+    // Values of fields taking one of a few strings, and of boolean fields
+    assert_eq!(labels(8, 15), vec!["continue", "stopAndFail"]);
+    assert_eq!(labels(10, 20), vec!["true", "false"]);
+    // Nor in a script line that looks like a key
+    assert!(labels(12, 18).is_empty(), "Got: {:?}", labels(12, 18));
+}
+
+#[test]
+fn test_complete_step_on_error_and_output_fields() {
+    let content = r#"apiVersion: tekton.dev/v1beta1
+kind: Task
+metadata:
+  name: test-task
+spec:
+  steps:
+    - name: build
+      image: alpine
+      "#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+    let completions = provider.provide_completions(
+        &yaml_doc,
+        Position {
+            line: 8,
+            character: 6,
+        },
+    );
+    let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+    for field in ["onError", "stdoutConfig", "stderrConfig"] {
+        assert!(
+            labels.contains(&field),
+            "Should suggest '{}'. Got: {:?}",
+            field,
+            labels
+        );
+    }
+}
+
+#[test]
+fn test_complete_inside_matrix() {
+    let header = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test-pipeline
+spec:
+  params:
+    - name: platforms
+      type: array
+    - name: browsers
+      type: array
+    - name: version
+  tasks:
+    - name: test
+      taskRef:
+        name: browser-test
+      matrix:
+"#;
+    let provider = CompletionProvider::new();
+    let labels_at = |body: &str, line, character| -> Vec<String> {
+        let yaml_doc = parser::parse_yaml("test.yaml", &format!("{}{}", header, body))
+            .expect("Failed to parse YAML");
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+
+    // Fields of the matrix itself
+    assert_eq!(labels_at("        pa", 16, 10), vec!["params", "include"]);
+
+    // Matrix params are named after, and take the values of, array params
+    let params = "        params:\n          - name: \n            value: x";
+    assert_eq!(labels_at(params, 17, 18), vec!["platforms", "browsers"]);
+    assert_eq!(
+        labels_at(params, 18, 19),
+        vec!["$(params.platforms[*])", "$(params.browsers[*])"]
+    );
+
+    // Entries of include, and their params
+    assert_eq!(
+        labels_at("        include:\n          - na", 17, 14),
+        vec!["name", "params"]
+    );
+    let include =
+        "        include:\n          - name: chrome\n            params:\n              - na";
+    assert_eq!(labels_at(include, 19, 18), vec!["name", "value"]);
+}
+
+#[test]
+fn test_complete_pac_variables_inside_template() {
+    let provider = CompletionProvider::new();
+    let content = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  name: pull-request
+spec:
+  params:
+    - name: revision
+      value: "{{ rev }}""#;
+
+    // Inside `{{ rev` on line 7
+    let position = Position {
+        line: 7,
+        character: 20,
+    };
+    let yaml_doc = parser::parse_yaml("file:///repo/.tekton/pull-request.yaml", content)
+        .expect("Failed to parse YAML");
+    let completions = provider.provide_completions(&yaml_doc, position);
+    let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+    assert!(
+        labels.contains(&"revision"),
+        "Should offer PAC variables: {:?}",
+        labels
+    );
+    assert!(labels.contains(&"source_branch"));
+    assert!(!labels.contains(&"value"));
+
+    // After the closing braces there is no template to complete
+    let after = Position {
+        line: 7,
+        character: 23,
+    };
+    let completions = provider.provide_completions(&yaml_doc, after);
+    assert!(!completions.iter().any(|c| c.label == "revision"));
+
+    // Outside .tekton/ the braces are plain text
+    let yaml_doc = parser::parse_yaml("file:///repo/tekton/pull-request.yaml", content)
+        .expect("Failed to parse YAML");
+    let completions = provider.provide_completions(&yaml_doc, position);
+    assert!(!completions.iter().any(|c| c.label == "revision"));
+}
+
+#[test]
+fn test_complete_step_action_spec_fields() {
+    let content = r#"apiVersion: tekton.dev/v1beta1
+kind: StepAction
+metadata:
+  name: git-clone
+spec:
+  params: []"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+
+    let position = Position {
+        line: 5,
+        character: 2,
+    };
+    let completions = provider.provide_completions(&yaml_doc, position);
+    let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+
+    assert!(labels.contains(&"image"), "Got: {:?}", labels);
+    assert!(labels.contains(&"script"), "Got: {:?}", labels);
+    assert!(labels.contains(&"results"), "Got: {:?}", labels);
+    assert!(!labels.contains(&"steps"), "Got: {:?}", labels);
+}
+
+#[test]
+fn test_complete_when_expression_fields() {
+    let yaml = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test-pipeline
+spec:
+  tasks:
+    - name: deploy
+      taskRef:
+        name: deploy
+      when:
+        - ce
+"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", yaml).expect("Failed to parse YAML");
+    let labels: Vec<String> = CompletionProvider::new()
+        .provide_completions(
+            &yaml_doc,
+            Position {
+                line: 10,
+                character: 12,
+            },
+        )
+        .into_iter()
+        .map(|c| c.label)
+        .collect();
+
+    assert_eq!(labels, vec!["input", "operator", "values", "cel"]);
+}
+
+#[test]
+fn test_complete_when_expression_values() {
+    let yaml = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test-pipeline
+spec:
+  params:
+    - name: environment
+      description: Where to deploy
+      default: staging
+    - name: targets
+      type: array
+  tasks:
+    - name: clone
+      taskRef:
+        name: git-clone
+    - name: check
+      taskSpec:
+        results:
+          - name: changed
+            description: Whether sources changed
+          - name: files
+            type: array
+        steps:
+          - image: alpine
+    - name: deploy
+      taskRef:
+        name: deploy
+      when:
+        - input: 
+          operator: 
+          values: ["true"]
+"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", yaml).expect("Failed to parse YAML");
+
+    let index = tekton_lsp::workspace::WorkspaceIndex::new();
+    index
+        .index_document(
+            &tower_lsp::lsp_types::Url::parse("file:///tasks/git-clone.yaml").unwrap(),
+            r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: git-clone
+spec:
+  results:
+    - name: commit
+  steps:
+    - image: alpine/git
+"#,
+        )
+        .unwrap();
+    let provider = CompletionProvider::with_index(index);
+    let found = |line, character| -> Vec<(String, String)> {
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| (c.label, c.detail.unwrap_or_default()))
+            .collect()
+    };
+    let pair = |label: &str, detail: &str| (label.to_string(), detail.to_string());
+
+    // String params and results, not arrays
+    assert_eq!(
+        found(28, 17),
+        vec![
+            pair("$(params.environment)", "string param, default: staging"),
+            pair(
+                "$(tasks.clone.results.commit)",
+                "string result of Task 'git-clone'"
+            ),
+            pair(
+                "$(tasks.check.results.changed)",
+                "string result of task 'check'"
+            ),
+        ]
+    );
+    assert_eq!(
+        found(29, 20),
+        vec![
+            pair("in", "The input is one of the values"),
+            pair("notin", "The input is none of the values"),
+        ]
+    );
+}
+
+#[test]
+fn test_complete_params_in_variable() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  params:
+    - name: revision
+      description: Revision to build
+      default: main
+    - name: flags
+      type: array
+  steps:
+    - name: build
+      image: golang:1.21
+      args: ["$(params.flags[*])"]
+      script: |
+        git checkout $(params.re
+        go build $(
+"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+
+    // After `$(params.re` in a block scalar
+    let completions = provider.provide_completions(
+        &yaml_doc,
+        Position {
+            line: 16,
+            character: 32,
+        },
+    );
+    let found: Vec<_> = completions
+        .iter()
+        .map(|c| (c.label.as_str(), c.detail.as_deref().unwrap_or_default()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("params.revision", "string param, default: main"),
+            ("params.flags[*]", "array param"),
+        ]
+    );
+    let Some(CompletionTextEdit::Edit(edit)) = &completions[0].text_edit else {
+        panic!("Expected a text edit, got {:?}", completions[0]);
+    };
+    assert_eq!(edit.new_text, "params.revision)");
+    assert_eq!(edit.range.start.character, 23);
+    assert_eq!(edit.range.end.character, 32);
+
+    // Right after `$(`
+    let completions = provider.provide_completions(
+        &yaml_doc,
+        Position {
+            line: 17,
+            character: 19,
+        },
+    );
+    assert_eq!(completions.len(), 2);
+
+    // Inside a closed expression, the name is replaced and no `)` added
+    let completions = provider.provide_completions(
+        &yaml_doc,
+        Position {
+            line: 14,
+            character: 20,
+        },
+    );
+    let Some(CompletionTextEdit::Edit(edit)) = &completions[1].text_edit else {
+        panic!("Expected a text edit, got {:?}", completions[1]);
+    };
+    assert_eq!(edit.new_text, "params.flags[*]");
+    assert_eq!(edit.range.start.character, 16);
+    assert_eq!(edit.range.end.character, 31);
+}
+
+#[test]
+fn test_complete_params_in_inline_task_spec() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  params:
+    - name: repo-url
+  tasks:
+    - name: build
+      taskSpec:
+        params:
+          - name: target
+        steps:
+          - image: alpine
+            script: echo $(params.
+    - name: notify
+      params:
+        - name: url
+          value: $(
+"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+
+    let labels = |line, character| -> Vec<String> {
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+    assert_eq!(labels(14, 34), vec!["params.target", "params.repo-url"]);
+    assert_eq!(labels(18, 19), vec!["params.repo-url", "tasks.build"]);
+}
+
+#[test]
+fn test_complete_task_results_in_variable() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: clone
+      taskRef:
+        name: git-clone
+    - name: build
+      taskSpec:
+        results:
+          - name: digest
+            description: Digest of the image
+          - name: tags
+            type: array
+        steps:
+          - image: alpine
+            script: echo $(tasks.clone.results.
+    - name: push
+      params:
+        - name: image
+          value: $(tasks.
+        - name: digest
+          value: $(tasks.build.results.di
+"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+
+    let index = tekton_lsp::workspace::WorkspaceIndex::new();
+    index
+        .index_document(
+            &tower_lsp::lsp_types::Url::parse("file:///tasks/git-clone.yaml").unwrap(),
+            r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: git-clone
+spec:
+  results:
+    - name: commit
+    - name: url
+  steps:
+    - image: alpine/git
+"#,
+        )
+        .unwrap();
+    let provider = CompletionProvider::with_index(index);
+    let completions =
+        |line, character| provider.provide_completions(&yaml_doc, Position { line, character });
+    let labels = |line, character| -> Vec<String> {
+        completions(line, character)
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+
+    // The other tasks, without closing the expression
+    assert_eq!(labels(22, 24), vec!["tasks.clone", "tasks.build"]);
+    let Some(CompletionTextEdit::Edit(edit)) = &completions(22, 24)[0].text_edit else {
+        panic!("Expected a text edit");
+    };
+    assert_eq!(edit.new_text, "tasks.clone");
+
+    // Results of an inline taskSpec, with their type
+    let items = completions(24, 40);
+    let found: Vec<_> = items
+        .iter()
+        .map(|c| (c.label.as_str(), c.detail.as_deref().unwrap_or_default()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                "tasks.build.results.digest",
+                "string result of task 'build'"
+            ),
+            (
+                "tasks.build.results.tags[*]",
+                "array result of task 'build'"
+            ),
+        ]
+    );
+    let Some(CompletionTextEdit::Edit(edit)) = &items[0].text_edit else {
+        panic!("Expected a text edit");
+    };
+    assert_eq!(edit.new_text, "tasks.build.results.digest)");
+
+    // Results of the indexed Task a taskRef names
+    assert_eq!(
+        labels(18, 47),
+        vec!["tasks.clone.results.commit", "tasks.clone.results.url"]
+    );
+}
+
+#[test]
+fn test_complete_workspaces_in_variable() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  workspaces:
+    - name: source
+    - name: cache
+      description: Go build cache
+      optional: true
+  steps:
+    - name: build
+      image: golang:1.21
+      workingDir: $(workspaces.
+      script: |
+        ls $(workspaces.cache.
+"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+    let found = |line, character| -> Vec<(String, String)> {
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| (c.label, c.detail.unwrap_or_default()))
+            .collect()
+    };
+
+    assert_eq!(
+        found(13, 31),
+        vec![
+            ("workspaces.source".to_string(), "Workspace".to_string()),
+            (
+                "workspaces.cache".to_string(),
+                "Optional workspace".to_string()
+            ),
+        ]
+    );
+    let labels: Vec<_> = found(15, 30).into_iter().map(|(label, _)| label).collect();
+    assert_eq!(
+        labels,
+        vec![
+            "workspaces.cache.path",
+            "workspaces.cache.bound",
+            "workspaces.cache.claim",
+            "workspaces.cache.volume",
+        ]
+    );
+
+    // Pipelines can only check whether their workspaces are bound
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  workspaces:
+    - name: cache
+  tasks:
+    - name: warm
+      when:
+        - input: $(workspaces.cache.
+"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let labels: Vec<_> = provider
+        .provide_completions(
+            &yaml_doc,
+            Position {
+                line: 10,
+                character: 36,
+            },
+        )
+        .into_iter()
+        .map(|c| c.label)
+        .collect();
+    assert_eq!(labels, vec!["workspaces.cache.bound"]);
+}
+
+#[test]
+fn test_complete_run_after_task_names() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: clone
+      taskRef:
+        name: git-clone
+    - name: lint
+      taskRef:
+        name: golangci-lint
+    - name: build
+      runAfter:
+        - clone
+        - 
+      taskRef:
+        name: golang-build
+    - name: test
+      runAfter: [lint, ]
+      taskRef:
+        name: golang-test
+  finally:
+    - name: notify
+      taskRef:
+        name: send-to-slack
+"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+    let labels = |line, character| -> Vec<String> {
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+
+    // Neither the task itself, the tasks it already runs after, nor finally tasks
+    assert_eq!(labels(15, 10), vec!["lint", "test"]);
+    // Replacing an entry offers it again
+    assert_eq!(labels(14, 12), vec!["clone", "lint", "test"]);
+    // In a flow sequence
+    assert_eq!(labels(19, 23), vec!["clone", "build"]);
+}
+
+#[test]
+fn test_complete_param_names_of_referenced_task() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: clone
+      taskRef:
+        name: git-clone
+      params:
+        - name: url
+          value: https://github.com/tektoncd/pipeline
+        - name: 
+    - name: build
+      taskRef:
+        name: golang-build
+      params:
+        - name: 
+"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+
+    let index = tekton_lsp::workspace::WorkspaceIndex::new();
+    index
+        .index_document(
+            &tower_lsp::lsp_types::Url::parse("file:///tasks/git-clone.yaml").unwrap(),
+            r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: git-clone
+spec:
+  params:
+    - name: url
+      description: Repository URL to clone from
+    - name: revision
+      description: Revision to checkout
+      default: main
+    - name: refspec
+      type: array
+      default: []
+  steps:
+    - image: alpine/git
+"#,
+        )
+        .unwrap();
+    let provider = CompletionProvider::with_index(index);
+    let completions =
+        |line, character| provider.provide_completions(&yaml_doc, Position { line, character });
+
+    // The params of git-clone, except `url` which is already passed
+    let items = completions(12, 16);
+    let found: Vec<_> = items
+        .iter()
+        .map(|c| (c.label.as_str(), c.detail.as_deref().unwrap_or_default()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                "revision",
+                "string param of Task 'git-clone', default: main"
+            ),
+            ("refspec", "array param of Task 'git-clone', default: []"),
+        ]
+    );
+    assert_eq!(
+        items[0].documentation,
+        Some(Documentation::String("Revision to checkout".to_string()))
+    );
+
+    // Replacing the name of a passed param offers it again
+    let labels: Vec<_> = completions(10, 16).into_iter().map(|c| c.label).collect();
+    assert_eq!(labels, vec!["url", "revision", "refspec"]);
+
+    // A Task that is not indexed has nothing to offer
+    assert!(completions(17, 16)
+        .iter()
+        .all(|c| c.kind != Some(tower_lsp::lsp_types::CompletionItemKind::ENUM_MEMBER)));
+}
+
+#[test]
+fn test_complete_pipeline_task_workspace_bindings() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  workspaces:
+    - name: source
+      description: The cloned repository
+    - name: ssh-creds
+      optional: true
+  tasks:
+    - name: clone
+      taskRef:
+        name: git-clone
+      workspaces:
+        - name: output
+          workspace: 
+        - name: 
+"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+
+    let index = tekton_lsp::workspace::WorkspaceIndex::new();
+    index
+        .index_document(
+            &tower_lsp::lsp_types::Url::parse("file:///tasks/git-clone.yaml").unwrap(),
+            r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: git-clone
+spec:
+  workspaces:
+    - name: output
+    - name: ssh-directory
+      optional: true
+  steps:
+    - image: alpine/git
+"#,
+        )
+        .unwrap();
+    let provider = CompletionProvider::with_index(index);
+    let found = |line, character| -> Vec<(String, String)> {
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| (c.label, c.detail.unwrap_or_default()))
+            .collect()
+    };
+    let pair = |label: &str, detail: &str| (label.to_string(), detail.to_string());
+
+    // The Pipeline's workspaces
+    assert_eq!(
+        found(16, 21),
+        vec![
+            pair("source", "Workspace of the Pipeline"),
+            pair("ssh-creds", "Optional workspace of the Pipeline"),
+        ]
+    );
+    // The Task's workspaces that are not bound yet
+    assert_eq!(
+        found(17, 16),
+        vec![pair(
+            "ssh-directory",
+            "Optional workspace of Task 'git-clone'"
+        )]
+    );
+}
+
+#[test]
+fn test_complete_hub_tasks_and_versions() {
+    use tekton_lsp::integrations::hub::resource_versions_url;
+    use tekton_lsp::integrations::{HubOptions, HubResource, RemoteFile};
+
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test
+spec:
+  tasks:
+    - name: clone
+      taskRef:
+        resolver: hub
+        params:
+          - name: catalog
+            value: tekton
+          - name: name
+            value: git-clone
+          - name: version
+            value: 
+"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let dir = std::env::temp_dir().join(format!("tekton-lsp-hub-e2e-{}", std::process::id()));
+    let provider = CompletionProvider::new();
+    let completions =
+        |line, character| provider.provide_completions(&yaml_doc, Position { line, character });
+
+    // Disabled by default
+    assert!(completions(13, 19).is_empty());
+
+    provider.hub().set_options(HubOptions {
+        enabled: true,
+        cache_dir: Some(dir.clone()),
+        ..HubOptions::default()
+    });
+    let resource = |name: &str, catalog: &str, kind: &str, version: &str| HubResource {
+        id: None,
+        name: name.to_string(),
+        catalog: catalog.to_string(),
+        kind: kind.to_string(),
+        latest_version: version.to_string(),
+        description: None,
+    };
+    provider.hub().set_catalog(vec![
+        HubResource {
+            id: Some(1),
+            ..resource("git-clone", "tekton", "Task", "0.9")
+        },
+        resource("buildah", "tekton", "Task", "0.6"),
+        resource("git-clone", "other", "Task", "1.0"),
+        resource("buildpacks", "tekton", "Pipeline", "0.2"),
+    ]);
+
+    // Tasks of the catalog named by the `catalog` param
+    let found: Vec<_> = completions(13, 19)
+        .into_iter()
+        .map(|c| (c.label, c.detail.unwrap_or_default()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                "git-clone".to_string(),
+                "Task 0.9 of the 'tekton' Hub catalog".to_string()
+            ),
+            (
+                "buildah".to_string(),
+                "Task 0.6 of the 'tekton' Hub catalog".to_string()
+            ),
+        ]
+    );
+
+    // The latest version of the named task, quoted, until its versions are fetched
+    let versions_url = resource_versions_url(&HubOptions::default().url, 1);
+    provider
+        .hub()
+        .files()
+        .set(&versions_url, RemoteFile::Fetching);
+    let versions = completions(15, 19);
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].label, "0.9");
+    assert_eq!(versions[0].insert_text.as_deref(), Some("\"0.9\""));
+
+    let json = r#"{"data": {"versions": [{"version": "0.8"}, {"version": "0.9"}]}}"#;
+    provider
+        .hub()
+        .files()
+        .set(&versions_url, RemoteFile::Fetched(json.to_string()));
+    let found: Vec<_> = completions(15, 19)
+        .into_iter()
+        .map(|c| (c.label, c.detail.unwrap_or_default()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                "0.9".to_string(),
+                "Latest version of 'git-clone' in 'tekton'".to_string()
+            ),
+            (
+                "0.8".to_string(),
+                "Version of 'git-clone' in 'tekton'".to_string()
+            ),
+        ]
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_complete_top_level_document() {
+    let provider = CompletionProvider::new();
+    let completions = |content: &str, line: u32, character: u32| {
+        let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+        provider.provide_completions(&yaml_doc, Position { line, character })
+    };
+    let labels = |content: &str, line: u32, character: u32| -> Vec<String> {
+        completions(content, line, character)
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+
+    // An empty file offers the resource keys and whole resources
+    let empty = labels("", 0, 0);
+    for label in [
+        "apiVersion",
+        "kind",
+        "metadata",
+        "spec",
+        "New Tekton Task",
+        "New Pipeline",
+        "New PipelineRun",
+    ] {
+        assert!(empty.contains(&label.to_string()), "Got: {:?}", empty);
+    }
+    let task = completions("\n", 0, 0)
+        .into_iter()
+        .find(|c| c.label == "New Tekton Task")
+        .expect("Should suggest a Task");
+    assert_eq!(task.insert_text_format, Some(InsertTextFormat::SNIPPET));
+    assert!(task
+        .insert_text
+        .unwrap()
+        .starts_with("apiVersion: tekton.dev/v1\nkind: Task\nmetadata:\n  name: ${1:name}"));
+
+    // Above `spec`, the missing keys are offered, without whole resources
+    let content = r#"apiVersion: tekton.dev/v1
+
+spec:
+  steps: []"#;
+    let top = completions(content, 1, 0);
+    let top_labels: Vec<&str> = top.iter().map(|c| c.label.as_str()).collect();
+    assert_eq!(top_labels, vec!["kind", "metadata"]);
+    assert!(top.iter().all(|c| c.preselect == Some(true)));
+    // A key being typed at the top level
+    assert_eq!(labels("ki", 0, 2), empty);
+
+    // Clients without snippet support get the resource with default values
+    provider.set_snippet_support(false);
+    let pipeline_run = completions("", 0, 0)
+        .into_iter()
+        .find(|c| c.label == "New PipelineRun")
+        .expect("Should suggest a PipelineRun");
+    assert_eq!(
+        pipeline_run.insert_text_format,
+        Some(InsertTextFormat::PLAIN_TEXT)
+    );
+    assert_eq!(
+        pipeline_run.insert_text.as_deref(),
+        Some("apiVersion: tekton.dev/v1\nkind: PipelineRun\nmetadata:\n  generateName: name-\nspec:\n  pipelineRef:\n    name: pipeline")
+    );
+}
+
+#[test]
+fn test_complete_run_spec_fields() {
+    let pipeline_run = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  name: test
+spec:
+  pipelineSpec:
+    description: inline
+    tasks:
+      - name: build
+        taskRef:
+          name: build
+  params:
+    - name: revision
+      value: main
+  timeouts:
+    pipeline: 1h"#;
+    let task_run = r#"apiVersion: tekton.dev/v1
+kind: TaskRun
+metadata:
+  name: test
+spec:
+  taskSpec:
+    description: inline
+    steps:
+      - name: build
+        image: golang
+  serviceAccountName: builder"#;
+    let provider = CompletionProvider::new();
+    let labels = |content: &str, line, character| -> Vec<String> {
+        let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+
+    let run_fields = labels(pipeline_run, 14, 2);
+    for field in [
+        "pipelineRef",
+        "timeouts",
+        "taskRunSpecs",
+        "serviceAccountName",
+    ] {
+        assert!(
+            run_fields.contains(&field.to_string()),
+            "Should suggest '{}'. Got: {:?}",
+            field,
+            run_fields
+        );
+    }
+    // Present keys are left out
+    assert!(!run_fields.contains(&"pipelineSpec".to_string()));
+    assert!(!run_fields.contains(&"params".to_string()));
+    // Inside the inline pipelineSpec, the Pipeline's fields and its tasks'
+    assert!(labels(pipeline_run, 6, 4).contains(&"finally".to_string()));
+    assert!(labels(pipeline_run, 8, 8).contains(&"runAfter".to_string()));
+
+    let run_fields = labels(task_run, 10, 2);
+    for field in ["taskRef", "timeout", "podTemplate", "serviceAccountName"] {
+        assert!(
+            run_fields.contains(&field.to_string()),
+            "Should suggest '{}'. Got: {:?}",
+            field,
+            run_fields
+        );
+    }
+    assert!(!run_fields.contains(&"timeouts".to_string()));
+    // Inside the inline taskSpec, the Task's fields and its steps'
+    assert!(labels(task_run, 6, 4).contains(&"results".to_string()));
+    assert!(labels(task_run, 9, 8).contains(&"script".to_string()));
+}
+
+#[test]
+fn test_complete_triggers_fields() {
+    let event_listener = r#"apiVersion: triggers.tekton.dev/v1beta1
+kind: EventListener
+metadata:
+  name: listener
+spec:
+  serviceAccountName: tekton-triggers
+  triggers:
+    - name: on-push
+      bindings:
+        - ref: push-binding
+      template:
+        ref: build-template"#;
+    let binding = r#"apiVersion: triggers.tekton.dev/v1beta1
+kind: TriggerBinding
+metadata:
+  name: push-binding
+spec:
+  params:
+    - name: revision
+      value: $(body.head_commit.id)"#;
+    let template = r#"apiVersion: triggers.tekton.dev/v1beta1
+kind: TriggerTemplate
+metadata:
+  name: build-template
+spec:
+  params:
+    - name: revision
+  resourcetemplates: []"#;
+    let provider = CompletionProvider::new();
+    let labels = |content: &str, line, character| -> Vec<String> {
+        let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+
+    assert_eq!(
+        labels(event_listener, 5, 2),
+        vec![
+            "serviceAccountName",
+            "triggerGroups",
+            "namespaceSelector",
+            "labelSelector",
+            "resources"
+        ]
+    );
+    assert_eq!(
+        labels(event_listener, 8, 6),
+        vec![
+            "bindings",
+            "interceptors",
+            "serviceAccountName",
+            "triggerRef"
+        ]
+    );
+    // Nothing inside the values of a trigger's fields
+    assert!(labels(event_listener, 11, 8).is_empty());
+
+    assert_eq!(labels(binding, 5, 2), vec!["params"]);
+    assert_eq!(labels(binding, 7, 6), vec!["value"]);
+    assert_eq!(
+        labels(template, 6, 6),
+        vec!["name", "description", "default"]
+    );
+    assert_eq!(labels(template, 7, 2), vec!["resourcetemplates"]);
+}
+
+#[test]
+fn test_complete_declaration_enum_values() {
+    let task = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  params:
+    - name: flags
+      type: 
+  workspaces:
+    - name: source
+      readOnly: 
+      optional: t
+  steps:
+    - name: build
+      image: golang"#;
+    let pipeline = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  workspaces:
+    - name: shared
+      optional: 
+  tasks: []"#;
+    let provider = CompletionProvider::new();
+    let labels = |content: &str, line, character| -> Vec<String> {
+        let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+        provider
+            .provide_completions(&yaml_doc, Position { line, character })
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+
+    assert_eq!(labels(task, 7, 12), vec!["string", "array", "object"]);
+    assert_eq!(labels(task, 10, 16), vec!["true", "false"]);
+    assert_eq!(labels(task, 11, 17), vec!["true", "false"]);
+    assert_eq!(labels(pipeline, 7, 16), vec!["true", "false"]);
+
+    // The fields of the declarations themselves
+    assert_eq!(labels(task, 9, 6), vec!["name", "description", "mountPath"]);
+    assert_eq!(labels(pipeline, 6, 6), vec!["name", "description"]);
+    assert_eq!(
+        labels(task, 6, 6),
+        vec!["name", "description", "default", "properties", "enum"]
+    );
+}
+
+#[test]
+fn test_complete_metadata_label_and_annotation_keys() {
+    let task = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: git-clone
+  labels:
+    app.kubernetes.io/version: "0.9"
+    app.kub
+  annotations:
+    
+spec:
+  steps: []"#;
+    let provider = CompletionProvider::new();
+    let completions = |content: &str, filename: &str, line, character| {
+        let yaml_doc = parser::parse_yaml(filename, content).expect("Failed to parse YAML");
+        provider.provide_completions(&yaml_doc, Position { line, character })
+    };
+    let labels = |content: &str, filename: &str, line, character| -> Vec<String> {
+        completions(content, filename, line, character)
+            .into_iter()
+            .map(|c| c.label)
+            .collect()
+    };
+
+    let label_keys = completions(task, "test.yaml", 6, 11);
+    let keys: Vec<&str> = label_keys.iter().map(|c| c.label.as_str()).collect();
+    assert!(keys.contains(&"app.kubernetes.io/name"), "Got: {:?}", keys);
+    // Keys the labels already have are left out
+    assert!(!keys.contains(&"app.kubernetes.io/version"));
+    // The typed key is replaced, dots and all
+    let Some(CompletionTextEdit::Edit(edit)) = &label_keys[0].text_edit else {
+        panic!("Expected a text edit");
+    };
+    assert_eq!(edit.range.start, Position::new(6, 4));
+    assert_eq!(edit.range.end, Position::new(6, 11));
+    assert!(label_keys[0].documentation.is_some());
+
+    // Tekton annotations, on an empty line below the field
+    let keys = labels(task, "test.yaml", 8, 4);
+    assert!(keys.contains(&"tekton.dev/pipelines.minVersion".to_string()));
+    assert!(keys.contains(&"tekton.dev/platforms".to_string()));
+    assert!(!keys
+        .iter()
+        .any(|k| k.starts_with("pipelinesascode.tekton.dev/")));
+    // Pipelines-as-Code annotations in `.tekton/`
+    let keys = labels(task, "file:///repo/.tekton/push.yaml", 8, 4);
+    assert!(keys.contains(&"pipelinesascode.tekton.dev/on-event".to_string()));
+
+    // The metadata's own fields at its indentation
+    assert!(labels(task, "test.yaml", 7, 2).contains(&"namespace".to_string()));
+}
+
+#[test]
+fn test_completion_items_are_ranked() {
+    let event_listener = r#"apiVersion: triggers.tekton.dev/v1beta1
+kind: EventListener
+metadata:
+  name: listener
+spec:
+  triggers:
+    - name: on-push
+      bindings: []
+    - name: on-pr"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", event_listener).expect("Failed to parse YAML");
+    let provider = CompletionProvider::new();
+    let completions = provider.provide_completions(
+        &yaml_doc,
+        Position {
+            line: 7,
+            character: 6,
+        },
+    );
+    let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+
+    // The missing required field comes first, then the others in schema order
+    assert_eq!(
+        labels,
+        vec![
+            "template",
+            "bindings",
+            "interceptors",
+            "serviceAccountName",
+            "triggerRef"
+        ]
+    );
+    assert_eq!(completions[0].preselect, Some(true));
+    // Clients sorting by sort_text keep that order
+    let mut sorted = completions.clone();
+    sorted.sort_by(|a, b| a.sort_text.cmp(&b.sort_text));
+    assert_eq!(sorted, completions);
 
-    assert!(labels.contains(&"name".to_string()),
-        "Should suggest 'name'. Got: {:?}", labels);
-    assert!(labels.contains(&"image".to_string()),
-        "Should suggest 'image'. Got: {:?}", labels);
-    assert!(labels.contains(&"script".to_string()),
-        "Should suggest 'script'. Got: {:?}", labels);
-    assert!(labels.contains(&"command".to_string()),
-        "Should suggest 'command'. Got: {:?}", labels);
+    // Snippets come before the fields
+    let completions = provider.provide_completions(
+        &yaml_doc,
+        Position {
+            line: 9,
+            character: 4,
+        },
+    );
+    assert_eq!(completions[0].label, "- name");
+    assert!(completions[0].sort_text < completions[1].sort_text);
 }
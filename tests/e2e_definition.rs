@@ -45,7 +45,7 @@ spec:
         name: build-task"#;
 
     let pipeline_uri = Url::parse("file:///tmp/pipelines/main.yaml").unwrap();
-    let yaml_doc = parser::parse_yaml(&pipeline_uri.to_string(), pipeline_content)
+    let yaml_doc = parser::parse_yaml(pipeline_uri.as_ref(), pipeline_content)
         .expect("Failed to parse pipeline");
 
     // Position on "build-task" in taskRef.name (line 8)
@@ -79,7 +79,7 @@ spec:
         name: unknown-task"#;
 
     let pipeline_uri = Url::parse("file:///tmp/pipelines/main.yaml").unwrap();
-    let yaml_doc = parser::parse_yaml(&pipeline_uri.to_string(), pipeline_content)
+    let yaml_doc = parser::parse_yaml(pipeline_uri.as_ref(), pipeline_content)
         .expect("Failed to parse pipeline");
 
     let position = Position {
@@ -111,7 +111,7 @@ spec:
         name: build-task"#;
 
     let pipeline_uri = Url::parse("file:///tmp/pipelines/main.yaml").unwrap();
-    let yaml_doc = parser::parse_yaml(&pipeline_uri.to_string(), pipeline_content)
+    let yaml_doc = parser::parse_yaml(pipeline_uri.as_ref(), pipeline_content)
         .expect("Failed to parse pipeline");
 
     // Position on "Pipeline" (not a reference)
@@ -175,7 +175,7 @@ spec:
         name: task-two"#;
 
     let pipeline_uri = Url::parse("file:///tmp/pipelines/main.yaml").unwrap();
-    let yaml_doc = parser::parse_yaml(&pipeline_uri.to_string(), pipeline_content)
+    let yaml_doc = parser::parse_yaml(pipeline_uri.as_ref(), pipeline_content)
         .expect("Failed to parse pipeline");
 
     let position = Position {
@@ -185,8 +185,110 @@ spec:
 
     let definition = provider.provide_definition(&yaml_doc, position);
 
-    assert!(
-        definition.is_some(),
-        "Should find task-two definition"
+    assert!(definition.is_some(), "Should find task-two definition");
+}
+
+#[test]
+fn test_goto_step_action_definition_from_step_ref() {
+    let index = WorkspaceIndex::new();
+    let step_action_uri = Url::parse("file:///tmp/stepactions/git-clone.yaml").unwrap();
+    index
+        .index_document(
+            &step_action_uri,
+            r#"apiVersion: tekton.dev/v1beta1
+kind: StepAction
+metadata:
+  name: git-clone
+spec:
+  image: alpine/git"#,
+        )
+        .unwrap();
+    let provider = DefinitionProvider::new(index);
+
+    let task_content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - name: clone
+      ref:
+        name: git-clone"#;
+    let yaml_doc = parser::parse_yaml("file:///tmp/tasks/build.yaml", task_content)
+        .expect("Failed to parse task");
+
+    let definition = provider
+        .provide_definition(
+            &yaml_doc,
+            Position {
+                line: 8,
+                character: 15,
+            },
+        )
+        .expect("Should find the StepAction");
+    match definition {
+        tower_lsp::lsp_types::GotoDefinitionResponse::Scalar(location) => {
+            assert_eq!(location.uri, step_action_uri)
+        }
+        other => panic!("Expected scalar location, got {:?}", other),
+    }
+
+    // The step name is not a reference
+    let definition = provider.provide_definition(
+        &yaml_doc,
+        Position {
+            line: 6,
+            character: 14,
+        },
     );
+    assert!(definition.is_none());
+}
+
+#[test]
+fn test_goto_pipeline_task_and_result_definitions() {
+    let (provider, task_uri) = create_provider_with_indexed_task();
+
+    let pipeline_content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build-task
+  finally:
+    - name: notify
+      taskSpec:
+        results:
+          - name: sent
+        steps:
+          - image: alpine
+  results:
+    - name: image
+      value: $(tasks.build.results.image)
+    - name: notified
+      value: $(finally.notify.results.sent)"#;
+    let pipeline_uri = Url::parse("file:///tmp/pipelines/release.yaml").unwrap();
+    let yaml_doc = parser::parse_yaml(pipeline_uri.as_ref(), pipeline_content)
+        .expect("Failed to parse pipeline");
+    let location_at = |line, character| match provider
+        .provide_definition(&yaml_doc, Position { line, character })
+    {
+        Some(tower_lsp::lsp_types::GotoDefinitionResponse::Scalar(location)) => location,
+        other => panic!("Expected scalar location, got {:?}", other),
+    };
+
+    // A finally result goes to its declaration in the inline taskSpec
+    let location = location_at(20, 40);
+    assert_eq!(location.uri, pipeline_uri);
+    assert_eq!(location.range.start, Position::new(13, 18));
+
+    // A finally task name goes to the finally task
+    let location = location_at(20, 24);
+    assert_eq!(location.range.start, Position::new(10, 12));
+
+    // A result of a task without a taskSpec goes to the indexed Task
+    let location = location_at(18, 36);
+    assert_eq!(location.uri, task_uri);
 }
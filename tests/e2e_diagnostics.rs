@@ -10,13 +10,16 @@
 
 #[cfg(test)]
 mod e2e_tests {
-    use tower_lsp::lsp_types::*;
     use std::sync::{Arc, Mutex};
+    use tekton_lsp::workspace::WorkspaceIndex;
+    use tower_lsp::lsp_types::*;
 
     /// Test client for sending LSP requests
     struct TestClient {
         diagnostics: Arc<Mutex<Vec<PublishDiagnosticsParams>>>,
         documents: Arc<Mutex<std::collections::HashMap<String, String>>>,
+        /// Workspace index documents are added to on open, when set
+        index: Option<WorkspaceIndex>,
     }
 
     impl TestClient {
@@ -74,7 +77,13 @@ mod e2e_tests {
             };
 
             // Use real validator from production code
-            let validator = TektonValidator::new();
+            let validator = match &self.index {
+                Some(index) => {
+                    let _ = index.index_document(&Url::parse(uri).unwrap(), content);
+                    TektonValidator::with_index(index.clone())
+                }
+                None => TektonValidator::new(),
+            };
             let diagnostics_vec = validator.validate(&doc);
 
             // Store diagnostics
@@ -146,17 +155,25 @@ mod e2e_tests {
 
     /// Create test LSP client and server
     async fn create_test_lsp() -> (TestClient, TestServer) {
+        create_test_lsp_with(None).await
+    }
+
+    /// Create test LSP client and server sharing a workspace index, like the server
+    async fn create_indexed_test_lsp() -> (TestClient, TestServer) {
+        create_test_lsp_with(Some(WorkspaceIndex::new())).await
+    }
+
+    async fn create_test_lsp_with(index: Option<WorkspaceIndex>) -> (TestClient, TestServer) {
         let diagnostics = Arc::new(Mutex::new(Vec::new()));
         let documents = Arc::new(Mutex::new(std::collections::HashMap::new()));
 
         let client = TestClient {
             diagnostics: diagnostics.clone(),
             documents,
+            index,
         };
 
-        let server = TestServer {
-            diagnostics,
-        };
+        let server = TestServer { diagnostics };
 
         (client, server)
     }
@@ -184,7 +201,9 @@ spec:
 "#;
 
         client.initialize().await;
-        client.did_open("file:///test/pipeline.yaml", valid_pipeline).await;
+        client
+            .did_open("file:///test/pipeline.yaml", valid_pipeline)
+            .await;
 
         let diagnostics = server.receive_diagnostics().await;
         assert_eq!(diagnostics.len(), 0, "Valid pipeline should have no errors");
@@ -213,7 +232,9 @@ spec:
 "#;
 
         client.initialize().await;
-        client.did_open("file:///test/pipeline.yaml", invalid_pipeline).await;
+        client
+            .did_open("file:///test/pipeline.yaml", invalid_pipeline)
+            .await;
 
         let diagnostics = server.receive_diagnostics().await;
         assert_eq!(diagnostics.len(), 1);
@@ -246,7 +267,9 @@ spec:
 "#;
 
         client.initialize().await;
-        client.did_open("file:///test/pipeline.yaml", invalid_pipeline).await;
+        client
+            .did_open("file:///test/pipeline.yaml", invalid_pipeline)
+            .await;
 
         let diagnostics = server.receive_diagnostics().await;
         assert_eq!(diagnostics.len(), 1);
@@ -275,13 +298,19 @@ spec:
 "#;
 
         client.initialize().await;
-        client.did_open("file:///test/pipeline.yaml", invalid_pipeline).await;
+        client
+            .did_open("file:///test/pipeline.yaml", invalid_pipeline)
+            .await;
 
         let diagnostics = server.receive_diagnostics().await;
-        assert!(diagnostics.len() > 0);
+        assert!(!diagnostics.is_empty());
 
         let error = &diagnostics[0];
-        assert!(error.message.contains("type") || error.message.contains("array") || error.message.contains("sequence"));
+        assert!(
+            error.message.contains("type")
+                || error.message.contains("array")
+                || error.message.contains("sequence")
+        );
     }
 
     /// Test Case 5: Incremental Update Clears Error
@@ -310,23 +339,39 @@ spec:
         client.did_open("file:///test/pipeline.yaml", invalid).await;
 
         let diagnostics = server.receive_diagnostics().await;
-        assert!(diagnostics.len() > 0, "Should have error initially (missing name)");
+        assert!(
+            !diagnostics.is_empty(),
+            "Should have error initially (missing name)"
+        );
 
         // User adds the missing 'name' field
-        client.did_change("file:///test/pipeline.yaml", vec![
-            TextDocumentContentChangeEvent {
-                range: Some(Range {
-                    start: Position { line: 4, character: 0 },
-                    end: Position { line: 4, character: 0 },
-                }),
-                text: "  name: fixed-pipeline\n".to_string(),
-                range_length: None,
-            }
-        ]).await;
+        client
+            .did_change(
+                "file:///test/pipeline.yaml",
+                vec![TextDocumentContentChangeEvent {
+                    range: Some(Range {
+                        start: Position {
+                            line: 4,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: 4,
+                            character: 0,
+                        },
+                    }),
+                    text: "  name: fixed-pipeline\n".to_string(),
+                    range_length: None,
+                }],
+            )
+            .await;
 
         let updated_diagnostics = server.receive_diagnostics().await;
         // Should no longer have missing name error
-        assert_eq!(updated_diagnostics.len(), 0, "Error should be cleared after fix");
+        assert_eq!(
+            updated_diagnostics.len(),
+            0,
+            "Error should be cleared after fix"
+        );
     }
 
     /// Test Case 6: Multiple Errors in Same Document
@@ -347,17 +392,28 @@ spec:
 "#;
 
         client.initialize().await;
-        client.did_open("file:///test/pipeline.yaml", invalid_pipeline).await;
+        client
+            .did_open("file:///test/pipeline.yaml", invalid_pipeline)
+            .await;
 
         let diagnostics = server.receive_diagnostics().await;
-        assert_eq!(diagnostics.len(), 2, "Should report both errors (missing name + empty tasks)");
+        assert_eq!(
+            diagnostics.len(),
+            2,
+            "Should report both errors (missing name + empty tasks)"
+        );
 
         // Verify each error has unique position
-        let positions: Vec<_> = diagnostics.iter()
+        let positions: Vec<_> = diagnostics
+            .iter()
             .map(|d| (d.range.start.line, d.range.start.character))
             .collect();
         let unique_positions: std::collections::HashSet<_> = positions.iter().collect();
-        assert_eq!(positions.len(), unique_positions.len(), "Each error should have unique position");
+        assert_eq!(
+            positions.len(),
+            unique_positions.len(),
+            "Each error should have unique position"
+        );
     }
 
     /// Test Case 7: Unknown Field Warning
@@ -379,14 +435,17 @@ spec:
 "#;
 
         client.initialize().await;
-        client.did_open("file:///test/pipeline.yaml", pipeline_with_typo).await;
+        client
+            .did_open("file:///test/pipeline.yaml", pipeline_with_typo)
+            .await;
 
         let diagnostics = server.receive_diagnostics().await;
-        let warnings: Vec<_> = diagnostics.iter()
+        let warnings: Vec<_> = diagnostics
+            .iter()
             .filter(|d| d.severity == Some(DiagnosticSeverity::WARNING))
             .collect();
 
-        assert!(warnings.len() > 0, "Should have at least one warning");
+        assert!(!warnings.is_empty(), "Should have at least one warning");
         assert!(warnings[0].message.contains("taskz") || warnings[0].message.contains("unknown"));
     }
 
@@ -396,31 +455,53 @@ spec:
     /// When: Client opens the document
     /// Then: Server warns about unresolved reference
     #[tokio::test]
-    #[ignore] // Enable in Task 6 (requires cross-file reference resolution)
     async fn test_unresolved_task_reference() {
-        // let (client, server) = create_test_lsp().await;
-
-        // let pipeline = r#"
-        // apiVersion: tekton.dev/v1
-        // kind: Pipeline
-        // metadata:
-        //   name: test
-        // spec:
-        //   tasks:
-        //     - name: build
-        //       taskRef:
-        //         name: non-existent-task  # WARN: Task not found
-        // "#;
-
-        // client.initialize().await;
-        // client.did_open("file:///test/pipeline.yaml", pipeline).await;
-
-        // let diagnostics = server.receive_diagnostics().await;
-        // let warnings: Vec<_> = diagnostics.iter()
-        //     .filter(|d| d.message.contains("non-existent-task"))
-        //     .collect();
-
-        // assert!(warnings.len() > 0);
+        let (client, server) = create_indexed_test_lsp().await;
+
+        let task = r#"
+apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - image: golang
+"#;
+
+        let pipeline = r#"
+apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+    - name: test
+      taskRef:
+        name: non-existent-task  # WARN: Task not found
+"#;
+
+        client.initialize().await;
+        client.did_open("file:///test/task.yaml", task).await;
+        client
+            .did_open("file:///test/pipeline.yaml", pipeline)
+            .await;
+
+        let diagnostics = server.receive_diagnostics().await;
+        let warnings: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.severity == Some(DiagnosticSeverity::WARNING))
+            .map(|d| d.message.as_str())
+            .collect();
+
+        assert_eq!(
+            warnings,
+            vec![
+                "Task 'non-existent-task' of pipeline task 'test' is not defined in the workspace"
+            ]
+        );
     }
 }
 
@@ -4,6 +4,7 @@
 //! YAML structure and indentation.
 
 use tekton_lsp::formatting::FormattingProvider;
+use tekton_lsp::parser::PositionEncoding;
 
 #[test]
 fn test_format_normalizes_indentation() {
@@ -19,12 +20,15 @@ spec:
         - name: build
           image: golang:1.21"#;
 
-    let edits = provider.format(content);
+    let edits = provider.format(content, PositionEncoding::default());
 
     assert!(edits.is_some(), "Should return formatting edits");
 
     let edits = edits.unwrap();
-    assert!(!edits.is_empty(), "Should have edits for inconsistent indentation");
+    assert!(
+        !edits.is_empty(),
+        "Should have edits for inconsistent indentation"
+    );
 
     let formatted = &edits[0].new_text;
     assert!(
@@ -55,7 +59,7 @@ spec:
       taskRef:
         name: build-task"#;
 
-    let edits = provider.format(content);
+    let edits = provider.format(content, PositionEncoding::default());
 
     assert!(edits.is_some(), "Should return edits");
 
@@ -64,7 +68,10 @@ spec:
         let formatted = &edits[0].new_text;
 
         // Verify key elements are preserved
-        assert!(formatted.contains("apiVersion:"), "Should preserve apiVersion");
+        assert!(
+            formatted.contains("apiVersion:"),
+            "Should preserve apiVersion"
+        );
         assert!(formatted.contains("Pipeline"), "Should preserve Pipeline");
         assert!(formatted.contains("main-pipeline"), "Should preserve name");
         assert!(formatted.contains("params:"), "Should preserve params");
@@ -83,7 +90,7 @@ kind: Task
   invalid: indentation
     here: is wrong"#;
 
-    let edits = provider.format(content);
+    let edits = provider.format(content, PositionEncoding::default());
 
     // Invalid YAML should return None (can't format)
     assert!(edits.is_none(), "Should return None for invalid YAML");
@@ -99,7 +106,7 @@ kind: Task
 metadata:
   name: test"#;
 
-    let edits = provider.format(content);
+    let edits = provider.format(content, PositionEncoding::default());
     assert!(edits.is_some());
 
     // If we format again, it should be stable
@@ -108,15 +115,12 @@ metadata:
             let formatted = &edits[0].new_text;
 
             // Format the formatted content
-            let edits2 = provider.format(formatted);
+            let edits2 = provider.format(formatted, PositionEncoding::default());
             assert!(edits2.is_some());
 
             let edits2 = edits2.unwrap();
             // Should have no changes (already formatted)
-            assert!(
-                edits2.is_empty(),
-                "Formatting should be idempotent"
-            );
+            assert!(edits2.is_empty(), "Formatting should be idempotent");
         }
     }
 }
@@ -156,7 +160,7 @@ spec:
       runAfter:
         - fetch-source"#;
 
-    let edits = provider.format(content);
+    let edits = provider.format(content, PositionEncoding::default());
 
     assert!(edits.is_some(), "Should format complex pipeline");
 
@@ -189,7 +193,7 @@ spec:
         echo "Hello World"
         exit 0"#;
 
-    let edits = provider.format(content);
+    let edits = provider.format(content, PositionEncoding::default());
 
     assert!(edits.is_some(), "Should format task with script");
 
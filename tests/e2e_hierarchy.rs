@@ -0,0 +1,134 @@
+//! End-to-end tests for the type hierarchy of Tekton resources.
+//!
+//! These tests verify that the hierarchy follows references from a
+//! PipelineRun down to StepActions, and back up to the referrers.
+
+use tekton_lsp::{hierarchy::TypeHierarchyProvider, parser, workspace::WorkspaceIndex};
+use tower_lsp::lsp_types::{Position, TypeHierarchyItem, Url};
+
+const RUN: &str = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  generateName: release-
+spec:
+  pipelineRef:
+    name: release"#;
+
+fn create_indexed_provider() -> TypeHierarchyProvider {
+    let index = WorkspaceIndex::new();
+    let documents = [
+        ("file:///tmp/runs/release-run.yaml", RUN),
+        (
+            "file:///tmp/pipelines/release.yaml",
+            r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+    - name: test
+      taskRef:
+        name: build"#,
+        ),
+        (
+            "file:///tmp/tasks/build.yaml",
+            r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - name: clone
+      ref:
+        name: git-clone"#,
+        ),
+        (
+            "file:///tmp/stepactions/git-clone.yaml",
+            r#"apiVersion: tekton.dev/v1beta1
+kind: StepAction
+metadata:
+  name: git-clone
+spec:
+  image: alpine/git"#,
+        ),
+    ];
+    for (uri, content) in documents {
+        index
+            .index_document(&Url::parse(uri).unwrap(), content)
+            .expect("Failed to index document");
+    }
+    TypeHierarchyProvider::new(index)
+}
+
+fn names(items: &[TypeHierarchyItem]) -> Vec<(String, Option<String>)> {
+    items
+        .iter()
+        .map(|i| (i.name.clone(), i.detail.clone()))
+        .collect()
+}
+
+#[test]
+fn test_type_hierarchy_from_pipeline_run_to_step_actions() {
+    let provider = create_indexed_provider();
+    let uri = Url::parse("file:///tmp/runs/release-run.yaml").unwrap();
+    let yaml_doc = parser::parse_yaml(uri.as_ref(), RUN).unwrap();
+
+    // Anywhere in the document prepares the PipelineRun itself
+    let run = provider
+        .prepare(&yaml_doc, &uri, Position::new(1, 2))
+        .expect("Should prepare the PipelineRun");
+    assert_eq!(
+        names(&run),
+        vec![("release-".to_string(), Some("PipelineRun".to_string()))]
+    );
+
+    let pipeline = provider.subtypes(&run[0]);
+    assert_eq!(
+        names(&pipeline),
+        vec![("release".to_string(), Some("Pipeline".to_string()))]
+    );
+
+    // Both pipeline tasks run the same Task, listed once
+    let tasks = provider.subtypes(&pipeline[0]);
+    assert_eq!(
+        names(&tasks),
+        vec![("build".to_string(), Some("Task".to_string()))]
+    );
+
+    let step_actions = provider.subtypes(&tasks[0]);
+    assert_eq!(
+        names(&step_actions),
+        vec![("git-clone".to_string(), Some("StepAction".to_string()))]
+    );
+    assert!(provider.subtypes(&step_actions[0]).is_empty());
+}
+
+#[test]
+fn test_type_hierarchy_supertypes() {
+    let provider = create_indexed_provider();
+    let uri = Url::parse("file:///tmp/runs/release-run.yaml").unwrap();
+    let yaml_doc = parser::parse_yaml(uri.as_ref(), RUN).unwrap();
+
+    // On pipelineRef.name (line 6) prepares the referenced Pipeline
+    let pipeline = provider
+        .prepare(&yaml_doc, &uri, Position::new(6, 12))
+        .expect("Should prepare the Pipeline");
+    assert_eq!(pipeline[0].uri.path(), "/tmp/pipelines/release.yaml");
+
+    let runs = provider.supertypes(&pipeline[0]);
+    assert_eq!(
+        names(&runs),
+        vec![("release-".to_string(), Some("PipelineRun".to_string()))]
+    );
+    assert_eq!(runs[0].uri, uri);
+
+    let tasks = provider.subtypes(&pipeline[0]);
+    let step_actions = provider.subtypes(&tasks[0]);
+    assert_eq!(
+        names(&provider.supertypes(&step_actions[0])),
+        vec![("build".to_string(), Some("Task".to_string()))]
+    );
+}
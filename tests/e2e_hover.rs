@@ -3,8 +3,9 @@
 //! These tests verify that the hover provider returns appropriate
 //! documentation based on cursor position and document context.
 
-use tekton_lsp::{hover::HoverProvider, parser};
+use tekton_lsp::{hover::HoverProvider, parser, workspace::WorkspaceIndex};
 use tower_lsp::lsp_types::Position;
+use tower_lsp::lsp_types::Url;
 
 // TDD Cycle 1: Hover on field keys (tasks, steps, params)
 #[test]
@@ -18,21 +19,23 @@ spec:
   tasks:
     - name: build"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
 
     let provider = HoverProvider::new();
 
     // When: Request hover on "tasks" field (line 5, "tasks:")
     let position = Position {
-        line: 5,  // "  tasks:" line
-        character: 4,  // On "tasks"
+        line: 5,      // "  tasks:" line
+        character: 4, // On "tasks"
     };
 
     let hover = provider.provide_hover(&yaml_doc, position);
 
     // Then: Should return documentation about tasks
-    assert!(hover.is_some(), "Should return hover information for 'tasks' field");
+    assert!(
+        hover.is_some(),
+        "Should return hover information for 'tasks' field"
+    );
 
     let hover = hover.unwrap();
     let content = match hover.contents {
@@ -40,10 +43,16 @@ spec:
         _ => panic!("Expected Markup content"),
     };
 
-    assert!(content.contains("tasks") || content.contains("Tasks"),
-        "Hover should mention tasks. Got: {}", content);
-    assert!(content.contains("PipelineTask") || content.contains("Pipeline"),
-        "Hover should describe PipelineTask. Got: {}", content);
+    assert!(
+        content.contains("tasks") || content.contains("Tasks"),
+        "Hover should mention tasks. Got: {}",
+        content
+    );
+    assert!(
+        content.contains("PipelineTask") || content.contains("Pipeline"),
+        "Hover should describe PipelineTask. Got: {}",
+        content
+    );
 }
 
 #[test]
@@ -56,8 +65,7 @@ spec:
   steps:
     - name: compile"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
 
     let provider = HoverProvider::new();
 
@@ -76,8 +84,11 @@ spec:
         _ => panic!("Expected Markup content"),
     };
 
-    assert!(content.contains("steps") || content.contains("Steps") || content.contains("container"),
-        "Hover should describe steps. Got: {}", content);
+    assert!(
+        content.contains("steps") || content.contains("Steps") || content.contains("container"),
+        "Hover should describe steps. Got: {}",
+        content
+    );
 }
 
 #[test]
@@ -90,8 +101,7 @@ spec:
   params:
     - name: version"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
 
     let provider = HoverProvider::new();
 
@@ -109,8 +119,11 @@ spec:
         _ => panic!("Expected Markup content"),
     };
 
-    assert!(content.contains("param") || content.contains("Param"),
-        "Hover should describe params. Got: {}", content);
+    assert!(
+        content.contains("param") || content.contains("Param"),
+        "Hover should describe params. Got: {}",
+        content
+    );
 }
 
 // TDD Cycle 2: Hover on kind values (Pipeline, Task)
@@ -121,30 +134,38 @@ kind: Pipeline
 metadata:
   name: test"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
 
     let provider = HoverProvider::new();
 
     // Position on "Pipeline" value (line 1, character 6+)
     let position = Position {
         line: 1,
-        character: 7,  // On "Pipeline"
+        character: 7, // On "Pipeline"
     };
 
     let hover = provider.provide_hover(&yaml_doc, position);
 
-    assert!(hover.is_some(), "Should return hover for 'Pipeline' kind value");
+    assert!(
+        hover.is_some(),
+        "Should return hover for 'Pipeline' kind value"
+    );
 
     let content = match hover.unwrap().contents {
         tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
         _ => panic!("Expected Markup content"),
     };
 
-    assert!(content.contains("Pipeline"),
-        "Hover should describe Pipeline. Got: {}", content);
-    assert!(content.contains("Task") || content.contains("collection"),
-        "Hover should describe Pipeline as a collection of Tasks. Got: {}", content);
+    assert!(
+        content.contains("Pipeline"),
+        "Hover should describe Pipeline. Got: {}",
+        content
+    );
+    assert!(
+        content.contains("Task") || content.contains("collection"),
+        "Hover should describe Pipeline as a collection of Tasks. Got: {}",
+        content
+    );
 }
 
 #[test]
@@ -154,8 +175,7 @@ kind: Task
 metadata:
   name: build-task"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
 
     let provider = HoverProvider::new();
 
@@ -173,10 +193,16 @@ metadata:
         _ => panic!("Expected Markup content"),
     };
 
-    assert!(content.contains("Task"),
-        "Hover should describe Task. Got: {}", content);
-    assert!(content.contains("Step") || content.contains("Pod"),
-        "Hover should describe Task execution. Got: {}", content);
+    assert!(
+        content.contains("Task"),
+        "Hover should describe Task. Got: {}",
+        content
+    );
+    assert!(
+        content.contains("Step") || content.contains("Pod"),
+        "Hover should describe Task execution. Got: {}",
+        content
+    );
 }
 
 // TDD Cycle 3: Hover on metadata fields
@@ -187,8 +213,7 @@ kind: Pipeline
 metadata:
   name: test-pipeline"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
 
     let provider = HoverProvider::new();
 
@@ -207,8 +232,11 @@ metadata:
         _ => panic!("Expected Markup content"),
     };
 
-    assert!(content.contains("metadata") || content.contains("Kubernetes"),
-        "Hover should describe metadata. Got: {}", content);
+    assert!(
+        content.contains("metadata") || content.contains("Kubernetes"),
+        "Hover should describe metadata. Got: {}",
+        content
+    );
 }
 
 #[test]
@@ -218,8 +246,7 @@ kind: Pipeline
 metadata:
   name: test-pipeline"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
 
     let provider = HoverProvider::new();
 
@@ -238,8 +265,11 @@ metadata:
         _ => panic!("Expected Markup content"),
     };
 
-    assert!(content.contains("name") || content.contains("Name"),
-        "Hover should describe name field. Got: {}", content);
+    assert!(
+        content.contains("name") || content.contains("Name"),
+        "Hover should describe name field. Got: {}",
+        content
+    );
 }
 
 #[test]
@@ -251,8 +281,7 @@ metadata:
   labels:
     app: my-app"#;
 
-    let yaml_doc = parser::parse_yaml("test.yaml", content)
-        .expect("Failed to parse YAML");
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
 
     let provider = HoverProvider::new();
 
@@ -271,6 +300,1093 @@ metadata:
         _ => panic!("Expected Markup content"),
     };
 
-    assert!(content.contains("label") || content.contains("Label"),
-        "Hover should describe labels. Got: {}", content);
+    assert!(
+        content.contains("label") || content.contains("Label"),
+        "Hover should describe labels. Got: {}",
+        content
+    );
+}
+
+#[test]
+fn test_hover_on_run_after_entry() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: test
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build-task
+      params:
+        - name: version
+          value: $(params.version)
+      when:
+        - input: $(params.enabled)
+          operator: in
+          values: ["true"]
+    - name: deploy
+      runAfter:
+        - build
+      taskRef:
+        name: deploy-task"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+
+    let provider = HoverProvider::new();
+
+    // Position on "build" inside runAfter
+    let position = Position {
+        line: 18,
+        character: 11,
+    };
+
+    let hover = provider.provide_hover(&yaml_doc, position);
+
+    assert!(hover.is_some(), "Should return hover for runAfter entry");
+
+    let hover = hover.unwrap();
+    assert_eq!(hover.range.unwrap().start.line, 18);
+
+    let content = match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+        _ => panic!("Expected Markup content"),
+    };
+
+    assert!(
+        content.contains("`build-task`"),
+        "Hover should show the taskRef target. Got: {}",
+        content
+    );
+    assert!(
+        content.contains("`version`: `$(params.version)`"),
+        "Hover should list params. Got: {}",
+        content
+    );
+    assert!(
+        content.contains("`$(params.enabled)` in [true]"),
+        "Hover should list when conditions. Got: {}",
+        content
+    );
+    assert!(
+        content.contains("**Defined at:** line 7"),
+        "Hover should show where the task is. Got: {}",
+        content
+    );
+}
+
+#[test]
+fn test_hover_on_run_after_entry_of_pipeline_spec() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  generateName: ci-
+spec:
+  pipelineSpec:
+    tasks:
+      - name: lint
+        taskSpec:
+          steps:
+            - name: golangci
+              image: golangci/golangci-lint
+            - name: vet
+      - name: test
+        runAfter: [lint]
+        taskRef:
+          name: go-test"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let hover = HoverProvider::new()
+        .provide_hover(
+            &yaml_doc,
+            Position {
+                line: 14,
+                character: 20,
+            },
+        )
+        .expect("Should return hover for runAfter entry");
+    let content = match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+        _ => panic!("Expected Markup content"),
+    };
+    assert_eq!(
+        content,
+        "# Pipeline task `lint`\n\n\
+         **Defined at:** line 8\n\n\
+         **Runs:** inline `taskSpec`\n\n\
+         **Steps:**\n\
+         - `golangci` (image `golangci/golangci-lint`)\n\
+         - `vet`"
+    );
+}
+
+#[test]
+fn test_hover_on_param_name_renders_description() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  description: >
+    Builds the sources
+    with Go.
+  params:
+    - name: flags
+      type: array
+      description: |
+        Extra flags passed to `go build`.
+
+        See **go help build**.
+      default: ["-v"]
+  steps:
+    - image: golang"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = HoverProvider::new();
+
+    // Position on "flags" in the param name
+    let hover = provider
+        .provide_hover(
+            &yaml_doc,
+            Position {
+                line: 9,
+                character: 14,
+            },
+        )
+        .expect("Should return hover for a described param");
+    assert_eq!(hover.range.unwrap().start.line, 9);
+
+    let content = match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+        _ => panic!("Expected Markup content"),
+    };
+    assert!(content.starts_with("# Param `flags`"), "Got: {}", content);
+    assert!(
+        content.contains("Extra flags passed to `go build`.\n\nSee **go help build**."),
+        "Got: {}",
+        content
+    );
+    assert!(content.contains("**Type:** `array`"), "Got: {}", content);
+    assert!(
+        content.contains("**Default:** `[\"-v\"]`"),
+        "Got: {}",
+        content
+    );
+
+    // Position on the resource name shows the spec description
+    let hover = provider
+        .provide_hover(
+            &yaml_doc,
+            Position {
+                line: 3,
+                character: 9,
+            },
+        )
+        .expect("Should return hover for the resource name");
+    let content = match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+        _ => panic!("Expected Markup content"),
+    };
+    assert_eq!(content, "# Task `build`\n\nBuilds the sources with Go.");
+}
+
+#[test]
+fn test_hover_on_task_result_reference_shows_type() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: build
+      taskSpec:
+        results:
+          - name: report
+            type: object
+            description: Where the scan report was uploaded
+            properties:
+              url: {type: string}
+              sha: {type: string}
+        steps:
+          - image: alpine
+    - name: notify
+      taskRef:
+        name: notify
+      params:
+        - name: url
+          value: "see $(tasks.build.results.report.url)"
+        - name: other
+          value: $(tasks.lint.results.report)"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = HoverProvider::new();
+
+    // Position inside the expression
+    let hover = provider
+        .provide_hover(
+            &yaml_doc,
+            Position {
+                line: 22,
+                character: 30,
+            },
+        )
+        .expect("Should return hover for a task result reference");
+    let range = hover.range.unwrap();
+    assert_eq!((range.start.line, range.start.character), (22, 22));
+
+    let content = match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+        _ => panic!("Expected Markup content"),
+    };
+    assert_eq!(
+        content,
+        "# Result `report` of task `build`\n\nWhere the scan report was uploaded\n\n**Type:** `object`  \n**Properties:** `url`, `sha`"
+    );
+
+    // Results of tasks without an inline taskSpec are unknown
+    let hover = provider.provide_hover(
+        &yaml_doc,
+        Position {
+            line: 24,
+            character: 25,
+        },
+    );
+    assert!(hover.is_none_or(|h| match h.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => !m.value.contains("Result"),
+        _ => true,
+    }));
+}
+
+#[test]
+fn test_hover_on_finally_task_references() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build-task
+  finally:
+    - name: notify
+      taskSpec:
+        results:
+          - name: sent
+            description: Whether the message went out
+        steps:
+          - image: alpine
+  results:
+    - name: notified
+      value: $(finally.notify.results.sent)
+    - name: status
+      value: $(finally.notify.status)"#;
+
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = HoverProvider::new();
+    let hover_text = |line, character| {
+        let hover = provider
+            .provide_hover(&yaml_doc, Position { line, character })
+            .expect("Should return hover");
+        match hover.contents {
+            tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+            _ => panic!("Expected Markup content"),
+        }
+    };
+
+    let content = hover_text(19, 40);
+    assert!(
+        content.starts_with("# Result `sent` of task `notify`"),
+        "Got: {}",
+        content
+    );
+
+    let content = hover_text(21, 24);
+    assert!(
+        content.contains("# Pipeline task `notify`") && content.contains("inline `taskSpec`"),
+        "Got: {}",
+        content
+    );
+}
+
+#[test]
+fn test_hover_on_param_reference_shows_resolution_chain() {
+    let task = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  params:
+    - name: version
+      description: Version to stamp
+      default: dev
+  steps:
+    - name: build
+      image: golang
+      script: go build -ldflags "-X main.version=$(params.version)""#;
+    let pipeline = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  params:
+    - name: tag
+  tasks:
+    - name: compile
+      taskRef:
+        name: build
+      params:
+        - name: version
+          value: $(params.tag)
+    - name: snapshot
+      taskRef:
+        name: build"#;
+    let run = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  generateName: release-
+spec:
+  pipelineRef:
+    name: release
+  params:
+    - name: tag
+      value: v1.2.3"#;
+
+    let index = WorkspaceIndex::new();
+    index
+        .index_document(&Url::parse("file:///repo/pipeline.yaml").unwrap(), pipeline)
+        .unwrap();
+    index
+        .index_document(&Url::parse("file:///repo/run.yaml").unwrap(), run)
+        .unwrap();
+    let provider = HoverProvider::with_index(index);
+
+    let yaml_doc =
+        parser::parse_yaml("file:///repo/task.yaml", task).expect("Failed to parse YAML");
+    let hover = provider
+        .provide_hover(
+            &yaml_doc,
+            Position {
+                line: 12,
+                character: 55,
+            },
+        )
+        .expect("Should return hover for a param reference");
+    let range = hover.range.unwrap();
+    assert_eq!((range.start.line, range.start.character), (12, 49));
+
+    let content = match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+        _ => panic!("Expected Markup content"),
+    };
+    assert_eq!(
+        content,
+        "# Param `version`\n\nVersion to stamp\n\n**Type:** `string`  \n**Default:** `dev`\n\n**Resolution:**\n\
+         - Pipeline `release` (task `compile`) passes `$(params.tag)`\n\
+         \x20 - `tag`: PipelineRun `release-` sets `v1.2.3`\n\
+         - Pipeline `release` (task `snapshot`) does not set it; the default applies"
+    );
+
+    // Without an index, only the param itself is shown
+    let hover = HoverProvider::new()
+        .provide_hover(
+            &yaml_doc,
+            Position {
+                line: 12,
+                character: 55,
+            },
+        )
+        .unwrap();
+    let content = match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+        _ => panic!("Expected Markup content"),
+    };
+    assert!(!content.contains("Resolution"));
+}
+
+#[test]
+fn test_hover_on_task_ref_name_shows_task_interface() {
+    let task = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  description: Build the sources
+  params:
+    - name: revision
+      description: Commit to build
+      default: main
+    - name: url
+  workspaces:
+    - name: source
+    - name: cache
+      optional: true
+  results:
+    - name: digest
+  steps:
+    - name: build
+      image: golang"#;
+    let pipeline = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+    - name: test
+      taskRef:
+        name: missing"#;
+
+    let index = WorkspaceIndex::new();
+    index
+        .index_document(&Url::parse("file:///repo/tasks/build.yaml").unwrap(), task)
+        .unwrap();
+    let provider = HoverProvider::with_index(index);
+    let yaml_doc =
+        parser::parse_yaml("file:///repo/pipeline.yaml", pipeline).expect("Failed to parse YAML");
+
+    let hover = provider
+        .provide_hover(
+            &yaml_doc,
+            Position {
+                line: 8,
+                character: 15,
+            },
+        )
+        .expect("Should return hover for a taskRef name");
+    let range = hover.range.unwrap();
+    assert_eq!((range.start.line, range.start.character), (8, 14));
+    let content = match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+        _ => panic!("Expected Markup content"),
+    };
+    assert_eq!(
+        content,
+        "# Task `build`\n\n\
+         Build the sources\n\n\
+         **Defined in:** [build.yaml](file:///repo/tasks/build.yaml#L4)\n\n\
+         **Params:**\n\
+         - `revision` (`string`, default `main`): Commit to build\n\
+         - `url` (`string`, required)\n\n\
+         **Workspaces:**\n\
+         - `source`\n\
+         - `cache` (optional)\n\n\
+         **Results:** `digest`"
+    );
+
+    // A Task the index does not know falls back to the field documentation
+    let hover = provider.provide_hover(
+        &yaml_doc,
+        Position {
+            line: 11,
+            character: 15,
+        },
+    );
+    if let Some(hover) = hover {
+        let tower_lsp::lsp_types::HoverContents::Markup(m) = hover.contents else {
+            panic!("Expected Markup content");
+        };
+        assert!(!m.value.starts_with("# Task `missing`"));
+    }
+}
+
+#[test]
+fn test_hover_on_result_reference_of_indexed_task() {
+    let task = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  results:
+    - name: digest
+      description: Digest of the built image
+  steps:
+    - name: build
+      image: golang"#;
+    let pipeline = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: ci
+spec:
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+    - name: deploy
+      params:
+        - name: image
+          value: $(tasks.build.results.digest)
+      taskRef:
+        name: deploy"#;
+
+    let index = WorkspaceIndex::new();
+    index
+        .index_document(&Url::parse("file:///repo/build.yaml").unwrap(), task)
+        .unwrap();
+    let provider = HoverProvider::with_index(index);
+    let yaml_doc =
+        parser::parse_yaml("file:///repo/pipeline.yaml", pipeline).expect("Failed to parse YAML");
+
+    let hover = provider
+        .provide_hover(
+            &yaml_doc,
+            Position {
+                line: 12,
+                character: 35,
+            },
+        )
+        .expect("Should return hover for a result reference");
+    let content = match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+        _ => panic!("Expected Markup content"),
+    };
+    assert_eq!(
+        content,
+        "# Result `digest` of task `build`\n\n\
+         Digest of the built image\n\n\
+         **Type:** `string`  \n\
+         **Declared by:** Task `build` in [build.yaml](file:///repo/build.yaml#L4)"
+    );
+
+    // Without the index, the result is unknown
+    let hover = HoverProvider::new().provide_hover(
+        &yaml_doc,
+        Position {
+            line: 12,
+            character: 35,
+        },
+    );
+    assert!(hover.is_none_or(|hover| match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => !m.value.contains("Digest"),
+        _ => true,
+    }));
+}
+
+#[test]
+fn test_hover_field_documents_its_fields() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - name: build
+      image: golang
+      securityContext:
+        runAsNonRoot: true"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = HoverProvider::new();
+
+    let hover = provider
+        .provide_hover(
+            &yaml_doc,
+            Position {
+                line: 8,
+                character: 8,
+            },
+        )
+        .expect("Should document securityContext");
+    let content = match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+        _ => panic!("Expected Markup content"),
+    };
+
+    assert!(
+        content.starts_with("# Field `securityContext` of Task\n\n"),
+        "Got: {}",
+        content
+    );
+    assert!(content.contains("**Required:** no"), "Got: {}", content);
+    assert!(
+        content.contains("| Field | Type | Required | Description |"),
+        "Got: {}",
+        content
+    );
+    assert!(
+        content.contains("\n| `runAsNonRoot` | boolean | no |"),
+        "Got: {}",
+        content
+    );
+}
+
+#[test]
+fn test_hover_field_of_pipeline_run() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  generateName: build-
+spec:
+  pipelineRef:
+    name: build
+  timeouts:
+    pipeline: 1h"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = HoverProvider::new();
+
+    let hover = provider
+        .provide_hover(
+            &yaml_doc,
+            Position {
+                line: 7,
+                character: 4,
+            },
+        )
+        .expect("Should document timeouts");
+    let content = match hover.contents {
+        tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+        _ => panic!("Expected Markup content"),
+    };
+
+    assert!(
+        content.starts_with("# Field `timeouts` of PipelineRun\n\n"),
+        "Got: {}",
+        content
+    );
+    assert!(
+        content.contains("\n| `pipeline` | string | no |"),
+        "Got: {}",
+        content
+    );
+    assert!(
+        content.ends_with(
+            "[Tekton documentation](https://tekton.dev/docs/pipelines/pipelineruns/#configuring-a-failure-timeout)"
+        ),
+        "Got: {}",
+        content
+    );
+}
+
+#[test]
+fn test_hover_follows_api_version() {
+    let content = r#"apiVersion: tekton.dev/v1beta1
+kind: Task
+metadata:
+  name: build
+spec:
+  resources:
+    inputs:
+      - name: source
+        type: git
+  steps:
+    - name: build
+      image: golang
+      resources:
+        limits:
+          memory: 1Gi"#;
+    let provider = HoverProvider::new();
+    let hover_at = |content: &str, line: u32, character: u32| {
+        let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+        provider
+            .provide_hover(&yaml_doc, Position { line, character })
+            .map(|hover| match hover.contents {
+                tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+                _ => panic!("Expected Markup content"),
+            })
+    };
+
+    // tekton.dev/v1beta1 documents the PipelineResources and the step's
+    // compute resources
+    let resources = hover_at(content, 5, 4).expect("Should document resources");
+    assert!(
+        resources.contains("PipelineResources") && resources.contains("**Deprecated:**"),
+        "Got: {}",
+        resources
+    );
+    let step_resources = hover_at(content, 12, 8).expect("Should document step resources");
+    assert!(
+        step_resources.contains("computeResources"),
+        "Got: {}",
+        step_resources
+    );
+
+    // tekton.dev/v1 removed them
+    let v1 = content.replace("tekton.dev/v1beta1", "tekton.dev/v1");
+    assert_eq!(hover_at(&v1, 5, 4), None);
+    assert_eq!(hover_at(&v1, 12, 8), None);
+}
+
+#[test]
+fn test_hover_leaves_out_fields_of_other_api_versions() {
+    let content = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  generateName: build-
+spec:
+  serviceAccountName: builder
+  taskRunTemplate:
+    serviceAccountName: builder"#;
+    let provider = HoverProvider::new();
+    let hover_at = |content: &str, line: u32| {
+        let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+        provider.provide_hover(&yaml_doc, Position { line, character: 4 })
+    };
+
+    assert!(hover_at(content, 5).is_none());
+    assert!(hover_at(content, 6).is_some());
+
+    let v1beta1 = content.replace("tekton.dev/v1", "tekton.dev/v1beta1");
+    assert!(hover_at(&v1beta1, 5).is_some());
+    assert!(hover_at(&v1beta1, 6).is_none());
+}
+
+#[test]
+fn test_hover_hub_resolver_shows_remote_task() {
+    use tekton_lsp::integrations::hub::resource_yaml_url;
+    use tekton_lsp::integrations::{HubClient, HubOptions, HubResource, RemoteFile};
+
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: clone
+      taskRef:
+        resolver: hub
+        params:
+          - name: catalog
+            value: tekton
+          - name: name
+            value: git-clone
+          - name: version
+            value: "0.8""#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let dir = std::env::temp_dir().join(format!("tekton-lsp-hub-hover-{}", std::process::id()));
+    let options = HubOptions {
+        enabled: true,
+        url: "https://hub.example.com".to_string(),
+        cache_dir: Some(dir.clone()),
+        ..HubOptions::default()
+    };
+    let hub = HubClient::new(options.clone());
+    let resource = HubResource {
+        id: None,
+        name: "git-clone".to_string(),
+        catalog: "tekton".to_string(),
+        kind: "Task".to_string(),
+        latest_version: "0.9".to_string(),
+        description: Some("Clone a repository".to_string()),
+    };
+    hub.set_catalog(vec![resource.clone()]);
+    let url = resource_yaml_url(&options.url, &resource, "0.8");
+    let provider = HoverProvider::new().with_hub(hub.clone());
+    let hover = || {
+        let hover = provider
+            .provide_hover(
+                &yaml_doc,
+                Position {
+                    line: 8,
+                    character: 19,
+                },
+            )
+            .expect("Should describe the Hub task");
+        match hover.contents {
+            tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+            _ => panic!("Expected Markup content"),
+        }
+    };
+
+    // The catalog entry while the task is fetched
+    hub.files().set(&url, RemoteFile::Fetching);
+    let content = hover();
+    assert!(
+        content.starts_with(
+            "# Task `git-clone` from the Hub\n\n**Catalog:** `tekton`  \n**Version:** `0.8` (latest `0.9`)\n\nClone a repository\n\n_Fetching"
+        ),
+        "Got: {}",
+        content
+    );
+
+    // Its interface once fetched
+    hub.files().set(
+        &url,
+        RemoteFile::Fetched(
+            r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: git-clone
+spec:
+  description: Clone a git repository.
+  params:
+    - name: url
+      description: Repository URL
+  results:
+    - name: commit"#
+                .to_string(),
+        ),
+    );
+    let content = hover();
+    assert!(
+        content.contains("Clone a git repository."),
+        "Got: {}",
+        content
+    );
+    assert!(
+        content.contains("- `url` (`string`, required): Repository URL"),
+        "Got: {}",
+        content
+    );
+    assert!(
+        content.contains("**Results:** `commit`"),
+        "Got: {}",
+        content
+    );
+    assert!(
+        content.contains("(https://hub.example.com/v1/resource/tekton/task/git-clone/0.8/yaml#L4)"),
+        "Got: {}",
+        content
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_hover_git_resolver_shows_remote_task() {
+    use tekton_lsp::integrations::{GitOptions, RemoteFile};
+
+    let content = r#"apiVersion: tekton.dev/v1
+kind: TaskRun
+metadata:
+  generateName: build-
+spec:
+  taskRef:
+    resolver: git
+    params:
+      - name: url
+        value: https://github.com/org/tasks.git
+      - name: revision
+        value: v1
+      - name: pathInRepo
+        value: task/build.yaml"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = HoverProvider::new();
+    let hover = || {
+        provider
+            .provide_hover(
+                &yaml_doc,
+                Position {
+                    line: 6,
+                    character: 15,
+                },
+            )
+            .map(|hover| match hover.contents {
+                tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+                _ => panic!("Expected Markup content"),
+            })
+    };
+
+    // Disabled by default: the field's documentation
+    assert!(hover().unwrap().starts_with("# Field `resolver`"));
+
+    provider.git().set_options(GitOptions { enabled: true });
+    provider.git().files().set(
+        "https://raw.githubusercontent.com/org/tasks/v1/task/build.yaml",
+        RemoteFile::Failed("404 Not Found".to_string()),
+    );
+    let content = hover().unwrap();
+    assert_eq!(
+        content,
+        "# Task from Git\n\n**Repository:** https://github.com/org/tasks.git  \n**Revision:** `v1`  \n**Path:** `task/build.yaml`\n\n_Failed to fetch the resource: 404 Not Found_"
+    );
+
+    provider.git().files().set(
+        "https://raw.githubusercontent.com/org/tasks/v1/task/build.yaml",
+        RemoteFile::Fetched(
+            "apiVersion: tekton.dev/v1\nkind: Task\nmetadata:\n  name: build\nspec:\n  steps: []"
+                .to_string(),
+        ),
+    );
+    let content = hover().unwrap();
+    assert!(
+        content.starts_with("# Task `build` from Git\n\n"),
+        "Got: {}",
+        content
+    );
+}
+
+#[test]
+fn test_hover_range_covers_the_hovered_token() {
+    use tower_lsp::lsp_types::Range;
+
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - name: build
+      image: golang
+      script: |
+        go build ./...
+        go test ./..."#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = HoverProvider::new();
+    let range_at = |line, character| {
+        provider
+            .provide_hover(&yaml_doc, Position { line, character })
+            .expect("Should document the field")
+            .range
+    };
+    let range = |line, start, end| {
+        Some(Range {
+            start: Position {
+                line,
+                character: start,
+            },
+            end: Position {
+                line,
+                character: end,
+            },
+        })
+    };
+
+    // The key, not the whole steps block
+    assert_eq!(range_at(5, 3), range(5, 2, 7));
+    // A single-line value
+    assert_eq!(range_at(7, 14), range(7, 13, 19));
+    // The word of a block scalar
+    assert_eq!(range_at(10, 12), range(10, 11, 15));
+    // Nothing between tokens
+    assert_eq!(range_at(6, 1), None);
+}
+
+#[test]
+fn test_hover_task_containers_and_pipeline_task_fields() {
+    let task = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  stepTemplate:
+    env:
+      - name: HOME
+        value: /workspace
+  sidecars:
+    - name: docker
+      image: docker:dind
+  steps:
+    - name: build
+      image: golang"#;
+    let pipeline = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: build
+      displayName: Build
+      retries: 2
+      onError: continue
+      taskRef:
+        name: build"#;
+    let provider = HoverProvider::new();
+    let hover_at = |content: &str, line, character| {
+        let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+        let hover = provider
+            .provide_hover(&yaml_doc, Position { line, character })
+            .expect("Should document the field");
+        match hover.contents {
+            tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+            _ => panic!("Expected Markup content"),
+        }
+    };
+
+    let step_template = hover_at(task, 5, 4);
+    assert!(
+        step_template.contains("\n| `env` | array | no |"),
+        "Got: {}",
+        step_template
+    );
+    let sidecars = hover_at(task, 9, 4);
+    assert!(
+        sidecars.contains("\n| `image` | string | yes |")
+            && sidecars.contains("#specifying-sidecars)"),
+        "Got: {}",
+        sidecars
+    );
+    // A field of a sidecar
+    let image = hover_at(task, 11, 8);
+    assert!(
+        image.starts_with("# Field `image` of Task\n\nContainer image of the sidecar"),
+        "Got: {}",
+        image
+    );
+
+    for (line, field) in [(7, "displayName"), (8, "retries"), (9, "onError")] {
+        let content = hover_at(pipeline, line, 8);
+        assert!(
+            content.starts_with(&format!("# Field `{}` of Pipeline", field))
+                && content.contains("[Tekton documentation]"),
+            "Got: {}",
+            content
+        );
+    }
+}
+
+#[test]
+fn test_hover_step_image_shows_registry_and_pinning() {
+    use tekton_lsp::integrations::{RegistryOptions, RemoteFile};
+
+    let content = r#"apiVersion: tekton.dev/v1
+kind: Task
+metadata:
+  name: build
+spec:
+  steps:
+    - name: build
+      image: golang:1.22
+    - name: scan
+      image: quay.io/org/scanner
+    - name: push
+      image: gcr.io/org/pusher:v2@sha256:0123
+    - name: release
+      image: $(params.image)"#;
+    let yaml_doc = parser::parse_yaml("test.yaml", content).expect("Failed to parse YAML");
+    let provider = HoverProvider::new();
+    let hover_at = |line, character| {
+        provider
+            .provide_hover(&yaml_doc, Position { line, character })
+            .map(|hover| match hover.contents {
+                tower_lsp::lsp_types::HoverContents::Markup(m) => m.value,
+                _ => panic!("Expected Markup content"),
+            })
+            .expect("Should document the image")
+    };
+
+    assert_eq!(
+        hover_at(7, 16),
+        "# Image `golang:1.22`\n\n**Registry:** [docker.io](https://hub.docker.com/_/golang)  \n**Repository:** `library/golang`  \n**Tag:** `1.22`\n\nTags can be moved to another image. Pin a digest so that every run uses the same image.\n\n```yaml\nimage: golang:1.22@sha256:<digest>\n```"
+    );
+    let scanner = hover_at(9, 16);
+    assert!(
+        scanner.contains("[quay.io](https://quay.io/repository/org/scanner)")
+            && scanner.contains("**Tag:** none (`latest`)")
+            && scanner.contains("Without a tag the `latest` one is pulled"),
+        "Got: {}",
+        scanner
+    );
+    // Pinned by digest: nothing to advise
+    assert!(hover_at(11, 16).ends_with("**Tag:** `v2`  \n**Digest:** `sha256:0123`"));
+    // The key and substituted images keep the field's documentation
+    assert!(hover_at(7, 7).starts_with("# Field `image`"));
+    assert!(hover_at(13, 16).starts_with("# Field `image`"));
+
+    // With the registry enabled, the digest the tag points to
+    provider
+        .registry()
+        .set_options(RegistryOptions { enabled: true });
+    provider.registry().digests().set(
+        "https://registry-1.docker.io/v2/library/golang/manifests/1.22",
+        RemoteFile::Fetched("sha256:abcd".to_string()),
+    );
+    assert!(hover_at(7, 16).ends_with(
+        "**Current digest of `1.22`:** `sha256:abcd`\n\n```yaml\nimage: golang:1.22@sha256:abcd\n```"
+    ));
+    provider.registry().digests().set(
+        "https://quay.io/v2/org/scanner/manifests/latest",
+        RemoteFile::Fetching,
+    );
+    assert!(hover_at(9, 16).ends_with("_Fetching the current digest..._"));
 }
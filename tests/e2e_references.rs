@@ -0,0 +1,92 @@
+//! End-to-end tests for find-references.
+//!
+//! These tests verify that the references provider finds the uses of
+//! pipeline tasks and their results, including `finally` tasks and the
+//! Pipeline's own results.
+
+use tekton_lsp::{parser, references::ReferencesProvider};
+use tower_lsp::lsp_types::{Position, Url};
+
+const PIPELINE: &str = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  tasks:
+    - name: build
+      taskSpec:
+        results:
+          - name: digest
+        steps:
+          - image: alpine
+    - name: scan
+      runAfter: [build]
+      params:
+        - name: image
+          value: registry/app@$(tasks.build.results.digest)
+  finally:
+    - name: notify
+      when:
+        - input: $(tasks.scan.status)
+          operator: in
+          values: [Failed]
+      taskSpec:
+        results:
+          - name: sent
+        steps:
+          - image: alpine
+  results:
+    - name: digest
+      value: $(tasks.build.results.digest)
+    - name: notified
+      value: $(finally.notify.results.sent)"#;
+
+fn references_at(position: Position, include_declaration: bool) -> Option<Vec<(u32, u32)>> {
+    let uri = Url::parse("file:///tmp/pipelines/release.yaml").unwrap();
+    let yaml_doc = parser::parse_yaml(uri.as_ref(), PIPELINE).expect("Failed to parse pipeline");
+    let locations = ReferencesProvider::new().provide_references(
+        &yaml_doc,
+        &uri,
+        position,
+        include_declaration,
+    )?;
+    assert!(locations.iter().all(|l| l.uri == uri));
+    Some(
+        locations
+            .iter()
+            .map(|l| (l.range.start.line, l.range.start.character))
+            .collect(),
+    )
+}
+
+#[test]
+fn test_references_to_pipeline_task() {
+    // From the task name: runAfter, result references and pipeline results
+    let references = references_at(Position::new(6, 14), true).unwrap();
+    assert_eq!(references, vec![(6, 12), (13, 17), (16, 38), (30, 21)]);
+
+    let references = references_at(Position::new(6, 14), false).unwrap();
+    assert_eq!(references, vec![(13, 17), (16, 38), (30, 21)]);
+}
+
+#[test]
+fn test_references_to_task_result() {
+    // From the declaration in the inline taskSpec
+    let references = references_at(Position::new(9, 20), true).unwrap();
+    assert_eq!(references, vec![(9, 18), (16, 52), (30, 35)]);
+}
+
+#[test]
+fn test_references_to_finally_task_and_result() {
+    // From `$(finally.notify.results.sent)` in the pipeline results
+    let references = references_at(Position::new(32, 40), true).unwrap();
+    assert_eq!(references, vec![(25, 18), (32, 38)]);
+
+    let references = references_at(Position::new(18, 14), true).unwrap();
+    assert_eq!(references, vec![(18, 12), (32, 23)]);
+}
+
+#[test]
+fn test_no_references_outside_names() {
+    assert_eq!(references_at(Position::new(2, 3), true), None);
+}
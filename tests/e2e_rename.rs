@@ -0,0 +1,79 @@
+//! End-to-end tests for renaming Pipeline workspaces.
+//!
+//! These tests verify that a rename edits the Pipeline document and reports
+//! the indexed PipelineRuns that still bind the old name.
+
+use tekton_lsp::{parser, rename::RenameProvider, workspace::WorkspaceIndex};
+use tower_lsp::lsp_types::{Position, Url};
+
+const PIPELINE: &str = r#"apiVersion: tekton.dev/v1
+kind: Pipeline
+metadata:
+  name: release
+spec:
+  workspaces:
+    - name: shared
+  tasks:
+    - name: build
+      taskRef:
+        name: build
+      workspaces:
+        - name: source
+          workspace: shared"#;
+
+#[test]
+fn test_rename_workspace_reports_pipeline_runs() {
+    let index = WorkspaceIndex::new();
+    let pipeline_uri = Url::parse("file:///tmp/pipelines/release.yaml").unwrap();
+    index.index_document(&pipeline_uri, PIPELINE).unwrap();
+
+    let run_uri = Url::parse("file:///tmp/runs/release-run.yaml").unwrap();
+    let run = r#"apiVersion: tekton.dev/v1
+kind: PipelineRun
+metadata:
+  generateName: release-
+spec:
+  pipelineRef:
+    name: release
+  workspaces:
+    - name: shared
+      emptyDir: {}"#;
+    index.index_document(&run_uri, run).unwrap();
+
+    let provider = RenameProvider::with_index(index);
+    let yaml_doc = parser::parse_yaml(pipeline_uri.as_ref(), PIPELINE).unwrap();
+
+    // Rename from the declaration in spec.workspaces (line 6)
+    let rename = provider
+        .provide_rename(&yaml_doc, &pipeline_uri, Position::new(6, 14), "workspace")
+        .expect("Rename should be valid")
+        .expect("Should rename the workspace");
+
+    let edits = &rename.edit.changes.as_ref().unwrap()[&pipeline_uri];
+    assert_eq!(edits.len(), 2, "Declaration and binding should be renamed");
+    assert!(edits.iter().all(|e| e.new_text == "workspace"));
+
+    assert_eq!(rename.old_name, "shared");
+    assert_eq!(rename.stale_runs.len(), 1);
+    assert_eq!(rename.stale_runs[0].uri, run_uri);
+    assert_eq!(
+        rename.stale_runs[0].referrer_name.as_deref(),
+        Some("release-")
+    );
+}
+
+#[test]
+fn test_rename_outside_workspaces_is_rejected() {
+    let provider = RenameProvider::new();
+    let uri = Url::parse("file:///tmp/pipelines/release.yaml").unwrap();
+    let yaml_doc = parser::parse_yaml(uri.as_ref(), PIPELINE).unwrap();
+
+    // On the pipeline task name (line 8)
+    assert!(provider
+        .prepare_rename(&yaml_doc, Position::new(8, 14))
+        .is_none());
+    assert!(provider
+        .provide_rename(&yaml_doc, &uri, Position::new(8, 14), "compile")
+        .unwrap()
+        .is_none());
+}